@@ -0,0 +1,60 @@
+//! Wall-clock time of multi-vehicle runs on one and on all cores.
+//!
+//! Run with `cargo bench --bench multi_vehicle`. Workers are spawned once per run, so
+//! a run of many ticks is compared with the same ticks driven one `run` call at a
+//! time, which spawns the workers for every tick.
+
+use lap_simulation::controllers::stanley::StanleyController;
+use lap_simulation::models::point_mass::PointMass;
+use lap_simulation::simulation::multi_vehicle::{grid_positions, MultiVehicleSimulation};
+use lap_simulation::tracks::base_track::SharedTrack;
+use lap_simulation::tracks::circle::CircleTrack;
+use std::sync::Arc;
+use std::thread;
+use std::time::{Duration, Instant};
+
+const VEHICLES: usize = 64;
+const DT: f64 = 0.01;
+const TICKS: usize = 1000;
+
+fn simulation(track: &SharedTrack, threads: usize) -> MultiVehicleSimulation {
+    let mut simulation = MultiVehicleSimulation::new(Arc::clone(track)).with_threads(threads);
+    for (x, y, yaw) in grid_positions(track.as_ref(), VEHICLES, 5.0) {
+        simulation.add_controlled_vehicle(
+            PointMass::with_initial_state(x, y, 0.0, yaw),
+            StanleyController::new(track.as_ref(), 1.0, 1.0, 15.0),
+        );
+    }
+    simulation
+}
+
+fn time(run: impl FnOnce()) -> Duration {
+    let start = Instant::now();
+    run();
+    start.elapsed()
+}
+
+fn main() {
+    let track: SharedTrack = Arc::new(CircleTrack::new(200.0, 10.0, 400));
+    let cores = thread::available_parallelism().map_or(1, |cores| cores.get());
+    println!("{VEHICLES} vehicles, {TICKS} ticks of {DT} s, {cores} cores");
+    // At least two workers, so the threaded path is measured on a single core too
+    for threads in [1, cores.max(2)] {
+        let mut whole = simulation(&track, threads);
+        let run = time(|| {
+            whole.run(DT, TICKS as f64 * DT).expect("run");
+        });
+        let mut per_tick = simulation(&track, threads);
+        let ticks = time(|| {
+            for _ in 0..TICKS {
+                per_tick.run(DT, DT).expect("tick");
+            }
+        });
+        println!(
+            "{threads:>3} threads: {:>8.1} ms per run, {:>8.1} ms tick by tick ({:.2}x)",
+            run.as_secs_f64() * 1e3,
+            ticks.as_secs_f64() * 1e3,
+            ticks.as_secs_f64() / run.as_secs_f64()
+        );
+    }
+}
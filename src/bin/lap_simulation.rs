@@ -1,39 +1,441 @@
-use lap_simulation::models::base_model::Model;
-use lap_simulation::models::point_mass::PointMass;
-use lap_simulation::plotting::render_open_loop_outputs;
-use lap_simulation::simulation::base_simulation::Simulation;
-use lap_simulation::simulation::open_loop::OpenLoopSimulation;
-use lap_simulation::tracks::circle::CircleTrack;
+use lap_simulation::config::{ConfigOverrides, ScenarioConfig};
+use lap_simulation::controllers::presets::ControllerPreset;
+use lap_simulation::diagnostics;
+use lap_simulation::io::leaderboard::{LapRecord, Leaderboard, DEFAULT_LEADERBOARD_FILE};
+#[cfg(feature = "video")]
+use lap_simulation::plotting::{render_simulation_outputs, OpenLoopArtifacts, RenderOptions};
+#[cfg(feature = "video")]
+use lap_simulation::provenance::Provenance;
+use lap_simulation::scenarios::{self, Demo};
+use lap_simulation::models::point_mass::PointMassState;
+use lap_simulation::simulation::comparison::{self, LapComparison};
+use lap_simulation::simulation::performance::PerformanceReport;
+use lap_simulation::simulation::result::SimulationResult;
+use lap_simulation::tracks::base_track::Track;
+use lap_simulation::server::registry::Scenario;
+use lap_simulation::telemetry::ndjson::NdjsonTelemetry;
+use lap_simulation::telemetry::TelemetrySink;
+use lap_simulation::Result;
+
+const USAGE: &str = "Usage: lap_simulation [OPTIONS]
+       lap_simulation compare REFERENCE CANDIDATE [--step METERS] [--output-dir DIR]
+
+Options:
+  --config FILE       Scenario config (JSON with the `serde` feature, .toml with `toml`, .yaml with `yaml`)
+  --dt SECONDS        Simulation time step
+  --duration SECONDS  Simulated duration
+  --fps FPS           Frame rate of the rendered outputs
+  --output-dir DIR    Directory the outputs are written to
+  --preset NAME       Drive the vehicle with a controller preset instead of the controls
+  --presets FILE      Preset file extending the built-in presets
+  --list-presets      List available controller presets and exit
+  --scenario NAME     Run a built-in demo scenario, write its report, and exit
+  --list-scenarios    List the built-in demo scenarios and exit
+  --ndjson            Stream each snapshot to stdout as a JSON line instead of rendering
+  --frames-only       Write SVG frames and an animated HTML preview instead of a video (no ffmpeg)
+  --leaderboard FILE  Record the best lap of the run in a leaderboard file
+  --show-leaderboard  Print the leaderboard (--leaderboard FILE or results/leaderboard.csv) and exit
+  -h, --help          Print this help
+
+Settings are taken from CLI flags first, then from the LAP_SIM_DT, LAP_SIM_DURATION,
+LAP_SIM_FPS, and LAP_SIM_OUTPUT_DIR environment variables, then from the config file.
+
+The compare subcommand aligns two exported laps (.ghost, telemetry CSV, or JSON results)
+by track progress, prints a summary, and writes comparison.txt and comparison.svg
+(time delta and speeds) into the output directory.";
+
+/// Parsed command line options
+#[derive(Default)]
+struct CliArgs {
+    config_file: Option<String>,
+    overrides: ConfigOverrides,
+    controller_preset: Option<String>,
+    presets_file: Option<String>,
+    list_presets: bool,
+    scenario: Option<String>,
+    list_scenarios: bool,
+    ndjson: bool,
+    frames_only: bool,
+    leaderboard: Option<String>,
+    show_leaderboard: bool,
+}
+
+/// Parse command line options
+///
+/// # Returns
+/// The parsed options, or `None` when help was requested
+fn parse_args(args: &[String]) -> Result<Option<CliArgs>, String> {
+    let mut cli = CliArgs::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |what: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{arg} requires {what}"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--config" => cli.config_file = Some(value("a FILE")?),
+            "--dt" => cli.overrides.dt = Some(parse_number(arg, &value("SECONDS")?)?),
+            "--duration" => cli.overrides.duration = Some(parse_number(arg, &value("SECONDS")?)?),
+            "--fps" => cli.overrides.fps = Some(parse_number(arg, &value("FPS")?)?),
+            "--output-dir" => cli.overrides.output_dir = Some(value("a DIR")?),
+            "--preset" => cli.controller_preset = Some(value("a NAME")?),
+            "--presets" => cli.presets_file = Some(value("a FILE")?),
+            "--list-presets" => cli.list_presets = true,
+            "--scenario" => cli.scenario = Some(value("a NAME")?),
+            "--list-scenarios" => cli.list_scenarios = true,
+            "--ndjson" => cli.ndjson = true,
+            "--frames-only" => cli.frames_only = true,
+            "--leaderboard" => cli.leaderboard = Some(value("a FILE")?),
+            "--show-leaderboard" => cli.show_leaderboard = true,
+            other => return Err(format!("unknown option '{other}'")),
+        }
+    }
+    Ok(Some(cli))
+}
+
+fn parse_number<T: std::str::FromStr>(flag: &str, value: &str) -> Result<T, String> {
+    value
+        .parse()
+        .map_err(|_| format!("invalid value '{value}' for {flag}"))
+}
+
+/// Options of the compare subcommand
+struct CompareArgs {
+    reference: String,
+    candidate: String,
+    step: f64,
+    overrides: ConfigOverrides,
+}
+
+/// Parse the arguments following `compare`
+fn parse_compare_args(args: &[String]) -> Result<Option<CompareArgs>, String> {
+    let mut files = Vec::new();
+    let mut step = comparison::DEFAULT_STEP;
+    let mut overrides = ConfigOverrides::default();
+    let mut iter = args.iter();
+    while let Some(arg) = iter.next() {
+        let mut value = |what: &str| {
+            iter.next()
+                .cloned()
+                .ok_or_else(|| format!("{arg} requires {what}"))
+        };
+        match arg.as_str() {
+            "-h" | "--help" => return Ok(None),
+            "--step" => step = parse_number(arg, &value("METERS")?)?,
+            "--output-dir" => overrides.output_dir = Some(value("a DIR")?),
+            other if other.starts_with("--") => return Err(format!("unknown option '{other}'")),
+            file => files.push(file.to_string()),
+        }
+    }
+    let [reference, candidate] = <[String; 2]>::try_from(files)
+        .map_err(|_| "compare requires a REFERENCE and a CANDIDATE file".to_string())?;
+    Ok(Some(CompareArgs {
+        reference,
+        candidate,
+        step,
+        overrides,
+    }))
+}
+
+/// Compare two exported laps and write the summary and charts
+fn run_compare(args: &CompareArgs) -> Result<()> {
+    let mut config = ScenarioConfig::default();
+    ConfigOverrides::from_env()?
+        .merged(args.overrides.clone())
+        .apply(&mut config);
+    let reference = comparison::load_lap(&args.reference)?;
+    let candidate = comparison::load_lap(&args.candidate)?;
+    let comparison = LapComparison::new(&reference, &candidate, args.step)?;
+    println!("{comparison}");
+
+    let output_dir = std::path::Path::new(&config.output_dir);
+    std::fs::create_dir_all(output_dir)?;
+    std::fs::write(output_dir.join("comparison.txt"), format!("{comparison}\n"))?;
+    #[cfg(feature = "plotting")]
+    lap_simulation::plotting::plot_lap_comparison(
+        &comparison,
+        &output_dir.join("comparison.svg").to_string_lossy(),
+    )?;
+    Ok(())
+}
+
+/// Submit the best complete lap of `runs` to the leaderboard file at `path`
+fn record_best_lap(
+    path: &str,
+    track: &dyn Track,
+    controller: &str,
+    runs: &[SimulationResult<PointMassState>],
+) -> Result<()> {
+    let report = runs
+        .iter()
+        .enumerate()
+        .fold(PerformanceReport::builder(track), |builder, (index, run)| {
+            builder.run(format!("Vehicle {index}"), run)
+        })
+        .build()?;
+    let Some((_, lap)) = report.best_lap() else {
+        eprintln!("No complete lap to record on the leaderboard");
+        return Ok(());
+    };
+    let record = LapRecord::new(track.get_track_name(), "point_mass", controller, lap.time)?;
+    let submission = Leaderboard::update_file(path, record)?;
+    println!("Best lap {:.3} s on {}: {submission}", lap.time, track.get_track_name());
+    Ok(())
+}
+
+/// Load the scenario file, if any
+fn load_config(path: Option<&str>) -> Result<ScenarioConfig> {
+    match path {
+        #[cfg(feature = "serde")]
+        Some(path) => Ok(ScenarioConfig::load(path)?),
+        #[cfg(not(feature = "serde"))]
+        Some(_) => Err(lap_simulation::Error::InvalidConfig(
+            "--config requires building with the `serde` feature".into(),
+        )),
+        None => Ok(ScenarioConfig::default()),
+    }
+}
+
+/// Resolve the scenario config: file, then environment, then CLI flags
+fn resolve_config(cli: &CliArgs) -> Result<ScenarioConfig> {
+    let mut config = load_config(cli.config_file.as_deref())?;
+    ConfigOverrides::from_env()?
+        .merged(cli.overrides.clone())
+        .apply(&mut config);
+    if cli.controller_preset.is_some() {
+        config.controller_preset = cli.controller_preset.clone();
+    }
+    if cli.presets_file.is_some() {
+        config.presets_file = cli.presets_file.clone();
+    }
+    Ok(config)
+}
 
 fn main() {
-    let track = CircleTrack::new(50.0, 10.0, 100);
-    let model = PointMass::new();
-    let mut simulation = OpenLoopSimulation::new();
-    simulation.init(track, model);
+    // Library status messages (written files, warnings) go to the console
+    diagnostics::set_logger(diagnostics::print_logger);
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().is_some_and(|arg| arg == "compare") {
+        match parse_compare_args(&args[1..]) {
+            Ok(Some(compare)) => {
+                if let Err(err) = run_compare(&compare) {
+                    eprintln!("Failed to compare laps: {err}");
+                    std::process::exit(1);
+                }
+            }
+            Ok(None) => println!("{USAGE}"),
+            Err(err) => {
+                eprintln!("{err}\n\n{USAGE}");
+                std::process::exit(2);
+            }
+        }
+        return;
+    }
+    let cli = match parse_args(&args) {
+        Ok(Some(cli)) => cli,
+        Ok(None) => {
+            println!("{USAGE}");
+            return;
+        }
+        Err(err) => {
+            eprintln!("{err}\n\n{USAGE}");
+            std::process::exit(2);
+        }
+    };
+    let config = match resolve_config(&cli) {
+        Ok(config) => config,
+        Err(err) => {
+            eprintln!("Failed to load configuration: {err}");
+            std::process::exit(1);
+        }
+    };
 
-    let dt = 0.1;
-    let duration = 10.0;
-    let fps = 10;
-    let states = simulation.run(dt, duration);
+    if cli.list_presets {
+        match config.preset_library() {
+            Ok(library) => {
+                for preset in library.presets() {
+                    let track = preset.track.as_deref().unwrap_or("any");
+                    println!("{} ({}, {} track)", preset.name, preset.controller, track);
+                }
+            }
+            Err(err) => eprintln!("Failed to load presets: {err}"),
+        }
+        return;
+    }
 
-    let Some(track) = simulation.track() else {
-        eprintln!("Simulation track missing after run");
+    if cli.show_leaderboard {
+        let path = cli.leaderboard.as_deref().unwrap_or(DEFAULT_LEADERBOARD_FILE);
+        match Leaderboard::load(path) {
+            Ok(leaderboard) => println!("{leaderboard}"),
+            Err(err) => {
+                eprintln!("Failed to load leaderboard: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    if cli.list_scenarios {
+        for demo in Demo::ALL {
+            println!("{} - {}", demo.name(), demo.description());
+        }
+        return;
+    }
+    if let Some(name) = &cli.scenario {
+        match scenarios::run(name, &config.output_dir) {
+            Ok(report) => {
+                println!("{report}");
+                if let (Some(path), Ok(demo)) = (&cli.leaderboard, name.parse::<Demo>()) {
+                    let track = report.shared_track();
+                    if let Err(err) =
+                        record_best_lap(path, track.as_ref(), demo.controller(), report.runs())
+                    {
+                        eprintln!("Failed to update leaderboard: {err}");
+                    }
+                }
+            }
+            Err(err) => {
+                eprintln!("Failed to run scenario '{name}': {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+
+    match config.resolve_controller_preset() {
+        Ok(Some(preset)) => {
+            run_preset(&cli, &config, &preset);
+            return;
+        }
+        Ok(None) => {}
+        Err(err) => {
+            eprintln!("Failed to resolve controller preset: {err}");
+            std::process::exit(1);
+        }
+    }
+
+    let mut scenario = match Scenario::new(config.clone()) {
+        Ok(scenario) => scenario,
+        Err(err) => {
+            eprintln!("Failed to create scenario: {err}");
+            std::process::exit(1);
+        }
+    };
+    if cli.ndjson {
+        // Snapshots are written while recording, including the initial state
+        scenario
+            .simulation_mut()
+            .set_telemetry(NdjsonTelemetry::stdout());
+    }
+    if let Err(err) = scenario.run(config.dt, config.duration) {
+        eprintln!("Failed to run scenario: {err}");
+        std::process::exit(1);
+    }
+    if let (Some(path), Some(track)) = (&cli.leaderboard, scenario.simulation().track()) {
+        let runs = std::slice::from_ref(scenario.result());
+        if let Err(err) = record_best_lap(path, track, "open_loop", runs) {
+            eprintln!("Failed to update leaderboard: {err}");
+        }
+    }
+    if cli.ndjson {
         return;
+    }
+    render_outputs(&config, &mut scenario, cli.frames_only);
+}
+
+/// Drive the vehicle with the controller of `preset` instead of the configured controls
+fn run_preset(cli: &CliArgs, config: &ScenarioConfig, preset: &ControllerPreset) {
+    eprintln!(
+        "Using {} preset '{}': {:?}",
+        preset.controller, preset.name, preset.parameters
+    );
+    let result = match config.run_with_preset(preset) {
+        Ok(result) => result,
+        Err(err) => {
+            eprintln!("Failed to run scenario: {err}");
+            std::process::exit(1);
+        }
     };
-    let Some(model) = simulation.model() else {
-        eprintln!("Simulation model missing after run");
+    let track = config.track.build();
+    if let Some(path) = &cli.leaderboard {
+        let controller = preset.controller.to_string();
+        let runs = std::slice::from_ref(&result);
+        if let Err(err) = record_best_lap(path, track.as_ref(), &controller, runs) {
+            eprintln!("Failed to update leaderboard: {err}");
+        }
+    }
+    if cli.ndjson {
+        let mut sink = NdjsonTelemetry::stdout();
+        for snapshot in result.snapshots() {
+            if let Err(err) = sink.publish(snapshot) {
+                eprintln!("Failed to write telemetry: {err}");
+                std::process::exit(1);
+            }
+        }
+        return;
+    }
+    render_run(config, track.as_ref(), &result, cli.frames_only);
+}
+
+/// Render the SVG, video, and HTML outputs of a finished scenario
+#[cfg(feature = "video")]
+fn render_outputs(config: &ScenarioConfig, scenario: &mut Scenario, frames_only: bool) {
+    let Some(track) = scenario.simulation().shared_track() else {
+        eprintln!("Simulation track missing after run");
         return;
     };
+    let artifacts = render_run(config, track.as_ref(), scenario.result(), frames_only);
+    if let (Some(artifacts), Some(outputs)) = (artifacts, scenario.simulation_mut().outputs_mut()) {
+        artifacts.register(outputs);
+    }
+}
 
-    if let Err(err) = render_open_loop_outputs(
-        "results/images",
+/// Render the SVG, video, and HTML outputs of a run
+#[cfg(feature = "video")]
+fn render_run(
+    config: &ScenarioConfig,
+    track: &dyn Track,
+    result: &SimulationResult<PointMassState>,
+    frames_only: bool,
+) -> Option<OpenLoopArtifacts> {
+    #[cfg(feature = "profiling")]
+    let render_start = std::time::Instant::now();
+    let rendered = render_simulation_outputs(
+        &config.output_dir,
         track,
-        &states,
-        model.get_size(),
-        dt,
-        duration,
-        fps,
-    ) {
-        eprintln!("Failed to render open-loop outputs: {err}");
+        result,
+        config.model.size,
+        config.fps,
+        &RenderOptions {
+            provenance: Some(Provenance::for_scenario(config)),
+            frames_only,
+            ..RenderOptions::default()
+        },
+    );
+    #[cfg(feature = "profiling")]
+    {
+        let mut profile = result.profile().copied().unwrap_or_default();
+        profile.rendering = render_start.elapsed();
+        eprintln!("Profile: {profile}");
     }
+    rendered
+        .map_err(|err| eprintln!("Failed to render outputs: {err}"))
+        .ok()
+}
+
+#[cfg(not(feature = "video"))]
+fn render_outputs(_config: &ScenarioConfig, _scenario: &mut Scenario, _frames_only: bool) {
+    eprintln!("Rendering skipped: built without the `video` feature");
+}
+
+#[cfg(not(feature = "video"))]
+fn render_run(
+    _config: &ScenarioConfig,
+    _track: &dyn Track,
+    _result: &SimulationResult<PointMassState>,
+    _frames_only: bool,
+) {
+    eprintln!("Rendering skipped: built without the `video` feature");
 }
@@ -0,0 +1,694 @@
+//! Scenario configuration describing a simulation run.
+//!
+//! With the `serde` feature enabled, configs can be stored as JSON (see `io::json`)
+//! so experiments can be archived and rerun. The `toml` and `yaml` features add
+//! scenario files in those formats, picked by extension in `ScenarioConfig::load`:
+//!
+//! ```toml
+//! dt = 0.05
+//! duration = 20.0
+//! controls = [2.0, 0.4]
+//! output_dir = "results/circle"
+//!
+//! [track]
+//! type = "circle"
+//! radius = 50.0
+//! track_width = 10.0
+//! num_points = 100
+//!
+//! [model]
+//! mass = 1200.0
+//! integrator = "rk4"
+//! ```
+
+use crate::controllers::presets::{ControllerPreset, PresetLibrary};
+use crate::controllers::scripted::ControlSchedule;
+use crate::models::base_model::Model;
+use crate::models::integrators::Integrator;
+use crate::models::limits::ControlLimits;
+use crate::models::point_mass::{PointMass, PointMassState, DEFAULT_MASS};
+use crate::simulation::multi_vehicle::MultiVehicleSimulation;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::base_track::{SharedTrack, Track};
+use crate::tracks::circle::CircleTrack;
+use crate::tracks::square::SquareTrack;
+use std::str::FromStr;
+use std::sync::Arc;
+#[cfg(feature = "serde")]
+use {crate::server::registry::Scenario, std::path::Path};
+
+/// Environment variable overriding `ScenarioConfig::dt`
+pub const ENV_DT: &str = "LAP_SIM_DT";
+/// Environment variable overriding `ScenarioConfig::duration`
+pub const ENV_DURATION: &str = "LAP_SIM_DURATION";
+/// Environment variable overriding `ScenarioConfig::fps`
+pub const ENV_FPS: &str = "LAP_SIM_FPS";
+/// Environment variable overriding `ScenarioConfig::output_dir`
+pub const ENV_OUTPUT_DIR: &str = "LAP_SIM_OUTPUT_DIR";
+
+/// Parametric description of a track
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(tag = "type", rename_all = "snake_case"))]
+pub enum TrackConfig {
+    /// Circular track, see `CircleTrack::new`
+    Circle {
+        radius: f64,
+        track_width: f64,
+        num_points: usize,
+    },
+    /// Square track, see `SquareTrack::new`
+    Square {
+        height: f64,
+        track_width: f64,
+        points_per_side: usize,
+    },
+}
+
+impl TrackConfig {
+    /// Track type name used to select controller presets (e.g., "circle")
+    pub fn kind(&self) -> &'static str {
+        match self {
+            TrackConfig::Circle { .. } => "circle",
+            TrackConfig::Square { .. } => "square",
+        }
+    }
+
+    /// Check that the parameters describe a valid track
+    pub fn validate(&self) -> crate::Result<()> {
+        match *self {
+            TrackConfig::Circle {
+                radius,
+                track_width,
+                num_points,
+            } => {
+                if !(radius > 0.0 && track_width > 0.0 && num_points >= 3) {
+                    return Err(crate::Error::invalid_config(
+                        "circle track needs a positive radius and width and at least 3 points",
+                    ));
+                }
+            }
+            TrackConfig::Square {
+                height,
+                track_width,
+                points_per_side,
+            } => {
+                if !(height > 0.0 && track_width > 0.0 && points_per_side >= 1) {
+                    return Err(crate::Error::invalid_config(
+                        "square track needs a positive height and width and at least 1 point per side",
+                    ));
+                }
+            }
+        }
+        Ok(())
+    }
+
+    /// Build the described track
+    pub fn build(&self) -> Box<dyn Track + Send + Sync> {
+        match *self {
+            TrackConfig::Circle {
+                radius,
+                track_width,
+                num_points,
+            } => Box::new(CircleTrack::new(radius, track_width, num_points)),
+            TrackConfig::Square {
+                height,
+                track_width,
+                points_per_side,
+            } => Box::new(SquareTrack::new(height, track_width, points_per_side)),
+        }
+    }
+}
+
+impl Default for TrackConfig {
+    fn default() -> Self {
+        TrackConfig::Circle {
+            radius: 50.0,
+            track_width: 10.0,
+            num_points: 100,
+        }
+    }
+}
+
+/// Point mass parameters of a scenario
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ModelConfig {
+    /// Vehicle (length, width) in meters
+    pub size: (f64, f64),
+    /// Mass in kg
+    pub mass: f64,
+    /// Aerodynamic drag coefficient in 1/m, see `PointMass::set_drag`
+    pub drag: f64,
+    /// Tire-road friction coefficient, `None` for unlimited grip
+    pub friction: Option<f64>,
+    /// Integration scheme
+    pub integrator: Integrator,
+    /// Saturation limits on the control inputs
+    pub limits: ControlLimits,
+}
+
+impl ModelConfig {
+    /// Check that the parameters describe a physical vehicle
+    pub fn validate(&self) -> crate::Result<()> {
+        let (length, width) = self.size;
+        if !(length > 0.0 && width > 0.0 && self.mass > 0.0) {
+            return Err(crate::Error::invalid_config(
+                "model needs a positive length, width, and mass",
+            ));
+        }
+        if !(self.drag.is_finite() && self.drag >= 0.0) {
+            return Err(crate::Error::InvalidConfig(format!(
+                "model drag must be non-negative, got {}",
+                self.drag
+            )));
+        }
+        if let Some(friction) = self.friction
+            && !(friction.is_finite() && friction > 0.0)
+        {
+            return Err(crate::Error::InvalidConfig(format!(
+                "model friction must be positive, got {}",
+                friction
+            )));
+        }
+        Ok(())
+    }
+
+    /// Build the described point mass
+    pub fn build(&self) -> PointMass {
+        let mut model = PointMass::new();
+        model.set_size(self.size.0, self.size.1);
+        model.set_mass(self.mass);
+        model.set_drag(self.drag);
+        model.set_friction(self.friction);
+        model.set_integrator(self.integrator);
+        model.set_limits(self.limits);
+        model
+    }
+}
+
+impl Default for ModelConfig {
+    fn default() -> Self {
+        Self {
+            size: (4.5, 2.0),
+            mass: DEFAULT_MASS,
+            drag: 0.0,
+            friction: None,
+            integrator: Integrator::default(),
+            limits: ControlLimits::none(),
+        }
+    }
+}
+
+/// Everything needed to reproduce a simulation run
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(default))]
+pub struct ScenarioConfig {
+    /// Track to drive on
+    pub track: TrackConfig,
+    /// Vehicle driving on the track
+    pub model: ModelConfig,
+    /// Time step in seconds
+    pub dt: f64,
+    /// Simulated duration in seconds
+    pub duration: f64,
+    /// Frames per second of the rendered video
+    pub fps: u32,
+    /// Constant (acceleration, yaw rate) controls, used when no schedule is given
+    pub controls: (f64, f64),
+    /// Optional time-stamped control schedule replacing the constant controls
+    pub schedule: Option<ControlSchedule>,
+    /// Directory the rendered outputs are written to
+    pub output_dir: String,
+    /// Name of the controller parameter preset to use
+    pub controller_preset: Option<String>,
+    /// Preset file extending (and overriding) the built-in presets
+    pub presets_file: Option<String>,
+}
+
+impl Default for ScenarioConfig {
+    fn default() -> Self {
+        Self {
+            track: TrackConfig::default(),
+            model: ModelConfig::default(),
+            dt: 0.1,
+            duration: 10.0,
+            fps: 10,
+            controls: (2.0, 0.4),
+            schedule: None,
+            output_dir: "results/images".to_string(),
+            controller_preset: None,
+            presets_file: None,
+        }
+    }
+}
+
+impl ScenarioConfig {
+    /// Load a scenario file, choosing the format by extension
+    ///
+    /// `.toml` files need the `toml` feature and `.yaml`/`.yml` files the `yaml` feature;
+    /// anything else is read as JSON (see `io::json::load_json`).
+    ///
+    /// # Arguments
+    /// * `path` - Scenario file (e.g., "scenarios/circle.toml")
+    ///
+    /// # Returns
+    /// The config, `Error::Io` if the file cannot be read, or `Error::InvalidConfig`
+    /// mentioning the file if it cannot be parsed
+    #[cfg(feature = "serde")]
+    pub fn load<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        let path = path.as_ref();
+        let extension = path
+            .extension()
+            .and_then(|extension| extension.to_str())
+            .map(str::to_ascii_lowercase);
+        match extension.as_deref() {
+            Some("toml") => Self::load_toml(path),
+            Some("yaml" | "yml") => Self::load_yaml(path),
+            _ => crate::io::json::load_json(path),
+        }
+    }
+
+    #[cfg(all(feature = "serde", feature = "toml"))]
+    fn load_toml(path: &Path) -> crate::Result<Self> {
+        let contents = crate::io::compression::read_to_string(path)?;
+        toml::from_str(&contents).map_err(|err| {
+            crate::Error::invalid_config(format!("failed to parse {}: {}", path.display(), err))
+        })
+    }
+
+    #[cfg(all(feature = "serde", not(feature = "toml")))]
+    fn load_toml(path: &Path) -> crate::Result<Self> {
+        Err(crate::Error::invalid_config(format!(
+            "{}: TOML scenario files require the `toml` feature",
+            path.display()
+        )))
+    }
+
+    #[cfg(all(feature = "serde", feature = "yaml"))]
+    fn load_yaml(path: &Path) -> crate::Result<Self> {
+        let contents = crate::io::compression::read_to_string(path)?;
+        serde_yaml::from_str(&contents).map_err(|err| {
+            crate::Error::invalid_config(format!("failed to parse {}: {}", path.display(), err))
+        })
+    }
+
+    #[cfg(all(feature = "serde", not(feature = "yaml")))]
+    fn load_yaml(path: &Path) -> crate::Result<Self> {
+        Err(crate::Error::invalid_config(format!(
+            "{}: YAML scenario files require the `yaml` feature",
+            path.display()
+        )))
+    }
+
+    /// Check the track, the model, and the run settings
+    pub fn validate(&self) -> crate::Result<()> {
+        self.track.validate()?;
+        self.model.validate()?;
+        if !(self.dt.is_finite() && self.dt > 0.0) {
+            return Err(crate::Error::InvalidConfig(format!("dt must be positive, got {}", self.dt)));
+        }
+        if !(self.duration.is_finite() && self.duration >= 0.0) {
+            return Err(crate::Error::InvalidConfig(format!(
+                "duration must be non-negative, got {}",
+                self.duration
+            )));
+        }
+        if self.fps == 0 {
+            return Err(crate::Error::invalid_config("fps must be greater than zero"));
+        }
+        Ok(())
+    }
+
+    /// Presets available to this scenario: the built-in ones plus `presets_file`
+    pub fn preset_library(&self) -> crate::Result<PresetLibrary> {
+        let mut library = PresetLibrary::builtin();
+        if let Some(path) = &self.presets_file {
+            library.merge(PresetLibrary::load(path)?);
+        }
+        Ok(library)
+    }
+
+    /// Resolve `controller_preset` by name
+    ///
+    /// # Returns
+    /// `None` when no preset is configured, or `Error::InvalidConfig` if it does not exist
+    /// or is tuned for another track type
+    pub fn resolve_controller_preset(&self) -> crate::Result<Option<ControllerPreset>> {
+        let Some(name) = &self.controller_preset else {
+            return Ok(None);
+        };
+        let library = self.preset_library()?;
+        let preset = library.get(name)?;
+        if let Some(track) = &preset.track
+            && track != self.track.kind()
+        {
+            return Err(crate::Error::InvalidConfig(format!(
+                "preset '{}' is tuned for {} tracks, but the scenario uses a {} track",
+                name,
+                track,
+                self.track.kind()
+            )));
+        }
+        Ok(Some(preset.clone()))
+    }
+
+    /// Drive the point mass with the controller of `preset` from the start of the track
+    ///
+    /// The constant controls and the schedule are not used.
+    ///
+    /// # Returns
+    /// The recorded run, or `Error::InvalidConfig` if the config is invalid or the preset
+    /// cannot drive a vehicle (see `ControllerPreset::build`)
+    pub fn run_with_preset(
+        &self,
+        preset: &ControllerPreset,
+    ) -> crate::Result<SimulationResult<PointMassState>> {
+        self.validate()?;
+        let track: SharedTrack = Arc::from(self.track.build());
+        let controller = preset.build(track.as_ref())?;
+        let mut model = self.model.build();
+        let (x, y, yaw) = track.get_start_position();
+        model.set_position(x, y, yaw);
+        let mut simulation = MultiVehicleSimulation::new(track).with_threads(1);
+        simulation.add_controlled_vehicle(model, controller);
+        let mut result = simulation.run(self.dt, self.duration)?;
+        Ok(result.runs.swap_remove(0))
+    }
+}
+
+/// Load a scenario file (see `ScenarioConfig::load`) and run it for its duration
+///
+/// With a `controller_preset`, the preset's controller drives the vehicle (see
+/// `ScenarioConfig::run_with_preset`); otherwise the controls or the schedule do. Only
+/// the simulation is run; render the outputs into `config.output_dir` with
+/// `plotting::render_simulation_outputs` if needed.
+///
+/// # Arguments
+/// * `path` - Scenario file in JSON, TOML, or YAML
+///
+/// # Returns
+/// The loaded config and the recorded run
+#[cfg(feature = "serde")]
+pub fn run_from_config<P: AsRef<Path>>(
+    path: P,
+) -> crate::Result<(ScenarioConfig, SimulationResult<PointMassState>)> {
+    let config = ScenarioConfig::load(path)?;
+    config.validate()?;
+    if let Some(preset) = config.resolve_controller_preset()? {
+        let result = config.run_with_preset(&preset)?;
+        return Ok((config, result));
+    }
+    let mut scenario = Scenario::new(config.clone())?;
+    scenario.run(config.dt, config.duration)?;
+    Ok((config, scenario.into_result()))
+}
+
+/// Partial scenario settings layered on top of a `ScenarioConfig`
+///
+/// Settings are resolved with the following precedence (highest first):
+/// 1. CLI flags
+/// 2. Environment variables (`LAP_SIM_DT`, `LAP_SIM_DURATION`, `LAP_SIM_FPS`, `LAP_SIM_OUTPUT_DIR`)
+/// 3. Scenario file
+/// 4. `ScenarioConfig::default()`
+#[derive(Debug, Clone, Default, PartialEq)]
+pub struct ConfigOverrides {
+    pub dt: Option<f64>,
+    pub duration: Option<f64>,
+    pub fps: Option<u32>,
+    pub output_dir: Option<String>,
+}
+
+impl ConfigOverrides {
+    /// Read overrides from the process environment
+    pub fn from_env() -> crate::Result<Self> {
+        Self::from_vars(std::env::vars())
+    }
+
+    /// Read overrides from `(name, value)` pairs, ignoring unrelated variables
+    ///
+    /// # Returns
+    /// The overrides, or `Error::InvalidConfig` naming the variable that does not parse
+    ///
+    /// # Arguments
+    /// * `vars` - Environment variables, e.g. `std::env::vars()`
+    pub fn from_vars<I>(vars: I) -> crate::Result<Self>
+    where
+        I: IntoIterator<Item = (String, String)>,
+    {
+        let mut overrides = Self::default();
+        for (name, value) in vars {
+            match name.as_str() {
+                ENV_DT => overrides.dt = Some(parse_value(&name, &value)?),
+                ENV_DURATION => overrides.duration = Some(parse_value(&name, &value)?),
+                ENV_FPS => overrides.fps = Some(parse_value(&name, &value)?),
+                ENV_OUTPUT_DIR => overrides.output_dir = Some(value),
+                _ => {}
+            }
+        }
+        Ok(overrides)
+    }
+
+    /// Combine two layers, preferring values set in `other`
+    pub fn merged(self, other: ConfigOverrides) -> Self {
+        Self {
+            dt: other.dt.or(self.dt),
+            duration: other.duration.or(self.duration),
+            fps: other.fps.or(self.fps),
+            output_dir: other.output_dir.or(self.output_dir),
+        }
+    }
+
+    /// Write the set values into `config`
+    pub fn apply(&self, config: &mut ScenarioConfig) {
+        if let Some(dt) = self.dt {
+            config.dt = dt;
+        }
+        if let Some(duration) = self.duration {
+            config.duration = duration;
+        }
+        if let Some(fps) = self.fps {
+            config.fps = fps;
+        }
+        if let Some(output_dir) = &self.output_dir {
+            config.output_dir = output_dir.clone();
+        }
+    }
+}
+
+/// Parse a setting value, naming the setting in the error
+fn parse_value<T: FromStr>(name: &str, value: &str) -> crate::Result<T> {
+    value
+        .trim()
+        .parse()
+        .map_err(|_| crate::Error::InvalidConfig(format!("invalid value '{}' for {}", value, name)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ConfigOverrides, ModelConfig, ScenarioConfig, TrackConfig};
+    use crate::controllers::presets::PresetLibrary;
+    use crate::models::base_model::Model;
+    use crate::models::integrators::Integrator;
+
+    #[test]
+    fn test_track_config_build() {
+        let circle = TrackConfig::default().build();
+        assert_eq!(circle.get_track_name(), "Circle Track");
+
+        let square = TrackConfig::Square {
+            height: 100.0,
+            track_width: 10.0,
+            points_per_side: 25,
+        }
+        .build();
+        assert_eq!(square.get_track_name(), "Square Track");
+    }
+
+    #[test]
+    fn test_model_config_build_and_validate() {
+        let config = ModelConfig {
+            size: (4.0, 1.8),
+            mass: 1200.0,
+            friction: Some(1.1),
+            integrator: Integrator::Rk4,
+            ..ModelConfig::default()
+        };
+        config.validate().expect("valid model");
+        let model = config.build();
+        assert_eq!(model.get_size(), (4.0, 1.8));
+        assert_eq!(model.mass(), 1200.0);
+        assert_eq!(model.friction(), Some(1.1));
+        assert_eq!(model.integrator(), Integrator::Rk4);
+
+        let massless = ModelConfig {
+            mass: 0.0,
+            ..ModelConfig::default()
+        };
+        assert!(massless.validate().is_err());
+        let scenario = ScenarioConfig {
+            dt: 0.0,
+            ..ScenarioConfig::default()
+        };
+        let err = scenario.validate().expect_err("zero dt");
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(err.to_string().contains("dt must be positive"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_load_toml_scenario() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("scenario.toml");
+        let text = "dt = 0.05\nduration = 2.0\ncontrols = [1.0, 0.2]\n\n\
+                    [track]\ntype = \"square\"\nheight = 80.0\ntrack_width = 8.0\npoints_per_side = 20\n\n\
+                    [model]\nmass = 1200.0\nintegrator = \"rk4\"\n";
+        std::fs::write(&path, text).expect("write scenario");
+
+        let config = ScenarioConfig::load(&path).expect("load toml");
+        assert_eq!(config.dt, 0.05);
+        assert_eq!(config.controls, (1.0, 0.2));
+        assert_eq!(config.track.kind(), "square");
+        assert_eq!(config.model.integrator, Integrator::Rk4);
+        assert_eq!(config.model.size, ModelConfig::default().size);
+        assert_eq!(config.fps, ScenarioConfig::default().fps);
+
+        std::fs::write(&path, "dt = \"fast\"\n").expect("write broken scenario");
+        let err = ScenarioConfig::load(&path).expect_err("invalid dt");
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(err.to_string().contains("scenario.toml"));
+    }
+
+    #[cfg(feature = "yaml")]
+    #[test]
+    fn test_load_yaml_scenario() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("scenario.yml");
+        let text = "duration: 3.0\noutput_dir: results/yaml\n\
+                    track:\n  type: circle\n  radius: 30.0\n  track_width: 6.0\n  num_points: 50\n\
+                    model:\n  friction: 1.0\n";
+        std::fs::write(&path, text).expect("write scenario");
+
+        let config = ScenarioConfig::load(&path).expect("load yaml");
+        assert_eq!(config.duration, 3.0);
+        assert_eq!(config.output_dir, "results/yaml");
+        assert_eq!(config.model.friction, Some(1.0));
+        assert_eq!(config.model.mass, ModelConfig::default().mass);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_run_from_config() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("scenario.json");
+        std::fs::write(&path, r#"{"dt": 0.1, "duration": 1.0, "model": {"mass": 900.0}}"#)
+            .expect("write scenario");
+
+        let (config, result) = super::run_from_config(&path).expect("run scenario");
+        assert_eq!(config.model.mass, 900.0);
+        assert_eq!(result.len(), 11);
+        assert!((result.duration() - 1.0).abs() < 1e-9);
+
+        std::fs::write(&path, r#"{"duration": -1.0}"#).expect("write invalid scenario");
+        assert!(super::run_from_config(&path).is_err());
+    }
+
+    #[test]
+    fn test_resolve_controller_preset() {
+        assert_eq!(ScenarioConfig::default().resolve_controller_preset().expect("no preset"), None);
+
+        let config = ScenarioConfig {
+            controller_preset: Some("circle_stanley".to_string()),
+            ..ScenarioConfig::default()
+        };
+        let preset = config
+            .resolve_controller_preset()
+            .expect("resolve preset")
+            .expect("preset");
+        assert_eq!(preset.get("gain"), Some(1.0));
+
+        let mismatched = ScenarioConfig {
+            controller_preset: Some("square_stanley".to_string()),
+            ..ScenarioConfig::default()
+        };
+        let err = mismatched.resolve_controller_preset().expect_err("track mismatch");
+        assert!(err.to_string().contains("tuned for square tracks"));
+    }
+
+    #[test]
+    fn test_run_with_preset() {
+        let config = ScenarioConfig {
+            dt: 0.05,
+            duration: 20.0,
+            controller_preset: Some("circle_pure_pursuit".to_string()),
+            ..ScenarioConfig::default()
+        };
+        let preset = config
+            .resolve_controller_preset()
+            .expect("resolve preset")
+            .expect("preset");
+        let result = config.run_with_preset(&preset).expect("run with preset");
+        assert_eq!(result.len(), 401);
+        assert!(result.snapshots().iter().all(|snapshot| snapshot.in_track));
+        let last = result.last().expect("final snapshot");
+        assert!((last.state.vx - 12.0).abs() < 0.5, "{}", last.state.vx);
+
+        let pid = PresetLibrary::builtin().get("speed_pid").expect("pid preset").clone();
+        let err = config.run_with_preset(&pid).expect_err("pid preset");
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_resolve_controller_preset_from_file() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("presets.ini");
+        std::fs::write(&path, "[my_pid]\ncontroller = pid\nkp = 4.0\n").expect("write presets");
+
+        let config = ScenarioConfig {
+            controller_preset: Some("my_pid".to_string()),
+            presets_file: Some(path.to_string_lossy().into_owned()),
+            ..ScenarioConfig::default()
+        };
+        let preset = config
+            .resolve_controller_preset()
+            .expect("resolve preset")
+            .expect("preset");
+        assert_eq!(preset.get("kp"), Some(4.0));
+    }
+
+    #[test]
+    fn test_config_overrides_precedence() {
+        let env = ConfigOverrides::from_vars([
+            ("LAP_SIM_DT".to_string(), "0.05".to_string()),
+            ("LAP_SIM_OUTPUT_DIR".to_string(), "env_dir".to_string()),
+            ("PATH".to_string(), "/usr/bin".to_string()),
+        ])
+        .expect("parse env overrides");
+        let cli = ConfigOverrides {
+            output_dir: Some("cli_dir".to_string()),
+            fps: Some(30),
+            ..ConfigOverrides::default()
+        };
+
+        let mut config = ScenarioConfig {
+            duration: 5.0,
+            ..ScenarioConfig::default()
+        };
+        env.merged(cli).apply(&mut config);
+
+        assert_eq!(config.dt, 0.05);
+        assert_eq!(config.duration, 5.0);
+        assert_eq!(config.fps, 30);
+        assert_eq!(config.output_dir, "cli_dir");
+    }
+
+    #[test]
+    fn test_config_overrides_invalid_value() {
+        let err = ConfigOverrides::from_vars([("LAP_SIM_FPS".to_string(), "fast".to_string())])
+            .expect_err("invalid fps");
+        assert!(err.to_string().contains("LAP_SIM_FPS"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+    }
+}
@@ -0,0 +1,105 @@
+use crate::tracks::arc_length::TrackPosition;
+
+/// Breakdown of the yaw rate command of the last control step
+///
+/// The feedback part corrects the tracking errors; the feedforward part is what the
+/// path itself demands (`v * kappa(s)`), 0 for controllers without one. Both are
+/// before the friction limit scales the command.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ControllerTelemetry {
+    /// Yaw rate in radians/s from the tracking errors
+    pub feedback_yaw_rate: f64,
+    /// Yaw rate in radians/s from the path curvature
+    pub feedforward_yaw_rate: f64,
+}
+
+impl ControllerTelemetry {
+    /// Total yaw rate command in radians/s, before the friction limit
+    pub fn yaw_rate(&self) -> f64 {
+        self.feedback_yaw_rate + self.feedforward_yaw_rate
+    }
+}
+
+/// Trait for controllers computing model control inputs from the current state
+pub trait Controller<S> {
+    /// Compute the control inputs for the next step
+    ///
+    /// # Arguments
+    /// * `time` - Current simulation time in seconds
+    /// * `state` - Current model state
+    ///
+    /// # Returns
+    /// Tuple of (ax, yaw_rate) where ax is the body frame acceleration in m/s² and
+    /// yaw_rate is in radians/s
+    fn step(&mut self, time: f64, state: &S) -> (f64, f64);
+
+    /// Compute the control inputs knowing where the vehicle is relative to the track
+    ///
+    /// Simulations that already locate the vehicles on the center line (e.g.
+    /// `MultiVehicleSimulation`) call this instead of `step`, so controllers needing the
+    /// distance along the track, the lateral error, or the curvature do not search the
+    /// center line again. The default ignores the position and calls `step`.
+    ///
+    /// # Arguments
+    /// * `time` - Current simulation time in seconds
+    /// * `state` - Current model state
+    /// * `position` - Position relative to the center line, `None` if the track has none
+    ///
+    /// # Returns
+    /// Tuple of (ax, yaw_rate), see `step`
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &S,
+        _position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        self.step(time, state)
+    }
+
+    /// Tell the controller the friction limit of the model it drives
+    ///
+    /// Simulations call this before every control step with `Model::friction_limit`, so
+    /// controllers can keep their commands within the friction circle (e.g., brake less
+    /// while cornering). The default ignores the limit.
+    ///
+    /// # Arguments
+    /// * `limit` - Largest combined acceleration in m/s², `None` for unlimited grip
+    fn set_friction_limit(&mut self, _limit: Option<f64>) {}
+
+    /// Breakdown of the last command, `None` before the first step or if the
+    /// controller does not report one (the default)
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        None
+    }
+
+    /// Reset any internal controller state
+    fn reset(&mut self) {}
+}
+
+/// Boxed controllers are controllers too, so `Box<dyn Controller<S>>` can be swapped at runtime
+impl<S, C: Controller<S> + ?Sized> Controller<S> for Box<C> {
+    fn step(&mut self, time: f64, state: &S) -> (f64, f64) {
+        (**self).step(time, state)
+    }
+
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &S,
+        position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        (**self).step_on_track(time, state, position)
+    }
+
+    fn set_friction_limit(&mut self, limit: Option<f64>) {
+        (**self).set_friction_limit(limit)
+    }
+
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        (**self).telemetry()
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+}
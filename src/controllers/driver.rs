@@ -0,0 +1,218 @@
+//! Human driver wrapped around any controller.
+//!
+//! A human reacts late, cannot hold the steering perfectly still, and does not brake as
+//! hard or as consistently as an ideal controller asks for. `HumanDriver` applies these
+//! imperfections to the commands of the wrapped controller:
+//! - commands reach the vehicle `reaction_time` seconds after the controller issued
+//!   them (before the first one arrives the driver coasts),
+//! - gaussian jitter is added to the yaw rate,
+//! - decelerations are scaled by `braking_efficiency` with a random relative error.
+//!
+//! The noise comes from a seeded generator, so a driver with the same profile drives the
+//! same lap every time.
+
+use super::base_controller::{Controller, ControllerTelemetry};
+use crate::math::random::SplitMix64;
+use crate::tracks::arc_length::TrackPosition;
+use std::collections::VecDeque;
+
+/// Imperfections of a human driver
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DriverProfile {
+    /// Delay in seconds between a controller command and the vehicle receiving it
+    pub reaction_time: f64,
+    /// Standard deviation of the yaw rate jitter in radians/s
+    pub steering_noise: f64,
+    /// Share of the requested deceleration actually applied
+    pub braking_efficiency: f64,
+    /// Standard deviation of the relative error of each braking command
+    pub braking_noise: f64,
+    /// Seed of the noise
+    pub seed: u64,
+}
+
+impl DriverProfile {
+    /// Driver without imperfections, passing the controller commands through unchanged
+    pub fn ideal() -> Self {
+        Self {
+            reaction_time: 0.0,
+            steering_noise: 0.0,
+            braking_efficiency: 1.0,
+            braking_noise: 0.0,
+            seed: 0,
+        }
+    }
+
+    /// Use another seed for the noise
+    pub fn with_seed(self, seed: u64) -> Self {
+        Self { seed, ..self }
+    }
+}
+
+impl Default for DriverProfile {
+    /// Attentive amateur: 0.25 s reaction time, light jitter, 85 % braking
+    fn default() -> Self {
+        Self {
+            reaction_time: 0.25,
+            steering_noise: 0.02,
+            braking_efficiency: 0.85,
+            braking_noise: 0.1,
+            seed: 0,
+        }
+    }
+}
+
+/// Controller driving like a human with the commands of another controller
+#[derive(Debug, Clone)]
+pub struct HumanDriver<C> {
+    controller: C,
+    profile: DriverProfile,
+    rng: SplitMix64,
+    /// Commands issued by the controller and not yet received, as (time, ax, yaw_rate)
+    pending: VecDeque<(f64, f64, f64)>,
+    /// Last command received by the vehicle
+    current: (f64, f64),
+}
+
+impl<C> HumanDriver<C> {
+    /// Wrap `controller` with the imperfections of `profile`
+    pub fn new(controller: C, profile: DriverProfile) -> Self {
+        Self {
+            controller,
+            rng: SplitMix64::new(profile.seed),
+            profile,
+            pending: VecDeque::new(),
+            current: (0.0, 0.0),
+        }
+    }
+
+    pub fn profile(&self) -> &DriverProfile {
+        &self.profile
+    }
+
+    /// Get the wrapped controller
+    pub fn controller(&self) -> &C {
+        &self.controller
+    }
+
+    /// Unwrap the controller
+    pub fn into_inner(self) -> C {
+        self.controller
+    }
+
+    /// Queue the controller command issued at `time` and return the one the vehicle receives
+    fn drive(&mut self, time: f64, (ax, yaw_rate): (f64, f64)) -> (f64, f64) {
+        self.pending.push_back((time, ax, yaw_rate));
+        while let Some(&(issued, ax, yaw_rate)) = self.pending.front() {
+            // Tolerate rounding in accumulated simulation times
+            if issued + self.profile.reaction_time > time + 1e-9 {
+                break;
+            }
+            self.pending.pop_front();
+            self.current = (ax, yaw_rate);
+        }
+
+        let (mut ax, mut yaw_rate) = self.current;
+        if self.profile.steering_noise > 0.0 {
+            yaw_rate += self.profile.steering_noise * self.rng.gaussian();
+        }
+        if ax < 0.0 {
+            let error = if self.profile.braking_noise > 0.0 {
+                self.profile.braking_noise * self.rng.gaussian()
+            } else {
+                0.0
+            };
+            ax *= (self.profile.braking_efficiency * (1.0 + error)).max(0.0);
+        }
+        (ax, yaw_rate)
+    }
+}
+
+impl<S, C: Controller<S>> Controller<S> for HumanDriver<C> {
+    fn step(&mut self, time: f64, state: &S) -> (f64, f64) {
+        let command = self.controller.step(time, state);
+        self.drive(time, command)
+    }
+
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &S,
+        position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        let command = self.controller.step_on_track(time, state, position);
+        self.drive(time, command)
+    }
+
+    fn set_friction_limit(&mut self, limit: Option<f64>) {
+        self.controller.set_friction_limit(limit);
+    }
+
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        self.controller.telemetry()
+    }
+
+    fn reset(&mut self) {
+        self.controller.reset();
+        self.rng = SplitMix64::new(self.profile.seed);
+        self.pending.clear();
+        self.current = (0.0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DriverProfile, HumanDriver};
+    use crate::controllers::base_controller::Controller;
+    use crate::controllers::scripted::{ControlSample, ControlSchedule, ScriptedController};
+
+    fn step_input() -> ScriptedController {
+        let schedule = ControlSchedule::new(vec![
+            ControlSample { time: 1.0, ax: 2.0, yaw_rate: 0.1 },
+            ControlSample { time: 2.0, ax: -4.0, yaw_rate: 0.0 },
+        ])
+        .expect("schedule");
+        ScriptedController::new(schedule)
+    }
+
+    fn drive(driver: &mut HumanDriver<ScriptedController>) -> Vec<(f64, f64)> {
+        (0..40).map(|i| driver.step(i as f64 * 0.1, &())).collect()
+    }
+
+    #[test]
+    fn test_reaction_delay_and_braking() {
+        let profile = DriverProfile {
+            reaction_time: 0.3,
+            steering_noise: 0.0,
+            braking_efficiency: 0.5,
+            braking_noise: 0.0,
+            seed: 0,
+        };
+        let mut driver = HumanDriver::new(step_input(), profile);
+        let commands = drive(&mut driver);
+
+        // The step at 1 s arrives at 1.3 s, the braking at 2 s arrives at 2.3 s at half strength
+        assert_eq!(commands[12], (0.0, 0.0));
+        assert_eq!(commands[13], (2.0, 0.1));
+        assert_eq!(commands[22], (2.0, 0.1));
+        assert_eq!(commands[23], (-2.0, 0.0));
+
+        let mut ideal = HumanDriver::new(step_input(), DriverProfile::ideal());
+        assert_eq!(ideal.step(1.0, &()), (2.0, 0.1));
+    }
+
+    #[test]
+    fn test_noise_is_seeded() {
+        let mut a = HumanDriver::new(step_input(), DriverProfile::default());
+        let mut b = HumanDriver::new(step_input(), DriverProfile::default());
+        let mut c = HumanDriver::new(step_input(), DriverProfile::default().with_seed(1));
+        let first = drive(&mut a);
+        assert_eq!(first, drive(&mut b));
+        assert_ne!(first, drive(&mut c));
+        assert!(first.iter().any(|&(_, yaw_rate)| yaw_rate != 0.0 && yaw_rate != 0.1));
+
+        // Reset replays the same lap
+        Controller::<()>::reset(&mut a);
+        assert_eq!(first, drive(&mut a));
+    }
+}
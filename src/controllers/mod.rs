@@ -0,0 +1,6 @@
+pub mod base_controller;
+pub mod driver;
+pub mod presets;
+pub mod pure_pursuit;
+pub mod scripted;
+pub mod stanley;
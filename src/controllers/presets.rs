@@ -0,0 +1,426 @@
+//! Named controller parameter sets loaded from preset files.
+//!
+//! A preset file is a small INI-style text file with one section per preset:
+//!
+//! ```text
+//! # Pure pursuit tuned for the circular track
+//! [circle_pure_pursuit]
+//! controller = pure_pursuit
+//! track = circle
+//! lookahead = 8.0
+//! target_speed = 12.0
+//! ```
+//!
+//! `controller` (`pure_pursuit`, `stanley`, or `pid`) is required; `track` is optional
+//! and restricts the preset to one track type. Every other key is a numeric parameter.
+//!
+//! `ControllerPreset::build` turns a pure pursuit or Stanley preset into the controller
+//! driving a run. `pid` presets only store gains: there is no PID controller yet, so
+//! they can be listed and loaded but not driven.
+
+use super::base_controller::Controller;
+use super::pure_pursuit::PurePursuitController;
+use super::stanley::StanleyController;
+use crate::error::{Error, Result};
+use crate::io::compression;
+use crate::models::point_mass::PointMassState;
+use crate::tracks::base_track::Track;
+use std::collections::BTreeMap;
+use std::fmt;
+use std::path::Path;
+use std::str::FromStr;
+
+/// Controller family a preset applies to
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ControllerKind {
+    PurePursuit,
+    Stanley,
+    Pid,
+}
+
+impl fmt::Display for ControllerKind {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            ControllerKind::PurePursuit => "pure_pursuit",
+            ControllerKind::Stanley => "stanley",
+            ControllerKind::Pid => "pid",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+impl FromStr for ControllerKind {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        match value.trim().to_ascii_lowercase().as_str() {
+            "pure_pursuit" | "purepursuit" => Ok(ControllerKind::PurePursuit),
+            "stanley" => Ok(ControllerKind::Stanley),
+            "pid" => Ok(ControllerKind::Pid),
+            other => Err(format!(
+                "unknown controller '{}' (expected pure_pursuit, stanley, or pid)",
+                other
+            )),
+        }
+    }
+}
+
+/// Named set of controller parameters
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControllerPreset {
+    pub name: String,
+    pub controller: ControllerKind,
+    /// Track type the preset is tuned for (e.g., "circle"), or `None` for any track
+    pub track: Option<String>,
+    pub parameters: BTreeMap<String, f64>,
+}
+
+impl ControllerPreset {
+    /// Value of a parameter, if set
+    pub fn get(&self, key: &str) -> Option<f64> {
+        self.parameters.get(key).copied()
+    }
+
+    /// Value of a parameter, or `default` if it is not set
+    pub fn get_or(&self, key: &str, default: f64) -> f64 {
+        self.get(key).unwrap_or(default)
+    }
+
+    /// Build the controller the preset describes, following the center line of `track`
+    ///
+    /// # Returns
+    /// The controller, or `Error::InvalidConfig` for a `pid` preset, which no controller
+    /// of this crate can drive
+    pub fn build(&self, track: &dyn Track) -> Result<Box<dyn Controller<PointMassState> + Send>> {
+        match self.controller {
+            ControllerKind::PurePursuit => {
+                Ok(Box::new(PurePursuitController::from_preset(track, self)?))
+            }
+            ControllerKind::Stanley => Ok(Box::new(StanleyController::from_preset(track, self)?)),
+            ControllerKind::Pid => Err(Error::InvalidConfig(format!(
+                "preset '{}' only holds pid gains and cannot drive a vehicle; \
+                 use a pure_pursuit or stanley preset",
+                self.name
+            ))),
+        }
+    }
+}
+
+/// Collection of presets addressable by name
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PresetLibrary {
+    presets: Vec<ControllerPreset>,
+}
+
+impl PresetLibrary {
+    /// Presets shipped with the crate, one per controller and track type
+    pub fn builtin() -> Self {
+        Self::parse(BUILTIN_PRESETS).expect("built-in presets are valid")
+    }
+
+    /// Parse presets from INI-style text (see the module documentation)
+    ///
+    /// # Returns
+    /// The presets, or `Error::InvalidConfig` naming the line that is malformed
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut presets: Vec<ControllerPreset> = Vec::new();
+        let mut current: Option<Section> = None;
+
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line.starts_with(';') {
+                continue;
+            }
+            if let Some(name) = line
+                .strip_prefix('[')
+                .and_then(|rest| rest.strip_suffix(']'))
+            {
+                let name = name.trim();
+                if name.is_empty() {
+                    let message = format!("line {}: empty preset name", line_index + 1);
+                    return Err(Error::invalid_config(message));
+                }
+                if let Some(section) = current.take() {
+                    presets.push(section.finish()?);
+                }
+                if presets.iter().any(|preset| preset.name == name) {
+                    let message = format!("line {}: duplicate preset '{}'", line_index + 1, name);
+                    return Err(Error::invalid_config(message));
+                }
+                current = Some(Section::new(name));
+                continue;
+            }
+
+            let Some(section) = current.as_mut() else {
+                let message = format!("line {}: key outside of a [preset] section", line_index + 1);
+                return Err(Error::invalid_config(message));
+            };
+            let (key, value) = line
+                .split_once('=')
+                .ok_or_else(|| {
+                    Error::invalid_config(format!("line {}: expected key = value", line_index + 1))
+                })?;
+            section.set(key.trim(), value.trim()).map_err(|err| {
+                Error::invalid_config(format!("line {}: {}", line_index + 1, err))
+            })?;
+        }
+        if let Some(section) = current {
+            presets.push(section.finish()?);
+        }
+
+        Ok(Self { presets })
+    }
+
+    /// Load presets from a file
+    ///
+    /// # Returns
+    /// The presets, `Error::Io` if the file cannot be read, or `Error::InvalidConfig`
+    /// mentioning the file if it is malformed
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = compression::read_to_string(path)?;
+        Self::parse(&text).map_err(|err| err.context(path.display()))
+    }
+
+    /// All presets in file order
+    pub fn presets(&self) -> &[ControllerPreset] {
+        &self.presets
+    }
+
+    /// Preset with the given name
+    ///
+    /// # Returns
+    /// The preset, or `Error::InvalidConfig` listing the available presets
+    pub fn get(&self, name: &str) -> Result<&ControllerPreset> {
+        self.presets
+            .iter()
+            .find(|preset| preset.name == name)
+            .ok_or_else(|| {
+                let names: Vec<&str> = self.presets.iter().map(|p| p.name.as_str()).collect();
+                Error::invalid_config(format!(
+                    "unknown preset '{}' (available: {})",
+                    name,
+                    names.join(", ")
+                ))
+            })
+    }
+
+    /// Best preset for a controller on a track type
+    ///
+    /// A preset tuned for `track` wins over a track-agnostic one.
+    pub fn find(&self, controller: ControllerKind, track: &str) -> Option<&ControllerPreset> {
+        let track = track.to_ascii_lowercase();
+        let candidates = || {
+            self.presets
+                .iter()
+                .filter(|preset| preset.controller == controller)
+        };
+        candidates()
+            .find(|preset| preset.track.as_deref() == Some(track.as_str()))
+            .or_else(|| candidates().find(|preset| preset.track.is_none()))
+    }
+
+    /// Add presets from `other`, replacing presets with the same name
+    pub fn merge(&mut self, other: PresetLibrary) {
+        for preset in other.presets {
+            match self
+                .presets
+                .iter_mut()
+                .find(|existing| existing.name == preset.name)
+            {
+                Some(existing) => *existing = preset,
+                None => self.presets.push(preset),
+            }
+        }
+    }
+}
+
+/// Preset section being parsed
+struct Section {
+    name: String,
+    controller: Option<ControllerKind>,
+    track: Option<String>,
+    parameters: BTreeMap<String, f64>,
+}
+
+impl Section {
+    fn new(name: &str) -> Self {
+        Self {
+            name: name.to_string(),
+            controller: None,
+            track: None,
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    fn set(&mut self, key: &str, value: &str) -> Result<(), String> {
+        match key {
+            "controller" => self.controller = Some(value.parse()?),
+            "track" => self.track = Some(value.to_ascii_lowercase()),
+            _ => {
+                let number = value
+                    .parse::<f64>()
+                    .ok()
+                    .filter(|number| number.is_finite())
+                    .ok_or_else(|| format!("invalid value '{}' for {}", value, key))?;
+                self.parameters.insert(key.to_string(), number);
+            }
+        }
+        Ok(())
+    }
+
+    fn finish(self) -> Result<ControllerPreset> {
+        let controller = self
+            .controller
+            .ok_or_else(|| {
+                Error::invalid_config(format!(
+                    "preset '{}' is missing the controller key",
+                    self.name
+                ))
+            })?;
+        Ok(ControllerPreset {
+            name: self.name,
+            controller,
+            track: self.track,
+            parameters: self.parameters,
+        })
+    }
+}
+
+const BUILTIN_PRESETS: &str = "\
+[circle_pure_pursuit]
+controller = pure_pursuit
+track = circle
+lookahead = 8.0
+target_speed = 12.0
+
+[square_pure_pursuit]
+controller = pure_pursuit
+track = square
+lookahead = 5.0
+target_speed = 8.0
+
+[circle_stanley]
+controller = stanley
+track = circle
+gain = 1.0
+softening = 1.0
+target_speed = 12.0
+
+[square_stanley]
+controller = stanley
+track = square
+gain = 2.0
+softening = 1.0
+target_speed = 8.0
+
+[speed_pid]
+controller = pid
+kp = 1.0
+ki = 0.1
+kd = 0.05
+";
+
+#[cfg(test)]
+mod tests {
+    use super::{ControllerKind, PresetLibrary};
+    use crate::models::point_mass::PointMassState;
+    use crate::tracks::base_track::Track;
+    use crate::tracks::circle::CircleTrack;
+
+    #[test]
+    fn test_parse_presets() {
+        let library = PresetLibrary::parse(
+            "# comment\n[fast]\ncontroller = stanley\ntrack = Circle\ngain = 2.5\n\n[slow]\ncontroller = pid\nkp = 0.5\n",
+        )
+        .expect("parse presets");
+
+        let fast = library.get("fast").expect("fast preset");
+        assert_eq!(fast.controller, ControllerKind::Stanley);
+        assert_eq!(fast.track.as_deref(), Some("circle"));
+        assert_eq!(fast.get("gain"), Some(2.5));
+        assert_eq!(fast.get_or("softening", 1.0), 1.0);
+        assert_eq!(library.presets().len(), 2);
+    }
+
+    #[test]
+    fn test_parse_presets_errors() {
+        let cases = [
+            ("gain = 1\n", "outside of a [preset]"),
+            ("[a]\ngain = 1\n", "missing the controller"),
+            ("[a]\ncontroller = mpc\n", "unknown controller 'mpc'"),
+            (
+                "[a]\ncontroller = pid\nkp = fast\n",
+                "line 3: invalid value",
+            ),
+            (
+                "[a]\ncontroller = pid\n[a]\ncontroller = pid\n",
+                "duplicate preset 'a'",
+            ),
+        ];
+        for (text, expected) in cases {
+            let err = PresetLibrary::parse(text).expect_err("expected parse error");
+            assert!(err.to_string().contains(expected), "{}: {}", expected, err);
+            assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        }
+    }
+
+    #[test]
+    fn test_find_prefers_track_specific_presets() {
+        let library = PresetLibrary::builtin();
+
+        let square = library
+            .find(ControllerKind::PurePursuit, "square")
+            .expect("square preset");
+        assert_eq!(square.name, "square_pure_pursuit");
+        let pid = library
+            .find(ControllerKind::Pid, "circle")
+            .expect("pid preset");
+        assert_eq!(pid.name, "speed_pid");
+        assert!(library.find(ControllerKind::Stanley, "oval").is_none());
+
+        let err = library.get("missing").expect_err("expected unknown preset");
+        assert!(err.to_string().contains("available: circle_pure_pursuit"));
+    }
+
+    #[test]
+    fn test_build_controllers_from_presets() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let (x, y, yaw) = track.get_start_position();
+        let start = PointMassState { x, y, vx: 0.0, vy: 0.0, yaw };
+        for preset in PresetLibrary::builtin().presets() {
+            match preset.controller {
+                ControllerKind::PurePursuit | ControllerKind::Stanley => {
+                    let mut controller = preset.build(&track).expect("controller");
+                    // Starting at rest on the center line: speed up, barely steer
+                    let (ax, yaw_rate) = controller.step(0.0, &start);
+                    assert!(ax > 0.0, "{}: {}", preset.name, ax);
+                    assert!(yaw_rate.abs() < 0.1, "{}: {}", preset.name, yaw_rate);
+                }
+                ControllerKind::Pid => {
+                    let err = preset.build(&track).err().expect("pid preset");
+                    assert!(err.to_string().contains("cannot drive a vehicle"), "{err}");
+                    assert!(matches!(err, crate::Error::InvalidConfig(_)));
+                }
+            }
+        }
+    }
+
+    #[test]
+    fn test_merge_replaces_by_name() {
+        let mut library = PresetLibrary::builtin();
+        let count = library.presets().len();
+        library.merge(
+            PresetLibrary::parse("[speed_pid]\ncontroller = pid\nkp = 3.0\n").expect("parse"),
+        );
+
+        assert_eq!(library.presets().len(), count);
+        assert_eq!(
+            library.get("speed_pid").expect("preset").get("kp"),
+            Some(3.0)
+        );
+    }
+}
@@ -0,0 +1,286 @@
+//! Pure pursuit path tracking controller.
+//!
+//! Aims at the point of the track center line `lookahead` meters ahead of the vehicle
+//! and steers along the circular arc through it, `kappa = 2 * y / L²`, where `y` is
+//! the lateral offset of the point in the vehicle frame and `L` its distance. The
+//! speed loop, the steering limit, and the kinematic bicycle turning steering into
+//! yaw rate are those of the Stanley controller.
+//!
+//! With feedforward enabled, the yaw rate the path demands, `v * kappa(s)`, is added
+//! to the feedback as in `StanleyController::with_feedforward`. The feedback then
+//! pursues the aim point relative to the path: the arc a vehicle on the center line
+//! would steer along to reach it is taken out of the pursuit arc. On a constant radius
+//! turn the two parts add up to plain pure pursuit, while at curvature changes the
+//! vehicle no longer turns in before the path does, so it cuts corners less.
+
+use super::base_controller::{Controller, ControllerTelemetry};
+use super::presets::{ControllerKind, ControllerPreset};
+use super::stanley::{
+    friction_limited, speed_command, DEFAULT_TARGET_SPEED, DEFAULT_WHEELBASE, MAX_STEER,
+};
+use crate::error::{Error, Result};
+use crate::models::point_mass::PointMassState;
+use crate::tracks::arc_length::{ArcLengthTable, TrackPosition};
+use crate::tracks::base_track::Track;
+
+/// Lookahead distance in meters used when a preset does not set `lookahead`
+pub const DEFAULT_LOOKAHEAD: f64 = 6.0;
+
+/// Pure pursuit controller following the center line of a track at a constant speed
+#[derive(Debug, Clone, PartialEq)]
+pub struct PurePursuitController {
+    center_line: ArcLengthTable,
+    lookahead: f64,
+    target_speed: f64,
+    wheelbase: f64,
+    feedforward: bool,
+    friction_limit: Option<f64>, // Combined acceleration in m/s² the model can transmit
+    telemetry: Option<ControllerTelemetry>,
+}
+
+impl PurePursuitController {
+    /// Create a controller following the center line of `track`
+    ///
+    /// # Arguments
+    /// * `track` - Track whose center line is followed
+    /// * `lookahead` - Distance in meters along the center line to the aim point
+    /// * `target_speed` - Speed in m/s held by the speed loop
+    pub fn new(track: &dyn Track, lookahead: f64, target_speed: f64) -> Self {
+        Self {
+            center_line: ArcLengthTable::from_track(track),
+            lookahead,
+            target_speed,
+            wheelbase: DEFAULT_WHEELBASE,
+            feedforward: false,
+            friction_limit: None,
+            telemetry: None,
+        }
+    }
+
+    /// Create a controller from a `pure_pursuit` preset
+    ///
+    /// Reads the `lookahead`, `target_speed`, and `wheelbase` parameters and falls back
+    /// to the defaults for missing ones. A nonzero `feedforward` parameter enables the
+    /// curvature feedforward.
+    ///
+    /// # Returns
+    /// The controller, or `Error::InvalidConfig` if the preset is for another controller
+    pub fn from_preset(track: &dyn Track, preset: &ControllerPreset) -> Result<Self> {
+        if preset.controller != ControllerKind::PurePursuit {
+            return Err(Error::InvalidConfig(format!(
+                "preset '{}' is for the {} controller, not pure_pursuit",
+                preset.name, preset.controller
+            )));
+        }
+        Ok(Self::new(
+            track,
+            preset.get_or("lookahead", DEFAULT_LOOKAHEAD),
+            preset.get_or("target_speed", DEFAULT_TARGET_SPEED),
+        )
+        .with_wheelbase(preset.get_or("wheelbase", DEFAULT_WHEELBASE))
+        .with_feedforward(preset.get_or("feedforward", 0.0) != 0.0))
+    }
+
+    /// Use another wheelbase in meters to turn steering angles into yaw rates
+    pub fn with_wheelbase(mut self, wheelbase: f64) -> Self {
+        self.wheelbase = wheelbase;
+        self
+    }
+
+    /// Add the curvature feedforward `v * kappa(s)` to the yaw rate
+    pub fn with_feedforward(mut self, feedforward: bool) -> Self {
+        self.feedforward = feedforward;
+        self
+    }
+
+    pub fn feedforward(&self) -> bool {
+        self.feedforward
+    }
+
+    pub fn lookahead(&self) -> f64 {
+        self.lookahead
+    }
+
+    pub fn target_speed(&self) -> f64 {
+        self.target_speed
+    }
+
+    /// Change the speed in m/s held by the speed loop
+    pub fn set_target_speed(&mut self, target_speed: f64) {
+        self.target_speed = target_speed;
+    }
+
+    /// Curvature in 1/m of the arc from the vehicle to the aim point
+    fn arc_curvature(&self, state: &PointMassState, position: &TrackPosition) -> f64 {
+        self.arc_curvature_from(state.x, state.y, state.yaw, position.s)
+    }
+
+    /// Curvature in 1/m of the arc from (x, y) at heading `yaw` to the aim point
+    /// `lookahead` meters past `s`
+    fn arc_curvature_from(&self, x: f64, y: f64, yaw: f64, s: f64) -> f64 {
+        let Some((aim_x, aim_y, _)) = self.center_line.point_at(s + self.lookahead) else {
+            return 0.0;
+        };
+        let (dx, dy) = (aim_x - x, aim_y - y);
+        let distance_squared = dx * dx + dy * dy;
+        if distance_squared <= f64::EPSILON {
+            return 0.0;
+        }
+        // Offset of the aim point to the left
+        let lateral = dy * yaw.cos() - dx * yaw.sin();
+        2.0 * lateral / distance_squared
+    }
+
+    fn controls(
+        &mut self,
+        state: &PointMassState,
+        position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        let ax = speed_command(self.target_speed, state.vx);
+        let Some(position) = position else {
+            self.telemetry = Some(ControllerTelemetry::default());
+            return (ax, 0.0);
+        };
+        let mut curvature = self.arc_curvature(state, position);
+        // With feedforward, only pursue what the path itself does not already turn
+        if self.feedforward
+            && let Some((x, y, yaw)) = self.center_line.point_at(position.s)
+        {
+            curvature -= self.arc_curvature_from(x, y, yaw, position.s);
+        }
+        let steer = (self.wheelbase * curvature)
+            .atan()
+            .clamp(-MAX_STEER, MAX_STEER);
+        let telemetry = ControllerTelemetry {
+            feedback_yaw_rate: state.vx * steer.tan() / self.wheelbase,
+            feedforward_yaw_rate: if self.feedforward {
+                state.vx * position.curvature
+            } else {
+                0.0
+            },
+        };
+        self.telemetry = Some(telemetry);
+        friction_limited(state.vx, ax, telemetry.yaw_rate(), self.friction_limit)
+    }
+}
+
+impl Controller<PointMassState> for PurePursuitController {
+    fn step(&mut self, _time: f64, state: &PointMassState) -> (f64, f64) {
+        let position = self.center_line.position(state.x, state.y);
+        self.controls(state, position.as_ref())
+    }
+
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &PointMassState,
+        position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        match position {
+            Some(position) => self.controls(state, Some(position)),
+            None => self.step(time, state),
+        }
+    }
+
+    fn set_friction_limit(&mut self, limit: Option<f64>) {
+        self.friction_limit = limit;
+    }
+
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        self.telemetry
+    }
+
+    fn reset(&mut self) {
+        self.telemetry = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PurePursuitController;
+    use crate::controllers::base_controller::Controller;
+    use crate::controllers::presets::PresetLibrary;
+    use crate::models::point_mass::{PointMass, PointMassState};
+    use crate::simulation::multi_vehicle::MultiVehicleSimulation;
+    use crate::tracks::arc_length::ArcLengthTable;
+    use crate::tracks::base_track::SharedTrack;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+    use std::f64::consts::FRAC_PI_2;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_pure_pursuit_steers_towards_aim_point() {
+        // Center line runs up the right side of the square (x = 50, heading +y)
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let library = PresetLibrary::builtin();
+        let mut controller = PurePursuitController::from_preset(
+            &track,
+            library.get("square_pure_pursuit").expect("pure pursuit preset"),
+        )
+        .expect("pure pursuit controller");
+        assert_eq!((controller.lookahead(), controller.target_speed()), (5.0, 8.0));
+        let on_line = PointMassState {
+            x: 50.0,
+            y: -20.0,
+            vx: 8.0,
+            vy: 0.0,
+            yaw: FRAC_PI_2,
+        };
+        let (ax, yaw_rate) = controller.step(0.0, &on_line);
+        assert!(ax.abs() < 1e-9);
+        assert!(yaw_rate.abs() < 1e-9);
+
+        // Left of the line: turn right (clockwise); right of the line: turn left
+        let left = PointMassState { x: 48.0, ..on_line.clone() };
+        let right = PointMassState { x: 52.0, ..on_line };
+        assert!(controller.step(0.0, &left).1 < 0.0);
+        assert!(controller.step(0.0, &right).1 > 0.0);
+
+        let err = PurePursuitController::from_preset(
+            &track,
+            library.get("square_stanley").expect("stanley preset"),
+        )
+        .expect_err("wrong controller kind");
+        assert!(err.to_string().contains("not pure_pursuit"));
+    }
+
+    #[test]
+    fn test_pure_pursuit_feedforward_reduces_corner_cutting() {
+        // Largest lateral error and final telemetry of a run
+        let run = |track: &SharedTrack, feedforward: bool| {
+            let center_line = ArcLengthTable::from_track(track.as_ref());
+            let (x, y, yaw) = track.get_start_position();
+            let controller =
+                PurePursuitController::new(track.as_ref(), 8.0, 12.0).with_feedforward(feedforward);
+            let mut simulation = MultiVehicleSimulation::new(Arc::clone(track)).with_threads(1);
+            let model = PointMass::with_initial_state(x, y, 12.0, yaw);
+            simulation.add_controlled_vehicle(model, controller);
+            let result = simulation.run(0.01, 30.0).expect("run");
+            let max_error = result.runs[0]
+                .states()
+                .filter_map(|state| center_line.position(state.x, state.y))
+                .fold(0.0_f64, |max, position| max.max(position.lateral_error.abs()));
+            let telemetry = result.controller_telemetry.last().expect("final tick")[0];
+            (max_error, telemetry.expect("pure pursuit telemetry"))
+        };
+
+        // The corners of the square are where pure pursuit turns in early
+        let square: SharedTrack = Arc::new(SquareTrack::new(100.0, 10.0, 25));
+        let (plain, _) = run(&square, false);
+        let (with_feedforward, _) = run(&square, true);
+        assert!(with_feedforward < 0.9 * plain, "{with_feedforward} vs {plain}");
+
+        // Counterclockwise circle: the feedforward carries the turn
+        let circle: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 200));
+        let (error, telemetry) = run(&circle, false);
+        assert!(error < 0.1, "{error}");
+        assert_eq!(telemetry.feedforward_yaw_rate, 0.0);
+        assert!((telemetry.feedback_yaw_rate - 12.0 / 50.0).abs() < 0.01);
+        let (error, telemetry) = run(&circle, true);
+        assert!(error < 0.1, "{error}");
+        assert!((telemetry.feedforward_yaw_rate - 12.0 / 50.0).abs() < 0.01);
+        // What is left comes from the segment headings of the polygonal center line
+        assert!(telemetry.feedback_yaw_rate.abs() < 0.05, "{telemetry:?}");
+    }
+}
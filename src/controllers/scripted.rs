@@ -0,0 +1,299 @@
+use super::base_controller::Controller;
+use crate::error::{Error, Result};
+use crate::io::compression;
+use std::path::Path;
+
+/// How control inputs are evaluated between schedule samples
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(rename_all = "snake_case"))]
+pub enum ScheduleInterpolation {
+    /// Hold the most recent sample until the next one (zero-order hold)
+    #[default]
+    Hold,
+    /// Linearly interpolate between neighbouring samples
+    Linear,
+}
+
+/// Control input sample at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlSample {
+    /// Time in seconds at which the sample becomes active
+    pub time: f64,
+    /// Body frame x-axis acceleration in m/s²
+    pub ax: f64,
+    /// Yaw rate in radians/s
+    pub yaw_rate: f64,
+}
+
+/// Time-stamped sequence of control inputs
+///
+/// Before the first sample the controls are zero; after the last sample the last
+/// sample is held.
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(try_from = "ControlScheduleData"))]
+pub struct ControlSchedule {
+    samples: Vec<ControlSample>,
+    interpolation: ScheduleInterpolation,
+}
+
+/// Serialized form of `ControlSchedule`, checked by `ControlSchedule::new` on load
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct ControlScheduleData {
+    samples: Vec<ControlSample>,
+    #[serde(default)]
+    interpolation: ScheduleInterpolation,
+}
+
+#[cfg(feature = "serde")]
+impl TryFrom<ControlScheduleData> for ControlSchedule {
+    type Error = Error;
+
+    fn try_from(data: ControlScheduleData) -> Result<Self> {
+        Ok(Self::new(data.samples)?.with_interpolation(data.interpolation))
+    }
+}
+
+impl ControlSchedule {
+    /// Create a schedule from samples sorted by time
+    ///
+    /// # Returns
+    /// The schedule, or `Error::InvalidConfig` naming the first invalid or unsorted sample
+    pub fn new(samples: Vec<ControlSample>) -> Result<Self> {
+        for (index, sample) in samples.iter().enumerate() {
+            if !(sample.time.is_finite() && sample.ax.is_finite() && sample.yaw_rate.is_finite()) {
+                let message = format!("control sample {} contains non-finite values", index);
+                return Err(Error::invalid_config(message));
+            }
+            if index > 0 && sample.time < samples[index - 1].time {
+                return Err(Error::invalid_config(format!(
+                    "control sample {} at t={} is earlier than the previous sample",
+                    index, sample.time
+                )));
+            }
+        }
+        Ok(Self {
+            samples,
+            interpolation: ScheduleInterpolation::default(),
+        })
+    }
+
+    /// Load a schedule from a CSV file with `time,ax,yaw_rate` columns
+    ///
+    /// A header row and lines starting with `#` are skipped. Times must be non-decreasing.
+    ///
+    /// # Returns
+    /// The schedule, `Error::Io` if the file cannot be read, or `Error::InvalidConfig`
+    /// mentioning the file if it is malformed
+    pub fn from_csv<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let contents = compression::read_to_string(path)?;
+        Self::parse_csv(&contents).map_err(|err| err.context(path.display()))
+    }
+
+    /// Parse a schedule from CSV text with `time,ax,yaw_rate` columns
+    pub fn parse_csv(contents: &str) -> Result<Self> {
+        let mut samples = Vec::new();
+        for (line_index, line) in contents.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            if samples.is_empty() && fields.first().is_some_and(|f| f.parse::<f64>().is_err()) {
+                // Header row
+                continue;
+            }
+            if fields.len() != 3 {
+                return Err(Error::invalid_config(format!(
+                    "line {}: expected 3 columns (time,ax,yaw_rate), found {}",
+                    line_index + 1,
+                    fields.len()
+                )));
+            }
+
+            let parse = |value: &str, name: &str| {
+                value.parse::<f64>().map_err(|_| {
+                    let message =
+                        format!("line {}: invalid {} value '{}'", line_index + 1, name, value);
+                    Error::invalid_config(message)
+                })
+            };
+            samples.push(ControlSample {
+                time: parse(fields[0], "time")?,
+                ax: parse(fields[1], "ax")?,
+                yaw_rate: parse(fields[2], "yaw_rate")?,
+            });
+        }
+
+        if samples.is_empty() {
+            return Err(Error::invalid_config("control schedule contains no samples"));
+        }
+        Self::new(samples)
+    }
+
+    /// Set how controls are evaluated between samples
+    pub fn with_interpolation(mut self, interpolation: ScheduleInterpolation) -> Self {
+        self.interpolation = interpolation;
+        self
+    }
+
+    /// Schedule samples sorted by time
+    pub fn samples(&self) -> &[ControlSample] {
+        &self.samples
+    }
+
+    /// Evaluate the scheduled controls at the given time
+    ///
+    /// # Returns
+    /// Tuple of (ax, yaw_rate)
+    pub fn controls_at(&self, time: f64) -> (f64, f64) {
+        let next = self.samples.partition_point(|sample| sample.time <= time);
+        if next == 0 {
+            return (0.0, 0.0);
+        }
+        let prev = &self.samples[next - 1];
+        match (self.interpolation, self.samples.get(next)) {
+            (ScheduleInterpolation::Linear, Some(next)) if next.time > prev.time => {
+                let alpha = (time - prev.time) / (next.time - prev.time);
+                (
+                    prev.ax + (next.ax - prev.ax) * alpha,
+                    prev.yaw_rate + (next.yaw_rate - prev.yaw_rate) * alpha,
+                )
+            }
+            _ => (prev.ax, prev.yaw_rate),
+        }
+    }
+}
+
+/// Controller replaying a recorded or hand-authored control schedule
+///
+/// The output depends only on time, so it can drive open-loop runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct ScriptedController {
+    schedule: ControlSchedule,
+}
+
+impl ScriptedController {
+    /// Create a controller replaying the given schedule
+    pub fn new(schedule: ControlSchedule) -> Self {
+        Self { schedule }
+    }
+
+    /// Get the replayed schedule
+    pub fn schedule(&self) -> &ControlSchedule {
+        &self.schedule
+    }
+}
+
+impl<S> Controller<S> for ScriptedController {
+    fn step(&mut self, time: f64, _state: &S) -> (f64, f64) {
+        self.schedule.controls_at(time)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ControlSample, ControlSchedule, ScheduleInterpolation, ScriptedController};
+    use crate::controllers::base_controller::Controller;
+
+    const CSV: &str = "# maneuver\ntime,ax,yaw_rate\n0.0,2.0,0.0\n1.0,0.0,0.5\n2.0,-1.0,0.0\n";
+
+    #[test]
+    fn test_parse_csv_skips_header_and_comments() {
+        let schedule = ControlSchedule::parse_csv(CSV).expect("parse schedule");
+        assert_eq!(schedule.samples().len(), 3);
+        assert_eq!(
+            schedule.samples()[1],
+            ControlSample {
+                time: 1.0,
+                ax: 0.0,
+                yaw_rate: 0.5
+            }
+        );
+    }
+
+    #[test]
+    fn test_parse_csv_rejects_bad_rows() {
+        let err = ControlSchedule::parse_csv("0.0,1.0\n").expect_err("expected column error");
+        assert!(err.to_string().contains("line 1"));
+
+        let err = ControlSchedule::parse_csv("0.0,1.0,0.0\n1.0,abc,0.0\n")
+            .expect_err("expected value error");
+        assert!(err.to_string().contains("line 2: invalid ax"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+
+        let err = ControlSchedule::parse_csv("1.0,1.0,0.0\n0.5,1.0,0.0\n")
+            .expect_err("expected ordering error");
+        assert!(err.to_string().contains("earlier"));
+    }
+
+    #[test]
+    fn test_controls_at_hold() {
+        let schedule = ControlSchedule::parse_csv(CSV).expect("parse schedule");
+        assert_eq!(schedule.controls_at(-0.5), (0.0, 0.0));
+        assert_eq!(schedule.controls_at(0.0), (2.0, 0.0));
+        assert_eq!(schedule.controls_at(0.5), (2.0, 0.0));
+        assert_eq!(schedule.controls_at(1.5), (0.0, 0.5));
+        assert_eq!(schedule.controls_at(10.0), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_controls_at_linear() {
+        let schedule = ControlSchedule::parse_csv(CSV)
+            .expect("parse schedule")
+            .with_interpolation(ScheduleInterpolation::Linear);
+        let (ax, yaw_rate) = schedule.controls_at(0.5);
+        assert!((ax - 1.0).abs() < 1e-12);
+        assert!((yaw_rate - 0.25).abs() < 1e-12);
+        assert_eq!(schedule.controls_at(10.0), (-1.0, 0.0));
+    }
+
+    #[test]
+    fn test_from_csv_file() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("schedule.csv");
+        std::fs::write(&path, CSV).expect("write schedule");
+
+        let mut controller =
+            ScriptedController::new(ControlSchedule::from_csv(&path).expect("load schedule"));
+        assert_eq!(controller.step(1.2, &()), (0.0, 0.5));
+    }
+
+    #[test]
+    fn test_boxed_controller() {
+        let schedule = ControlSchedule::parse_csv(CSV).expect("parse schedule");
+        let mut controller: Box<dyn Controller<()>> = Box::new(ScriptedController::new(schedule));
+        assert_eq!(controller.step(0.5, &()), (2.0, 0.0));
+        controller.reset();
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_deserialize_checks_samples() {
+        let schedule: ControlSchedule = serde_json::from_str(
+            r#"{"samples": [{"time": 0.0, "ax": 1.0, "yaw_rate": 0.0},
+                            {"time": 1.0, "ax": 0.0, "yaw_rate": 0.2}],
+                "interpolation": "linear"}"#,
+        )
+        .expect("sorted schedule");
+        let expected = ControlSchedule::new(vec![
+            ControlSample { time: 0.0, ax: 1.0, yaw_rate: 0.0 },
+            ControlSample { time: 1.0, ax: 0.0, yaw_rate: 0.2 },
+        ])
+        .expect("schedule")
+        .with_interpolation(ScheduleInterpolation::Linear);
+        assert_eq!(schedule, expected);
+
+        let err = serde_json::from_str::<ControlSchedule>(
+            r#"{"samples": [{"time": 1.0, "ax": 1.0, "yaw_rate": 0.0},
+                            {"time": 0.5, "ax": 0.0, "yaw_rate": 0.2}]}"#,
+        )
+        .expect_err("unsorted schedule");
+        assert!(err.to_string().contains("earlier than the previous sample"), "{err}");
+    }
+}
@@ -0,0 +1,328 @@
+//! Stanley path tracking controller.
+//!
+//! Steers towards the track center line from the heading error and the lateral error,
+//! `delta = heading_error - atan(gain * lateral_error / (softening + v))`, and holds a
+//! target speed with a proportional speed loop. The point mass is controlled by yaw
+//! rate, so the steering angle is turned into one with the kinematic bicycle relation
+//! `yaw_rate = v * tan(delta) / wheelbase`.
+//!
+//! With feedforward enabled, the yaw rate the path demands, `v * kappa(s)` from the
+//! center line curvature, is added to the feedback. The feedback then only corrects
+//! errors, so the vehicle holds the center line of a constant radius turn instead of
+//! settling where the errors balance the turn.
+
+use super::base_controller::{Controller, ControllerTelemetry};
+use super::presets::{ControllerKind, ControllerPreset};
+use crate::error::{Error, Result};
+use crate::math::angle::Angle;
+use crate::models::point_mass::PointMassState;
+use crate::tracks::arc_length::{ArcLengthTable, TrackPosition};
+use crate::tracks::base_track::Track;
+
+/// Cross-track error gain used when a preset does not set `gain`
+pub const DEFAULT_GAIN: f64 = 1.0;
+/// Speed in m/s added to the vehicle speed in the cross-track term, used when a preset
+/// does not set `softening`
+pub const DEFAULT_SOFTENING: f64 = 1.0;
+/// Target speed in m/s used when a preset does not set `target_speed`
+pub const DEFAULT_TARGET_SPEED: f64 = 10.0;
+/// Wheelbase in meters of the kinematic bicycle turning steering into yaw rate
+pub const DEFAULT_WHEELBASE: f64 = 2.7;
+
+/// Largest steering angle in radians
+pub(super) const MAX_STEER: f64 = 0.6;
+/// Gain in 1/s of the proportional speed loop
+const SPEED_GAIN: f64 = 1.0;
+/// Largest acceleration or deceleration command in m/s²
+const MAX_ACCELERATION: f64 = 4.0;
+
+/// Stanley controller following the center line of a track at a constant speed
+#[derive(Debug, Clone, PartialEq)]
+pub struct StanleyController {
+    center_line: ArcLengthTable,
+    gain: f64,
+    softening: f64,
+    target_speed: f64,
+    wheelbase: f64,
+    feedforward: bool,
+    friction_limit: Option<f64>, // Combined acceleration in m/s² the model can transmit
+    telemetry: Option<ControllerTelemetry>,
+}
+
+impl StanleyController {
+    /// Create a controller following the center line of `track`
+    ///
+    /// # Arguments
+    /// * `track` - Track whose center line is followed
+    /// * `gain` - Cross-track error gain
+    /// * `softening` - Speed in m/s added to the vehicle speed in the cross-track term
+    /// * `target_speed` - Speed in m/s held by the speed loop
+    pub fn new(track: &dyn Track, gain: f64, softening: f64, target_speed: f64) -> Self {
+        Self {
+            center_line: ArcLengthTable::from_track(track),
+            gain,
+            softening,
+            target_speed,
+            wheelbase: DEFAULT_WHEELBASE,
+            feedforward: false,
+            friction_limit: None,
+            telemetry: None,
+        }
+    }
+
+    /// Create a controller from a `stanley` preset
+    ///
+    /// Reads the `gain`, `softening`, `target_speed`, and `wheelbase` parameters and
+    /// falls back to the defaults of this module for missing ones. A nonzero
+    /// `feedforward` parameter enables the curvature feedforward.
+    ///
+    /// # Returns
+    /// The controller, or `Error::InvalidConfig` if the preset is for another controller
+    pub fn from_preset(track: &dyn Track, preset: &ControllerPreset) -> Result<Self> {
+        if preset.controller != ControllerKind::Stanley {
+            return Err(Error::InvalidConfig(format!(
+                "preset '{}' is for the {} controller, not stanley",
+                preset.name, preset.controller
+            )));
+        }
+        Ok(Self::new(
+            track,
+            preset.get_or("gain", DEFAULT_GAIN),
+            preset.get_or("softening", DEFAULT_SOFTENING),
+            preset.get_or("target_speed", DEFAULT_TARGET_SPEED),
+        )
+        .with_wheelbase(preset.get_or("wheelbase", DEFAULT_WHEELBASE))
+        .with_feedforward(preset.get_or("feedforward", 0.0) != 0.0))
+    }
+
+    /// Use another wheelbase in meters to turn steering angles into yaw rates
+    pub fn with_wheelbase(mut self, wheelbase: f64) -> Self {
+        self.wheelbase = wheelbase;
+        self
+    }
+
+    /// Add the curvature feedforward `v * kappa(s)` to the yaw rate
+    pub fn with_feedforward(mut self, feedforward: bool) -> Self {
+        self.feedforward = feedforward;
+        self
+    }
+
+    pub fn feedforward(&self) -> bool {
+        self.feedforward
+    }
+
+    pub fn target_speed(&self) -> f64 {
+        self.target_speed
+    }
+
+    /// Change the speed in m/s held by the speed loop
+    pub fn set_target_speed(&mut self, target_speed: f64) {
+        self.target_speed = target_speed;
+    }
+
+    /// Steering angle in radians of the Stanley law, before saturation
+    fn steering_angle(&self, state: &PointMassState, position: &TrackPosition) -> f64 {
+        let Some((_, _, path_yaw)) = self.center_line.point_at(position.s) else {
+            return 0.0;
+        };
+        let heading_error = Angle::wrap(path_yaw - state.yaw);
+        // The lateral error is positive to the left of the center line, so steer right
+        let cross_track = (self.gain * position.lateral_error)
+            .atan2(self.softening + state.vx.abs());
+        heading_error - cross_track
+    }
+
+    fn controls(
+        &mut self,
+        state: &PointMassState,
+        position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        let ax = speed_command(self.target_speed, state.vx);
+        let Some(position) = position else {
+            self.telemetry = Some(ControllerTelemetry::default());
+            return (ax, 0.0);
+        };
+        let steer = self
+            .steering_angle(state, position)
+            .clamp(-MAX_STEER, MAX_STEER);
+        let telemetry = ControllerTelemetry {
+            feedback_yaw_rate: state.vx * steer.tan() / self.wheelbase,
+            feedforward_yaw_rate: if self.feedforward {
+                state.vx * position.curvature
+            } else {
+                0.0
+            },
+        };
+        self.telemetry = Some(telemetry);
+        friction_limited(state.vx, ax, telemetry.yaw_rate(), self.friction_limit)
+    }
+}
+
+/// Acceleration in m/s² of the proportional speed loop holding `target_speed`
+pub(super) fn speed_command(target_speed: f64, speed: f64) -> f64 {
+    (SPEED_GAIN * (target_speed - speed)).clamp(-MAX_ACCELERATION, MAX_ACCELERATION)
+}
+
+/// Scale (ax, yaw_rate) at `speed` into the friction circle of radius `limit`
+///
+/// Steering has priority: the lateral acceleration is kept within the circle and the
+/// vehicle accelerates or brakes with the grip left over.
+pub(super) fn friction_limited(
+    speed: f64,
+    ax: f64,
+    yaw_rate: f64,
+    limit: Option<f64>,
+) -> (f64, f64) {
+    let Some(limit) = limit else {
+        return (ax, yaw_rate);
+    };
+    let lateral = (speed * yaw_rate).clamp(-limit, limit);
+    let yaw_rate = if speed != 0.0 { lateral / speed } else { yaw_rate };
+    let max_ax = (limit * limit - lateral * lateral).max(0.0).sqrt();
+    (ax.clamp(-max_ax, max_ax), yaw_rate)
+}
+
+impl Controller<PointMassState> for StanleyController {
+    fn step(&mut self, _time: f64, state: &PointMassState) -> (f64, f64) {
+        let position = self.center_line.position(state.x, state.y);
+        self.controls(state, position.as_ref())
+    }
+
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &PointMassState,
+        position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        match position {
+            Some(position) => self.controls(state, Some(position)),
+            None => self.step(time, state),
+        }
+    }
+
+    fn set_friction_limit(&mut self, limit: Option<f64>) {
+        self.friction_limit = limit;
+    }
+
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        self.telemetry
+    }
+
+    fn reset(&mut self) {
+        self.telemetry = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::StanleyController;
+    use crate::controllers::base_controller::Controller;
+    use crate::controllers::presets::PresetLibrary;
+    use crate::models::point_mass::{PointMass, PointMassState};
+    use crate::simulation::multi_vehicle::MultiVehicleSimulation;
+    use crate::tracks::arc_length::ArcLengthTable;
+    use crate::tracks::base_track::SharedTrack;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+    use std::f64::consts::FRAC_PI_2;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_stanley_steers_back_to_center_line() {
+        // Center line runs up the right side of the square (x = 50, heading +y)
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let mut controller = StanleyController::new(&track, 2.0, 1.0, 8.0);
+        let on_line = PointMassState {
+            x: 50.0,
+            y: -20.0,
+            vx: 8.0,
+            vy: 0.0,
+            yaw: FRAC_PI_2,
+        };
+        let (ax, yaw_rate) = controller.step(0.0, &on_line);
+        assert!(ax.abs() < 1e-9);
+        assert!(yaw_rate.abs() < 1e-9);
+
+        // Left of the line: turn right (clockwise); right of the line: turn left
+        let left = PointMassState { x: 48.0, ..on_line.clone() };
+        let right = PointMassState { x: 52.0, ..on_line.clone() };
+        assert!(controller.step(0.0, &left).1 < 0.0);
+        assert!(controller.step(0.0, &right).1 > 0.0);
+
+        // Slower than the target speed: accelerate
+        let slow = PointMassState { vx: 2.0, ..on_line };
+        assert!(controller.step(0.0, &slow).0 > 0.0);
+    }
+
+    #[test]
+    fn test_stanley_from_preset() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let library = PresetLibrary::builtin();
+        let controller = StanleyController::from_preset(
+            &track,
+            library.get("square_stanley").expect("stanley preset"),
+        )
+        .expect("stanley controller");
+        assert_eq!(controller.target_speed(), 8.0);
+
+        let err = StanleyController::from_preset(
+            &track,
+            library.get("circle_pure_pursuit").expect("pure pursuit preset"),
+        )
+        .expect_err("wrong controller kind");
+        assert!(err.to_string().contains("not stanley"));
+    }
+
+    #[test]
+    fn test_stanley_respects_friction_limit() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let mut controller = StanleyController::new(&track, 2.0, 1.0, 8.0);
+        // Far left of the line and too fast: steer hard right and brake
+        let state = PointMassState {
+            x: 45.0,
+            y: -20.0,
+            vx: 15.0,
+            vy: 0.0,
+            yaw: FRAC_PI_2,
+        };
+        let (ax, yaw_rate) = controller.step(0.0, &state);
+        assert!((ax * ax + (state.vx * yaw_rate).powi(2)).sqrt() > 5.0);
+
+        controller.set_friction_limit(Some(5.0));
+        let (limited_ax, limited_yaw_rate) = controller.step(0.0, &state);
+        let combined = (limited_ax * limited_ax + (state.vx * limited_yaw_rate).powi(2)).sqrt();
+        assert!(combined <= 5.0 + 1e-9, "{combined}");
+        assert!(limited_yaw_rate < 0.0 && limited_ax <= 0.0);
+    }
+
+    #[test]
+    fn test_stanley_feedforward_removes_steady_state_error() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 200));
+        let center_line = ArcLengthTable::from_track(track.as_ref());
+        let (x, y, yaw) = track.get_start_position();
+        // Final lateral error and telemetry after settling on the circle
+        let settle = |feedforward: bool| {
+            let controller = StanleyController::new(track.as_ref(), 1.0, 1.0, 12.0)
+                .with_feedforward(feedforward);
+            let mut simulation = MultiVehicleSimulation::new(Arc::clone(&track)).with_threads(1);
+            let model = PointMass::with_initial_state(x, y, 12.0, yaw);
+            simulation.add_controlled_vehicle(model, controller);
+            let result = simulation.run(0.01, 20.0).expect("run");
+            let state = &result.runs[0].last().expect("final snapshot").state;
+            let position = center_line.position(state.x, state.y).expect("on the center line");
+            let telemetry = result.controller_telemetry.last().expect("final tick")[0];
+            (position.lateral_error, telemetry.expect("stanley telemetry"))
+        };
+
+        let (error, telemetry) = settle(false);
+        assert!(error.abs() > 0.5, "{error}");
+        assert_eq!(telemetry.feedforward_yaw_rate, 0.0);
+
+        let (error, telemetry) = settle(true);
+        // What is left comes from the polygonal center line
+        assert!(error.abs() < 0.05, "{error}");
+        // Counterclockwise circle: the feedforward carries the turn
+        assert!((telemetry.feedforward_yaw_rate - 12.0 / 50.0).abs() < 0.01);
+        assert!(telemetry.feedback_yaw_rate.abs() < 0.1);
+    }
+}
@@ -0,0 +1,101 @@
+//! Status and warning messages of the library.
+//!
+//! The library does not print anything by itself: plotting, video encoding, servers,
+//! and telemetry report through the process-wide logger installed with `set_logger`,
+//! and messages are dropped while none is installed. Applications that want the
+//! messages on the console install `print_logger`, as the `lap_simulation` binary does.
+
+use std::fmt;
+use std::sync::{Arc, RwLock};
+
+/// Severity of a message
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
+pub enum Level {
+    /// Progress and status, e.g. the path of a written file
+    Info,
+    /// Recoverable failures, e.g. a telemetry sink that stopped accepting snapshots
+    Warn,
+}
+
+impl fmt::Display for Level {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Level::Info => write!(f, "info"),
+            Level::Warn => write!(f, "warn"),
+        }
+    }
+}
+
+/// Callback receiving the library's messages
+pub type Logger = Arc<dyn Fn(Level, &str) + Send + Sync>;
+
+static LOGGER: RwLock<Option<Logger>> = RwLock::new(None);
+
+/// Install `logger` for the whole process, replacing the previous one
+pub fn set_logger<F: Fn(Level, &str) + Send + Sync + 'static>(logger: F) {
+    *LOGGER.write().unwrap_or_else(|err| err.into_inner()) = Some(Arc::new(logger));
+}
+
+/// Remove the installed logger; messages are dropped afterwards
+pub fn clear_logger() {
+    *LOGGER.write().unwrap_or_else(|err| err.into_inner()) = None;
+}
+
+/// Logger printing info messages to stdout and warnings to stderr
+pub fn print_logger(level: Level, message: &str) {
+    match level {
+        Level::Info => println!("{}", message),
+        Level::Warn => eprintln!("{}", message),
+    }
+}
+
+/// Report a status message
+#[cfg_attr(not(feature = "plotting"), allow(dead_code))]
+pub(crate) fn info(message: fmt::Arguments<'_>) {
+    log(Level::Info, message);
+}
+
+/// Report a recoverable failure
+pub(crate) fn warn(message: fmt::Arguments<'_>) {
+    log(Level::Warn, message);
+}
+
+fn log(level: Level, message: fmt::Arguments<'_>) {
+    // Release the lock before calling, so the logger may itself install another one
+    let logger = LOGGER.read().unwrap_or_else(|err| err.into_inner()).clone();
+    if let Some(logger) = logger {
+        logger(level, &message.to_string());
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{clear_logger, info, set_logger, warn, Level};
+    use std::sync::{Arc, Mutex};
+
+    #[test]
+    fn test_messages_reach_installed_logger() {
+        // Other tests may log concurrently, so only look at this test's messages
+        let received: Arc<Mutex<Vec<(Level, String)>>> = Arc::default();
+        let sink = Arc::clone(&received);
+        set_logger(move |level, message| {
+            if message.starts_with("diagnostics test") {
+                sink.lock().expect("lock messages").push((level, message.to_string()));
+            }
+        });
+
+        info(format_args!("diagnostics test {}", 1));
+        warn(format_args!("diagnostics test {}", 2));
+        clear_logger();
+        info(format_args!("diagnostics test {}", 3));
+
+        let received = received.lock().expect("lock messages");
+        assert_eq!(
+            *received,
+            [
+                (Level::Info, "diagnostics test 1".to_string()),
+                (Level::Warn, "diagnostics test 2".to_string()),
+            ]
+        );
+    }
+}
@@ -0,0 +1,133 @@
+//! Crate-level error type.
+//!
+//! Every fallible function of the crate returns `Error`, so callers can match on the
+//! failure mode (e.g., retry without video when ffmpeg is missing, or report a bad
+//! scenario file) instead of inspecting error strings. Files that cannot be read or
+//! written are `Error::Io`, malformed data files (leaderboards, ghosts, logs) and
+//! rejected arguments are `Error::InvalidInput`, and malformed track, scenario, and
+//! configuration files are `Error::InvalidConfig`. It converts into
+//! `Box<dyn std::error::Error>` for applications that mix it with other errors.
+
+#[cfg(feature = "plotting")]
+use plotters::drawing::DrawingAreaErrorKind;
+use std::fmt;
+use std::io;
+
+/// Failure modes of file I/O, parsing, plotting, video encoding, rendering, and simulation runs
+#[derive(Debug, thiserror::Error)]
+pub enum Error {
+    /// Reading or writing a file failed
+    #[error("I/O error: {0}")]
+    Io(#[from] io::Error),
+    /// ffmpeg could not be started or exited with an error
+    #[error("ffmpeg error: {0}")]
+    Ffmpeg(String),
+    /// An argument was rejected before any work was done
+    #[error("invalid input: {0}")]
+    InvalidInput(String),
+    /// A simulation or scenario setting (time step, track, model, ...) is invalid
+    #[error("invalid configuration: {0}")]
+    InvalidConfig(String),
+    /// A component (e.g., a track whose `init` was never called) has no data yet
+    #[error("{0} is not initialized")]
+    NotInitialized(String),
+    /// No item (e.g., a scenario of the server registry) has the requested id
+    #[error("unknown {0}")]
+    NotFound(String),
+    /// The drawing backend failed
+    #[error("rendering failed: {0}")]
+    Render(String),
+    /// The model state became NaN or infinite during a run
+    #[error("model state became non-finite at t = {time} s")]
+    Diverged { time: f64 },
+    /// The run was stopped through its `CancelToken`
+    #[error("simulation cancelled")]
+    Cancelled,
+}
+
+/// Result of the fallible functions of the crate
+///
+/// The error type defaults to `Error`, so `Result<T, Box<dyn std::error::Error>>`
+/// can still be spelled out where needed.
+pub type Result<T, E = Error> = std::result::Result<T, E>;
+
+impl Error {
+    pub(crate) fn invalid_input<M: Into<String>>(message: M) -> Self {
+        Error::InvalidInput(message.into())
+    }
+
+    pub(crate) fn invalid_config<M: Into<String>>(message: M) -> Self {
+        Error::InvalidConfig(message.into())
+    }
+
+    /// Prefix the message of an invalid input or configuration with where it was found
+    /// (a file, a line); I/O errors already name their file
+    pub(crate) fn context<C: fmt::Display>(self, context: C) -> Self {
+        match self {
+            Error::InvalidInput(message) => {
+                Error::InvalidInput(format!("{}: {}", context, message))
+            }
+            Error::InvalidConfig(message) => {
+                Error::InvalidConfig(format!("{}: {}", context, message))
+            }
+            err => err,
+        }
+    }
+}
+
+#[cfg(feature = "plotting")]
+impl<E: std::error::Error + Send + Sync> From<DrawingAreaErrorKind<E>> for Error {
+    fn from(err: DrawingAreaErrorKind<E>) -> Self {
+        Error::Render(err.to_string())
+    }
+}
+
+#[cfg(feature = "ros2")]
+impl From<rusqlite::Error> for Error {
+    fn from(err: rusqlite::Error) -> Self {
+        Error::Io(io::Error::other(err))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Error, Result};
+
+    #[test]
+    fn test_error_messages() {
+        let err = Error::invalid_input("fps must be greater than zero");
+        assert_eq!(err.to_string(), "invalid input: fps must be greater than zero");
+        assert!(matches!(err, Error::InvalidInput(_)));
+
+        let err: Error = std::io::Error::new(std::io::ErrorKind::NotFound, "gone").into();
+        assert!(matches!(err, Error::Io(_)));
+
+        let err = Error::invalid_config("dt must be a positive number, got 0");
+        assert_eq!(err.to_string(), "invalid configuration: dt must be a positive number, got 0");
+
+        let err = Error::invalid_config("line 3: expected 2 columns").context("track.csv");
+        assert_eq!(err.to_string(), "invalid configuration: track.csv: line 3: expected 2 columns");
+
+        assert_eq!(Error::NotFound("scenario 3".into()).to_string(), "unknown scenario 3");
+        let err = Error::Diverged { time: 1.5 };
+        assert_eq!(err.to_string(), "model state became non-finite at t = 1.5 s");
+        assert_eq!(Error::Cancelled.to_string(), "simulation cancelled");
+
+        let boxed: Box<dyn std::error::Error> = Error::NotInitialized("track".into()).into();
+        assert_eq!(boxed.to_string(), "track is not initialized");
+    }
+
+    #[test]
+    fn test_result_alias_converts_errors() {
+        fn read(path: &str) -> Result<String> {
+            Ok(std::fs::read_to_string(path)?)
+        }
+        fn boxed(path: &str) -> Result<String, Box<dyn std::error::Error>> {
+            Ok(read(path)?)
+        }
+
+        let err = read("/nonexistent/lap_simulation").expect_err("missing file");
+        assert!(matches!(err, Error::Io(_)));
+        assert!(boxed("/nonexistent/lap_simulation").is_err());
+    }
+}
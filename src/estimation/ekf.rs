@@ -0,0 +1,271 @@
+//! Extended Kalman filter estimating the kinematic vehicle state from sensors.
+//!
+//! The filter state is `[x, y, yaw, vx]`, the part of the state shared by the point
+//! mass and the dynamic bicycle. It is predicted with the unicycle kinematics driven by
+//! a forward acceleration and a yaw rate (from the IMU, or the commanded controls
+//! without one), and corrected with GPS fixes and speed measurements.
+
+use crate::math::angle::Angle;
+use crate::models::point_mass::PointMassState;
+use crate::sensors::{Measurements, SensorSuite};
+
+/// Index of each quantity in the filter state
+const X: usize = 0;
+const Y: usize = 1;
+const YAW: usize = 2;
+const VX: usize = 3;
+
+/// Filter state covariance
+pub type Covariance = [[f64; 4]; 4];
+
+/// Standard deviation of the inputs without an IMU: how far the commanded controls may
+/// be from the actual motion
+const COMMAND_ACCELERATION_STD_DEV: f64 = 0.5;
+const COMMAND_YAW_RATE_STD_DEV: f64 = 0.05;
+
+/// Smallest standard deviation, so that noise-free sensors keep the innovation
+/// covariance invertible
+const MIN_STD_DEV: f64 = 1e-3;
+
+/// Initial yaw standard deviation in radians
+const INITIAL_YAW_STD_DEV: f64 = 0.1;
+
+/// Noise the filter assumes, as standard deviations
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct FilterNoise {
+    /// Forward acceleration input in m/s²
+    pub acceleration: f64,
+    /// Yaw rate input in radians/s
+    pub yaw_rate: f64,
+    /// GPS position on each axis in meters
+    pub position: f64,
+    /// Measured speed in m/s
+    pub speed: f64,
+}
+
+impl FilterNoise {
+    /// Noise of the sensors of `suite`
+    ///
+    /// Without an IMU, the inputs are the commanded controls and get a default noise;
+    /// the noise of absent GPS or speed sensors is unused. Sensor biases are not
+    /// estimated, so they show as a constant estimation error.
+    pub fn for_sensors(suite: &SensorSuite) -> Self {
+        let (acceleration, yaw_rate) = suite.imu.as_ref().map_or(
+            (COMMAND_ACCELERATION_STD_DEV, COMMAND_YAW_RATE_STD_DEV),
+            |imu| (imu.acceleration_noise().std_dev, imu.yaw_rate_noise().std_dev),
+        );
+        Self {
+            acceleration,
+            yaw_rate,
+            position: suite.gps.as_ref().map_or(1.0, |gps| gps.noise().std_dev),
+            speed: suite.speed.as_ref().map_or(1.0, |speed| speed.noise().std_dev),
+        }
+    }
+}
+
+/// Extended Kalman filter over `[x, y, yaw, vx]`
+#[derive(Debug, Clone)]
+pub struct ExtendedKalmanFilter {
+    state: [f64; 4],
+    covariance: Covariance,
+    noise: FilterNoise,
+    initial: ([f64; 4], Covariance),
+}
+
+impl ExtendedKalmanFilter {
+    /// Create a filter starting at `initial`
+    ///
+    /// The initial covariance is the GPS and speed measurement noise, with 0.1 radians
+    /// on the yaw.
+    pub fn new(initial: &PointMassState, noise: FilterNoise) -> Self {
+        let state = [initial.x, initial.y, initial.yaw, initial.vx];
+        let mut covariance = [[0.0; 4]; 4];
+        covariance[X][X] = variance(noise.position);
+        covariance[Y][Y] = variance(noise.position);
+        covariance[YAW][YAW] = INITIAL_YAW_STD_DEV * INITIAL_YAW_STD_DEV;
+        covariance[VX][VX] = variance(noise.speed);
+        Self {
+            state,
+            covariance,
+            noise,
+            initial: (state, covariance),
+        }
+    }
+
+    /// Replace the covariance of the current estimate
+    pub fn set_covariance(&mut self, covariance: Covariance) {
+        self.covariance = covariance;
+    }
+
+    pub fn covariance(&self) -> &Covariance {
+        &self.covariance
+    }
+
+    pub fn noise(&self) -> FilterNoise {
+        self.noise
+    }
+
+    /// Estimated state; the lateral velocity is not estimated and is 0
+    pub fn state(&self) -> PointMassState {
+        PointMassState {
+            x: self.state[X],
+            y: self.state[Y],
+            vx: self.state[VX],
+            vy: 0.0,
+            yaw: self.state[YAW],
+        }
+    }
+
+    /// Standard deviations of (x, y, yaw, vx)
+    pub fn std_devs(&self) -> [f64; 4] {
+        std::array::from_fn(|index| self.covariance[index][index].max(0.0).sqrt())
+    }
+
+    /// Propagate the estimate over `dt` seconds with the given inputs
+    ///
+    /// # Arguments
+    /// * `dt` - Time step in seconds
+    /// * `ax` - Forward acceleration in m/s²
+    /// * `yaw_rate` - Yaw rate in radians/s
+    pub fn predict(&mut self, dt: f64, ax: f64, yaw_rate: f64) {
+        let [x, y, yaw, vx] = self.state;
+        let (sin, cos) = yaw.sin_cos();
+        self.state = [
+            x + vx * cos * dt,
+            y + vx * sin * dt,
+            Angle::wrap(yaw + yaw_rate * dt),
+            vx + ax * dt,
+        ];
+
+        // Jacobian of the motion model at the previous estimate
+        let mut jacobian = identity();
+        jacobian[X][YAW] = -vx * sin * dt;
+        jacobian[X][VX] = cos * dt;
+        jacobian[Y][YAW] = vx * cos * dt;
+        jacobian[Y][VX] = sin * dt;
+
+        let mut covariance =
+            multiply(&multiply(&jacobian, &self.covariance), &transpose(&jacobian));
+        covariance[YAW][YAW] += variance(self.noise.yaw_rate * dt);
+        covariance[VX][VX] += variance(self.noise.acceleration * dt);
+        self.covariance = covariance;
+    }
+
+    /// Correct the estimate with a GPS fix
+    pub fn update_position(&mut self, x: f64, y: f64) {
+        let position_variance = variance(self.noise.position);
+        // Independent axes: two scalar updates equal one joint update
+        self.update(X, x, position_variance);
+        self.update(Y, y, position_variance);
+    }
+
+    /// Correct the estimate with a speed measurement
+    pub fn update_speed(&mut self, speed: f64) {
+        self.update(VX, speed, variance(self.noise.speed));
+    }
+
+    /// Correct the estimate with the GPS fix and speed of `measurements`, if any
+    pub fn fuse(&mut self, measurements: &Measurements) {
+        if let Some(fix) = measurements.gps {
+            self.update_position(fix.x, fix.y);
+        }
+        if let Some(measurement) = measurements.speed {
+            self.update_speed(measurement.speed);
+        }
+    }
+
+    /// Return to the initial estimate and covariance
+    pub fn reset(&mut self) {
+        (self.state, self.covariance) = self.initial;
+    }
+
+    /// Kalman update of state component `index`, measured directly with `variance`
+    fn update(&mut self, index: usize, measured: f64, variance: f64) {
+        let innovation = measured - self.state[index];
+        let innovation_variance = self.covariance[index][index] + variance;
+        let gain: [f64; 4] =
+            std::array::from_fn(|row| self.covariance[row][index] / innovation_variance);
+        for (row, gain) in gain.iter().enumerate() {
+            self.state[row] += gain * innovation;
+        }
+        self.state[YAW] = Angle::wrap(self.state[YAW]);
+        let measured_row = self.covariance[index];
+        for (row, gain) in gain.iter().enumerate() {
+            for (column, value) in measured_row.iter().enumerate() {
+                self.covariance[row][column] -= gain * value;
+            }
+        }
+    }
+}
+
+fn variance(std_dev: f64) -> f64 {
+    let std_dev = std_dev.abs().max(MIN_STD_DEV);
+    std_dev * std_dev
+}
+
+fn identity() -> Covariance {
+    std::array::from_fn(|row| std::array::from_fn(|column| if row == column { 1.0 } else { 0.0 }))
+}
+
+fn transpose(matrix: &Covariance) -> Covariance {
+    std::array::from_fn(|row| std::array::from_fn(|column| matrix[column][row]))
+}
+
+fn multiply(a: &Covariance, b: &Covariance) -> Covariance {
+    std::array::from_fn(|row| {
+        std::array::from_fn(|column| (0..4).map(|k| a[row][k] * b[k][column]).sum())
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ExtendedKalmanFilter, FilterNoise};
+    use crate::models::base_model::Model;
+    use crate::models::point_mass::PointMass;
+    use crate::sensors::gps::GpsSensor;
+    use crate::sensors::imu::ImuSensor;
+    use crate::sensors::speed::SpeedSensor;
+    use crate::sensors::{SensorNoise, SensorSuite};
+
+    #[test]
+    fn test_filter_beats_raw_gps() {
+        let mut model = PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0);
+        let mut suite = SensorSuite::new()
+            .with_gps(GpsSensor::new(10.0, SensorNoise::new(0.0, 1.0), 5))
+            .with_imu(ImuSensor::new(
+                0.0,
+                SensorNoise::new(0.0, 0.05),
+                SensorNoise::new(0.0, 0.01),
+                5,
+            ))
+            .with_speed(SpeedSensor::new(20.0, SensorNoise::new(0.0, 0.2), 5));
+        let noise = FilterNoise::for_sensors(&suite);
+        let mut filter = ExtendedKalmanFilter::new(model.get_state(), noise);
+        suite.measure(0.0, model.get_state());
+
+        let dt = 0.01;
+        let (mut gps_error, mut filter_error, mut samples) = (0.0, 0.0, 0);
+        for step in 1..=2000 {
+            model.set_controls(0.0, 0.2);
+            model.step(dt);
+            let truth = model.get_state();
+            let measurements = suite.measure(step as f64 * dt, truth);
+            let imu = measurements.imu.expect("IMU samples every state");
+            filter.predict(dt, imu.ax, imu.yaw_rate);
+            filter.fuse(&measurements);
+            if let (Some(fix), true) = (measurements.gps, step > 500) {
+                gps_error += (fix.x - truth.x).hypot(fix.y - truth.y);
+                let estimate = filter.state();
+                filter_error += (estimate.x - truth.x).hypot(estimate.y - truth.y);
+                samples += 1;
+            }
+        }
+        let (gps_error, filter_error) = (gps_error / samples as f64, filter_error / samples as f64);
+        assert!(filter_error < 0.5 * gps_error, "{filter_error} vs {gps_error}");
+        let [sigma_x, _, _, sigma_vx] = filter.std_devs();
+        assert!(sigma_x < 1.0 && sigma_vx < 0.2, "{sigma_x} {sigma_vx}");
+
+        filter.reset();
+        assert_eq!(filter.state().x, 0.0);
+    }
+}
@@ -0,0 +1,218 @@
+//! State estimation from simulated sensors.
+//!
+//! `EstimatingController` sits between a `SensorSuite` and a controller in the closed
+//! loop: every step it feeds the true state to the sensors, fuses their measurements
+//! with an `ExtendedKalmanFilter`, and hands the estimate to the controller. The
+//! estimates are logged next to the truth so the error can be plotted afterwards.
+
+pub mod ekf;
+
+use crate::controllers::base_controller::{Controller, ControllerTelemetry};
+use crate::math::angle::Angle;
+use crate::models::point_mass::PointMassState;
+use crate::sensors::{Sensor, SensorSuite};
+use crate::tracks::arc_length::TrackPosition;
+use ekf::ExtendedKalmanFilter;
+use std::sync::{Arc, Mutex, PoisonError};
+
+/// Estimated and true state at one step
+#[derive(Debug, Clone, PartialEq)]
+pub struct EstimationSample {
+    /// Time in seconds
+    pub time: f64,
+    pub truth: PointMassState,
+    pub estimate: PointMassState,
+    /// Standard deviations of the estimated (x, y, yaw, vx)
+    pub std_devs: [f64; 4],
+}
+
+impl EstimationSample {
+    /// Distance between the estimated and the true position in meters
+    pub fn position_error(&self) -> f64 {
+        (self.estimate.x - self.truth.x).hypot(self.estimate.y - self.truth.y)
+    }
+
+    /// Estimated minus true yaw in radians, wrapped to [-π, π)
+    pub fn heading_error(&self) -> f64 {
+        Angle::wrap(self.estimate.yaw - self.truth.yaw)
+    }
+
+    /// Estimated minus true forward speed in m/s
+    pub fn speed_error(&self) -> f64 {
+        self.estimate.vx - self.truth.vx
+    }
+}
+
+/// Shared log of the samples of an `EstimatingController`
+///
+/// Controllers are moved into the simulations; keep a clone of the log to read the
+/// samples after the run.
+#[derive(Debug, Clone, Default)]
+pub struct EstimationLog(Arc<Mutex<Vec<EstimationSample>>>);
+
+impl EstimationLog {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Copy of the samples logged so far
+    pub fn samples(&self) -> Vec<EstimationSample> {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).clone()
+    }
+
+    pub fn clear(&self) {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).clear();
+    }
+
+    fn push(&self, sample: EstimationSample) {
+        self.0.lock().unwrap_or_else(PoisonError::into_inner).push(sample);
+    }
+}
+
+/// Controller driving on the state estimated from sensor measurements
+///
+/// The filter is predicted with the IMU measurements, or with the previous controls
+/// when the suite has no IMU, and corrected with the GPS and speed measurements. As
+/// with `SensedController`, the position on the track known to the simulation is
+/// ignored.
+#[derive(Debug, Clone)]
+pub struct EstimatingController<C> {
+    controller: C,
+    sensors: SensorSuite,
+    filter: ExtendedKalmanFilter,
+    log: EstimationLog,
+    /// Time of the previous step
+    time: Option<f64>,
+    /// Controls returned at the previous step
+    controls: (f64, f64),
+}
+
+impl<C> EstimatingController<C> {
+    pub fn new(controller: C, sensors: SensorSuite, filter: ExtendedKalmanFilter) -> Self {
+        Self {
+            controller,
+            sensors,
+            filter,
+            log: EstimationLog::new(),
+            time: None,
+            controls: (0.0, 0.0),
+        }
+    }
+
+    /// Log the samples into `log` instead of a log of its own
+    pub fn with_log(mut self, log: EstimationLog) -> Self {
+        self.log = log;
+        self
+    }
+
+    /// Handle to the estimation log
+    pub fn log(&self) -> EstimationLog {
+        self.log.clone()
+    }
+
+    pub fn filter(&self) -> &ExtendedKalmanFilter {
+        &self.filter
+    }
+
+    /// Get the wrapped controller
+    pub fn controller(&self) -> &C {
+        &self.controller
+    }
+}
+
+impl<C: Controller<PointMassState>> Controller<PointMassState> for EstimatingController<C> {
+    fn step(&mut self, time: f64, state: &PointMassState) -> (f64, f64) {
+        let measurements = self.sensors.measure(time, state);
+        if let Some(previous) = self.time {
+            let (ax, yaw_rate) = match self.sensors.imu.as_ref() {
+                Some(imu) => imu.last().map_or((0.0, 0.0), |imu| (imu.ax, imu.yaw_rate)),
+                None => self.controls,
+            };
+            self.filter.predict(time - previous, ax, yaw_rate);
+        }
+        self.filter.fuse(&measurements);
+        self.time = Some(time);
+
+        let estimate = self.filter.state();
+        self.log.push(EstimationSample {
+            time,
+            truth: state.clone(),
+            estimate: estimate.clone(),
+            std_devs: self.filter.std_devs(),
+        });
+        self.controls = self.controller.step(time, &estimate);
+        self.controls
+    }
+
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &PointMassState,
+        _position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        self.step(time, state)
+    }
+
+    fn set_friction_limit(&mut self, limit: Option<f64>) {
+        self.controller.set_friction_limit(limit);
+    }
+
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        self.controller.telemetry()
+    }
+
+    fn reset(&mut self) {
+        self.controller.reset();
+        self.sensors.reset();
+        self.filter.reset();
+        self.time = None;
+        self.controls = (0.0, 0.0);
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::EstimatingController;
+    use super::ekf::{ExtendedKalmanFilter, FilterNoise};
+    use crate::controllers::stanley::StanleyController;
+    use crate::models::base_model::Model;
+    use crate::models::point_mass::PointMass;
+    use crate::sensors::gps::GpsSensor;
+    use crate::sensors::speed::SpeedSensor;
+    use crate::sensors::{SensorNoise, SensorSuite};
+    use crate::simulation::multi_vehicle::MultiVehicleSimulation;
+    use crate::tracks::base_track::SharedTrack;
+    use crate::tracks::circle::CircleTrack;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_estimating_controller_logs_estimates() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let (x, y, yaw) = track.get_start_position();
+        let model = PointMass::with_initial_state(x, y, 0.0, yaw);
+        // No IMU: the filter predicts with the commanded controls
+        let sensors = SensorSuite::new()
+            .with_gps(GpsSensor::new(5.0, SensorNoise::new(0.0, 1.0), 11))
+            .with_speed(SpeedSensor::new(20.0, SensorNoise::new(0.0, 0.2), 11));
+        let noise = FilterNoise::for_sensors(&sensors);
+        let filter = ExtendedKalmanFilter::new(model.get_state(), noise);
+        let controller = EstimatingController::new(
+            StanleyController::new(track.as_ref(), 2.0, 1.0, 10.0),
+            sensors,
+            filter,
+        );
+        let log = controller.log();
+        let mut simulation = MultiVehicleSimulation::new(Arc::clone(&track)).with_threads(1);
+        simulation.add_controlled_vehicle(model, controller);
+
+        let result = simulation.run(0.02, 30.0).expect("run");
+        assert!(result.runs[0].snapshots().iter().all(|snapshot| snapshot.in_track));
+        let samples = log.samples();
+        assert_eq!(samples.len(), result.runs[0].len() - 1);
+        let settled = &samples[samples.len() / 2..];
+        let mean_error =
+            settled.iter().map(|sample| sample.position_error()).sum::<f64>() / settled.len() as f64;
+        assert!(mean_error < 0.8, "{mean_error}");
+        assert!(settled.iter().all(|sample| sample.heading_error().abs() < 0.2));
+    }
+}
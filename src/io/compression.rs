@@ -0,0 +1,162 @@
+//! Transparent compression of output files, selected by file extension.
+//!
+//! Paths ending in `.gz` are gzip-compressed and paths ending in `.zst` (or `.zstd`)
+//! are zstd-compressed; everything else is stored as is. Compression needs the
+//! `compression` feature; without it, compressed paths are rejected with an error.
+
+use crate::error::{Error, Result};
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Compression applied to a file
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum Compression {
+    #[default]
+    None,
+    Gzip,
+    Zstd,
+}
+
+impl Compression {
+    /// Compression selected by the extension of `path` (e.g., "run.json.gz")
+    pub fn from_path<P: AsRef<Path>>(path: P) -> Self {
+        match path.as_ref().extension().and_then(|ext| ext.to_str()) {
+            Some("gz") => Compression::Gzip,
+            Some("zst" | "zstd") => Compression::Zstd,
+            _ => Compression::None,
+        }
+    }
+
+    /// Compress `data`
+    pub fn compress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            #[cfg(feature = "compression")]
+            Compression::Gzip => {
+                use std::io::Write;
+                let mut encoder =
+                    flate2::write::GzEncoder::new(Vec::new(), flate2::Compression::default());
+                encoder.write_all(data)?;
+                Ok(encoder.finish()?)
+            }
+            #[cfg(feature = "compression")]
+            Compression::Zstd => Ok(zstd::encode_all(data, 0)?),
+            #[cfg(not(feature = "compression"))]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    /// Decompress `data`
+    pub fn decompress(self, data: &[u8]) -> Result<Vec<u8>> {
+        match self {
+            Compression::None => Ok(data.to_vec()),
+            #[cfg(feature = "compression")]
+            Compression::Gzip => {
+                use std::io::Read;
+                let mut decoded = Vec::new();
+                flate2::read::MultiGzDecoder::new(data).read_to_end(&mut decoded)?;
+                Ok(decoded)
+            }
+            #[cfg(feature = "compression")]
+            Compression::Zstd => Ok(zstd::decode_all(data)?),
+            #[cfg(not(feature = "compression"))]
+            _ => Err(self.unsupported()),
+        }
+    }
+
+    #[cfg(not(feature = "compression"))]
+    fn unsupported(self) -> Error {
+        Error::invalid_input(format!(
+            "{:?} compression requires building with the `compression` feature",
+            self
+        ))
+    }
+}
+
+/// Write `data` to `path`, compressed according to its extension
+///
+/// Parent directories are created if needed.
+pub fn write_file<P: AsRef<Path>>(path: P, data: &[u8]) -> Result<()> {
+    let path = path.as_ref();
+    if let Some(parent) = path.parent()
+        && !parent.as_os_str().is_empty()
+    {
+        fs::create_dir_all(parent).map_err(|err| with_path("create", parent, err))?;
+    }
+    let bytes = Compression::from_path(path).compress(data)?;
+    fs::write(path, bytes).map_err(|err| with_path("write", path, err))?;
+    Ok(())
+}
+
+/// Read a file written by `write_file` (or any file with a matching extension)
+pub fn read_file<P: AsRef<Path>>(path: P) -> Result<Vec<u8>> {
+    let path = path.as_ref();
+    let bytes = fs::read(path).map_err(|err| with_path("read", path, err))?;
+    Compression::from_path(path).decompress(&bytes).map_err(|err| match err {
+        Error::Io(err) => with_path("decompress", path, err).into(),
+        err => err,
+    })
+}
+
+/// Read a UTF-8 text file, decompressing it according to its extension
+pub fn read_to_string<P: AsRef<Path>>(path: P) -> Result<String> {
+    let path = path.as_ref();
+    String::from_utf8(read_file(path)?).map_err(|_| {
+        io::Error::new(
+            io::ErrorKind::InvalidData,
+            format!("{} is not valid UTF-8", path.display()),
+        )
+        .into()
+    })
+}
+
+/// `err` with the failed operation and the file it was on in its message
+fn with_path(operation: &str, path: &Path, err: io::Error) -> io::Error {
+    io::Error::new(err.kind(), format!("failed to {} {}: {}", operation, path.display(), err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_to_string, write_file, Compression};
+
+    #[test]
+    fn test_compression_from_path() {
+        assert_eq!(Compression::from_path("run.json"), Compression::None);
+        assert_eq!(Compression::from_path("run.json.gz"), Compression::Gzip);
+        assert_eq!(Compression::from_path("run.csv.zst"), Compression::Zstd);
+        assert_eq!(Compression::from_path("run"), Compression::None);
+    }
+
+    #[test]
+    fn test_plain_file_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("out").join("run.csv");
+        write_file(&path, b"time,x\n0,1\n").expect("write file");
+        assert_eq!(read_to_string(&path).expect("read file"), "time,x\n0,1\n");
+    }
+
+    #[cfg(feature = "compression")]
+    #[test]
+    fn test_compressed_file_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let text = "time,x,y\n".to_string() + &"0.1,50,0\n".repeat(1000);
+        for name in ["run.csv.gz", "run.csv.zst"] {
+            let path = temp_dir.path().join(name);
+            write_file(&path, text.as_bytes()).expect("write compressed file");
+            let size = std::fs::metadata(&path).expect("metadata").len();
+            assert!(size < text.len() as u64 / 10, "{} is not compressed", name);
+            assert_eq!(read_to_string(&path).expect("read compressed file"), text);
+        }
+    }
+
+    #[cfg(not(feature = "compression"))]
+    #[test]
+    fn test_compressed_paths_need_feature() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let err = write_file(temp_dir.path().join("run.json.gz"), b"{}")
+            .expect_err("compression disabled");
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+        assert!(err.to_string().contains("`compression` feature"));
+    }
+}
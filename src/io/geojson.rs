@@ -0,0 +1,256 @@
+//! GeoJSON export of tracks and trajectories for GIS tools and web maps.
+//!
+//! Without a `GeoReference`, coordinates are written in the local track frame (meters),
+//! which most viewers display as a plain cartesian plane. With a reference, local
+//! coordinates are placed on the WGS84 ellipsoid around the reference point, which is
+//! accurate for track-sized areas (a few kilometers).
+
+use super::{json_number, json_string};
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::base_track::Track;
+use std::fs;
+use std::path::Path;
+
+/// WGS84 semi-major axis in meters
+const WGS84_A: f64 = 6_378_137.0;
+/// WGS84 first eccentricity squared
+const WGS84_E2: f64 = 6.694_379_990_14e-3;
+
+/// WGS84 position of the local origin, with local x pointing east and y pointing north
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GeoReference {
+    /// Latitude of the local origin in degrees
+    pub latitude: f64,
+    /// Longitude of the local origin in degrees
+    pub longitude: f64,
+}
+
+impl GeoReference {
+    pub fn new(latitude: f64, longitude: f64) -> Self {
+        Self {
+            latitude,
+            longitude,
+        }
+    }
+
+    /// Convert local east/north coordinates to WGS84
+    ///
+    /// # Arguments
+    /// * `x` - Local east coordinate in meters
+    /// * `y` - Local north coordinate in meters
+    ///
+    /// # Returns
+    /// Tuple of (longitude, latitude) in degrees, the GeoJSON axis order
+    pub fn to_wgs84(&self, x: f64, y: f64) -> (f64, f64) {
+        let lat = self.latitude.to_radians();
+        let sin_lat = lat.sin();
+        let w = (1.0 - WGS84_E2 * sin_lat * sin_lat).sqrt();
+        // Radii of curvature in the meridian and prime vertical
+        let meridian = WGS84_A * (1.0 - WGS84_E2) / (w * w * w);
+        let prime_vertical = WGS84_A / w;
+
+        let latitude = self.latitude + (y / meridian).to_degrees();
+        let longitude = self.longitude + (x / (prime_vertical * lat.cos())).to_degrees();
+        (longitude, latitude)
+    }
+}
+
+/// Collection of GeoJSON features written as one `FeatureCollection`
+pub struct GeoJsonWriter {
+    reference: Option<GeoReference>,
+    features: Vec<String>,
+}
+
+impl GeoJsonWriter {
+    /// Create an empty collection
+    ///
+    /// # Arguments
+    /// * `reference` - WGS84 origin of the local frame, or `None` to keep local meters
+    pub fn new(reference: Option<GeoReference>) -> Self {
+        Self {
+            reference,
+            features: Vec::new(),
+        }
+    }
+
+    /// Number of features added so far
+    pub fn len(&self) -> usize {
+        self.features.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.features.is_empty()
+    }
+
+    /// Add the center line and both boundaries as closed `LineString` features
+    pub fn add_track(&mut self, track: &dyn Track) {
+        let lines = [
+            ("center_line", track.get_center_line()),
+            ("inside_boundary", track.get_inside_boundary()),
+            ("outside_boundary", track.get_outside_boundary()),
+        ];
+        for (kind, points) in lines {
+            if points.is_empty() {
+                continue;
+            }
+            let mut closed = points.to_vec();
+            closed.push(points[0]);
+            let properties = format!(
+                "{{\"track\":{},\"kind\":{}}}",
+                json_string(track.get_track_name()),
+                json_string(kind)
+            );
+            self.add_line_string(&closed, properties);
+        }
+    }
+
+    /// Add a simulated trajectory as a `LineString` feature
+    ///
+    /// The snapshot times and in-track flags are stored as per-vertex property arrays.
+    ///
+    /// # Arguments
+    /// * `name` - Name stored in the feature properties
+    /// * `result` - Recorded snapshots of the run
+    pub fn add_trajectory<S: Pose>(&mut self, name: &str, result: &SimulationResult<S>) {
+        let points: Vec<Vec2> = result
+            .states()
+            .map(|state| Vec2::new(state.x(), state.y()))
+            .collect();
+        let times: Vec<String> = result.times().map(json_number).collect();
+        let in_track: Vec<&str> = result
+            .snapshots()
+            .iter()
+            .map(|snapshot| if snapshot.in_track { "true" } else { "false" })
+            .collect();
+        let properties = format!(
+            "{{\"name\":{},\"kind\":\"trajectory\",\"times\":[{}],\"in_track\":[{}]}}",
+            json_string(name),
+            times.join(","),
+            in_track.join(",")
+        );
+        self.add_line_string(&points, properties);
+    }
+
+    /// Render the `FeatureCollection`
+    pub fn to_geojson(&self) -> String {
+        format!(
+            "{{\"type\":\"FeatureCollection\",\"features\":[{}]}}",
+            self.features.join(",")
+        )
+    }
+
+    /// Write the `FeatureCollection` to `path`, creating parent directories
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_geojson())?;
+        Ok(())
+    }
+
+    fn add_line_string(&mut self, points: &[Vec2], properties: String) {
+        let coordinates: Vec<String> = points
+            .iter()
+            .map(|&Vec2 { x, y }| {
+                let (a, b) = match &self.reference {
+                    Some(reference) => reference.to_wgs84(x, y),
+                    None => (x, y),
+                };
+                format!("[{},{}]", json_number(a), json_number(b))
+            })
+            .collect();
+        self.features.push(format!(
+            "{{\"type\":\"Feature\",\"geometry\":{{\"type\":\"LineString\",\"coordinates\":[{}]}},\"properties\":{}}}",
+            coordinates.join(","),
+            properties
+        ));
+    }
+}
+
+/// Export a track and, optionally, a simulated trajectory to a GeoJSON file
+///
+/// # Arguments
+/// * `path` - Output file (e.g., "results/track.geojson")
+/// * `track` - Track whose center line and boundaries are exported
+/// * `result` - Simulated run to export as a trajectory, if any
+/// * `reference` - WGS84 origin of the local frame, or `None` to keep local meters
+pub fn export_geojson<P: AsRef<Path>, S: Pose>(
+    path: P,
+    track: &dyn Track,
+    result: Option<&SimulationResult<S>>,
+    reference: Option<GeoReference>,
+) -> crate::Result<()> {
+    let mut writer = GeoJsonWriter::new(reference);
+    writer.add_track(track);
+    if let Some(result) = result {
+        writer.add_trajectory("trajectory", result);
+    }
+    writer.save(path)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_geojson, GeoJsonWriter, GeoReference};
+    use crate::simulation::result::SimulationResult;
+    use crate::tracks::circle::CircleTrack;
+
+    #[test]
+    fn test_geo_reference_offsets() {
+        let reference = GeoReference::new(48.0, 11.0);
+        let (lon, lat) = reference.to_wgs84(0.0, 0.0);
+        assert_eq!((lon, lat), (11.0, 48.0));
+
+        // One arc minute of latitude is roughly one nautical mile
+        let (_, lat) = reference.to_wgs84(0.0, 1852.0);
+        assert!(((lat - 48.0) * 60.0 - 1.0).abs() < 0.01);
+
+        // Longitude degrees shrink with cos(latitude)
+        let (lon, _) = reference.to_wgs84(1000.0, 0.0);
+        let expected = 1000.0 / (111_320.0 * 48.0f64.to_radians().cos());
+        assert!((lon - 11.0 - expected).abs() < 1e-4);
+    }
+
+    #[test]
+    fn test_track_and_trajectory_features() {
+        let track = CircleTrack::new(50.0, 10.0, 4);
+        let mut result = SimulationResult::new();
+        result.push(0.0, (1.0, 2.0, 0.0), true);
+        result.push(0.5, (3.0, 4.0, 0.0), false);
+
+        let mut writer = GeoJsonWriter::new(None);
+        writer.add_track(&track);
+        writer.add_trajectory("run \"a\"", &result);
+        assert_eq!(writer.len(), 4);
+
+        let geojson = writer.to_geojson();
+        assert!(geojson.starts_with("{\"type\":\"FeatureCollection\""));
+        assert!(geojson.contains("\"kind\":\"outside_boundary\""));
+        assert!(geojson.contains("\"coordinates\":[[1,2],[3,4]]"));
+        assert!(geojson.contains("\"name\":\"run \\\"a\\\"\""));
+        assert!(geojson.contains("\"times\":[0,0.5],\"in_track\":[true,false]"));
+    }
+
+    #[test]
+    fn test_export_geojson_writes_file() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("maps").join("track.geojson");
+        let track = CircleTrack::new(50.0, 10.0, 8);
+
+        export_geojson::<_, (f64, f64, f64)>(
+            &path,
+            &track,
+            None,
+            Some(GeoReference::new(0.0, 0.0)),
+        )
+        .expect("export geojson");
+
+        let geojson = std::fs::read_to_string(&path).expect("read geojson");
+        assert_eq!(geojson.matches("\"type\":\"Feature\"").count(), 3);
+        assert!(!geojson.contains("[50,"));
+    }
+}
@@ -0,0 +1,288 @@
+//! HDF5 export of batch runs (parameter sweeps, Monte Carlo studies).
+//!
+//! Every run is a group `run_NNNN` holding one dataset per signal (`time`, `x`, `y`,
+//! `vx`, `vy`, `yaw`, and `in_track` as 0/1 bytes). The run's parameters are float
+//! attributes of its group, listed in order by the `parameters` attribute, and its
+//! seed, if any, is the `seed` attribute. The root group carries the crate version
+//! and git commit. Files are written by the pure-Rust `hdf5-pure` crate, so no native
+//! `libhdf5` is needed; h5py, MATLAB, and the HDF5 tools read them as usual.
+
+use crate::error::{Error, Result};
+use crate::models::point_mass::PointMassState;
+use crate::provenance::Provenance;
+use crate::simulation::monte_carlo::MonteCarloResult;
+use crate::simulation::result::SimulationResult;
+use hdf5_pure::{AttrValue, File, FileBuilder};
+use std::collections::HashMap;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+/// Run read back from a batch file
+#[derive(Debug, Clone, PartialEq)]
+pub struct BatchRun {
+    /// (name, value) pairs of the run's parameters, in the order they were written
+    pub parameters: Vec<(String, f64)>,
+    /// Seed of the run's random generator, for stochastic runs
+    pub seed: Option<u64>,
+    pub result: SimulationResult<PointMassState>,
+}
+
+/// Collects the runs of a batch and writes them to one HDF5 file
+pub struct Hdf5BatchWriter {
+    builder: FileBuilder,
+    runs: usize,
+}
+
+impl Default for Hdf5BatchWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Hdf5BatchWriter {
+    pub fn new() -> Self {
+        let provenance = Provenance::current();
+        let mut builder = FileBuilder::new();
+        builder.set_attr("crate_version", AttrValue::String(provenance.crate_version));
+        builder.set_attr("git_commit", AttrValue::String(provenance.git_commit));
+        Self { builder, runs: 0 }
+    }
+
+    /// Number of runs added so far
+    pub fn len(&self) -> usize {
+        self.runs
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.runs == 0
+    }
+
+    /// Add a run as the next group
+    ///
+    /// # Arguments
+    /// * `parameters` - (name, value) pairs, e.g. `SweepPoint::values`
+    /// * `seed` - Seed of the run's random generator, `None` for deterministic runs
+    /// * `result` - Trajectory of the run
+    ///
+    /// # Returns
+    /// Name of the run's group
+    pub fn add_run(
+        &mut self,
+        parameters: &[(String, f64)],
+        seed: Option<u64>,
+        result: &SimulationResult<PointMassState>,
+    ) -> Result<String> {
+        let mut names = Vec::with_capacity(parameters.len());
+        for (name, _) in parameters {
+            if name.is_empty() || matches!(name.as_str(), "parameters" | "seed") {
+                return Err(Error::invalid_input(format!(
+                    "parameter name {:?} is reserved in batch files",
+                    name
+                )));
+            }
+            if names.contains(name) {
+                return Err(Error::invalid_input(format!("duplicate parameter {:?}", name)));
+            }
+            names.push(name.clone());
+        }
+
+        let name = format!("run_{:04}", self.runs);
+        let mut group = self.builder.create_group(&name);
+        group.set_attr("parameters", AttrValue::StringArray(names));
+        for (parameter, value) in parameters {
+            group.set_attr(parameter, AttrValue::F64(*value));
+        }
+        if let Some(seed) = seed {
+            group.set_attr("seed", AttrValue::U64(seed));
+        }
+        let signal = |value: fn(&PointMassState) -> f64| -> Vec<f64> {
+            result.states().map(value).collect()
+        };
+        group.create_dataset("time").with_f64_data(&result.times().collect::<Vec<_>>());
+        group.create_dataset("x").with_f64_data(&signal(|state| state.x));
+        group.create_dataset("y").with_f64_data(&signal(|state| state.y));
+        group.create_dataset("vx").with_f64_data(&signal(|state| state.vx));
+        group.create_dataset("vy").with_f64_data(&signal(|state| state.vy));
+        group.create_dataset("yaw").with_f64_data(&signal(|state| state.yaw));
+        let in_track: Vec<u8> =
+            result.snapshots().iter().map(|snapshot| u8::from(snapshot.in_track)).collect();
+        group.create_dataset("in_track").with_u8_data(&in_track);
+        self.builder.add_group(group.finish());
+        self.runs += 1;
+        Ok(name)
+    }
+
+    /// Add every run of a Monte Carlo study with its seed and initial offset
+    /// (`initial.lateral`, `initial.heading`, `initial.speed`)
+    pub fn add_monte_carlo(&mut self, study: &MonteCarloResult) -> Result<()> {
+        for run in &study.runs {
+            let parameters = [
+                ("initial.lateral".to_string(), run.initial.lateral),
+                ("initial.heading".to_string(), run.initial.heading),
+                ("initial.speed".to_string(), run.initial.speed),
+            ];
+            self.add_run(&parameters, Some(run.seed), &run.result)?;
+        }
+        Ok(())
+    }
+
+    /// Write the file, creating parent directories if needed
+    pub fn write<P: AsRef<Path>>(self, path: P) -> Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        let bytes = self.builder.finish().map_err(hdf5_error)?;
+        fs::write(path, bytes).map_err(|err| {
+            io::Error::new(err.kind(), format!("failed to write {}: {}", path.display(), err))
+        })?;
+        Ok(())
+    }
+}
+
+/// Read every run of a file written by `Hdf5BatchWriter`, in run order
+pub fn read_batch_hdf5<P: AsRef<Path>>(path: P) -> Result<Vec<BatchRun>> {
+    let file = File::open(path).map_err(hdf5_error)?;
+    let mut names: Vec<String> = file
+        .root()
+        .groups()
+        .map_err(hdf5_error)?
+        .into_iter()
+        .filter(|name| name.starts_with("run_"))
+        .collect();
+    names.sort();
+
+    let mut runs = Vec::with_capacity(names.len());
+    for name in names {
+        let group = file.group(&name).map_err(hdf5_error)?;
+        let attrs = group.attrs().map_err(hdf5_error)?;
+        let parameters = match attrs.get("parameters") {
+            Some(AttrValue::StringArray(names)) => names
+                .iter()
+                .map(|parameter| Ok((parameter.clone(), float_attr(&attrs, &name, parameter)?)))
+                .collect::<Result<Vec<_>>>()?,
+            _ => return Err(malformed(&name, "has no parameter list")),
+        };
+        let seed = match attrs.get("seed") {
+            None => None,
+            Some(AttrValue::U64(seed)) => Some(*seed),
+            Some(_) => return Err(malformed(&name, "has a seed that is not an unsigned integer")),
+        };
+
+        let signal = |signal: &str| -> Result<Vec<f64>> {
+            group.dataset(signal).and_then(|data| data.read_f64()).map_err(hdf5_error)
+        };
+        let time = signal("time")?;
+        let (x, y, yaw) = (signal("x")?, signal("y")?, signal("yaw")?);
+        let (vx, vy) = (signal("vx")?, signal("vy")?);
+        let in_track = group
+            .dataset("in_track")
+            .and_then(|data| data.read_u8())
+            .map_err(hdf5_error)?;
+        let len = time.len();
+        if [x.len(), y.len(), vx.len(), vy.len(), yaw.len(), in_track.len()]
+            .iter()
+            .any(|&signal_len| signal_len != len)
+        {
+            return Err(malformed(&name, "has signals of different lengths"));
+        }
+        let mut result = SimulationResult::with_capacity(len);
+        for i in 0..len {
+            let state = PointMassState {
+                x: x[i],
+                y: y[i],
+                vx: vx[i],
+                vy: vy[i],
+                yaw: yaw[i],
+            };
+            result.push(time[i], state, in_track[i] != 0);
+        }
+        runs.push(BatchRun {
+            parameters,
+            seed,
+            result,
+        });
+    }
+    Ok(runs)
+}
+
+fn float_attr(attrs: &HashMap<String, AttrValue>, group: &str, name: &str) -> Result<f64> {
+    match attrs.get(name) {
+        Some(AttrValue::F64(value)) => Ok(*value),
+        _ => Err(malformed(group, &format!("has no float parameter {:?}", name))),
+    }
+}
+
+fn malformed(group: &str, problem: &str) -> Error {
+    Error::invalid_input(format!("batch run {} {}", group, problem))
+}
+
+fn hdf5_error<E: std::fmt::Display>(err: E) -> Error {
+    Error::Io(io::Error::other(format!("HDF5 error: {}", err)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{read_batch_hdf5, Hdf5BatchWriter};
+    use crate::models::point_mass::PointMassState;
+    use crate::simulation::result::SimulationResult;
+
+    fn trajectory(offset: f64) -> SimulationResult<PointMassState> {
+        (0..20)
+            .map(|step| {
+                let time = step as f64 * 0.1;
+                let state = PointMassState {
+                    x: 10.0 * time,
+                    y: offset,
+                    vx: 10.0,
+                    vy: 0.0,
+                    yaw: 0.01 * step as f64,
+                };
+                crate::simulation::result::StateSnapshot {
+                    time,
+                    state,
+                    in_track: step < 15,
+                }
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_batch_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("sweep").join("batch.h5");
+        let mut writer = Hdf5BatchWriter::new();
+        let sweep = [
+            vec![("k".to_string(), 0.5), ("speed".to_string(), 8.0)],
+            vec![("k".to_string(), 2.0), ("speed".to_string(), 12.0)],
+        ];
+        for (index, parameters) in sweep.iter().enumerate() {
+            let seed = (index == 1).then_some(u64::MAX - 1);
+            writer.add_run(parameters, seed, &trajectory(index as f64)).expect("add run");
+        }
+        assert_eq!(writer.len(), 2);
+        writer.write(&path).expect("write batch");
+
+        let runs = read_batch_hdf5(&path).expect("read batch");
+        assert_eq!(runs.len(), 2);
+        for (index, run) in runs.iter().enumerate() {
+            assert_eq!(run.parameters, sweep[index]);
+            assert_eq!(run.result, trajectory(index as f64));
+        }
+        assert_eq!(runs[0].seed, None);
+        assert_eq!(runs[1].seed, Some(u64::MAX - 1));
+    }
+
+    #[test]
+    fn test_reserved_parameter_names_rejected() {
+        let mut writer = Hdf5BatchWriter::new();
+        let result = trajectory(0.0);
+        assert!(writer.add_run(&[("seed".to_string(), 1.0)], None, &result).is_err());
+        let duplicate = [("k".to_string(), 1.0), ("k".to_string(), 2.0)];
+        assert!(writer.add_run(&duplicate, None, &result).is_err());
+        assert!(writer.is_empty());
+    }
+}
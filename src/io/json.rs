@@ -0,0 +1,202 @@
+//! JSON persistence for anything implementing serde's traits, such as
+//! `SimulationResult`, `TrackGeometry`, and `ScenarioConfig`.
+//!
+//! Objects are saved with an extra `provenance` field (see `provenance::Provenance`)
+//! ahead of their own fields. Loading ignores it, like any unknown field, so the files
+//! still deserialize into the saved type; read it with `io::result_file::load_provenance`.
+
+use super::compression;
+use crate::error::{Error, Result};
+use crate::provenance::Provenance;
+use serde::Serialize;
+use serde::de::DeserializeOwned;
+use std::io;
+use std::path::Path;
+
+/// Value saved with its provenance as an extra field
+#[derive(Serialize)]
+struct WithProvenance<'a, T: ?Sized> {
+    provenance: &'a Provenance,
+    #[serde(flatten)]
+    value: &'a T,
+}
+
+/// Save a value as pretty-printed JSON, with the provenance of the running build
+///
+/// Parent directories are created if needed. Paths ending in `.gz` or `.zst` are
+/// compressed (see `io::compression`).
+///
+/// # Arguments
+/// * `value` - Value to save
+/// * `path` - Destination file (e.g., "results/run.json")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn save_json<T: Serialize + ?Sized, P: AsRef<Path>>(
+    value: &T,
+    path: P,
+) -> Result<()> {
+    save_json_with_provenance(value, &Provenance::current(), path)
+}
+
+/// Save a value as `save_json` does, with the scenario parameters and seed of
+/// `provenance`
+///
+/// Values that do not serialize to a JSON object (sequences, numbers, strings) are
+/// saved without provenance.
+pub fn save_json_with_provenance<T: Serialize + ?Sized, P: AsRef<Path>>(
+    value: &T,
+    provenance: &Provenance,
+    path: P,
+) -> Result<()> {
+    // Only structs and maps can be flattened; anything else is saved as is
+    let mut contents = serde_json::to_string_pretty(&WithProvenance { provenance, value })
+        .or_else(|_| serde_json::to_string_pretty(value))
+        .map_err(io::Error::from)?;
+    contents.push('\n');
+    compression::write_file(path, contents.as_bytes())
+}
+
+/// Load a value from a JSON file
+///
+/// # Arguments
+/// * `path` - File written by `save_json` (or by hand), decompressed by extension
+///
+/// # Returns
+/// The deserialized value, `Error::Io` if the file cannot be read, or
+/// `Error::InvalidConfig` mentioning the file if it cannot be parsed
+pub fn load_json<T: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<T> {
+    let path = path.as_ref();
+    let contents = compression::read_to_string(path)?;
+    serde_json::from_str(&contents).map_err(|err| {
+        Error::invalid_config(format!("failed to parse {}: {}", path.display(), err))
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_json, save_json, save_json_with_provenance};
+    use crate::config::{ScenarioConfig, TrackConfig};
+    use crate::controllers::scripted::{ControlSample, ControlSchedule, ScheduleInterpolation};
+    use crate::io::result_file::load_provenance;
+    use crate::models::point_mass::PointMassState;
+    use crate::provenance::Provenance;
+    use crate::simulation::result::SimulationResult;
+    use crate::tracks::base_track::Track;
+    use crate::tracks::geometry::TrackGeometry;
+    use crate::tracks::square::SquareTrack;
+
+    #[test]
+    fn test_simulation_result_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("nested").join("result.json");
+        let mut result = SimulationResult::new();
+        result.push(
+            0.0,
+            PointMassState {
+                x: 50.0,
+                y: 0.0,
+                vx: 0.0,
+                vy: 0.0,
+                yaw: 1.5,
+            },
+            true,
+        );
+        result.push(
+            0.1,
+            PointMassState {
+                x: 50.0,
+                y: 0.1,
+                vx: 0.2,
+                vy: 0.0,
+                yaw: 1.54,
+            },
+            false,
+        );
+
+        save_json(&result, &path).expect("save result");
+        let loaded: SimulationResult<PointMassState> = load_json(&path).expect("load result");
+
+        assert_eq!(loaded.len(), 2);
+        assert_eq!(loaded.times().collect::<Vec<_>>(), vec![0.0, 0.1]);
+        assert!(!loaded.last().expect("last snapshot").in_track);
+        assert_eq!(loaded.last().expect("last snapshot").state.yaw, 1.54);
+        assert_eq!(load_provenance(&path).expect("load provenance"), Some(Provenance::current()));
+    }
+
+    #[test]
+    fn test_provenance_field() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("config.json");
+        let provenance = Provenance::current().with_seed(11).with_parameter("dt", 0.05);
+        save_json_with_provenance(&ScenarioConfig::default(), &provenance, &path)
+            .expect("save config");
+        let text = std::fs::read_to_string(&path).expect("read config");
+        assert!(text.starts_with("{\n  \"provenance\": {"), "{text}");
+        assert_eq!(load_json::<ScenarioConfig, _>(&path).expect("load"), ScenarioConfig::default());
+        assert_eq!(load_provenance(&path).expect("load provenance"), Some(provenance));
+
+        // Values that are not objects are saved without provenance
+        save_json(&[1.0, 2.0], &path).expect("save array");
+        assert_eq!(load_json::<Vec<f64>, _>(&path).expect("load array"), [1.0, 2.0]);
+        assert_eq!(load_provenance(&path).expect("load provenance"), None);
+    }
+
+    #[test]
+    fn test_track_geometry_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("track.json");
+        let geometry = TrackGeometry::from_track(&SquareTrack::new(100.0, 10.0, 25));
+
+        save_json(&geometry, &path).expect("save track");
+        let loaded: TrackGeometry = load_json(&path).expect("load track");
+
+        assert_eq!(loaded, geometry);
+        assert_eq!(loaded.get_center_line_yaw().len(), loaded.get_center_line().len());
+    }
+
+    #[test]
+    fn test_scenario_config_round_trip_and_defaults() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("scenario.json");
+        let config = ScenarioConfig {
+            track: TrackConfig::Square {
+                height: 80.0,
+                track_width: 8.0,
+                points_per_side: 20,
+            },
+            schedule: Some(
+                ControlSchedule::new(vec![ControlSample {
+                    time: 0.0,
+                    ax: 1.0,
+                    yaw_rate: 0.1,
+                }])
+                .expect("valid schedule")
+                .with_interpolation(ScheduleInterpolation::Linear),
+            ),
+            ..ScenarioConfig::default()
+        };
+
+        save_json(&config, &path).expect("save config");
+        let loaded: ScenarioConfig = load_json(&path).expect("load config");
+        assert_eq!(loaded, config);
+
+        let partial = r#"{
+            "track": {"type": "circle", "radius": 30.0, "track_width": 6.0, "num_points": 50},
+            "dt": 0.05
+        }"#;
+        let partial: ScenarioConfig = serde_json::from_str(partial).expect("parse partial config");
+        assert_eq!(partial.dt, 0.05);
+        assert_eq!(partial.fps, ScenarioConfig::default().fps);
+    }
+
+    #[test]
+    fn test_load_json_reports_path() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("broken.json");
+        std::fs::write(&path, "{ not json").expect("write broken file");
+
+        let err = load_json::<ScenarioConfig, _>(&path).expect_err("expected parse error");
+        assert!(err.to_string().contains("broken.json"));
+    }
+}
@@ -0,0 +1,206 @@
+//! KML export of GPS-referenced tracks and trajectories for Google Earth.
+//!
+//! KML coordinates are always WGS84, so unlike GeoJSON export a `GeoReference` is
+//! required: local track coordinates (meters, x east and y north) are placed around
+//! the reference point. Each line becomes a `Placemark` with a clamped-to-ground
+//! `LineString`; track lines and trajectories get separate styles.
+
+use super::geojson::GeoReference;
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::base_track::Track;
+use std::fs;
+use std::path::Path;
+
+/// Style of track center lines and boundaries (KML colors are aabbggrr)
+const TRACK_STYLE: &str =
+    "<Style id=\"track\"><LineStyle><color>ffb4b4b4</color><width>2</width></LineStyle></Style>";
+/// Style of simulated trajectories
+const TRAJECTORY_STYLE: &str = "<Style id=\"trajectory\"><LineStyle><color>ff0000ff</color><width>3</width></LineStyle></Style>";
+
+/// Collection of placemarks written as one KML document
+pub struct KmlWriter {
+    name: String,
+    reference: GeoReference,
+    placemarks: Vec<String>,
+}
+
+impl KmlWriter {
+    /// Create an empty document
+    ///
+    /// # Arguments
+    /// * `name` - Document name shown in Google Earth
+    /// * `reference` - WGS84 origin of the local frame
+    pub fn new(name: &str, reference: GeoReference) -> Self {
+        Self {
+            name: name.to_string(),
+            reference,
+            placemarks: Vec::new(),
+        }
+    }
+
+    /// Number of placemarks added so far
+    pub fn len(&self) -> usize {
+        self.placemarks.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.placemarks.is_empty()
+    }
+
+    /// Add the center line and both boundaries as closed line placemarks
+    pub fn add_track(&mut self, track: &dyn Track) {
+        let lines = [
+            ("center_line", track.get_center_line()),
+            ("inside_boundary", track.get_inside_boundary()),
+            ("outside_boundary", track.get_outside_boundary()),
+        ];
+        for (kind, points) in lines {
+            if points.is_empty() {
+                continue;
+            }
+            let mut closed = points.to_vec();
+            closed.push(points[0]);
+            let name = format!("{} {}", track.get_track_name(), kind);
+            self.add_line_string(&name, "track", &closed);
+        }
+    }
+
+    /// Add a simulated trajectory as a line placemark
+    ///
+    /// # Arguments
+    /// * `name` - Placemark name
+    /// * `result` - Recorded snapshots of the run
+    pub fn add_trajectory<S: Pose>(&mut self, name: &str, result: &SimulationResult<S>) {
+        let points: Vec<Vec2> = result
+            .states()
+            .map(|state| Vec2::new(state.x(), state.y()))
+            .collect();
+        self.add_line_string(name, "trajectory", &points);
+    }
+
+    /// Render the KML document
+    pub fn to_kml(&self) -> String {
+        let mut kml = String::from("<?xml version=\"1.0\" encoding=\"UTF-8\"?>\n");
+        kml.push_str("<kml xmlns=\"http://www.opengis.net/kml/2.2\">\n<Document>\n");
+        kml.push_str(&format!("<name>{}</name>\n", escape_xml(&self.name)));
+        kml.push_str(TRACK_STYLE);
+        kml.push('\n');
+        kml.push_str(TRAJECTORY_STYLE);
+        kml.push('\n');
+        for placemark in &self.placemarks {
+            kml.push_str(placemark);
+            kml.push('\n');
+        }
+        kml.push_str("</Document>\n</kml>\n");
+        kml
+    }
+
+    /// Write the KML document to `path`, creating parent directories
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(path, self.to_kml())?;
+        Ok(())
+    }
+
+    fn add_line_string(&mut self, name: &str, style: &str, points: &[Vec2]) {
+        let coordinates: Vec<String> = points
+            .iter()
+            .filter(|point| point.x.is_finite() && point.y.is_finite())
+            .map(|&Vec2 { x, y }| {
+                let (longitude, latitude) = self.reference.to_wgs84(x, y);
+                format!("{},{},0", longitude, latitude)
+            })
+            .collect();
+        self.placemarks.push(format!(
+            "<Placemark><name>{}</name><styleUrl>#{}</styleUrl><LineString><tessellate>1</tessellate><altitudeMode>clampToGround</altitudeMode><coordinates>{}</coordinates></LineString></Placemark>",
+            escape_xml(name),
+            style,
+            coordinates.join(" ")
+        ));
+    }
+}
+
+/// Export a track and, optionally, a simulated trajectory to a KML file
+///
+/// # Arguments
+/// * `path` - Output file (e.g., "results/track.kml")
+/// * `track` - Track whose center line and boundaries are exported
+/// * `result` - Simulated run to export as a trajectory, if any
+/// * `reference` - WGS84 origin of the local frame
+pub fn export_kml<P: AsRef<Path>, S: Pose>(
+    path: P,
+    track: &dyn Track,
+    result: Option<&SimulationResult<S>>,
+    reference: GeoReference,
+) -> crate::Result<()> {
+    let mut writer = KmlWriter::new(track.get_track_name(), reference);
+    writer.add_track(track);
+    if let Some(result) = result {
+        writer.add_trajectory("trajectory", result);
+    }
+    writer.save(path)
+}
+
+fn escape_xml(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&apos;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_kml, KmlWriter};
+    use crate::io::geojson::GeoReference;
+    use crate::simulation::result::SimulationResult;
+    use crate::tracks::circle::CircleTrack;
+
+    #[test]
+    fn test_track_and_trajectory_placemarks() {
+        let track = CircleTrack::new(50.0, 10.0, 4);
+        let mut result = SimulationResult::new();
+        result.push(0.0, (0.0, 0.0, 0.0), true);
+        result.push(0.5, (1000.0, 0.0, 0.0), true);
+
+        let mut writer = KmlWriter::new("Run <1>", GeoReference::new(0.0, 11.0));
+        writer.add_track(&track);
+        writer.add_trajectory("lap & co", &result);
+        assert_eq!(writer.len(), 4);
+
+        let kml = writer.to_kml();
+        assert!(kml.starts_with("<?xml"));
+        assert!(kml.contains("<name>Run &lt;1&gt;</name>"));
+        assert!(kml.contains("<name>lap &amp; co</name><styleUrl>#trajectory</styleUrl>"));
+        assert!(kml.contains("<coordinates>11,0,0 11.00898"));
+        assert_eq!(kml.matches("<styleUrl>#track</styleUrl>").count(), 3);
+    }
+
+    #[test]
+    fn test_export_kml_writes_file() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("maps").join("track.kml");
+        let track = CircleTrack::new(50.0, 10.0, 8);
+
+        export_kml::<_, (f64, f64, f64)>(&path, &track, None, GeoReference::new(48.0, 11.0))
+            .expect("export kml");
+
+        let kml = std::fs::read_to_string(&path).expect("read kml");
+        assert_eq!(kml.matches("<Placemark>").count(), 3);
+        assert!(kml.trim_end().ends_with("</kml>"));
+    }
+}
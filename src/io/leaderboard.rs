@@ -0,0 +1,291 @@
+//! Best lap times per track, model, and controller, kept in a local file across runs.
+//!
+//! The leaderboard file is a version line followed by a CSV table with one record per
+//! track/model/controller combination:
+//!
+//! ```text
+//! # lap_simulation leaderboard v1
+//! track,model,controller,lap_time,set_at
+//! Square Track,point_mass,stanley,52.35,1760000000
+//! ```
+//!
+//! `set_at` is the time the record was set, in seconds since the Unix epoch. Names may
+//! not contain commas or line breaks.
+
+use super::compression;
+use crate::error::{Error, Result};
+use std::fmt;
+use std::path::Path;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// First line of a leaderboard file
+pub const LEADERBOARD_FILE_HEADER: &str = "# lap_simulation leaderboard v1";
+
+/// Leaderboard file used by the CLI when none is given
+pub const DEFAULT_LEADERBOARD_FILE: &str = "results/leaderboard.csv";
+
+const LEADERBOARD_COLUMNS: &str = "track,model,controller,lap_time,set_at";
+
+/// Best lap of one track/model/controller combination
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapRecord {
+    pub track: String,
+    pub model: String,
+    pub controller: String,
+    /// Lap time in seconds
+    pub lap_time: f64,
+    /// Time the record was set, in seconds since the Unix epoch
+    pub set_at: u64,
+}
+
+impl LapRecord {
+    /// Create a record set now
+    pub fn new(track: &str, model: &str, controller: &str, lap_time: f64) -> Result<Self> {
+        for (what, name) in [("track", track), ("model", model), ("controller", controller)] {
+            if name.trim().is_empty() || name.contains([',', '\n', '\r']) {
+                return Err(Error::invalid_input(format!("invalid {} name '{}'", what, name)));
+            }
+        }
+        if !(lap_time.is_finite() && lap_time > 0.0) {
+            return Err(Error::invalid_input(format!(
+                "lap time must be positive, got {}",
+                lap_time
+            )));
+        }
+        Ok(Self {
+            track: track.trim().to_string(),
+            model: model.trim().to_string(),
+            controller: controller.trim().to_string(),
+            lap_time,
+            set_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map_or(0, |elapsed| elapsed.as_secs()),
+        })
+    }
+
+    fn key(&self) -> (&str, &str, &str) {
+        (&self.track, &self.model, &self.controller)
+    }
+}
+
+/// Outcome of submitting a lap to the leaderboard
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Submission {
+    /// The lap is the new record; `previous` is the record it beat, if any
+    NewRecord { previous: Option<f64> },
+    /// The lap did not beat the record of `best` seconds
+    NotImproved { best: f64 },
+}
+
+impl fmt::Display for Submission {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Submission::NewRecord { previous: Some(previous) } => {
+                write!(f, "new record (previous {:.3} s)", previous)
+            }
+            Submission::NewRecord { previous: None } => write!(f, "first record"),
+            Submission::NotImproved { best } => write!(f, "no improvement (record {:.3} s)", best),
+        }
+    }
+}
+
+/// Best lap records, sorted by track, model, and controller
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Leaderboard {
+    records: Vec<LapRecord>,
+}
+
+impl Leaderboard {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn records(&self) -> &[LapRecord] {
+        &self.records
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.records.is_empty()
+    }
+
+    /// Record of the given combination, if any
+    pub fn best(&self, track: &str, model: &str, controller: &str) -> Option<&LapRecord> {
+        self.records
+            .iter()
+            .find(|record| record.key() == (track, model, controller))
+    }
+
+    /// Keep `record` if it beats the record of its combination
+    pub fn submit(&mut self, record: LapRecord) -> Submission {
+        match self.records.binary_search_by(|other| other.key().cmp(&record.key())) {
+            Ok(index) => {
+                let best = &mut self.records[index];
+                if record.lap_time < best.lap_time {
+                    let previous = best.lap_time;
+                    *best = record;
+                    Submission::NewRecord { previous: Some(previous) }
+                } else {
+                    Submission::NotImproved { best: best.lap_time }
+                }
+            }
+            Err(index) => {
+                self.records.insert(index, record);
+                Submission::NewRecord { previous: None }
+            }
+        }
+    }
+
+    /// Serialize the leaderboard in the leaderboard file format (see the module documentation)
+    pub fn to_file_string(&self) -> String {
+        let mut text = format!("{}\n{}\n", LEADERBOARD_FILE_HEADER, LEADERBOARD_COLUMNS);
+        for record in &self.records {
+            text.push_str(&format!(
+                "{},{},{},{},{}\n",
+                record.track, record.model, record.controller, record.lap_time, record.set_at
+            ));
+        }
+        text
+    }
+
+    /// Parse a leaderboard file (see the module documentation)
+    ///
+    /// # Returns
+    /// The leaderboard, or `Error::InvalidInput` naming the line that is malformed
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut lines = text.lines().enumerate();
+        match lines.next() {
+            Some((_, header)) if header.trim() == LEADERBOARD_FILE_HEADER => {}
+            _ => {
+                let message = format!("missing '{}' header", LEADERBOARD_FILE_HEADER);
+                return Err(Error::invalid_input(message));
+            }
+        }
+
+        let mut leaderboard = Self::new();
+        for (line_index, line) in lines {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') || line == LEADERBOARD_COLUMNS {
+                continue;
+            }
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let [track, model, controller, lap_time, set_at] = fields[..] else {
+                let message = format!("line {}: expected 5 columns", line_index + 1);
+                return Err(Error::invalid_input(message));
+            };
+            let invalid = |field: &str| {
+                Error::invalid_input(format!("line {}: invalid value '{}'", line_index + 1, field))
+            };
+            let mut record = LapRecord::new(
+                track,
+                model,
+                controller,
+                lap_time.parse().map_err(|_| invalid(lap_time))?,
+            )
+            .map_err(|err| err.context(format_args!("line {}", line_index + 1)))?;
+            record.set_at = set_at.parse().map_err(|_| invalid(set_at))?;
+            leaderboard.submit(record);
+        }
+        Ok(leaderboard)
+    }
+
+    /// Load a leaderboard file; a missing file is an empty leaderboard
+    ///
+    /// # Returns
+    /// The leaderboard, `Error::Io` if the file cannot be read, or `Error::InvalidInput`
+    /// mentioning the file if it is malformed
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if !path.exists() {
+            return Ok(Self::new());
+        }
+        let text = compression::read_to_string(path)?;
+        Self::parse(&text).map_err(|err| err.context(path.display()))
+    }
+
+    /// Save the leaderboard file, creating parent directories
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        compression::write_file(path, self.to_file_string().as_bytes())
+    }
+
+    /// Submit `record` to the leaderboard file at `path`, saving it if the record improved
+    pub fn update_file<P: AsRef<Path>>(path: P, record: LapRecord) -> Result<Submission> {
+        let mut leaderboard = Self::load(&path)?;
+        let submission = leaderboard.submit(record);
+        if matches!(submission, Submission::NewRecord { .. }) {
+            leaderboard.save(&path)?;
+        }
+        Ok(submission)
+    }
+}
+
+impl fmt::Display for Leaderboard {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        if self.records.is_empty() {
+            return write!(f, "No lap records yet");
+        }
+        write!(f, "{:<20} {:<12} {:<20} {:>10}", "Track", "Model", "Controller", "Lap (s)")?;
+        for record in &self.records {
+            write!(
+                f,
+                "\n{:<20} {:<12} {:<20} {:>10.3}",
+                record.track, record.model, record.controller, record.lap_time
+            )?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{LapRecord, Leaderboard, Submission};
+
+    fn record(controller: &str, lap_time: f64) -> LapRecord {
+        LapRecord::new("Square Track", "point_mass", controller, lap_time).expect("record")
+    }
+
+    #[test]
+    fn test_submit_keeps_best_lap() {
+        let mut leaderboard = Leaderboard::new();
+        assert_eq!(
+            leaderboard.submit(record("stanley", 52.0)),
+            Submission::NewRecord { previous: None }
+        );
+        assert_eq!(
+            leaderboard.submit(record("stanley", 53.0)),
+            Submission::NotImproved { best: 52.0 }
+        );
+        assert_eq!(
+            leaderboard.submit(record("stanley", 51.5)),
+            Submission::NewRecord { previous: Some(52.0) }
+        );
+        leaderboard.submit(record("pure_pursuit", 55.0));
+
+        assert_eq!(leaderboard.records().len(), 2);
+        assert_eq!(leaderboard.records()[0].controller, "pure_pursuit");
+        let best = leaderboard.best("Square Track", "point_mass", "stanley").expect("best");
+        assert_eq!(best.lap_time, 51.5);
+        assert!(leaderboard.to_string().contains("51.500"));
+        assert!(LapRecord::new("a,b", "point_mass", "stanley", 1.0).is_err());
+    }
+
+    #[test]
+    fn test_file_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("sessions").join("leaderboard.csv");
+        assert!(Leaderboard::load(&path).expect("missing file").is_empty());
+
+        Leaderboard::update_file(&path, record("stanley", 52.0)).expect("first update");
+        let submission =
+            Leaderboard::update_file(&path, record("stanley", 54.0)).expect("second update");
+        assert_eq!(submission, Submission::NotImproved { best: 52.0 });
+
+        let loaded = Leaderboard::load(&path).expect("load leaderboard");
+        assert_eq!(loaded.records().len(), 1);
+        assert_eq!(loaded.records()[0].lap_time, 52.0);
+
+        let err = Leaderboard::parse("# lap_simulation leaderboard v1\nSquare Track,x\n")
+            .expect_err("bad row");
+        assert!(err.to_string().contains("line 2"));
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+    }
+}
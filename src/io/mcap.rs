@@ -0,0 +1,506 @@
+//! MCAP logging of simulation runs for inspection in Foxglove Studio.
+//!
+//! Runs are written as an unchunked MCAP file with JSON-encoded messages on three
+//! channels: `/state` (model state and track status), `/controls` (inputs applied
+//! from that time on), and `/events` (off-track excursions, run start and end).
+//! Each channel has a JSON schema so Foxglove can plot the fields directly.
+
+use super::{json_number, json_string};
+use crate::models::point_mass::PointMassState;
+use crate::provenance::Provenance;
+use crate::simulation::result::SimulationResult;
+use crate::error::{Error, Result};
+use std::collections::BTreeMap;
+use std::fs::{self, File};
+use std::io::{BufWriter, Write};
+use std::path::Path;
+
+const MAGIC: &[u8] = b"\x89MCAP0\r\n";
+
+const OP_HEADER: u8 = 0x01;
+const OP_FOOTER: u8 = 0x02;
+const OP_SCHEMA: u8 = 0x03;
+const OP_CHANNEL: u8 = 0x04;
+const OP_MESSAGE: u8 = 0x05;
+const OP_STATISTICS: u8 = 0x0B;
+const OP_METADATA: u8 = 0x0C;
+const OP_DATA_END: u8 = 0x0F;
+
+const STATE_SCHEMA: &str = r#"{"type":"object","properties":{"time":{"type":"number"},"x":{"type":"number"},"y":{"type":"number"},"vx":{"type":"number"},"vy":{"type":"number"},"yaw":{"type":"number"},"in_track":{"type":"boolean"}}}"#;
+const CONTROLS_SCHEMA: &str = r#"{"type":"object","properties":{"time":{"type":"number"},"ax":{"type":"number"},"yaw_rate":{"type":"number"}}}"#;
+const EVENT_SCHEMA: &str = r#"{"type":"object","properties":{"time":{"type":"number"},"kind":{"type":"string"},"message":{"type":"string"}}}"#;
+
+struct Schema {
+    id: u16,
+    name: String,
+    encoding: String,
+    data: Vec<u8>,
+}
+
+struct Channel {
+    id: u16,
+    schema_id: u16,
+    topic: String,
+    message_encoding: String,
+}
+
+/// Handle of a channel registered in an MCAP file
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct ChannelId(u16);
+
+/// Low-level writer for unchunked MCAP files
+///
+/// Schemas and channels are written when registered and repeated in the summary
+/// section together with message statistics. CRCs are left at zero (not computed),
+/// which the MCAP specification allows.
+pub struct McapWriter<W: Write> {
+    writer: W,
+    position: u64,
+    schemas: Vec<Schema>,
+    channels: Vec<Channel>,
+    message_counts: BTreeMap<u16, u64>,
+    sequences: BTreeMap<u16, u32>,
+    time_range: Option<(u64, u64)>,
+    metadata_count: u32,
+}
+
+impl McapWriter<BufWriter<File>> {
+    /// Create an MCAP file at `path`, creating parent directories if needed
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        if let Some(parent) = path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        Self::new(BufWriter::new(File::create(path)?))
+    }
+}
+
+impl<W: Write> McapWriter<W> {
+    /// Start an MCAP stream on `writer` (magic and header are written immediately)
+    pub fn new(writer: W) -> Result<Self> {
+        let mut mcap = Self {
+            writer,
+            position: 0,
+            schemas: Vec::new(),
+            channels: Vec::new(),
+            message_counts: BTreeMap::new(),
+            sequences: BTreeMap::new(),
+            time_range: None,
+            metadata_count: 0,
+        };
+        mcap.write_bytes(MAGIC)?;
+        let mut header = Vec::new();
+        put_string(&mut header, "");
+        put_string(&mut header, "lap_simulation");
+        mcap.write_record(OP_HEADER, &header)?;
+        Ok(mcap)
+    }
+
+    /// Register a schema (e.g., encoding "jsonschema")
+    ///
+    /// # Returns
+    /// The schema id to use in `add_channel`
+    pub fn add_schema(&mut self, name: &str, encoding: &str, data: &[u8]) -> Result<u16> {
+        let schema = Schema {
+            id: self.schemas.len() as u16 + 1,
+            name: name.to_string(),
+            encoding: encoding.to_string(),
+            data: data.to_vec(),
+        };
+        self.write_record(OP_SCHEMA, &schema_record(&schema))?;
+        let id = schema.id;
+        self.schemas.push(schema);
+        Ok(id)
+    }
+
+    /// Register a channel publishing `message_encoding` messages (e.g., "json") on `topic`
+    ///
+    /// # Returns
+    /// The channel, or `Error::InvalidInput` if `schema_id` was not registered
+    pub fn add_channel(
+        &mut self,
+        schema_id: u16,
+        topic: &str,
+        message_encoding: &str,
+    ) -> Result<ChannelId> {
+        if !self.schemas.iter().any(|schema| schema.id == schema_id) {
+            return Err(Error::invalid_input(format!("unknown schema id {}", schema_id)));
+        }
+        let channel = Channel {
+            id: self.channels.len() as u16,
+            schema_id,
+            topic: topic.to_string(),
+            message_encoding: message_encoding.to_string(),
+        };
+        self.write_record(OP_CHANNEL, &channel_record(&channel))?;
+        let id = channel.id;
+        self.channels.push(channel);
+        Ok(ChannelId(id))
+    }
+
+    /// Write a message logged at `time` seconds
+    pub fn write_message(&mut self, channel: ChannelId, time: f64, data: &[u8]) -> Result<()> {
+        if usize::from(channel.0) >= self.channels.len() {
+            return Err(Error::invalid_input("unknown channel id"));
+        }
+        let log_time = (time.max(0.0) * 1e9).round() as u64;
+        let sequence = self.sequences.entry(channel.0).or_insert(0);
+        let mut record = Vec::with_capacity(22 + data.len());
+        record.extend_from_slice(&channel.0.to_le_bytes());
+        record.extend_from_slice(&sequence.to_le_bytes());
+        record.extend_from_slice(&log_time.to_le_bytes());
+        record.extend_from_slice(&log_time.to_le_bytes());
+        record.extend_from_slice(data);
+        *sequence += 1;
+        self.write_record(OP_MESSAGE, &record)?;
+
+        *self.message_counts.entry(channel.0).or_insert(0) += 1;
+        self.time_range = Some(match self.time_range {
+            Some((start, end)) => (start.min(log_time), end.max(log_time)),
+            None => (log_time, log_time),
+        });
+        Ok(())
+    }
+
+    /// Write a named metadata record (e.g., "provenance") of key/value pairs
+    pub fn write_metadata(&mut self, name: &str, entries: &[(String, String)]) -> Result<()> {
+        let mut map = Vec::new();
+        for (key, value) in entries {
+            put_string(&mut map, key);
+            put_string(&mut map, value);
+        }
+        let mut record = Vec::with_capacity(8 + name.len() + map.len());
+        put_string(&mut record, name);
+        record.extend_from_slice(&(map.len() as u32).to_le_bytes());
+        record.extend_from_slice(&map);
+        self.write_record(OP_METADATA, &record)?;
+        self.metadata_count += 1;
+        Ok(())
+    }
+
+    /// Write the summary section and footer, and return the underlying writer
+    pub fn finish(mut self) -> Result<W> {
+        self.write_record(OP_DATA_END, &0u32.to_le_bytes())?;
+
+        let summary_start = self.position;
+        let schemas: Vec<Vec<u8>> = self.schemas.iter().map(schema_record).collect();
+        for record in &schemas {
+            self.write_record(OP_SCHEMA, record)?;
+        }
+        let channels: Vec<Vec<u8>> = self.channels.iter().map(channel_record).collect();
+        for record in &channels {
+            self.write_record(OP_CHANNEL, record)?;
+        }
+        let statistics = self.statistics_record();
+        self.write_record(OP_STATISTICS, &statistics)?;
+
+        let mut footer = Vec::with_capacity(20);
+        footer.extend_from_slice(&summary_start.to_le_bytes());
+        footer.extend_from_slice(&0u64.to_le_bytes());
+        footer.extend_from_slice(&0u32.to_le_bytes());
+        self.write_record(OP_FOOTER, &footer)?;
+        self.write_bytes(MAGIC)?;
+        self.writer.flush()?;
+        Ok(self.writer)
+    }
+
+    fn statistics_record(&self) -> Vec<u8> {
+        let (start, end) = self.time_range.unwrap_or((0, 0));
+        let mut record = Vec::new();
+        record.extend_from_slice(&self.message_counts.values().sum::<u64>().to_le_bytes());
+        record.extend_from_slice(&(self.schemas.len() as u16).to_le_bytes());
+        record.extend_from_slice(&(self.channels.len() as u32).to_le_bytes());
+        record.extend_from_slice(&0u32.to_le_bytes()); // attachments
+        record.extend_from_slice(&self.metadata_count.to_le_bytes());
+        record.extend_from_slice(&0u32.to_le_bytes()); // chunks
+        record.extend_from_slice(&start.to_le_bytes());
+        record.extend_from_slice(&end.to_le_bytes());
+        record.extend_from_slice(&((self.message_counts.len() * 10) as u32).to_le_bytes());
+        for (channel, count) in &self.message_counts {
+            record.extend_from_slice(&channel.to_le_bytes());
+            record.extend_from_slice(&count.to_le_bytes());
+        }
+        record
+    }
+
+    fn write_record(&mut self, opcode: u8, content: &[u8]) -> Result<()> {
+        self.write_bytes(&[opcode])?;
+        self.write_bytes(&(content.len() as u64).to_le_bytes())?;
+        self.write_bytes(content)
+    }
+
+    fn write_bytes(&mut self, bytes: &[u8]) -> Result<()> {
+        self.writer.write_all(bytes)?;
+        self.position += bytes.len() as u64;
+        Ok(())
+    }
+}
+
+fn put_string(buffer: &mut Vec<u8>, value: &str) {
+    buffer.extend_from_slice(&(value.len() as u32).to_le_bytes());
+    buffer.extend_from_slice(value.as_bytes());
+}
+
+fn schema_record(schema: &Schema) -> Vec<u8> {
+    let mut record = Vec::new();
+    record.extend_from_slice(&schema.id.to_le_bytes());
+    put_string(&mut record, &schema.name);
+    put_string(&mut record, &schema.encoding);
+    record.extend_from_slice(&(schema.data.len() as u32).to_le_bytes());
+    record.extend_from_slice(&schema.data);
+    record
+}
+
+fn channel_record(channel: &Channel) -> Vec<u8> {
+    let mut record = Vec::new();
+    record.extend_from_slice(&channel.id.to_le_bytes());
+    record.extend_from_slice(&channel.schema_id.to_le_bytes());
+    put_string(&mut record, &channel.topic);
+    put_string(&mut record, &channel.message_encoding);
+    record.extend_from_slice(&0u32.to_le_bytes()); // empty metadata map
+    record
+}
+
+/// MCAP writer with the standard `/state`, `/controls`, and `/events` channels
+pub struct McapRunLogger<W: Write> {
+    mcap: McapWriter<W>,
+    state: ChannelId,
+    controls: ChannelId,
+    events: ChannelId,
+}
+
+impl McapRunLogger<BufWriter<File>> {
+    /// Create a run log at `path`
+    pub fn create<P: AsRef<Path>>(path: P) -> Result<Self> {
+        Self::new(McapWriter::create(path)?)
+    }
+}
+
+impl<W: Write> McapRunLogger<W> {
+    /// Register the run channels on an MCAP writer
+    pub fn new(mut mcap: McapWriter<W>) -> Result<Self> {
+        let state_schema =
+            mcap.add_schema("PointMassState", "jsonschema", STATE_SCHEMA.as_bytes())?;
+        let controls_schema =
+            mcap.add_schema("Controls", "jsonschema", CONTROLS_SCHEMA.as_bytes())?;
+        let event_schema = mcap.add_schema("Event", "jsonschema", EVENT_SCHEMA.as_bytes())?;
+        Ok(Self {
+            state: mcap.add_channel(state_schema, "/state", "json")?,
+            controls: mcap.add_channel(controls_schema, "/controls", "json")?,
+            events: mcap.add_channel(event_schema, "/events", "json")?,
+            mcap,
+        })
+    }
+
+    /// Log the model state at `time`
+    pub fn log_state(&mut self, time: f64, state: &PointMassState, in_track: bool) -> Result<()> {
+        let json = format!(
+            "{{\"time\":{},\"x\":{},\"y\":{},\"vx\":{},\"vy\":{},\"yaw\":{},\"in_track\":{}}}",
+            json_number(time),
+            json_number(state.x),
+            json_number(state.y),
+            json_number(state.vx),
+            json_number(state.vy),
+            json_number(state.yaw),
+            in_track
+        );
+        self.mcap.write_message(self.state, time, json.as_bytes())
+    }
+
+    /// Log the control inputs applied from `time` on
+    pub fn log_controls(&mut self, time: f64, ax: f64, yaw_rate: f64) -> Result<()> {
+        let json = format!(
+            "{{\"time\":{},\"ax\":{},\"yaw_rate\":{}}}",
+            json_number(time),
+            json_number(ax),
+            json_number(yaw_rate)
+        );
+        self.mcap
+            .write_message(self.controls, time, json.as_bytes())
+    }
+
+    /// Log a discrete event (e.g., kind "off_track")
+    pub fn log_event(&mut self, time: f64, kind: &str, message: &str) -> Result<()> {
+        let json = format!(
+            "{{\"time\":{},\"kind\":{},\"message\":{}}}",
+            json_number(time),
+            json_string(kind),
+            json_string(message)
+        );
+        self.mcap.write_message(self.events, time, json.as_bytes())
+    }
+
+    /// Record the provenance of the run as a "provenance" metadata record
+    pub fn log_provenance(&mut self, provenance: &Provenance) -> Result<()> {
+        self.mcap.write_metadata("provenance", &provenance.entries())
+    }
+
+    /// Finish the MCAP file
+    pub fn finish(self) -> Result<W> {
+        self.mcap.finish()
+    }
+}
+
+/// Write a recorded point mass run as an MCAP file
+///
+/// States are logged for every snapshot, controls as returned by `controls_at` for
+/// each snapshot time, and events for the run start and end and for every transition
+/// off and back onto the track. The provenance is stored as a metadata record.
+///
+/// # Arguments
+/// * `path` - Output file (e.g., "results/run.mcap")
+/// * `result` - Recorded run
+/// * `provenance` - Build and scenario details needed to reproduce the run
+/// * `controls_at` - Control inputs (acceleration, yaw rate) applied at a given time
+pub fn export_run_mcap<P: AsRef<Path>, F: Fn(f64) -> (f64, f64)>(
+    path: P,
+    result: &SimulationResult<PointMassState>,
+    provenance: &Provenance,
+    controls_at: F,
+) -> Result<()> {
+    let mut logger = McapRunLogger::create(path)?;
+    logger.log_provenance(provenance)?;
+    let mut previous_in_track: Option<bool> = None;
+
+    for snapshot in result.snapshots() {
+        if previous_in_track.is_none() {
+            logger.log_event(snapshot.time, "run_start", "simulation started")?;
+        }
+        match (previous_in_track, snapshot.in_track) {
+            (Some(true), false) | (None, false) => {
+                logger.log_event(snapshot.time, "off_track", "vehicle left the track")?;
+            }
+            (Some(false), true) => {
+                logger.log_event(
+                    snapshot.time,
+                    "back_on_track",
+                    "vehicle returned to the track",
+                )?;
+            }
+            _ => {}
+        }
+        previous_in_track = Some(snapshot.in_track);
+
+        let (ax, yaw_rate) = controls_at(snapshot.time);
+        logger.log_controls(snapshot.time, ax, yaw_rate)?;
+        logger.log_state(snapshot.time, &snapshot.state, snapshot.in_track)?;
+    }
+    if let Some(last) = result.last() {
+        logger.log_event(last.time, "run_end", "simulation finished")?;
+    }
+
+    logger.finish()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_run_mcap, McapWriter, MAGIC};
+    use crate::io::json_string;
+    use crate::models::point_mass::PointMassState;
+    use crate::provenance::Provenance;
+    use crate::simulation::result::SimulationResult;
+
+    /// Parse the top-level records of an MCAP file as (opcode, content)
+    fn records(bytes: &[u8]) -> Vec<(u8, &[u8])> {
+        assert!(bytes.starts_with(MAGIC) && bytes.ends_with(MAGIC));
+        let mut records = Vec::new();
+        let mut offset = MAGIC.len();
+        while offset < bytes.len() - MAGIC.len() {
+            let opcode = bytes[offset];
+            let mut len = [0u8; 8];
+            len.copy_from_slice(&bytes[offset + 1..offset + 9]);
+            let len = u64::from_le_bytes(len) as usize;
+            records.push((opcode, &bytes[offset + 9..offset + 9 + len]));
+            offset += 9 + len;
+        }
+        assert_eq!(offset, bytes.len() - MAGIC.len());
+        records
+    }
+
+    #[test]
+    fn test_mcap_writer_record_structure() {
+        let mut mcap = McapWriter::new(Vec::new()).expect("create writer");
+        let schema = mcap
+            .add_schema("Test", "jsonschema", b"{}")
+            .expect("add schema");
+        let channel = mcap
+            .add_channel(schema, "/test", "json")
+            .expect("add channel");
+        mcap.write_message(channel, 1.5, b"{\"a\":1}")
+            .expect("write message");
+        let err = mcap.add_channel(42, "/bad", "json").expect_err("unknown schema");
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+        let bytes = mcap.finish().expect("finish");
+
+        let records = records(&bytes);
+        let opcodes: Vec<u8> = records.iter().map(|(opcode, _)| *opcode).collect();
+        assert_eq!(
+            opcodes,
+            vec![0x01, 0x03, 0x04, 0x05, 0x0F, 0x03, 0x04, 0x0B, 0x02]
+        );
+
+        let message = records[3].1;
+        assert_eq!(&message[6..14], &1_500_000_000u64.to_le_bytes());
+        assert_eq!(&message[22..], b"{\"a\":1}");
+
+        // Footer points at the first summary record
+        let footer = records[8].1;
+        let mut summary_start = [0u8; 8];
+        summary_start.copy_from_slice(&footer[..8]);
+        let summary_start = u64::from_le_bytes(summary_start) as usize;
+        assert_eq!(bytes[summary_start], 0x03);
+    }
+
+    #[test]
+    fn test_export_run_mcap_logs_states_controls_and_events() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("runs").join("run.mcap");
+        let mut result = SimulationResult::new();
+        for (step, in_track) in [true, false, false, true].into_iter().enumerate() {
+            let state = PointMassState {
+                x: step as f64,
+                y: 0.0,
+                vx: 1.0,
+                vy: 0.0,
+                yaw: 0.0,
+            };
+            result.push(step as f64 * 0.1, state, in_track);
+        }
+
+        let provenance = Provenance::current().with_seed(3);
+        export_run_mcap(&path, &result, &provenance, |_| (2.0, 0.4)).expect("export mcap");
+
+        let bytes = std::fs::read(&path).expect("read mcap");
+        let metadata: Vec<&[u8]> = records(&bytes)
+            .into_iter()
+            .filter(|(opcode, _)| *opcode == 0x0C)
+            .map(|(_, content)| content)
+            .collect();
+        assert_eq!(metadata.len(), 1);
+        assert!(metadata[0].starts_with(b"\x0a\0\0\0provenance"));
+        assert!(metadata[0].windows(4).any(|window| window == b"seed"));
+
+        let messages: Vec<String> = records(&bytes)
+            .into_iter()
+            .filter(|(opcode, _)| *opcode == 0x05)
+            .map(|(_, content)| String::from_utf8(content[22..].to_vec()).expect("utf-8"))
+            .collect();
+        // 4 states, 4 controls, and run_start/off_track/back_on_track/run_end events
+        assert_eq!(messages.len(), 12);
+        assert!(messages
+            .iter()
+            .any(|m| m.contains("\"kind\":\"off_track\"") && m.contains("\"time\":0.1")));
+        assert!(messages
+            .iter()
+            .any(|m| m.contains("\"kind\":\"back_on_track\"")));
+        assert!(messages.contains(&"{\"time\":0,\"ax\":2,\"yaw_rate\":0.4}".to_string()));
+    }
+
+    #[test]
+    fn test_json_string_escapes() {
+        assert_eq!(json_string("a\"b\\c\n"), "\"a\\\"b\\\\c\\n\"");
+    }
+}
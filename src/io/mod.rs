@@ -0,0 +1,56 @@
+//! Persistence of simulation data (results, tracks, and configs) and exchange formats.
+
+pub mod compression;
+pub mod geojson;
+#[cfg(feature = "hdf5")]
+pub mod hdf5;
+#[cfg(feature = "serde")]
+pub mod json;
+pub mod kml;
+pub mod leaderboard;
+pub mod mcap;
+pub mod output_dir;
+#[cfg(feature = "serde")]
+pub mod result_file;
+
+/// Text with the HTML special characters escaped
+pub(crate) fn escape_html(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len());
+    for ch in value.chars() {
+        match ch {
+            '&' => escaped.push_str("&amp;"),
+            '<' => escaped.push_str("&lt;"),
+            '>' => escaped.push_str("&gt;"),
+            '"' => escaped.push_str("&quot;"),
+            '\'' => escaped.push_str("&#39;"),
+            _ => escaped.push(ch),
+        }
+    }
+    escaped
+}
+
+/// JSON number, with `null` for non-finite values
+pub(crate) fn json_number(value: f64) -> String {
+    if value.is_finite() {
+        format!("{}", value)
+    } else {
+        "null".to_string()
+    }
+}
+
+/// JSON string literal with the required escapes
+pub(crate) fn json_string(value: &str) -> String {
+    let mut escaped = String::with_capacity(value.len() + 2);
+    escaped.push('"');
+    for c in value.chars() {
+        match c {
+            '"' => escaped.push_str("\\\""),
+            '\\' => escaped.push_str("\\\\"),
+            '\n' => escaped.push_str("\\n"),
+            c if (c as u32) < 0x20 => escaped.push_str(&format!("\\u{:04x}", c as u32)),
+            c => escaped.push(c),
+        }
+    }
+    escaped.push('"');
+    escaped
+}
@@ -0,0 +1,193 @@
+//! Output directories that remember the artifacts written into them.
+//!
+//! Rendering a run writes final artifacts (state plots, the video, the HTML preview)
+//! and, on request, intermediates such as per-step frames. Registering each file with
+//! an `OutputDirectory` lets `clean` remove the intermediates of a run selectively,
+//! instead of deleting the whole directory or leaving the frames behind.
+
+use crate::error::Result;
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// Role of an artifact, deciding whether `OutputDirectory::clean` keeps it
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ArtifactKind {
+    /// Result of the run (plots, videos, reports), kept by `clean`
+    Final,
+    /// Byproduct only needed to produce the final artifacts (e.g., frames), removed by `clean`
+    Intermediate,
+}
+
+/// File written into an output directory
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Artifact {
+    pub path: PathBuf,
+    pub kind: ArtifactKind,
+}
+
+/// Directory receiving the artifacts of runs, with the list of files written into it
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct OutputDirectory {
+    root: PathBuf,
+    artifacts: Vec<Artifact>,
+    /// Whether `create` made the directory, so `clean_all` may remove it again
+    created: bool,
+}
+
+impl OutputDirectory {
+    /// Manage `root`; nothing is created until `create` is called
+    pub fn new<P: AsRef<Path>>(root: P) -> Self {
+        Self {
+            root: root.as_ref().to_path_buf(),
+            artifacts: Vec::new(),
+            created: false,
+        }
+    }
+
+    pub fn root(&self) -> &Path {
+        &self.root
+    }
+
+    /// Create the directory and its parents if missing
+    pub fn create(&mut self) -> io::Result<()> {
+        if !self.root.exists() {
+            fs::create_dir_all(&self.root)?;
+            self.created = true;
+        }
+        Ok(())
+    }
+
+    /// Path of the file `name` in the directory
+    pub fn join<P: AsRef<Path>>(&self, name: P) -> PathBuf {
+        self.root.join(name)
+    }
+
+    /// Record a file written for a run; registering the same path again updates its kind
+    pub fn register<P: Into<PathBuf>>(&mut self, path: P, kind: ArtifactKind) {
+        let path = path.into();
+        match self
+            .artifacts
+            .iter_mut()
+            .find(|artifact| artifact.path == path)
+        {
+            Some(artifact) => artifact.kind = kind,
+            None => self.artifacts.push(Artifact { path, kind }),
+        }
+    }
+
+    /// Registered artifacts, in the order they were first registered
+    pub fn artifacts(&self) -> &[Artifact] {
+        &self.artifacts
+    }
+
+    /// Paths of the registered artifacts of `kind`
+    pub fn paths(&self, kind: ArtifactKind) -> impl Iterator<Item = &Path> {
+        self.artifacts
+            .iter()
+            .filter(move |artifact| artifact.kind == kind)
+            .map(|artifact| artifact.path.as_path())
+    }
+
+    /// Delete the intermediate artifacts and keep the final ones
+    ///
+    /// Files that were already removed are skipped. Deleted artifacts are forgotten, so
+    /// calling `clean` again does nothing.
+    ///
+    /// # Returns
+    /// Number of deleted files, or `Error::Io` naming the file that could not be deleted
+    pub fn clean(&mut self) -> Result<usize> {
+        self.remove(|artifact| artifact.kind == ArtifactKind::Intermediate)
+    }
+
+    /// Delete every registered artifact
+    ///
+    /// The directory itself is removed too if `create` made it and it is empty afterwards.
+    ///
+    /// # Returns
+    /// Number of deleted files, or `Error::Io` if a file or the directory could not be
+    /// deleted
+    pub fn clean_all(&mut self) -> Result<usize> {
+        let removed = self.remove(|_| true)?;
+        if self.created && fs::read_dir(&self.root)?.next().is_none() {
+            fs::remove_dir(&self.root)?;
+            self.created = false;
+        }
+        Ok(removed)
+    }
+
+    fn remove<F: Fn(&Artifact) -> bool>(&mut self, selected: F) -> Result<usize> {
+        let mut removed = 0;
+        while let Some(index) = self.artifacts.iter().position(&selected) {
+            let path = &self.artifacts[index].path;
+            match fs::remove_file(path) {
+                Ok(()) => removed += 1,
+                Err(err) if err.kind() == io::ErrorKind::NotFound => {}
+                Err(err) => {
+                    let message = format!("failed to remove {}: {}", path.display(), err);
+                    return Err(io::Error::new(err.kind(), message).into());
+                }
+            }
+            self.artifacts.remove(index);
+        }
+        Ok(removed)
+    }
+}
+
+impl AsRef<Path> for OutputDirectory {
+    fn as_ref(&self) -> &Path {
+        &self.root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ArtifactKind, OutputDirectory};
+    use std::fs;
+
+    #[test]
+    fn test_clean_keeps_final_artifacts() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut outputs = OutputDirectory::new(temp_dir.path().join("run"));
+        outputs.create().expect("create output dir");
+
+        let video = outputs.join("open_loop.mp4");
+        let frame = outputs.join("step_001.svg");
+        fs::write(&video, "video").expect("write video");
+        fs::write(&frame, "frame").expect("write frame");
+        outputs.register(&video, ArtifactKind::Final);
+        outputs.register(&frame, ArtifactKind::Intermediate);
+        // Registered but never written, e.g. a failed render
+        outputs.register(outputs.join("step_002.svg"), ArtifactKind::Intermediate);
+
+        assert_eq!(outputs.clean().expect("clean"), 1);
+        assert!(video.exists());
+        assert!(!frame.exists());
+        assert_eq!(
+            outputs.paths(ArtifactKind::Final).collect::<Vec<_>>(),
+            [video.as_path()]
+        );
+        assert_eq!(outputs.paths(ArtifactKind::Intermediate).count(), 0);
+        assert_eq!(outputs.clean().expect("clean again"), 0);
+    }
+
+    #[test]
+    fn test_clean_all_removes_created_directory() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut outputs = OutputDirectory::new(temp_dir.path().join("nested").join("run"));
+        outputs.create().expect("create output dir");
+        let report = outputs.join("report.html");
+        fs::write(&report, "report").expect("write report");
+        outputs.register(&report, ArtifactKind::Final);
+
+        assert_eq!(outputs.clean_all().expect("clean all"), 1);
+        assert!(outputs.artifacts().is_empty());
+        assert!(!outputs.root().exists());
+
+        // Directories that existed before are left in place
+        let mut existing = OutputDirectory::new(temp_dir.path());
+        existing.create().expect("create output dir");
+        existing.clean_all().expect("clean all");
+        assert!(temp_dir.path().exists());
+    }
+}
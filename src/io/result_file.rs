@@ -0,0 +1,259 @@
+//! Versioned, self-describing container for archived simulation results.
+//!
+//! ```text
+//! {
+//!   "format": "lap_simulation.result",
+//!   "schema_version": 1,
+//!   "crate_version": "0.1.0",
+//!   "state_type": "lap_simulation::models::point_mass::PointMassState",
+//!   "provenance": { "git_commit": "...", "seed": null, "parameters": {...}, ... },
+//!   "result": { "snapshots": [...] }
+//! }
+//! ```
+//!
+//! Files are upgraded to `RESULT_SCHEMA_VERSION` on load, so results archived by older
+//! versions stay readable when snapshot fields change. Plain `SimulationResult` JSON
+//! written by `io::json::save_json` is read as schema version 0. The `provenance`
+//! field is optional, so files without it remain valid version 1 containers.
+
+use super::compression;
+use crate::error::{Error, Result};
+use crate::provenance::Provenance;
+use crate::simulation::result::SimulationResult;
+use serde::de::DeserializeOwned;
+use serde::Serialize;
+use serde_json::{Map, Value};
+use std::io;
+use std::path::Path;
+
+/// Value of the `format` field
+pub const RESULT_FORMAT: &str = "lap_simulation.result";
+
+/// Schema version written by this crate version
+pub const RESULT_SCHEMA_VERSION: u64 = 1;
+
+/// Upgrade of a container from schema version `n` (its index) to `n + 1`
+type Migration = fn(Value) -> Result<Value>;
+
+/// Migrations in version order; add one here whenever the schema version is bumped
+const MIGRATIONS: [Migration; RESULT_SCHEMA_VERSION as usize] = [migrate_v0_to_v1];
+
+/// Version 0 is a bare `SimulationResult`, wrap it in the container
+fn migrate_v0_to_v1(mut result: Value) -> Result<Value> {
+    let mut container = Map::new();
+    container.insert("format".to_string(), Value::from(RESULT_FORMAT));
+    container.insert("schema_version".to_string(), Value::from(1));
+    // `save_json` puts the provenance next to the snapshots
+    let provenance = result.as_object_mut().and_then(|fields| fields.remove("provenance"));
+    if let Some(provenance) = provenance {
+        container.insert("provenance".to_string(), provenance);
+    }
+    container.insert("result".to_string(), result);
+    Ok(Value::Object(container))
+}
+
+/// Serialize a result into the versioned container
+///
+/// # Arguments
+/// * `result` - Result to archive
+/// * `provenance` - Build and scenario details needed to reproduce the run
+pub fn to_versioned_json<S: Serialize>(
+    result: &SimulationResult<S>,
+    provenance: &Provenance,
+) -> Result<String> {
+    let container = serde_json::json!({
+        "format": RESULT_FORMAT,
+        "schema_version": RESULT_SCHEMA_VERSION,
+        "crate_version": env!("CARGO_PKG_VERSION"),
+        "state_type": std::any::type_name::<S>(),
+        "provenance": provenance,
+        "result": result,
+    });
+    let mut text = serde_json::to_string_pretty(&container).map_err(io::Error::from)?;
+    text.push('\n');
+    Ok(text)
+}
+
+/// Parse a versioned container, migrating older schema versions
+pub fn from_versioned_json<S: DeserializeOwned>(text: &str) -> Result<SimulationResult<S>> {
+    let mut value: Value = serde_json::from_str(text).map_err(parse_error)?;
+    let mut version = schema_version(&value)?;
+    if version > RESULT_SCHEMA_VERSION {
+        return Err(Error::invalid_config(format!(
+            "result schema version {} is newer than the supported version {}",
+            version, RESULT_SCHEMA_VERSION
+        )));
+    }
+    while version < RESULT_SCHEMA_VERSION {
+        value = MIGRATIONS[version as usize](value).map_err(|err| {
+            Error::invalid_config(format!("failed to migrate schema version {}: {}", version, err))
+        })?;
+        version += 1;
+    }
+
+    let result = value
+        .get_mut("result")
+        .map(Value::take)
+        .ok_or_else(|| Error::invalid_config("result container has no 'result' field"))?;
+    serde_json::from_value(result).map_err(parse_error)
+}
+
+/// Provenance stored in a versioned container, if any
+pub fn provenance_from_json(text: &str) -> Result<Option<Provenance>> {
+    let mut value: Value = serde_json::from_str(text).map_err(parse_error)?;
+    match value.get_mut("provenance").map(Value::take) {
+        Some(provenance) if !provenance.is_null() => {
+            Ok(Some(serde_json::from_value(provenance).map_err(parse_error)?))
+        }
+        _ => Ok(None),
+    }
+}
+
+/// Schema version of a parsed file, 0 for a bare `SimulationResult`
+fn schema_version(value: &Value) -> Result<u64> {
+    let Some(format) = value.get("format") else {
+        return if value.get("snapshots").is_some() {
+            Ok(0)
+        } else {
+            Err(Error::invalid_config("not a simulation result file"))
+        };
+    };
+    if format != RESULT_FORMAT {
+        return Err(Error::invalid_config(format!("unexpected result format {}", format)));
+    }
+    value
+        .get("schema_version")
+        .and_then(Value::as_u64)
+        .ok_or_else(|| Error::invalid_config("result container has no valid 'schema_version'"))
+}
+
+fn parse_error(err: serde_json::Error) -> Error {
+    Error::invalid_config(err.to_string())
+}
+
+/// Prefix the message of a parse error with the file it came from
+fn in_file(path: &Path, err: Error) -> Error {
+    match err {
+        Error::InvalidConfig(message) => {
+            Error::InvalidConfig(format!("{}: {}", path.display(), message))
+        }
+        err => err,
+    }
+}
+
+/// Save a result in the versioned container, creating parent directories
+///
+/// Paths ending in `.gz` or `.zst` are compressed (see `io::compression`).
+///
+/// # Arguments
+/// * `result` - Result to archive
+/// * `provenance` - Build and scenario details needed to reproduce the run
+/// * `path` - Destination file (e.g., "results/run.result.json")
+pub fn save_result<S: Serialize, P: AsRef<Path>>(
+    result: &SimulationResult<S>,
+    provenance: &Provenance,
+    path: P,
+) -> Result<()> {
+    compression::write_file(path, to_versioned_json(result, provenance)?.as_bytes())
+}
+
+/// Load a result saved by `save_result` (or an older version of it)
+pub fn load_result<S: DeserializeOwned, P: AsRef<Path>>(path: P) -> Result<SimulationResult<S>> {
+    let path = path.as_ref();
+    let text = compression::read_to_string(path)?;
+    from_versioned_json(&text).map_err(|err| in_file(path, err))
+}
+
+/// Load the provenance of a result saved by `save_result`
+pub fn load_provenance<P: AsRef<Path>>(path: P) -> Result<Option<Provenance>> {
+    let path = path.as_ref();
+    let text = compression::read_to_string(path)?;
+    provenance_from_json(&text).map_err(|err| in_file(path, err))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        from_versioned_json, load_provenance, load_result, save_result, to_versioned_json,
+        RESULT_SCHEMA_VERSION,
+    };
+    use crate::io::json::save_json;
+    use crate::models::point_mass::PointMassState;
+    use crate::provenance::Provenance;
+    use crate::simulation::result::SimulationResult;
+
+    fn sample_result() -> SimulationResult<PointMassState> {
+        let mut result = SimulationResult::new();
+        result.push(
+            0.0,
+            PointMassState {
+                x: 1.0,
+                y: 2.0,
+                vx: 3.0,
+                vy: 0.0,
+                yaw: 0.5,
+            },
+            true,
+        );
+        result
+    }
+
+    fn assert_same(a: &SimulationResult<PointMassState>, b: &SimulationResult<PointMassState>) {
+        assert_eq!(
+            serde_json::to_value(a).expect("json"),
+            serde_json::to_value(b).expect("json")
+        );
+    }
+
+    #[test]
+    fn test_versioned_round_trip() {
+        let result = sample_result();
+        let text = to_versioned_json(&result, &Provenance::current()).expect("serialize");
+        let value: serde_json::Value = serde_json::from_str(&text).expect("json");
+        assert_eq!(value["schema_version"], RESULT_SCHEMA_VERSION);
+        assert!(value["state_type"]
+            .as_str()
+            .expect("state type")
+            .ends_with("PointMassState"));
+
+        let loaded: SimulationResult<PointMassState> =
+            from_versioned_json(&text).expect("deserialize");
+        assert_same(&loaded, &result);
+    }
+
+    #[test]
+    fn test_load_migrates_plain_result_json() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let legacy = temp_dir.path().join("legacy.json");
+        let result = sample_result();
+        save_json(&result, &legacy).expect("save legacy json");
+
+        let loaded: SimulationResult<PointMassState> =
+            load_result(&legacy).expect("load legacy result");
+        assert_same(&loaded, &result);
+
+        let current = temp_dir.path().join("runs").join("run.result.json");
+        let provenance = Provenance::current().with_seed(42);
+        save_result(&result, &provenance, &current).expect("save result");
+        let loaded: SimulationResult<PointMassState> = load_result(&current).expect("load result");
+        assert_same(&loaded, &result);
+        let loaded_provenance = load_provenance(&current).expect("load provenance");
+        assert_eq!(loaded_provenance, Some(provenance));
+        let legacy_provenance = load_provenance(&legacy).expect("legacy provenance");
+        assert_eq!(legacy_provenance, Some(Provenance::current()));
+    }
+
+    #[test]
+    fn test_rejects_unknown_files() {
+        let newer = r#"{"format": "lap_simulation.result", "schema_version": 99, "result": {}}"#;
+        let err = from_versioned_json::<PointMassState>(newer).expect_err("newer version");
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(err.to_string().contains("newer than the supported version"));
+
+        let err = from_versioned_json::<PointMassState>(r#"{"format": "other"}"#)
+            .expect_err("other format");
+        assert!(err.to_string().contains("unexpected result format"));
+
+        assert!(from_versioned_json::<PointMassState>("{}").is_err());
+    }
+}
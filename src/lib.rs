@@ -1,4 +1,25 @@
+pub mod math;
 pub mod models;
+pub mod controllers;
+pub mod sensors;
+pub mod estimation;
 pub mod tracks;
+#[cfg(feature = "plotting")]
 pub mod plotting;
-pub mod simulation;
\ No newline at end of file
+pub mod simulation;
+pub mod config;
+pub mod error;
+pub mod diagnostics;
+pub mod provenance;
+pub mod scenarios;
+pub mod io;
+pub mod telemetry;
+pub mod ros2;
+pub mod server;
+pub mod testing;
+#[cfg(feature = "wasm")]
+pub mod wasm;
+#[cfg(feature = "units")]
+pub mod units;
+
+pub use error::{Error, Result};
@@ -0,0 +1,138 @@
+//! Planar angle normalized to a single turn.
+
+use std::f64::consts::{PI, TAU};
+use std::fmt;
+use std::ops::{Add, Neg, Sub};
+
+/// Angle in radians, always normalized to [-π, π)
+///
+/// Sums and differences wrap around, so code comparing or interpolating headings
+/// (controllers, track yaw, ghost alignment) does not need its own wrapping.
+#[derive(Debug, Clone, Copy, PartialEq, PartialOrd, Default)]
+pub struct Angle(f64);
+
+impl Angle {
+    pub const ZERO: Angle = Angle(0.0);
+
+    /// Angle of `radians`, wrapped to [-π, π)
+    pub fn from_radians(radians: f64) -> Self {
+        Angle(Self::wrap(radians))
+    }
+
+    /// Angle of `degrees`, wrapped to [-180°, 180°)
+    pub fn from_degrees(degrees: f64) -> Self {
+        Self::from_radians(degrees.to_radians())
+    }
+
+    /// Direction of the vector (dx, dy), measured counter-clockwise from the x axis
+    pub fn from_direction(dx: f64, dy: f64) -> Self {
+        Self::from_radians(dy.atan2(dx))
+    }
+
+    /// Wrap `radians` to [-π, π)
+    pub fn wrap(radians: f64) -> f64 {
+        (radians + PI).rem_euclid(TAU) - PI
+    }
+
+    /// Value in radians, in [-π, π)
+    pub fn radians(self) -> f64 {
+        self.0
+    }
+
+    /// Value in degrees, in [-180, 180)
+    pub fn to_degrees(self) -> f64 {
+        self.0.to_degrees()
+    }
+
+    /// Signed shortest rotation from `other` to `self`
+    ///
+    /// # Returns
+    /// Positive for counter-clockwise rotations, in [-π, π)
+    pub fn diff(self, other: Angle) -> Angle {
+        Angle::from_radians(self.0 - other.0)
+    }
+
+    /// Interpolate from `self` towards `to` along the shortest arc
+    ///
+    /// # Arguments
+    /// * `to` - Angle reached at `alpha` = 1
+    /// * `alpha` - Interpolation factor, usually in [0, 1]
+    pub fn lerp(self, to: Angle, alpha: f64) -> Angle {
+        Angle::from_radians(self.0 + to.diff(self).0 * alpha)
+    }
+
+    pub fn sin(self) -> f64 {
+        self.0.sin()
+    }
+
+    pub fn cos(self) -> f64 {
+        self.0.cos()
+    }
+}
+
+impl Add for Angle {
+    type Output = Angle;
+
+    fn add(self, rhs: Angle) -> Angle {
+        Angle::from_radians(self.0 + rhs.0)
+    }
+}
+
+impl Sub for Angle {
+    type Output = Angle;
+
+    fn sub(self, rhs: Angle) -> Angle {
+        self.diff(rhs)
+    }
+}
+
+impl Neg for Angle {
+    type Output = Angle;
+
+    fn neg(self) -> Angle {
+        Angle::from_radians(-self.0)
+    }
+}
+
+impl From<Angle> for f64 {
+    fn from(angle: Angle) -> f64 {
+        angle.0
+    }
+}
+
+impl fmt::Display for Angle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{:.2}°", self.to_degrees())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Angle;
+    use std::f64::consts::{FRAC_PI_2, PI};
+
+    #[test]
+    fn test_angle_normalizes_on_construction() {
+        assert!((Angle::from_radians(3.0 * PI).radians() + PI).abs() < 1e-12);
+        assert!((Angle::from_radians(-FRAC_PI_2 - 2.0 * PI).radians() + FRAC_PI_2).abs() < 1e-12);
+        assert!((Angle::from_degrees(270.0).to_degrees() + 90.0).abs() < 1e-9);
+        assert_eq!(Angle::from_radians(PI).radians(), -PI);
+        assert!((Angle::from_direction(0.0, 1.0).radians() - FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(Angle::wrap(0.5), 0.5);
+    }
+
+    #[test]
+    fn test_angle_diff_takes_shortest_arc() {
+        let a = Angle::from_degrees(170.0);
+        let b = Angle::from_degrees(-170.0);
+        assert!((b.diff(a).to_degrees() - 20.0).abs() < 1e-9);
+        assert!((a.diff(b).to_degrees() + 20.0).abs() < 1e-9);
+        assert!(((a - b).to_degrees() + 20.0).abs() < 1e-9);
+        assert!(((a + Angle::from_degrees(20.0)).to_degrees() + 170.0).abs() < 1e-9);
+        assert!(((-a).to_degrees() + 170.0).abs() < 1e-9);
+
+        let halfway = a.lerp(b, 0.5);
+        assert!((halfway.to_degrees() + 180.0).abs() < 1e-9);
+        assert_eq!(format!("{}", Angle::from_degrees(45.0)), "45.00°");
+    }
+}
@@ -0,0 +1,10 @@
+//! Small geometry types shared by models, tracks, controllers, and plotting, and the
+//! seeded random number generator of stochastic runs.
+
+pub mod angle;
+pub mod random;
+pub mod vec2;
+
+pub use angle::Angle;
+pub use random::SplitMix64;
+pub use vec2::Vec2;
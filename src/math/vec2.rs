@@ -0,0 +1,175 @@
+//! Planar vector used for track points, boundaries, and vehicle geometry.
+
+use std::fmt;
+use std::ops::{Add, AddAssign, Mul, Neg, Sub, SubAssign};
+
+/// 2D vector (or point) in world frame meters
+///
+/// Converts from and into `(x, y)` tuples, so coordinates can be passed to plotting
+/// backends or written out without unpacking the fields by hand.
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "(f64, f64)", into = "(f64, f64)"))]
+pub struct Vec2 {
+    pub x: f64,
+    pub y: f64,
+}
+
+impl Vec2 {
+    pub const ZERO: Vec2 = Vec2 { x: 0.0, y: 0.0 };
+
+    pub const fn new(x: f64, y: f64) -> Self {
+        Self { x, y }
+    }
+
+    /// Unit vector pointing along `yaw` (radians, counter-clockwise from the x axis)
+    pub fn from_angle(yaw: f64) -> Self {
+        let (sin, cos) = yaw.sin_cos();
+        Self::new(cos, sin)
+    }
+
+    pub fn dot(self, other: Vec2) -> f64 {
+        self.x * other.x + self.y * other.y
+    }
+
+    /// z component of the 3D cross product; positive when `other` lies to the left
+    pub fn cross(self, other: Vec2) -> f64 {
+        self.x * other.y - self.y * other.x
+    }
+
+    /// Euclidean length
+    pub fn norm(self) -> f64 {
+        self.x.hypot(self.y)
+    }
+
+    pub fn norm_squared(self) -> f64 {
+        self.dot(self)
+    }
+
+    pub fn distance(self, other: Vec2) -> f64 {
+        (other - self).norm()
+    }
+
+    /// Vector of unit length in the same direction, or `None` for the zero vector
+    pub fn normalized(self) -> Option<Vec2> {
+        let norm = self.norm();
+        (norm > 0.0).then(|| Vec2::new(self.x / norm, self.y / norm))
+    }
+
+    /// Rotate counter-clockwise by `angle` radians
+    pub fn rotate(self, angle: f64) -> Vec2 {
+        let (sin, cos) = angle.sin_cos();
+        Vec2::new(self.x * cos - self.y * sin, self.x * sin + self.y * cos)
+    }
+
+    /// Vector rotated by 90° counter-clockwise (the left normal)
+    pub fn perp(self) -> Vec2 {
+        Vec2::new(-self.y, self.x)
+    }
+
+    /// Direction of the vector in radians, in (-π, π]
+    pub fn angle(self) -> f64 {
+        self.y.atan2(self.x)
+    }
+
+    /// Point at `t` on the segment from `self` (t = 0) to `other` (t = 1)
+    pub fn lerp(self, other: Vec2, t: f64) -> Vec2 {
+        self + (other - self) * t
+    }
+}
+
+impl Add for Vec2 {
+    type Output = Vec2;
+
+    fn add(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x + rhs.x, self.y + rhs.y)
+    }
+}
+
+impl AddAssign for Vec2 {
+    fn add_assign(&mut self, rhs: Vec2) {
+        *self = *self + rhs;
+    }
+}
+
+impl Sub for Vec2 {
+    type Output = Vec2;
+
+    fn sub(self, rhs: Vec2) -> Vec2 {
+        Vec2::new(self.x - rhs.x, self.y - rhs.y)
+    }
+}
+
+impl SubAssign for Vec2 {
+    fn sub_assign(&mut self, rhs: Vec2) {
+        *self = *self - rhs;
+    }
+}
+
+impl Mul<f64> for Vec2 {
+    type Output = Vec2;
+
+    fn mul(self, rhs: f64) -> Vec2 {
+        Vec2::new(self.x * rhs, self.y * rhs)
+    }
+}
+
+impl Neg for Vec2 {
+    type Output = Vec2;
+
+    fn neg(self) -> Vec2 {
+        Vec2::new(-self.x, -self.y)
+    }
+}
+
+impl From<(f64, f64)> for Vec2 {
+    fn from((x, y): (f64, f64)) -> Self {
+        Vec2::new(x, y)
+    }
+}
+
+impl From<Vec2> for (f64, f64) {
+    fn from(v: Vec2) -> Self {
+        (v.x, v.y)
+    }
+}
+
+impl fmt::Display for Vec2 {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "({:.2}, {:.2})", self.x, self.y)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Vec2;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_vec2_products_and_norm() {
+        let a = Vec2::new(3.0, 4.0);
+        let b = Vec2::new(1.0, 0.0);
+        assert_eq!(a.norm(), 5.0);
+        assert_eq!(a.dot(b), 3.0);
+        assert_eq!(b.cross(a), 4.0);
+        assert_eq!(a.cross(b), -4.0);
+        assert_eq!(a - b, Vec2::new(2.0, 4.0));
+        assert_eq!(-a + b * 2.0, Vec2::new(-1.0, -4.0));
+        assert_eq!(Vec2::ZERO.distance(a), 5.0);
+        assert_eq!(Vec2::ZERO.normalized(), None);
+        assert_eq!(a.normalized(), Some(Vec2::new(0.6, 0.8)));
+        assert_eq!(a.lerp(b, 0.5), Vec2::new(2.0, 2.0));
+    }
+
+    #[test]
+    fn test_vec2_rotation() {
+        let v = Vec2::new(1.0, 0.0).rotate(FRAC_PI_2);
+        assert!(v.x.abs() < 1e-12 && (v.y - 1.0).abs() < 1e-12);
+        assert_eq!(Vec2::new(1.0, 2.0).perp(), Vec2::new(-2.0, 1.0));
+        assert!((Vec2::from_angle(FRAC_PI_2).angle() - FRAC_PI_2).abs() < 1e-12);
+
+        let tuple: (f64, f64) = Vec2::new(1.5, -2.0).into();
+        assert_eq!(tuple, (1.5, -2.0));
+        assert_eq!(Vec2::from((1.5, -2.0)), Vec2::new(1.5, -2.0));
+    }
+}
@@ -1,3 +1,5 @@
+use super::limits::Saturation;
+
 /// Trait for simulation models with standard lifecycle methods
 pub trait Model {
     /// State type returned by get_state
@@ -27,6 +29,100 @@ pub trait Model {
     /// Tuple of (x, y, yaw) where x and y are coordinates in meters and yaw is in radians
     fn get_position(&self) -> (f64, f64, f64);
     
+    /// Get the current velocity of the model
+    /// 
+    /// # Returns
+    /// Tuple of (vx, vy) in m/s in the body frame (vx forward, vy to the left)
+    fn get_velocity(&self) -> (f64, f64);
+    
+    /// Get the current speed of the model
+    /// 
+    /// # Returns
+    /// Magnitude of the velocity in m/s
+    fn get_speed(&self) -> f64 {
+        let (vx, vy) = self.get_velocity();
+        vx.hypot(vy)
+    }
+    
     /// Get the current state of the model
     fn get_state(&self) -> &Self::State;
-}
\ No newline at end of file
+
+    /// Set the control inputs from a yaw rate command, as produced by the controllers
+    ///
+    /// # Arguments
+    /// * `ax` - Body frame longitudinal acceleration in m/s²
+    /// * `yaw_rate` - Commanded yaw rate in radians/s, positive to the left
+    ///
+    /// Models with another steering input convert the yaw rate into it (e.g., the
+    /// dynamic bicycle into a steering angle), so any model can be driven by the
+    /// simulations and controllers.
+    fn set_yaw_rate_controls(&mut self, ax: f64, yaw_rate: f64);
+
+    /// Control limits hit by the inputs of the last step
+    ///
+    /// Models without limits never saturate (the default).
+    fn saturation(&self) -> Saturation {
+        Saturation::default()
+    }
+
+    /// Largest combined acceleration in m/s² the tires can transmit (friction circle)
+    ///
+    /// `None` for models without a grip limit (the default).
+    fn friction_limit(&self) -> Option<f64> {
+        None
+    }
+}
+
+/// Boxed models are models too, so `Box<dyn Model<State = S>>` can be used wherever a
+/// `Model` is expected (e.g., to pick the model at runtime)
+impl<M: Model + ?Sized> Model for Box<M> {
+    type State = M::State;
+
+    fn init(&mut self) {
+        (**self).init()
+    }
+
+    fn step(&mut self, dt: f64) {
+        (**self).step(dt)
+    }
+
+    fn reset(&mut self) {
+        (**self).reset()
+    }
+
+    fn set_position(&mut self, x: f64, y: f64, yaw: f64) {
+        (**self).set_position(x, y, yaw)
+    }
+
+    fn get_size(&self) -> (f64, f64) {
+        (**self).get_size()
+    }
+
+    fn get_position(&self) -> (f64, f64, f64) {
+        (**self).get_position()
+    }
+
+    fn get_velocity(&self) -> (f64, f64) {
+        (**self).get_velocity()
+    }
+
+    fn get_speed(&self) -> f64 {
+        (**self).get_speed()
+    }
+
+    fn get_state(&self) -> &Self::State {
+        (**self).get_state()
+    }
+
+    fn set_yaw_rate_controls(&mut self, ax: f64, yaw_rate: f64) {
+        (**self).set_yaw_rate_controls(ax, yaw_rate)
+    }
+
+    fn saturation(&self) -> Saturation {
+        (**self).saturation()
+    }
+
+    fn friction_limit(&self) -> Option<f64> {
+        (**self).friction_limit()
+    }
+}
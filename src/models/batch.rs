@@ -0,0 +1,196 @@
+//! Batched point mass propagation for Monte Carlo and particle filters.
+//!
+//! `PointMassBatch` stores N point masses as separate arrays per state component
+//! (structure of arrays), so one `step` runs tight loops over contiguous memory that
+//! the compiler can vectorize, instead of N calls through the `Model` trait.
+
+use super::point_mass::PointMassState;
+use super::scalar::Float;
+use crate::tracks::base_track::Track;
+
+/// N point masses advanced together, each with its own controls
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PointMassBatch<T = f64> {
+    x: Vec<T>,
+    y: Vec<T>,
+    vx: Vec<T>,
+    yaw: Vec<T>,
+    ax: Vec<T>,
+    yaw_rate: Vec<T>,
+}
+
+impl<T: Float> PointMassBatch<T> {
+    /// Create `count` point masses with the same initial state and zero controls
+    pub fn new(count: usize, initial_state: &PointMassState<T>) -> Self {
+        Self {
+            x: vec![initial_state.x; count],
+            y: vec![initial_state.y; count],
+            vx: vec![initial_state.vx; count],
+            yaw: vec![initial_state.yaw; count],
+            ax: vec![T::ZERO; count],
+            yaw_rate: vec![T::ZERO; count],
+        }
+    }
+
+    /// Create one point mass per state with zero controls
+    pub fn from_states(states: &[PointMassState<T>]) -> Self {
+        let mut batch = Self::new(0, &zero_state());
+        for state in states {
+            batch.push(state);
+        }
+        batch
+    }
+
+    /// Add a point mass with zero controls
+    pub fn push(&mut self, state: &PointMassState<T>) {
+        self.x.push(state.x);
+        self.y.push(state.y);
+        self.vx.push(state.vx);
+        self.yaw.push(state.yaw);
+        self.ax.push(T::ZERO);
+        self.yaw_rate.push(T::ZERO);
+    }
+
+    pub fn len(&self) -> usize {
+        self.x.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.x.is_empty()
+    }
+
+    /// Set the acceleration inputs of the point mass at `index`
+    pub fn set_controls(&mut self, index: usize, ax: f64, yaw_rate: f64) {
+        self.ax[index] = T::from_f64(ax);
+        self.yaw_rate[index] = T::from_f64(yaw_rate);
+    }
+
+    /// Set the same acceleration inputs for every point mass
+    pub fn set_all_controls(&mut self, ax: f64, yaw_rate: f64) {
+        self.ax.fill(T::from_f64(ax));
+        self.yaw_rate.fill(T::from_f64(yaw_rate));
+    }
+
+    /// Advance every point mass by `dt` seconds
+    ///
+    /// Uses the same integration as `PointMass::step`: velocity and yaw first, then
+    /// the position with the updated values.
+    pub fn step(&mut self, dt: f64) {
+        let dt = T::from_f64(dt);
+        for ((vx, yaw), (&ax, &yaw_rate)) in self
+            .vx
+            .iter_mut()
+            .zip(self.yaw.iter_mut())
+            .zip(self.ax.iter().zip(&self.yaw_rate))
+        {
+            *vx += ax * dt;
+            *yaw += yaw_rate * dt;
+        }
+        for ((x, y), (&vx, &yaw)) in self
+            .x
+            .iter_mut()
+            .zip(self.y.iter_mut())
+            .zip(self.vx.iter().zip(&self.yaw))
+        {
+            *x += vx * yaw.cos() * dt;
+            *y += vx * yaw.sin() * dt;
+        }
+    }
+
+    /// State of the point mass at `index`
+    pub fn state(&self, index: usize) -> PointMassState<T> {
+        PointMassState {
+            x: self.x[index],
+            y: self.y[index],
+            vx: self.vx[index],
+            vy: T::ZERO,
+            yaw: self.yaw[index],
+        }
+    }
+
+    /// Iterate over the (x, y) positions in meters
+    pub fn positions(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.x
+            .iter()
+            .zip(&self.y)
+            .map(|(x, y)| (x.to_f64(), y.to_f64()))
+    }
+
+    /// Whether each point mass lies inside the track boundaries
+    pub fn in_track(&self, track: &dyn Track) -> Vec<bool> {
+        self.positions()
+            .map(|(x, y)| track.is_in_track(x, y))
+            .collect()
+    }
+}
+
+fn zero_state<T: Float>() -> PointMassState<T> {
+    PointMassState {
+        x: T::ZERO,
+        y: T::ZERO,
+        vx: T::ZERO,
+        vy: T::ZERO,
+        yaw: T::ZERO,
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PointMassBatch;
+    use crate::models::base_model::Model;
+    use crate::models::point_mass::{PointMass, PointMassState};
+    use crate::tracks::circle::CircleTrack;
+
+    #[test]
+    fn test_batch_matches_point_mass() {
+        let mut models: Vec<PointMass> = (0..8)
+            .map(|i| PointMass::with_initial_state(i as f64, 0.0, 1.0, 0.1 * i as f64))
+            .collect();
+        let states: Vec<_> = models
+            .iter()
+            .map(|model| model.get_state().clone())
+            .collect();
+        let mut batch = PointMassBatch::from_states(&states);
+        for (i, model) in models.iter_mut().enumerate() {
+            let (ax, yaw_rate) = (0.5 * i as f64, 0.3 - 0.1 * i as f64);
+            model.set_controls(ax, yaw_rate);
+            batch.set_controls(i, ax, yaw_rate);
+        }
+
+        for _ in 0..50 {
+            batch.step(0.05);
+            models.iter_mut().for_each(|model| model.step(0.05));
+        }
+
+        assert_eq!(batch.len(), 8);
+        for (i, model) in models.iter().enumerate() {
+            let expected = model.get_state();
+            let state = batch.state(i);
+            assert!((state.x - expected.x).abs() < 1e-12, "particle {i}");
+            assert!((state.y - expected.y).abs() < 1e-12, "particle {i}");
+            assert!((state.vx - expected.vx).abs() < 1e-12, "particle {i}");
+            assert!((state.yaw - expected.yaw).abs() < 1e-12, "particle {i}");
+        }
+    }
+
+    #[test]
+    fn test_batch_in_track() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let start = PointMassState {
+            x: 50.0f32,
+            y: 0.0,
+            vx: 0.0,
+            vy: 0.0,
+            yaw: 0.0,
+        };
+        let mut batch = PointMassBatch::new(3, &start);
+        batch.set_controls(1, 20.0, 0.0);
+        batch.set_controls(2, -20.0, 0.0);
+        batch.step(1.0);
+        assert_eq!(batch.in_track(&track), vec![true, false, false]);
+
+        batch.set_all_controls(0.0, 0.0);
+        batch.step(1.0);
+        assert_eq!(batch.positions().nth(1), Some((90.0, 0.0)));
+    }
+}
@@ -0,0 +1,643 @@
+use super::base_model::Model;
+use super::integrators::Integrator;
+use super::limits::{ControlLimits, Saturation};
+use super::point_mass::{PointMassState, GRAVITY};
+use std::fmt;
+
+/// Speed in m/s below which the slip angles are ill-defined and the kinematic bicycle
+/// relations are used instead of the tire forces
+const KINEMATIC_SPEED: f64 = 1.0;
+
+/// Longest integration step in seconds; longer steps are split so the stiff lateral
+/// dynamics stay stable with any of the integrators
+const MAX_SUBSTEP: f64 = 0.005;
+
+/// State of a dynamic bicycle model
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynamicBicycleState {
+    pub x: f64,          // World frame x position
+    pub y: f64,          // World frame y position
+    pub vx: f64,         // Body frame x velocity
+    pub vy: f64,         // Body frame y velocity
+    pub yaw: f64,        // Orientation angle (radians)
+    pub yaw_rate: f64,   // Yaw rate (radians/s)
+    pub steering: f64,   // Front wheel steering angle (radians)
+    pub slip_front: f64, // Front tire slip angle (radians)
+    pub slip_rear: f64,  // Rear tire slip angle (radians)
+}
+
+impl DynamicBicycleState {
+    /// Front slip angle minus rear slip angle in radians
+    ///
+    /// Positive when the front axle slides more than the rear (understeer), negative when
+    /// the rear slides more (oversteer).
+    pub fn slip_balance(&self) -> f64 {
+        self.slip_front.abs() - self.slip_rear.abs()
+    }
+}
+
+/// Pose and body frame velocities of the bicycle, e.g. to feed it to the sensors
+impl From<&DynamicBicycleState> for PointMassState {
+    fn from(state: &DynamicBicycleState) -> Self {
+        PointMassState {
+            x: state.x,
+            y: state.y,
+            vx: state.vx,
+            vy: state.vy,
+            yaw: state.yaw,
+        }
+    }
+}
+
+impl fmt::Display for DynamicBicycleState {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "Pos: ({:.2}, {:.2}), Vel: ({:.2}, {:.2}), Yaw: {:.2}, Yaw rate: {:.3}, Slip: ({:.3}, {:.3})",
+            self.x, self.y, self.vx, self.vy, self.yaw, self.yaw_rate, self.slip_front, self.slip_rear
+        )
+    }
+}
+
+/// Mass, inertia, geometry, and tire parameters of a dynamic bicycle model
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct DynamicBicycleParams {
+    /// Vehicle mass in kg
+    pub mass: f64,
+    /// Yaw moment of inertia in kg·m²
+    pub yaw_inertia: f64,
+    /// Distance from the center of gravity to the front axle in meters
+    pub front_axle: f64,
+    /// Distance from the center of gravity to the rear axle in meters
+    pub rear_axle: f64,
+    /// Cornering stiffness of the front axle in N/rad
+    pub cornering_stiffness_front: f64,
+    /// Cornering stiffness of the rear axle in N/rad
+    pub cornering_stiffness_rear: f64,
+}
+
+impl DynamicBicycleParams {
+    /// Distance between the axles in meters
+    pub fn wheelbase(&self) -> f64 {
+        self.front_axle + self.rear_axle
+    }
+
+    /// Understeer gradient in rad/(m/s²)
+    ///
+    /// Positive for an understeering vehicle (it needs more steering as the lateral
+    /// acceleration grows), negative for an oversteering one, zero for neutral steer.
+    pub fn understeer_gradient(&self) -> f64 {
+        self.mass / self.wheelbase()
+            * (self.rear_axle / self.cornering_stiffness_front
+                - self.front_axle / self.cornering_stiffness_rear)
+    }
+}
+
+impl Default for DynamicBicycleParams {
+    /// A mid-size passenger car with a slight understeer
+    fn default() -> Self {
+        Self {
+            mass: 1500.0,
+            yaw_inertia: 2500.0,
+            front_axle: 1.2,
+            rear_axle: 1.4,
+            cornering_stiffness_front: 80_000.0,
+            cornering_stiffness_rear: 90_000.0,
+        }
+    }
+}
+
+/// Dynamic bicycle model with linear tire slip
+///
+/// Both axles are lumped into one wheel each. The lateral tire forces are proportional
+/// to the slip angles (cornering stiffness times slip angle), so the model captures
+/// understeer and oversteer, side slip, and the yaw response lagging the steering.
+/// The tires saturate only with a friction coefficient (`set_friction`). The inputs are the body frame longitudinal acceleration and the
+/// front steering angle. Below 1 m/s, where slip angles are ill-defined, the model
+/// follows the kinematic bicycle relations.
+#[derive(Debug, Clone, PartialEq)]
+pub struct DynamicBicycle {
+    state: DynamicBicycleState,
+    initial_state: DynamicBicycleState,
+    params: DynamicBicycleParams,
+    ax: f64,                       // Body frame x-axis acceleration input
+    steering: f64,                 // Front steering angle input (radians)
+    yaw_rate_command: Option<f64>, // Yaw rate the steering follows, if commanded
+    length: f64,                   // Vehicle length in meters
+    width: f64,                    // Vehicle width in meters
+    integrator: Integrator,
+    friction: Option<f64>, // Tire-road friction coefficient, `None` for unlimited grip
+    limits: ControlLimits,
+    saturation: Saturation, // Limits hit by the inputs of the last step
+}
+
+impl DynamicBicycle {
+    /// Create a new dynamic bicycle at the origin with zero velocity and default parameters
+    pub fn new() -> Self {
+        Self::with_initial_state(0.0, 0.0, 0.0, 0.0)
+    }
+
+    /// Create a new dynamic bicycle with initial position and forward velocity
+    pub fn with_initial_state(x: f64, y: f64, vx: f64, yaw: f64) -> Self {
+        Self::from_state(DynamicBicycleState {
+            x,
+            y,
+            vx,
+            yaw,
+            ..DynamicBicycleState::default()
+        })
+    }
+
+    /// Create a new dynamic bicycle starting from `initial_state`
+    pub fn from_state(initial_state: DynamicBicycleState) -> Self {
+        Self {
+            state: initial_state.clone(),
+            initial_state,
+            params: DynamicBicycleParams::default(),
+            ax: 0.0,
+            steering: 0.0,
+            yaw_rate_command: None,
+            length: 4.5,
+            width: 2.0,
+            integrator: Integrator::Euler,
+            friction: None,
+            limits: ControlLimits::none(),
+            saturation: Saturation::default(),
+        }
+    }
+
+    /// Set the mass, inertia, geometry, and tire parameters
+    pub fn set_params(&mut self, params: DynamicBicycleParams) {
+        self.params = params;
+    }
+
+    pub fn params(&self) -> &DynamicBicycleParams {
+        &self.params
+    }
+
+    /// Set the inputs
+    ///
+    /// # Arguments
+    /// * `ax` - Body frame longitudinal acceleration in m/s²
+    /// * `steering` - Front wheel steering angle in radians, positive to the left
+    pub fn set_controls(&mut self, ax: f64, steering: f64) {
+        self.ax = ax;
+        self.steering = steering;
+        self.yaw_rate_command = None;
+    }
+
+    /// Kinematic steering angle in radians for `yaw_rate` at the current speed
+    pub fn steering_for_yaw_rate(&self, yaw_rate: f64) -> f64 {
+        let vx = self.state.vx.abs().max(KINEMATIC_SPEED);
+        (self.params.wheelbase() * yaw_rate / vx).atan()
+    }
+
+    /// Set the size of the vehicle
+    pub fn set_size(&mut self, length: f64, width: f64) {
+        self.length = length;
+        self.width = width;
+    }
+
+    /// Set the integration scheme of the tire-force dynamics (default `Integrator::Euler`)
+    ///
+    /// `Euler` is semi-implicit: the velocities and the yaw rate are updated first and
+    /// move the pose within the same substep. `Rk2` and `Rk4` integrate the pose and the
+    /// velocities together. The kinematic low-speed regime always uses `Euler`.
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    pub fn integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    /// Limit the tire forces to what the road can transmit
+    ///
+    /// With a friction coefficient `mu`, the combined acceleration of the acceleration
+    /// input and the lateral tire forces is limited to the friction circle
+    /// `sqrt(ax² + ay²) <= mu * g`. Larger forces are scaled back onto the circle,
+    /// front and rear alike. `None` (the default) leaves the tire forces unlimited.
+    pub fn set_friction(&mut self, friction: Option<f64>) {
+        self.friction = friction;
+    }
+
+    pub fn friction(&self) -> Option<f64> {
+        self.friction
+    }
+
+    /// Radius in m/s² of the friction circle, `None` without friction
+    pub fn max_acceleration(&self) -> Option<f64> {
+        self.friction.map(|friction| friction * GRAVITY)
+    }
+
+    /// Factor scaling the accelerations `ax` and `lateral` back onto the friction circle
+    ///
+    /// 1 inside the circle or without friction.
+    fn friction_scale(&self, ax: f64, lateral: f64) -> f64 {
+        match self.max_acceleration() {
+            Some(limit) if ax.hypot(lateral) > limit => limit / ax.hypot(lateral),
+            _ => 1.0,
+        }
+    }
+
+    /// Clamp the inputs to `limits` in every substep (default: no limits)
+    ///
+    /// `max_ax` clamps the acceleration input. `max_yaw_rate` and `max_lateral` clamp
+    /// the steering angle so the kinematic yaw rate `vx * tan(steering) / wheelbase`
+    /// stays within them; `saturation` reports which limits were hit.
+    pub fn set_limits(&mut self, limits: ControlLimits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> &ControlLimits {
+        &self.limits
+    }
+
+    /// Inputs (ax, steering) after the control limits at the current speed
+    fn limited_inputs(&self) -> (f64, f64, Saturation) {
+        if self.limits == ControlLimits::none() {
+            return (self.ax, self.steering, Saturation::default());
+        }
+        let (vx, wheelbase) = (self.state.vx, self.params.wheelbase());
+        let speed = vx.abs().max(KINEMATIC_SPEED);
+        let yaw_rate = speed * self.steering.tan() / wheelbase;
+        let ((ax, limited_yaw_rate), saturation) = self.limits.apply(self.ax, yaw_rate, vx);
+        let steering = if limited_yaw_rate == yaw_rate {
+            self.steering
+        } else {
+            (wheelbase * limited_yaw_rate / speed).atan()
+        };
+        (ax, steering, saturation)
+    }
+
+    /// Front and rear slip angles in radians for the given steering and body frame motion
+    fn slip_angles(&self, steering: f64, vx: f64, vy: f64, yaw_rate: f64) -> (f64, f64) {
+        let params = &self.params;
+        (
+            steering - (vy + params.front_axle * yaw_rate).atan2(vx.abs()),
+            -(vy - params.rear_axle * yaw_rate).atan2(vx.abs()),
+        )
+    }
+
+    /// Acceleration input and front and rear lateral tire forces in N, scaled back onto
+    /// the friction circle by the returned factor (1 inside it)
+    fn tire_forces(
+        &self,
+        inputs: (f64, f64),
+        vx: f64,
+        vy: f64,
+        yaw_rate: f64,
+    ) -> (f64, f64, f64, f64) {
+        let params = &self.params;
+        let (ax, steering) = inputs;
+        let (slip_front, slip_rear) = self.slip_angles(steering, vx, vy, yaw_rate);
+        let force_front = params.cornering_stiffness_front * slip_front;
+        let force_rear = params.cornering_stiffness_rear * slip_rear;
+        let lateral = (force_front * steering.cos() + force_rear) / params.mass;
+        let scale = self.friction_scale(ax, lateral);
+        (ax * scale, force_front * scale, force_rear * scale, scale)
+    }
+
+    /// Body frame accelerations (longitudinal, lateral, yaw) from the inputs (ax,
+    /// steering) and the tire forces
+    fn accelerations(
+        &self,
+        inputs: (f64, f64),
+        vx: f64,
+        vy: f64,
+        yaw_rate: f64,
+    ) -> (f64, f64, f64) {
+        let params = &self.params;
+        let (ax, force_front, force_rear, _) = self.tire_forces(inputs, vx, vy, yaw_rate);
+        let (sin_steering, cos_steering) = inputs.1.sin_cos();
+        (
+            ax - force_front * sin_steering / params.mass + vy * yaw_rate,
+            (force_front * cos_steering + force_rear) / params.mass - vx * yaw_rate,
+            (params.front_axle * force_front * cos_steering - params.rear_axle * force_rear)
+                / params.yaw_inertia,
+        )
+    }
+
+    /// Advance the state by one integration step of at most `MAX_SUBSTEP`
+    fn substep(&mut self, dt: f64) {
+        if let Some(yaw_rate) = self.yaw_rate_command {
+            self.steering = self.steering_for_yaw_rate(yaw_rate);
+        }
+        let (ax, steering, saturation) = self.limited_inputs();
+        self.saturation = self.saturation | saturation;
+        self.state.steering = steering;
+
+        if self.state.vx.abs() < KINEMATIC_SPEED {
+            // Kinematic bicycle: no slip, the velocity points along the rear axle
+            let lateral = self.state.vx.powi(2) * steering.tan() / self.params.wheelbase();
+            let scale = self.friction_scale(ax, lateral);
+            self.saturation.ax |= scale < 1.0 && ax != 0.0;
+            let state = &mut self.state;
+            state.vx += ax * scale * dt;
+            state.yaw_rate = state.vx * steering.tan() / self.params.wheelbase();
+            state.vy = state.yaw_rate * self.params.rear_axle;
+            state.slip_front = 0.0;
+            state.slip_rear = 0.0;
+        } else {
+            let (vx, vy, yaw_rate) = (self.state.vx, self.state.vy, self.state.yaw_rate);
+            (self.state.slip_front, self.state.slip_rear) =
+                self.slip_angles(steering, vx, vy, yaw_rate);
+            let (_, force_front, force_rear, scale) =
+                self.tire_forces((ax, steering), vx, vy, yaw_rate);
+            if scale < 1.0 {
+                self.saturation.ax |= ax != 0.0;
+                self.saturation.lateral |= force_front != 0.0 || force_rear != 0.0;
+            }
+            if self.integrator != Integrator::Euler {
+                let state = [self.state.x, self.state.y, self.state.yaw, vx, vy, yaw_rate];
+                let [x, y, yaw, vx, vy, yaw_rate] =
+                    self.integrator.step(state, dt, |&[_, _, yaw, vx, vy, yaw_rate]| {
+                        let (sin_yaw, cos_yaw) = yaw.sin_cos();
+                        let (ax, ay, yaw_acceleration) =
+                            self.accelerations((ax, steering), vx, vy, yaw_rate);
+                        [
+                            vx * cos_yaw - vy * sin_yaw,
+                            vx * sin_yaw + vy * cos_yaw,
+                            yaw_rate,
+                            ax,
+                            ay,
+                            yaw_acceleration,
+                        ]
+                    });
+                self.state = DynamicBicycleState { x, y, yaw, vx, vy, yaw_rate, ..self.state };
+                return;
+            }
+            let (ax, ay, yaw_acceleration) = self.accelerations((ax, steering), vx, vy, yaw_rate);
+            self.state.vx += ax * dt;
+            self.state.vy += ay * dt;
+            self.state.yaw_rate += yaw_acceleration * dt;
+        }
+        let state = &mut self.state;
+        state.yaw += state.yaw_rate * dt;
+
+        // Transform body frame velocities to world frame
+        let (sin_yaw, cos_yaw) = state.yaw.sin_cos();
+        state.x += (state.vx * cos_yaw - state.vy * sin_yaw) * dt;
+        state.y += (state.vx * sin_yaw + state.vy * cos_yaw) * dt;
+    }
+}
+
+impl Default for DynamicBicycle {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Model for DynamicBicycle {
+    type State = DynamicBicycleState;
+
+    fn init(&mut self) {
+        self.reset();
+    }
+
+    /// Steps longer than 5 ms are split into equal substeps. A non-finite or
+    /// non-positive `dt` is rejected and leaves the state unchanged.
+    fn step(&mut self, dt: f64) {
+        if !dt.is_finite() || dt <= 0.0 {
+            return;
+        }
+        self.saturation = Saturation::default();
+        let substeps = (dt / MAX_SUBSTEP).ceil().max(1.0);
+        for _ in 0..substeps as usize {
+            self.substep(dt / substeps);
+        }
+    }
+
+    fn reset(&mut self) {
+        self.state = self.initial_state.clone();
+        self.ax = 0.0;
+        self.steering = 0.0;
+        self.yaw_rate_command = None;
+        self.saturation = Saturation::default();
+    }
+
+    fn set_position(&mut self, x: f64, y: f64, yaw: f64) {
+        self.state.x = x;
+        self.state.y = y;
+        self.state.yaw = yaw;
+    }
+
+    fn get_size(&self) -> (f64, f64) {
+        (self.length, self.width)
+    }
+
+    fn get_position(&self) -> (f64, f64, f64) {
+        (self.state.x, self.state.y, self.state.yaw)
+    }
+
+    fn get_velocity(&self) -> (f64, f64) {
+        (self.state.vx, self.state.vy)
+    }
+
+    fn get_state(&self) -> &Self::State {
+        &self.state
+    }
+
+    /// The yaw rate is converted into the steering angle a neutral-steer vehicle would
+    /// need at the current speed, so an understeering model turns less than commanded
+    /// and an oversteering one more. The steering follows the speed in every substep
+    /// until the next `set_controls`.
+    fn set_yaw_rate_controls(&mut self, ax: f64, yaw_rate: f64) {
+        self.set_controls(ax, self.steering_for_yaw_rate(yaw_rate));
+        self.yaw_rate_command = Some(yaw_rate);
+    }
+
+    fn saturation(&self) -> Saturation {
+        self.saturation
+    }
+
+    fn friction_limit(&self) -> Option<f64> {
+        self.max_acceleration()
+    }
+}
+
+impl fmt::Display for DynamicBicycle {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "DynamicBicycle {{ {}, ax: {:.3} m/s², steering: {:.3} rad }}",
+            self.state, self.ax, self.steering
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{DynamicBicycle, DynamicBicycleParams};
+    use crate::models::base_model::Model;
+    use crate::models::integrators::Integrator;
+    use crate::models::limits::ControlLimits;
+    use crate::simulation::base_simulation::Simulation;
+    use crate::simulation::open_loop::OpenLoopSimulation;
+    use crate::simulation::spill::SpilledResult;
+    use crate::tracks::circle::CircleTrack;
+    use std::sync::Arc;
+
+    /// Yaw rate and speed after holding `steering` from 20 m/s for 5 s
+    fn steady_state(params: DynamicBicycleParams, steering: f64) -> (f64, f64) {
+        let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 20.0, 0.0);
+        model.set_params(params);
+        model.set_controls(0.0, steering);
+        for _ in 0..50 {
+            model.step(0.1);
+        }
+        (model.get_state().yaw_rate, model.get_state().vx)
+    }
+
+    #[test]
+    fn test_straight_line_has_no_slip() {
+        let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 10.0, 0.0);
+        model.set_controls(2.0, 0.0);
+        model.step(0.5);
+        let state = model.get_state();
+        assert!((state.vx - 11.0).abs() < 1e-9);
+        assert_eq!((state.vy, state.yaw_rate, state.slip_front), (0.0, 0.0, 0.0));
+        assert!((state.x - 5.25).abs() < 1e-2);
+
+        model.reset();
+        assert_eq!(model.get_velocity(), (10.0, 0.0));
+    }
+
+    #[test]
+    fn test_steady_state_matches_understeer_gradient() {
+        let params = DynamicBicycleParams::default();
+        let steering = 0.02;
+        // The front tire force slightly brakes the car, so use the speed it settled at
+        let (yaw_rate, speed) = steady_state(params, steering);
+        let expected =
+            speed * steering / (params.wheelbase() + params.understeer_gradient() * speed * speed);
+        assert!((yaw_rate - expected).abs() < 1e-3 * expected, "{yaw_rate} vs {expected}");
+
+        // Understeer turns less than the kinematic yaw rate, oversteer more
+        let kinematic = speed * steering / params.wheelbase();
+        assert!(params.understeer_gradient() > 0.0 && yaw_rate < kinematic);
+        let oversteer = DynamicBicycleParams {
+            cornering_stiffness_front: 90_000.0,
+            cornering_stiffness_rear: 60_000.0,
+            ..params
+        };
+        assert!(oversteer.understeer_gradient() < 0.0);
+        assert!(steady_state(oversteer, steering).0 > kinematic);
+    }
+
+    #[test]
+    fn test_slip_angles_in_state() {
+        let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 20.0, 0.0);
+        model.set_yaw_rate_controls(0.0, 0.3);
+        for _ in 0..30 {
+            model.step(0.1);
+        }
+        let state = model.get_state();
+        assert!(state.slip_front > 0.0 && state.slip_rear > 0.0, "{state}");
+        assert!(state.slip_balance() > 0.0);
+        assert!(state.yaw_rate > 0.0 && state.yaw_rate < 0.3);
+
+        // The integrators agree on the steady state
+        let mut rk4 = DynamicBicycle::with_initial_state(0.0, 0.0, 20.0, 0.0);
+        rk4.set_integrator(Integrator::Rk4);
+        rk4.set_controls(0.0, 0.02);
+        for _ in 0..50 {
+            rk4.step(0.1);
+        }
+        let (euler_yaw_rate, _) = steady_state(DynamicBicycleParams::default(), 0.02);
+        assert!((rk4.get_state().yaw_rate - euler_yaw_rate).abs() < 1e-4);
+
+        // From standstill the kinematic relations apply
+        let mut model = DynamicBicycle::new();
+        model.set_controls(1.0, 0.1);
+        model.step(0.5);
+        assert_eq!(model.get_state().slip_front, 0.0);
+        assert!(model.get_state().yaw_rate > 0.0);
+    }
+
+    #[test]
+    fn test_control_limits_clamp_acceleration_and_steering() {
+        let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 10.0, 0.0);
+        model.set_limits(ControlLimits::none().with_max_ax(3.0).with_max_yaw_rate(0.2));
+        model.set_controls(8.0, 0.0);
+        model.step(0.1);
+        assert!(model.saturation().ax && !model.saturation().yaw_rate);
+        assert!((model.get_state().vx - 10.3).abs() < 1e-9);
+
+        // 0.3 rad of steering at 20 m/s asks for about 2.4 rad/s of yaw rate
+        let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 20.0, 0.0);
+        model.set_limits(ControlLimits::none().with_max_yaw_rate(0.2));
+        model.set_controls(0.0, 0.3);
+        model.step(0.005);
+        let steering = (model.params().wheelbase() * 0.2 / 20.0).atan();
+        assert!((model.get_state().steering - steering).abs() < 1e-12);
+        assert!(model.saturation().yaw_rate && !model.saturation().ax);
+        model.reset();
+        assert!(!model.saturation().any());
+    }
+
+    #[test]
+    fn test_friction_circle_caps_tire_forces() {
+        // Lateral acceleration `vx * yaw_rate` settled at after 5 s of steering
+        let lateral = |friction: Option<f64>| {
+            let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 20.0, 0.0);
+            model.set_friction(friction);
+            model.set_controls(0.0, 0.1);
+            for _ in 0..50 {
+                model.step(0.1);
+            }
+            let state = model.get_state();
+            (state.vx * state.yaw_rate, model.saturation(), model.friction_limit())
+        };
+        let (free, saturation, limit) = lateral(None);
+        assert!(free > 7.0 && !saturation.any() && limit.is_none(), "{free}");
+        let (gripped, saturation, limit) = lateral(Some(0.5));
+        assert_eq!(limit, Some(0.5 * 9.81));
+        // The car slides wide at the limit instead of turning tighter
+        assert!(gripped <= 0.5 * 9.81 && gripped > 0.9 * 0.5 * 9.81, "{gripped}");
+        assert!(saturation.lateral && !saturation.ax);
+
+        // Accelerating out of the turn shares the grip
+        let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 20.0, 0.0);
+        model.set_friction(Some(0.5));
+        model.set_controls(4.0, 0.1);
+        model.step(1.0);
+        assert!(model.saturation().ax && model.saturation().lateral);
+        assert!(model.get_state().vx < 24.0);
+    }
+
+    #[test]
+    fn test_step_rejects_invalid_time_steps() {
+        let mut model = DynamicBicycle::with_initial_state(0.0, 0.0, 10.0, 0.0);
+        model.set_controls(1.0, 0.05);
+        for dt in [0.0, -0.1, f64::NAN, f64::INFINITY] {
+            model.step(dt);
+            assert_eq!(model.get_state().x, 0.0, "dt = {dt}");
+        }
+        model.step(0.1);
+        assert!(model.get_state().x > 0.0);
+    }
+
+    #[test]
+    fn test_dynamic_bicycle_runs_open_loop() {
+        let mut sim = OpenLoopSimulation::builder_for(DynamicBicycle::new())
+            .controls(2.0, 0.2)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+        let result = sim.run(0.1, 5.0).expect("run");
+        let state = &result.last().expect("final snapshot").state;
+        // Turning at speed, the tires slip and the understeering car turns less than asked
+        assert!(state.vx > 9.0 && state.slip_front > 0.0, "{state}");
+        assert!(state.yaw_rate > 0.0 && state.yaw_rate < 0.2, "{state}");
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut store =
+            SpilledResult::create(temp_dir.path().join("bicycle.spill"), 8).expect("create store");
+        sim.reset();
+        sim.run_spilled(0.1, 5.0, &mut store).expect("spilled run");
+        assert_eq!(store.to_result().expect("read spilled run"), result);
+    }
+}
@@ -0,0 +1,133 @@
+//! Saturation limits on the control inputs of a model.
+//!
+//! Open-loop schedules and aggressive controllers can command accelerations no car
+//! could produce. Models clamp their inputs to the configured `ControlLimits` in
+//! `step` and report which limits were hit in the last step as a `Saturation`.
+
+use std::fmt;
+
+/// Largest control inputs a model accepts; `None` leaves an input unlimited
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct ControlLimits {
+    /// Largest longitudinal acceleration or braking in m/s²
+    pub max_ax: Option<f64>,
+    /// Largest lateral acceleration `vx * yaw_rate` in m/s²
+    pub max_lateral: Option<f64>,
+    /// Largest yaw rate in radians/s
+    pub max_yaw_rate: Option<f64>,
+}
+
+impl ControlLimits {
+    /// No limits (the default)
+    pub fn none() -> Self {
+        Self::default()
+    }
+
+    pub fn with_max_ax(mut self, max_ax: f64) -> Self {
+        self.max_ax = Some(max_ax);
+        self
+    }
+
+    pub fn with_max_lateral(mut self, max_lateral: f64) -> Self {
+        self.max_lateral = Some(max_lateral);
+        self
+    }
+
+    pub fn with_max_yaw_rate(mut self, max_yaw_rate: f64) -> Self {
+        self.max_yaw_rate = Some(max_yaw_rate);
+        self
+    }
+
+    /// Clamp (ax, yaw_rate) at speed `vx`
+    ///
+    /// # Returns
+    /// The clamped (ax, yaw_rate) and the limits that were hit
+    pub fn apply(&self, ax: f64, yaw_rate: f64, vx: f64) -> ((f64, f64), Saturation) {
+        let mut saturation = Saturation::default();
+        let clamp = |value: f64, limit: Option<f64>, hit: &mut bool| match limit {
+            Some(limit) if value.abs() > limit => {
+                *hit = true;
+                value.clamp(-limit, limit)
+            }
+            _ => value,
+        };
+        let ax = clamp(ax, self.max_ax, &mut saturation.ax);
+        let mut yaw_rate = clamp(yaw_rate, self.max_yaw_rate, &mut saturation.yaw_rate);
+        if vx != 0.0 {
+            let max_yaw_rate = self.max_lateral.map(|limit| limit / vx.abs());
+            yaw_rate = clamp(yaw_rate, max_yaw_rate, &mut saturation.lateral);
+        }
+        ((ax, yaw_rate), saturation)
+    }
+}
+
+/// Limits hit by the inputs of a model step
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub struct Saturation {
+    /// The longitudinal acceleration was clamped
+    pub ax: bool,
+    /// The yaw rate was clamped to keep the lateral acceleration within its limit
+    pub lateral: bool,
+    /// The yaw rate was clamped to its limit
+    pub yaw_rate: bool,
+}
+
+impl Saturation {
+    /// Whether any input was clamped
+    pub fn any(&self) -> bool {
+        self.ax || self.lateral || self.yaw_rate
+    }
+}
+
+impl std::ops::BitOr for Saturation {
+    type Output = Self;
+
+    fn bitor(self, other: Self) -> Self {
+        Self {
+            ax: self.ax || other.ax,
+            lateral: self.lateral || other.lateral,
+            yaw_rate: self.yaw_rate || other.yaw_rate,
+        }
+    }
+}
+
+impl fmt::Display for Saturation {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let names: Vec<&str> = [(self.ax, "ax"), (self.lateral, "lateral"), (self.yaw_rate, "yaw rate")]
+            .into_iter()
+            .filter_map(|(hit, name)| hit.then_some(name))
+            .collect();
+        if names.is_empty() {
+            write!(f, "none")
+        } else {
+            write!(f, "{}", names.join(", "))
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ControlLimits, Saturation};
+
+    #[test]
+    fn test_apply_limits() {
+        let limits = ControlLimits::none()
+            .with_max_ax(5.0)
+            .with_max_lateral(10.0)
+            .with_max_yaw_rate(1.0);
+        assert_eq!(limits.apply(2.0, 0.2, 20.0), ((2.0, 0.2), Saturation::default()));
+
+        // 0.8 rad/s at 20 m/s is 16 m/s² lateral: clamped to 0.5 rad/s
+        let ((ax, yaw_rate), saturation) = limits.apply(-8.0, 0.8, 20.0);
+        assert_eq!((ax, yaw_rate), (-5.0, 0.5));
+        assert!(saturation.ax && saturation.lateral && !saturation.yaw_rate);
+        assert_eq!(saturation.to_string(), "ax, lateral");
+
+        // At walking pace only the yaw rate limit applies
+        let ((_, yaw_rate), saturation) = limits.apply(0.0, 3.0, 1.0);
+        assert_eq!(yaw_rate, 1.0);
+        assert!(saturation.yaw_rate && !saturation.lateral);
+        assert!(!ControlLimits::none().apply(100.0, 100.0, 100.0).1.any());
+    }
+}
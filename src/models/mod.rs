@@ -1,2 +1,8 @@
 pub mod base_model;
+pub mod batch;
+pub mod dynamic_bicycle;
+pub mod integrators;
+pub mod limits;
 pub mod point_mass;
+pub mod pose;
+pub mod scalar;
@@ -1,17 +1,28 @@
 use super::base_model::Model;
+use super::integrators::Integrator;
+use super::limits::{ControlLimits, Saturation};
+use super::scalar::Float;
 use std::fmt;
 
-/// State of a 2D point mass
-#[derive(Debug, Clone)]
-pub struct PointMassState {
-    pub x: f64,    // World frame x position
-    pub y: f64,    // World frame y position
-    pub vx: f64,   // Body frame x velocity
-    pub vy: f64,   // Body frame y velocity
-    pub yaw: f64,  // Orientation angle (radians)
+/// Gravitational acceleration in m/s²
+pub(crate) const GRAVITY: f64 = 9.81;
+/// Mass in kg used until `PointMass::set_mass` is called
+pub const DEFAULT_MASS: f64 = 1500.0;
+/// Speed in m/s below which a lateral force input does not turn the vehicle
+const MIN_TURNING_SPEED: f64 = 1e-3;
+
+/// State of a 2D point mass, stored as `T` (`f64` by default, see `Float`)
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct PointMassState<T = f64> {
+    pub x: T,    // World frame x position
+    pub y: T,    // World frame y position
+    pub vx: T,   // Body frame x velocity
+    pub vy: T,   // Body frame y velocity
+    pub yaw: T,  // Orientation angle (radians)
 }
 
-impl fmt::Display for PointMassState {
+impl<T: Float> fmt::Display for PointMassState<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
@@ -22,66 +33,116 @@ impl fmt::Display for PointMassState {
 }
 
 /// Point mass model with 2D dynamics
-pub struct PointMass {
-    state: PointMassState,
-    initial_state: PointMassState,
-    ax: f64,       // Body frame x-axis acceleration input
-    yaw_rate: f64, // Yaw rate input (radians/s)
+///
+/// The state is integrated in `T`; use `PointMass::<f32>::from_state` for reduced
+/// precision batch runs.
+#[derive(Debug, Clone, PartialEq)]
+pub struct PointMass<T = f64> {
+    state: PointMassState<T>,
+    initial_state: PointMassState<T>,
+    ax: T,       // Body frame x-axis acceleration input
+    yaw_rate: T, // Yaw rate input (radians/s)
+    forces: Option<(f64, f64)>, // Body frame (Fx, Fy) input in N, replacing ax and yaw_rate
+    mass: f64,   // Vehicle mass in kg
     length: f64, // Vehicle length in meters
     width: f64,  // Vehicle width in meters
+    friction: Option<f64>, // Tire-road friction coefficient, `None` for unlimited grip
+    drag: f64,             // Aerodynamic drag deceleration per squared speed in 1/m
+    grip: f64,             // Factor on `friction` set by the surface conditions
+    drag_factor: f64,      // Factor on `drag` set by the surface conditions
+    integrator: Integrator,
+    limits: ControlLimits,
+    saturation: Saturation, // Limits hit by the inputs of the last step
 }
 
 impl PointMass {
     /// Create a new point mass at the origin with zero velocity
     pub fn new() -> Self {
-        let initial_state = PointMassState {
-            x: 0.0,
-            y: 0.0,
-            vx: 0.0,
-            vy: 0.0,
-            yaw: 0.0,
-        };
-        
-        Self {
-            state: initial_state.clone(),
-            initial_state,
-            ax: 0.0,
-            yaw_rate: 0.0,
-            length: 4.5,  // Default car length
-            width: 2.0,   // Default car width
-        }
+        Self::with_initial_state(0.0, 0.0, 0.0, 0.0)
     }
     
     /// Create a new point mass with initial position and velocity
     pub fn with_initial_state(x: f64, y: f64, vx: f64, yaw: f64) -> Self {
-        let initial_state = PointMassState {
+        Self::from_state(PointMassState {
             x,
             y,
             vx,
             vy: 0.0,
             yaw,
-        };
-        
+        })
+    }
+}
+
+impl<T: Float> PointMass<T> {
+    /// Create a new point mass starting from `initial_state`
+    pub fn from_state(initial_state: PointMassState<T>) -> Self {
         Self {
             state: initial_state.clone(),
             initial_state,
-            ax: 0.0,
-            yaw_rate: 0.0,
+            ax: T::ZERO,
+            yaw_rate: T::ZERO,
+            forces: None,
+            mass: DEFAULT_MASS,
             length: 4.5,  // Default car length
             width: 2.0,   // Default car width
+            friction: None,
+            drag: 0.0,
+            grip: 1.0,
+            drag_factor: 1.0,
+            integrator: Integrator::Euler,
+            limits: ControlLimits::none(),
+            saturation: Saturation::default(),
         }
     }
     
     /// Set acceleration inputs
     pub fn set_controls(&mut self, ax: f64, yaw_rate: f64) {
-        self.ax = ax;
-        self.yaw_rate = yaw_rate;
+        self.ax = T::from_f64(ax);
+        self.yaw_rate = T::from_f64(yaw_rate);
+        self.forces = None;
+    }
+
+    /// Set force inputs instead of accelerations
+    ///
+    /// The forces are converted with the mass in every step: the longitudinal
+    /// acceleration is `fx / mass` and the lateral force turns the vehicle at
+    /// `fy / (mass * vx)`, so the lateral acceleration `vx * yaw_rate` is `fy / mass`.
+    /// Standing still, the lateral force has no effect. The force inputs hold until the
+    /// next `set_controls` or `set_forces`, and go through the same limits.
+    ///
+    /// # Arguments
+    /// * `fx` - Body frame longitudinal force in N (traction positive, braking negative)
+    /// * `fy` - Body frame lateral force in N (positive to the left)
+    pub fn set_forces(&mut self, fx: f64, fy: f64) {
+        self.forces = Some((fx, fy));
+    }
+
+    /// Force inputs in N set by `set_forces`, `None` when driven by accelerations
+    pub fn forces(&self) -> Option<(f64, f64)> {
+        self.forces
+    }
+
+    /// Set the vehicle mass in kg (default `DEFAULT_MASS`)
+    ///
+    /// The mass converts force inputs into accelerations; the acceleration inputs of
+    /// `set_controls` do not depend on it.
+    pub fn set_mass(&mut self, mass: f64) {
+        self.mass = mass;
+    }
+
+    pub fn mass(&self) -> f64 {
+        self.mass
     }
     
     /// Set the position
     pub fn set_pos(&mut self, x: f64, y: f64) {
-        self.state.x = x;
-        self.state.y = y;
+        self.state.x = T::from_f64(x);
+        self.state.y = T::from_f64(y);
+    }
+    
+    /// Set the forward (body frame x) speed in m/s
+    pub fn set_speed(&mut self, vx: f64) {
+        self.state.vx = T::from_f64(vx);
     }
     
     /// Set the size of the vehicle
@@ -89,22 +150,159 @@ impl PointMass {
         self.length = length;
         self.width = width;
     }
+
+    /// Limit the accelerations to what the tires can transmit
+    ///
+    /// With a friction coefficient `mu`, the combined acceleration of the longitudinal
+    /// acceleration and the lateral acceleration `vx * yaw_rate` is limited to the
+    /// friction circle `sqrt(ax² + ay²) <= mu * g`. Larger commands are scaled back onto
+    /// the circle, keeping the direction of the commanded acceleration. `None` (the
+    /// default) leaves the commands unlimited.
+    pub fn set_friction(&mut self, friction: Option<f64>) {
+        self.friction = friction;
+    }
+
+    pub fn friction(&self) -> Option<f64> {
+        self.friction
+    }
+
+    /// Radius in m/s² of the friction circle under the current surface conditions
+    ///
+    /// `None` without friction. Controllers receive it through
+    /// `Controller::set_friction_limit` to plan inputs the tires can transmit.
+    pub fn max_acceleration(&self) -> Option<f64> {
+        self.friction.map(|friction| friction * self.grip * GRAVITY)
+    }
+
+    /// Set the aerodynamic drag, a deceleration of `drag * vx²` (default 0)
+    ///
+    /// # Arguments
+    /// * `drag` - Drag coefficient in 1/m, i.e. `0.5 * air_density * cd * area / mass`
+    pub fn set_drag(&mut self, drag: f64) {
+        self.drag = drag;
+    }
+
+    pub fn drag(&self) -> f64 {
+        self.drag
+    }
+
+    /// Scale the friction and the drag for the current surface conditions (e.g. rain)
+    ///
+    /// # Arguments
+    /// * `grip` - Factor on the friction coefficient
+    /// * `drag` - Factor on the drag coefficient
+    pub fn set_surface_conditions(&mut self, grip: f64, drag: f64) {
+        self.grip = grip;
+        self.drag_factor = drag;
+    }
+
+    /// Set the integration scheme used by `step` (default `Integrator::Euler`)
+    ///
+    /// `Euler` is the semi-implicit update the model has always used: the velocity and
+    /// yaw are updated first and move the position within the same step. `Rk2` and `Rk4`
+    /// integrate position, speed, and yaw together with the inputs held over the step,
+    /// which follows curved paths much more closely for the same time step.
+    pub fn set_integrator(&mut self, integrator: Integrator) {
+        self.integrator = integrator;
+    }
+
+    pub fn integrator(&self) -> Integrator {
+        self.integrator
+    }
+
+    /// Clamp the inputs to `limits` in every step (default: no limits)
+    ///
+    /// Applied before the friction limits; `saturation` reports which limits were hit.
+    pub fn set_limits(&mut self, limits: ControlLimits) {
+        self.limits = limits;
+    }
+
+    pub fn limits(&self) -> &ControlLimits {
+        &self.limits
+    }
+
+    /// Acceleration inputs (ax, yaw_rate), converted from the force inputs if set
+    fn acceleration_inputs(&self) -> (f64, f64) {
+        let Some((fx, fy)) = self.forces else {
+            return (self.ax.to_f64(), self.yaw_rate.to_f64());
+        };
+        let vx = self.state.vx.to_f64();
+        let yaw_rate = if vx.abs() > MIN_TURNING_SPEED {
+            fy / (self.mass * vx)
+        } else {
+            0.0
+        };
+        (fx / self.mass, yaw_rate)
+    }
+
+    /// Control inputs after the control limits, the friction limits, and the drag
+    fn effective_controls(&self) -> (T, T, Saturation) {
+        if self.forces.is_none()
+            && self.friction.is_none()
+            && self.drag == 0.0
+            && self.limits == ControlLimits::none()
+        {
+            return (self.ax, self.yaw_rate, Saturation::default());
+        }
+        let vx = self.state.vx.to_f64();
+        let (ax, yaw_rate) = self.acceleration_inputs();
+        let ((mut ax, mut yaw_rate), mut saturation) = self.limits.apply(ax, yaw_rate, vx);
+        if let Some(limit) = self.max_acceleration() {
+            // Scale the combined acceleration back onto the friction circle
+            let lateral = vx * yaw_rate;
+            let combined = ax.hypot(lateral);
+            if combined > limit {
+                let scale = limit / combined;
+                saturation.ax |= ax != 0.0;
+                saturation.lateral |= lateral != 0.0;
+                ax *= scale;
+                yaw_rate *= scale;
+            }
+        }
+        ax -= self.drag * self.drag_factor * vx * vx.abs();
+        (T::from_f64(ax), T::from_f64(yaw_rate), saturation)
+    }
+}
+
+impl Default for PointMass {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
-impl Model for PointMass {
-    type State = PointMassState;
+impl<T: Float> Model for PointMass<T> {
+    type State = PointMassState<T>;
     
     fn init(&mut self) {
         self.state = self.initial_state.clone();
-        self.ax = 0.0;
-        self.yaw_rate = 0.0;
+        self.ax = T::ZERO;
+        self.yaw_rate = T::ZERO;
+        self.forces = None;
+        self.saturation = Saturation::default();
     }
     
     fn step(&mut self, dt: f64) {
+        let (ax, yaw_rate, saturation) = self.effective_controls();
+        self.saturation = saturation;
+        if self.integrator != Integrator::Euler {
+            let (ax, yaw_rate) = (ax.to_f64(), yaw_rate.to_f64());
+            let state = [self.state.x, self.state.y, self.state.vx, self.state.yaw].map(T::to_f64);
+            let [x, y, vx, yaw] = self.integrator.step(state, dt, |&[_, _, vx, yaw]| {
+                [vx * yaw.cos(), vx * yaw.sin(), ax, yaw_rate]
+            });
+            self.state.x = T::from_f64(x);
+            self.state.y = T::from_f64(y);
+            self.state.vx = T::from_f64(vx);
+            self.state.vy = T::ZERO;
+            self.state.yaw = T::from_f64(yaw);
+            return;
+        }
+
+        let dt = T::from_f64(dt);
         // Update velocities in body frame using acceleration inputs
-        self.state.vx += self.ax * dt;
-        self.state.vy = 0.0;
-        self.state.yaw += self.yaw_rate * dt;
+        self.state.vx += ax * dt;
+        self.state.vy = T::ZERO;
+        self.state.yaw += yaw_rate * dt;
         
         // Transform body frame velocities to world frame
         let cos_yaw = self.state.yaw.cos();
@@ -120,14 +318,16 @@ impl Model for PointMass {
     
     fn reset(&mut self) {
         self.state = self.initial_state.clone();
-        self.ax = 0.0;
-        self.yaw_rate = 0.0;
+        self.ax = T::ZERO;
+        self.yaw_rate = T::ZERO;
+        self.forces = None;
+        self.saturation = Saturation::default();
     }
     
     fn set_position(&mut self, x: f64, y: f64, yaw: f64) {
-        self.state.x = x;
-        self.state.y = y;
-        self.state.yaw = yaw;
+        self.state.x = T::from_f64(x);
+        self.state.y = T::from_f64(y);
+        self.state.yaw = T::from_f64(yaw);
     }
     
     fn get_size(&self) -> (f64, f64) {
@@ -135,15 +335,31 @@ impl Model for PointMass {
     }
     
     fn get_position(&self) -> (f64, f64, f64) {
-        (self.state.x, self.state.y, self.state.yaw)
+        (self.state.x.to_f64(), self.state.y.to_f64(), self.state.yaw.to_f64())
+    }
+    
+    fn get_velocity(&self) -> (f64, f64) {
+        (self.state.vx.to_f64(), self.state.vy.to_f64())
     }
     
     fn get_state(&self) -> &Self::State {
         &self.state
     }
+
+    fn set_yaw_rate_controls(&mut self, ax: f64, yaw_rate: f64) {
+        self.set_controls(ax, yaw_rate);
+    }
+
+    fn saturation(&self) -> Saturation {
+        self.saturation
+    }
+
+    fn friction_limit(&self) -> Option<f64> {
+        self.max_acceleration()
+    }
 }
 
-impl fmt::Display for PointMass {
+impl<T: Float> fmt::Display for PointMass<T> {
     fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
         write!(
             f,
@@ -155,8 +371,10 @@ impl fmt::Display for PointMass {
 
 #[cfg(test)]
 mod tests {
-    use super::PointMass;
+    use super::{PointMass, PointMassState};
     use crate::models::base_model::Model;
+    use crate::models::integrators::Integrator;
+    use crate::models::limits::ControlLimits;
 
     #[test]
     fn test_point_mass_creation() {
@@ -347,6 +565,22 @@ mod tests {
         assert!((state.y - 0.1).abs() < 1e-10);
     }
 
+    #[test]
+    fn test_point_mass_get_velocity() {
+        let mut model = PointMass::with_initial_state(0.0, 0.0, 3.0, 1.0);
+        assert_eq!(model.get_velocity(), (3.0, 0.0));
+        assert_eq!(model.get_speed(), 3.0);
+
+        // Reversing gives a negative vx but a positive speed
+        model.set_controls(-5.0, 0.0);
+        model.step(1.0);
+        assert_eq!(model.get_velocity(), (-2.0, 0.0));
+        assert_eq!(model.get_speed(), 2.0);
+
+        let boxed: Box<dyn Model<State = PointMassState>> = Box::new(model);
+        assert_eq!(boxed.get_speed(), 2.0);
+    }
+
     #[test]
     fn test_point_mass_get_size() {
         let model = PointMass::new();
@@ -399,4 +633,124 @@ mod tests {
         let state = model.get_state();
         assert!((state.yaw - 0.1).abs() < 1e-10);
     }
+
+    #[test]
+    fn test_point_mass_f32_tracks_f64() {
+        let start = PointMassState {
+            x: 10.0f32,
+            y: 20.0,
+            vx: 5.0,
+            vy: 0.0,
+            yaw: 0.5,
+        };
+        let mut single = PointMass::<f32>::from_state(start);
+        let mut double = PointMass::with_initial_state(10.0, 20.0, 5.0, 0.5);
+        single.set_controls(1.5, 0.2);
+        double.set_controls(1.5, 0.2);
+        for _ in 0..100 {
+            single.step(0.01);
+            double.step(0.01);
+        }
+
+        let (x, y, yaw) = single.get_position();
+        let (x_ref, y_ref, yaw_ref) = double.get_position();
+        assert!((x - x_ref).abs() < 1e-3 && (y - y_ref).abs() < 1e-3);
+        assert!((yaw - yaw_ref).abs() < 1e-5);
+        assert_eq!(std::mem::size_of::<PointMassState<f32>>(), 20);
+    }
+
+    #[test]
+    fn test_point_mass_rk4_follows_circle() {
+        // 10 m/s at 0.5 rad/s: a circle of radius 20 m around (0, 20)
+        let error = |integrator: Integrator| {
+            let mut model = PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0);
+            model.set_integrator(integrator);
+            model.set_controls(0.0, 0.5);
+            for _ in 0..100 {
+                model.step(0.1);
+            }
+            let (x, y, _) = model.get_position();
+            (x - 20.0 * 5.0f64.sin()).hypot(y - 20.0 * (1.0 - 5.0f64.cos()))
+        };
+        let (euler, rk2, rk4) = (error(Integrator::Euler), error(Integrator::Rk2), error(Integrator::Rk4));
+        assert!(euler > 0.4, "{euler}");
+        assert!(rk2 < euler / 10.0, "{rk2}");
+        assert!(rk4 < 1e-4, "{rk4}");
+    }
+
+    #[test]
+    fn test_point_mass_control_limits() {
+        let mut model = PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0);
+        model.set_limits(ControlLimits::none().with_max_ax(3.0).with_max_lateral(5.0));
+        model.set_controls(8.0, 0.2);
+        model.step(0.1);
+        assert!(model.saturation().ax && !model.saturation().lateral);
+        assert!((model.get_state().vx - 10.3).abs() < 1e-9);
+
+        // 1 rad/s at 10.3 m/s would be 10.3 m/s² lateral
+        model.set_controls(-1.0, 1.0);
+        model.step(0.1);
+        let saturation = model.saturation();
+        assert!(saturation.lateral && !saturation.ax);
+        assert!((model.get_state().yaw - (0.02 + 5.0 / 10.3 * 0.1)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_point_mass_force_inputs() {
+        let mut model = PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0);
+        model.set_mass(1000.0);
+        // 2 kN forward and 5 kN to the left: 2 m/s² and 0.5 rad/s at 10 m/s
+        model.set_forces(2000.0, 5000.0);
+        model.step(0.1);
+        let state = model.get_state();
+        assert!((state.vx - 10.2).abs() < 1e-9);
+        assert!((state.yaw - 0.05).abs() < 1e-9);
+        assert_eq!(model.forces(), Some((2000.0, 5000.0)));
+
+        // Acceleration inputs replace the forces
+        model.set_controls(0.0, 0.0);
+        assert_eq!(model.forces(), None);
+
+        // Standing still, a lateral force does not turn the vehicle
+        let mut model = PointMass::new();
+        model.set_forces(1500.0, 3000.0);
+        model.step(0.1);
+        assert!((model.get_state().vx - 0.1).abs() < 1e-9);
+        assert_eq!(model.get_state().yaw, 0.0);
+    }
+
+    #[test]
+    fn test_point_mass_friction_and_drag() {
+        let mut model = PointMass::with_initial_state(0.0, 0.0, 20.0, 0.0);
+        model.set_friction(Some(1.0));
+        model.set_controls(-15.0, 1.0);
+        model.step(0.1);
+        // Braking at 15 m/s² while turning at 20 m/s² combine to 25 m/s²: both are
+        // scaled to 9.81 / 25 of the command
+        let state = model.get_state();
+        assert!((state.vx - (20.0 - 0.5886)).abs() < 1e-9);
+        assert!((state.yaw - 0.03924).abs() < 1e-9);
+        assert_eq!(model.max_acceleration(), Some(9.81));
+
+        // Commands inside the circle pass unchanged even if they use most of the grip
+        model.reset();
+        model.set_controls(-6.0, 0.35);
+        model.step(0.1);
+        assert!(!model.saturation().any());
+        assert!((model.get_state().vx - 19.4).abs() < 1e-9);
+
+        // Half the grip on a wet surface halves the limits
+        model.reset();
+        model.set_surface_conditions(0.5, 1.0);
+        model.set_controls(-15.0, 0.0);
+        model.step(0.1);
+        assert!((model.get_state().vx - (20.0 - 0.4905)).abs() < 1e-9);
+
+        assert!(model.saturation().ax && !model.saturation().lateral);
+
+        let mut coasting = PointMass::with_initial_state(0.0, 0.0, 20.0, 0.0);
+        coasting.set_drag(0.001);
+        coasting.step(0.1);
+        assert!((coasting.get_state().vx - (20.0 - 0.04)).abs() < 1e-9);
+    }
 }
@@ -0,0 +1,116 @@
+use super::dynamic_bicycle::DynamicBicycleState;
+use super::point_mass::PointMassState;
+use super::scalar::Float;
+
+/// Trait for model states that expose a planar pose for plotting and rendering
+pub trait Pose {
+    /// World frame x position in meters
+    fn x(&self) -> f64;
+
+    /// World frame y position in meters
+    fn y(&self) -> f64;
+
+    /// Orientation angle in radians
+    fn yaw(&self) -> f64;
+
+    /// Size of the vehicle, if the state carries it
+    ///
+    /// # Returns
+    /// Tuple of (length, width) in meters, or `None` to use the renderer's default size
+    fn size(&self) -> Option<(f64, f64)> {
+        None
+    }
+
+    /// Get the pose as a tuple
+    ///
+    /// # Returns
+    /// Tuple of (x, y, yaw) where x and y are coordinates in meters and yaw is in radians
+    fn pose(&self) -> (f64, f64, f64) {
+        (self.x(), self.y(), self.yaw())
+    }
+}
+
+impl<T: Float> Pose for PointMassState<T> {
+    fn x(&self) -> f64 {
+        self.x.to_f64()
+    }
+
+    fn y(&self) -> f64 {
+        self.y.to_f64()
+    }
+
+    fn yaw(&self) -> f64 {
+        self.yaw.to_f64()
+    }
+}
+
+impl Pose for DynamicBicycleState {
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    fn y(&self) -> f64 {
+        self.y
+    }
+
+    fn yaw(&self) -> f64 {
+        self.yaw
+    }
+}
+
+impl Pose for (f64, f64, f64) {
+    fn x(&self) -> f64 {
+        self.0
+    }
+
+    fn y(&self) -> f64 {
+        self.1
+    }
+
+    fn yaw(&self) -> f64 {
+        self.2
+    }
+}
+
+impl<T: Pose + ?Sized> Pose for &T {
+    fn x(&self) -> f64 {
+        (**self).x()
+    }
+
+    fn y(&self) -> f64 {
+        (**self).y()
+    }
+
+    fn yaw(&self) -> f64 {
+        (**self).yaw()
+    }
+
+    fn size(&self) -> Option<(f64, f64)> {
+        (**self).size()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::Pose;
+    use crate::models::point_mass::PointMassState;
+
+    fn pose_of<P: Pose>(pose: P) -> (f64, f64, f64) {
+        pose.pose()
+    }
+
+    #[test]
+    fn test_point_mass_state_pose() {
+        let state = PointMassState {
+            x: 1.0,
+            y: 2.0,
+            vx: 3.0,
+            vy: 0.0,
+            yaw: 0.5,
+        };
+
+        assert_eq!(state.pose(), (1.0, 2.0, 0.5));
+        assert_eq!(pose_of(&state), (1.0, 2.0, 0.5));
+        assert_eq!(state.size(), None);
+    }
+}
@@ -0,0 +1,84 @@
+//! Scalar type used for model states.
+//!
+//! Models store their state in a `Float` (`f64` by default), so large batch runs can
+//! use `f32` to halve the memory of recorded states at the cost of precision. Inputs
+//! and outputs of the `Model` and `Pose` traits stay `f64`; conversion happens at the
+//! model boundary.
+
+use std::fmt::{Debug, Display};
+use std::ops::{Add, AddAssign, Div, Mul, Neg, Sub};
+
+/// Floating point type a model state can be stored in (`f32` or `f64`)
+pub trait Float:
+    Copy
+    + PartialOrd
+    + Debug
+    + Display
+    + Default
+    + Send
+    + Sync
+    + 'static
+    + Add<Output = Self>
+    + Sub<Output = Self>
+    + Mul<Output = Self>
+    + Div<Output = Self>
+    + Neg<Output = Self>
+    + AddAssign
+{
+    const ZERO: Self;
+
+    /// Convert from `f64`, rounding to the nearest representable value
+    fn from_f64(value: f64) -> Self;
+
+    /// Convert to `f64` (exact for both `f32` and `f64`)
+    fn to_f64(self) -> f64;
+
+    fn sin(self) -> Self;
+
+    fn cos(self) -> Self;
+}
+
+macro_rules! impl_float {
+    ($type:ty) => {
+        impl Float for $type {
+            const ZERO: Self = 0.0;
+
+            fn from_f64(value: f64) -> Self {
+                value as $type
+            }
+
+            fn to_f64(self) -> f64 {
+                self as f64
+            }
+
+            fn sin(self) -> Self {
+                <$type>::sin(self)
+            }
+
+            fn cos(self) -> Self {
+                <$type>::cos(self)
+            }
+        }
+    };
+}
+
+impl_float!(f32);
+impl_float!(f64);
+
+#[cfg(test)]
+mod tests {
+    use super::Float;
+
+    fn quarter_turn_sine<T: Float>() -> f64 {
+        T::from_f64(std::f64::consts::FRAC_PI_2).sin().to_f64()
+    }
+
+    #[test]
+    fn test_float_conversions() {
+        assert_eq!(<f64 as Float>::from_f64(0.1), 0.1);
+        assert_eq!(<f32 as Float>::from_f64(0.1), 0.1f32);
+        assert_eq!(Float::to_f64(0.5f32), 0.5);
+        assert_eq!(quarter_turn_sine::<f64>(), 1.0);
+        assert!((quarter_turn_sine::<f32>() - 1.0).abs() < 1e-6);
+    }
+}
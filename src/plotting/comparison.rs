@@ -0,0 +1,135 @@
+use crate::diagnostics;
+use crate::error::{Error, Result};
+use crate::simulation::comparison::LapComparison;
+use plotters::prelude::*;
+
+/// Plot the time delta and the speed difference of a lap comparison to an SVG file
+///
+/// The upper chart shows the candidate's time delta to the reference along the lap
+/// (above zero: behind), the lower one the speeds of both laps.
+///
+/// # Arguments
+/// * `comparison` - Laps aligned by track progress (see `simulation::comparison`)
+/// * `filename` - Path to save the plot (e.g., "comparison.svg")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_lap_comparison(comparison: &LapComparison, filename: &str) -> Result<()> {
+    let samples = &comparison.samples;
+    let (Some(first), Some(last)) = (samples.first(), samples.last()) else {
+        return Err(Error::invalid_input("no comparison samples to plot"));
+    };
+    let distance = first.distance..last.distance.max(first.distance + 1.0);
+
+    let root = SVGBackend::new(filename, (900, 700)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let (upper, lower) = root.split_vertically(350);
+
+    let (min_delta, max_delta) = padded_range(samples.iter().map(|sample| sample.delta_time));
+    let mut delta_chart = ChartBuilder::on(&upper)
+        .caption("Time Delta", ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .build_cartesian_2d(distance.clone(), min_delta..max_delta)?;
+    delta_chart
+        .configure_mesh()
+        .x_desc("Distance (m)")
+        .y_desc("Delta (s)")
+        .draw()?;
+    delta_chart.draw_series(LineSeries::new(
+        [(distance.start, 0.0), (distance.end, 0.0)],
+        BLACK.mix(0.4),
+    ))?;
+    delta_chart.draw_series(LineSeries::new(
+        samples.iter().map(|sample| (sample.distance, sample.delta_time)),
+        RED.stroke_width(2),
+    ))?;
+
+    let (min_speed, max_speed) = padded_range(
+        samples
+            .iter()
+            .flat_map(|sample| [sample.reference_speed, sample.candidate_speed]),
+    );
+    let mut speed_chart = ChartBuilder::on(&lower)
+        .caption("Speed", ("sans-serif", 24))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(50)
+        .build_cartesian_2d(distance, min_speed..max_speed)?;
+    speed_chart
+        .configure_mesh()
+        .x_desc("Distance (m)")
+        .y_desc("Speed (m/s)")
+        .draw()?;
+    speed_chart
+        .draw_series(LineSeries::new(
+            samples.iter().map(|sample| (sample.distance, sample.reference_speed)),
+            BLUE.stroke_width(2),
+        ))?
+        .label("Reference")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE.stroke_width(2)));
+    speed_chart
+        .draw_series(LineSeries::new(
+            samples.iter().map(|sample| (sample.distance, sample.candidate_speed)),
+            RED.stroke_width(2),
+        ))?
+        .label("Candidate")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED.stroke_width(2)));
+    speed_chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::LowerRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    diagnostics::info(format_args!("Lap comparison plot saved to {}", filename));
+    Ok(())
+}
+
+/// Range of `values` with 10% margin, at least 0.1 wide
+fn padded_range(values: impl Iterator<Item = f64>) -> (f64, f64) {
+    let (min, max) = values
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+            (min.min(value), max.max(value))
+        });
+    if !min.is_finite() {
+        return (-1.0, 1.0);
+    }
+    let margin = ((max - min) * 0.1).max(0.05);
+    (min - margin, max + margin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plot_lap_comparison;
+    use crate::simulation::comparison::{ComparisonSample, LapComparison};
+
+    #[test]
+    fn test_plot_lap_comparison() {
+        let comparison = LapComparison {
+            reference_lap_time: 10.0,
+            candidate_lap_time: 10.5,
+            samples: (0..=10)
+                .map(|index| ComparisonSample {
+                    distance: index as f64 * 10.0,
+                    delta_time: index as f64 * 0.05,
+                    reference_speed: 10.0,
+                    candidate_speed: 9.5,
+                })
+                .collect(),
+        };
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("comparison.svg");
+        plot_lap_comparison(&comparison, path.to_str().expect("utf-8 path"))
+            .expect("plot comparison");
+        let svg = std::fs::read_to_string(&path).expect("read svg");
+        assert!(svg.contains("Time Delta"));
+        assert!(svg.contains("Candidate"));
+
+        let empty = LapComparison { samples: Vec::new(), ..comparison };
+        assert!(plot_lap_comparison(&empty, path.to_str().expect("utf-8 path")).is_err());
+    }
+}
@@ -1,19 +1,297 @@
-use std::error::Error;
+use crate::io::{escape_html, json_string};
+use crate::plotting::open_loop::OffTrackInterval;
+use crate::provenance::Provenance;
+use crate::simulation::performance::PerformanceReport;
+use crate::error::{Error, Result};
+use sha2::{Digest, Sha256};
 use std::fs;
+use std::io;
 use std::path::{Path, PathBuf};
 
+/// Additional run details listed in the HTML preview.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct PreviewDetails {
+    /// Intervals where the vehicle was outside the track boundaries
+    pub off_track_intervals: Vec<OffTrackInterval>,
+    /// Build and scenario details needed to reproduce the run
+    pub provenance: Option<Provenance>,
+    /// Lap, sector, and energy summary of the run
+    pub performance: Option<PerformanceReport>,
+    /// Trajectory and telemetry plots embedded in the report
+    pub figures: Vec<ReportFigure>,
+    /// Raw data exports linked from the report, relative to the output directory
+    pub exports: Vec<String>,
+    /// Checksums of the files written alongside the preview
+    pub artifacts: Vec<ArtifactChecksum>,
+}
+
+/// Image embedded in the HTML report
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ReportFigure {
+    pub caption: String,
+    /// Path relative to the output directory
+    pub path: String,
+}
+
+impl ReportFigure {
+    pub fn new(caption: &str, path: &str) -> Self {
+        Self {
+            caption: caption.to_string(),
+            path: path.to_string(),
+        }
+    }
+}
+
+/// Size and SHA-256 checksum of an output file
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct ArtifactChecksum {
+    /// Path relative to the output directory
+    pub path: String,
+    /// Size in bytes
+    pub size: u64,
+    /// SHA-256 digest as lowercase hex, as printed by `sha256sum`
+    pub sha256: String,
+}
+
+impl ArtifactChecksum {
+    /// Checksum of the file at `path` inside `output_dir`
+    pub fn of_file<P: AsRef<Path>>(output_dir: P, path: &str) -> Result<Self> {
+        let mut file = fs::File::open(output_dir.as_ref().join(path))?;
+        let mut hasher = Sha256::new();
+        let size = io::copy(&mut file, &mut hasher)?;
+        let sha256 = hasher
+            .finalize()
+            .iter()
+            .map(|byte| format!("{:02x}", byte))
+            .collect();
+        Ok(Self {
+            path: path.to_string(),
+            size,
+            sha256,
+        })
+    }
+}
+
 pub fn write_open_loop_html_preview<P: AsRef<Path>>(
     output_dir: P,
     video_filename: &str,
     initial_svg: Option<&str>,
     final_svg: Option<&str>,
-) -> Result<PathBuf, Box<dyn Error>> {
-    let output_dir = output_dir.as_ref();
-    let html_path = output_dir.join("open_loop_preview.html");
+) -> Result<PathBuf> {
+    write_open_loop_html_preview_with_details(
+        output_dir,
+        video_filename,
+        initial_svg,
+        final_svg,
+        &PreviewDetails::default(),
+    )
+}
 
+/// Same as `write_open_loop_html_preview`, additionally listing the given run details.
+pub fn write_open_loop_html_preview_with_details<P: AsRef<Path>>(
+    output_dir: P,
+    video_filename: &str,
+    initial_svg: Option<&str>,
+    final_svg: Option<&str>,
+    details: &PreviewDetails,
+) -> Result<PathBuf> {
+    let output_dir = output_dir.as_ref();
     let video_path = output_dir.join(video_filename);
     if !video_path.exists() {
-        return Err(format!("missing video file: {}", video_path.display()).into());
+        return Err(Error::InvalidInput(format!(
+            "missing video file: {}",
+            video_path.display()
+        )));
+    }
+    write_preview(
+        output_dir,
+        PreviewMedia::Video(video_filename),
+        initial_svg,
+        final_svg,
+        details,
+    )
+}
+
+/// Write the HTML preview of a run rendered without a video
+///
+/// Instead of a video, the preview animates the SVG frames in the browser at `fps`
+/// frames per second, with a play/pause button and a slider to step through them.
+///
+/// # Arguments
+/// * `output_dir` - Directory containing the frames; the preview is written there too
+/// * `frames` - Frame files in playback order, relative to `output_dir`
+/// * `fps` - Playback rate in frames per second
+/// * `initial_svg` / `final_svg` - Optional still images of the first and last state
+/// * `details` - Run details listed below the animation
+pub fn write_frames_html_preview<P: AsRef<Path>>(
+    output_dir: P,
+    frames: &[String],
+    fps: u32,
+    initial_svg: Option<&str>,
+    final_svg: Option<&str>,
+    details: &PreviewDetails,
+) -> Result<PathBuf> {
+    let output_dir = output_dir.as_ref();
+    if frames.is_empty() {
+        return Err(Error::invalid_input("no frames to animate"));
+    }
+    if fps == 0 {
+        return Err(Error::invalid_input("fps must be greater than zero"));
+    }
+    if let Some(missing) = frames.iter().find(|frame| !output_dir.join(frame).exists()) {
+        return Err(Error::InvalidInput(format!(
+            "missing frame file: {}",
+            output_dir.join(missing).display()
+        )));
+    }
+    write_preview(
+        output_dir,
+        PreviewMedia::Frames { frames, fps },
+        initial_svg,
+        final_svg,
+        details,
+    )
+}
+
+/// Moving picture at the top of the preview
+enum PreviewMedia<'a> {
+    /// Video file relative to the output directory
+    Video(&'a str),
+    /// Frame files relative to the output directory, animated at `fps`
+    Frames { frames: &'a [String], fps: u32 },
+}
+
+fn write_preview(
+    output_dir: &Path,
+    media: PreviewMedia<'_>,
+    initial_svg: Option<&str>,
+    final_svg: Option<&str>,
+    details: &PreviewDetails,
+) -> Result<PathBuf> {
+    let html_path = output_dir.join("open_loop_preview.html");
+
+    // Sections as (id, title, HTML including the heading), listed in the table of contents
+    let mut sections: Vec<(&str, &str, String)> = Vec::new();
+    let titled = |title: &str, body: String| format!("  <h2>{}</h2>\n{}", title, body);
+
+    let (media_title, mut video) = match media {
+        PreviewMedia::Video(video_filename) => (
+            "Video preview",
+            format!("  <video controls src=\"{}\"></video>\n", escape_html(video_filename)),
+        ),
+        PreviewMedia::Frames { frames, fps } => ("Frame animation", frame_player(frames, fps)),
+    };
+    let initial_ref = initial_svg.and_then(|path| normalize_media_path(output_dir, path));
+    let final_ref = final_svg.and_then(|path| normalize_media_path(output_dir, path));
+    if initial_ref.is_some() || final_ref.is_some() {
+        video.push_str("  <h3>Frame previews</h3>\n");
+        video.push_str("  <div class=\"media\">\n");
+        for (caption, path) in [("Initial state", initial_ref), ("Final state", final_ref)] {
+            if let Some(path) = path {
+                video.push_str(&figure(caption, &path));
+            }
+        }
+        video.push_str("  </div>\n");
+    }
+    sections.push(("video", media_title, titled(media_title, video)));
+
+    let figures: Vec<String> = details
+        .figures
+        .iter()
+        .filter_map(|report_figure| {
+            normalize_media_path(output_dir, &report_figure.path)
+                .map(|path| figure(&report_figure.caption, &path))
+        })
+        .collect();
+    if !figures.is_empty() {
+        let mut body = String::from("  <div class=\"media\">\n");
+        body.extend(figures);
+        body.push_str("  </div>\n");
+        sections.push(("telemetry", "Trajectory and telemetry", titled("Trajectory and telemetry", body)));
+    }
+
+    if let Some(performance) = &details.performance {
+        sections.push(("performance", "Laps and sectors", performance.to_html_section()));
+    }
+
+    let mut off_track = String::new();
+    if details.off_track_intervals.is_empty() {
+        off_track.push_str("  <p>The vehicle stayed on track for the whole run.</p>\n");
+    } else {
+        off_track.push_str("  <table class=\"off-track\">\n");
+        off_track.push_str("    <tr><th>#</th><th>Start (s)</th><th>End (s)</th><th>Duration (s)</th></tr>\n");
+        for (index, interval) in details.off_track_intervals.iter().enumerate() {
+            off_track.push_str(&format!(
+                "    <tr><td>{}</td><td>{:.2}</td><td>{:.2}</td><td>{:.2}</td></tr>\n",
+                index + 1,
+                interval.start,
+                interval.end,
+                interval.end - interval.start
+            ));
+        }
+        off_track.push_str("  </table>\n");
+    }
+    sections.push(("off-track", "Off-track excursions", titled("Off-track excursions", off_track)));
+
+    if let Some(provenance) = &details.provenance {
+        let mut parameters = String::from("  <table class=\"parameters\">\n");
+        let seed = provenance.seed.map_or("none".to_string(), |seed| seed.to_string());
+        let entries = provenance
+            .parameters
+            .iter()
+            .map(|(name, value)| (name.as_str(), value.as_str()));
+        for (name, value) in std::iter::once(("seed", seed.as_str())).chain(entries) {
+            parameters.push_str(&format!(
+                "    <tr><th>{}</th><td>{}</td></tr>\n",
+                escape_html(name),
+                escape_html(value)
+            ));
+        }
+        parameters.push_str("  </table>\n");
+        sections.push(("parameters", "Parameters", titled("Parameters", parameters)));
+
+        let mut build = String::from("  <table class=\"provenance\">\n");
+        build.push_str(&format!(
+            "    <tr><th>crate_version</th><td>{}</td></tr>\n",
+            escape_html(&provenance.crate_version)
+        ));
+        build.push_str(&format!(
+            "    <tr><th>git_commit</th><td>{}</td></tr>\n",
+            escape_html(&provenance.git_commit)
+        ));
+        build.push_str("  </table>\n");
+        sections.push(("provenance", "Provenance", titled("Provenance", build)));
+    }
+
+    let exports: Vec<String> = details
+        .exports
+        .iter()
+        .filter_map(|path| normalize_media_path(output_dir, path))
+        .map(|path| {
+            let path = escape_html(&path);
+            format!("    <li><a href=\"{}\">{}</a></li>\n", path, path)
+        })
+        .collect();
+    if !exports.is_empty() {
+        let mut body = String::from("  <ul class=\"exports\">\n");
+        body.extend(exports);
+        body.push_str("  </ul>\n");
+        sections.push(("exports", "Data exports", titled("Data exports", body)));
+    }
+
+    if !details.artifacts.is_empty() {
+        let mut artifacts = String::from("  <table class=\"artifacts\">\n");
+        artifacts.push_str("    <tr><th>File</th><th>Size (bytes)</th><th>SHA-256</th></tr>\n");
+        for artifact in &details.artifacts {
+            artifacts.push_str(&format!(
+                "    <tr><td>{}</td><td>{}</td><td><code>{}</code></td></tr>\n",
+                escape_html(&artifact.path),
+                artifact.size,
+                artifact.sha256
+            ));
+        }
+        artifacts.push_str("  </table>\n");
+        sections.push(("artifacts", "Artifacts", titled("Artifacts", artifacts)));
     }
 
     let mut html = String::new();
@@ -26,61 +304,79 @@ pub fn write_open_loop_html_preview<P: AsRef<Path>>(
     html.push_str("    .media { display: grid; gap: 16px; grid-template-columns: repeat(auto-fit, minmax(240px, 1fr)); }\n");
     html.push_str("    figure { margin: 0; }\n");
     html.push_str("    img, video { max-width: 100%; height: auto; border: 1px solid #ddd; border-radius: 6px; }\n");
+    html.push_str("    table { border-collapse: collapse; }\n");
+    html.push_str("    th, td { border: 1px solid #ddd; padding: 4px 12px; text-align: right; }\n");
+    html.push_str("    .off-track { color: #c00; }\n");
     html.push_str("  </style>\n");
     html.push_str("</head>\n<body>\n");
     html.push_str("  <h1>Open-loop simulation preview</h1>\n");
-    html.push_str("  <p>Video preview:</p>\n");
-    html.push_str(&format!(
-        "  <video controls src=\"{}\"></video>\n",
-        escape_html(video_filename)
-    ));
-
-    let initial_ref = initial_svg.and_then(|path| normalize_media_path(output_dir, path));
-    let final_ref = final_svg.and_then(|path| normalize_media_path(output_dir, path));
-
-    if initial_ref.is_some() || final_ref.is_some() {
-        html.push_str("  <h2>Frame previews</h2>\n");
-        html.push_str("  <div class=\"media\">\n");
-        if let Some(path) = initial_ref {
-            html.push_str("    <figure>\n");
-            html.push_str("      <figcaption>Initial state</figcaption>\n");
-            html.push_str(&format!(
-                "      <img alt=\"Initial state\" src=\"{}\">\n",
-                escape_html(&path)
-            ));
-            html.push_str("    </figure>\n");
-        }
-        if let Some(path) = final_ref {
-            html.push_str("    <figure>\n");
-            html.push_str("      <figcaption>Final state</figcaption>\n");
-            html.push_str(&format!(
-                "      <img alt=\"Final state\" src=\"{}\">\n",
-                escape_html(&path)
-            ));
-            html.push_str("    </figure>\n");
-        }
-        html.push_str("  </div>\n");
+    html.push_str("  <nav>\n    <ul>\n");
+    for (id, title, _) in &sections {
+        html.push_str(&format!("      <li><a href=\"#{}\">{}</a></li>\n", id, title));
+    }
+    html.push_str("    </ul>\n  </nav>\n");
+    for (id, _, body) in &sections {
+        html.push_str(&format!("  <section id=\"{}\">\n", id));
+        html.push_str(body);
+        html.push_str("  </section>\n");
     }
-
     html.push_str("</body>\n</html>\n");
 
     fs::write(&html_path, html)?;
     Ok(html_path)
 }
 
-fn escape_html(value: &str) -> String {
-    let mut escaped = String::with_capacity(value.len());
-    for ch in value.chars() {
-        match ch {
-            '&' => escaped.push_str("&amp;"),
-            '<' => escaped.push_str("&lt;"),
-            '>' => escaped.push_str("&gt;"),
-            '"' => escaped.push_str("&quot;"),
-            '\'' => escaped.push_str("&#39;"),
-            _ => escaped.push(ch),
-        }
-    }
-    escaped
+/// Image cycling through `frames` at `fps`, with a play/pause button and a slider
+fn frame_player(frames: &[String], fps: u32) -> String {
+    // Keep "</script>" in a file name from closing the script
+    let names: Vec<String> = frames
+        .iter()
+        .map(|frame| json_string(frame).replace('<', "\\u003c"))
+        .collect();
+    let mut html = String::new();
+    html.push_str(&format!(
+        "  <img id=\"frame\" alt=\"Animation frame\" src=\"{}\">\n",
+        escape_html(&frames[0])
+    ));
+    html.push_str("  <p>\n");
+    html.push_str("    <button id=\"play\" type=\"button\">Pause</button>\n");
+    html.push_str(&format!(
+        "    <input id=\"scrub\" type=\"range\" min=\"0\" max=\"{}\" value=\"0\">\n",
+        frames.len() - 1
+    ));
+    html.push_str("  </p>\n");
+    html.push_str("  <script>\n");
+    html.push_str(&format!("    const frames = [{}];\n", names.join(", ")));
+    html.push_str("    const image = document.getElementById(\"frame\");\n");
+    html.push_str("    const play = document.getElementById(\"play\");\n");
+    html.push_str("    const scrub = document.getElementById(\"scrub\");\n");
+    html.push_str("    let index = 0;\n");
+    html.push_str("    let timer = null;\n");
+    html.push_str("    const show = (next) => { index = next; image.src = frames[index]; scrub.value = index; };\n");
+    html.push_str("    const start = () => {\n");
+    html.push_str(&format!(
+        "      timer = setInterval(() => show((index + 1) % frames.length), {});\n",
+        1000.0 / fps as f64
+    ));
+    html.push_str("      play.textContent = \"Pause\";\n");
+    html.push_str("    };\n");
+    html.push_str("    const stop = () => { clearInterval(timer); timer = null; play.textContent = \"Play\"; };\n");
+    html.push_str("    play.addEventListener(\"click\", () => (timer === null ? start() : stop()));\n");
+    html.push_str("    scrub.addEventListener(\"input\", () => { stop(); show(Number(scrub.value)); });\n");
+    html.push_str("    start();\n");
+    html.push_str("  </script>\n");
+    html
+}
+
+/// Image with a caption, for the media grids
+fn figure(caption: &str, path: &str) -> String {
+    let caption = escape_html(caption);
+    format!(
+        "    <figure>\n      <figcaption>{}</figcaption>\n      <img alt=\"{}\" src=\"{}\">\n    </figure>\n",
+        caption,
+        caption,
+        escape_html(path)
+    )
 }
 
 fn normalize_media_path(output_dir: &Path, path: &str) -> Option<String> {
@@ -106,7 +402,12 @@ fn normalize_media_path(output_dir: &Path, path: &str) -> Option<String> {
 
 #[cfg(test)]
 mod tests {
-    use super::write_open_loop_html_preview;
+    use super::{
+        write_frames_html_preview, write_open_loop_html_preview,
+        write_open_loop_html_preview_with_details, ArtifactChecksum, PreviewDetails, ReportFigure,
+    };
+    use crate::plotting::open_loop::OffTrackInterval;
+    use crate::provenance::Provenance;
     use std::fs;
 
     #[test]
@@ -130,4 +431,92 @@ mod tests {
         assert!(html.contains("final_state.svg"));
         assert!(html.contains("Open-loop simulation preview"));
     }
+
+    #[test]
+    fn test_write_open_loop_html_preview_lists_off_track_intervals() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let output_dir = temp_dir.path();
+        fs::write(output_dir.join("open_loop.mp4"), b"video").expect("write video");
+        let details = PreviewDetails {
+            off_track_intervals: vec![OffTrackInterval { start: 1.5, end: 2.25 }],
+            provenance: Some(Provenance::current().with_seed(7).with_parameter("dt", 0.1)),
+            performance: None,
+            artifacts: vec![ArtifactChecksum::of_file(output_dir, "open_loop.mp4").expect("checksum")],
+            ..PreviewDetails::default()
+        };
+        let html_path = write_open_loop_html_preview_with_details(
+            output_dir,
+            "open_loop.mp4",
+            None,
+            None,
+            &details,
+        )
+        .expect("write html preview");
+
+        let html = fs::read_to_string(html_path).expect("read html preview");
+        assert!(html.contains("Off-track excursions"));
+        assert!(html.contains("<td>1.50</td><td>2.25</td><td>0.75</td>"));
+        assert!(html.contains("<tr><th>seed</th><td>7</td></tr>"));
+        assert!(html.contains("<tr><th>dt</th><td>0.1</td></tr>"));
+        assert!(html.contains("<th>git_commit</th>"));
+        // sha256sum of "video"
+        assert!(html.contains(
+            "<td>open_loop.mp4</td><td>5</td>\
+             <td><code>0cab1c9617404faf2b24e221e189ca5945813e14d3f766345b09ca13bbe28ffc</code></td>"
+        ));
+    }
+
+    #[test]
+    fn test_report_sections_link_figures_and_exports() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let output_dir = temp_dir.path();
+        fs::write(output_dir.join("open_loop.mp4"), b"video").expect("write video");
+        fs::write(output_dir.join("telemetry.svg"), b"<svg/>").expect("write telemetry");
+        fs::write(output_dir.join("trajectory.csv"), b"time").expect("write trace");
+        let details = PreviewDetails {
+            figures: vec![
+                ReportFigure::new("Speed and heading", "telemetry.svg"),
+                ReportFigure::new("Missing", "missing.svg"),
+            ],
+            exports: vec!["trajectory.csv".to_string()],
+            ..PreviewDetails::default()
+        };
+        let html_path = write_open_loop_html_preview_with_details(
+            output_dir,
+            "open_loop.mp4",
+            None,
+            None,
+            &details,
+        )
+        .expect("write html preview");
+
+        let html = fs::read_to_string(html_path).expect("read html preview");
+        assert!(html.contains("<li><a href=\"#telemetry\">Trajectory and telemetry</a></li>"));
+        assert!(html.contains("<section id=\"off-track\">"));
+        assert!(html.contains("<img alt=\"Speed and heading\" src=\"telemetry.svg\">"));
+        assert!(!html.contains("missing.svg"));
+        assert!(html.contains("<a href=\"trajectory.csv\">trajectory.csv</a>"));
+        // Sections without content are left out of the report and its contents
+        assert!(!html.contains("#performance") && !html.contains("#artifacts"));
+    }
+
+    #[test]
+    fn test_write_frames_html_preview_checks_frames() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let output_dir = temp_dir.path();
+        fs::write(output_dir.join("step_001.svg"), b"<svg/>").expect("write frame");
+        let frames = vec!["step_001.svg".to_string(), "step_002.svg".to_string()];
+        let details = PreviewDetails::default();
+
+        let err = write_frames_html_preview(output_dir, &frames, 10, None, None, &details)
+            .expect_err("missing frame");
+        assert!(err.to_string().contains("step_002.svg"));
+        assert!(write_frames_html_preview(output_dir, &[], 10, None, None, &details).is_err());
+
+        let html_path = write_frames_html_preview(output_dir, &frames[..1], 4, None, None, &details)
+            .expect("write frames preview");
+        let html = fs::read_to_string(html_path).expect("read html preview");
+        assert!(html.contains("<img id=\"frame\" alt=\"Animation frame\" src=\"step_001.svg\">"));
+        assert!(html.contains("frames.length), 250);"));
+    }
 }
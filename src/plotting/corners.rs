@@ -0,0 +1,133 @@
+use crate::diagnostics;
+use crate::error::Result;
+use crate::models::pose::Pose;
+use crate::plotting::create::{ensure_track_initialized, trail_runs};
+use crate::simulation::lap_analysis::LapAnalysis;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::base_track::Track;
+use plotters::prelude::*;
+
+/// Plot the driven path with the corner analysis of each lap to an SVG file
+///
+/// Apexes are drawn as green circles, braking points as red triangles, and the
+/// minimum speed of each corner pass as an orange cross.
+///
+/// # Arguments
+/// * `track` - Track the run was recorded on
+/// * `result` - Recorded run
+/// * `analysis` - Corner analysis of `result` (see `simulation::lap_analysis::analyze_laps`)
+/// * `filename` - Path to save the plot (e.g., "corners.svg")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_corner_analysis<S: Pose>(
+    track: &dyn Track,
+    result: &SimulationResult<S>,
+    analysis: &LapAnalysis,
+    filename: &str,
+) -> Result<()> {
+    ensure_track_initialized(track)?;
+
+    let root = SVGBackend::new(filename, (800, 800)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let (min_coord, max_coord) = track.get_plot_range();
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Corner Analysis", ("sans-serif", 30))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .build_cartesian_2d(min_coord..max_coord, min_coord..max_coord)?;
+
+    chart.configure_mesh().draw()?;
+
+    for boundary in [track.get_outside_boundary(), track.get_inside_boundary()] {
+        if let Some(&first) = boundary.first() {
+            chart.draw_series(LineSeries::new(
+                boundary.iter().chain(std::iter::once(&first)).map(|&point| point.into()),
+                &BLACK,
+            ))?;
+        }
+    }
+
+    // Plot driven path, with off-track portions in red
+    let trail: Vec<(f64, f64)> = result.states().map(|state| (state.x(), state.y())).collect();
+    let in_track: Vec<bool> = result
+        .snapshots()
+        .iter()
+        .map(|snapshot| snapshot.in_track)
+        .collect();
+    chart
+        .draw_series(trail_runs(&trail, &in_track).into_iter().map(|(off_track, points)| {
+            let color = if off_track { RED } else { BLUE };
+            PathElement::new(points, color.stroke_width(2))
+        }))?
+        .label("Path")
+        .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLUE.stroke_width(2)));
+
+    chart
+        .draw_series(analysis.passes().filter_map(|pass| pass.braking).map(|point| {
+            TriangleMarker::new((point.x, point.y), 7, RED.filled())
+        }))?
+        .label("Braking Point")
+        .legend(|(x, y)| TriangleMarker::new((x + 10, y), 7, RED.filled()));
+    chart
+        .draw_series(analysis.passes().map(|pass| {
+            Cross::new((pass.min_speed.x, pass.min_speed.y), 6, ORANGE_STROKE.stroke_width(3))
+        }))?
+        .label("Minimum Speed")
+        .legend(|(x, y)| Cross::new((x + 10, y), 6, ORANGE_STROKE.stroke_width(3)));
+    chart
+        .draw_series(analysis.passes().map(|pass| {
+            Circle::new((pass.apex.x, pass.apex.y), 5, GREEN.filled())
+        }))?
+        .label("Apex")
+        .legend(|(x, y)| Circle::new((x + 10, y), 5, GREEN.filled()));
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    diagnostics::info(format_args!("Corner analysis plot saved to {}", filename));
+    Ok(())
+}
+
+/// Color of the minimum speed markers
+const ORANGE_STROKE: RGBColor = RGBColor(255, 140, 0);
+
+#[cfg(test)]
+mod tests {
+    use super::plot_corner_analysis;
+    use crate::simulation::lap_analysis::{analyze_laps, AnalysisOptions};
+    use crate::simulation::result::SimulationResult;
+    use crate::tracks::arc_length::ArcLengthTable;
+    use crate::tracks::square::SquareTrack;
+
+    #[test]
+    fn test_plot_corner_analysis() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let center_line = ArcLengthTable::from_track(&track);
+        let mut result = SimulationResult::new();
+        let mut s: f64 = 0.0;
+        for step in 0..100 {
+            let speed = if s.rem_euclid(100.0) > 80.0 { 5.0 } else { 15.0 };
+            result.push(step as f64 * 0.1, center_line.point_at(s).expect("point"), true);
+            s += speed * 0.1;
+        }
+        let analysis =
+            analyze_laps(&track, &result, &AnalysisOptions::default()).expect("analysis");
+        assert!(analysis.passes().any(|pass| pass.braking.is_some()));
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("corners.svg");
+        plot_corner_analysis(&track, &result, &analysis, path.to_str().expect("utf-8 path"))
+            .expect("plot corner analysis");
+        let svg = std::fs::read_to_string(&path).expect("read svg");
+        assert!(svg.contains("Corner Analysis"));
+        assert!(svg.contains("Braking Point"));
+    }
+}
@@ -1,6 +1,12 @@
-use std::error::Error;
+use crate::diagnostics;
+use crate::error::{Error, Result};
+use crate::math::vec2::Vec2;
 use crate::models::base_model::Model;
+use crate::models::pose::Pose;
+use crate::plotting::glyph::VehicleGlyph;
 use crate::tracks::base_track::Track;
+use plotters::chart::ChartState;
+use plotters::coord::types::RangedCoordf64;
 use plotters::prelude::*;
 
 /// Plot both the track and the model to a single SVG file
@@ -16,127 +22,596 @@ pub fn plot<M: Model + ?Sized>(
     track_obj: &dyn Track,
     model_obj: &M,
     filename: &str,
-) -> Result<(), Box<dyn Error>> {
-    let root = SVGBackend::new(filename, (800, 800)).into_drawing_area();
-    root.fill(&WHITE)?;
-    
-    let (min_coord, max_coord) = track_obj.get_plot_range();
-    
-    let mut chart = ChartBuilder::on(&root)
-        .caption("Track and Model", ("sans-serif", 30))
-        .margin(10)
-        .x_label_area_size(30)
-        .y_label_area_size(30)
-        .build_cartesian_2d(min_coord..max_coord, min_coord..max_coord)?;
-    
-    chart.configure_mesh().draw()?;
-    
-    // Plot track outside boundary
-    chart.draw_series(LineSeries::new(
-        track_obj.get_outside_boundary().iter().map(|&(x, y)| (x, y))
-            .chain(std::iter::once(track_obj.get_outside_boundary()[0])),
-        &BLACK,
-    ))?
-    .label("Outside Boundary")
-    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLACK));
-    
-    // Plot track center line (dotted)
-    chart.draw_series(
-        track_obj.get_center_line().iter().map(|&(x, y)| (x, y))
-            .chain(std::iter::once(track_obj.get_center_line()[0]))
-            .collect::<Vec<_>>()
-            .windows(2)
-            .enumerate()
-            .filter(|(i, _)| i % 2 == 0)
-            .flat_map(|(_, w)| {
-                vec![
-                    PathElement::new(vec![w[0], w[1]], RED.stroke_width(2))
-                ]
-            })
-    )?
-    .label("Center Line")
-    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], RED.stroke_width(2)));
-    
-    // Plot track inside boundary
-    chart.draw_series(LineSeries::new(
-        track_obj.get_inside_boundary().iter().map(|&(x, y)| (x, y))
-            .chain(std::iter::once(track_obj.get_inside_boundary()[0])),
-        &BLACK,
-    ))?
-    .label("Inside Boundary")
-    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLACK));
-    
-    // Plot track start position
-    let start_pos = track_obj.get_start_position();
-    chart.draw_series(std::iter::once(Circle::new(
-        (start_pos.0, start_pos.1),
-        5,
-        BLACK.filled(),
-    )))?
-    .label("Start Position")
-    .legend(|(x, y)| Circle::new((x + 10, y), 5, BLACK.filled()));
-    
-    // Plot model
-    let (x, y, yaw) = model_obj.get_position();
-    let (length, width) = model_obj.get_size();
-    
-    // Calculate the four corners of the rectangle in body frame
-    let half_length = length / 2.0;
-    let half_width = width / 2.0;
-    
-    let corners_body = [
-        (half_length, half_width),
-        (-half_length, half_width),
-        (-half_length, -half_width),
-        (half_length, -half_width),
-    ];
-    
-    // Transform corners to world frame using yaw rotation
-    let cos_yaw = yaw.cos();
-    let sin_yaw = yaw.sin();
-    
-    let corners_world: Vec<(f64, f64)> = corners_body
-        .iter()
-        .map(|(x_body, y_body)| {
-            let x_world = x + x_body * cos_yaw - y_body * sin_yaw;
-            let y_world = y + x_body * sin_yaw + y_body * cos_yaw;
-            (x_world, y_world)
-        })
-        .collect();
-    
-    // Draw filled rectangle for model
-    chart.draw_series(std::iter::once(Polygon::new(
-        corners_world.clone(),
-        &BLUE.mix(0.5),
-    )))?
-    .label("Vehicle")
-    .legend(|(x, y)| Rectangle::new([(x, y), (x + 20, y + 10)], BLUE.mix(0.5).filled()));
-    
-    // Draw rectangle outline
-    let mut outline = corners_world.clone();
-    outline.push(corners_world[0]); // Close the polygon
-    chart.draw_series(LineSeries::new(
-        outline,
-        ShapeStyle::from(&BLUE).stroke_width(2),
-    ))?;
-    
-    // Draw orientation arrow (pointing in the direction of positive x in body frame)
-    let arrow_length = length * 0.6;
-    let arrow_x = x + arrow_length * cos_yaw;
-    let arrow_y = y + arrow_length * sin_yaw;
-    
-    chart.draw_series(LineSeries::new(
-        vec![(x, y), (arrow_x, arrow_y)],
-        ShapeStyle::from(&GREEN).stroke_width(3),
-    ))?;
-    
-    chart.configure_series_labels()
-        .position(SeriesLabelPosition::UpperRight)
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
-        .draw()?;
-    
-    root.present()?;
-    println!("Combined plot saved to {}", filename);
+) -> Result<()> {
+    plot_with_size(track_obj, model_obj, filename, DEFAULT_CANVAS_SIZE)
+}
+
+/// Default canvas size in pixels used by `plot`
+pub const DEFAULT_CANVAS_SIZE: (u32, u32) = (800, 800);
+
+/// Reject tracks without a center line, i.e. tracks whose `init` was never called
+pub(crate) fn ensure_track_initialized(track_obj: &dyn Track) -> Result<()> {
+    if track_obj.get_center_line().is_empty() {
+        return Err(Error::NotInitialized(format!(
+            "track '{}'",
+            track_obj.get_track_name()
+        )));
+    }
     Ok(())
 }
+
+/// Plot both the track and the model to a single SVG file with a custom canvas size
+///
+/// Fonts, strokes, and markers are scaled relative to the default 800×800 canvas,
+/// and the x range is widened on non-square canvases so the track keeps its aspect ratio.
+///
+/// # Arguments
+/// * `track_obj` - Reference to the track to plot
+/// * `model_obj` - Reference to the model to plot
+/// * `filename` - Path to save the combined plot (e.g., "output.svg")
+/// * `size` - Canvas size in pixels as (width, height)
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_with_size<M: Model + ?Sized>(
+    track_obj: &dyn Track,
+    model_obj: &M,
+    filename: &str,
+    size: (u32, u32),
+) -> Result<()> {
+    plot_with_trail(track_obj, model_obj, filename, size, &[], &[])
+}
+
+/// Plot the track, the model, and the path driven so far to a single SVG file
+///
+/// Portions of the path where the vehicle left the track are drawn in red.
+///
+/// # Arguments
+/// * `track_obj` - Reference to the track to plot
+/// * `model_obj` - Reference to the model to plot
+/// * `filename` - Path to save the combined plot (e.g., "output.svg")
+/// * `size` - Canvas size in pixels as (width, height)
+/// * `trail` - List of (x, y) positions driven so far
+/// * `in_track` - Whether each trail position was inside the track boundaries
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_with_trail<M: Model + ?Sized>(
+    track_obj: &dyn Track,
+    model_obj: &M,
+    filename: &str,
+    size: (u32, u32),
+    trail: &[(f64, f64)],
+    in_track: &[bool],
+) -> Result<()> {
+    plot_pose_with_trail(
+        track_obj,
+        &model_obj.get_position(),
+        model_obj.get_size(),
+        filename,
+        size,
+        trail,
+        in_track,
+    )
+}
+
+/// Plot the track, a vehicle at the given pose, and the path driven so far to a single SVG file
+///
+/// Unlike `plot_with_trail`, this does not need a `Model`, so any state implementing
+/// `Pose` can be rendered.
+///
+/// # Arguments
+/// * `track_obj` - Reference to the track to plot
+/// * `pose` - Vehicle pose
+/// * `vehicle_size` - Vehicle (length, width) in meters, used when `pose` carries no size
+/// * `filename` - Path to save the combined plot (e.g., "output.svg")
+/// * `size` - Canvas size in pixels as (width, height)
+/// * `trail` - List of (x, y) positions driven so far
+/// * `in_track` - Whether each trail position was inside the track boundaries
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_pose_with_trail<P: Pose + ?Sized>(
+    track_obj: &dyn Track,
+    pose: &P,
+    vehicle_size: (f64, f64),
+    filename: &str,
+    size: (u32, u32),
+    trail: &[(f64, f64)],
+    in_track: &[bool],
+) -> Result<()> {
+    let svg = render_pose_with_trail(track_obj, pose, vehicle_size, size, trail, in_track)?;
+    std::fs::write(filename, svg)?;
+    diagnostics::info(format_args!("Combined plot saved to {}", filename));
+    Ok(())
+}
+
+/// Render the same plot as `plot_pose_with_trail` to an SVG document in memory
+///
+/// Used to stream video frames to ffmpeg without writing them to disk. To render many
+/// frames of the same track, build a `TrackLayer` once and call `render_frame` instead.
+///
+/// # Returns
+/// The SVG document
+pub fn render_pose_with_trail<P: Pose + ?Sized>(
+    track_obj: &dyn Track,
+    pose: &P,
+    vehicle_size: (f64, f64),
+    size: (u32, u32),
+    trail: &[(f64, f64)],
+    in_track: &[bool],
+) -> Result<String> {
+    TrackLayer::new(track_obj, size)?.render_frame(pose, vehicle_size, trail, in_track)
+}
+
+/// Coordinate system of the track plots
+type TrackChartState = ChartState<Cartesian2d<RangedCoordf64, RangedCoordf64>>;
+
+/// Static part of a track plot (caption, mesh, boundaries, center line, start position)
+///
+/// The track is drawn once; each frame only draws the trail, vehicle, and legend on top
+/// of it, in the same coordinate system.
+pub struct TrackLayer {
+    /// SVG document of the static layer, without its closing `</svg>` tag
+    svg: String,
+    chart_state: TrackChartState,
+    size: (u32, u32),
+}
+
+impl TrackLayer {
+    /// Draw the static layer of `track_obj` on a canvas of `size` pixels
+    pub fn new(track_obj: &dyn Track, size: (u32, u32)) -> Result<Self> {
+        if size.0 == 0 || size.1 == 0 {
+            return Err(Error::invalid_input("canvas size must be greater than zero"));
+        }
+        ensure_track_initialized(track_obj)?;
+
+        let scale = canvas_scale(size);
+        let px = move |value: f64| (value * scale).round().max(1.0) as u32;
+
+        let mut svg = String::new();
+        let root = SVGBackend::with_string(&mut svg, size).into_drawing_area();
+        root.fill(&WHITE)?;
+
+        let (min_coord, max_coord) = track_obj.get_plot_range();
+        let (x_min, x_max) = widen_range(min_coord, max_coord, size.0 as f64 / size.1 as f64);
+
+        let mut chart = ChartBuilder::on(&root)
+            .caption("Track and Model", ("sans-serif", 30.0 * scale))
+            .margin(px(10.0))
+            .x_label_area_size(px(30.0))
+            .y_label_area_size(px(30.0))
+            .build_cartesian_2d(x_min..x_max, min_coord..max_coord)?;
+
+        chart.configure_mesh().draw()?;
+
+        // Plot track outside boundary
+        chart.draw_series(LineSeries::new(
+            track_obj
+                .get_outside_boundary()
+                .iter()
+                .map(|&Vec2 { x, y }| (x, y))
+                .chain(std::iter::once(track_obj.get_outside_boundary()[0].into())),
+            BLACK.stroke_width(px(1.0)),
+        ))?;
+
+        // Plot track center line (dotted)
+        chart.draw_series(
+            track_obj
+                .get_center_line()
+                .iter()
+                .map(|&Vec2 { x, y }| (x, y))
+                .chain(std::iter::once(track_obj.get_center_line()[0].into()))
+                .collect::<Vec<_>>()
+                .windows(2)
+                .enumerate()
+                .filter(|(i, _)| i % 2 == 0)
+                .map(|(_, w)| PathElement::new(vec![w[0], w[1]], RED.stroke_width(px(2.0)))),
+        )?;
+
+        // Plot track inside boundary
+        chart.draw_series(LineSeries::new(
+            track_obj
+                .get_inside_boundary()
+                .iter()
+                .map(|&Vec2 { x, y }| (x, y))
+                .chain(std::iter::once(track_obj.get_inside_boundary()[0].into())),
+            BLACK.stroke_width(px(1.0)),
+        ))?;
+
+        // Plot track start position
+        let start_pos = track_obj.get_start_position();
+        chart.draw_series(std::iter::once(Circle::new(
+            (start_pos.0, start_pos.1),
+            px(5.0),
+            BLACK.filled(),
+        )))?;
+
+        let chart_state = chart.into_chart_state();
+        root.present()?;
+        drop(root);
+        let closing = svg.rfind("</svg>").unwrap_or(svg.len());
+        svg.truncate(closing);
+        Ok(Self {
+            svg,
+            chart_state,
+            size,
+        })
+    }
+
+    /// Render one frame: the static layer with the trail, vehicle, and legend on top
+    ///
+    /// # Arguments
+    /// * `pose` - Vehicle pose
+    /// * `vehicle_size` - Vehicle (length, width) in meters, used when `pose` carries no size
+    /// * `trail` - List of (x, y) positions driven so far
+    /// * `in_track` - Whether each trail position was inside the track boundaries
+    ///
+    /// # Returns
+    /// The SVG document of the frame
+    pub fn render_frame<P: Pose + ?Sized>(
+        &self,
+        pose: &P,
+        vehicle_size: (f64, f64),
+        trail: &[(f64, f64)],
+        in_track: &[bool],
+    ) -> Result<String> {
+        self.render_glyph(&VehicleGlyph::from_pose(pose, vehicle_size), trail, in_track)
+    }
+
+    /// Render one frame with `vehicle` drawn on top of the trail
+    ///
+    /// # Arguments
+    /// * `vehicle` - Vehicle rectangle to draw
+    /// * `trail` - List of (x, y) positions driven so far
+    /// * `in_track` - Whether each trail position was inside the track boundaries
+    ///
+    /// # Returns
+    /// The SVG document of the frame
+    pub fn render_glyph(
+        &self,
+        vehicle: &VehicleGlyph,
+        trail: &[(f64, f64)],
+        in_track: &[bool],
+    ) -> Result<String> {
+        if trail.len() != in_track.len() {
+            return Err(Error::InvalidInput(format!(
+                "trail has {} points but in_track has {} flags",
+                trail.len(),
+                in_track.len()
+            )));
+        }
+
+        let scale = canvas_scale(self.size);
+        let px = move |value: f64| (value * scale).round().max(1.0) as u32;
+        let legend = move |value: i32| (value as f64 * scale).round() as i32;
+
+        let mut layer = String::new();
+        let root = SVGBackend::with_string(&mut layer, self.size).into_drawing_area();
+        let mut chart = self.chart_state.clone().restore(&root);
+
+        // Legend entries of the static layer
+        chart
+            .draw_series(std::iter::empty::<PathElement<(f64, f64)>>())?
+            .label("Outside Boundary")
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + legend(20), y)], BLACK.stroke_width(px(1.0)))
+            });
+        chart
+            .draw_series(std::iter::empty::<PathElement<(f64, f64)>>())?
+            .label("Center Line")
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + legend(20), y)], RED.stroke_width(px(2.0)))
+            });
+        chart
+            .draw_series(std::iter::empty::<PathElement<(f64, f64)>>())?
+            .label("Inside Boundary")
+            .legend(move |(x, y)| {
+                PathElement::new(vec![(x, y), (x + legend(20), y)], BLACK.stroke_width(px(1.0)))
+            });
+        chart
+            .draw_series(std::iter::empty::<Circle<(f64, f64), u32>>())?
+            .label("Start Position")
+            .legend(move |(x, y)| Circle::new((x + legend(10), y), px(5.0), BLACK.filled()));
+
+        // Plot driven path, with off-track portions in red
+        let runs = trail_runs(trail, in_track);
+        if runs.iter().any(|(off_track, _)| !off_track) {
+            chart
+                .draw_series(
+                    runs.iter()
+                        .filter(|(off_track, _)| !off_track)
+                        .map(|(_, points)| {
+                            PathElement::new(points.clone(), BLUE.stroke_width(px(2.0)))
+                        }),
+                )?
+                .label("Path")
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + legend(20), y)], BLUE.stroke_width(px(2.0)))
+                });
+        }
+        if runs.iter().any(|(off_track, _)| *off_track) {
+            chart
+                .draw_series(
+                    runs.iter()
+                        .filter(|(off_track, _)| *off_track)
+                        .map(|(_, points)| {
+                            PathElement::new(points.clone(), RED.stroke_width(px(3.0)))
+                        }),
+                )?
+                .label("Off Track")
+                .legend(move |(x, y)| {
+                    PathElement::new(vec![(x, y), (x + legend(20), y)], RED.stroke_width(px(3.0)))
+                });
+        }
+
+        // Plot vehicle
+        let corners_world: Vec<(f64, f64)> = vehicle.corners().map(Vec2::into).to_vec();
+
+        // Draw filled rectangle for model
+        chart
+            .draw_series(std::iter::once(Polygon::new(corners_world.clone(), BLUE.mix(0.5))))?
+            .label("Vehicle")
+            .legend(move |(x, y)| {
+                Rectangle::new([(x, y), (x + legend(20), y + legend(10))], BLUE.mix(0.5).filled())
+            });
+
+        // Draw rectangle outline
+        let mut outline = corners_world.clone();
+        outline.push(corners_world[0]); // Close the polygon
+        chart.draw_series(LineSeries::new(
+            outline,
+            ShapeStyle::from(&BLUE).stroke_width(px(2.0)),
+        ))?;
+
+        // Draw orientation arrow (pointing in the direction of positive x in body frame)
+        chart.draw_series(LineSeries::new(
+            vec![(vehicle.x, vehicle.y), vehicle.heading_tip().into()],
+            ShapeStyle::from(&GREEN).stroke_width(px(3.0)),
+        ))?;
+
+        chart
+            .configure_series_labels()
+            .position(SeriesLabelPosition::UpperRight)
+            .label_font(("sans-serif", 12.0 * scale))
+            .legend_area_size(px(30.0))
+            .background_style(WHITE.mix(0.8))
+            .border_style(BLACK)
+            .draw()?;
+
+        root.present()?;
+        drop(chart);
+        drop(root);
+
+        // Composite: the frame layer's elements go inside the static layer's document
+        let body_start = layer
+            .find("<svg")
+            .and_then(|start| layer[start..].find('>').map(|end| start + end + 1))
+            .unwrap_or(0);
+        let body_end = layer.rfind("</svg>").unwrap_or(layer.len());
+        let mut frame = String::with_capacity(self.svg.len() + body_end - body_start + 7);
+        frame.push_str(&self.svg);
+        frame.push_str(&layer[body_start..body_end]);
+        frame.push_str("</svg>\n");
+        Ok(frame)
+    }
+}
+
+/// Scale of line widths and fonts relative to the default canvas
+fn canvas_scale(size: (u32, u32)) -> f64 {
+    size.0.min(size.1) as f64 / DEFAULT_CANVAS_SIZE.1 as f64
+}
+
+/// Split a trail into polylines of consecutive segments sharing the same off-track flag.
+///
+/// A segment counts as off-track when either of its endpoints is outside the track.
+pub(crate) fn trail_runs(trail: &[(f64, f64)], in_track: &[bool]) -> Vec<(bool, Vec<(f64, f64)>)> {
+    let mut runs: Vec<(bool, Vec<(f64, f64)>)> = Vec::new();
+    for i in 1..trail.len() {
+        let off_track = !in_track[i - 1] || !in_track[i];
+        match runs.last_mut() {
+            Some((run_off_track, points)) if *run_off_track == off_track => points.push(trail[i]),
+            _ => runs.push((off_track, vec![trail[i - 1], trail[i]])),
+        }
+    }
+    runs
+}
+
+/// Ranges (x, y) drawing the square coordinate range with one scale on both axes,
+/// centered on a canvas of `width` by `height` pixels
+///
+/// The range is widened along x on wide canvases and along y on tall ones.
+pub(super) fn uniform_ranges(
+    min_coord: f64,
+    max_coord: f64,
+    width: u32,
+    height: u32,
+) -> ((f64, f64), (f64, f64)) {
+    let aspect = width.max(1) as f64 / height.max(1) as f64;
+    (
+        widen_range(min_coord, max_coord, aspect),
+        widen_range(min_coord, max_coord, 1.0 / aspect),
+    )
+}
+
+/// Widen a square coordinate range along x to match the canvas aspect ratio.
+fn widen_range(min_coord: f64, max_coord: f64, aspect: f64) -> (f64, f64) {
+    if aspect <= 1.0 {
+        return (min_coord, max_coord);
+    }
+    let center = (min_coord + max_coord) / 2.0;
+    let half_span = (max_coord - min_coord) / 2.0 * aspect;
+    (center - half_span, center + half_span)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        plot_with_size, plot_with_trail, render_pose_with_trail, trail_runs, uniform_ranges,
+        widen_range, TrackLayer,
+    };
+    use crate::error::Error;
+    use crate::models::base_model::Model;
+    use crate::models::point_mass::{PointMass, PointMassState};
+    use crate::tracks::base_track::Track;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::geometry::TrackGeometry;
+
+    #[test]
+    fn test_widen_range_keeps_square_canvas() {
+        assert_eq!(widen_range(-10.0, 10.0, 1.0), (-10.0, 10.0));
+    }
+
+    #[test]
+    fn test_uniform_ranges_tall_canvas() {
+        let ((x_min, x_max), (y_min, y_max)) = uniform_ranges(-10.0, 10.0, 100, 200);
+        assert_eq!((x_min, x_max), (-10.0, 10.0));
+        assert!((y_min + 20.0).abs() < 1e-9 && (y_max - 20.0).abs() < 1e-9);
+        // Same meters per pixel on both axes
+        assert!(((x_max - x_min) / 100.0 - (y_max - y_min) / 200.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_widen_range_widescreen() {
+        let (x_min, x_max) = widen_range(-10.0, 10.0, 16.0 / 9.0);
+        assert!((x_max - x_min - 20.0 * 16.0 / 9.0).abs() < 1e-10);
+        assert!((x_min + x_max).abs() < 1e-10);
+    }
+
+    #[test]
+    fn test_track_layer_frames_match_full_render() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let state = (50.0, 0.0, 1.5);
+        let trail = [(50.0, 0.0), (49.0, 5.0), (70.0, 10.0)];
+        let in_track = [true, true, false];
+        let layer = TrackLayer::new(&track, (640, 480)).expect("failed to draw track layer");
+
+        let frame = layer
+            .render_frame(&state, (4.0, 2.0), &trail, &in_track)
+            .expect("failed to render frame");
+        let full = render_pose_with_trail(&track, &state, (4.0, 2.0), (640, 480), &trail, &in_track)
+            .expect("failed to render");
+
+        assert_eq!(frame, full);
+        assert_eq!(frame.matches("<svg").count(), 1);
+        assert!(frame.trim_end().ends_with("</svg>"));
+        for label in ["Outside Boundary", "Center Line", "Start Position", "Off Track", "Vehicle"] {
+            assert!(frame.contains(label), "legend is missing {label}");
+        }
+
+        // The layer is reused as is for later frames
+        let empty = layer
+            .render_frame(&state, (4.0, 2.0), &[], &[])
+            .expect("failed to render frame");
+        assert!(!empty.contains("Off Track"));
+        assert!(frame.starts_with(&layer.svg));
+        assert!(empty.starts_with(&layer.svg));
+    }
+
+    #[test]
+    fn test_plot_with_size_writes_canvas_size() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let model = PointMass::new();
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("large.svg");
+
+        plot_with_size(
+            &track,
+            &model,
+            filename.to_str().expect("temp path not utf-8"),
+            (3840, 2160),
+        )
+        .expect("failed to plot");
+
+        let svg = std::fs::read_to_string(&filename).expect("read svg");
+        assert!(svg.contains("width=\"3840\""));
+        assert!(svg.contains("height=\"2160\""));
+    }
+
+    #[test]
+    fn test_plot_accepts_boxed_trait_objects() {
+        let track: Box<dyn Track> = Box::new(CircleTrack::new(50.0, 10.0, 100));
+        let model: Box<dyn Model<State = PointMassState>> = Box::new(PointMass::new());
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("boxed.svg");
+
+        plot_with_size(
+            &track,
+            &model,
+            filename.to_str().expect("temp path not utf-8"),
+            (800, 800),
+        )
+        .expect("failed to plot boxed track and model");
+        assert!(filename.exists());
+    }
+
+    #[test]
+    fn test_trail_runs_splits_off_track_segments() {
+        let trail = [(0.0, 0.0), (1.0, 0.0), (2.0, 0.0), (3.0, 0.0), (4.0, 0.0)];
+        let in_track = [true, true, false, true, true];
+        let runs = trail_runs(&trail, &in_track);
+
+        assert_eq!(runs.len(), 3);
+        assert_eq!(runs[0], (false, vec![(0.0, 0.0), (1.0, 0.0)]));
+        assert_eq!(runs[1], (true, vec![(1.0, 0.0), (2.0, 0.0), (3.0, 0.0)]));
+        assert_eq!(runs[2], (false, vec![(3.0, 0.0), (4.0, 0.0)]));
+    }
+
+    #[test]
+    fn test_plot_with_trail_marks_off_track() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let model = PointMass::new();
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("trail.svg");
+
+        plot_with_trail(
+            &track,
+            &model,
+            filename.to_str().expect("temp path not utf-8"),
+            (800, 800),
+            &[(50.0, 0.0), (58.0, 5.0), (50.0, 10.0)],
+            &[true, false, true],
+        )
+        .expect("failed to plot");
+
+        let svg = std::fs::read_to_string(&filename).expect("read svg");
+        assert!(svg.contains("Off Track"));
+    }
+
+    #[test]
+    fn test_plot_with_trail_rejects_mismatched_flags() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let model = PointMass::new();
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("trail.svg");
+
+        let err = plot_with_trail(
+            &track,
+            &model,
+            filename.to_str().expect("temp path not utf-8"),
+            (800, 800),
+            &[(50.0, 0.0)],
+            &[],
+        )
+        .expect_err("expected error for mismatched trail");
+        assert!(err.to_string().contains("in_track"));
+    }
+
+    #[test]
+    fn test_plot_errors_are_typed() {
+        let model = PointMass::new();
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("plot.svg");
+        let filename = filename.to_str().expect("temp path not utf-8");
+
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let err = plot_with_size(&track, &model, filename, (0, 800)).expect_err("zero size");
+        assert!(matches!(err, Error::InvalidInput(_)));
+
+        let empty =
+            TrackGeometry::new("Empty", Vec::new(), Vec::new(), Vec::new(), (0.0, 0.0, 0.0));
+        let err = plot_with_size(&empty, &model, filename, (800, 800)).expect_err("empty track");
+        assert!(matches!(err, Error::NotInitialized(_)));
+        assert_eq!(err.to_string(), "track 'Empty' is not initialized");
+    }
+}
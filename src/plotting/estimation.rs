@@ -0,0 +1,134 @@
+use super::telemetry::value_range;
+use crate::diagnostics;
+use crate::error::{Error, Result};
+use crate::estimation::EstimationSample;
+use plotters::prelude::*;
+
+/// Plot the estimation error of a run over time to an SVG file
+///
+/// One panel each for the position, heading, and speed error, with the ±2σ bounds
+/// the filter reported as dashed lines. A consistent filter keeps its error mostly
+/// within the bounds; a constant offset hints at an unmodeled sensor bias.
+///
+/// # Arguments
+/// * `samples` - Logged estimates and truth, e.g. from `EstimationLog::samples`
+/// * `filename` - Path to save the plot (e.g., "estimation.svg")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_estimation_error(samples: &[EstimationSample], filename: &str) -> Result<()> {
+    if samples.len() < 2 {
+        return Err(Error::invalid_input(
+            "at least two samples are needed to plot the estimation error",
+        ));
+    }
+    let times: Vec<f64> = samples.iter().map(|sample| sample.time).collect();
+    let time_range = times[0]..times[times.len() - 1].max(times[0] + 1e-3);
+    let errors = |error: fn(&EstimationSample) -> f64| -> Vec<f64> {
+        samples.iter().map(error).collect()
+    };
+    let bounds = |bound: fn(&[f64; 4]) -> f64| -> Vec<f64> {
+        samples.iter().map(|sample| 2.0 * bound(&sample.std_devs)).collect()
+    };
+
+    let root = SVGBackend::new(filename, (900, 800)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let panels = root.split_evenly((3, 1));
+
+    let panel_data = [
+        (
+            "Position error",
+            "Error (m)",
+            errors(EstimationSample::position_error),
+            bounds(|std_devs| std_devs[0].hypot(std_devs[1])),
+            BLUE,
+        ),
+        (
+            "Heading error",
+            "Error (rad)",
+            errors(EstimationSample::heading_error),
+            bounds(|std_devs| std_devs[2]),
+            GREEN,
+        ),
+        (
+            "Speed error",
+            "Error (m/s)",
+            errors(EstimationSample::speed_error),
+            bounds(|std_devs| std_devs[3]),
+            MAGENTA,
+        ),
+    ];
+    for (area, (caption, y_desc, values, bound, color)) in panels.iter().zip(panel_data) {
+        let extremes: Vec<f64> =
+            values.iter().chain(bound.iter()).map(|value| value.abs()).collect();
+        let (_, max) = value_range(&extremes);
+        let mut chart = ChartBuilder::on(area)
+            .caption(caption, ("sans-serif", 22))
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d(time_range.clone(), -max..max)?;
+        chart
+            .configure_mesh()
+            .x_desc("Time (s)")
+            .y_desc(y_desc)
+            .draw()?;
+
+        for sign in [1.0, -1.0] {
+            chart.draw_series(DashedLineSeries::new(
+                times.iter().zip(&bound).map(|(&time, &bound)| (time, sign * bound)),
+                6,
+                4,
+                BLACK.mix(0.5).stroke_width(1),
+            ))?;
+        }
+        chart.draw_series(LineSeries::new(
+            times.iter().copied().zip(values.iter().copied()),
+            color.stroke_width(2),
+        ))?;
+    }
+
+    root.present()?;
+    diagnostics::info(format_args!("Estimation error plot saved to {}", filename));
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plot_estimation_error;
+    use crate::estimation::EstimationSample;
+    use crate::models::point_mass::PointMassState;
+
+    #[test]
+    fn test_plot_estimation_error() {
+        let samples: Vec<EstimationSample> = (0..50)
+            .map(|step| {
+                let time = step as f64 * 0.1;
+                let truth = PointMassState {
+                    x: time * 10.0,
+                    y: 0.0,
+                    vx: 10.0,
+                    vy: 0.0,
+                    yaw: 0.0,
+                };
+                let estimate = PointMassState {
+                    y: 0.3 * time.sin(),
+                    ..truth.clone()
+                };
+                EstimationSample {
+                    time,
+                    truth,
+                    estimate,
+                    std_devs: [0.5, 0.5, 0.05, 0.2],
+                }
+            })
+            .collect();
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("estimation.svg");
+        plot_estimation_error(&samples, path.to_str().expect("utf-8 path"))
+            .expect("plot estimation error");
+        let svg = std::fs::read_to_string(&path).expect("read svg");
+        assert!(svg.contains("Position error") && svg.contains("Speed error"));
+        assert!(plot_estimation_error(&samples[..1], path.to_str().expect("utf-8 path")).is_err());
+    }
+}
@@ -0,0 +1,141 @@
+//! Vehicle shape drawn by the track plots.
+//!
+//! Renderers only need a pose and a footprint to draw a vehicle, so they consume a
+//! `VehicleGlyph` instead of a model or a model state.
+
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+
+/// Fraction of the vehicle length covered by the orientation arrow
+const HEADING_ARROW_RATIO: f64 = 0.6;
+
+/// Vehicle rectangle with its orientation, in world frame meters
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VehicleGlyph {
+    /// World frame x position of the vehicle center in meters
+    pub x: f64,
+    /// World frame y position of the vehicle center in meters
+    pub y: f64,
+    /// Orientation angle in radians
+    pub yaw: f64,
+    /// Vehicle length in meters (along the body x axis)
+    pub length: f64,
+    /// Vehicle width in meters (along the body y axis)
+    pub width: f64,
+}
+
+impl VehicleGlyph {
+    /// Glyph of a vehicle at `pose`
+    ///
+    /// # Arguments
+    /// * `pose` - Vehicle pose
+    /// * `default_size` - Vehicle (length, width) in meters, used when `pose` carries no size
+    pub fn from_pose<P: Pose + ?Sized>(pose: &P, default_size: (f64, f64)) -> Self {
+        let (x, y, yaw) = pose.pose();
+        let (length, width) = pose.size().unwrap_or(default_size);
+        Self {
+            x,
+            y,
+            yaw,
+            length,
+            width,
+        }
+    }
+
+    /// The same glyph moved to another pose
+    pub fn with_pose(self, (x, y, yaw): (f64, f64, f64)) -> Self {
+        Self { x, y, yaw, ..self }
+    }
+
+    /// Corners of the vehicle rectangle in world frame
+    ///
+    /// # Returns
+    /// Front left, rear left, rear right, and front right corners in meters
+    pub fn corners(&self) -> [Vec2; 4] {
+        let half_length = self.length / 2.0;
+        let half_width = self.width / 2.0;
+        [
+            Vec2::new(half_length, half_width),
+            Vec2::new(-half_length, half_width),
+            Vec2::new(-half_length, -half_width),
+            Vec2::new(half_length, -half_width),
+        ]
+        .map(|body| self.body_to_world(body))
+    }
+
+    /// Tip of the orientation arrow drawn from the vehicle center
+    pub fn heading_tip(&self) -> Vec2 {
+        self.body_to_world(Vec2::new(self.length * HEADING_ARROW_RATIO, 0.0))
+    }
+
+    /// Transform a body frame point to world frame
+    fn body_to_world(&self, body: Vec2) -> Vec2 {
+        Vec2::new(self.x, self.y) + body.rotate(self.yaw)
+    }
+}
+
+impl Pose for VehicleGlyph {
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    fn y(&self) -> f64 {
+        self.y
+    }
+
+    fn yaw(&self) -> f64 {
+        self.yaw
+    }
+
+    fn size(&self) -> Option<(f64, f64)> {
+        Some((self.length, self.width))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::VehicleGlyph;
+    use crate::models::point_mass::PointMassState;
+    use crate::models::pose::Pose;
+    use std::f64::consts::FRAC_PI_2;
+
+    #[test]
+    fn test_glyph_from_pose_uses_default_size() {
+        let state = PointMassState {
+            x: 1.0,
+            y: 2.0,
+            vx: 0.0,
+            vy: 0.0,
+            yaw: 0.5,
+        };
+        let glyph = VehicleGlyph::from_pose(&state, (4.0, 2.0));
+        assert_eq!((glyph.x, glyph.y, glyph.yaw), (1.0, 2.0, 0.5));
+        assert_eq!(glyph.size(), Some((4.0, 2.0)));
+
+        // A glyph carries its own size, so converting it again keeps it
+        assert_eq!(VehicleGlyph::from_pose(&glyph, (1.0, 1.0)), glyph);
+        let moved = glyph.with_pose((3.0, 4.0, 0.0));
+        assert_eq!(moved.pose(), (3.0, 4.0, 0.0));
+        assert_eq!(moved.length, 4.0);
+    }
+
+    #[test]
+    fn test_glyph_corners_follow_yaw() {
+        let glyph = VehicleGlyph {
+            x: 10.0,
+            y: 5.0,
+            yaw: FRAC_PI_2,
+            length: 4.0,
+            width: 2.0,
+        };
+        let expected = [(9.0, 7.0), (9.0, 3.0), (11.0, 3.0), (11.0, 7.0)];
+        for (corner, expected) in glyph.corners().iter().zip(expected) {
+            assert!((corner.x - expected.0).abs() < 1e-9);
+            assert!((corner.y - expected.1).abs() < 1e-9);
+        }
+
+        let tip = glyph.heading_tip();
+        assert!((tip.x - 10.0).abs() < 1e-9);
+        assert!((tip.y - 7.4).abs() < 1e-9);
+    }
+}
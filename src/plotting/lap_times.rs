@@ -0,0 +1,236 @@
+use plotters::prelude::*;
+use crate::diagnostics;
+use crate::error::{Error, Result};
+
+/// Plot the lap times of a single multi-lap run as a bar chart
+///
+/// Each lap is drawn as a bar with a line connecting the lap times; the best
+/// (fastest) lap is highlighted in green.
+///
+/// # Arguments
+/// * `lap_times` - Lap times in seconds, in lap order
+/// * `filename` - Path to save the plot (e.g., "lap_times.svg")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_lap_times(lap_times: &[f64], filename: &str) -> Result<()> {
+    let best = best_lap_index(lap_times)
+        .ok_or_else(|| Error::invalid_input("no valid lap times to plot"))?;
+    let (min_time, max_time) = lap_time_range(lap_times.iter().copied());
+
+    let root = SVGBackend::new(filename, (800, 600)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Lap Times", ("sans-serif", 30))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0.5f64..(lap_times.len() as f64 + 0.5), min_time..max_time)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Lap")
+        .y_desc("Lap time (s)")
+        .x_labels(lap_times.len().min(20))
+        .x_label_formatter(&|lap| format!("{:.0}", lap))
+        .draw()?;
+
+    chart.draw_series(
+        lap_times
+            .iter()
+            .enumerate()
+            .filter(|(index, time)| *index != best && time.is_finite())
+            .map(|(index, &time)| lap_bar(index, time, min_time, BLUE.mix(0.5).filled())),
+    )?
+    .label("Lap")
+    .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], BLUE.mix(0.5).filled()));
+
+    chart.draw_series(std::iter::once(lap_bar(
+        best,
+        lap_times[best],
+        min_time,
+        GREEN.filled(),
+    )))?
+    .label(format!("Best lap ({}: {:.3} s)", best + 1, lap_times[best]))
+    .legend(|(x, y)| Rectangle::new([(x, y - 5), (x + 20, y + 5)], GREEN.filled()));
+
+    chart.draw_series(LineSeries::new(
+        lap_points(lap_times),
+        ShapeStyle::from(&BLACK).stroke_width(2),
+    ))?;
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    diagnostics::info(format_args!("Lap time plot saved to {}", filename));
+    Ok(())
+}
+
+/// Plot the lap time evolution of several runs (e.g., Monte Carlo runs) as lines
+///
+/// Each run is drawn as its own line; the best lap across all runs is
+/// highlighted with a green marker.
+///
+/// # Arguments
+/// * `runs` - Lap times in seconds for each run, in lap order
+/// * `filename` - Path to save the plot (e.g., "lap_times_runs.svg")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_lap_times_runs<L: AsRef<[f64]>>(
+    runs: &[L],
+    filename: &str,
+) -> Result<()> {
+    let (best_run, best_lap) = runs
+        .iter()
+        .enumerate()
+        .filter_map(|(run, laps)| best_lap_index(laps.as_ref()).map(|lap| (run, lap)))
+        .min_by(|a, b| runs[a.0].as_ref()[a.1].total_cmp(&runs[b.0].as_ref()[b.1]))
+        .ok_or_else(|| Error::invalid_input("no valid lap times to plot"))?;
+    let best_time = runs[best_run].as_ref()[best_lap];
+
+    let max_laps = runs.iter().map(|laps| laps.as_ref().len()).max().unwrap_or(0);
+    let (min_time, max_time) =
+        lap_time_range(runs.iter().flat_map(|laps| laps.as_ref().iter().copied()));
+
+    let root = SVGBackend::new(filename, (800, 600)).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    let mut chart = ChartBuilder::on(&root)
+        .caption("Lap Time Evolution", ("sans-serif", 30))
+        .margin(10)
+        .x_label_area_size(40)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0.5f64..(max_laps as f64 + 0.5), min_time..max_time)?;
+
+    chart
+        .configure_mesh()
+        .x_desc("Lap")
+        .y_desc("Lap time (s)")
+        .x_labels(max_laps.min(20))
+        .x_label_formatter(&|lap| format!("{:.0}", lap))
+        .draw()?;
+
+    for (run, laps) in runs.iter().enumerate() {
+        let color = Palette99::pick(run).mix(0.8);
+        chart.draw_series(LineSeries::new(lap_points(laps.as_ref()), color.stroke_width(2)))?;
+        chart.draw_series(
+            lap_points(laps.as_ref()).map(|point| Circle::new(point, 3, color.filled())),
+        )?;
+    }
+
+    chart
+        .draw_series(std::iter::once(Circle::new(
+            ((best_lap + 1) as f64, best_time),
+            7,
+            GREEN.filled(),
+        )))?
+        .label(format!(
+            "Best lap (run {}, lap {}: {:.3} s)",
+            best_run + 1,
+            best_lap + 1,
+            best_time
+        ))
+        .legend(|(x, y)| Circle::new((x + 10, y), 5, GREEN.filled()));
+
+    chart
+        .configure_series_labels()
+        .position(SeriesLabelPosition::UpperRight)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
+        .draw()?;
+
+    root.present()?;
+    diagnostics::info(format_args!("Lap time evolution plot saved to {}", filename));
+    Ok(())
+}
+
+/// Index of the fastest finite lap time, if any.
+fn best_lap_index(lap_times: &[f64]) -> Option<usize> {
+    lap_times
+        .iter()
+        .enumerate()
+        .filter(|(_, time)| time.is_finite())
+        .min_by(|a, b| a.1.total_cmp(b.1))
+        .map(|(index, _)| index)
+}
+
+/// Y-axis range covering all finite lap times with some headroom.
+fn lap_time_range<I: Iterator<Item = f64>>(times: I) -> (f64, f64) {
+    let (min_time, max_time) = times
+        .filter(|time| time.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), time| {
+            (lo.min(time), hi.max(time))
+        });
+    let span = (max_time - min_time).max(max_time.abs() * 0.05).max(1e-3);
+    ((min_time - span * 0.5).max(0.0), max_time + span * 0.5)
+}
+
+fn lap_points(lap_times: &[f64]) -> impl Iterator<Item = (f64, f64)> + '_ {
+    lap_times
+        .iter()
+        .enumerate()
+        .filter(|(_, time)| time.is_finite())
+        .map(|(index, &time)| ((index + 1) as f64, time))
+}
+
+fn lap_bar(index: usize, time: f64, base: f64, style: ShapeStyle) -> Rectangle<(f64, f64)> {
+    let lap = (index + 1) as f64;
+    Rectangle::new([(lap - 0.35, base), (lap + 0.35, time)], style)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{best_lap_index, plot_lap_times, plot_lap_times_runs};
+
+    #[test]
+    fn test_best_lap_index_ignores_non_finite() {
+        assert_eq!(best_lap_index(&[]), None);
+        assert_eq!(best_lap_index(&[f64::NAN]), None);
+        assert_eq!(best_lap_index(&[31.2, f64::NAN, 30.4, 30.9]), Some(2));
+    }
+
+    #[test]
+    fn test_plot_lap_times() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("lap_times.svg");
+
+        let result = plot_lap_times(
+            &[32.5, 31.8, 31.2, 31.4],
+            filename.to_str().expect("temp path not utf-8"),
+        );
+        assert!(result.is_ok(), "Failed to plot lap times: {:?}", result.err());
+
+        let svg = std::fs::read_to_string(&filename).expect("read lap time plot");
+        assert!(svg.contains("Best lap (3: 31.200 s)"));
+    }
+
+    #[test]
+    fn test_plot_lap_times_rejects_empty() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("lap_times.svg");
+
+        let err = plot_lap_times(&[], filename.to_str().expect("temp path not utf-8"))
+            .expect_err("expected error for empty lap times");
+        assert!(err.to_string().contains("no valid lap times"));
+    }
+
+    #[test]
+    fn test_plot_lap_times_runs() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("lap_times_runs.svg");
+        let runs = vec![vec![32.5, 31.8, 31.2], vec![32.1, 31.0, 31.6], vec![33.0]];
+
+        let result = plot_lap_times_runs(&runs, filename.to_str().expect("temp path not utf-8"));
+        assert!(result.is_ok(), "Failed to plot lap times: {:?}", result.err());
+
+        let svg = std::fs::read_to_string(&filename).expect("read lap time plot");
+        assert!(svg.contains("Best lap (run 2, lap 2: 31.000 s)"));
+    }
+}
@@ -1,11 +1,48 @@
 pub mod track;
 pub mod model;
 pub mod create;
+pub mod glyph;
+#[cfg(feature = "video")]
 pub mod video;
+#[cfg(feature = "raster")]
+pub mod raster;
+#[cfg(feature = "video")]
 pub mod conversion;
+#[cfg(feature = "video")]
 pub mod open_loop;
+pub mod lap_times;
+pub mod corners;
+pub mod comparison;
+pub mod telemetry;
+pub mod estimation;
+pub mod thumbnail;
+pub mod trajectory;
 
-pub use create::plot;
-pub use video::create_video_from_svgs;
-pub use conversion::write_open_loop_html_preview;
-pub use open_loop::{render_open_loop_outputs, OpenLoopArtifacts};
+pub use create::{
+    plot, plot_pose_with_trail, plot_with_size, plot_with_trail, render_pose_with_trail,
+    TrackLayer,
+};
+pub use glyph::VehicleGlyph;
+#[cfg(feature = "video")]
+pub use video::{
+    create_video_from_svgs, create_video_from_svgs_with_options, EncodingOptions, SvgVideoEncoder,
+    VideoCodec,
+};
+#[cfg(feature = "video")]
+pub use conversion::{
+    write_frames_html_preview, write_open_loop_html_preview,
+    write_open_loop_html_preview_with_details, ArtifactChecksum, PreviewDetails, ReportFigure,
+};
+#[cfg(feature = "video")]
+pub use open_loop::{
+    frames, frames_with_options, off_track_intervals, render_open_loop_outputs,
+    render_open_loop_outputs_with_options, render_simulation_outputs, Frame, FrameKind, Frames,
+    OffTrackInterval, OpenLoopArtifacts, RenderOptions, DEFAULT_VEHICLE_SIZE,
+};
+pub use lap_times::{plot_lap_times, plot_lap_times_runs};
+pub use corners::plot_corner_analysis;
+pub use comparison::plot_lap_comparison;
+pub use telemetry::plot_telemetry;
+pub use estimation::plot_estimation_error;
+pub use thumbnail::{plot_run_thumbnail, plot_track_thumbnail};
+pub use trajectory::plot_trajectory;
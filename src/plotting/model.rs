@@ -1,5 +1,5 @@
 use plotters::prelude::*;
-use std::error::Error;
+use crate::error::Result;
 use crate::models::base_model::Model;
 
 /// Plot a model as a rectangle to an SVG file
@@ -10,7 +10,7 @@ use crate::models::base_model::Model;
 /// 
 /// # Returns
 /// Result indicating success or error
-pub fn plot_model<M: Model + ?Sized>(model: &M, path: &str) -> Result<(), Box<dyn Error>> {
+pub fn plot_model<M: Model + ?Sized>(model: &M, path: &str) -> Result<()> {
     let (x, y, yaw) = model.get_position();
     let (length, width) = model.get_size();
     
@@ -63,7 +63,7 @@ pub fn plot_model<M: Model + ?Sized>(model: &M, path: &str) -> Result<(), Box<dy
     // Draw filled rectangle
     chart.draw_series(std::iter::once(Polygon::new(
         corners_world.clone(),
-        &RGBColor(150, 150, 150).mix(0.7),
+        RGBColor(150, 150, 150).mix(0.7),
     )))?;
     
     // Draw rectangle outline
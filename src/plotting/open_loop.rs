@@ -1,17 +1,125 @@
-use crate::models::base_model::Model;
-use crate::models::point_mass::{PointMass, PointMassState};
+use crate::io::output_dir::{ArtifactKind, OutputDirectory};
+use crate::math::angle::Angle;
+use crate::models::point_mass::PointMassState;
+use crate::models::pose::Pose;
 use crate::plotting;
+use crate::io::geojson::GeoJsonWriter;
+use crate::plotting::conversion::{ArtifactChecksum, PreviewDetails, ReportFigure};
+use crate::plotting::create::TrackLayer;
+use crate::plotting::glyph::VehicleGlyph;
+use crate::plotting::video::{EncodingOptions, SvgVideoEncoder, VideoCodec};
+use crate::provenance::Provenance;
+use crate::simulation::performance::PerformanceReport;
+use crate::simulation::result::{SimulationResult, StateSnapshot};
 use crate::tracks::base_track::Track;
-use std::error::Error;
+use crate::error::{Error, Result};
 use std::fs;
 use std::path::{Path, PathBuf};
 
-#[derive(Debug)]
+#[derive(Debug, Clone, PartialEq)]
 pub struct OpenLoopArtifacts {
     pub initial_svg: PathBuf,
     pub final_svg: PathBuf,
-    pub video_path: PathBuf,
+    /// Encoded video, `None` when rendered with `RenderOptions::frames_only`
+    pub video_path: Option<PathBuf>,
     pub html_path: PathBuf,
+    /// Step frames, populated when `RenderOptions::keep_frames` or `frames_only` is set.
+    pub step_svgs: Vec<PathBuf>,
+    /// Trajectory and telemetry plots embedded in the HTML report
+    pub report_svgs: Vec<PathBuf>,
+    /// Raw data exports linked from the HTML report (CSV trace and GeoJSON)
+    pub data_exports: Vec<PathBuf>,
+}
+
+impl OpenLoopArtifacts {
+    /// Record the artifacts in `outputs`, the step frames as intermediates
+    ///
+    /// `OutputDirectory::clean` (or `Simulation::clean` on a simulation owning `outputs`)
+    /// then deletes the step frames and keeps the state plots, the video, and the preview.
+    /// Without a video the preview animates the step frames, so they are kept too.
+    pub fn register(&self, outputs: &mut OutputDirectory) {
+        let finals = [&self.initial_svg, &self.final_svg, &self.html_path]
+            .into_iter()
+            .chain(&self.video_path)
+            .chain(&self.report_svgs)
+            .chain(&self.data_exports);
+        for path in finals {
+            outputs.register(path, ArtifactKind::Final);
+        }
+        let step_kind = if self.video_path.is_some() {
+            ArtifactKind::Intermediate
+        } else {
+            ArtifactKind::Final
+        };
+        for path in &self.step_svgs {
+            outputs.register(path, step_kind);
+        }
+    }
+}
+
+/// Options controlling how open-loop outputs are rendered.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RenderOptions {
+    /// Also write the intermediate `step_XXX.svg` frames, which are otherwise only streamed
+    /// to ffmpeg.
+    pub keep_frames: bool,
+    /// Output video resolution as (width, height), e.g. (1920, 1080) or (3840, 2160).
+    pub resolution: (u32, u32),
+    /// Frames are rendered at `resolution * supersampling` and downscaled during encoding.
+    pub supersampling: u32,
+    /// Interpolate position and yaw between recorded states at each frame timestamp
+    /// instead of snapping to the next recorded state.
+    pub interpolate_frames: bool,
+    /// Video codec; also selects the container (`open_loop.mp4` or `open_loop.webm`).
+    pub codec: VideoCodec,
+    /// Provenance listed in the HTML preview; defaults to `Provenance::current()`.
+    pub provenance: Option<Provenance>,
+    /// Skip video encoding, so ffmpeg is not needed: every frame is written as
+    /// `step_XXX.svg` and the HTML preview animates them in the browser.
+    pub frames_only: bool,
+}
+
+/// Canvas size in pixels of the trajectory plot in the HTML report
+const REPORT_PLOT_SIZE: (u32, u32) = (600, 600);
+
+/// Vehicle (length, width) in meters drawn for states that carry no size
+pub const DEFAULT_VEHICLE_SIZE: (f64, f64) = (4.5, 2.0);
+
+impl Default for RenderOptions {
+    fn default() -> Self {
+        Self {
+            keep_frames: false,
+            resolution: plotting::create::DEFAULT_CANVAS_SIZE,
+            supersampling: 1,
+            interpolate_frames: true,
+            codec: VideoCodec::default(),
+            provenance: None,
+            frames_only: false,
+        }
+    }
+}
+
+impl RenderOptions {
+    fn frame_size(&self) -> (u32, u32) {
+        (
+            self.resolution.0 * self.supersampling,
+            self.resolution.1 * self.supersampling,
+        )
+    }
+
+    fn encoding_options(&self) -> EncodingOptions {
+        let resolution = if self.supersampling > 1
+            || self.resolution != plotting::create::DEFAULT_CANVAS_SIZE
+        {
+            Some(self.resolution)
+        } else {
+            None
+        };
+        EncodingOptions {
+            resolution,
+            codec: Some(self.codec),
+        }
+    }
 }
 
 pub fn render_open_loop_outputs<P: AsRef<Path>>(
@@ -22,79 +130,491 @@ pub fn render_open_loop_outputs<P: AsRef<Path>>(
     dt: f64,
     duration: f64,
     fps: u32,
-) -> Result<OpenLoopArtifacts, Box<dyn Error>> {
-    if states.is_empty() {
-        return Err("no states to render".into());
-    }
-    if fps == 0 {
-        return Err("fps must be greater than zero".into());
-    }
+) -> Result<OpenLoopArtifacts> {
+    render_open_loop_outputs_with_options(
+        output_dir,
+        track,
+        states,
+        model_size,
+        dt,
+        duration,
+        fps,
+        &RenderOptions::default(),
+    )
+}
+
+/// Same as `render_open_loop_outputs`, with explicit rendering options.
+#[allow(clippy::too_many_arguments)]
+pub fn render_open_loop_outputs_with_options<P: AsRef<Path>>(
+    output_dir: P,
+    track: &dyn Track,
+    states: &[PointMassState],
+    model_size: (f64, f64),
+    dt: f64,
+    duration: f64,
+    fps: u32,
+    options: &RenderOptions,
+) -> Result<OpenLoopArtifacts> {
+    let result: SimulationResult<&PointMassState> = build_state_times(states.len(), dt, duration)
+        .into_iter()
+        .zip(states)
+        .map(|(time, state)| StateSnapshot {
+            time,
+            state,
+            in_track: track.is_in_track(state.x, state.y),
+        })
+        .collect();
+
+    render_simulation_outputs(output_dir, track, &result, model_size, fps, options)
+}
+
+/// Render the initial/final SVGs, the video, and the HTML preview for a simulation result.
+///
+/// Works for any model state implementing `Pose`; frame timing and off-track flags
+/// come from the snapshots.
+///
+/// # Arguments
+/// * `output_dir` - Directory receiving the artifacts
+/// * `track` - Track the simulation ran on
+/// * `result` - Recorded simulation result
+/// * `vehicle_size` - Vehicle (length, width) in meters, used for states that carry no size
+/// * `fps` - Video frame rate
+/// * `options` - Rendering options
+pub fn render_simulation_outputs<S, P>(
+    output_dir: P,
+    track: &dyn Track,
+    result: &SimulationResult<S>,
+    vehicle_size: (f64, f64),
+    fps: u32,
+    options: &RenderOptions,
+) -> Result<OpenLoopArtifacts>
+where
+    S: Pose,
+    P: AsRef<Path>,
+{
+    let mut frames = frames_with_options(track, result, vehicle_size, fps, options)?;
 
     let output_dir = output_dir.as_ref();
     fs::create_dir_all(output_dir)?;
 
     let initial_svg = output_dir.join("initial_state.svg");
     let final_svg = output_dir.join("final_state.svg");
-    let video_path = output_dir.join("open_loop.mp4");
+    let video_filename = format!("open_loop.{}", options.codec.extension());
+    let video_path = output_dir.join(&video_filename);
+
+    // Frames are rendered one at a time and streamed to ffmpeg; only the initial and
+    // final states are kept as files unless the step frames are requested or replace
+    // the video.
+    let mut encoder = if options.frames_only {
+        None
+    } else {
+        Some(SvgVideoEncoder::start(&video_path, fps, &options.encoding_options())?)
+    };
+    let mut step_svgs: Vec<PathBuf> = Vec::new();
+    for frame in &mut frames {
+        let frame = frame?;
+        if let Some(encoder) = encoder.as_mut() {
+            encoder.push_frame(&frame.svg)?;
+        }
+        match frame.kind {
+            FrameKind::Initial => fs::write(&initial_svg, &frame.svg)?,
+            FrameKind::Final => fs::write(&final_svg, &frame.svg)?,
+            FrameKind::Step if options.keep_frames || options.frames_only => {
+                let step_svg = output_dir.join(format!("step_{:03}.svg", frame.index));
+                fs::write(&step_svg, &frame.svg)?;
+                step_svgs.push(step_svg);
+            }
+            FrameKind::Step => {}
+        }
+    }
+    if let Some(encoder) = encoder {
+        encoder.finish()?;
+    }
 
-    let mut model = PointMass::new();
-    model.set_size(model_size.0, model_size.1);
+    let state_times: Vec<f64> = result.times().collect();
+    let in_track: Vec<bool> = result
+        .snapshots()
+        .iter()
+        .map(|snapshot| snapshot.in_track)
+        .collect();
+    let mut provenance = options
+        .provenance
+        .clone()
+        .unwrap_or_default()
+        .with_parameter("track.name", track.get_track_name())
+        .with_parameter("model.length", vehicle_size.0)
+        .with_parameter("model.width", vehicle_size.1);
+    // Runs rendered without a scenario still document their timing
+    if let [first, second, ..] = state_times[..] {
+        provenance
+            .parameters
+            .entry("dt".to_string())
+            .or_insert_with(|| (second - first).to_string());
+    }
+    provenance
+        .parameters
+        .entry("duration".to_string())
+        .or_insert_with(|| result.duration().to_string());
+
+    // Report plots and raw data next to the video
+    let mut figures = vec![ReportFigure::new("Trajectory", "trajectory.svg")];
+    let trail: Vec<(f64, f64)> = result
+        .snapshots()
+        .iter()
+        .map(|snapshot| (snapshot.state.x(), snapshot.state.y()))
+        .collect();
+    plotting::plot_run_thumbnail(
+        track,
+        &trail,
+        &in_track,
+        &output_dir.join("trajectory.svg").to_string_lossy(),
+        REPORT_PLOT_SIZE,
+    )?;
+    if result.len() >= 2 {
+        plotting::plot_telemetry(result, &output_dir.join("telemetry.svg").to_string_lossy())?;
+        figures.push(ReportFigure::new("Speed and heading", "telemetry.svg"));
+    }
+    result.write_pose_csv(output_dir.join("trajectory.csv"), &provenance)?;
+    let mut geojson = GeoJsonWriter::new(None);
+    geojson.add_track(track);
+    geojson.add_trajectory("trajectory", result);
+    fs::write(output_dir.join("trajectory.geojson"), geojson.to_geojson())?;
+    let exports = vec!["trajectory.csv".to_string(), "trajectory.geojson".to_string()];
+
+    let mut artifacts = Vec::new();
+    let report_files = figures
+        .iter()
+        .map(|figure| figure.path.as_str())
+        .chain(exports.iter().map(String::as_str));
+    let media = if options.frames_only { None } else { Some(video_filename.as_str()) };
+    for path in ["initial_state.svg", "final_state.svg"]
+        .into_iter()
+        .chain(media)
+        .chain(report_files)
+    {
+        artifacts.push(ArtifactChecksum::of_file(output_dir, path)?);
+    }
 
-    let initial_state = &states[0];
-    model.set_position(initial_state.x, initial_state.y, initial_state.yaw);
-    plotting::plot(track, &model, path_as_str(&initial_svg)?)?;
+    let report_svgs = figures.iter().map(|figure| output_dir.join(&figure.path)).collect();
+    let data_exports = exports.iter().map(|path| output_dir.join(path)).collect();
+    let details = PreviewDetails {
+        off_track_intervals: off_track_intervals(&state_times, &in_track),
+        provenance: Some(provenance),
+        performance: PerformanceReport::builder(track)
+            .run("Vehicle", result)
+            .build()
+            .ok()
+            .filter(PerformanceReport::has_laps),
+        figures,
+        exports,
+        artifacts,
+    };
+    let html_path = if options.frames_only {
+        let animation: Vec<String> = std::iter::once("initial_state.svg".to_string())
+            .chain(step_svgs.iter().filter_map(|path| {
+                path.file_name().map(|name| name.to_string_lossy().into_owned())
+            }))
+            .chain(std::iter::once("final_state.svg".to_string()))
+            .collect();
+        plotting::write_frames_html_preview(
+            output_dir,
+            &animation,
+            fps,
+            Some("initial_state.svg"),
+            Some("final_state.svg"),
+            &details,
+        )?
+    } else {
+        plotting::write_open_loop_html_preview_with_details(
+            output_dir,
+            &video_filename,
+            Some("initial_state.svg"),
+            Some("final_state.svg"),
+            &details,
+        )?
+    };
 
-    let frame_times = scheduled_frame_times(duration, fps);
-    let state_times = build_state_times(states.len(), dt, duration);
-    let mut step_svgs: Vec<PathBuf> = Vec::new();
-    let mut frame_index = 1usize;
-    let mut next_frame_index = 0usize;
+    Ok(OpenLoopArtifacts {
+        initial_svg,
+        final_svg,
+        video_path: (!options.frames_only).then_some(video_path),
+        html_path,
+        step_svgs,
+        report_svgs,
+        data_exports,
+    })
+}
 
-    for (state, time) in states.iter().zip(state_times.iter()) {
-        while next_frame_index < frame_times.len()
-            && *time + 1e-9 >= frame_times[next_frame_index]
-        {
-            let step_svg = output_dir.join(format!("step_{:03}.svg", frame_index));
-            model.set_position(state.x, state.y, state.yaw);
-            plotting::plot(track, &model, path_as_str(&step_svg)?)?;
-            step_svgs.push(step_svg);
-            frame_index += 1;
-            next_frame_index += 1;
+/// Position of a frame in the video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum FrameKind {
+    /// First frame, showing the initial state without a trail
+    Initial,
+    /// Frame at a multiple of the frame interval
+    Step,
+    /// Last frame, showing the final state with the full trail
+    Final,
+}
+
+/// Video frame rendered by `Frames`.
+#[derive(Debug, Clone, PartialEq)]
+pub struct Frame {
+    /// Position of the frame in the video, starting at 0 for the initial frame
+    pub index: usize,
+    /// Simulation time shown in the frame in seconds
+    pub time: f64,
+    pub kind: FrameKind,
+    /// SVG document of the frame
+    pub svg: String,
+}
+
+/// Lazy iterator over the video frames of a simulation result.
+///
+/// Yields the initial frame, one frame per frame interval, and the final frame. Each
+/// frame is rendered when requested, on top of a track layer drawn once, so consumers
+/// (video encoders, live viewers) hold only one frame at a time. Iteration stops after
+/// the first rendering error.
+pub struct Frames<'a> {
+    track: &'a dyn Track,
+    layer: TrackLayer,
+    glyphs: Vec<VehicleGlyph>,
+    state_times: Vec<f64>,
+    in_track: Vec<bool>,
+    frame_times: Vec<f64>,
+    interpolate: bool,
+    /// Index of the next frame; `None` once the final frame was produced
+    next: Option<usize>,
+}
+
+/// Lazily render the video frames of `result` at `fps` frames per second.
+///
+/// Uses the default `RenderOptions` and `DEFAULT_VEHICLE_SIZE` for states that carry
+/// no size.
+pub fn frames<'a, S: Pose>(
+    track: &'a dyn Track,
+    result: &SimulationResult<S>,
+    fps: u32,
+) -> Result<Frames<'a>> {
+    frames_with_options(track, result, DEFAULT_VEHICLE_SIZE, fps, &RenderOptions::default())
+}
+
+/// Same as `frames`, with an explicit vehicle size and rendering options.
+///
+/// Only the resolution, supersampling, and frame interpolation options apply to frames.
+///
+/// # Arguments
+/// * `track` - Track the simulation ran on
+/// * `result` - Recorded simulation result
+/// * `vehicle_size` - Vehicle (length, width) in meters, used for states that carry no size
+/// * `fps` - Video frame rate
+/// * `options` - Rendering options
+pub fn frames_with_options<'a, S: Pose>(
+    track: &'a dyn Track,
+    result: &SimulationResult<S>,
+    vehicle_size: (f64, f64),
+    fps: u32,
+    options: &RenderOptions,
+) -> Result<Frames<'a>> {
+    if result.is_empty() {
+        return Err(Error::invalid_input("no states to render"));
+    }
+    if fps == 0 {
+        return Err(Error::invalid_input("fps must be greater than zero"));
+    }
+    if options.supersampling == 0 {
+        return Err(Error::invalid_input("supersampling must be greater than zero"));
+    }
+
+    Ok(Frames {
+        track,
+        layer: TrackLayer::new(track, options.frame_size())?,
+        glyphs: result
+            .states()
+            .map(|state| VehicleGlyph::from_pose(state, vehicle_size))
+            .collect(),
+        state_times: result.times().collect(),
+        in_track: result
+            .snapshots()
+            .iter()
+            .map(|snapshot| snapshot.in_track)
+            .collect(),
+        frame_times: scheduled_frame_times(result.duration(), fps),
+        interpolate: options.interpolate_frames,
+        next: Some(0),
+    })
+}
+
+impl Frames<'_> {
+    /// Render the frame at `index` (0 is the initial frame, the final frame follows the
+    /// last scheduled frame time that lies within the result)
+    fn render(&self, index: usize) -> Result<Frame> {
+        let last = self.glyphs.len() - 1;
+        if index == 0 {
+            let svg = self.layer.render_glyph(&self.glyphs[0], &[], &[])?;
+            return Ok(Frame {
+                index,
+                time: self.state_times[0],
+                kind: FrameKind::Initial,
+                svg,
+            });
+        }
+
+        let step = self.frame_times.get(index - 1).and_then(|&frame_time| {
+            frame_pose(&self.glyphs, &self.state_times, frame_time, self.interpolate)
+                .map(|frame| (frame_time, frame))
+        });
+        let Some((time, frame)) = step else {
+            let svg =
+                self.layer
+                    .render_glyph(&self.glyphs[last], &self.trail(last), &self.in_track)?;
+            return Ok(Frame {
+                index,
+                time: self.state_times[last],
+                kind: FrameKind::Final,
+                svg,
+            });
+        };
+
+        let mut trail = self.trail(frame.last_state);
+        let mut in_track = self.in_track[..=frame.last_state].to_vec();
+        if frame.interpolated {
+            trail.push((frame.glyph.x, frame.glyph.y));
+            in_track.push(self.track.is_in_track(frame.glyph.x, frame.glyph.y));
         }
+        let svg = self.layer.render_glyph(&frame.glyph, &trail, &in_track)?;
+        Ok(Frame {
+            index,
+            time,
+            kind: FrameKind::Step,
+            svg,
+        })
     }
 
-    let final_state = states
-        .last()
-        .expect("states should not be empty when rendering output");
-    model.set_position(final_state.x, final_state.y, final_state.yaw);
-    plotting::plot(track, &model, path_as_str(&final_svg)?)?;
+    /// Driven positions up to and including the state at `last_state`
+    fn trail(&self, last_state: usize) -> Vec<(f64, f64)> {
+        self.glyphs[..=last_state]
+            .iter()
+            .map(|glyph| (glyph.x, glyph.y))
+            .collect()
+    }
+}
 
-    let mut frames: Vec<PathBuf> = Vec::with_capacity(step_svgs.len() + 2);
-    frames.push(initial_svg.clone());
-    frames.extend(step_svgs.iter().cloned());
-    frames.push(final_svg.clone());
+impl Iterator for Frames<'_> {
+    type Item = Result<Frame>;
 
-    plotting::create_video_from_svgs(&frames, &video_path, fps)?;
+    fn next(&mut self) -> Option<Self::Item> {
+        let index = self.next?;
+        let frame = self.render(index);
+        self.next = match &frame {
+            Ok(frame) if frame.kind != FrameKind::Final => Some(index + 1),
+            _ => None,
+        };
+        Some(frame)
+    }
 
-    let html_path = plotting::write_open_loop_html_preview(
-        output_dir,
-        "open_loop.mp4",
-        Some("initial_state.svg"),
-        Some("final_state.svg"),
-    )?;
+    fn size_hint(&self) -> (usize, Option<usize>) {
+        match self.next {
+            // Scheduled frames past the last state are skipped, so this is an upper bound
+            Some(index) => (1, Some(self.frame_times.len() + 2 - index)),
+            None => (0, Some(0)),
+        }
+    }
+}
 
-    for step_svg in &step_svgs {
-        fs::remove_file(step_svg)?;
+/// Time interval during which the vehicle was outside the track boundaries.
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct OffTrackInterval {
+    /// Time of the first off-track state in seconds
+    pub start: f64,
+    /// Time of the first state back on track (or the last state) in seconds
+    pub end: f64,
+}
+
+/// Collect the intervals where `in_track` is false.
+///
+/// # Arguments
+/// * `times` - Time of each state in seconds
+/// * `in_track` - Whether each state was inside the track boundaries
+pub fn off_track_intervals(times: &[f64], in_track: &[bool]) -> Vec<OffTrackInterval> {
+    let mut intervals = Vec::new();
+    let mut start: Option<f64> = None;
+    for (&time, &inside) in times.iter().zip(in_track.iter()) {
+        match (start, inside) {
+            (None, false) => start = Some(time),
+            (Some(begin), true) => {
+                intervals.push(OffTrackInterval { start: begin, end: time });
+                start = None;
+            }
+            _ => {}
+        }
     }
+    if let (Some(begin), Some(&end)) = (start, times.last()) {
+        intervals.push(OffTrackInterval { start: begin, end });
+    }
+    intervals
+}
 
-    Ok(OpenLoopArtifacts {
-        initial_svg,
-        final_svg,
-        video_path,
-        html_path,
+/// Vehicle shown in a video frame.
+struct FramePose {
+    /// Index of the last recorded state at or before the frame time
+    last_state: usize,
+    /// Vehicle at the frame time, with the size of `last_state`
+    glyph: VehicleGlyph,
+    /// Whether `glyph` lies strictly between `last_state` and the next recorded state
+    interpolated: bool,
+}
+
+/// Compute the vehicle glyph at a frame timestamp.
+///
+/// Returns `None` if the frame lies after the last recorded state.
+fn frame_pose(
+    glyphs: &[VehicleGlyph],
+    state_times: &[f64],
+    frame_time: f64,
+    interpolate: bool,
+) -> Option<FramePose> {
+    // Times are sorted, so binary search for the first state at or after the frame
+    let next = state_times.partition_point(|&time| time + 1e-9 < frame_time);
+    if next == state_times.len() {
+        return None;
+    }
+    let next_glyph = glyphs[next];
+    let snapped = FramePose {
+        last_state: next,
+        glyph: next_glyph,
+        interpolated: false,
+    };
+    if !interpolate || next == 0 || (state_times[next] - frame_time).abs() <= 1e-9 {
+        return Some(snapped);
+    }
+
+    let prev = next - 1;
+    let span = state_times[next] - state_times[prev];
+    if span <= 0.0 {
+        return Some(snapped);
+    }
+    let alpha = ((frame_time - state_times[prev]) / span).clamp(0.0, 1.0);
+    let prev_glyph = glyphs[prev];
+    Some(FramePose {
+        last_state: prev,
+        glyph: prev_glyph.with_pose((
+            lerp(prev_glyph.x, next_glyph.x, alpha),
+            lerp(prev_glyph.y, next_glyph.y, alpha),
+            lerp_angle(prev_glyph.yaw, next_glyph.yaw, alpha),
+        )),
+        interpolated: true,
     })
 }
 
+fn lerp(a: f64, b: f64, alpha: f64) -> f64 {
+    a + (b - a) * alpha
+}
+
+/// Interpolate between two angles along the shortest arc.
+fn lerp_angle(a: f64, b: f64, alpha: f64) -> f64 {
+    a + Angle::wrap(b - a) * alpha
+}
+
 fn build_state_times(states_len: usize, dt: f64, duration: f64) -> Vec<f64> {
     if states_len == 0 {
         return Vec::new();
@@ -162,20 +682,26 @@ fn scheduled_frame_times(duration: f64, fps: u32) -> Vec<f64> {
     times
 }
 
-fn path_as_str(path: &Path) -> Result<&str, std::io::Error> {
-    path.to_str().ok_or_else(|| {
-        std::io::Error::new(
-            std::io::ErrorKind::InvalidData,
-            "path contains non-UTF-8 characters",
-        )
-    })
-}
-
 #[cfg(test)]
 mod tests {
-    use super::{render_open_loop_outputs, scheduled_frame_times};
+    use super::{
+        frame_pose, frames, frames_with_options, lerp_angle, off_track_intervals,
+        render_open_loop_outputs, render_open_loop_outputs_with_options, scheduled_frame_times,
+        FrameKind, OffTrackInterval, RenderOptions,
+    };
+    use crate::io::output_dir::OutputDirectory;
+    use crate::simulation::result::SimulationResult;
     use crate::tracks::circle::CircleTrack;
     use crate::models::point_mass::PointMassState;
+    use crate::models::pose::Pose;
+    use crate::plotting::glyph::VehicleGlyph;
+
+    fn glyphs(poses: &[(f64, f64, f64)]) -> Vec<VehicleGlyph> {
+        poses
+            .iter()
+            .map(|pose| VehicleGlyph::from_pose(pose, (4.0, 2.0)))
+            .collect()
+    }
 
     #[test]
     fn test_scheduled_frame_times_zero_duration() {
@@ -204,6 +730,115 @@ mod tests {
         assert!((times[9] - 1.0).abs() < 1e-9);
     }
 
+    #[test]
+    fn test_frame_pose_interpolates_between_states() {
+        let poses = glyphs(&[(0.0, 0.0, 0.0), (1.0, 2.0, 0.4)]);
+        let times = [0.0, 0.3];
+        let frame = frame_pose(&poses, &times, 0.1, true).expect("frame in range");
+
+        assert!(frame.interpolated);
+        assert_eq!(frame.last_state, 0);
+        assert!((frame.glyph.x - 1.0 / 3.0).abs() < 1e-9);
+        assert!((frame.glyph.y - 2.0 / 3.0).abs() < 1e-9);
+        assert!((frame.glyph.yaw - 0.4 / 3.0).abs() < 1e-9);
+        assert_eq!(frame.glyph.size(), Some((4.0, 2.0)));
+    }
+
+    #[test]
+    fn test_frame_pose_snaps_when_disabled() {
+        let poses = glyphs(&[(0.0, 0.0, 0.0), (1.0, 2.0, 0.4)]);
+        let times = [0.0, 0.3];
+        let frame = frame_pose(&poses, &times, 0.1, false).expect("frame in range");
+
+        assert!(!frame.interpolated);
+        assert_eq!(frame.last_state, 1);
+        assert_eq!(frame.glyph.pose(), (1.0, 2.0, 0.4));
+    }
+
+    #[test]
+    fn test_frame_pose_after_last_state() {
+        assert!(frame_pose(&glyphs(&[(0.0, 0.0, 0.0)]), &[0.0], 0.5, true).is_none());
+    }
+
+    #[test]
+    fn test_frames_are_rendered_in_order() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let mut result = SimulationResult::new();
+        for step in 0..=10 {
+            let angle = step as f64 * 0.02;
+            result.push(step as f64 * 0.1, (50.0 * angle.cos(), 50.0 * angle.sin(), 1.6), true);
+        }
+
+        let mut frames = frames(&track, &result, 10).expect("frames");
+        assert_eq!(frames.size_hint(), (1, Some(11)));
+        let initial = frames.next().expect("initial frame").expect("rendered frame");
+        assert_eq!((initial.index, initial.kind), (0, FrameKind::Initial));
+        assert!(initial.svg.trim_end().ends_with("</svg>"));
+
+        let rest: Vec<_> = frames
+            .map(|frame| frame.expect("rendered frame"))
+            .collect();
+        // Frames every 0.1 s strictly inside the run, then the final state
+        assert_eq!(rest.len(), 10);
+        for (offset, frame) in rest[..9].iter().enumerate() {
+            assert_eq!(frame.index, offset + 1);
+            assert_eq!(frame.kind, FrameKind::Step);
+            assert!((frame.time - (offset + 1) as f64 * 0.1).abs() < 1e-9);
+        }
+        assert_eq!(rest[9].kind, FrameKind::Final);
+        assert_eq!(rest[9].index, 10);
+        assert!((rest[9].time - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_frames_reject_invalid_input() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let empty: SimulationResult<(f64, f64, f64)> = SimulationResult::new();
+        let err = frames(&track, &empty, 10).err().expect("expected error for empty result");
+        assert!(err.to_string().contains("no states"));
+
+        let mut result = SimulationResult::new();
+        result.push(0.0, (50.0, 0.0, 1.6), true);
+        let options = RenderOptions {
+            supersampling: 0,
+            ..RenderOptions::default()
+        };
+        let err = frames_with_options(&track, &result, (4.5, 2.0), 10, &options)
+            .err()
+            .expect("expected error for zero supersampling");
+        assert!(err.to_string().contains("supersampling"));
+
+        // A single state yields the initial and final frames
+        let count = frames(&track, &result, 10).expect("frames").count();
+        assert_eq!(count, 2);
+    }
+
+    #[test]
+    fn test_lerp_angle_wraps_shortest_arc() {
+        let yaw = lerp_angle(3.0, -3.0, 0.5);
+        assert!((yaw - (3.0 + (2.0 * std::f64::consts::PI - 6.0) / 2.0)).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_off_track_intervals() {
+        let times = [0.0, 0.1, 0.2, 0.3, 0.4, 0.5];
+        let in_track = [true, false, false, true, true, false];
+        let intervals = off_track_intervals(&times, &in_track);
+
+        assert_eq!(
+            intervals,
+            vec![
+                OffTrackInterval { start: 0.1, end: 0.3 },
+                OffTrackInterval { start: 0.5, end: 0.5 },
+            ]
+        );
+    }
+
+    #[test]
+    fn test_off_track_intervals_all_on_track() {
+        assert!(off_track_intervals(&[0.0, 0.1], &[true, true]).is_empty());
+    }
+
     #[test]
     fn test_render_open_loop_outputs_rejects_empty_states() {
         let track = CircleTrack::new(50.0, 10.0, 100);
@@ -246,4 +881,84 @@ mod tests {
         .expect_err("expected error for zero fps");
         assert!(err.to_string().contains("fps"));
     }
+
+    #[test]
+    fn test_render_frames_only_without_ffmpeg() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let states: Vec<PointMassState> = (0..11)
+            .map(|step| PointMassState {
+                x: 50.0,
+                y: step as f64,
+                vx: 10.0,
+                vy: 0.0,
+                yaw: std::f64::consts::FRAC_PI_2,
+            })
+            .collect();
+        let options = RenderOptions {
+            frames_only: true,
+            ..RenderOptions::default()
+        };
+
+        let artifacts = render_open_loop_outputs_with_options(
+            temp_dir.path(),
+            &track,
+            &states,
+            (4.5, 2.0),
+            0.1,
+            1.0,
+            10,
+            &options,
+        )
+        .expect("render without a video");
+        assert_eq!(artifacts.video_path, None);
+        assert_eq!(artifacts.step_svgs.len(), 9);
+        assert!(artifacts.report_svgs.iter().all(|path| path.exists()));
+        assert!(artifacts.data_exports.iter().all(|path| path.exists()));
+        let csv = std::fs::read_to_string(temp_dir.path().join("trajectory.csv")).expect("read csv");
+        let mut lines = csv.lines().skip_while(|line| line.starts_with('#'));
+        assert_eq!(lines.next(), Some("time,x,y,yaw,in_track"));
+        assert_eq!(lines.count(), states.len());
+
+        let html = std::fs::read_to_string(&artifacts.html_path).expect("read preview");
+        assert!(html.contains("Frame animation"));
+        assert!(html.contains("const frames = [\"initial_state.svg\", \"step_001.svg\""));
+        assert!(html.contains("<td>trajectory.csv</td>"));
+        assert!(!html.contains("<video"));
+
+        let mut outputs = OutputDirectory::new(temp_dir.path());
+        artifacts.register(&mut outputs);
+        outputs.clean().expect("clean outputs");
+        assert!(artifacts.step_svgs.iter().all(|path| path.exists()));
+    }
+
+    #[test]
+    fn test_render_open_loop_outputs_rejects_zero_supersampling() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let states = vec![PointMassState {
+            x: 0.0,
+            y: 0.0,
+            vx: 0.0,
+            vy: 0.0,
+            yaw: 0.0,
+        }];
+        let options = RenderOptions {
+            supersampling: 0,
+            ..RenderOptions::default()
+        };
+
+        let err = render_open_loop_outputs_with_options(
+            temp_dir.path(),
+            &track,
+            &states,
+            (4.5, 2.0),
+            0.1,
+            1.0,
+            10,
+            &options,
+        )
+        .expect_err("expected error for zero supersampling");
+        assert!(err.to_string().contains("supersampling"));
+    }
 }
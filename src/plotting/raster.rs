@@ -0,0 +1,91 @@
+//! In-process SVG rasterization (`raster` feature).
+//!
+//! Many ffmpeg builds lack SVG decoding (librsvg), so with this feature video frames
+//! are rasterized with resvg and piped to ffmpeg as raw RGB pixels instead.
+
+use crate::error::{Error, Result};
+use resvg::tiny_skia::{Pixmap, Transform};
+use resvg::usvg::{Options, Tree};
+
+/// Rasterized frame in RGBA order, row by row from the top
+#[derive(Debug, Clone, PartialEq)]
+pub struct RasterFrame {
+    pub width: u32,
+    pub height: u32,
+    /// `width * height * 4` bytes (premultiplied alpha, opaque for plotted frames)
+    pub rgba: Vec<u8>,
+}
+
+/// SVG rasterizer with the system fonts loaded once
+pub struct SvgRasterizer {
+    options: Options<'static>,
+}
+
+impl RasterFrame {
+    /// Pixels in RGB order without the alpha channel (`width * height * 3` bytes)
+    ///
+    /// Plotted frames are opaque, so this is what ffmpeg is fed as `rgb24`.
+    pub fn rgb(&self) -> Vec<u8> {
+        self.rgba
+            .chunks_exact(4)
+            .flat_map(|pixel| [pixel[0], pixel[1], pixel[2]])
+            .collect()
+    }
+}
+
+impl SvgRasterizer {
+    /// Create a rasterizer, loading the system fonts used for captions and labels
+    pub fn new() -> Self {
+        let mut options = Options::default();
+        options.fontdb_mut().load_system_fonts();
+        Self { options }
+    }
+
+    /// Rasterize an SVG document at its intrinsic size
+    pub fn rasterize(&self, svg: &str) -> Result<RasterFrame> {
+        let tree = Tree::from_str(svg, &self.options)
+            .map_err(|err| Error::Render(format!("failed to parse SVG frame: {}", err)))?;
+        let size = tree.size().to_int_size();
+        let mut pixmap = Pixmap::new(size.width(), size.height()).ok_or_else(|| {
+            Error::Render(format!(
+                "cannot allocate a {}x{} frame",
+                size.width(),
+                size.height()
+            ))
+        })?;
+        resvg::render(&tree, Transform::default(), &mut pixmap.as_mut());
+        Ok(RasterFrame {
+            width: size.width(),
+            height: size.height(),
+            rgba: pixmap.take(),
+        })
+    }
+}
+
+impl Default for SvgRasterizer {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SvgRasterizer;
+
+    #[test]
+    fn test_rasterize_svg() {
+        let svg = r#"<svg xmlns="http://www.w3.org/2000/svg" width="4" height="2">
+            <rect width="4" height="2" fill="white"/>
+            <rect x="2" width="2" height="2" fill="blue"/>
+        </svg>"#;
+        let frame = SvgRasterizer::new().rasterize(svg).expect("rasterize");
+        assert_eq!((frame.width, frame.height), (4, 2));
+        assert_eq!(frame.rgba.len(), 4 * 2 * 4);
+        assert_eq!(&frame.rgba[..4], &[255, 255, 255, 255]);
+        assert_eq!(&frame.rgba[12..16], &[0, 0, 255, 255]);
+        assert_eq!(frame.rgb().len(), 4 * 2 * 3);
+        assert_eq!(&frame.rgb()[9..12], &[0, 0, 255]);
+
+        assert!(SvgRasterizer::new().rasterize("not an svg").is_err());
+    }
+}
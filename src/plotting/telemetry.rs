@@ -0,0 +1,108 @@
+use crate::diagnostics;
+use crate::error::{Error, Result};
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::simulation::lap_analysis::estimate_speeds;
+use crate::simulation::result::SimulationResult;
+use plotters::prelude::*;
+
+/// Plot the speed and heading of a run over time to an SVG file
+///
+/// Speeds are estimated from consecutive positions, so the states of any model
+/// implementing `Pose` can be plotted. Stretches where the vehicle was off the track are
+/// shaded in red.
+///
+/// # Arguments
+/// * `result` - Recorded simulation result
+/// * `filename` - Path to save the plot (e.g., "telemetry.svg")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_telemetry<S: Pose>(result: &SimulationResult<S>, filename: &str) -> Result<()> {
+    let snapshots = result.snapshots();
+    if snapshots.len() < 2 {
+        return Err(Error::invalid_input("at least two snapshots are needed to plot telemetry"));
+    }
+    let times: Vec<f64> = result.times().collect();
+    let positions: Vec<Vec2> = snapshots
+        .iter()
+        .map(|snapshot| Vec2::new(snapshot.state.x(), snapshot.state.y()))
+        .collect();
+    let speeds = estimate_speeds(&positions, &times);
+    let headings: Vec<f64> = snapshots.iter().map(|snapshot| snapshot.state.yaw()).collect();
+    let time_range = times[0]..times[times.len() - 1].max(times[0] + 1e-3);
+
+    let root = SVGBackend::new(filename, (900, 600)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let panels = root.split_evenly((2, 1));
+
+    let panel_data = [
+        ("Speed", "Speed (m/s)", &speeds, BLUE),
+        ("Heading", "Yaw (rad)", &headings, GREEN),
+    ];
+    for (area, (caption, y_desc, values, color)) in panels.iter().zip(panel_data) {
+        let (min, max) = value_range(values);
+        let mut chart = ChartBuilder::on(area)
+            .caption(caption, ("sans-serif", 22))
+            .margin(10)
+            .x_label_area_size(30)
+            .y_label_area_size(50)
+            .build_cartesian_2d(time_range.clone(), min..max)?;
+        chart
+            .configure_mesh()
+            .x_desc("Time (s)")
+            .y_desc(y_desc)
+            .draw()?;
+
+        // Shade the off-track stretches behind the trace
+        chart.draw_series(snapshots.windows(2).filter(|pair| !pair[1].in_track).map(|pair| {
+            Rectangle::new([(pair[0].time, min), (pair[1].time, max)], RED.mix(0.15).filled())
+        }))?;
+        chart.draw_series(LineSeries::new(
+            times.iter().copied().zip(values.iter().copied()),
+            color.stroke_width(2),
+        ))?;
+    }
+
+    root.present()?;
+    diagnostics::info(format_args!("Telemetry plot saved to {}", filename));
+    Ok(())
+}
+
+/// Range of `values` with 5% margin, at least 0.1 wide
+pub(super) fn value_range(values: &[f64]) -> (f64, f64) {
+    let (min, max) = values
+        .iter()
+        .filter(|value| value.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &value| {
+            (min.min(value), max.max(value))
+        });
+    if !min.is_finite() {
+        return (0.0, 1.0);
+    }
+    let margin = ((max - min) * 0.05).max(0.05);
+    (min - margin, max + margin)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::plot_telemetry;
+    use crate::simulation::result::SimulationResult;
+
+    #[test]
+    fn test_plot_telemetry() {
+        let mut result = SimulationResult::new();
+        for step in 0..20 {
+            let time = step as f64 * 0.5;
+            result.push(time, (time * 10.0, 0.0, 0.1 * time), step < 15);
+        }
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("telemetry.svg");
+        plot_telemetry(&result, path.to_str().expect("utf-8 path")).expect("plot telemetry");
+        let svg = std::fs::read_to_string(&path).expect("read svg");
+        assert!(svg.contains("Speed") && svg.contains("Heading"));
+
+        let single: SimulationResult<(f64, f64, f64)> = result.snapshots()[..1].iter().cloned().collect();
+        assert!(plot_telemetry(&single, path.to_str().expect("utf-8 path")).is_err());
+    }
+}
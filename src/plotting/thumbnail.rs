@@ -0,0 +1,145 @@
+use crate::plotting::create::{trail_runs, uniform_ranges};
+use crate::tracks::base_track::Track;
+use plotters::prelude::*;
+use crate::error::{Error, Result};
+
+/// Default canvas size in pixels for thumbnails
+pub const DEFAULT_THUMBNAIL_SIZE: (u32, u32) = (200, 200);
+
+/// Margin in pixels around the thumbnail drawing
+const THUMBNAIL_MARGIN: u32 = 2;
+
+/// Plot a compact thumbnail of a track to an SVG file
+///
+/// Thumbnails have no caption, legend, or axes, which makes them suitable for
+/// galleries and comparison grids.
+///
+/// # Arguments
+/// * `track` - Reference to the track to plot
+/// * `filename` - Path to save the thumbnail (e.g., "track_thumb.svg")
+/// * `size` - Canvas size in pixels as (width, height)
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_track_thumbnail(
+    track: &dyn Track,
+    filename: &str,
+    size: (u32, u32),
+) -> Result<()> {
+    plot_run_thumbnail(track, &[], &[], filename, size)
+}
+
+/// Plot a compact thumbnail of a run (track plus driven path) to an SVG file
+///
+/// Portions of the path where the vehicle left the track are drawn in red. The track
+/// is drawn at one scale on both axes and centered, whatever the canvas shape.
+///
+/// # Arguments
+/// * `track` - Reference to the track to plot
+/// * `trail` - List of (x, y) positions driven during the run
+/// * `in_track` - Whether each trail position was inside the track boundaries
+/// * `filename` - Path to save the thumbnail (e.g., "run_thumb.svg")
+/// * `size` - Canvas size in pixels as (width, height)
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_run_thumbnail(
+    track: &dyn Track,
+    trail: &[(f64, f64)],
+    in_track: &[bool],
+    filename: &str,
+    size: (u32, u32),
+) -> Result<()> {
+    if size.0 == 0 || size.1 == 0 {
+        return Err(Error::invalid_input("canvas size must be greater than zero"));
+    }
+    if trail.len() != in_track.len() {
+        return Err(Error::InvalidInput(format!(
+            "trail has {} points but in_track has {} flags",
+            trail.len(),
+            in_track.len()
+        )));
+    }
+
+    let root = SVGBackend::new(filename, size).into_drawing_area();
+    root.fill(&WHITE)?;
+
+    // One scale on both axes, so the track keeps its shape on non-square canvases
+    let (min_coord, max_coord) = track.get_plot_range();
+    let ((x_min, x_max), (y_min, y_max)) = uniform_ranges(
+        min_coord,
+        max_coord,
+        size.0.saturating_sub(2 * THUMBNAIL_MARGIN),
+        size.1.saturating_sub(2 * THUMBNAIL_MARGIN),
+    );
+    let mut chart = ChartBuilder::on(&root)
+        .margin(THUMBNAIL_MARGIN)
+        .build_cartesian_2d(x_min..x_max, y_min..y_max)?;
+
+    for boundary in [track.get_outside_boundary(), track.get_inside_boundary()] {
+        if let Some(&first) = boundary.first() {
+            chart.draw_series(LineSeries::new(
+                boundary.iter().chain(std::iter::once(&first)).map(|&point| point.into()),
+                &BLACK,
+            ))?;
+        }
+    }
+
+    for (off_track, points) in trail_runs(trail, in_track) {
+        let color = if off_track { RED } else { BLUE };
+        chart.draw_series(std::iter::once(PathElement::new(points, color.stroke_width(2))))?;
+    }
+
+    let start_pos = track.get_start_position();
+    chart.draw_series(std::iter::once(Circle::new(
+        (start_pos.0, start_pos.1),
+        3,
+        BLACK.filled(),
+    )))?;
+
+    root.present()?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plot_run_thumbnail, plot_track_thumbnail, DEFAULT_THUMBNAIL_SIZE};
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+    use std::fs;
+
+    #[test]
+    fn test_plot_track_thumbnail() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("square_thumb.svg");
+
+        plot_track_thumbnail(
+            &track,
+            filename.to_str().expect("temp path not utf-8"),
+            DEFAULT_THUMBNAIL_SIZE,
+        )
+        .expect("failed to plot thumbnail");
+
+        let svg = fs::read_to_string(&filename).expect("read thumbnail");
+        assert!(svg.contains("width=\"200\""));
+        assert!(!svg.contains("<text"), "thumbnails should not contain labels");
+    }
+
+    #[test]
+    fn test_plot_run_thumbnail() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let filename = temp_dir.path().join("run_thumb.svg");
+
+        let result = plot_run_thumbnail(
+            &track,
+            &[(50.0, 0.0), (49.0, 10.0), (47.0, 20.0)],
+            &[true, true, true],
+            filename.to_str().expect("temp path not utf-8"),
+            (160, 120),
+        );
+        assert!(result.is_ok(), "Failed to plot thumbnail: {:?}", result.err());
+        assert!(filename.exists());
+    }
+}
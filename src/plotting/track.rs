@@ -1,5 +1,8 @@
 use plotters::prelude::*;
-use std::error::Error;
+use crate::diagnostics;
+use crate::error::Result;
+use crate::math::vec2::Vec2;
+use crate::plotting::create::ensure_track_initialized;
 use crate::tracks::base_track::Track;
 
 /// Plot a track to an SVG file
@@ -10,7 +13,8 @@ use crate::tracks::base_track::Track;
 /// 
 /// # Returns
 /// Result indicating success or error
-pub fn plot_track(track: &dyn Track, filename: &str) -> Result<(), Box<dyn Error>> {
+pub fn plot_track(track: &dyn Track, filename: &str) -> Result<()> {
+    ensure_track_initialized(track)?;
     let root = SVGBackend::new(filename, (800, 800)).into_drawing_area();
     root.fill(&WHITE)?;
     
@@ -27,17 +31,17 @@ pub fn plot_track(track: &dyn Track, filename: &str) -> Result<(), Box<dyn Error
     
     // Plot outside boundary
     chart.draw_series(LineSeries::new(
-        track.get_outside_boundary().iter().map(|&(x, y)| (x, y))
-            .chain(std::iter::once(track.get_outside_boundary()[0])),
+        track.get_outside_boundary().iter().map(|&Vec2 { x, y }| (x, y))
+            .chain(std::iter::once(track.get_outside_boundary()[0].into())),
         &BLACK,
     ))?
     .label("Outside Boundary")
-    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLACK));
+    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK));
     
     // Plot center line (dotted)
     chart.draw_series(
-        track.get_center_line().iter().map(|&(x, y)| (x, y))
-            .chain(std::iter::once(track.get_center_line()[0]))
+        track.get_center_line().iter().map(|&Vec2 { x, y }| (x, y))
+            .chain(std::iter::once(track.get_center_line()[0].into()))
             .collect::<Vec<_>>()
             .windows(2)
             .enumerate()
@@ -53,12 +57,12 @@ pub fn plot_track(track: &dyn Track, filename: &str) -> Result<(), Box<dyn Error
     
     // Plot inside boundary
     chart.draw_series(LineSeries::new(
-        track.get_inside_boundary().iter().map(|&(x, y)| (x, y))
-            .chain(std::iter::once(track.get_inside_boundary()[0])),
+        track.get_inside_boundary().iter().map(|&Vec2 { x, y }| (x, y))
+            .chain(std::iter::once(track.get_inside_boundary()[0].into())),
         &BLACK,
     ))?
     .label("Inside Boundary")
-    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], &BLACK));
+    .legend(|(x, y)| PathElement::new(vec![(x, y), (x + 20, y)], BLACK));
     
     // Plot start position
     let start_pos = track.get_start_position();
@@ -72,12 +76,16 @@ pub fn plot_track(track: &dyn Track, filename: &str) -> Result<(), Box<dyn Error
     
     chart.configure_series_labels()
         .position(SeriesLabelPosition::UpperRight)
-        .background_style(&WHITE.mix(0.8))
-        .border_style(&BLACK)
+        .background_style(WHITE.mix(0.8))
+        .border_style(BLACK)
         .draw()?;
     
     root.present()?;
-    println!("{} plot saved to {}", track.get_track_name(), filename);
+    diagnostics::info(format_args!(
+        "{} plot saved to {}",
+        track.get_track_name(),
+        filename
+    ));
     Ok(())
 }
 
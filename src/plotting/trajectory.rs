@@ -0,0 +1,179 @@
+use crate::diagnostics;
+use crate::error::{Error, Result};
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::plotting::create::ensure_track_initialized;
+use crate::simulation::lap_analysis::estimate_speeds;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::base_track::Track;
+use plotters::prelude::*;
+
+/// Number of color steps drawn in the speed scale
+const SCALE_STEPS: usize = 64;
+
+/// Plot the driven path of a run on the track, colored by speed, to an SVG file
+///
+/// Each segment between consecutive snapshots is colored from blue (slowest) to red
+/// (fastest), with the speed scale drawn to the right of the track. Speeds are estimated
+/// from consecutive positions, so the states of any model implementing `Pose` can be
+/// plotted.
+///
+/// # Arguments
+/// * `track` - Reference to the track to plot
+/// * `result` - Recorded simulation result
+/// * `filename` - Path to save the plot (e.g., "trajectory_speed.svg")
+///
+/// # Returns
+/// Result indicating success or error
+pub fn plot_trajectory<S: Pose>(
+    track: &dyn Track,
+    result: &SimulationResult<S>,
+    filename: &str,
+) -> Result<()> {
+    ensure_track_initialized(track)?;
+    if result.len() < 2 {
+        return Err(Error::invalid_input("at least two snapshots are needed to plot a trajectory"));
+    }
+    let times: Vec<f64> = result.times().collect();
+    let positions: Vec<Vec2> = result
+        .states()
+        .map(|state| Vec2::new(state.x(), state.y()))
+        .collect();
+    let speeds = estimate_speeds(&positions, &times);
+    let (min_speed, max_speed) = speed_range(&speeds);
+
+    let root = SVGBackend::new(filename, (900, 800)).into_drawing_area();
+    root.fill(&WHITE)?;
+    let (track_area, scale_area) = root.split_horizontally(800);
+
+    let (min_coord, max_coord) = track.get_plot_range();
+    let mut chart = ChartBuilder::on(&track_area)
+        .caption(track.get_track_name(), ("sans-serif", 30))
+        .margin(10)
+        .x_label_area_size(30)
+        .y_label_area_size(30)
+        .build_cartesian_2d(min_coord..max_coord, min_coord..max_coord)?;
+    chart.configure_mesh().draw()?;
+
+    for boundary in [track.get_outside_boundary(), track.get_inside_boundary()] {
+        if let Some(&first) = boundary.first() {
+            chart.draw_series(LineSeries::new(
+                boundary.iter().chain(std::iter::once(&first)).map(|&point| point.into()),
+                &BLACK,
+            ))?;
+        }
+    }
+
+    // Color each segment by the mean speed of its end points
+    chart.draw_series(positions.windows(2).zip(speeds.windows(2)).map(|(points, pair)| {
+        let color = speed_color(0.5 * (pair[0] + pair[1]), min_speed, max_speed);
+        PathElement::new(
+            vec![points[0].into(), points[1].into()],
+            color.stroke_width(3),
+        )
+    }))?;
+
+    let start_pos = track.get_start_position();
+    chart.draw_series(std::iter::once(Circle::new(
+        (start_pos.0, start_pos.1),
+        5,
+        BLACK.filled(),
+    )))?;
+
+    let mut scale = ChartBuilder::on(&scale_area)
+        .margin_top(50)
+        .margin_bottom(40)
+        .margin_right(10)
+        .y_label_area_size(50)
+        .build_cartesian_2d(0.0..1.0, min_speed..max_speed)?;
+    scale
+        .configure_mesh()
+        .disable_x_mesh()
+        .disable_x_axis()
+        .disable_y_mesh()
+        .y_desc("Speed (m/s)")
+        .draw()?;
+    let step = (max_speed - min_speed) / SCALE_STEPS as f64;
+    scale.draw_series((0..SCALE_STEPS).map(|i| {
+        let low = min_speed + i as f64 * step;
+        let color = speed_color(low + 0.5 * step, min_speed, max_speed);
+        Rectangle::new([(0.0, low), (1.0, low + step)], color.filled())
+    }))?;
+
+    root.present()?;
+    diagnostics::info(format_args!("Trajectory plot saved to {}", filename));
+    Ok(())
+}
+
+/// Range of the finite `speeds`, at least 0.1 m/s wide
+fn speed_range(speeds: &[f64]) -> (f64, f64) {
+    let (min, max) = speeds
+        .iter()
+        .filter(|speed| speed.is_finite())
+        .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), &speed| {
+            (min.min(speed), max.max(speed))
+        });
+    if !min.is_finite() {
+        return (0.0, 1.0);
+    }
+    (min, max.max(min + 0.1))
+}
+
+/// Color of `speed` on a blue (at `min`) to red (at `max`) hue gradient
+fn speed_color(speed: f64, min: f64, max: f64) -> HSLColor {
+    let fraction = ((speed - min) / (max - min)).clamp(0.0, 1.0);
+    let fraction = if fraction.is_nan() { 0.0 } else { fraction };
+    HSLColor(2.0 / 3.0 * (1.0 - fraction), 0.9, 0.45)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{plot_trajectory, speed_color};
+    use crate::models::point_mass::PointMassState;
+    use crate::simulation::result::SimulationResult;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+    use plotters::style::Color;
+
+    #[test]
+    fn test_plot_trajectory() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let mut result = SimulationResult::new();
+        for step in 0..40 {
+            // Speed up along the arc so the gradient spans several colors
+            let angle = 0.001 * (step * step) as f64;
+            let state = PointMassState {
+                x: 50.0 * angle.cos(),
+                y: 50.0 * angle.sin(),
+                vx: 0.0,
+                vy: 0.0,
+                yaw: angle + std::f64::consts::FRAC_PI_2,
+            };
+            result.push(step as f64 * 0.1, state, true);
+        }
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("trajectory.svg");
+        plot_trajectory(&track, &result, path.to_str().expect("utf-8 path"))
+            .expect("plot trajectory");
+
+        let svg = std::fs::read_to_string(&path).expect("read svg");
+        assert!(svg.contains("Speed (m/s)"));
+        let slow = speed_color(0.0, 0.0, 1.0).to_backend_color().rgb;
+        let fast = speed_color(1.0, 0.0, 1.0).to_backend_color().rgb;
+        assert!(slow.2 > slow.0 && fast.0 > fast.2, "slow is blue, fast is red");
+    }
+
+    #[test]
+    fn test_plot_trajectory_errors() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("trajectory.svg");
+        let mut result = SimulationResult::new();
+        result.push(0.0, (0.0, 0.0, 0.0), true);
+
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        assert!(plot_trajectory(&track, &result, path.to_str().expect("utf-8 path")).is_err());
+        result.push(0.1, (1.0, 0.0, 0.0), true);
+        plot_trajectory(&track, &result, path.to_str().expect("utf-8 path"))
+            .expect("constant speed still plots");
+    }
+}
@@ -1,7 +1,70 @@
-use std::error::Error;
+use crate::diagnostics;
+use crate::error::{Error, Result};
+use std::fmt;
 use std::fs;
 use std::path::{Path, PathBuf};
-use std::process::Command;
+use std::io::Write;
+use std::process::{Child, ChildStdin, Command, Stdio};
+
+/// Video codec (and matching container) used for the output video.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum VideoCodec {
+    /// H.264 in an MP4 container
+    #[default]
+    H264,
+    /// H.265/HEVC in an MP4 container
+    H265,
+    /// VP9 in a WebM container
+    Vp9,
+}
+
+impl VideoCodec {
+    /// Default file extension of the container for this codec.
+    pub fn extension(&self) -> &'static str {
+        match self {
+            VideoCodec::H264 | VideoCodec::H265 => "mp4",
+            VideoCodec::Vp9 => "webm",
+        }
+    }
+
+    /// Container file extensions this codec can be written to.
+    fn supported_extensions(&self) -> &'static [&'static str] {
+        match self {
+            VideoCodec::H264 | VideoCodec::H265 => &["mp4", "mov", "mkv"],
+            VideoCodec::Vp9 => &["webm", "mkv"],
+        }
+    }
+
+    fn ffmpeg_args(&self) -> &'static [&'static str] {
+        match self {
+            VideoCodec::H264 => &["-c:v", "libx264"],
+            VideoCodec::H265 => &["-c:v", "libx265", "-tag:v", "hvc1"],
+            VideoCodec::Vp9 => &["-c:v", "libvpx-vp9", "-b:v", "0", "-crf", "32"],
+        }
+    }
+}
+
+impl fmt::Display for VideoCodec {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            VideoCodec::H264 => "h264",
+            VideoCodec::H265 => "h265",
+            VideoCodec::Vp9 => "vp9",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Options controlling how ffmpeg encodes the video.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct EncodingOptions {
+    /// Output resolution as (width, height); frames are downscaled with a Lanczos filter.
+    /// `None` keeps the SVG's intrinsic size.
+    pub resolution: Option<(u32, u32)>,
+    /// Codec used to encode the video; its container must match the output file
+    /// extension. `None` lets ffmpeg choose the codec and container from the extension.
+    pub codec: Option<VideoCodec>,
+}
 
 /// Create a video from a list of SVG frames using ffmpeg.
 ///
@@ -10,98 +73,258 @@ pub fn create_video_from_svgs<P: AsRef<Path>, Q: AsRef<Path>>(
     svgs: &[P],
     output_path: Q,
     fps: u32,
-) -> Result<(), Box<dyn Error>> {
+) -> Result<()> {
+    create_video_from_svgs_with_options(svgs, output_path, fps, &EncodingOptions::default())
+}
+
+/// Create a video from a list of SVG frames using ffmpeg with explicit encoding options.
+///
+/// The frames are streamed to ffmpeg's stdin in order (see `SvgVideoEncoder`).
+/// Requires `ffmpeg` to be available on PATH with SVG decoding support.
+pub fn create_video_from_svgs_with_options<P: AsRef<Path>, Q: AsRef<Path>>(
+    svgs: &[P],
+    output_path: Q,
+    fps: u32,
+    options: &EncodingOptions,
+) -> Result<()> {
     if svgs.is_empty() {
-        return Err("no SVG frames provided".into());
+        return Err(Error::invalid_input("no SVG frames provided"));
     }
-    if fps == 0 {
-        return Err("fps must be greater than zero".into());
+    validate_encoding(output_path.as_ref(), fps, options)?;
+    if let Some(missing) = svgs.iter().map(AsRef::as_ref).find(|svg| !svg.exists()) {
+        return Err(Error::InvalidInput(format!(
+            "missing SVG frame: {}",
+            missing.display()
+        )));
+    }
+
+    let mut encoder = SvgVideoEncoder::start(output_path, fps, options)?;
+    for svg in svgs {
+        encoder.push_frame(&fs::read_to_string(svg)?)?;
     }
+    encoder.finish()
+}
+
+/// Video encoder fed with SVG documents through ffmpeg's stdin
+///
+/// Frames are piped with the `svg_pipe` demuxer at a constant `fps`, so no frame files
+/// or concat lists are needed; this requires an ffmpeg built with SVG decoding. With the
+/// `raster` feature, frames are rasterized in-process instead and piped as raw RGB, so
+/// any ffmpeg build works.
+pub struct SvgVideoEncoder {
+    /// Running ffmpeg and its stdin; started on the first frame with `raster`
+    process: Option<(Child, ChildStdin)>,
+    output_path: PathBuf,
+    fps: u32,
+    options: EncodingOptions,
+    frames: usize,
+    #[cfg(feature = "raster")]
+    rasterizer: super::raster::SvgRasterizer,
+    /// Size of the first rasterized frame; later frames must match it
+    #[cfg(feature = "raster")]
+    frame_size: (u32, u32),
+}
 
-    let output_path = output_path.as_ref();
-    if let Some(parent) = output_path.parent() {
-        if !parent.as_os_str().is_empty() {
+impl SvgVideoEncoder {
+    /// Start ffmpeg writing to `output_path`
+    ///
+    /// # Arguments
+    /// * `output_path` - Video file; its extension must match `options.codec`, if set
+    /// * `fps` - Frames per second of the video
+    /// * `options` - Resolution and codec
+    pub fn start<Q: AsRef<Path>>(
+        output_path: Q,
+        fps: u32,
+        options: &EncodingOptions,
+    ) -> Result<Self> {
+        let output_path = output_path.as_ref();
+        validate_encoding(output_path, fps, options)?;
+        if let Some(parent) = output_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
             fs::create_dir_all(parent)?;
         }
+
+        #[allow(unused_mut)]
+        let mut encoder = Self {
+            process: None,
+            output_path: output_path.to_path_buf(),
+            fps,
+            options: options.clone(),
+            frames: 0,
+            #[cfg(feature = "raster")]
+            rasterizer: super::raster::SvgRasterizer::new(),
+            #[cfg(feature = "raster")]
+            frame_size: (0, 0),
+        };
+        // Raw frames need the frame size, so with `raster` ffmpeg starts on the first frame
+        #[cfg(not(feature = "raster"))]
+        encoder.spawn(&["-f", "svg_pipe"])?;
+        Ok(encoder)
     }
 
-    let concat_path = concat_list_path(output_path);
-    write_concat_list(svgs, &concat_path, fps)?;
-
-    let status = Command::new("ffmpeg")
-        .arg("-y")
-        .arg("-f")
-        .arg("concat")
-        .arg("-safe")
-        .arg("0")
-        .arg("-i")
-        .arg(&concat_path)
-        .arg("-vsync")
-        .arg("vfr")
-        .arg("-pix_fmt")
-        .arg("yuv420p")
-        .arg(output_path)
-        .status()?;
-
-    let _ = fs::remove_file(&concat_path);
-
-    if !status.success() {
-        return Err(format!(
-            "ffmpeg failed with status {} (output: {})",
-            status,
-            output_path.display()
-        )
-        .into());
-    }
-
-    println!("Video saved to {}", output_path.display());
-    Ok(())
-}
+    /// Number of frames written so far
+    pub fn frames(&self) -> usize {
+        self.frames
+    }
 
-fn concat_list_path(output_path: &Path) -> PathBuf {
-    let mut path = output_path.to_path_buf();
-    let stem = output_path
-        .file_stem()
-        .and_then(|s| s.to_str())
-        .unwrap_or("frames");
-    let filename = format!("{}_frames.txt", stem);
-    path.set_file_name(filename);
-    path
-}
+    /// Write the next frame
+    ///
+    /// # Arguments
+    /// * `svg` - Complete SVG document of the frame
+    pub fn push_frame(&mut self, svg: &str) -> Result<()> {
+        #[cfg(feature = "raster")]
+        let raster = {
+            let frame = self.rasterizer.rasterize(svg)?;
+            let size = (frame.width, frame.height);
+            if self.process.is_none() {
+                let video_size = format!("{}x{}", size.0, size.1);
+                self.spawn(&["-f", "rawvideo", "-pix_fmt", "rgb24", "-s", &video_size])?;
+                self.frame_size = size;
+            } else if size != self.frame_size {
+                return Err(Error::InvalidInput(format!(
+                    "frame {} is {}x{}, expected {}x{} like the first frame",
+                    self.frames + 1,
+                    size.0,
+                    size.1,
+                    self.frame_size.0,
+                    self.frame_size.1
+                )));
+            }
+            frame.rgb()
+        };
+        #[cfg(feature = "raster")]
+        let frame = raster.as_slice();
+        #[cfg(not(feature = "raster"))]
+        let frame = svg.as_bytes();
 
-fn write_concat_list<P: AsRef<Path>>(
-    svgs: &[P],
-    list_path: &Path,
-    fps: u32,
-) -> Result<(), Box<dyn Error>> {
-    let frame_duration = 1.0f64 / fps as f64;
-    let mut contents = String::new();
-    for (index, svg) in svgs.iter().enumerate() {
-        let svg_path = svg.as_ref();
-        if !svg_path.exists() {
-            return Err(format!("missing SVG frame: {}", svg_path.display()).into());
+        let (_, stdin) = self
+            .process
+            .as_mut()
+            .ok_or_else(|| Error::Ffmpeg("stdin is closed".to_string()))?;
+        stdin.write_all(frame).map_err(|err| {
+            Error::Ffmpeg(format!("failed to write frame {}: {}", self.frames + 1, err))
+        })?;
+        self.frames += 1;
+        Ok(())
+    }
+
+    /// Close the stream and wait for ffmpeg to write the video
+    pub fn finish(mut self) -> Result<()> {
+        let Some((mut child, stdin)) = self.process.take() else {
+            return Err(Error::invalid_input("no SVG frames provided"));
+        };
+        if self.frames == 0 {
+            let _ = child.kill();
+            let _ = child.wait();
+            return Err(Error::invalid_input("no SVG frames provided"));
         }
-        let abs_path = svg_path.canonicalize()?;
-        let path_str = abs_path
-            .to_str()
-            .ok_or("SVG path contains non-UTF-8 characters")?;
+        // Closing stdin signals the end of the stream
+        drop(stdin);
+        let status = child.wait()?;
+        if !status.success() {
+            return Err(Error::Ffmpeg(format!(
+                "exited with status {} (output: {})",
+                status,
+                self.output_path.display()
+            )));
+        }
+
+        diagnostics::info(format_args!("Video saved to {}", self.output_path.display()));
+        Ok(())
+    }
 
-        contents.push_str("file '");
-        contents.push_str(path_str);
-        contents.push_str("'\n");
+    /// Start ffmpeg reading frames in the given input format from stdin
+    fn spawn(&mut self, input_args: &[&str]) -> Result<()> {
+        let mut command = Command::new("ffmpeg");
+        command
+            .arg("-y")
+            .args(input_args)
+            .arg("-framerate")
+            .arg(self.fps.to_string())
+            .arg("-i")
+            .arg("-");
+        if let Some((width, height)) = self.options.resolution {
+            command
+                .arg("-vf")
+                .arg(format!("scale={}:{}:flags=lanczos", width, height));
+        }
+        if let Some(codec) = self.options.codec {
+            command.args(codec.ffmpeg_args());
+        }
+        let mut child = command
+            .arg("-pix_fmt")
+            .arg("yuv420p")
+            .arg(&self.output_path)
+            .stdin(Stdio::piped())
+            .spawn()
+            .map_err(|err| Error::Ffmpeg(format!("failed to start ffmpeg: {}", err)))?;
+        let stdin = child
+            .stdin
+            .take()
+            .ok_or_else(|| Error::Ffmpeg("failed to open ffmpeg stdin".to_string()))?;
+        self.process = Some((child, stdin));
+        Ok(())
+    }
+}
 
-        if index + 1 != svgs.len() {
-            contents.push_str(&format!("duration {}\n", frame_duration));
+impl Drop for SvgVideoEncoder {
+    /// Stop ffmpeg if the encoder was dropped without `finish`, e.g. after a failed frame
+    ///
+    /// The video is abandoned: ffmpeg is killed rather than left to encode a truncated
+    /// stream, and waited for so no zombie process remains.
+    fn drop(&mut self) {
+        if let Some((mut child, stdin)) = self.process.take() {
+            drop(stdin);
+            let _ = child.kill();
+            let _ = child.wait();
         }
     }
+}
+
+/// Check the frame rate, resolution, and output container before starting ffmpeg
+///
+/// The container is only checked against an explicitly chosen codec; otherwise ffmpeg
+/// picks the codec for the extension (e.g. `.avi`, `.gif`) as it always did.
+fn validate_encoding(output_path: &Path, fps: u32, options: &EncodingOptions) -> Result<()> {
+    if fps == 0 {
+        return Err(Error::invalid_input("fps must be greater than zero"));
+    }
+    if let Some((width, height)) = options.resolution
+        && (width == 0 || height == 0 || width % 2 != 0 || height % 2 != 0)
+    {
+        return Err(Error::InvalidInput(format!(
+            "video resolution must be non-zero and even, got {}x{}",
+            width, height
+        )));
+    }
 
-    fs::write(list_path, contents)?;
+    let Some(codec) = options.codec else {
+        return Ok(());
+    };
+    let extension = output_path
+        .extension()
+        .and_then(|ext| ext.to_str())
+        .map(|ext| ext.to_ascii_lowercase())
+        .unwrap_or_default();
+    if !codec.supported_extensions().contains(&extension.as_str()) {
+        return Err(Error::InvalidInput(format!(
+            "output extension '{}' is not supported by codec {} (expected one of: {})",
+            extension,
+            codec,
+            codec.supported_extensions().join(", ")
+        )));
+    }
     Ok(())
 }
 
 #[cfg(test)]
 mod tests {
-    use super::create_video_from_svgs;
+    use super::{
+        create_video_from_svgs, create_video_from_svgs_with_options, validate_encoding,
+        EncodingOptions, SvgVideoEncoder, VideoCodec,
+    };
+    use std::path::Path;
 
     #[test]
     fn test_create_video_from_svgs_empty_frames() {
@@ -126,4 +349,58 @@ mod tests {
             .expect_err("expected error for missing frame");
         assert!(err.to_string().contains("missing SVG frame"));
     }
+
+    #[test]
+    fn test_create_video_from_svgs_rejects_odd_resolution() {
+        let options = EncodingOptions {
+            resolution: Some((1919, 1080)),
+            ..EncodingOptions::default()
+        };
+        let err = create_video_from_svgs_with_options(&["frame.svg"], "out.mp4", 10, &options)
+            .expect_err("expected error for odd resolution");
+        assert!(err.to_string().contains("resolution"));
+    }
+
+    #[test]
+    fn test_create_video_from_svgs_rejects_container_mismatch() {
+        let options = EncodingOptions {
+            codec: Some(VideoCodec::Vp9),
+            ..EncodingOptions::default()
+        };
+        let err = create_video_from_svgs_with_options(&["frame.svg"], "out.mp4", 10, &options)
+            .expect_err("expected error for vp9 in mp4");
+        assert!(err.to_string().contains("not supported by codec vp9"));
+    }
+
+    #[test]
+    fn test_svg_video_encoder_validates_before_starting() {
+        let h264 = EncodingOptions {
+            codec: Some(VideoCodec::H264),
+            ..EncodingOptions::default()
+        };
+        let err = SvgVideoEncoder::start("out.avi", 10, &h264)
+            .err()
+            .expect("expected error for avi output");
+        assert!(err.to_string().contains("not supported by codec h264"));
+        let err = SvgVideoEncoder::start("out.mp4", 0, &EncodingOptions::default())
+            .err()
+            .expect("expected error for fps=0");
+        assert!(err.to_string().contains("fps must be greater than zero"));
+    }
+
+    #[test]
+    fn test_container_left_to_ffmpeg_without_codec() {
+        let options = EncodingOptions::default();
+        for output in ["out.avi", "out.gif", "out"] {
+            assert!(validate_encoding(Path::new(output), 10, &options).is_ok(), "{output}");
+        }
+    }
+
+    #[test]
+    fn test_video_codec_extension() {
+        assert_eq!(VideoCodec::default(), VideoCodec::H264);
+        assert_eq!(VideoCodec::H264.extension(), "mp4");
+        assert_eq!(VideoCodec::H265.extension(), "mp4");
+        assert_eq!(VideoCodec::Vp9.extension(), "webm");
+    }
 }
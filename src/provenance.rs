@@ -0,0 +1,159 @@
+//! Provenance of exported artifacts.
+//!
+//! Results, MCAP logs, and HTML previews embed the crate version, the git commit the
+//! binary was built from, the scenario parameters, and the RNG seed, so any artifact
+//! can be traced back to the code and inputs that produced it.
+
+use crate::config::{ScenarioConfig, TrackConfig};
+use std::collections::BTreeMap;
+
+/// Version of this crate
+pub const CRATE_VERSION: &str = env!("CARGO_PKG_VERSION");
+
+/// Git commit the crate was built from, or "unknown" outside a git checkout
+pub const GIT_COMMIT: &str = env!("LAP_SIM_GIT_COMMIT");
+
+/// Everything needed to reproduce a run
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct Provenance {
+    pub crate_version: String,
+    pub git_commit: String,
+    /// Seed of the random number generator, for runs with stochastic inputs
+    pub seed: Option<u64>,
+    /// Scenario parameters by name (e.g., "dt" or "track.radius")
+    pub parameters: BTreeMap<String, String>,
+}
+
+impl Default for Provenance {
+    fn default() -> Self {
+        Self::current()
+    }
+}
+
+impl Provenance {
+    /// Provenance of the running build, without parameters or seed
+    pub fn current() -> Self {
+        Self {
+            crate_version: CRATE_VERSION.to_string(),
+            git_commit: GIT_COMMIT.to_string(),
+            seed: None,
+            parameters: BTreeMap::new(),
+        }
+    }
+
+    /// Provenance of the running build with the parameters of `config`
+    pub fn for_scenario(config: &ScenarioConfig) -> Self {
+        let mut provenance = Self::current()
+            .with_parameter("track.type", config.track.kind())
+            .with_parameter("dt", config.dt)
+            .with_parameter("duration", config.duration)
+            .with_parameter("fps", config.fps)
+            .with_parameter("controls.ax", config.controls.0)
+            .with_parameter("controls.yaw_rate", config.controls.1)
+            .with_parameter("schedule", config.schedule.is_some())
+            .with_parameter("model.mass", config.model.mass)
+            .with_parameter("model.integrator", config.model.integrator.name());
+        provenance = match config.track {
+            TrackConfig::Circle {
+                radius,
+                track_width,
+                num_points,
+            } => provenance
+                .with_parameter("track.radius", radius)
+                .with_parameter("track.track_width", track_width)
+                .with_parameter("track.num_points", num_points),
+            TrackConfig::Square {
+                height,
+                track_width,
+                points_per_side,
+            } => provenance
+                .with_parameter("track.height", height)
+                .with_parameter("track.track_width", track_width)
+                .with_parameter("track.points_per_side", points_per_side),
+        };
+        if let Some(preset) = &config.controller_preset {
+            provenance = provenance.with_parameter("controller_preset", preset);
+        }
+        // An unresolvable preset fails the run itself; the name above is enough here
+        if let Ok(Some(preset)) = config.resolve_controller_preset() {
+            provenance = provenance.with_parameter("controller", preset.controller);
+            for (name, value) in &preset.parameters {
+                provenance = provenance.with_parameter(&format!("controller.{}", name), value);
+            }
+        }
+        provenance
+    }
+
+    pub fn with_seed(mut self, seed: u64) -> Self {
+        self.seed = Some(seed);
+        self
+    }
+
+    /// Record a parameter, replacing an earlier value with the same name
+    pub fn with_parameter<V: ToString>(mut self, name: &str, value: V) -> Self {
+        self.parameters.insert(name.to_string(), value.to_string());
+        self
+    }
+
+    /// All fields as (name, value) pairs, parameters prefixed with "parameter."
+    pub fn entries(&self) -> Vec<(String, String)> {
+        let mut entries = vec![
+            ("crate_version".to_string(), self.crate_version.clone()),
+            ("git_commit".to_string(), self.git_commit.clone()),
+        ];
+        if let Some(seed) = self.seed {
+            entries.push(("seed".to_string(), seed.to_string()));
+        }
+        entries.extend(
+            self.parameters
+                .iter()
+                .map(|(name, value)| (format!("parameter.{}", name), value.clone())),
+        );
+        entries
+    }
+
+    /// Comment line (without newline) heading exported CSV files, e.g.
+    /// `# provenance: crate_version=0.1.0; git_commit=abc1234; seed=7`
+    pub fn csv_comment(&self) -> String {
+        let entries: Vec<String> = self
+            .entries()
+            .into_iter()
+            .map(|(name, value)| format!("{}={}", name, value))
+            .collect();
+        format!("# provenance: {}", entries.join("; "))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Provenance, CRATE_VERSION};
+    use crate::config::ScenarioConfig;
+
+    #[test]
+    fn test_provenance_for_scenario() {
+        let provenance = Provenance::for_scenario(&ScenarioConfig::default()).with_seed(7);
+
+        assert_eq!(provenance.crate_version, CRATE_VERSION);
+        assert!(!provenance.git_commit.is_empty());
+        assert_eq!(provenance.parameters["track.radius"], "50");
+        assert_eq!(provenance.parameters["dt"], "0.1");
+        assert_eq!(provenance.parameters["model.integrator"], "euler");
+
+        let entries = provenance.entries();
+        assert_eq!(entries[0].0, "crate_version");
+        assert!(entries.contains(&("seed".to_string(), "7".to_string())));
+        assert!(entries.contains(&("parameter.fps".to_string(), "10".to_string())));
+        let comment = provenance.csv_comment();
+        assert!(comment.starts_with("# provenance: crate_version="));
+        assert!(comment.contains("; seed=7; ") && !comment.contains('\n'));
+
+        let config = ScenarioConfig {
+            controller_preset: Some("circle_stanley".to_string()),
+            ..ScenarioConfig::default()
+        };
+        let provenance = Provenance::for_scenario(&config);
+        assert_eq!(provenance.parameters["controller"], "stanley");
+        assert_eq!(provenance.parameters["controller.gain"], "1");
+    }
+}
@@ -0,0 +1,279 @@
+//! rosbag2 writer using the sqlite3 storage plugin.
+
+use super::messages::{Odometry, PoseStamped, RosMessage};
+use crate::error::{Error, Result};
+use crate::math::angle::Angle;
+use crate::models::point_mass::PointMassState;
+use crate::simulation::result::SimulationResult;
+use rusqlite::{params, Connection};
+use std::fmt::{self, Write as _};
+use std::fs;
+use std::io;
+use std::path::{Path, PathBuf};
+
+/// QoS profile advertised for every topic (reliable, volatile, keep last 10)
+const DEFAULT_QOS: &str = "- history: 1\n  depth: 10\n  reliability: 1\n  durability: 2\n  deadline:\n    sec: 2147483647\n    nsec: 4294967295\n  lifespan:\n    sec: 2147483647\n    nsec: 4294967295\n  liveliness: 1\n  liveliness_lease_duration:\n    sec: 2147483647\n    nsec: 4294967295\n  avoid_ros_namespace_conventions: false";
+
+/// Handle of a topic registered in a bag
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub struct TopicId(i64);
+
+struct BagTopic {
+    name: String,
+    type_name: String,
+    message_count: u64,
+}
+
+/// Writer producing a rosbag2 directory (`metadata.yaml` + one `.db3` file)
+///
+/// Timestamps are simulation times converted to nanoseconds.
+pub struct Ros2BagWriter {
+    directory: PathBuf,
+    db_file: String,
+    connection: Connection,
+    topics: Vec<BagTopic>,
+    start_ns: Option<i64>,
+    end_ns: i64,
+}
+
+impl Ros2BagWriter {
+    /// Create a new bag in `directory`, which must not exist yet (as with `ros2 bag record -o`)
+    ///
+    /// # Returns
+    /// The writer, or `Error::Io` if `directory` exists or the database cannot be created
+    pub fn create<P: AsRef<Path>>(directory: P) -> Result<Self> {
+        let directory = directory.as_ref().to_path_buf();
+        if directory.exists() {
+            let message = format!("bag directory {} already exists", directory.display());
+            return Err(io::Error::new(io::ErrorKind::AlreadyExists, message).into());
+        }
+        fs::create_dir_all(&directory)?;
+
+        let name = directory
+            .file_name()
+            .and_then(|name| name.to_str())
+            .unwrap_or("bag");
+        let db_file = format!("{}_0.db3", name);
+        let connection = Connection::open(directory.join(&db_file))?;
+        connection.execute_batch(
+            "CREATE TABLE topics(id INTEGER PRIMARY KEY, name TEXT NOT NULL, type TEXT NOT NULL, \
+             serialization_format TEXT NOT NULL, offered_qos_profiles TEXT NOT NULL);
+             CREATE TABLE messages(id INTEGER PRIMARY KEY, topic_id INTEGER NOT NULL, \
+             timestamp INTEGER NOT NULL, data BLOB NOT NULL);
+             CREATE INDEX timestamp_idx ON messages (timestamp ASC);",
+        )?;
+
+        Ok(Self {
+            directory,
+            db_file,
+            connection,
+            topics: Vec::new(),
+            start_ns: None,
+            end_ns: 0,
+        })
+    }
+
+    /// Register a topic carrying messages of type `type_name` (e.g., "nav_msgs/msg/Odometry")
+    pub fn add_topic(&mut self, name: &str, type_name: &str) -> Result<TopicId> {
+        if self.topics.iter().any(|topic| topic.name == name) {
+            return Err(Error::invalid_input(format!("topic {} already registered", name)));
+        }
+        let id = self.topics.len() as i64 + 1;
+        self.connection.execute(
+            "INSERT INTO topics (id, name, type, serialization_format, offered_qos_profiles) \
+             VALUES (?1, ?2, ?3, 'cdr', ?4)",
+            params![id, name, type_name, DEFAULT_QOS],
+        )?;
+        self.topics.push(BagTopic {
+            name: name.to_string(),
+            type_name: type_name.to_string(),
+            message_count: 0,
+        });
+        Ok(TopicId(id))
+    }
+
+    /// Write an already serialized CDR message at `time` seconds
+    pub fn write_raw(&mut self, topic: TopicId, time: f64, data: &[u8]) -> Result<()> {
+        let index = usize::try_from(topic.0 - 1)
+            .ok()
+            .filter(|&index| index < self.topics.len())
+            .ok_or_else(|| Error::invalid_input("unknown topic id"))?;
+        let timestamp = (time * 1e9).round() as i64;
+        self.connection.execute(
+            "INSERT INTO messages (topic_id, timestamp, data) VALUES (?1, ?2, ?3)",
+            params![topic.0, timestamp, data],
+        )?;
+        self.topics[index].message_count += 1;
+        self.start_ns = Some(self.start_ns.map_or(timestamp, |start| start.min(timestamp)));
+        self.end_ns = self.end_ns.max(timestamp);
+        Ok(())
+    }
+
+    /// Serialize and write a message at its time in seconds
+    pub fn write<M: RosMessage>(&mut self, topic: TopicId, time: f64, message: &M) -> Result<()> {
+        self.write_raw(topic, time, &message.encode_cdr())
+    }
+
+    /// Write `metadata.yaml` and close the bag
+    ///
+    /// # Returns
+    /// Path of the bag directory
+    pub fn finish(self) -> Result<PathBuf> {
+        let yaml = self.metadata_yaml().map_err(io::Error::other)?;
+        self.connection.close().map_err(|(_, err)| err)?;
+        fs::write(self.directory.join("metadata.yaml"), yaml)?;
+        Ok(self.directory)
+    }
+
+    /// Contents of `metadata.yaml` describing the bag as written so far
+    fn metadata_yaml(&self) -> Result<String, fmt::Error> {
+        let start_ns = self.start_ns.unwrap_or(0);
+        let message_count: u64 = self.topics.iter().map(|topic| topic.message_count).sum();
+
+        let mut yaml = String::from("rosbag2_bagfile_information:\n");
+        writeln!(yaml, "  version: 5")?;
+        writeln!(yaml, "  storage_identifier: sqlite3")?;
+        writeln!(yaml, "  duration:\n    nanoseconds: {}", self.end_ns - start_ns)?;
+        writeln!(yaml, "  starting_time:\n    nanoseconds_since_epoch: {}", start_ns)?;
+        writeln!(yaml, "  message_count: {}", message_count)?;
+        writeln!(yaml, "  topics_with_message_count:")?;
+        for topic in &self.topics {
+            writeln!(yaml, "    - topic_metadata:")?;
+            writeln!(yaml, "        name: {}", topic.name)?;
+            writeln!(yaml, "        type: {}", topic.type_name)?;
+            writeln!(yaml, "        serialization_format: cdr")?;
+            writeln!(yaml, "        offered_qos_profiles: \"{}\"", DEFAULT_QOS.replace('\n', "\\n"))?;
+            writeln!(yaml, "      message_count: {}", topic.message_count)?;
+        }
+        writeln!(yaml, "  compression_format: \"\"")?;
+        writeln!(yaml, "  compression_mode: \"\"")?;
+        writeln!(yaml, "  relative_file_paths:\n    - {}", self.db_file)?;
+        writeln!(yaml, "  files:\n    - path: {}", self.db_file)?;
+        writeln!(yaml, "      starting_time:\n        nanoseconds_since_epoch: {}", start_ns)?;
+        writeln!(yaml, "      duration:\n        nanoseconds: {}", self.end_ns - start_ns)?;
+        writeln!(yaml, "      message_count: {}", message_count)?;
+        Ok(yaml)
+    }
+}
+
+/// Topics and frames used when exporting a run to a bag
+#[derive(Debug, Clone, PartialEq)]
+pub struct Ros2ExportOptions {
+    /// Topic for `nav_msgs/msg/Odometry` messages
+    pub odometry_topic: String,
+    /// Topic for `geometry_msgs/msg/PoseStamped` messages
+    pub pose_topic: String,
+    /// World frame id
+    pub frame_id: String,
+    /// Body frame id
+    pub child_frame_id: String,
+}
+
+impl Default for Ros2ExportOptions {
+    fn default() -> Self {
+        Self {
+            odometry_topic: "/odom".to_string(),
+            pose_topic: "/pose".to_string(),
+            frame_id: "map".to_string(),
+            child_frame_id: "base_link".to_string(),
+        }
+    }
+}
+
+/// Export a point mass run as a rosbag2 bag with odometry and pose topics
+///
+/// The yaw rate published in the odometry is the finite difference of the yaw
+/// between consecutive snapshots.
+///
+/// # Arguments
+/// * `directory` - Bag directory to create (must not exist)
+/// * `result` - Recorded run
+/// * `options` - Topic names and frame ids
+///
+/// # Returns
+/// Path of the bag directory
+pub fn export_ros2_bag<P: AsRef<Path>>(
+    directory: P,
+    result: &SimulationResult<PointMassState>,
+    options: &Ros2ExportOptions,
+) -> Result<PathBuf> {
+    let mut bag = Ros2BagWriter::create(directory)?;
+    let odometry_topic = bag.add_topic(&options.odometry_topic, Odometry::TYPE_NAME)?;
+    let pose_topic = bag.add_topic(&options.pose_topic, PoseStamped::TYPE_NAME)?;
+
+    let snapshots = result.snapshots();
+    for (index, snapshot) in snapshots.iter().enumerate() {
+        let yaw_rate = snapshots
+            .get(index + 1)
+            .filter(|next| next.time > snapshot.time)
+            .map_or(0.0, |next| {
+                Angle::wrap(next.state.yaw - snapshot.state.yaw) / (next.time - snapshot.time)
+            });
+        let odometry = Odometry::from_state(
+            snapshot.time,
+            &snapshot.state,
+            yaw_rate,
+            &options.frame_id,
+            &options.child_frame_id,
+        );
+        bag.write(odometry_topic, snapshot.time, &odometry)?;
+        let pose = PoseStamped::from_state(snapshot.time, &snapshot.state, &options.frame_id);
+        bag.write(pose_topic, snapshot.time, &pose)?;
+    }
+
+    bag.finish()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{export_ros2_bag, Ros2BagWriter, Ros2ExportOptions};
+    use crate::simulation::base_simulation::Simulation;
+    use crate::simulation::open_loop::OpenLoopSimulation;
+    use crate::tracks::circle::CircleTrack;
+    use rusqlite::Connection;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_export_ros2_bag_writes_topics_and_metadata() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let bag_dir = temp_dir.path().join("run");
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(1.0, 0.2)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+        let result = sim.run(0.1, 0.5).expect("run");
+
+        let path = export_ros2_bag(&bag_dir, &result, &Ros2ExportOptions::default())
+            .expect("export bag");
+
+        let metadata = std::fs::read_to_string(path.join("metadata.yaml")).expect("metadata");
+        assert!(metadata.contains("storage_identifier: sqlite3"));
+        assert!(metadata.contains("name: /odom"));
+        assert!(metadata.contains("message_count: 12"));
+        assert!(metadata.contains("nanoseconds: 500000000"));
+
+        let connection = Connection::open(path.join("run_0.db3")).expect("open db");
+        let count: i64 = connection
+            .query_row(
+                "SELECT COUNT(*) FROM messages m JOIN topics t ON m.topic_id = t.id \
+                 WHERE t.type = 'nav_msgs/msg/Odometry'",
+                [],
+                |row| row.get(0),
+            )
+            .expect("count odometry");
+        assert_eq!(count, 6);
+    }
+
+    #[test]
+    fn test_bag_writer_rejects_existing_directory_and_duplicate_topics() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let err = Ros2BagWriter::create(temp_dir.path()).err().expect("existing directory");
+        assert!(matches!(err, crate::Error::Io(_)));
+
+        let mut bag = Ros2BagWriter::create(temp_dir.path().join("bag")).expect("create bag");
+        bag.add_topic("/odom", "nav_msgs/msg/Odometry").expect("add topic");
+        let err = bag.add_topic("/odom", "nav_msgs/msg/Odometry").expect_err("duplicate topic");
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+    }
+}
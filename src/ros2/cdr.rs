@@ -0,0 +1,93 @@
+//! Minimal little-endian CDR (XCDR1) serializer for ROS 2 messages.
+
+/// Encapsulation header for little-endian plain CDR
+const CDR_LE_HEADER: [u8; 4] = [0x00, 0x01, 0x00, 0x00];
+
+/// Serializer writing primitive values with CDR alignment rules
+///
+/// Alignment is relative to the start of the payload, after the 4-byte
+/// encapsulation header.
+pub struct CdrWriter {
+    buffer: Vec<u8>,
+}
+
+impl CdrWriter {
+    /// Create a writer with the little-endian encapsulation header already written
+    pub fn new() -> Self {
+        Self {
+            buffer: CDR_LE_HEADER.to_vec(),
+        }
+    }
+
+    fn align(&mut self, alignment: usize) {
+        let offset = self.buffer.len() - CDR_LE_HEADER.len();
+        let padding = (alignment - offset % alignment) % alignment;
+        self.buffer.extend(std::iter::repeat_n(0u8, padding));
+    }
+
+    pub fn write_u8(&mut self, value: u8) {
+        self.buffer.push(value);
+    }
+
+    pub fn write_i32(&mut self, value: i32) {
+        self.align(4);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_u32(&mut self, value: u32) {
+        self.align(4);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    pub fn write_f64(&mut self, value: f64) {
+        self.align(8);
+        self.buffer.extend_from_slice(&value.to_le_bytes());
+    }
+
+    /// Write a string as its length (including the terminating NUL), bytes, and NUL
+    pub fn write_string(&mut self, value: &str) {
+        self.write_u32(value.len() as u32 + 1);
+        self.buffer.extend_from_slice(value.as_bytes());
+        self.buffer.push(0);
+    }
+
+    /// Write a fixed-size array of `f64` (no length prefix)
+    pub fn write_f64_array(&mut self, values: &[f64]) {
+        for &value in values {
+            self.write_f64(value);
+        }
+    }
+
+    /// Finish serialization and return the encoded bytes
+    pub fn into_bytes(self) -> Vec<u8> {
+        self.buffer
+    }
+}
+
+impl Default for CdrWriter {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CdrWriter;
+
+    #[test]
+    fn test_cdr_alignment_and_strings() {
+        let mut writer = CdrWriter::new();
+        writer.write_i32(7);
+        writer.write_string("ab");
+        writer.write_f64(1.0);
+
+        let bytes = writer.into_bytes();
+        assert_eq!(&bytes[..4], &[0, 1, 0, 0]);
+        assert_eq!(&bytes[4..8], &7i32.to_le_bytes());
+        assert_eq!(&bytes[8..12], &3u32.to_le_bytes());
+        assert_eq!(&bytes[12..15], b"ab\0");
+        // Padded from payload offset 11 to 16
+        assert_eq!(&bytes[15..20], &[0; 5]);
+        assert_eq!(&bytes[20..28], &1.0f64.to_le_bytes());
+    }
+}
@@ -0,0 +1,195 @@
+//! ROS 2 message types produced from simulation states.
+
+use super::cdr::CdrWriter;
+use crate::models::point_mass::PointMassState;
+
+/// ROS 2 message that can be serialized as CDR
+pub trait RosMessage {
+    /// Fully qualified ROS 2 type name (e.g., "nav_msgs/msg/Odometry")
+    const TYPE_NAME: &'static str;
+
+    /// Serialize the message as little-endian CDR, including the encapsulation header
+    fn encode_cdr(&self) -> Vec<u8>;
+}
+
+/// `builtin_interfaces/msg/Time` split from a time in seconds
+fn stamp(time: f64) -> (i32, u32) {
+    let sec = time.floor();
+    let nanosec = ((time - sec) * 1e9).round().min(999_999_999.0);
+    (sec as i32, nanosec as u32)
+}
+
+/// Quaternion (x, y, z, w) for a rotation of `yaw` around the z-axis
+fn yaw_quaternion(yaw: f64) -> [f64; 4] {
+    let half = yaw / 2.0;
+    [0.0, 0.0, half.sin(), half.cos()]
+}
+
+fn write_header(writer: &mut CdrWriter, time: f64, frame_id: &str) {
+    let (sec, nanosec) = stamp(time);
+    writer.write_i32(sec);
+    writer.write_u32(nanosec);
+    writer.write_string(frame_id);
+}
+
+fn write_pose(writer: &mut CdrWriter, x: f64, y: f64, yaw: f64) {
+    writer.write_f64_array(&[x, y, 0.0]);
+    writer.write_f64_array(&yaw_quaternion(yaw));
+}
+
+/// `geometry_msgs/msg/PoseStamped` of a planar pose
+#[derive(Debug, Clone, PartialEq)]
+pub struct PoseStamped {
+    /// Stamp in seconds
+    pub time: f64,
+    /// Frame the pose is expressed in (e.g., "map")
+    pub frame_id: String,
+    pub x: f64,
+    pub y: f64,
+    pub yaw: f64,
+}
+
+impl PoseStamped {
+    /// Pose of a point mass state at `time`
+    pub fn from_state(time: f64, state: &PointMassState, frame_id: &str) -> Self {
+        Self {
+            time,
+            frame_id: frame_id.to_string(),
+            x: state.x,
+            y: state.y,
+            yaw: state.yaw,
+        }
+    }
+}
+
+impl RosMessage for PoseStamped {
+    const TYPE_NAME: &'static str = "geometry_msgs/msg/PoseStamped";
+
+    fn encode_cdr(&self) -> Vec<u8> {
+        let mut writer = CdrWriter::new();
+        write_header(&mut writer, self.time, &self.frame_id);
+        write_pose(&mut writer, self.x, self.y, self.yaw);
+        writer.into_bytes()
+    }
+}
+
+/// `nav_msgs/msg/Odometry` of a planar vehicle
+///
+/// The pose is expressed in `frame_id`, the twist in the body frame `child_frame_id`.
+/// Covariances are left at zero (unknown).
+#[derive(Debug, Clone, PartialEq)]
+pub struct Odometry {
+    /// Stamp in seconds
+    pub time: f64,
+    /// World frame (e.g., "map" or "odom")
+    pub frame_id: String,
+    /// Body frame (e.g., "base_link")
+    pub child_frame_id: String,
+    pub x: f64,
+    pub y: f64,
+    pub yaw: f64,
+    /// Body frame x velocity in m/s
+    pub vx: f64,
+    /// Body frame y velocity in m/s
+    pub vy: f64,
+    /// Yaw rate in radians/s
+    pub yaw_rate: f64,
+}
+
+impl Odometry {
+    /// Odometry of a point mass state at `time`
+    pub fn from_state(
+        time: f64,
+        state: &PointMassState,
+        yaw_rate: f64,
+        frame_id: &str,
+        child_frame_id: &str,
+    ) -> Self {
+        Self {
+            time,
+            frame_id: frame_id.to_string(),
+            child_frame_id: child_frame_id.to_string(),
+            x: state.x,
+            y: state.y,
+            yaw: state.yaw,
+            vx: state.vx,
+            vy: state.vy,
+            yaw_rate,
+        }
+    }
+}
+
+impl RosMessage for Odometry {
+    const TYPE_NAME: &'static str = "nav_msgs/msg/Odometry";
+
+    fn encode_cdr(&self) -> Vec<u8> {
+        let mut writer = CdrWriter::new();
+        write_header(&mut writer, self.time, &self.frame_id);
+        writer.write_string(&self.child_frame_id);
+        write_pose(&mut writer, self.x, self.y, self.yaw);
+        writer.write_f64_array(&[0.0; 36]);
+        writer.write_f64_array(&[self.vx, self.vy, 0.0]);
+        writer.write_f64_array(&[0.0, 0.0, self.yaw_rate]);
+        writer.write_f64_array(&[0.0; 36]);
+        writer.into_bytes()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{stamp, yaw_quaternion, Odometry, PoseStamped, RosMessage};
+
+    #[test]
+    fn test_stamp_splits_seconds() {
+        assert_eq!(stamp(0.0), (0, 0));
+        assert_eq!(stamp(1.25), (1, 250_000_000));
+    }
+
+    #[test]
+    fn test_yaw_quaternion() {
+        let [x, y, z, w] = yaw_quaternion(std::f64::consts::PI);
+        assert_eq!((x, y), (0.0, 0.0));
+        assert!((z - 1.0).abs() < 1e-12);
+        assert!(w.abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_pose_stamped_layout() {
+        let pose = PoseStamped {
+            time: 1.5,
+            frame_id: "map".to_string(),
+            x: 2.0,
+            y: 3.0,
+            yaw: 0.0,
+        };
+        let bytes = pose.encode_cdr();
+
+        // header (4) + stamp (8) + "map" (4 + 4) + 7 doubles
+        assert_eq!(bytes.len(), 4 + 8 + 8 + 7 * 8);
+        assert_eq!(&bytes[20..28], &2.0f64.to_le_bytes());
+        assert_eq!(&bytes[bytes.len() - 8..], &1.0f64.to_le_bytes());
+    }
+
+    #[test]
+    fn test_odometry_layout() {
+        let odometry = Odometry {
+            time: 0.0,
+            frame_id: "map".to_string(),
+            child_frame_id: "base_link".to_string(),
+            x: 1.0,
+            y: 2.0,
+            yaw: 0.0,
+            vx: 4.0,
+            vy: 0.0,
+            yaw_rate: 0.5,
+        };
+        let bytes = odometry.encode_cdr();
+
+        // stamp (8) + "map" (8) + "base_link" (4 + 10, padded to 32) + 7 + 36 + 6 + 36 doubles
+        assert_eq!(bytes.len(), 4 + 32 + (7 + 36 + 6 + 36) * 8);
+        let twist = 4 + 32 + (7 + 36) * 8;
+        assert_eq!(&bytes[twist..twist + 8], &4.0f64.to_le_bytes());
+        assert_eq!(&bytes[twist + 40..twist + 48], &0.5f64.to_le_bytes());
+        assert_eq!(Odometry::TYPE_NAME, "nav_msgs/msg/Odometry");
+    }
+}
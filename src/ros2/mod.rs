@@ -0,0 +1,12 @@
+//! ROS 2 interoperability.
+//!
+//! Simulation states are converted to standard ROS 2 messages
+//! (`nav_msgs/msg/Odometry`, `geometry_msgs/msg/PoseStamped`) serialized as CDR, the
+//! wire format used by DDS. With the `ros2` feature, runs can be written as rosbag2
+//! bags (sqlite3 storage) that `ros2 bag play` republishes to a live ROS graph.
+
+pub mod cdr;
+pub mod messages;
+
+#[cfg(feature = "ros2")]
+pub mod bag;
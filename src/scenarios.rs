@@ -0,0 +1,505 @@
+//! Built-in demo scenarios showing what the crate can do.
+//!
+//! Each `Demo` builds its track, vehicles, and controllers, runs them, and summarizes
+//! the run in a `ScenarioReport`. `run` also writes the report (and, with the
+//! `plotting` feature, a thumbnail of each vehicle's path) into its own directory:
+//!
+//! ```no_run
+//! let report = lap_simulation::scenarios::run("stanley_square", "results/gallery")?;
+//! println!("{report}");
+//! # Ok::<(), lap_simulation::Error>(())
+//! ```
+//!
+//! The CLI runs them with `--scenario NAME` and lists them with `--list-scenarios`.
+
+use crate::controllers::presets::PresetLibrary;
+use crate::controllers::stanley::StanleyController;
+use crate::error::{Error, Result};
+use crate::io::output_dir::{ArtifactKind, OutputDirectory};
+use crate::math::vec2::Vec2;
+use crate::models::point_mass::{PointMass, PointMassState};
+use crate::simulation::multi_vehicle::{grid_positions, MultiVehicleSimulation};
+use crate::simulation::open_loop::OpenLoopSimulation;
+use crate::simulation::result::SimulationResult;
+use crate::simulation::weather::{self, Weather, WeatherConditions};
+use crate::tracks::arc_length::ArcLengthTable;
+use crate::tracks::base_track::SharedTrack;
+use crate::tracks::circle::CircleTrack;
+use crate::tracks::geometry::TrackGeometry;
+use crate::tracks::square::SquareTrack;
+use std::f64::consts::PI;
+use std::fmt;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::str::FromStr;
+use std::sync::Arc;
+
+/// Built-in demo scenario
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Demo {
+    /// Constant controls driving a point mass around the circular track
+    OpenLoopCircle,
+    /// Stanley controller following the square track
+    StanleySquare,
+    /// Four Stanley-controlled vehicles with different target speeds on an oval
+    RaceOval,
+    /// Two Stanley-controlled vehicles on the oval as a shower sets in
+    RainOval,
+}
+
+impl Demo {
+    /// Every demo, in gallery order
+    pub const ALL: [Demo; 4] = [
+        Demo::OpenLoopCircle,
+        Demo::StanleySquare,
+        Demo::RaceOval,
+        Demo::RainOval,
+    ];
+
+    /// Name used to select the demo (e.g., "stanley_square")
+    pub fn name(self) -> &'static str {
+        match self {
+            Demo::OpenLoopCircle => "open_loop_circle",
+            Demo::StanleySquare => "stanley_square",
+            Demo::RaceOval => "race_oval",
+            Demo::RainOval => "rain_oval",
+        }
+    }
+
+    /// One-line description shown in listings and reports
+    pub fn description(self) -> &'static str {
+        match self {
+            Demo::OpenLoopCircle => "Constant controls driving once around the circle track",
+            Demo::StanleySquare => "Stanley controller following the square track",
+            Demo::RaceOval => "Four Stanley-controlled vehicles racing on an oval",
+            Demo::RainOval => "Two vehicles on the oval as rain reduces the grip",
+        }
+    }
+
+    /// Name of the controller driving the vehicles, as recorded on the leaderboard
+    pub fn controller(self) -> &'static str {
+        match self {
+            Demo::OpenLoopCircle => "open_loop",
+            Demo::StanleySquare | Demo::RaceOval | Demo::RainOval => "stanley",
+        }
+    }
+
+    /// Run the demo without writing any file
+    pub fn simulate(self) -> Result<ScenarioReport> {
+        let (track, runs, collisions, weather) = match self {
+            Demo::OpenLoopCircle => {
+                let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+                // 10 m/s at 0.2 rad/s turns on the 50 m center line radius
+                let mut simulation = OpenLoopSimulation::builder()
+                    .track(Arc::clone(&track))
+                    .model(PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0))
+                    .controls(0.0, 0.2)
+                    .dt(0.1)
+                    .duration(2.0 * PI / 0.2)
+                    .build()?;
+                (track, vec![simulation.simulate()?], 0, Vec::new())
+            }
+            Demo::StanleySquare => {
+                let track: SharedTrack = Arc::new(SquareTrack::new(100.0, 10.0, 25));
+                let preset = PresetLibrary::builtin().get("square_stanley")?.clone();
+                let controller = StanleyController::from_preset(track.as_ref(), &preset)?;
+                let (x, y, yaw) = track.get_start_position();
+                let mut simulation = MultiVehicleSimulation::new(Arc::clone(&track));
+                simulation.add_controlled_vehicle(
+                    PointMass::with_initial_state(x, y, 0.0, yaw),
+                    controller,
+                );
+                let result = simulation.run(0.05, 60.0)?;
+                (track, result.runs, result.collisions.len(), result.weather)
+            }
+            Demo::RaceOval => {
+                let track: SharedTrack = Arc::new(oval_track(100.0, 40.0, 12.0));
+                let mut simulation = MultiVehicleSimulation::new(Arc::clone(&track));
+                // Each vehicle is 1 m/s slower than the one ahead, so the field spreads out
+                for (index, (x, y, yaw)) in grid_positions(track.as_ref(), 4, 10.0)
+                    .into_iter()
+                    .enumerate()
+                {
+                    let target_speed = 16.0 - index as f64;
+                    simulation.add_controlled_vehicle(
+                        PointMass::with_initial_state(x, y, 0.0, yaw),
+                        StanleyController::new(track.as_ref(), 1.0, 1.0, target_speed),
+                    );
+                }
+                let result = simulation.run(0.05, 60.0)?;
+                (track, result.runs, result.collisions.len(), result.weather)
+            }
+            Demo::RainOval => {
+                let track: SharedTrack = Arc::new(oval_track(100.0, 40.0, 12.0));
+                // Dry for 20 s, then heavy rain after 35 s: 17 m/s needs 7.2 m/s² in the
+                // turns, more than the wet tires give, while 12 m/s stays within the limit
+                let weather = Weather::transition(20.0, 35.0, 0.0, 1.0)?;
+                let mut simulation =
+                    MultiVehicleSimulation::new(Arc::clone(&track)).with_weather(weather);
+                for (index, (x, y, yaw)) in grid_positions(track.as_ref(), 2, 10.0)
+                    .into_iter()
+                    .enumerate()
+                {
+                    let mut model = PointMass::with_initial_state(x, y, 0.0, yaw);
+                    model.set_friction(Some(1.0));
+                    model.set_drag(RAIN_OVAL_DRAG);
+                    let target_speed = 17.0 - 5.0 * index as f64;
+                    simulation.add_controlled_vehicle(
+                        model,
+                        StanleyController::new(track.as_ref(), 1.0, 1.0, target_speed),
+                    );
+                }
+                let result = simulation.run(0.05, 60.0)?;
+                (track, result.runs, result.collisions.len(), result.weather)
+            }
+        };
+
+        let center_line = ArcLengthTable::from_track(track.as_ref());
+        Ok(ScenarioReport {
+            scenario: self.name().to_string(),
+            description: self.description().to_string(),
+            track: track.get_track_name().to_string(),
+            duration: runs.first().map_or(0.0, |run| run.duration()),
+            vehicles: runs
+                .iter()
+                .map(|run| VehicleSummary::from_run(&center_line, run))
+                .collect(),
+            collisions,
+            weather,
+            shared_track: track,
+            runs,
+            artifacts: Vec::new(),
+        })
+    }
+
+    /// Run the demo and write its report into `output_dir/<name>`
+    ///
+    /// # Returns
+    /// The report, listing the written files in `artifacts`
+    pub fn run<P: AsRef<Path>>(self, output_dir: P) -> Result<ScenarioReport> {
+        let mut report = self.simulate()?;
+        let mut outputs = OutputDirectory::new(output_dir.as_ref().join(self.name()));
+        outputs.create()?;
+        report.write_files(&mut outputs)?;
+        report.artifacts = outputs.paths(ArtifactKind::Final).map(Path::to_path_buf).collect();
+        Ok(report)
+    }
+}
+
+impl fmt::Display for Demo {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{}", self.name())
+    }
+}
+
+impl FromStr for Demo {
+    type Err = String;
+
+    fn from_str(value: &str) -> Result<Self, Self::Err> {
+        let value = value.trim().to_ascii_lowercase();
+        Demo::ALL
+            .into_iter()
+            .find(|demo| demo.name() == value)
+            .ok_or_else(|| {
+                let names: Vec<&str> = Demo::ALL.iter().map(|demo| demo.name()).collect();
+                format!("unknown scenario '{}' (available: {})", value, names.join(", "))
+            })
+    }
+}
+
+/// Run the demo called `name` and write its report into `output_dir/<name>`
+///
+/// # Returns
+/// The report, or `Error::InvalidInput` listing the available demos if `name` is unknown
+pub fn run<P: AsRef<Path>>(name: &str, output_dir: P) -> Result<ScenarioReport> {
+    name.parse::<Demo>().map_err(Error::InvalidInput)?.run(output_dir)
+}
+
+/// Run every demo, each writing into its own directory below `output_dir`
+pub fn run_all<P: AsRef<Path>>(output_dir: P) -> Result<Vec<ScenarioReport>> {
+    Demo::ALL
+        .into_iter()
+        .map(|demo| demo.run(output_dir.as_ref()))
+        .collect()
+}
+
+/// Summary of one vehicle's run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct VehicleSummary {
+    /// Completed laps, counted from the distance covered along the center line
+    pub laps: usize,
+    /// Distance driven in meters
+    pub distance: f64,
+    /// Distance driven divided by the run duration in m/s
+    pub average_speed: f64,
+    /// Highest recorded speed in m/s
+    pub max_speed: f64,
+    /// Fraction of the snapshots inside the track boundaries, in [0, 1]
+    pub in_track: f64,
+}
+
+impl VehicleSummary {
+    /// Summarize `run` on the track whose center line is `center_line`
+    pub fn from_run(center_line: &ArcLengthTable, run: &SimulationResult<PointMassState>) -> Self {
+        let snapshots = run.snapshots();
+        let distance: f64 = snapshots
+            .windows(2)
+            .map(|pair| {
+                Vec2::new(pair[0].state.x, pair[0].state.y)
+                    .distance(Vec2::new(pair[1].state.x, pair[1].state.y))
+            })
+            .sum();
+
+        // Unwrap the progress along the loop; steps never cover half a lap
+        let total = center_line.total_length();
+        let mut progress = 0.0;
+        let mut previous: Option<f64> = None;
+        for snapshot in snapshots {
+            let Some(s) = center_line.project(snapshot.state.x, snapshot.state.y) else {
+                break;
+            };
+            if let Some(previous) = previous {
+                progress += (s - previous + total / 2.0).rem_euclid(total) - total / 2.0;
+            }
+            previous = Some(s);
+        }
+
+        let duration = run.duration();
+        let on_track = snapshots.iter().filter(|snapshot| snapshot.in_track).count();
+        Self {
+            laps: if total > 0.0 { (progress / total).max(0.0) as usize } else { 0 },
+            distance,
+            average_speed: if duration > 0.0 { distance / duration } else { 0.0 },
+            max_speed: run
+                .states()
+                .map(|state| state.vx.hypot(state.vy))
+                .fold(0.0, f64::max),
+            in_track: if snapshots.is_empty() {
+                0.0
+            } else {
+                on_track as f64 / snapshots.len() as f64
+            },
+        }
+    }
+}
+
+/// Standard report of a demo run
+#[derive(Clone)]
+pub struct ScenarioReport {
+    /// Name of the demo (e.g., "race_oval")
+    pub scenario: String,
+    pub description: String,
+    /// Name of the track driven on
+    pub track: String,
+    /// Simulated duration in seconds
+    pub duration: f64,
+    /// Summary of each vehicle, in the order the vehicles were added
+    pub vehicles: Vec<VehicleSummary>,
+    /// Number of contacts between vehicles
+    pub collisions: usize,
+    /// Weather conditions per tick, empty for runs without weather
+    pub weather: Vec<WeatherConditions>,
+    /// Files written by `Demo::run`, empty for `Demo::simulate`
+    pub artifacts: Vec<PathBuf>,
+    shared_track: SharedTrack,
+    runs: Vec<SimulationResult<PointMassState>>,
+}
+
+impl ScenarioReport {
+    /// Track the vehicles drove on
+    pub fn shared_track(&self) -> SharedTrack {
+        Arc::clone(&self.shared_track)
+    }
+
+    /// Recorded states of each vehicle
+    pub fn runs(&self) -> &[SimulationResult<PointMassState>] {
+        &self.runs
+    }
+
+    /// Write `report.txt` and the path thumbnails, registering them as final artifacts
+    fn write_files(&self, outputs: &mut OutputDirectory) -> Result<()> {
+        let report_path = outputs.join("report.txt");
+        fs::write(&report_path, self.to_string())?;
+        outputs.register(report_path, ArtifactKind::Final);
+
+        #[cfg(feature = "plotting")]
+        for (index, run) in self.runs.iter().enumerate() {
+            let trail: Vec<(f64, f64)> = run.states().map(|state| (state.x, state.y)).collect();
+            let in_track: Vec<bool> =
+                run.snapshots().iter().map(|snapshot| snapshot.in_track).collect();
+            let path = outputs.join(format!("vehicle_{}.svg", index));
+            crate::plotting::plot_run_thumbnail(
+                self.shared_track.as_ref(),
+                &trail,
+                &in_track,
+                &path.to_string_lossy(),
+                THUMBNAIL_SIZE,
+            )?;
+            outputs.register(path, ArtifactKind::Final);
+        }
+        Ok(())
+    }
+}
+
+impl fmt::Debug for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        f.debug_struct("ScenarioReport")
+            .field("scenario", &self.scenario)
+            .field("track", &self.track)
+            .field("duration", &self.duration)
+            .field("vehicles", &self.vehicles)
+            .field("collisions", &self.collisions)
+            .field("weather", &weather::summarize(&self.weather))
+            .field("artifacts", &self.artifacts)
+            .finish_non_exhaustive()
+    }
+}
+
+impl fmt::Display for ScenarioReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(f, "Scenario {}: {}", self.scenario, self.description)?;
+        writeln!(f, "Track: {}, {:.1} s simulated", self.track, self.duration)?;
+        writeln!(f, "Weather: {}", weather::summarize(&self.weather))?;
+        for (index, vehicle) in self.vehicles.iter().enumerate() {
+            writeln!(
+                f,
+                "Vehicle {}: {} laps, {:.1} m, avg {:.2} m/s, max {:.2} m/s, {:.1}% on track",
+                index,
+                vehicle.laps,
+                vehicle.distance,
+                vehicle.average_speed,
+                vehicle.max_speed,
+                vehicle.in_track * 100.0
+            )?;
+        }
+        write!(f, "Collisions: {}", self.collisions)
+    }
+}
+
+/// Drag coefficient in 1/m of the vehicles in the rain demo
+const RAIN_OVAL_DRAG: f64 = 0.0004;
+
+/// Canvas size in pixels of the path thumbnails written by `Demo::run`
+#[cfg(feature = "plotting")]
+const THUMBNAIL_SIZE: (u32, u32) = (400, 400);
+
+/// Counter-clockwise oval of two straights joined by half circles, centered on the origin
+///
+/// The center line starts in the middle of the lower straight, heading along +x, with
+/// points about 2 m apart.
+///
+/// # Arguments
+/// * `straight` - Length of each straight in meters
+/// * `radius` - Radius of the center line in the turns in meters
+/// * `track_width` - Distance between the boundaries in meters
+fn oval_track(straight: f64, radius: f64, track_width: f64) -> TrackGeometry {
+    let turn = PI * radius;
+    let total = 2.0 * (straight + turn);
+    let count = (total / 2.0).ceil() as usize;
+    let half = straight / 2.0;
+
+    // Point and heading at distance `s` from the start
+    let pose = |s: f64| -> (Vec2, f64) {
+        if s < half {
+            (Vec2::new(s, -radius), 0.0)
+        } else if s < half + turn {
+            let angle = (s - half) / radius;
+            (Vec2::new(half + radius * angle.sin(), -radius * angle.cos()), angle)
+        } else if s < half + turn + straight {
+            (Vec2::new(half - (s - half - turn), radius), PI)
+        } else if s < half + 2.0 * turn + straight {
+            let angle = (s - half - turn - straight) / radius;
+            (Vec2::new(-half - radius * angle.sin(), radius * angle.cos()), PI + angle)
+        } else {
+            (Vec2::new(s - total, -radius), 0.0)
+        }
+    };
+
+    let mut center_line = Vec::with_capacity(count);
+    let mut inside = Vec::with_capacity(count);
+    let mut outside = Vec::with_capacity(count);
+    for index in 0..count {
+        let (point, yaw) = pose(total * index as f64 / count as f64);
+        // The turns go left, so the inside boundary is to the left of the heading
+        let left = Vec2::from_angle(yaw).perp() * (track_width / 2.0);
+        center_line.push(point);
+        inside.push(point + left);
+        outside.push(point - left);
+    }
+    TrackGeometry::new(
+        "Oval Track",
+        center_line,
+        inside,
+        outside,
+        (0.0, -radius, 0.0),
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{oval_track, run, Demo};
+    use crate::tracks::base_track::Track;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_demo_names_round_trip() {
+        for demo in Demo::ALL {
+            assert_eq!(demo.name().parse::<Demo>(), Ok(demo));
+        }
+        let err = "drift_figure_eight".parse::<Demo>().expect_err("unknown demo");
+        assert!(err.contains("race_oval"));
+        let err = run("drift_figure_eight", "unused").expect_err("unknown demo");
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_demos_stay_on_track() {
+        let report = Demo::OpenLoopCircle.simulate().expect("open-loop demo");
+        assert_eq!(report.vehicles.len(), 1);
+        assert_eq!(report.vehicles[0].in_track, 1.0);
+        assert!((report.vehicles[0].distance - 2.0 * PI * 50.0).abs() < 1.0);
+
+        let report = Demo::StanleySquare.simulate().expect("stanley demo");
+        let vehicle = report.vehicles[0];
+        assert_eq!(vehicle.in_track, 1.0);
+        assert!(vehicle.laps >= 1, "{report}");
+        assert!((vehicle.max_speed - 8.0).abs() < 0.1);
+
+        let report = Demo::RaceOval.simulate().expect("race demo");
+        assert_eq!(report.vehicles.len(), 4);
+        assert!(report.vehicles.iter().all(|vehicle| vehicle.in_track == 1.0), "{report}");
+        assert!(report.to_string().contains("Vehicle 3:"));
+        assert!(report.to_string().contains("Weather: dry"));
+    }
+
+    #[test]
+    fn test_rain_demo_loses_grip() {
+        let report = Demo::RainOval.simulate().expect("rain demo");
+        assert!(report.to_string().contains("Weather: dry -> wet"), "{report}");
+        // The fast vehicle slides wide once the track is wet, the slow one stays on it
+        assert!(report.vehicles[0].in_track < 1.0, "{report}");
+        assert_eq!(report.vehicles[1].in_track, 1.0, "{report}");
+    }
+
+    #[test]
+    fn test_run_writes_report() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let report = run("open_loop_circle", temp_dir.path()).expect("run demo");
+
+        let report_path = temp_dir.path().join("open_loop_circle").join("report.txt");
+        let text = std::fs::read_to_string(&report_path).expect("read report");
+        assert_eq!(text, report.to_string());
+        assert!(report.artifacts.contains(&report_path));
+        assert!(run("unknown", temp_dir.path()).is_err());
+    }
+
+    #[test]
+    fn test_oval_track_geometry() {
+        let track = oval_track(100.0, 40.0, 12.0);
+        assert!((track.total_length() - (200.0 + 2.0 * PI * 40.0)).abs() < 0.5);
+        assert!(track.is_in_track(0.0, -40.0));
+        assert!(track.is_in_track(90.0, 0.0));
+        assert!(!track.is_in_track(0.0, 0.0));
+        assert!(!track.is_in_track(0.0, -50.0));
+    }
+}
@@ -0,0 +1,112 @@
+//! Satellite positioning: noisy, biased position fixes at a low rate.
+
+use super::{SampleClock, Sensor, SensorNoise};
+use crate::math::random::SplitMix64;
+use crate::models::point_mass::PointMassState;
+
+/// Fix rate in Hz of a typical GPS receiver
+pub const DEFAULT_GPS_RATE: f64 = 10.0;
+
+/// Position fix
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct GpsFix {
+    /// Time of the fix in seconds
+    pub time: f64,
+    /// Measured world frame x position in meters
+    pub x: f64,
+    /// Measured world frame y position in meters
+    pub y: f64,
+}
+
+/// GPS receiver measuring the position at a fixed rate
+///
+/// The bias and the noise apply to both axes; each axis draws its own noise.
+#[derive(Debug, Clone)]
+pub struct GpsSensor {
+    noise: SensorNoise,
+    clock: SampleClock,
+    seed: u64,
+    rng: SplitMix64,
+    last: Option<GpsFix>,
+}
+
+impl GpsSensor {
+    /// Create a receiver
+    ///
+    /// # Arguments
+    /// * `rate` - Fix rate in Hz (every state if not positive)
+    /// * `noise` - Bias and noise of each axis in meters
+    /// * `seed` - Seed of the noise sequence
+    pub fn new(rate: f64, noise: SensorNoise, seed: u64) -> Self {
+        Self {
+            noise,
+            clock: SampleClock::new(rate),
+            seed,
+            rng: SplitMix64::new(seed),
+            last: None,
+        }
+    }
+
+    pub fn noise(&self) -> SensorNoise {
+        self.noise
+    }
+}
+
+impl Sensor for GpsSensor {
+    type Measurement = GpsFix;
+
+    fn measure(&mut self, time: f64, state: &PointMassState) -> Option<GpsFix> {
+        if !self.clock.due(time) {
+            return None;
+        }
+        let fix = GpsFix {
+            time,
+            x: self.noise.apply(state.x, &mut self.rng),
+            y: self.noise.apply(state.y, &mut self.rng),
+        };
+        self.last = Some(fix);
+        Some(fix)
+    }
+
+    fn last(&self) -> Option<&GpsFix> {
+        self.last.as_ref()
+    }
+
+    fn reset(&mut self) {
+        self.clock.reset();
+        self.rng = SplitMix64::new(self.seed);
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::GpsSensor;
+    use crate::models::point_mass::PointMassState;
+    use crate::sensors::{Sensor, SensorNoise};
+
+    #[test]
+    fn test_gps_noise_and_rate() {
+        let state = PointMassState {
+            x: 10.0,
+            y: -5.0,
+            vx: 0.0,
+            vy: 0.0,
+            yaw: 0.0,
+        };
+        let mut gps = GpsSensor::new(5.0, SensorNoise::new(1.0, 0.5), 3);
+        let fixes: Vec<_> = (0..1000)
+            .filter_map(|step| gps.measure(step as f64 * 0.01, &state))
+            .collect();
+        assert_eq!(fixes.len(), 50);
+        assert_eq!(fixes[1].time, 0.2);
+
+        let mean_x = fixes.iter().map(|fix| fix.x).sum::<f64>() / fixes.len() as f64;
+        assert!((mean_x - 11.0).abs() < 0.25, "{mean_x}");
+        assert!(fixes.iter().any(|fix| (fix.y + 4.0).abs() > 0.1));
+
+        gps.reset();
+        assert_eq!(gps.last(), None);
+        assert_eq!(gps.measure(0.0, &state), Some(fixes[0]));
+    }
+}
@@ -0,0 +1,132 @@
+//! Inertial measurement unit: body frame accelerations and yaw rate.
+//!
+//! The point mass state carries no accelerations, so the IMU differentiates the
+//! states it is fed: the forward acceleration from the change in speed, the yaw rate
+//! from the change in yaw, and the lateral acceleration as `vx * yaw_rate`.
+
+use super::{SampleClock, Sensor, SensorNoise};
+use crate::math::angle::Angle;
+use crate::math::random::SplitMix64;
+use crate::models::point_mass::PointMassState;
+
+/// Inertial measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ImuMeasurement {
+    /// Time of the measurement in seconds
+    pub time: f64,
+    /// Measured forward acceleration in m/s²
+    pub ax: f64,
+    /// Measured lateral acceleration in m/s², positive to the left
+    pub ay: f64,
+    /// Measured yaw rate in radians/s
+    pub yaw_rate: f64,
+}
+
+/// IMU measuring accelerations and yaw rate at a fixed rate
+#[derive(Debug, Clone)]
+pub struct ImuSensor {
+    acceleration: SensorNoise,
+    yaw_rate: SensorNoise,
+    clock: SampleClock,
+    seed: u64,
+    rng: SplitMix64,
+    /// Previous state, as (time, vx, yaw)
+    previous: Option<(f64, f64, f64)>,
+    /// True (ax, yaw rate) over the last step
+    rates: (f64, f64),
+    last: Option<ImuMeasurement>,
+}
+
+impl ImuSensor {
+    /// Create an IMU
+    ///
+    /// # Arguments
+    /// * `rate` - Sample rate in Hz (every state if not positive)
+    /// * `acceleration` - Bias and noise of both accelerations in m/s²
+    /// * `yaw_rate` - Bias and noise of the yaw rate in radians/s
+    /// * `seed` - Seed of the noise sequence
+    pub fn new(rate: f64, acceleration: SensorNoise, yaw_rate: SensorNoise, seed: u64) -> Self {
+        Self {
+            acceleration,
+            yaw_rate,
+            clock: SampleClock::new(rate),
+            seed,
+            rng: SplitMix64::new(seed),
+            previous: None,
+            rates: (0.0, 0.0),
+            last: None,
+        }
+    }
+
+    pub fn acceleration_noise(&self) -> SensorNoise {
+        self.acceleration
+    }
+
+    pub fn yaw_rate_noise(&self) -> SensorNoise {
+        self.yaw_rate
+    }
+}
+
+impl Sensor for ImuSensor {
+    type Measurement = ImuMeasurement;
+
+    fn measure(&mut self, time: f64, state: &PointMassState) -> Option<ImuMeasurement> {
+        // Differentiate every state, also between samples
+        if let Some((previous_time, vx, yaw)) = self.previous {
+            let dt = time - previous_time;
+            if dt > 0.0 {
+                self.rates = ((state.vx - vx) / dt, Angle::wrap(state.yaw - yaw) / dt);
+            }
+        }
+        self.previous = Some((time, state.vx, state.yaw));
+        if !self.clock.due(time) {
+            return None;
+        }
+        let (ax, yaw_rate) = self.rates;
+        let measurement = ImuMeasurement {
+            time,
+            ax: self.acceleration.apply(ax, &mut self.rng),
+            ay: self.acceleration.apply(state.vx * yaw_rate, &mut self.rng),
+            yaw_rate: self.yaw_rate.apply(yaw_rate, &mut self.rng),
+        };
+        self.last = Some(measurement);
+        Some(measurement)
+    }
+
+    fn last(&self) -> Option<&ImuMeasurement> {
+        self.last.as_ref()
+    }
+
+    fn reset(&mut self) {
+        self.clock.reset();
+        self.rng = SplitMix64::new(self.seed);
+        self.previous = None;
+        self.rates = (0.0, 0.0);
+        self.last = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ImuSensor;
+    use crate::models::base_model::Model;
+    use crate::models::point_mass::PointMass;
+    use crate::sensors::{Sensor, SensorNoise};
+
+    #[test]
+    fn test_imu_measures_controls() {
+        let mut model = PointMass::with_initial_state(0.0, 0.0, 5.0, 0.0);
+        model.set_controls(1.5, 0.2);
+        let mut imu = ImuSensor::new(0.0, SensorNoise::NONE, SensorNoise::new(0.01, 0.0), 1);
+        imu.measure(0.0, model.get_state());
+        for step in 1..=10 {
+            model.step(0.1);
+            imu.measure(step as f64 * 0.1, model.get_state());
+        }
+        let measurement = imu.last().copied().expect("measurement");
+        assert!((measurement.ax - 1.5).abs() < 1e-9);
+        assert!((measurement.yaw_rate - 0.21).abs() < 1e-9);
+        let vx = model.get_state().vx;
+        assert!((measurement.ay - vx * 0.2).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,310 @@
+//! Simulated sensors measuring a model's true state.
+//!
+//! Each sensor samples the state at its own rate and adds a constant bias and white
+//! Gaussian noise from a seeded `SplitMix64`, so measurements are reproducible.
+//! `SensedController` feeds a controller the state rebuilt from a `SensorSuite`
+//! instead of the ground truth, in any simulation that accepts controllers.
+
+pub mod gps;
+pub mod imu;
+pub mod speed;
+
+use crate::controllers::base_controller::{Controller, ControllerTelemetry};
+use crate::math::angle::Angle;
+use crate::math::random::SplitMix64;
+use crate::models::point_mass::PointMassState;
+use crate::tracks::arc_length::TrackPosition;
+use gps::{GpsFix, GpsSensor};
+use imu::{ImuMeasurement, ImuSensor};
+use speed::{SpeedMeasurement, SpeedSensor};
+
+/// Sensor sampling a point mass state
+pub trait Sensor {
+    type Measurement;
+
+    /// Measure the state reached at `time`
+    ///
+    /// Call with every state of the run, in time order.
+    ///
+    /// # Returns
+    /// A new measurement if one is due at `time`, `None` between samples
+    fn measure(&mut self, time: f64, state: &PointMassState) -> Option<Self::Measurement>;
+
+    /// Latest measurement, if any
+    fn last(&self) -> Option<&Self::Measurement>;
+
+    /// Forget the measurements and restart the noise sequence
+    fn reset(&mut self);
+}
+
+/// Constant bias and white Gaussian noise of a measured quantity
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct SensorNoise {
+    /// Offset added to every measurement
+    pub bias: f64,
+    /// Standard deviation of the noise
+    pub std_dev: f64,
+}
+
+impl SensorNoise {
+    /// Noise-free, unbiased measurements
+    pub const NONE: SensorNoise = SensorNoise {
+        bias: 0.0,
+        std_dev: 0.0,
+    };
+
+    pub fn new(bias: f64, std_dev: f64) -> Self {
+        Self { bias, std_dev }
+    }
+
+    /// `value` as measured with this bias and noise
+    pub(crate) fn apply(&self, value: f64, rng: &mut SplitMix64) -> f64 {
+        let noise = if self.std_dev > 0.0 { self.std_dev * rng.gaussian() } else { 0.0 };
+        value + self.bias + noise
+    }
+}
+
+/// Tells when a sensor sampling at a fixed rate is due
+#[derive(Debug, Clone, PartialEq)]
+pub(crate) struct SampleClock {
+    period: f64,
+    next: Option<f64>,
+}
+
+impl SampleClock {
+    /// Clock sampling at `rate` Hz; a rate that is not positive samples every state
+    pub(crate) fn new(rate: f64) -> Self {
+        Self {
+            period: if rate > 0.0 && rate.is_finite() { 1.0 / rate } else { 0.0 },
+            next: None,
+        }
+    }
+
+    /// Whether a sample is due at `time`; the first call always samples
+    pub(crate) fn due(&mut self, time: f64) -> bool {
+        // Tolerate rounding in accumulated simulation times
+        if self.next.is_some_and(|next| time + 1e-9 < next) {
+            return false;
+        }
+        let next = self.next.map_or(time, |next| next.max(time - self.period));
+        self.next = Some(next + self.period);
+        true
+    }
+
+    pub(crate) fn reset(&mut self) {
+        self.next = None;
+    }
+}
+
+/// Measurements taken by a `SensorSuite` at one time, `None` for sensors that were
+/// absent or not due
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct Measurements {
+    pub gps: Option<GpsFix>,
+    pub imu: Option<ImuMeasurement>,
+    pub speed: Option<SpeedMeasurement>,
+}
+
+/// Sensors whose latest measurements stand in for the true state
+///
+/// Quantities without a sensor are taken from the true state.
+#[derive(Debug, Clone, Default)]
+pub struct SensorSuite {
+    pub gps: Option<GpsSensor>,
+    pub imu: Option<ImuSensor>,
+    pub speed: Option<SpeedSensor>,
+    /// Yaw integrated from the IMU yaw rate, as (time, yaw)
+    heading: Option<(f64, f64)>,
+}
+
+impl SensorSuite {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_gps(mut self, gps: GpsSensor) -> Self {
+        self.gps = Some(gps);
+        self
+    }
+
+    pub fn with_imu(mut self, imu: ImuSensor) -> Self {
+        self.imu = Some(imu);
+        self
+    }
+
+    pub fn with_speed(mut self, speed: SpeedSensor) -> Self {
+        self.speed = Some(speed);
+        self
+    }
+
+    /// Feed every sensor the state at `time`
+    ///
+    /// # Returns
+    /// The measurements of the sensors that were due
+    pub fn measure(&mut self, time: f64, truth: &PointMassState) -> Measurements {
+        Measurements {
+            gps: self.gps.as_mut().and_then(|gps| gps.measure(time, truth)),
+            imu: self.imu.as_mut().and_then(|imu| imu.measure(time, truth)),
+            speed: self.speed.as_mut().and_then(|speed| speed.measure(time, truth)),
+        }
+    }
+
+    /// Feed every sensor the state at `time` and rebuild the state from the latest
+    /// measurements
+    ///
+    /// The position comes from the latest GPS fix and the forward speed from the
+    /// speed sensor. With an IMU, the yaw is dead-reckoned from its yaw rate, starting
+    /// from the true yaw of the first call. Lateral velocity is always 0, as in the
+    /// point mass model.
+    pub fn measured_state(&mut self, time: f64, truth: &PointMassState) -> PointMassState {
+        self.measure(time, truth);
+        let mut state = PointMassState { vy: 0.0, ..truth.clone() };
+        if let Some(fix) = self.gps.as_ref().and_then(|gps| gps.last()) {
+            (state.x, state.y) = (fix.x, fix.y);
+        }
+        if let Some(measurement) = self.speed.as_ref().and_then(|speed| speed.last()) {
+            state.vx = measurement.speed;
+        }
+        if let Some(imu) = self.imu.as_ref() {
+            let yaw_rate = imu.last().map_or(0.0, |measurement| measurement.yaw_rate);
+            let (previous, yaw) = self.heading.unwrap_or((time, truth.yaw));
+            let yaw = Angle::wrap(yaw + yaw_rate * (time - previous));
+            self.heading = Some((time, yaw));
+            state.yaw = yaw;
+        }
+        state
+    }
+
+    /// Reset every sensor and the dead-reckoned yaw
+    pub fn reset(&mut self) {
+        if let Some(gps) = self.gps.as_mut() {
+            gps.reset();
+        }
+        if let Some(imu) = self.imu.as_mut() {
+            imu.reset();
+        }
+        if let Some(speed) = self.speed.as_mut() {
+            speed.reset();
+        }
+        self.heading = None;
+    }
+}
+
+/// Controller driving on measurements instead of the true state
+///
+/// Wraps another controller and hands it the state rebuilt by a `SensorSuite`. The
+/// position on the track known to the simulation is ignored, since it comes from the
+/// true state; the wrapped controller locates the measured state itself.
+#[derive(Debug, Clone)]
+pub struct SensedController<C> {
+    controller: C,
+    sensors: SensorSuite,
+}
+
+impl<C> SensedController<C> {
+    pub fn new(controller: C, sensors: SensorSuite) -> Self {
+        Self { controller, sensors }
+    }
+
+    pub fn sensors(&self) -> &SensorSuite {
+        &self.sensors
+    }
+
+    /// Get the wrapped controller
+    pub fn controller(&self) -> &C {
+        &self.controller
+    }
+}
+
+impl<C: Controller<PointMassState>> Controller<PointMassState> for SensedController<C> {
+    fn step(&mut self, time: f64, state: &PointMassState) -> (f64, f64) {
+        let measured = self.sensors.measured_state(time, state);
+        self.controller.step(time, &measured)
+    }
+
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &PointMassState,
+        _position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        self.step(time, state)
+    }
+
+    fn set_friction_limit(&mut self, limit: Option<f64>) {
+        self.controller.set_friction_limit(limit);
+    }
+
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        self.controller.telemetry()
+    }
+
+    fn reset(&mut self) {
+        self.controller.reset();
+        self.sensors.reset();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::gps::GpsSensor;
+    use super::speed::SpeedSensor;
+    use super::{SampleClock, SensedController, SensorNoise, SensorSuite};
+    use crate::controllers::stanley::StanleyController;
+    use crate::models::point_mass::{PointMass, PointMassState};
+    use crate::simulation::multi_vehicle::MultiVehicleSimulation;
+    use crate::tracks::base_track::SharedTrack;
+    use crate::tracks::circle::CircleTrack;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sample_clock_rate() {
+        let mut clock = SampleClock::new(10.0);
+        let due: Vec<bool> = (0..6).map(|step| clock.due(step as f64 * 0.05)).collect();
+        assert_eq!(due, [true, false, true, false, true, false]);
+
+        let mut every_state = SampleClock::new(0.0);
+        assert!((0..3).all(|step| every_state.due(step as f64 * 0.05)));
+    }
+
+    #[test]
+    fn test_suite_holds_measurements_between_samples() {
+        let mut suite = SensorSuite::new()
+            .with_gps(GpsSensor::new(1.0, SensorNoise::new(0.5, 0.0), 1))
+            .with_speed(SpeedSensor::new(0.0, SensorNoise::NONE, 1));
+        let state = |x: f64| PointMassState {
+            x,
+            y: 0.0,
+            vx: 10.0,
+            vy: 0.0,
+            yaw: 0.0,
+        };
+        let first = suite.measured_state(0.0, &state(0.0));
+        assert_eq!((first.x, first.y, first.vx), (0.5, 0.5, 10.0));
+        // No new fix until 1 s: the position holds
+        let held = suite.measured_state(0.5, &state(5.0));
+        assert_eq!(held.x, 0.5);
+        assert_eq!(suite.measured_state(1.0, &state(10.0)).x, 10.5);
+    }
+
+    #[test]
+    fn test_sensed_controller_drives_on_measurements() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let (x, y, yaw) = track.get_start_position();
+        let sensors = SensorSuite::new()
+            .with_gps(GpsSensor::new(10.0, SensorNoise::new(0.0, 0.3), 7))
+            .with_speed(SpeedSensor::new(50.0, SensorNoise::new(0.0, 0.1), 7));
+        let controller = StanleyController::new(track.as_ref(), 2.0, 1.0, 10.0);
+        let mut simulation = MultiVehicleSimulation::new(Arc::clone(&track)).with_threads(1);
+        simulation.add_controlled_vehicle(
+            PointMass::with_initial_state(x, y, 0.0, yaw),
+            SensedController::new(controller, sensors),
+        );
+
+        let result = simulation.run(0.02, 30.0).expect("run");
+        let run = &result.runs[0];
+        assert!(run.snapshots().iter().all(|snapshot| snapshot.in_track));
+        let vx = run.last().expect("final snapshot").state.vx;
+        assert!((vx - 10.0).abs() < 1.0, "{vx}");
+    }
+}
@@ -0,0 +1,72 @@
+//! Wheel speed sensor: noisy, biased forward speed.
+
+use super::{SampleClock, Sensor, SensorNoise};
+use crate::math::random::SplitMix64;
+use crate::models::point_mass::PointMassState;
+
+/// Speed measurement
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SpeedMeasurement {
+    /// Time of the measurement in seconds
+    pub time: f64,
+    /// Measured forward speed in m/s
+    pub speed: f64,
+}
+
+/// Sensor measuring the forward (body frame x) speed at a fixed rate
+#[derive(Debug, Clone)]
+pub struct SpeedSensor {
+    noise: SensorNoise,
+    clock: SampleClock,
+    seed: u64,
+    rng: SplitMix64,
+    last: Option<SpeedMeasurement>,
+}
+
+impl SpeedSensor {
+    /// Create a speed sensor
+    ///
+    /// # Arguments
+    /// * `rate` - Sample rate in Hz (every state if not positive)
+    /// * `noise` - Bias and noise in m/s
+    /// * `seed` - Seed of the noise sequence
+    pub fn new(rate: f64, noise: SensorNoise, seed: u64) -> Self {
+        Self {
+            noise,
+            clock: SampleClock::new(rate),
+            seed,
+            rng: SplitMix64::new(seed),
+            last: None,
+        }
+    }
+
+    pub fn noise(&self) -> SensorNoise {
+        self.noise
+    }
+}
+
+impl Sensor for SpeedSensor {
+    type Measurement = SpeedMeasurement;
+
+    fn measure(&mut self, time: f64, state: &PointMassState) -> Option<SpeedMeasurement> {
+        if !self.clock.due(time) {
+            return None;
+        }
+        let measurement = SpeedMeasurement {
+            time,
+            speed: self.noise.apply(state.vx, &mut self.rng),
+        };
+        self.last = Some(measurement);
+        Some(measurement)
+    }
+
+    fn last(&self) -> Option<&SpeedMeasurement> {
+        self.last.as_ref()
+    }
+
+    fn reset(&mut self) {
+        self.clock.reset();
+        self.rng = SplitMix64::new(self.seed);
+        self.last = None;
+    }
+}
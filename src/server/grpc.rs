@@ -0,0 +1,482 @@
+//! gRPC server exposing `ScenarioRegistry` (see `proto/simulation.proto`).
+//!
+//! The message and service types are written by hand to match the proto file, so
+//! building the crate does not require `protoc`. Clients in other languages generate
+//! their stubs from `proto/simulation.proto`.
+
+// RPC handlers return `tonic::Status` by design
+#![allow(clippy::result_large_err)]
+
+use super::metrics::RunMetrics;
+use super::registry::ScenarioRegistry;
+use crate::diagnostics;
+use crate::config::{ScenarioConfig, TrackConfig};
+use crate::error::Error;
+use crate::models::point_mass::PointMassState;
+use crate::simulation::result::StateSnapshot;
+use crate::simulation::tasks::spawn_cancellable;
+use std::convert::Infallible;
+use std::io;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Instant;
+use tonic::codec::ProstCodec;
+use tonic::codegen::{empty_body, http, Body, BoxFuture, Context, Poll, Service, StdError};
+use tonic::server::{NamedService, UnaryService};
+use tonic::{Request, Response, Status};
+
+/// Fully qualified gRPC service name
+pub const SERVICE_NAME: &str = "lap_simulation.SimulationService";
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct Snapshot {
+    #[prost(double, tag = "1")]
+    pub time: f64,
+    #[prost(double, tag = "2")]
+    pub x: f64,
+    #[prost(double, tag = "3")]
+    pub y: f64,
+    #[prost(double, tag = "4")]
+    pub vx: f64,
+    #[prost(double, tag = "5")]
+    pub vy: f64,
+    #[prost(double, tag = "6")]
+    pub yaw: f64,
+    #[prost(bool, tag = "7")]
+    pub in_track: bool,
+}
+
+impl From<&StateSnapshot<PointMassState>> for Snapshot {
+    fn from(snapshot: &StateSnapshot<PointMassState>) -> Self {
+        Self {
+            time: snapshot.time,
+            x: snapshot.state.x,
+            y: snapshot.state.y,
+            vx: snapshot.state.vx,
+            vy: snapshot.state.vy,
+            yaw: snapshot.state.yaw,
+            in_track: snapshot.in_track,
+        }
+    }
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct CreateScenarioRequest {
+    #[prost(double, tag = "1")]
+    pub radius: f64,
+    #[prost(double, tag = "2")]
+    pub track_width: f64,
+    #[prost(double, tag = "3")]
+    pub ax: f64,
+    #[prost(double, tag = "4")]
+    pub yaw_rate: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct CreateScenarioResponse {
+    #[prost(uint64, tag = "1")]
+    pub scenario_id: u64,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct StepRequest {
+    #[prost(uint64, tag = "1")]
+    pub scenario_id: u64,
+    #[prost(double, tag = "2")]
+    pub dt: f64,
+    #[prost(uint32, tag = "3")]
+    pub steps: u32,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct StepResponse {
+    #[prost(message, optional, tag = "1")]
+    pub snapshot: Option<Snapshot>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct RunRequest {
+    #[prost(uint64, tag = "1")]
+    pub scenario_id: u64,
+    #[prost(double, tag = "2")]
+    pub dt: f64,
+    #[prost(double, tag = "3")]
+    pub duration: f64,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct RunResponse {
+    #[prost(uint32, tag = "1")]
+    pub snapshot_count: u32,
+    #[prost(message, optional, tag = "2")]
+    pub final_snapshot: Option<Snapshot>,
+}
+
+#[derive(Clone, Copy, PartialEq, prost::Message)]
+pub struct FetchResultsRequest {
+    #[prost(uint64, tag = "1")]
+    pub scenario_id: u64,
+}
+
+#[derive(Clone, PartialEq, prost::Message)]
+pub struct FetchResultsResponse {
+    #[prost(message, repeated, tag = "1")]
+    pub snapshots: Vec<Snapshot>,
+}
+
+/// Implementation of the RPCs on top of a shared scenario registry
+#[derive(Clone, Default)]
+pub struct SimulationService {
+    registry: Arc<Mutex<ScenarioRegistry>>,
+    metrics: Arc<Mutex<RunMetrics>>,
+}
+
+impl SimulationService {
+    pub fn new(registry: Arc<Mutex<ScenarioRegistry>>) -> Self {
+        Self {
+            registry,
+            metrics: Arc::default(),
+        }
+    }
+
+    /// Metrics of the `Run` calls, e.g. to expose with `metrics::MetricsExporter`
+    pub fn metrics(&self) -> Arc<Mutex<RunMetrics>> {
+        Arc::clone(&self.metrics)
+    }
+
+    fn with_registry<T>(
+        &self,
+        f: impl FnOnce(&mut ScenarioRegistry) -> crate::Result<T>,
+    ) -> Result<T, Status> {
+        let mut registry = self
+            .registry
+            .lock()
+            .map_err(|_| Status::internal("scenario registry poisoned"))?;
+        f(&mut registry).map_err(|err| match err {
+            Error::NotFound(_) => Status::not_found(err.to_string()),
+            Error::Io(_) => Status::internal(err.to_string()),
+            err => Status::invalid_argument(err.to_string()),
+        })
+    }
+
+    pub fn create_scenario(
+        &self,
+        request: CreateScenarioRequest,
+    ) -> Result<CreateScenarioResponse, Status> {
+        let config = ScenarioConfig {
+            track: TrackConfig::Circle {
+                radius: request.radius,
+                track_width: request.track_width,
+                num_points: 100,
+            },
+            controls: (request.ax, request.yaw_rate),
+            ..ScenarioConfig::default()
+        };
+        let scenario_id = self.with_registry(|registry| registry.create(config))?;
+        Ok(CreateScenarioResponse { scenario_id })
+    }
+
+    pub fn step(&self, request: StepRequest) -> Result<StepResponse, Status> {
+        let snapshot = self.with_registry(|registry| {
+            let scenario = registry.get_mut(request.scenario_id)?;
+            Ok(Snapshot::from(scenario.step(request.dt, request.steps)?))
+        })?;
+        Ok(StepResponse {
+            snapshot: Some(snapshot),
+        })
+    }
+
+    pub fn run(&self, request: RunRequest) -> Result<RunResponse, Status> {
+        self.with_registry(|registry| {
+            let scenario = registry.get_mut(request.scenario_id)?;
+            let started = Instant::now();
+            let outcome = scenario.run(request.dt, request.duration);
+            if let Ok(mut metrics) = self.metrics.lock() {
+                match &outcome {
+                    Ok(_) => metrics.record_run(started.elapsed(), scenario.result()),
+                    Err(_) => metrics.record_failure(),
+                }
+            }
+            let count = outcome?;
+            Ok(RunResponse {
+                snapshot_count: u32::try_from(count).unwrap_or(u32::MAX),
+                final_snapshot: scenario.result().last().map(Snapshot::from),
+            })
+        })
+    }
+
+    pub fn fetch_results(
+        &self,
+        request: FetchResultsRequest,
+    ) -> Result<FetchResultsResponse, Status> {
+        self.with_registry(|registry| {
+            let scenario = registry.get(request.scenario_id)?;
+            Ok(FetchResultsResponse {
+                snapshots: scenario.result().snapshots().iter().map(Snapshot::from).collect(),
+            })
+        })
+    }
+}
+
+/// Adapter turning one `SimulationService` method into a tonic unary service
+struct Unary<Req, Resp> {
+    service: SimulationService,
+    method: fn(&SimulationService, Req) -> Result<Resp, Status>,
+}
+
+impl<Req: Send + 'static, Resp: Send + 'static> UnaryService<Req> for Unary<Req, Resp> {
+    type Response = Resp;
+    type Future = BoxFuture<Response<Resp>, Status>;
+
+    fn call(&mut self, request: Request<Req>) -> Self::Future {
+        let service = self.service.clone();
+        let method = self.method;
+        let request = request.into_inner();
+        // Runs are CPU-bound, so keep them off the executor threads
+        let job = spawn_cancellable(move |_| Ok(method(&service, request)));
+        Box::pin(async move {
+            job.await
+                .map_err(|err| Status::internal(err.to_string()))?
+                .map(Response::new)
+        })
+    }
+}
+
+fn unary<Req, Resp, B>(
+    service: SimulationService,
+    method: fn(&SimulationService, Req) -> Result<Resp, Status>,
+    request: http::Request<B>,
+) -> BoxFuture<http::Response<tonic::body::BoxBody>, Infallible>
+where
+    Req: prost::Message + Default + Send + 'static,
+    Resp: prost::Message + Send + 'static,
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    Box::pin(async move {
+        let mut grpc = tonic::server::Grpc::new(ProstCodec::<Resp, Req>::default());
+        Ok(grpc.unary(Unary { service, method }, request).await)
+    })
+}
+
+/// Tower service routing gRPC requests to `SimulationService`
+#[derive(Clone, Default)]
+pub struct SimulationServiceServer {
+    service: SimulationService,
+}
+
+impl SimulationServiceServer {
+    pub fn new(service: SimulationService) -> Self {
+        Self { service }
+    }
+}
+
+impl<B> Service<http::Request<B>> for SimulationServiceServer
+where
+    B: Body + Send + 'static,
+    B::Error: Into<StdError> + Send + 'static,
+{
+    type Response = http::Response<tonic::body::BoxBody>;
+    type Error = Infallible;
+    type Future = BoxFuture<Self::Response, Self::Error>;
+
+    fn poll_ready(&mut self, _cx: &mut Context<'_>) -> Poll<Result<(), Self::Error>> {
+        Poll::Ready(Ok(()))
+    }
+
+    fn call(&mut self, request: http::Request<B>) -> Self::Future {
+        let service = self.service.clone();
+        let method = request
+            .uri()
+            .path()
+            .strip_prefix(&format!("/{}/", SERVICE_NAME))
+            .unwrap_or_default()
+            .to_string();
+        match method.as_str() {
+            "CreateScenario" => unary(service, SimulationService::create_scenario, request),
+            "Step" => unary(service, SimulationService::step, request),
+            "Run" => unary(service, SimulationService::run, request),
+            "FetchResults" => unary(service, SimulationService::fetch_results, request),
+            _ => Box::pin(async move {
+                let mut response = http::Response::new(empty_body());
+                let headers = response.headers_mut();
+                headers.insert("grpc-status", http::HeaderValue::from_static("12"));
+                headers.insert(
+                    http::header::CONTENT_TYPE,
+                    http::HeaderValue::from_static("application/grpc"),
+                );
+                Ok(response)
+            }),
+        }
+    }
+}
+
+impl NamedService for SimulationServiceServer {
+    const NAME: &'static str = SERVICE_NAME;
+}
+
+/// Serve the simulation service on an already bound listener until the task is dropped
+pub async fn serve_with_listener(
+    listener: tokio::net::TcpListener,
+    service: SimulationService,
+) -> crate::Result<()> {
+    let incoming = tonic::transport::server::TcpIncoming::from_listener(listener, true, None)
+        .map_err(|err| io::Error::other(format!("failed to accept gRPC connections: {}", err)))?;
+    tonic::transport::Server::builder()
+        .add_service(SimulationServiceServer::new(service))
+        .serve_with_incoming(incoming)
+        .await
+        .map_err(io::Error::other)?;
+    Ok(())
+}
+
+/// Serve the simulation service on `addr` (e.g., "127.0.0.1:50051"), blocking forever
+pub fn serve(addr: SocketAddr) -> crate::Result<()> {
+    let runtime = tokio::runtime::Runtime::new()?;
+    runtime.block_on(async move {
+        let listener = tokio::net::TcpListener::bind(addr).await?;
+        diagnostics::info(format_args!(
+            "gRPC simulation server listening on {}",
+            listener.local_addr()?
+        ));
+        serve_with_listener(listener, SimulationService::default()).await
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        serve_with_listener, CreateScenarioRequest, CreateScenarioResponse, FetchResultsRequest,
+        FetchResultsResponse, RunRequest, SimulationService, StepRequest, SERVICE_NAME,
+    };
+    use tonic::codec::ProstCodec;
+    use tonic::codegen::http::uri::PathAndQuery;
+    use tonic::Code;
+
+    #[test]
+    fn test_service_methods() {
+        let service = SimulationService::default();
+        let created = service
+            .create_scenario(CreateScenarioRequest {
+                radius: 50.0,
+                track_width: 10.0,
+                ax: 1.0,
+                yaw_rate: 0.0,
+            })
+            .expect("create scenario");
+
+        let step = service
+            .step(StepRequest {
+                scenario_id: created.scenario_id,
+                dt: 0.1,
+                steps: 3,
+            })
+            .expect("step");
+        let snapshot = step.snapshot.expect("snapshot");
+        assert!((snapshot.time - 0.3).abs() < 1e-9);
+        assert!((snapshot.vx - 0.3).abs() < 1e-9);
+
+        let run = service
+            .run(RunRequest {
+                scenario_id: created.scenario_id,
+                dt: 0.1,
+                duration: 0.5,
+            })
+            .expect("run");
+        assert_eq!(run.snapshot_count, 6);
+        assert_eq!(service.metrics().lock().expect("metrics").runs_completed(), 1);
+
+        let results = service
+            .fetch_results(FetchResultsRequest {
+                scenario_id: created.scenario_id,
+            })
+            .expect("fetch results");
+        assert_eq!(results.snapshots.len(), 6);
+
+        let missing = service
+            .fetch_results(FetchResultsRequest { scenario_id: 99 })
+            .expect_err("expected unknown scenario");
+        assert_eq!(missing.code(), Code::NotFound);
+        let invalid = service
+            .step(StepRequest {
+                scenario_id: created.scenario_id,
+                dt: 0.0,
+                steps: 1,
+            })
+            .expect_err("expected invalid dt");
+        assert_eq!(invalid.code(), Code::InvalidArgument);
+    }
+
+    #[test]
+    fn test_grpc_round_trip_over_http2() {
+        let runtime = tokio::runtime::Runtime::new().expect("create runtime");
+        runtime.block_on(async {
+            let listener = tokio::net::TcpListener::bind("127.0.0.1:0")
+                .await
+                .expect("bind listener");
+            let addr = listener.local_addr().expect("listener addr");
+            tokio::spawn(async move {
+                let _ = serve_with_listener(listener, SimulationService::default()).await;
+            });
+
+            let channel = tonic::transport::Endpoint::from_shared(format!("http://{}", addr))
+                .expect("endpoint")
+                .connect()
+                .await
+                .expect("connect");
+            let mut client = tonic::client::Grpc::new(channel);
+
+            client.ready().await.expect("client ready");
+            let created: tonic::Response<CreateScenarioResponse> = client
+                .unary(
+                    tonic::Request::new(CreateScenarioRequest {
+                        radius: 50.0,
+                        track_width: 10.0,
+                        ax: 2.0,
+                        yaw_rate: 0.4,
+                    }),
+                    PathAndQuery::from_static("/lap_simulation.SimulationService/CreateScenario"),
+                    ProstCodec::default(),
+                )
+                .await
+                .expect("create scenario");
+            let scenario_id = created.into_inner().scenario_id;
+
+            client.ready().await.expect("client ready");
+            let _: tonic::Response<super::RunResponse> = client
+                .unary(
+                    tonic::Request::new(RunRequest {
+                        scenario_id,
+                        dt: 0.1,
+                        duration: 1.0,
+                    }),
+                    PathAndQuery::from_static("/lap_simulation.SimulationService/Run"),
+                    ProstCodec::default(),
+                )
+                .await
+                .expect("run");
+
+            client.ready().await.expect("client ready");
+            let results: tonic::Response<FetchResultsResponse> = client
+                .unary(
+                    tonic::Request::new(FetchResultsRequest { scenario_id }),
+                    PathAndQuery::from_static("/lap_simulation.SimulationService/FetchResults"),
+                    ProstCodec::default(),
+                )
+                .await
+                .expect("fetch results");
+            assert_eq!(results.into_inner().snapshots.len(), 11);
+
+            client.ready().await.expect("client ready");
+            let unknown: Result<tonic::Response<FetchResultsResponse>, _> = client
+                .unary(
+                    tonic::Request::new(FetchResultsRequest { scenario_id }),
+                    PathAndQuery::from_static("/lap_simulation.SimulationService/Missing"),
+                    ProstCodec::default(),
+                )
+                .await;
+            assert_eq!(unknown.expect_err("expected error").code(), Code::Unimplemented);
+        });
+        assert_eq!(SERVICE_NAME, "lap_simulation.SimulationService");
+    }
+}
@@ -0,0 +1,338 @@
+//! Prometheus metrics for long-running servers and batch jobs.
+//!
+//! `RunMetrics` collects counters and histograms about finished runs and renders them
+//! in the Prometheus text exposition format. The REST server serves them on
+//! `GET /metrics`; other batch loops can expose them with `MetricsExporter`.
+
+use crate::diagnostics;
+use crate::error::Result;
+use crate::simulation::result::SimulationResult;
+use std::fmt::Write as _;
+use std::io::{self, BufRead, BufReader, Write};
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+
+/// Content type of the Prometheus text exposition format
+pub const CONTENT_TYPE: &str = "text/plain; version=0.0.4";
+
+/// Upper bounds of the run duration buckets in seconds
+pub const RUN_DURATION_BUCKETS: [f64; 10] = [0.01, 0.05, 0.1, 0.25, 0.5, 1.0, 2.5, 5.0, 10.0, 30.0];
+
+/// Upper bounds of the off-track ratio buckets
+pub const OFF_TRACK_BUCKETS: [f64; 6] = [0.0, 0.01, 0.05, 0.1, 0.25, 0.5];
+
+/// Cumulative histogram with fixed bucket bounds
+#[derive(Debug, Clone, PartialEq)]
+pub struct Histogram {
+    bounds: Vec<f64>,
+    counts: Vec<u64>,
+    sum: f64,
+    count: u64,
+}
+
+impl Histogram {
+    /// Create a histogram from ascending bucket upper bounds (`+Inf` is implicit)
+    pub fn new(bounds: &[f64]) -> Self {
+        Self {
+            bounds: bounds.to_vec(),
+            counts: vec![0; bounds.len()],
+            sum: 0.0,
+            count: 0,
+        }
+    }
+
+    pub fn observe(&mut self, value: f64) {
+        for (bound, count) in self.bounds.iter().zip(self.counts.iter_mut()) {
+            if value <= *bound {
+                *count += 1;
+            }
+        }
+        self.sum += value;
+        self.count += 1;
+    }
+
+    pub fn count(&self) -> u64 {
+        self.count
+    }
+
+    pub fn sum(&self) -> f64 {
+        self.sum
+    }
+
+    fn render(&self, out: &mut String, name: &str, help: &str) {
+        let _ = writeln!(out, "# HELP {} {}", name, help);
+        let _ = writeln!(out, "# TYPE {} histogram", name);
+        for (bound, count) in self.bounds.iter().zip(&self.counts) {
+            let _ = writeln!(out, "{}_bucket{{le=\"{}\"}} {}", name, bound, count);
+        }
+        let _ = writeln!(out, "{}_bucket{{le=\"+Inf\"}} {}", name, self.count);
+        let _ = writeln!(out, "{}_sum {}", name, self.sum);
+        let _ = writeln!(out, "{}_count {}", name, self.count);
+    }
+}
+
+/// Counters and histograms describing the runs of a server or batch job
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunMetrics {
+    runs_completed: u64,
+    runs_failed: u64,
+    snapshots: u64,
+    off_track_snapshots: u64,
+    run_duration: Histogram,
+    off_track_ratio: Histogram,
+}
+
+impl Default for RunMetrics {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl RunMetrics {
+    pub fn new() -> Self {
+        Self {
+            runs_completed: 0,
+            runs_failed: 0,
+            snapshots: 0,
+            off_track_snapshots: 0,
+            run_duration: Histogram::new(&RUN_DURATION_BUCKETS),
+            off_track_ratio: Histogram::new(&OFF_TRACK_BUCKETS),
+        }
+    }
+
+    /// Record a finished run
+    ///
+    /// # Arguments
+    /// * `elapsed` - Wall-clock time the run took
+    /// * `result` - Recorded snapshots, used for the off-track rate
+    pub fn record_run<S>(&mut self, elapsed: Duration, result: &SimulationResult<S>) {
+        let total = result.len() as u64;
+        let off_track = result
+            .snapshots()
+            .iter()
+            .filter(|snapshot| !snapshot.in_track)
+            .count() as u64;
+
+        self.runs_completed += 1;
+        self.snapshots += total;
+        self.off_track_snapshots += off_track;
+        self.run_duration.observe(elapsed.as_secs_f64());
+        if total > 0 {
+            self.off_track_ratio.observe(off_track as f64 / total as f64);
+        }
+    }
+
+    /// Record a run that ended with an error
+    pub fn record_failure(&mut self) {
+        self.runs_failed += 1;
+    }
+
+    pub fn runs_completed(&self) -> u64 {
+        self.runs_completed
+    }
+
+    pub fn runs_failed(&self) -> u64 {
+        self.runs_failed
+    }
+
+    /// Fraction of all recorded snapshots that were off track
+    pub fn off_track_rate(&self) -> f64 {
+        if self.snapshots == 0 {
+            0.0
+        } else {
+            self.off_track_snapshots as f64 / self.snapshots as f64
+        }
+    }
+
+    /// Render the metrics in the Prometheus text exposition format
+    pub fn render(&self) -> String {
+        let mut out = String::new();
+        let counters = [
+            (
+                "lap_simulation_runs_completed_total",
+                "Runs that finished successfully.",
+                self.runs_completed,
+            ),
+            (
+                "lap_simulation_runs_failed_total",
+                "Runs that ended with an error.",
+                self.runs_failed,
+            ),
+            (
+                "lap_simulation_snapshots_total",
+                "Snapshots recorded by completed runs.",
+                self.snapshots,
+            ),
+            (
+                "lap_simulation_off_track_snapshots_total",
+                "Snapshots recorded outside the track boundaries.",
+                self.off_track_snapshots,
+            ),
+        ];
+        for (name, help, value) in counters {
+            let _ = writeln!(out, "# HELP {} {}", name, help);
+            let _ = writeln!(out, "# TYPE {} counter", name);
+            let _ = writeln!(out, "{} {}", name, value);
+        }
+        self.run_duration.render(
+            &mut out,
+            "lap_simulation_run_duration_seconds",
+            "Wall-clock duration of completed runs.",
+        );
+        self.off_track_ratio.render(
+            &mut out,
+            "lap_simulation_off_track_ratio",
+            "Fraction of snapshots off track per completed run.",
+        );
+        out
+    }
+}
+
+/// Minimal HTTP endpoint serving `RunMetrics` on `GET /metrics`
+///
+/// Meant for batch jobs that have no other server to attach the metrics to. The
+/// endpoint stops serving when the exporter is dropped.
+pub struct MetricsExporter {
+    local_addr: SocketAddr,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl MetricsExporter {
+    /// Serve `metrics` on `addr` from a background thread
+    pub fn bind<A: ToSocketAddrs>(addr: A, metrics: Arc<Mutex<RunMetrics>>) -> Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let stop = Arc::clone(&shutdown);
+        thread::spawn(move || {
+            for stream in listener.incoming().flatten() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                if let Err(err) = respond(stream, &metrics) {
+                    diagnostics::warn(format_args!("Failed to serve metrics: {err}"));
+                }
+            }
+        });
+        Ok(Self {
+            local_addr,
+            shutdown,
+        })
+    }
+
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+}
+
+impl Drop for MetricsExporter {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the accept loop so the background thread can exit and close the listener
+        let _ = TcpStream::connect(self.local_addr);
+    }
+}
+
+fn respond(mut stream: TcpStream, metrics: &Mutex<RunMetrics>) -> io::Result<()> {
+    let mut request_line = String::new();
+    BufReader::new(&stream).read_line(&mut request_line)?;
+    let path = request_line.split_whitespace().nth(1).unwrap_or_default();
+
+    let (status, content_type, body) = if path == "/metrics" {
+        let body = metrics.lock().map_err(|_| io::Error::other("metrics poisoned"))?.render();
+        ("200 OK", CONTENT_TYPE, body)
+    } else {
+        ("404 Not Found", "text/plain", "not found\n".to_string())
+    };
+    write!(
+        stream,
+        "HTTP/1.1 {}\r\nContent-Type: {}\r\nContent-Length: {}\r\nConnection: close\r\n\r\n{}",
+        status,
+        content_type,
+        body.len(),
+        body
+    )?;
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Histogram, MetricsExporter, RunMetrics};
+    use crate::simulation::result::SimulationResult;
+    use std::io::{Read, Write};
+    use std::net::TcpStream;
+    use std::sync::{Arc, Mutex};
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_histogram_buckets_are_cumulative() {
+        let mut histogram = Histogram::new(&[1.0, 2.0]);
+        histogram.observe(0.5);
+        histogram.observe(1.5);
+        histogram.observe(3.0);
+
+        let mut out = String::new();
+        histogram.render(&mut out, "h", "Help.");
+        assert!(out.contains("h_bucket{le=\"1\"} 1\n"));
+        assert!(out.contains("h_bucket{le=\"2\"} 2\n"));
+        assert!(out.contains("h_bucket{le=\"+Inf\"} 3\n"));
+        assert!(out.contains("h_sum 5\n"));
+        assert_eq!(histogram.count(), 3);
+    }
+
+    #[test]
+    fn test_run_metrics_off_track_rate() {
+        let mut result = SimulationResult::new();
+        result.push(0.0, (), true);
+        result.push(0.1, (), true);
+        result.push(0.2, (), false);
+        result.push(0.3, (), false);
+
+        let mut metrics = RunMetrics::new();
+        metrics.record_run(Duration::from_millis(20), &result);
+        metrics.record_failure();
+
+        assert_eq!(metrics.runs_completed(), 1);
+        assert_eq!(metrics.runs_failed(), 1);
+        assert_eq!(metrics.off_track_rate(), 0.5);
+        let text = metrics.render();
+        assert!(text.contains("lap_simulation_runs_completed_total 1\n"));
+        assert!(text.contains("lap_simulation_off_track_ratio_bucket{le=\"0.5\"} 1\n"));
+        assert!(text.contains("lap_simulation_run_duration_seconds_bucket{le=\"0.05\"} 1\n"));
+    }
+
+    #[test]
+    fn test_metrics_exporter_serves_metrics() {
+        let metrics = Arc::new(Mutex::new(RunMetrics::new()));
+        metrics.lock().expect("metrics").record_failure();
+        let exporter = MetricsExporter::bind("127.0.0.1:0", Arc::clone(&metrics)).expect("bind");
+
+        let mut stream = TcpStream::connect(exporter.local_addr()).expect("connect");
+        write!(stream, "GET /metrics HTTP/1.1\r\nHost: localhost\r\n\r\n").expect("write");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read");
+
+        assert!(response.starts_with("HTTP/1.1 200 OK"));
+        assert!(response.contains("lap_simulation_runs_failed_total 1\n"));
+    }
+
+    #[test]
+    fn test_metrics_exporter_stops_when_dropped() {
+        let exporter =
+            MetricsExporter::bind("127.0.0.1:0", Arc::new(Mutex::new(RunMetrics::new())))
+                .expect("bind");
+        let addr = exporter.local_addr();
+        drop(exporter);
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while TcpStream::connect(addr).is_ok() && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert!(TcpStream::connect(addr).is_err(), "listener still open");
+    }
+}
@@ -0,0 +1,14 @@
+//! Remote control of simulations.
+//!
+//! `ScenarioRegistry` keeps named simulations alive between requests; the transport
+//! specific servers (gRPC, REST) behind their features only translate requests into
+//! registry calls. `metrics` tracks completed runs for monitoring long batch jobs.
+
+pub mod metrics;
+pub mod registry;
+
+#[cfg(feature = "grpc")]
+pub mod grpc;
+
+#[cfg(feature = "rest")]
+pub mod rest;
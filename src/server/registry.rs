@@ -0,0 +1,232 @@
+use crate::config::ScenarioConfig;
+use crate::error::{Error, Result};
+use crate::models::point_mass::PointMassState;
+use crate::simulation::base_simulation::Simulation;
+use crate::simulation::batch::validate_run;
+use crate::simulation::open_loop::OpenLoopSimulation;
+use crate::simulation::result::{SimulationResult, StateSnapshot};
+use std::collections::BTreeMap;
+use std::sync::Arc;
+
+/// Simulation created from a scenario config, advanced on request
+pub struct Scenario {
+    config: ScenarioConfig,
+    simulation: OpenLoopSimulation,
+    time: f64,
+    result: SimulationResult<PointMassState>,
+}
+
+impl Scenario {
+    /// Create a scenario at the start position of its track
+    pub fn new(config: ScenarioConfig) -> Result<Self> {
+        config.track.validate()?;
+        config.model.validate()?;
+
+        let mut builder = OpenLoopSimulation::builder()
+            .controls(config.controls.0, config.controls.1)
+            .track(Arc::from(config.track.build()))
+            .model(config.model.build())
+            .output_dir(&config.output_dir);
+        if let Some(schedule) = &config.schedule {
+            builder = builder.schedule(schedule.clone());
+        }
+        let simulation = builder.build()?;
+        let mut scenario = Self {
+            config,
+            simulation,
+            time: 0.0,
+            result: SimulationResult::new(),
+        };
+        scenario.record(0.0, 0.0)?;
+        Ok(scenario)
+    }
+
+    pub fn config(&self) -> &ScenarioConfig {
+        &self.config
+    }
+
+    /// Underlying simulation (track and model in their current state)
+    pub fn simulation(&self) -> &OpenLoopSimulation {
+        &self.simulation
+    }
+
+    /// Mutable access to the simulation, e.g. to attach a telemetry sink
+    pub fn simulation_mut(&mut self) -> &mut OpenLoopSimulation {
+        &mut self.simulation
+    }
+
+    /// Simulation time reached so far in seconds
+    pub fn time(&self) -> f64 {
+        self.time
+    }
+
+    /// All snapshots recorded since the scenario was created or last run
+    pub fn result(&self) -> &SimulationResult<PointMassState> {
+        &self.result
+    }
+
+    /// Take the recorded snapshots, dropping the simulation
+    pub fn into_result(self) -> SimulationResult<PointMassState> {
+        self.result
+    }
+
+    /// Advance the scenario by `steps` steps of `dt` seconds
+    ///
+    /// # Returns
+    /// The latest snapshot, or `Error::InvalidConfig` if `dt` is not positive
+    pub fn step(&mut self, dt: f64, steps: u32) -> Result<&StateSnapshot<PointMassState>> {
+        let duration = dt * f64::from(steps);
+        validate_run(dt, duration)?;
+        self.record(dt, duration)?;
+        self.result
+            .last()
+            .ok_or_else(|| Error::NotInitialized("scenario result".to_string()))
+    }
+
+    /// Run the scenario from the start for `duration` seconds, replacing earlier results
+    ///
+    /// # Returns
+    /// Number of recorded snapshots, or `Error::InvalidConfig` if `dt` is not positive or
+    /// `duration` is negative
+    pub fn run(&mut self, dt: f64, duration: f64) -> Result<usize> {
+        validate_run(dt, duration)?;
+        self.simulation.reset();
+        self.time = 0.0;
+        self.result = SimulationResult::new();
+        self.record(dt, duration)?;
+        Ok(self.result.len())
+    }
+
+    /// Run from the current time and append the new snapshots
+    fn record(&mut self, dt: f64, duration: f64) -> Result<()> {
+        let segment = self.simulation.run_from(self.time, dt, duration)?;
+        let skip = usize::from(!self.result.is_empty());
+        for snapshot in segment.snapshots().iter().skip(skip) {
+            self.result
+                .push(snapshot.time, snapshot.state.clone(), snapshot.in_track);
+        }
+        if let Some(profile) = segment.profile() {
+            let mut total = self.result.profile().copied().unwrap_or_default();
+            total.add(profile);
+            self.result.set_profile(Some(total));
+        }
+        self.time = self.result.duration();
+        Ok(())
+    }
+}
+
+/// Scenarios addressed by numeric id
+#[derive(Default)]
+pub struct ScenarioRegistry {
+    next_id: u64,
+    scenarios: BTreeMap<u64, Scenario>,
+}
+
+impl ScenarioRegistry {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Create a scenario and return its id
+    pub fn create(&mut self, config: ScenarioConfig) -> Result<u64> {
+        let scenario = Scenario::new(config)?;
+        self.next_id += 1;
+        self.scenarios.insert(self.next_id, scenario);
+        Ok(self.next_id)
+    }
+
+    /// Scenario with the given id, or `Error::NotFound`
+    pub fn get(&self, id: u64) -> Result<&Scenario> {
+        self.scenarios
+            .get(&id)
+            .ok_or_else(|| Error::NotFound(format!("scenario {}", id)))
+    }
+
+    /// Scenario with the given id, or `Error::NotFound`
+    pub fn get_mut(&mut self, id: u64) -> Result<&mut Scenario> {
+        self.scenarios
+            .get_mut(&id)
+            .ok_or_else(|| Error::NotFound(format!("scenario {}", id)))
+    }
+
+    /// Remove a scenario, returning whether it existed
+    pub fn remove(&mut self, id: u64) -> bool {
+        self.scenarios.remove(&id).is_some()
+    }
+
+    pub fn len(&self) -> usize {
+        self.scenarios.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.scenarios.is_empty()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ScenarioRegistry;
+    use crate::config::{ScenarioConfig, TrackConfig};
+
+    #[test]
+    fn test_scenario_steps_match_single_run() {
+        let mut registry = ScenarioRegistry::new();
+        let stepped = registry.create(ScenarioConfig::default()).expect("create");
+        let single = registry.create(ScenarioConfig::default()).expect("create");
+
+        for _ in 0..5 {
+            registry.get_mut(stepped).expect("scenario").step(0.1, 2).expect("step");
+        }
+        let count = registry.get_mut(single).expect("scenario").run(0.1, 1.0).expect("run");
+
+        let stepped = registry.get(stepped).expect("scenario").result();
+        let single = registry.get(single).expect("scenario").result();
+        assert_eq!(count, 11);
+        assert_eq!(stepped.len(), single.len());
+        let (a, b) = (stepped.last().expect("last"), single.last().expect("last"));
+        assert!((a.time - b.time).abs() < 1e-9);
+        assert!((a.state.x - b.state.x).abs() < 1e-9);
+        assert!((a.state.y - b.state.y).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_registry_rejects_unknown_and_invalid() {
+        let mut registry = ScenarioRegistry::new();
+        let err = registry.get(1).err().expect("unknown scenario");
+        assert_eq!(err.to_string(), "unknown scenario 1");
+        assert!(matches!(err, crate::Error::NotFound(_)));
+
+        let invalid = ScenarioConfig {
+            track: TrackConfig::Circle {
+                radius: -1.0,
+                track_width: 10.0,
+                num_points: 100,
+            },
+            ..ScenarioConfig::default()
+        };
+        let err = registry.create(invalid).expect_err("expected error");
+        assert!(err.to_string().contains("positive radius"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(registry.is_empty());
+    }
+
+    #[test]
+    fn test_registry_runs_square_tracks() {
+        let mut registry = ScenarioRegistry::new();
+        let square = ScenarioConfig {
+            track: TrackConfig::Square {
+                height: 100.0,
+                track_width: 10.0,
+                points_per_side: 25,
+            },
+            ..ScenarioConfig::default()
+        };
+        let id = registry.create(square).expect("create square scenario");
+        let scenario = registry.get_mut(id).expect("scenario");
+        scenario.run(0.1, 1.0).expect("run");
+
+        let track = scenario.simulation().track().expect("track");
+        assert_eq!(track.get_track_name(), "Square Track");
+        assert!(scenario.result().snapshots().iter().all(|snapshot| snapshot.in_track));
+    }
+}
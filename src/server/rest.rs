@@ -0,0 +1,407 @@
+//! HTTP/JSON server for submitting scenarios and downloading their results.
+//!
+//! | Method | Path                      | Description                                  |
+//! |--------|---------------------------|----------------------------------------------|
+//! | POST   | `/scenarios`              | Submit a `ScenarioConfig` as JSON, returns its id |
+//! | GET    | `/scenarios`              | Status of every submitted scenario           |
+//! | GET    | `/scenarios/{id}`         | Status of one scenario                       |
+//! | GET    | `/scenarios/{id}/result`  | Recorded `SimulationResult` as JSON          |
+//! | GET    | `/scenarios/{id}/plot.svg`| Final state with the driven path             |
+//! | GET    | `/metrics`                | Run counters and histograms (Prometheus)     |
+//!
+//! Each submitted scenario runs on its own thread; clients poll the status until it
+//! is `done` (or `failed`).
+
+use super::metrics::{self, RunMetrics};
+use super::registry::Scenario;
+use crate::diagnostics;
+use crate::config::ScenarioConfig;
+use crate::error::{Error, Result};
+use crate::models::base_model::Model;
+use crate::models::point_mass::PointMassState;
+use crate::plotting::create::{plot_pose_with_trail, DEFAULT_CANVAS_SIZE};
+use crate::simulation::result::SimulationResult;
+use serde::Serialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::io;
+use std::net::{SocketAddr, ToSocketAddrs};
+use std::path::{Path, PathBuf};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Instant;
+use tiny_http::{Header, Method, Request, Response, Server};
+
+/// Progress of a submitted scenario
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum JobStatus {
+    Running,
+    Done,
+    Failed,
+}
+
+struct Job {
+    status: JobStatus,
+    error: Option<String>,
+    result: Option<SimulationResult<PointMassState>>,
+    plot_path: Option<PathBuf>,
+}
+
+#[derive(Serialize)]
+struct JobSummary<'a> {
+    id: u64,
+    status: JobStatus,
+    error: Option<&'a str>,
+    snapshots: usize,
+}
+
+type Jobs = Arc<Mutex<BTreeMap<u64, Job>>>;
+
+/// REST server running submitted scenarios in the background
+pub struct RestServer {
+    server: Server,
+    output_dir: PathBuf,
+    jobs: Jobs,
+    next_id: Mutex<u64>,
+    metrics: Arc<Mutex<RunMetrics>>,
+}
+
+impl RestServer {
+    /// Bind the server to `addr` (e.g., "127.0.0.1:8080")
+    ///
+    /// # Arguments
+    /// * `addr` - Address to listen on
+    /// * `output_dir` - Directory where plots of each scenario are written
+    pub fn bind<A: ToSocketAddrs, P: AsRef<Path>>(
+        addr: A,
+        output_dir: P,
+    ) -> Result<Self> {
+        let server = Server::http(addr)
+            .map_err(|err| io::Error::other(format!("failed to bind REST server: {}", err)))?;
+        Ok(Self {
+            server,
+            output_dir: output_dir.as_ref().to_path_buf(),
+            jobs: Arc::new(Mutex::new(BTreeMap::new())),
+            next_id: Mutex::new(0),
+            metrics: Arc::new(Mutex::new(RunMetrics::new())),
+        })
+    }
+
+    /// Metrics of the scenarios run by this server
+    pub fn metrics(&self) -> Arc<Mutex<RunMetrics>> {
+        Arc::clone(&self.metrics)
+    }
+
+    /// Address the server listens on
+    pub fn local_addr(&self) -> Option<SocketAddr> {
+        self.server.server_addr().to_ip()
+    }
+
+    /// Handle requests until `shutdown` is called
+    pub fn serve(&self) {
+        for request in self.server.incoming_requests() {
+            self.handle(request);
+        }
+    }
+
+    /// Stop a running `serve` loop
+    pub fn shutdown(&self) {
+        self.server.unblock();
+    }
+
+    fn handle(&self, mut request: Request) {
+        let path = request
+            .url()
+            .split('?')
+            .next()
+            .unwrap_or_default()
+            .to_string();
+        let segments: Vec<&str> = path.split('/').filter(|part| !part.is_empty()).collect();
+
+        let response = match (request.method(), segments.as_slice()) {
+            (Method::Post, ["scenarios"]) => {
+                let mut body = String::new();
+                match request.as_reader().read_to_string(&mut body) {
+                    Ok(_) => self.submit(&body),
+                    Err(err) => error_response(400, &format!("failed to read body: {}", err)),
+                }
+            }
+            (Method::Get, ["scenarios"]) => self.list(),
+            (Method::Get, ["scenarios", id]) => {
+                self.with_job(id, |id, job| json_response(200, &summary(id, job)))
+            }
+            (Method::Get, ["scenarios", id, "result"]) => {
+                self.with_job(id, |_, job| match &job.result {
+                    Some(result) => json_response(200, result),
+                    None => error_response(409, "scenario has not finished"),
+                })
+            }
+            (Method::Get, ["scenarios", id, "plot.svg"]) => {
+                self.with_job(id, |_, job| match job.plot_path.as_ref().map(fs::read) {
+                    Some(Ok(svg)) => response(200, "image/svg+xml", svg),
+                    Some(Err(err)) => error_response(500, &format!("failed to read plot: {}", err)),
+                    None => error_response(409, "scenario has not finished"),
+                })
+            }
+            (Method::Get, ["metrics"]) => match self.metrics.lock() {
+                Ok(run_metrics) => response(
+                    200,
+                    metrics::CONTENT_TYPE,
+                    run_metrics.render().into_bytes(),
+                ),
+                Err(_) => error_response(500, "metrics poisoned"),
+            },
+            _ => error_response(404, "not found"),
+        };
+
+        if let Err(err) = request.respond(response) {
+            diagnostics::warn(format_args!("Failed to send REST response: {err}"));
+        }
+    }
+
+    fn submit(&self, body: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+        let config: ScenarioConfig = match serde_json::from_str(body) {
+            Ok(config) => config,
+            Err(err) => return error_response(400, &format!("invalid scenario: {}", err)),
+        };
+        let scenario = match Scenario::new(config) {
+            Ok(scenario) => scenario,
+            Err(err) => return error_response(400, &err.to_string()),
+        };
+
+        let id = {
+            let Ok(mut next_id) = self.next_id.lock() else {
+                return error_response(500, "scenario counter poisoned");
+            };
+            *next_id += 1;
+            *next_id
+        };
+        let job = Job {
+            status: JobStatus::Running,
+            error: None,
+            result: None,
+            plot_path: None,
+        };
+        let body = summary(id, &job);
+        let response = json_response(202, &body)
+            .with_header(header("Location", &format!("/scenarios/{}", id)));
+        match self.jobs.lock() {
+            Ok(mut jobs) => {
+                jobs.insert(id, job);
+            }
+            Err(_) => return error_response(500, "job list poisoned"),
+        }
+
+        let jobs = Arc::clone(&self.jobs);
+        let run_metrics = Arc::clone(&self.metrics);
+        let plot_path = self
+            .output_dir
+            .join(format!("scenario_{}", id))
+            .join("final.svg");
+        thread::spawn(move || {
+            let started = Instant::now();
+            let outcome = run_scenario(scenario, &plot_path);
+            if let Ok(mut run_metrics) = run_metrics.lock() {
+                match &outcome {
+                    Ok(result) => run_metrics.record_run(started.elapsed(), result),
+                    Err(_) => run_metrics.record_failure(),
+                }
+            }
+            if let Ok(mut jobs) = jobs.lock()
+                && let Some(job) = jobs.get_mut(&id)
+            {
+                match outcome {
+                    Ok(result) => {
+                        job.status = JobStatus::Done;
+                        job.result = Some(result);
+                        job.plot_path = Some(plot_path);
+                    }
+                    Err(err) => {
+                        job.status = JobStatus::Failed;
+                        job.error = Some(err.to_string());
+                    }
+                }
+            }
+        });
+
+        response
+    }
+
+    fn list(&self) -> Response<std::io::Cursor<Vec<u8>>> {
+        match self.jobs.lock() {
+            Ok(jobs) => {
+                let summaries: Vec<JobSummary> =
+                    jobs.iter().map(|(&id, job)| summary(id, job)).collect();
+                json_response(200, &summaries)
+            }
+            Err(_) => error_response(500, "job list poisoned"),
+        }
+    }
+
+    fn with_job<F>(&self, id: &str, f: F) -> Response<std::io::Cursor<Vec<u8>>>
+    where
+        F: FnOnce(u64, &Job) -> Response<std::io::Cursor<Vec<u8>>>,
+    {
+        let Ok(id) = id.parse::<u64>() else {
+            return error_response(404, "not found");
+        };
+        match self.jobs.lock() {
+            Ok(jobs) => match jobs.get(&id) {
+                Some(job) => f(id, job),
+                None => error_response(404, &format!("unknown scenario {}", id)),
+            },
+            Err(_) => error_response(500, "job list poisoned"),
+        }
+    }
+}
+
+/// Run a scenario to completion and plot its final state
+fn run_scenario(
+    mut scenario: Scenario,
+    plot_path: &Path,
+) -> Result<SimulationResult<PointMassState>> {
+    let (dt, duration) = (scenario.config().dt, scenario.config().duration);
+    scenario.run(dt, duration)?;
+
+    let result = scenario.result().clone();
+    let simulation = scenario.simulation();
+    let (Some(track), Some(model), Some(last)) =
+        (simulation.track(), simulation.model(), result.last())
+    else {
+        return Err(Error::NotInitialized("scenario simulation".to_string()));
+    };
+    if let Some(parent) = plot_path.parent() {
+        fs::create_dir_all(parent)?;
+    }
+    let trail: Vec<(f64, f64)> = result.states().map(|state| (state.x, state.y)).collect();
+    let in_track: Vec<bool> = result.snapshots().iter().map(|s| s.in_track).collect();
+    plot_pose_with_trail(
+        track,
+        &last.state,
+        model.get_size(),
+        plot_path
+            .to_str()
+            .ok_or_else(|| Error::invalid_input("plot path is not valid UTF-8"))?,
+        DEFAULT_CANVAS_SIZE,
+        &trail,
+        &in_track,
+    )?;
+    Ok(result)
+}
+
+fn summary(id: u64, job: &Job) -> JobSummary<'_> {
+    JobSummary {
+        id,
+        status: job.status,
+        error: job.error.as_deref(),
+        snapshots: job.result.as_ref().map_or(0, SimulationResult::len),
+    }
+}
+
+fn header(name: &str, value: &str) -> Header {
+    Header::from_bytes(name.as_bytes(), value.as_bytes()).expect("valid header")
+}
+
+fn response(status: u16, content_type: &str, body: Vec<u8>) -> Response<std::io::Cursor<Vec<u8>>> {
+    Response::from_data(body)
+        .with_status_code(status)
+        .with_header(header("Content-Type", content_type))
+}
+
+fn json_response<T: Serialize + ?Sized>(
+    status: u16,
+    value: &T,
+) -> Response<std::io::Cursor<Vec<u8>>> {
+    match serde_json::to_vec(value) {
+        Ok(body) => response(status, "application/json", body),
+        Err(err) => error_response(500, &format!("failed to encode response: {}", err)),
+    }
+}
+
+fn error_response(status: u16, message: &str) -> Response<std::io::Cursor<Vec<u8>>> {
+    let body = serde_json::json!({ "error": message })
+        .to_string()
+        .into_bytes();
+    response(status, "application/json", body)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RestServer;
+    use std::io::{Read, Write};
+    use std::net::{SocketAddr, TcpStream};
+    use std::sync::Arc;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    /// Send a request and return (status code, body)
+    fn request(addr: SocketAddr, method: &str, path: &str, body: &str) -> (u16, String) {
+        let mut stream = TcpStream::connect(addr).expect("connect");
+        write!(
+            stream,
+            "{} {} HTTP/1.1\r\nHost: localhost\r\nConnection: close\r\nContent-Length: {}\r\n\r\n{}",
+            method,
+            path,
+            body.len(),
+            body
+        )
+        .expect("write request");
+        let mut response = String::new();
+        stream.read_to_string(&mut response).expect("read response");
+        let status = response[9..12].parse().expect("status code");
+        let body = response
+            .split_once("\r\n\r\n")
+            .map(|(_, body)| body.to_string())
+            .unwrap_or_default();
+        (status, body)
+    }
+
+    #[test]
+    fn test_rest_submit_poll_and_download() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let server = Arc::new(RestServer::bind("127.0.0.1:0", temp_dir.path()).expect("bind"));
+        let addr = server.local_addr().expect("server addr");
+        let serving = Arc::clone(&server);
+        let handle = thread::spawn(move || serving.serve());
+
+        let (status, body) = request(addr, "POST", "/scenarios", r#"{"duration": 1.0}"#);
+        assert_eq!(status, 202, "{}", body);
+        let submitted: serde_json::Value = serde_json::from_str(&body).expect("json");
+        let id = submitted["id"].as_u64().expect("id");
+
+        let deadline = Instant::now() + Duration::from_secs(10);
+        loop {
+            let (_, body) = request(addr, "GET", &format!("/scenarios/{}", id), "");
+            let job: serde_json::Value = serde_json::from_str(&body).expect("json");
+            if job["status"] == "done" {
+                assert_eq!(job["snapshots"], 11);
+                break;
+            }
+            assert_ne!(job["status"], "failed", "{}", body);
+            assert!(Instant::now() < deadline, "scenario did not finish");
+            thread::sleep(Duration::from_millis(20));
+        }
+
+        let (status, body) = request(addr, "GET", &format!("/scenarios/{}/result", id), "");
+        assert_eq!(status, 200);
+        let result: serde_json::Value = serde_json::from_str(&body).expect("json");
+        assert_eq!(result["snapshots"].as_array().expect("snapshots").len(), 11);
+
+        let (status, body) = request(addr, "GET", &format!("/scenarios/{}/plot.svg", id), "");
+        assert_eq!(status, 200);
+        assert!(body.contains("<svg"));
+
+        let (status, body) = request(addr, "GET", "/metrics", "");
+        assert_eq!(status, 200);
+        assert!(body.contains("lap_simulation_runs_completed_total 1\n"));
+
+        let (status, _) = request(addr, "POST", "/scenarios", "{ not json");
+        assert_eq!(status, 400);
+        let (status, _) = request(addr, "GET", "/scenarios/999", "");
+        assert_eq!(status, 404);
+
+        server.shutdown();
+        handle.join().expect("server thread");
+    }
+}
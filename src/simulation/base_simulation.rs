@@ -1,20 +1,51 @@
+use crate::error::Result;
 use crate::models::base_model::Model;
+use crate::simulation::result::SimulationResult;
 use crate::tracks::base_track::Track;
+use std::sync::Arc;
 
 /// Trait for simulations with a standard lifecycle.
 pub trait Simulation {
-    type Track: Track;
+    type Track: Track + ?Sized;
     type Model: Model;
 
     /// Initialize the simulation with a track and a model.
-    fn init(&mut self, track: Self::Track, model: Self::Model);
+    ///
+    /// The track is shared, so the same instance can also be handed to controllers and
+    /// plotting (e.g., `Arc::clone(&track)`). Fails with `Error::NotInitialized` if the
+    /// track has no geometry yet.
+    fn init(&mut self, track: Arc<Self::Track>, model: Self::Model) -> Result<()>;
 
-    /// Run the simulation and return the model states over the trajectory.
-    fn run(&mut self, dt: f64, duration: f64) -> Vec<<Self::Model as Model>::State>;
+    /// Run the simulation and return the recorded model states over the trajectory.
+    ///
+    /// Fails with `Error::NotInitialized` before `init` and with `Error::Diverged` when
+    /// the model state becomes NaN or infinite.
+    fn run(
+        &mut self,
+        dt: f64,
+        duration: f64,
+    ) -> Result<SimulationResult<<Self::Model as Model>::State>>;
+
+    /// Run the simulation and record the states into `result`, replacing its snapshots.
+    ///
+    /// Simulations can override this to reuse the storage of `result` between runs; the
+    /// default records into a new result.
+    fn run_into(
+        &mut self,
+        dt: f64,
+        duration: f64,
+        result: &mut SimulationResult<<Self::Model as Model>::State>,
+    ) -> Result<()> {
+        *result = self.run(dt, duration)?;
+        Ok(())
+    }
 
     /// Reset the simulation to its initial state.
     fn reset(&mut self);
 
     /// Clean up resources owned by the simulation.
+    ///
+    /// Simulations managing an output directory also delete the intermediate artifacts
+    /// registered in it (e.g., frames) and keep the final ones.
     fn clean(&mut self);
 }
@@ -0,0 +1,479 @@
+//! Parameter sweeps: one closed-loop run per combination of parameter values.
+//!
+//! A `SweepRunner` holds named parameters with the values to try (e.g., a Stanley gain
+//! from 0.5 to 3). For every combination it asks a closure for the model and
+//! controller to drive, runs them on the track from the start position, and reduces
+//! the run to a few metrics: the fastest lap, the largest distance from the center
+//! line, and how often the vehicle left the track. Runs are spread over worker threads
+//! and the table lists them in combination order, whatever the number of threads.
+
+use crate::controllers::base_controller::Controller;
+use crate::error::Error;
+use crate::models::base_model::Model;
+use crate::models::point_mass::{PointMass, PointMassState};
+use crate::provenance::Provenance;
+use crate::simulation::lap_analysis::start_line_crossings;
+use crate::simulation::multi_vehicle::MultiVehicleSimulation;
+use crate::simulation::result::{write_csv_file, SimulationResult};
+use crate::tracks::arc_length::ArcLengthTable;
+use crate::tracks::base_track::{SharedTrack, Track};
+use std::fmt;
+use std::path::Path;
+use std::thread;
+
+/// Time step in seconds of the sweep runs when none is configured
+pub const DEFAULT_SWEEP_DT: f64 = 0.05;
+
+/// Duration in seconds of the sweep runs when none is configured
+pub const DEFAULT_SWEEP_DURATION: f64 = 60.0;
+
+/// Named parameter and the values a sweep tries
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepParameter {
+    pub name: String,
+    pub values: Vec<f64>,
+}
+
+impl SweepParameter {
+    pub fn new(name: &str, values: Vec<f64>) -> Self {
+        Self {
+            name: name.to_string(),
+            values,
+        }
+    }
+
+    /// `count` evenly spaced values from `start` to `end`, both included
+    ///
+    /// A count of 1 tries `start` only.
+    pub fn range(name: &str, start: f64, end: f64, count: usize) -> Self {
+        let values = match count {
+            0 => Vec::new(),
+            1 => vec![start],
+            _ => (0..count)
+                .map(|i| start + (end - start) * i as f64 / (count - 1) as f64)
+                .collect(),
+        };
+        Self::new(name, values)
+    }
+}
+
+/// Values of every swept parameter for one run, in the order the parameters were added
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepPoint {
+    values: Vec<(String, f64)>,
+}
+
+impl SweepPoint {
+    /// Value of a parameter, if it is swept
+    pub fn get(&self, name: &str) -> Option<f64> {
+        self.values
+            .iter()
+            .find(|(key, _)| key == name)
+            .map(|&(_, value)| value)
+    }
+
+    /// Value of a parameter, or `default` if it is not swept
+    pub fn get_or(&self, name: &str, default: f64) -> f64 {
+        self.get(name).unwrap_or(default)
+    }
+
+    /// (name, value) pairs of the point
+    pub fn values(&self) -> &[(String, f64)] {
+        &self.values
+    }
+}
+
+impl fmt::Display for SweepPoint {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for (index, (name, value)) in self.values.iter().enumerate() {
+            if index > 0 {
+                write!(f, ", ")?;
+            }
+            write!(f, "{} = {}", name, value)?;
+        }
+        Ok(())
+    }
+}
+
+/// Summary of one sweep run
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct SweepMetrics {
+    /// Fastest lap in seconds between start line crossings, `None` without a full lap
+    pub lap_time: Option<f64>,
+    /// Largest distance in meters from the center line
+    pub max_cross_track_error: f64,
+    /// Number of times the vehicle left the track
+    pub off_track_count: usize,
+}
+
+impl SweepMetrics {
+    /// Metrics of a run started on the start line of `track`
+    pub fn from_run(track: &dyn Track, result: &SimulationResult<PointMassState>) -> Self {
+        let center_line = ArcLengthTable::from_track(track);
+        let mut crossings = start_line_crossings(track, result);
+        // Starting on the line begins the first lap, unless rounding put the start just
+        // behind the line and the first step already crossed it
+        let times: Vec<f64> = result.times().take(2).collect();
+        if let [start, first_step] = times[..]
+            && crossings.first().is_none_or(|&crossing| crossing > first_step)
+        {
+            crossings.insert(0, start);
+        }
+        let lap_time = crossings
+            .windows(2)
+            .map(|pair| pair[1] - pair[0])
+            .min_by(f64::total_cmp);
+        let max_cross_track_error = result
+            .states()
+            .filter_map(|state| center_line.position(state.x, state.y))
+            .map(|position| position.lateral_error.abs())
+            .fold(0.0, f64::max);
+        let mut was_in_track = true;
+        let mut off_track_count = 0;
+        for snapshot in result.snapshots() {
+            if was_in_track && !snapshot.in_track {
+                off_track_count += 1;
+            }
+            was_in_track = snapshot.in_track;
+        }
+        Self {
+            lap_time,
+            max_cross_track_error,
+            off_track_count,
+        }
+    }
+}
+
+/// Parameters and metrics of one sweep run
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepRow {
+    pub point: SweepPoint,
+    pub metrics: SweepMetrics,
+}
+
+/// Metrics of every run of a sweep, in combination order
+#[derive(Debug, Clone, PartialEq)]
+pub struct SweepTable {
+    parameters: Vec<String>,
+    rows: Vec<SweepRow>,
+}
+
+impl SweepTable {
+    /// Names of the swept parameters
+    pub fn parameters(&self) -> &[String] {
+        &self.parameters
+    }
+
+    pub fn rows(&self) -> &[SweepRow] {
+        &self.rows
+    }
+
+    /// Run with the fastest lap, ignoring runs that left the track
+    pub fn best_lap(&self) -> Option<&SweepRow> {
+        self.rows
+            .iter()
+            .filter(|row| row.metrics.off_track_count == 0)
+            .filter_map(|row| row.metrics.lap_time.map(|lap_time| (row, lap_time)))
+            .min_by(|(_, a), (_, b)| a.total_cmp(b))
+            .map(|(row, _)| row)
+    }
+
+    /// Write the table to a CSV file, one column per parameter followed by
+    /// `lap_time,max_cross_track_error,off_track_count`
+    ///
+    /// The header follows a `# provenance:` comment line with the crate version and
+    /// git commit. The lap time is left empty for runs without a full lap.
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        self.write_csv_with_provenance(path, &Provenance::current())
+    }
+
+    /// Write the table as `write_csv` does, with the parameters and seed of
+    /// `provenance` in the comment line
+    pub fn write_csv_with_provenance<P: AsRef<Path>>(
+        &self,
+        path: P,
+        provenance: &Provenance,
+    ) -> crate::Result<()> {
+        let mut header = String::new();
+        for name in &self.parameters {
+            header.push_str(name);
+            header.push(',');
+        }
+        header.push_str("lap_time,max_cross_track_error,off_track_count");
+        write_csv_file(path.as_ref(), provenance, &header, |writer| {
+            for row in &self.rows {
+                for (_, value) in row.point.values() {
+                    write!(writer, "{},", value)?;
+                }
+                let lap_time =
+                    row.metrics.lap_time.map_or(String::new(), |time| time.to_string());
+                writeln!(
+                    writer,
+                    "{},{},{}",
+                    lap_time, row.metrics.max_cross_track_error, row.metrics.off_track_count
+                )?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl fmt::Display for SweepTable {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        for name in &self.parameters {
+            write!(f, "{:>12} ", name)?;
+        }
+        write!(f, "{:>10} {:>10} {:>9}", "lap (s)", "max xte (m)", "off track")?;
+        for row in &self.rows {
+            writeln!(f)?;
+            for (_, value) in row.point.values() {
+                write!(f, "{:>12.3} ", value)?;
+            }
+            match row.metrics.lap_time {
+                Some(lap_time) => write!(f, "{:>10.3}", lap_time)?,
+                None => write!(f, "{:>10}", "-")?,
+            }
+            write!(
+                f,
+                " {:>10.2} {:>9}",
+                row.metrics.max_cross_track_error, row.metrics.off_track_count
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs one closed-loop simulation per combination of parameter values
+pub struct SweepRunner {
+    track: SharedTrack,
+    parameters: Vec<SweepParameter>,
+    dt: f64,
+    duration: f64,
+    threads: usize,
+}
+
+impl SweepRunner {
+    /// Create a sweep on `track` without parameters, running on all available cores
+    pub fn new(track: SharedTrack) -> Self {
+        Self {
+            track,
+            parameters: Vec::new(),
+            dt: DEFAULT_SWEEP_DT,
+            duration: DEFAULT_SWEEP_DURATION,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+
+    /// Sweep `parameter`; combinations vary the last added parameter fastest
+    pub fn parameter(mut self, parameter: SweepParameter) -> Self {
+        self.parameters.push(parameter);
+        self
+    }
+
+    /// Sweep `count` evenly spaced values of `name` from `start` to `end`
+    pub fn range(self, name: &str, start: f64, end: f64, count: usize) -> Self {
+        self.parameter(SweepParameter::range(name, start, end, count))
+    }
+
+    /// Time step in seconds of every run
+    pub fn dt(mut self, dt: f64) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Duration in seconds of every run
+    pub fn duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Limit the number of worker threads (at least 1; 1 runs on the calling thread)
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Every combination of the parameter values, in run order
+    pub fn points(&self) -> Vec<SweepPoint> {
+        self.parameters
+            .iter()
+            .fold(vec![SweepPoint { values: Vec::new() }], |points, parameter| {
+                points
+                    .iter()
+                    .flat_map(|point| {
+                        parameter.values.iter().map(move |&value| {
+                            let mut point = point.clone();
+                            point.values.push((parameter.name.clone(), value));
+                            point
+                        })
+                    })
+                    .collect()
+            })
+    }
+
+    /// Run every combination and summarize the runs
+    ///
+    /// `build` is called once per combination on the calling thread and returns the
+    /// model and the controller driving it; the model is placed on the start position
+    /// of the track and keeps its speed.
+    ///
+    /// # Returns
+    /// The metrics of every run, the first error of `build`, or `Error::InvalidConfig`
+    /// for an invalid time step or duration
+    pub fn run<C, F>(&self, mut build: F) -> crate::Result<SweepTable>
+    where
+        C: Controller<PointMassState> + Send + 'static,
+        F: FnMut(&SweepPoint) -> crate::Result<(PointMass, C)>,
+    {
+        validate_run(self.dt, self.duration)?;
+        let points = self.points();
+        let (x, y, yaw) = self.track.get_start_position();
+        let mut simulations = Vec::with_capacity(points.len());
+        for point in &points {
+            let (mut model, controller) = build(point)?;
+            model.set_position(x, y, yaw);
+            let mut simulation = MultiVehicleSimulation::new(self.track.clone()).with_threads(1);
+            simulation.add_controlled_vehicle(model, controller);
+            simulations.push(simulation);
+        }
+
+        let runs = run_parallel(&mut simulations, self.dt, self.duration, self.threads)?;
+        Ok(SweepTable {
+            parameters: self.parameters.iter().map(|parameter| parameter.name.clone()).collect(),
+            rows: points
+                .into_iter()
+                .zip(&runs)
+                .map(|(point, run)| SweepRow {
+                    point,
+                    metrics: SweepMetrics::from_run(self.track.as_ref(), run),
+                })
+                .collect(),
+        })
+    }
+}
+
+/// Check the time step and duration shared by every run of a batch
+pub(crate) fn validate_run(dt: f64, duration: f64) -> crate::Result<()> {
+    if !(dt.is_finite() && dt > 0.0) {
+        return Err(Error::invalid_config(format!("dt must be a positive number, got {}", dt)));
+    }
+    if !(duration.is_finite() && duration >= 0.0) {
+        return Err(Error::invalid_config(format!(
+            "duration must be non-negative, got {}",
+            duration
+        )));
+    }
+    Ok(())
+}
+
+/// Run single-vehicle simulations on up to `threads` worker threads
+///
+/// Each worker runs a contiguous slice of `simulations`, so the results are in the
+/// order of `simulations` whatever the number of threads.
+pub(crate) fn run_parallel(
+    simulations: &mut [MultiVehicleSimulation],
+    dt: f64,
+    duration: f64,
+    threads: usize,
+) -> crate::Result<Vec<SimulationResult<PointMassState>>> {
+    let mut runs: Vec<crate::Result<_>> =
+        simulations.iter().map(|_| Ok(SimulationResult::new())).collect();
+    let run_all = |simulations: &mut [MultiVehicleSimulation], runs: &mut [crate::Result<_>]| {
+        for (simulation, run) in simulations.iter_mut().zip(runs) {
+            *run = simulation
+                .run(dt, duration)
+                .map(|mut result| result.runs.swap_remove(0));
+        }
+    };
+    let threads = threads.min(simulations.len());
+    if threads <= 1 {
+        run_all(simulations, &mut runs);
+        return runs.into_iter().collect();
+    }
+    let chunk_len = simulations.len().div_ceil(threads);
+    thread::scope(|scope| {
+        let chunks = simulations
+            .chunks_mut(chunk_len)
+            .zip(runs.chunks_mut(chunk_len));
+        for (simulations, runs) in chunks {
+            scope.spawn(move || run_all(simulations, runs));
+        }
+    });
+    // The first failed run in order, whatever the number of threads
+    runs.into_iter().collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{SweepParameter, SweepRunner};
+    use crate::controllers::stanley::StanleyController;
+    use crate::models::point_mass::PointMass;
+    use crate::tracks::base_track::SharedTrack;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_sweep_points_cover_every_combination() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let runner = SweepRunner::new(track)
+            .range("gain", 1.0, 3.0, 3)
+            .parameter(SweepParameter::new("target_speed", vec![5.0, 10.0]));
+        let points = runner.points();
+        assert_eq!(points.len(), 6);
+        assert_eq!(points[0].get("gain"), Some(1.0));
+        assert_eq!(points[1].get("target_speed"), Some(10.0));
+        assert_eq!(points[5].get("gain"), Some(3.0));
+        assert_eq!(points[5].get_or("softening", 1.0), 1.0);
+        assert_eq!(points[3].to_string(), "gain = 2, target_speed = 10");
+        assert!(SweepParameter::range("gain", 1.0, 3.0, 0).values.is_empty());
+    }
+
+    #[test]
+    fn test_sweep_stanley_target_speed() {
+        let track: SharedTrack = Arc::new(SquareTrack::new(100.0, 10.0, 25));
+        let sweep = |threads: usize| {
+            SweepRunner::new(Arc::clone(&track))
+                .parameter(SweepParameter::new("target_speed", vec![8.0, 12.0]))
+                .dt(0.05)
+                .duration(80.0)
+                .threads(threads)
+                .run(|point| {
+                    let speed = point.get_or("target_speed", 10.0);
+                    let controller = StanleyController::new(track.as_ref(), 2.0, 1.0, speed);
+                    Ok((PointMass::new(), controller))
+                })
+                .expect("sweep")
+        };
+        let table = sweep(2);
+        assert_eq!(table, sweep(1));
+        assert_eq!(table.parameters(), ["target_speed"]);
+
+        let (slow, fast) = (&table.rows()[0].metrics, &table.rows()[1].metrics);
+        let (slow_lap, fast_lap) = (
+            slow.lap_time.expect("slow lap"),
+            fast.lap_time.expect("fast lap"),
+        );
+        assert!(fast_lap < slow_lap, "{table}");
+        assert!(slow.max_cross_track_error < 5.0, "{table}");
+        assert_eq!(table.best_lap().map(|row| row.metrics.lap_time), Some(Some(fast_lap)));
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("sweep.csv");
+        table.write_csv(&path).expect("write csv");
+        let csv = std::fs::read_to_string(&path).expect("read csv");
+        let mut lines = csv.lines();
+        assert!(lines.next().expect("comment").starts_with("# provenance: "));
+        assert_eq!(
+            lines.next(),
+            Some("target_speed,lap_time,max_cross_track_error,off_track_count")
+        );
+        assert!(lines.next().expect("first row").starts_with("8,"));
+
+        let err = SweepRunner::new(Arc::clone(&track))
+            .dt(0.0)
+            .run(|_| Ok((PointMass::new(), StanleyController::new(track.as_ref(), 2.0, 1.0, 10.0))))
+            .expect_err("invalid dt");
+        assert_eq!(err.to_string(), "invalid configuration: dt must be a positive number, got 0");
+    }
+}
@@ -0,0 +1,46 @@
+//! Cooperative cancellation of long-running simulations.
+//!
+//! A `CancelToken` is shared between the code running a simulation and the code that
+//! may want to stop it (a server handling a disconnect, an async handle). Runs check
+//! the token before every step, so a cancelled run stops within one step.
+
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::Arc;
+
+/// Shared flag requesting a run to stop; clones refer to the same flag
+#[derive(Debug, Clone, Default)]
+pub struct CancelToken {
+    cancelled: Arc<AtomicBool>,
+}
+
+impl CancelToken {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Request every run observing this token to stop
+    pub fn cancel(&self) {
+        self.cancelled.store(true, Ordering::Relaxed);
+    }
+
+    /// Whether `cancel` was called on this token or one of its clones
+    pub fn is_cancelled(&self) -> bool {
+        self.cancelled.load(Ordering::Relaxed)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::CancelToken;
+
+    #[test]
+    fn test_cancel_token_is_shared_by_clones() {
+        let token = CancelToken::new();
+        let observer = token.clone();
+        assert!(!observer.is_cancelled());
+
+        token.cancel();
+        assert!(observer.is_cancelled());
+        assert!(!CancelToken::new().is_cancelled());
+    }
+}
@@ -0,0 +1,385 @@
+//! Struct-of-arrays layout of point mass simulation results.
+//!
+//! `ColumnarResult` stores every state component in its own vector instead of one
+//! vector of snapshots. Loops that read a single component (plotting x/y, exporting a
+//! column, computing speed statistics) then walk contiguous memory, and the per-snapshot
+//! padding of `StateSnapshot` disappears. The accessor API mirrors `SimulationResult`;
+//! snapshots and states are assembled on the fly, so they are returned by value.
+
+use crate::models::point_mass::PointMassState;
+use crate::models::scalar::Float;
+use crate::provenance::Provenance;
+use crate::simulation::profile::RunProfile;
+use crate::simulation::result::{
+    snapshot_count, write_csv_file, SimulationResult, StateSnapshot,
+};
+use std::path::Path;
+
+/// Header line written by `ColumnarResult::write_csv`
+pub const COLUMNAR_CSV_HEADER: &str = "time,x,y,vx,vy,yaw,in_track";
+
+/// Recorded trajectory of a point mass run, one vector per state component
+///
+/// As for `SimulationResult`, equality ignores the profile.
+#[derive(Debug, Clone, Default)]
+pub struct ColumnarResult<T = f64> {
+    time: Vec<f64>,
+    x: Vec<T>,
+    y: Vec<T>,
+    vx: Vec<T>,
+    vy: Vec<T>,
+    yaw: Vec<T>,
+    in_track: Vec<bool>,
+    profile: Option<RunProfile>,
+}
+
+impl<T: Float> ColumnarResult<T> {
+    /// Create an empty result
+    pub fn new() -> Self {
+        Self::with_capacity(0)
+    }
+
+    /// Create an empty result with room for `capacity` snapshots
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            time: Vec::with_capacity(capacity),
+            x: Vec::with_capacity(capacity),
+            y: Vec::with_capacity(capacity),
+            vx: Vec::with_capacity(capacity),
+            vy: Vec::with_capacity(capacity),
+            yaw: Vec::with_capacity(capacity),
+            in_track: Vec::with_capacity(capacity),
+            profile: None,
+        }
+    }
+
+    /// Create an empty result with room for every snapshot of a run
+    ///
+    /// # Arguments
+    /// * `dt` - Time step in seconds
+    /// * `duration` - Run duration in seconds
+    pub fn for_run(dt: f64, duration: f64) -> Self {
+        Self::with_capacity(snapshot_count(dt, duration))
+    }
+
+    /// Remove all snapshots and the profile, keeping the allocated storage for the next run
+    pub fn clear(&mut self) {
+        self.time.clear();
+        self.x.clear();
+        self.y.clear();
+        self.vx.clear();
+        self.vy.clear();
+        self.yaw.clear();
+        self.in_track.clear();
+        self.profile = None;
+    }
+
+    /// Make room for at least `additional` more snapshots
+    pub fn reserve(&mut self, additional: usize) {
+        self.time.reserve(additional);
+        self.x.reserve(additional);
+        self.y.reserve(additional);
+        self.vx.reserve(additional);
+        self.vy.reserve(additional);
+        self.yaw.reserve(additional);
+        self.in_track.reserve(additional);
+    }
+
+    /// Number of snapshots the result can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        [
+            self.time.capacity(),
+            self.x.capacity(),
+            self.y.capacity(),
+            self.vx.capacity(),
+            self.vy.capacity(),
+            self.yaw.capacity(),
+            self.in_track.capacity(),
+        ]
+        .into_iter()
+        .min()
+        .unwrap_or(0)
+    }
+
+    /// Append a snapshot to the result
+    pub fn push(&mut self, time: f64, state: PointMassState<T>, in_track: bool) {
+        self.time.push(time);
+        self.x.push(state.x);
+        self.y.push(state.y);
+        self.vx.push(state.vx);
+        self.vy.push(state.vy);
+        self.yaw.push(state.yaw);
+        self.in_track.push(in_track);
+    }
+
+    /// Number of recorded snapshots
+    pub fn len(&self) -> usize {
+        self.time.len()
+    }
+
+    /// Whether no snapshots were recorded
+    pub fn is_empty(&self) -> bool {
+        self.time.is_empty()
+    }
+
+    /// Snapshot at `index`, if any
+    pub fn snapshot(&self, index: usize) -> Option<StateSnapshot<PointMassState<T>>> {
+        Some(StateSnapshot {
+            time: *self.time.get(index)?,
+            state: self.state(index)?,
+            in_track: self.in_track[index],
+        })
+    }
+
+    /// Iterate over all snapshots in time order
+    pub fn snapshots(&self) -> impl Iterator<Item = StateSnapshot<PointMassState<T>>> + '_ {
+        (0..self.len()).filter_map(|index| self.snapshot(index))
+    }
+
+    /// State at `index`, if any
+    pub fn state(&self, index: usize) -> Option<PointMassState<T>> {
+        Some(PointMassState {
+            x: *self.x.get(index)?,
+            y: self.y[index],
+            vx: self.vx[index],
+            vy: self.vy[index],
+            yaw: self.yaw[index],
+        })
+    }
+
+    /// Iterate over the recorded states in time order
+    pub fn states(&self) -> impl Iterator<Item = PointMassState<T>> + '_ {
+        (0..self.len()).filter_map(|index| self.state(index))
+    }
+
+    /// Iterate over the snapshot times in seconds
+    pub fn times(&self) -> impl Iterator<Item = f64> + '_ {
+        self.time.iter().copied()
+    }
+
+    /// Iterate over the (x, y) positions in meters
+    pub fn positions(&self) -> impl Iterator<Item = (f64, f64)> + '_ {
+        self.x
+            .iter()
+            .zip(&self.y)
+            .map(|(x, y)| (x.to_f64(), y.to_f64()))
+    }
+
+    /// First recorded snapshot, if any
+    pub fn first(&self) -> Option<StateSnapshot<PointMassState<T>>> {
+        self.snapshot(0)
+    }
+
+    /// Last recorded snapshot, if any
+    pub fn last(&self) -> Option<StateSnapshot<PointMassState<T>>> {
+        self.snapshot(self.len().checked_sub(1)?)
+    }
+
+    /// Timing breakdown of the run, if it was profiled (`profiling` feature)
+    pub fn profile(&self) -> Option<&RunProfile> {
+        self.profile.as_ref()
+    }
+
+    pub fn set_profile(&mut self, profile: Option<RunProfile>) {
+        self.profile = profile;
+    }
+
+    /// Time of the last snapshot in seconds (0 for an empty result)
+    pub fn duration(&self) -> f64 {
+        self.time.last().copied().unwrap_or(0.0)
+    }
+
+    /// Snapshot times in seconds
+    pub fn time_values(&self) -> &[f64] {
+        &self.time
+    }
+
+    /// World frame x positions
+    pub fn x_values(&self) -> &[T] {
+        &self.x
+    }
+
+    /// World frame y positions
+    pub fn y_values(&self) -> &[T] {
+        &self.y
+    }
+
+    /// Body frame x velocities
+    pub fn vx_values(&self) -> &[T] {
+        &self.vx
+    }
+
+    /// Body frame y velocities
+    pub fn vy_values(&self) -> &[T] {
+        &self.vy
+    }
+
+    /// Orientation angles in radians
+    pub fn yaw_values(&self) -> &[T] {
+        &self.yaw
+    }
+
+    /// Whether each position was inside the track boundaries
+    pub fn in_track_values(&self) -> &[bool] {
+        &self.in_track
+    }
+
+    /// Write the result to a CSV file with a `COLUMNAR_CSV_HEADER` header line, after a
+    /// `# provenance:` comment line with the crate version and git commit
+    ///
+    /// Same format as `SimulationResult::write_csv`.
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        self.write_csv_with_provenance(path, &Provenance::current())
+    }
+
+    /// Write the result as `write_csv` does, with the scenario parameters and seed of
+    /// `provenance` in the comment line
+    pub fn write_csv_with_provenance<P: AsRef<Path>>(
+        &self,
+        path: P,
+        provenance: &Provenance,
+    ) -> crate::Result<()> {
+        write_csv_file(path.as_ref(), provenance, COLUMNAR_CSV_HEADER, |writer| {
+            for index in 0..self.len() {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{}",
+                    self.time[index],
+                    self.x[index],
+                    self.y[index],
+                    self.vx[index],
+                    self.vy[index],
+                    self.yaw[index],
+                    u8::from(self.in_track[index])
+                )?;
+            }
+            Ok(())
+        })
+    }
+
+    /// Copy the snapshots into a `SimulationResult`
+    pub fn to_result(&self) -> SimulationResult<PointMassState<T>> {
+        let mut result = SimulationResult::with_capacity(self.len());
+        for snapshot in self.snapshots() {
+            result.push(snapshot.time, snapshot.state, snapshot.in_track);
+        }
+        result.set_profile(self.profile);
+        result
+    }
+}
+
+impl<T: PartialEq> PartialEq for ColumnarResult<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.time == other.time
+            && self.x == other.x
+            && self.y == other.y
+            && self.vx == other.vx
+            && self.vy == other.vy
+            && self.yaw == other.yaw
+            && self.in_track == other.in_track
+    }
+}
+
+impl<T: Float> From<&SimulationResult<PointMassState<T>>> for ColumnarResult<T> {
+    fn from(result: &SimulationResult<PointMassState<T>>) -> Self {
+        let mut columns = Self::with_capacity(result.len());
+        for snapshot in result.snapshots() {
+            columns.push(snapshot.time, snapshot.state.clone(), snapshot.in_track);
+        }
+        columns.set_profile(result.profile().copied());
+        columns
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{ColumnarResult, COLUMNAR_CSV_HEADER};
+    use crate::provenance::Provenance;
+    use crate::models::point_mass::PointMassState;
+    use crate::simulation::result::SimulationResult;
+
+    fn state(x: f64) -> PointMassState {
+        PointMassState {
+            x,
+            y: -x,
+            vx: 2.0 * x,
+            vy: 0.0,
+            yaw: 0.5,
+        }
+    }
+
+    #[test]
+    fn test_columnar_result_mirrors_simulation_result() {
+        let mut result = SimulationResult::new();
+        for step in 0..5 {
+            result.push(step as f64 * 0.1, state(step as f64), step != 3);
+        }
+
+        let columns = ColumnarResult::from(&result);
+        assert_eq!(columns.len(), result.len());
+        assert_eq!(columns.duration(), result.duration());
+        assert_eq!(
+            columns.times().collect::<Vec<_>>(),
+            result.times().collect::<Vec<_>>()
+        );
+        assert_eq!(columns.x_values(), &[0.0, 1.0, 2.0, 3.0, 4.0]);
+        assert_eq!(columns.in_track_values(), &[true, true, true, false, true]);
+        let last = columns.last().expect("last snapshot");
+        assert_eq!(last.state.vx, 8.0);
+        assert!(columns.snapshot(5).is_none());
+
+        let round_trip = columns.to_result();
+        for (a, b) in round_trip.snapshots().iter().zip(result.snapshots()) {
+            assert_eq!(a.time, b.time);
+            assert_eq!(a.state.y, b.state.y);
+            assert_eq!(a.in_track, b.in_track);
+        }
+    }
+
+    #[test]
+    fn test_columnar_result_reuses_storage() {
+        let mut columns = ColumnarResult::<f32>::for_run(0.1, 1.0);
+        let capacity = columns.capacity();
+        assert!(capacity >= 11);
+        for step in 0..11 {
+            columns.push(
+                step as f64 * 0.1,
+                PointMassState::<f32> {
+                    x: step as f32,
+                    y: 0.0,
+                    vx: 0.0,
+                    vy: 0.0,
+                    yaw: 0.0,
+                },
+                true,
+            );
+        }
+        assert_eq!(columns.positions().nth(10), Some((10.0, 0.0)));
+        columns.clear();
+        assert!(columns.is_empty());
+        assert!(columns.first().is_none());
+        assert_eq!(columns.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_columnar_result_write_csv() {
+        let mut columns = ColumnarResult::new();
+        columns.push(0.0, state(1.0), true);
+        columns.push(0.5, state(1.5), false);
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("run.csv");
+        columns.write_csv(&path).expect("write csv");
+        let csv = std::fs::read_to_string(&path).expect("read csv");
+        let lines: Vec<&str> = csv.lines().collect();
+        assert_eq!(
+            lines,
+            [
+                Provenance::current().csv_comment().as_str(),
+                COLUMNAR_CSV_HEADER,
+                "0,1,-1,2,0,0.5,1",
+                "0.5,1.5,-1.5,3,0,0.5,0"
+            ]
+        );
+    }
+}
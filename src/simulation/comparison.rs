@@ -0,0 +1,261 @@
+//! Side-by-side comparison of two laps aligned by track progress.
+//!
+//! Both laps are sampled at the same fractions of their driven distance, so laps on
+//! slightly different lines still line up corner by corner. At every sample the
+//! comparison records the time delta (positive when the candidate is behind the
+//! reference) and the speeds of both laps; distances are reported along the reference
+//! lap.
+//!
+//! Laps are loaded with `load_lap` from any exported format: ghost lap files, reference
+//! telemetry CSV, or (with the `serde` feature) archived JSON results.
+
+use crate::error::{Error, Result};
+use crate::simulation::ghost::GhostLap;
+use std::fmt;
+use std::path::Path;
+
+/// Distance in meters between comparison samples used when none is given
+pub const DEFAULT_STEP: f64 = 1.0;
+
+/// Both laps at the same track progress
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct ComparisonSample {
+    /// Distance along the reference lap in meters
+    pub distance: f64,
+    /// Candidate time minus reference time in seconds, positive when the candidate is behind
+    pub delta_time: f64,
+    /// Reference speed in m/s
+    pub reference_speed: f64,
+    /// Candidate speed in m/s
+    pub candidate_speed: f64,
+}
+
+impl ComparisonSample {
+    /// Candidate speed minus reference speed in m/s
+    pub fn speed_delta(&self) -> f64 {
+        self.candidate_speed - self.reference_speed
+    }
+}
+
+/// Stretch of the lap where the time delta changed the most
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct DeltaSegment {
+    /// Distance along the reference lap where the stretch starts in meters
+    pub start: f64,
+    /// Distance along the reference lap where the stretch ends in meters
+    pub end: f64,
+    /// Change of the time delta over the stretch in seconds
+    pub delta: f64,
+}
+
+/// Two laps aligned by track progress
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapComparison {
+    pub reference_lap_time: f64,
+    pub candidate_lap_time: f64,
+    /// Samples every `step` meters along the reference lap, including both ends
+    pub samples: Vec<ComparisonSample>,
+}
+
+impl LapComparison {
+    /// Compare `candidate` against `reference` every `step` meters of the reference lap
+    pub fn new(reference: &GhostLap, candidate: &GhostLap, step: f64) -> Result<Self> {
+        if !(step.is_finite() && step > 0.0) {
+            let message = format!("comparison step must be positive, got {}", step);
+            return Err(Error::invalid_input(message));
+        }
+        let (reference_length, candidate_length) =
+            (reference.lap_distance(), candidate.lap_distance());
+        if reference_length <= 0.0 || candidate_length <= 0.0 {
+            return Err(Error::invalid_input("both laps need to cover some distance"));
+        }
+
+        let count = (reference_length / step).ceil() as usize;
+        let samples = (0..=count)
+            .map(|index| {
+                let fraction = (index as f64 * step / reference_length).min(1.0);
+                let at = |lap: &GhostLap, length: f64| {
+                    let time = lap.time_at_progress(fraction * length).unwrap_or(0.0);
+                    let speed = lap.sample_at(time).map_or(0.0, |sample| sample.speed);
+                    (time, speed)
+                };
+                let (reference_time, reference_speed) = at(reference, reference_length);
+                let (candidate_time, candidate_speed) = at(candidate, candidate_length);
+                ComparisonSample {
+                    distance: fraction * reference_length,
+                    delta_time: candidate_time - reference_time,
+                    reference_speed,
+                    candidate_speed,
+                }
+            })
+            .collect();
+
+        Ok(Self {
+            reference_lap_time: reference.lap_time(),
+            candidate_lap_time: candidate.lap_time(),
+            samples,
+        })
+    }
+
+    /// Candidate lap time minus reference lap time in seconds
+    pub fn final_delta(&self) -> f64 {
+        self.candidate_lap_time - self.reference_lap_time
+    }
+
+    /// Average of the candidate speed minus the reference speed over the samples in m/s
+    pub fn mean_speed_delta(&self) -> f64 {
+        if self.samples.is_empty() {
+            return 0.0;
+        }
+        self.samples.iter().map(ComparisonSample::speed_delta).sum::<f64>()
+            / self.samples.len() as f64
+    }
+
+    /// Stretch of about `length` meters where the candidate lost the most time
+    pub fn largest_loss(&self, length: f64) -> Option<DeltaSegment> {
+        self.segments(length)
+            .max_by(|a, b| a.delta.total_cmp(&b.delta))
+            .filter(|segment| segment.delta > 0.0)
+    }
+
+    /// Stretch of about `length` meters where the candidate gained the most time
+    pub fn largest_gain(&self, length: f64) -> Option<DeltaSegment> {
+        self.segments(length)
+            .min_by(|a, b| a.delta.total_cmp(&b.delta))
+            .filter(|segment| segment.delta < 0.0)
+    }
+
+    /// Every stretch from one sample to the first sample at least `length` meters further
+    fn segments(&self, length: f64) -> impl Iterator<Item = DeltaSegment> + '_ {
+        self.samples.iter().enumerate().filter_map(move |(index, start)| {
+            let end = self.samples[index..]
+                .iter()
+                .find(|sample| sample.distance - start.distance >= length)?;
+            Some(DeltaSegment {
+                start: start.distance,
+                end: end.distance,
+                delta: end.delta_time - start.delta_time,
+            })
+        })
+    }
+}
+
+/// Length in meters of the stretches listed in the summary
+const SUMMARY_SEGMENT: f64 = 50.0;
+
+impl fmt::Display for LapComparison {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        writeln!(
+            f,
+            "Reference lap: {:.3} s, candidate lap: {:.3} s ({:+.3} s)",
+            self.reference_lap_time,
+            self.candidate_lap_time,
+            self.final_delta()
+        )?;
+        write!(f, "Mean speed difference: {:+.2} m/s", self.mean_speed_delta())?;
+        if let Some(loss) = self.largest_loss(SUMMARY_SEGMENT) {
+            write!(
+                f,
+                "\nLargest loss: {:+.3} s between {:.0} m and {:.0} m",
+                loss.delta, loss.start, loss.end
+            )?;
+        }
+        if let Some(gain) = self.largest_gain(SUMMARY_SEGMENT) {
+            write!(
+                f,
+                "\nLargest gain: {:+.3} s between {:.0} m and {:.0} m",
+                gain.delta, gain.start, gain.end
+            )?;
+        }
+        Ok(())
+    }
+}
+
+/// Load a lap from an exported file, picking the format from the extension
+///
+/// - `.ghost` (optionally `.gz` or `.zst` compressed): ghost lap file
+/// - `.json`: archived point mass result (requires the `serde` feature)
+/// - anything else: reference telemetry CSV with `time`, `x`, `y`, and `speed` columns
+pub fn load_lap<P: AsRef<Path>>(path: P) -> Result<GhostLap> {
+    let path = path.as_ref();
+    let name = path.to_string_lossy().to_ascii_lowercase();
+    let name = name
+        .strip_suffix(".gz")
+        .or_else(|| name.strip_suffix(".zst"))
+        .unwrap_or(&name);
+    if name.ends_with(".ghost") {
+        GhostLap::load(path)
+    } else if name.ends_with(".json") {
+        load_json_lap(path)
+    } else {
+        GhostLap::load_reference_csv(path)
+    }
+}
+
+#[cfg(feature = "serde")]
+fn load_json_lap(path: &Path) -> Result<GhostLap> {
+    use crate::models::point_mass::PointMassState;
+    let result = crate::io::result_file::load_result::<PointMassState, _>(path)?;
+    GhostLap::from_result(&result)
+}
+
+#[cfg(not(feature = "serde"))]
+fn load_json_lap(path: &Path) -> Result<GhostLap> {
+    Err(Error::invalid_input(format!(
+        "{}: loading JSON results requires the `serde` feature",
+        path.display()
+    )))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_lap, LapComparison};
+    use crate::simulation::ghost::{GhostLap, GhostSample};
+
+    /// Straight 100 m lap driven at `speed(distance)`
+    fn straight_lap(speed: impl Fn(f64) -> f64) -> GhostLap {
+        let (dt, mut x, mut time) = (0.01, 0.0, 0.0);
+        let mut samples = Vec::new();
+        while x < 100.0 {
+            samples.push(GhostSample { time, progress: 0.0, x, y: 0.0, yaw: 0.0, speed: speed(x) });
+            x += speed(x) * dt;
+            time += dt;
+        }
+        GhostLap::new(samples).expect("ghost lap")
+    }
+
+    #[test]
+    fn test_compare_laps() {
+        let reference = straight_lap(|_| 10.0);
+        // Same pace, but 5 m/s for the 20 m from 40 m on: 2 s instead of 4 s
+        let candidate = straight_lap(|x| if (40.0..60.0).contains(&x) { 5.0 } else { 10.0 });
+        let comparison = LapComparison::new(&reference, &candidate, 1.0).expect("comparison");
+
+        assert_eq!(comparison.samples.len(), 101);
+        assert!(comparison.samples[40].delta_time.abs() < 0.05);
+        assert!((comparison.samples[60].delta_time - 2.0).abs() < 0.05);
+        assert!((comparison.final_delta() - 2.0).abs() < 0.05);
+        assert_eq!(comparison.samples[50].speed_delta(), -5.0);
+
+        let loss = comparison.largest_loss(20.0).expect("loss");
+        assert!((loss.start - 40.0).abs() <= 1.0 && (loss.delta - 2.0).abs() < 0.05, "{loss:?}");
+        assert!(comparison.largest_gain(20.0).is_none_or(|gain| gain.delta > -0.05));
+        assert!(comparison.to_string().contains("Largest loss"));
+
+        let err = LapComparison::new(&reference, &candidate, 0.0).expect_err("zero step");
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+    }
+
+    #[test]
+    fn test_load_lap_by_extension() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let ghost_path = temp_dir.path().join("reference.ghost");
+        straight_lap(|_| 10.0).save(&ghost_path).expect("save ghost");
+        assert!((load_lap(&ghost_path).expect("load ghost").lap_time() - 10.0).abs() < 0.05);
+
+        let csv_path = temp_dir.path().join("telemetry.csv");
+        std::fs::write(&csv_path, "time,x,y,speed\n0,0,0,10\n1,10,0,10\n2,20,0,10\n")
+            .expect("write csv");
+        assert_eq!(load_lap(&csv_path).expect("load csv").lap_distance(), 20.0);
+    }
+}
@@ -0,0 +1,186 @@
+//! Track limits evaluated on the vehicle footprint instead of its center point.
+//!
+//! The footprint is the vehicle rectangle from its size and yaw; its four corners
+//! stand in for the wheels. A vehicle with some corners outside the boundaries is on a
+//! partial excursion (e.g., two wheels off on the exit of a corner), one with all four
+//! corners outside has left the track.
+
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::base_track::Track;
+use std::fmt;
+
+/// How a simulation decides whether a state is on the track
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum InTrackMode {
+    /// The vehicle center is inside the boundaries
+    #[default]
+    Center,
+    /// All four corners of the vehicle footprint are inside the boundaries
+    Footprint,
+}
+
+impl InTrackMode {
+    /// Whether a vehicle of `size` (length, width) at `pose` counts as on the track
+    pub fn is_in_track<P: Pose + ?Sized>(self, track: &dyn Track, pose: &P, size: (f64, f64)) -> bool {
+        match self {
+            InTrackMode::Center => track.is_in_track(pose.x(), pose.y()),
+            InTrackMode::Footprint => containment(track, pose, size) == Containment::Inside,
+        }
+    }
+}
+
+/// Position of the vehicle footprint relative to the track boundaries
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Containment {
+    /// All corners are inside the boundaries
+    Inside,
+    /// Between one and three corners are outside the boundaries
+    Partial { corners_off: usize },
+    /// All corners are outside the boundaries
+    Outside,
+}
+
+impl fmt::Display for Containment {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            Containment::Inside => write!(f, "on track"),
+            Containment::Partial { corners_off: 1 } => write!(f, "one wheel off"),
+            Containment::Partial { corners_off } => write!(f, "{} wheels off", corners_off),
+            Containment::Outside => write!(f, "off track"),
+        }
+    }
+}
+
+/// Front left, rear left, rear right, and front right corners of the footprint in meters
+pub fn footprint_corners<P: Pose + ?Sized>(pose: &P, (length, width): (f64, f64)) -> [Vec2; 4] {
+    let (half_length, half_width) = (length / 2.0, width / 2.0);
+    let center = Vec2::new(pose.x(), pose.y());
+    [
+        Vec2::new(half_length, half_width),
+        Vec2::new(-half_length, half_width),
+        Vec2::new(-half_length, -half_width),
+        Vec2::new(half_length, -half_width),
+    ]
+    .map(|body| center + body.rotate(pose.yaw()))
+}
+
+/// Containment of the footprint of a vehicle of `size` (length, width) at `pose`
+pub fn containment<P: Pose + ?Sized>(track: &dyn Track, pose: &P, size: (f64, f64)) -> Containment {
+    let corners_off = footprint_corners(pose, size)
+        .iter()
+        .filter(|corner| !track.is_in_track(corner.x, corner.y))
+        .count();
+    match corners_off {
+        0 => Containment::Inside,
+        4 => Containment::Outside,
+        corners_off => Containment::Partial { corners_off },
+    }
+}
+
+/// Stretch of a run with at least one corner of the footprint outside the boundaries
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Excursion {
+    /// Time of the first snapshot with a corner off in seconds
+    pub start: f64,
+    /// Time of the last snapshot with a corner off in seconds
+    pub end: f64,
+    /// Most corners outside the boundaries at once
+    pub max_corners_off: usize,
+}
+
+impl Excursion {
+    /// Whether the vehicle kept at least one corner on the track throughout
+    pub fn is_partial(&self) -> bool {
+        self.max_corners_off < 4
+    }
+}
+
+impl fmt::Display for Excursion {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let kind = if self.is_partial() {
+            Containment::Partial { corners_off: self.max_corners_off }
+        } else {
+            Containment::Outside
+        };
+        write!(f, "{:.2} s - {:.2} s: {}", self.start, self.end, kind)
+    }
+}
+
+/// Excursions of a recorded run, evaluated on the footprint of every snapshot
+///
+/// States that carry a size use it; the others use `default_size` (length, width).
+pub fn excursions<S: Pose>(
+    track: &dyn Track,
+    result: &SimulationResult<S>,
+    default_size: (f64, f64),
+) -> Vec<Excursion> {
+    let mut excursions: Vec<Excursion> = Vec::new();
+    let mut current: Option<Excursion> = None;
+    for snapshot in result.snapshots() {
+        let size = snapshot.state.size().unwrap_or(default_size);
+        let corners_off = match containment(track, &snapshot.state, size) {
+            Containment::Inside => 0,
+            Containment::Partial { corners_off } => corners_off,
+            Containment::Outside => 4,
+        };
+        if corners_off == 0 {
+            excursions.extend(current.take());
+            continue;
+        }
+        let excursion = current.get_or_insert(Excursion {
+            start: snapshot.time,
+            end: snapshot.time,
+            max_corners_off: 0,
+        });
+        excursion.end = snapshot.time;
+        excursion.max_corners_off = excursion.max_corners_off.max(corners_off);
+    }
+    excursions.extend(current);
+    excursions
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{containment, excursions, Containment, InTrackMode};
+    use crate::simulation::result::SimulationResult;
+    use crate::tracks::square::SquareTrack;
+
+    #[test]
+    fn test_footprint_containment() {
+        // Right side of the square: x from 45 to 55, driving along +y
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let size = (4.0, 2.0);
+        let yaw = std::f64::consts::FRAC_PI_2;
+        assert_eq!(containment(&track, &(50.0, 0.0, yaw), size), Containment::Inside);
+        assert_eq!(
+            containment(&track, &(54.5, 0.0, yaw), size),
+            Containment::Partial { corners_off: 2 }
+        );
+        assert_eq!(containment(&track, &(60.0, 0.0, yaw), size), Containment::Outside);
+
+        // Two wheels off: the center is still inside
+        assert!(InTrackMode::Center.is_in_track(&track, &(54.5, 0.0, yaw), size));
+        assert!(!InTrackMode::Footprint.is_in_track(&track, &(54.5, 0.0, yaw), size));
+        assert_eq!(Containment::Partial { corners_off: 2 }.to_string(), "2 wheels off");
+    }
+
+    #[test]
+    fn test_excursions_distinguish_partial() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let yaw = std::f64::consts::FRAC_PI_2;
+        let mut result = SimulationResult::new();
+        for (index, x) in [50.0, 54.5, 54.5, 50.0, 54.5, 60.0, 50.0].into_iter().enumerate() {
+            result.push(index as f64, (x, 0.0, yaw), true);
+        }
+        let excursions = excursions(&track, &result, (4.0, 2.0));
+
+        assert_eq!(excursions.len(), 2);
+        assert_eq!((excursions[0].start, excursions[0].end), (1.0, 2.0));
+        assert!(excursions[0].is_partial());
+        assert_eq!(excursions[0].to_string(), "1.00 s - 2.00 s: 2 wheels off");
+        assert_eq!((excursions[1].start, excursions[1].end), (4.0, 5.0));
+        assert!(!excursions[1].is_partial());
+    }
+}
@@ -0,0 +1,524 @@
+//! Ghost laps: time-stamped reference trajectories to compare simulated laps against.
+//!
+//! A ghost lap can come from a simulation run or from external telemetry (e.g., a
+//! real car's lap logged as CSV), and is normalized to start at time zero with a yaw
+//! derived from the driven path.
+//!
+//! Ghost laps are exchanged as `.ghost` files: a version line, `@key=value` metadata
+//! lines, and a CSV table with one sample per line.
+//!
+//! ```text
+//! # lap_simulation ghost lap v1
+//! @track=Circle Track
+//! @lap_time=1.5
+//! time,s,x,y,yaw,speed
+//! 0,0,50,0,1.5707963267948966,0
+//! ...
+//! ```
+//!
+//! `s` is the distance driven since the start of the lap in meters; `lap_time` is
+//! informational and recomputed on load.
+
+use crate::error::{Error, Result};
+use crate::io::compression;
+use crate::math::angle::Angle;
+use crate::models::point_mass::PointMassState;
+use crate::models::pose::Pose;
+use crate::simulation::result::SimulationResult;
+use std::collections::BTreeMap;
+use std::fmt::Write as _;
+use std::path::Path;
+
+/// First line of a ghost lap file
+pub const GHOST_FILE_HEADER: &str = "# lap_simulation ghost lap v1";
+
+const GHOST_COLUMNS: &str = "time,s,x,y,yaw,speed";
+
+/// Sample of a ghost lap
+#[derive(Debug, Clone, Copy, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GhostSample {
+    /// Time since the start of the lap in seconds
+    pub time: f64,
+    /// Distance driven since the start of the lap in meters (computed by `GhostLap::new`)
+    pub progress: f64,
+    /// World frame x position in meters
+    pub x: f64,
+    /// World frame y position in meters
+    pub y: f64,
+    /// Heading in radians
+    pub yaw: f64,
+    /// Speed in m/s
+    pub speed: f64,
+}
+
+impl Pose for GhostSample {
+    fn x(&self) -> f64 {
+        self.x
+    }
+
+    fn y(&self) -> f64 {
+        self.y
+    }
+
+    fn yaw(&self) -> f64 {
+        self.yaw
+    }
+}
+
+/// Reference lap sampled over time
+#[derive(Debug, Clone, PartialEq, Default)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct GhostLap {
+    samples: Vec<GhostSample>,
+    metadata: BTreeMap<String, String>,
+}
+
+impl GhostLap {
+    /// Create a ghost lap from samples with strictly increasing times
+    ///
+    /// Times are shifted so that the first sample is at zero, and the progress of each
+    /// sample is set to the path length driven up to it.
+    pub fn new(mut samples: Vec<GhostSample>) -> Result<Self> {
+        for (index, sample) in samples.iter().enumerate() {
+            let values = [sample.time, sample.x, sample.y, sample.yaw, sample.speed];
+            if values.iter().any(|value| !value.is_finite()) {
+                let message = format!("sample {}: values must be finite", index);
+                return Err(Error::invalid_input(message));
+            }
+            if index > 0 && sample.time <= samples[index - 1].time {
+                let message = format!("sample {}: times must be strictly increasing", index);
+                return Err(Error::invalid_input(message));
+            }
+        }
+        if let Some(&first) = samples.first() {
+            let mut progress = 0.0;
+            let mut previous = first;
+            for sample in &mut samples {
+                progress += (sample.x - previous.x).hypot(sample.y - previous.y);
+                previous = *sample;
+                sample.time -= first.time;
+                sample.progress = progress;
+            }
+        }
+        Ok(Self {
+            samples,
+            metadata: BTreeMap::new(),
+        })
+    }
+
+    /// Attach a metadata entry (e.g., "track", "driver", or "source")
+    ///
+    /// Keys may not contain `=`; line breaks in values are replaced with spaces.
+    pub fn with_metadata(mut self, key: &str, value: &str) -> Result<Self> {
+        let key = key.trim();
+        if key.is_empty() || key.contains(['=', '\n', '\r']) {
+            return Err(Error::invalid_input(format!("invalid metadata key '{}'", key)));
+        }
+        self.metadata
+            .insert(key.to_string(), value.replace(['\n', '\r'], " "));
+        Ok(self)
+    }
+
+    /// Metadata entries of the lap
+    pub fn metadata(&self) -> &BTreeMap<String, String> {
+        &self.metadata
+    }
+
+    /// Ghost lap of a simulated point mass run
+    pub fn from_result(result: &SimulationResult<PointMassState>) -> Result<Self> {
+        Self::new(
+            result
+                .snapshots()
+                .iter()
+                .map(|snapshot| GhostSample {
+                    time: snapshot.time,
+                    progress: 0.0,
+                    x: snapshot.state.x,
+                    y: snapshot.state.y,
+                    yaw: snapshot.state.yaw,
+                    speed: snapshot.state.vx.hypot(snapshot.state.vy),
+                })
+                .collect(),
+        )
+    }
+
+    /// Parse reference lap telemetry from CSV text
+    ///
+    /// The header must name the `time`, `x`, `y`, and `speed` columns (any order,
+    /// case-insensitive); other columns are ignored. Rows with a repeated timestamp
+    /// are dropped. The yaw is derived from the direction of travel.
+    ///
+    /// # Returns
+    /// The lap, or `Error::InvalidInput` naming the line that is malformed
+    pub fn parse_reference_csv(text: &str) -> Result<Self> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .filter(|(_, line)| !line.trim().is_empty() && !line.trim_start().starts_with('#'));
+        let (_, header) = lines
+            .next()
+            .ok_or_else(|| Error::invalid_input("reference telemetry is empty"))?;
+        let columns: Vec<String> = header
+            .split(',')
+            .map(|column| column.trim().to_ascii_lowercase())
+            .collect();
+        let column = |name: &str| {
+            columns
+                .iter()
+                .position(|column| column == name)
+                .ok_or_else(|| {
+                    Error::invalid_input(format!("missing '{}' column in header", name))
+                })
+        };
+        let indices = [
+            column("time")?,
+            column("x")?,
+            column("y")?,
+            column("speed")?,
+        ];
+
+        let mut rows: Vec<[f64; 4]> = Vec::new();
+        for (line_index, line) in lines {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let mut row = [0.0; 4];
+            for (value, &index) in row.iter_mut().zip(indices.iter()) {
+                let field = fields.get(index).ok_or_else(|| {
+                    let message = format!("line {}: missing column {}", line_index + 1, index + 1);
+                    Error::invalid_input(message)
+                })?;
+                *value = field.parse().map_err(|_| {
+                    let message = format!("line {}: invalid value '{}'", line_index + 1, field);
+                    Error::invalid_input(message)
+                })?;
+            }
+            if rows.last().is_some_and(|last| last[0] == row[0]) {
+                continue;
+            }
+            rows.push(row);
+        }
+
+        let samples = rows
+            .iter()
+            .enumerate()
+            .map(|(index, &[time, x, y, speed])| GhostSample {
+                time,
+                progress: 0.0,
+                x,
+                y,
+                yaw: heading(&rows, index),
+                speed,
+            })
+            .collect();
+        Self::new(samples)
+    }
+
+    /// Load reference lap telemetry from a CSV file (see `parse_reference_csv`)
+    pub fn load_reference_csv<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = compression::read_to_string(path)?;
+        Self::parse_reference_csv(&text).map_err(|err| err.context(path.display()))
+    }
+
+    /// Serialize the lap in the ghost lap file format (see the module documentation)
+    pub fn to_ghost_string(&self) -> String {
+        let mut text = format!("{}\n", GHOST_FILE_HEADER);
+        for (key, value) in &self.metadata {
+            let _ = writeln!(text, "@{}={}", key, value);
+        }
+        let _ = writeln!(text, "@lap_time={}", self.lap_time());
+        let _ = writeln!(text, "{}", GHOST_COLUMNS);
+        for sample in &self.samples {
+            let _ = writeln!(
+                text,
+                "{},{},{},{},{},{}",
+                sample.time, sample.progress, sample.x, sample.y, sample.yaw, sample.speed
+            );
+        }
+        text
+    }
+
+    /// Parse a lap in the ghost lap file format
+    ///
+    /// # Returns
+    /// The lap, or `Error::InvalidInput` if the text is not a ghost lap file of this
+    /// version or a line is malformed
+    pub fn parse_ghost(text: &str) -> Result<Self> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty());
+        match lines.next() {
+            Some((_, GHOST_FILE_HEADER)) => {}
+            Some((_, line)) if line.starts_with("# lap_simulation ghost lap") => {
+                let message = format!("unsupported ghost lap version '{}'", line);
+                return Err(Error::invalid_input(message));
+            }
+            _ => return Err(Error::invalid_input("not a ghost lap file")),
+        }
+
+        let mut metadata = Vec::new();
+        let mut samples = Vec::new();
+        let mut columns_seen = false;
+        for (line_number, line) in lines {
+            if let Some(entry) = line.strip_prefix('@') {
+                let (key, value) = entry
+                    .split_once('=')
+                    .ok_or_else(|| {
+                        Error::invalid_input(format!("line {}: expected @key=value", line_number))
+                    })?;
+                if key != "lap_time" {
+                    metadata.push((key.to_string(), value.to_string()));
+                }
+                continue;
+            }
+            if !columns_seen {
+                if line != GHOST_COLUMNS {
+                    return Err(Error::invalid_input(format!(
+                        "line {}: expected columns '{}'",
+                        line_number, GHOST_COLUMNS
+                    )));
+                }
+                columns_seen = true;
+                continue;
+            }
+            let values: Vec<f64> = line
+                .split(',')
+                .map(|field| field.trim().parse::<f64>())
+                .collect::<Result<_, _>>()
+                .map_err(|_| {
+                    Error::invalid_input(format!("line {}: invalid sample '{}'", line_number, line))
+                })?;
+            let &[time, progress, x, y, yaw, speed] = values.as_slice() else {
+                let message = format!("line {}: expected 6 values", line_number);
+                return Err(Error::invalid_input(message));
+            };
+            samples.push(GhostSample {
+                time,
+                progress,
+                x,
+                y,
+                yaw,
+                speed,
+            });
+        }
+
+        let mut lap = Self::new(samples)?;
+        for (key, value) in metadata {
+            lap = lap.with_metadata(&key, &value)?;
+        }
+        Ok(lap)
+    }
+
+    /// Save the lap as a ghost lap file, creating parent directories
+    ///
+    /// Paths ending in `.gz` or `.zst` are compressed (see `io::compression`).
+    pub fn save<P: AsRef<Path>>(&self, path: P) -> Result<()> {
+        compression::write_file(path, self.to_ghost_string().as_bytes())
+    }
+
+    /// Load a ghost lap file (see `parse_ghost`)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = compression::read_to_string(path)?;
+        Self::parse_ghost(&text).map_err(|err| err.context(path.display()))
+    }
+
+    pub fn samples(&self) -> &[GhostSample] {
+        &self.samples
+    }
+
+    /// Distance driven over the whole lap in meters
+    pub fn lap_distance(&self) -> f64 {
+        self.samples.last().map_or(0.0, |sample| sample.progress)
+    }
+
+    /// Interpolated time at which the lap reached `progress` meters, clamped to the lap
+    ///
+    /// Comparing this against another lap at the same progress gives the time delta.
+    pub fn time_at_progress(&self, progress: f64) -> Option<f64> {
+        let next = self
+            .samples
+            .partition_point(|sample| sample.progress <= progress);
+        if next == 0 {
+            return self.samples.first().map(|sample| sample.time);
+        }
+        let prev = self.samples[next - 1];
+        let Some(next) = self.samples.get(next) else {
+            return Some(prev.time);
+        };
+        let alpha = (progress - prev.progress) / (next.progress - prev.progress);
+        Some(prev.time + (next.time - prev.time) * alpha)
+    }
+
+    /// Duration of the lap in seconds
+    pub fn lap_time(&self) -> f64 {
+        self.samples.last().map_or(0.0, |sample| sample.time)
+    }
+
+    /// Interpolated sample at `time`, clamped to the lap
+    pub fn sample_at(&self, time: f64) -> Option<GhostSample> {
+        let next = self.samples.partition_point(|sample| sample.time <= time);
+        if next == 0 {
+            return self.samples.first().copied();
+        }
+        let prev = self.samples[next - 1];
+        let Some(&next) = self.samples.get(next) else {
+            return Some(prev);
+        };
+        let alpha = (time - prev.time) / (next.time - prev.time);
+        let yaw_delta = Angle::wrap(next.yaw - prev.yaw);
+        Some(GhostSample {
+            time,
+            progress: prev.progress + (next.progress - prev.progress) * alpha,
+            x: prev.x + (next.x - prev.x) * alpha,
+            y: prev.y + (next.y - prev.y) * alpha,
+            yaw: prev.yaw + yaw_delta * alpha,
+            speed: prev.speed + (next.speed - prev.speed) * alpha,
+        })
+    }
+}
+
+/// Direction of travel at row `index`, from its neighbours
+fn heading(rows: &[[f64; 4]], index: usize) -> f64 {
+    let from = rows[index.saturating_sub(1)];
+    let to = rows[(index + 1).min(rows.len() - 1)];
+    let (dx, dy) = (to[1] - from[1], to[2] - from[2]);
+    if dx == 0.0 && dy == 0.0 {
+        0.0
+    } else {
+        dy.atan2(dx)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{GhostLap, GhostSample};
+    use crate::simulation::base_simulation::Simulation;
+    use crate::simulation::open_loop::OpenLoopSimulation;
+    use crate::tracks::circle::CircleTrack;
+    use std::f64::consts::FRAC_PI_2;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_parse_reference_csv_normalizes() {
+        let csv =
+            "Speed,Time,X,Y,Gear\n10,100.0,0,0,2\n10,100.0,0,0,2\n12,100.5,5,0,2\n14,101.0,5,5,3\n";
+        let ghost = GhostLap::parse_reference_csv(csv).expect("parse reference lap");
+
+        let samples = ghost.samples();
+        assert_eq!(samples.len(), 3);
+        assert_eq!(samples[0].time, 0.0);
+        assert_eq!(ghost.lap_time(), 1.0);
+        assert_eq!(samples[0].yaw, 0.0);
+        assert!((samples[2].yaw - FRAC_PI_2).abs() < 1e-12);
+        assert_eq!(samples[1].speed, 12.0);
+
+        let middle = ghost.sample_at(0.25).expect("sample");
+        assert_eq!((middle.x, middle.y, middle.speed), (2.5, 0.0, 11.0));
+        assert_eq!(ghost.sample_at(5.0).expect("sample").time, 1.0);
+    }
+
+    #[test]
+    fn test_parse_reference_csv_errors() {
+        let err = GhostLap::parse_reference_csv("time,x,y\n0,0,0\n").expect_err("missing speed");
+        assert!(err.to_string().contains("missing 'speed' column"));
+
+        let err = GhostLap::parse_reference_csv("time,x,y,speed\n0,0,0,1\n1,a,0,1\n")
+            .expect_err("invalid value");
+        assert!(err.to_string().contains("line 3"));
+
+        let err = GhostLap::parse_reference_csv("time,x,y,speed\n1,0,0,1\n0,1,0,1\n")
+            .expect_err("decreasing time");
+        assert!(err.to_string().contains("strictly increasing"));
+    }
+
+    #[test]
+    fn test_ghost_lap_from_result() {
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(1.0, 0.0)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+        let result = sim.run(0.1, 1.0).expect("run");
+
+        let ghost = GhostLap::from_result(&result).expect("ghost lap");
+
+        assert_eq!(ghost.samples().len(), result.len());
+        assert!((ghost.lap_time() - 1.0).abs() < 1e-9);
+        assert!((ghost.samples()[10].speed - 1.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_ghost_progress_and_time_at_progress() {
+        let ghost =
+            GhostLap::parse_reference_csv("time,x,y,speed\n0,0,0,10\n1,10,0,10\n3,10,10,5\n")
+                .expect("parse reference lap");
+
+        let progress: Vec<f64> = ghost
+            .samples()
+            .iter()
+            .map(|sample| sample.progress)
+            .collect();
+        assert_eq!(progress, vec![0.0, 10.0, 20.0]);
+        assert_eq!(ghost.lap_distance(), 20.0);
+        assert_eq!(ghost.time_at_progress(5.0), Some(0.5));
+        assert_eq!(ghost.time_at_progress(15.0), Some(2.0));
+        assert_eq!(ghost.time_at_progress(50.0), Some(3.0));
+        assert_eq!(ghost.sample_at(2.0).expect("sample").progress, 15.0);
+    }
+
+    #[test]
+    fn test_ghost_file_round_trip() {
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(1.0, 0.2)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+        let ghost = GhostLap::from_result(&sim.run(0.1, 1.0).expect("run"))
+            .expect("ghost lap")
+            .with_metadata("track", "Circle Track")
+            .expect("metadata")
+            .with_metadata("notes", "line one\nline two")
+            .expect("metadata");
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("laps").join("best.ghost");
+        ghost.save(&path).expect("save ghost");
+        let text = std::fs::read_to_string(&path).expect("read ghost");
+        assert!(text.starts_with("# lap_simulation ghost lap v1\n"));
+        assert!(text.contains("@track=Circle Track\n"));
+
+        let loaded = GhostLap::load(&path).expect("load ghost");
+        assert_eq!(loaded, ghost);
+        assert_eq!(loaded.metadata()["notes"], "line one line two");
+    }
+
+    #[test]
+    fn test_parse_ghost_errors() {
+        let err = GhostLap::parse_ghost("time,x,y,speed\n").expect_err("not a ghost file");
+        assert!(err.to_string().contains("not a ghost lap file"));
+
+        let err = GhostLap::parse_ghost("# lap_simulation ghost lap v9\n").expect_err("version");
+        assert!(err.to_string().contains("unsupported"));
+
+        let err = GhostLap::parse_ghost(
+            "# lap_simulation ghost lap v1\ntime,s,x,y,yaw,speed\n0,0,0,0,0\n",
+        )
+        .expect_err("short row");
+        assert!(err.to_string().contains("line 3"));
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+
+        assert!(GhostLap::default().with_metadata("a=b", "c").is_err());
+        let sample = GhostSample {
+            time: 0.0,
+            progress: 0.0,
+            x: 0.0,
+            y: 0.0,
+            yaw: 0.0,
+            speed: 0.0,
+        };
+        assert!(GhostLap::new(vec![sample]).is_ok());
+    }
+}
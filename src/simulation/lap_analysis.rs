@@ -0,0 +1,565 @@
+//! Corner analysis of recorded laps: apexes, braking points, and minimum speeds.
+//!
+//! Corners are the stretches of the track center line whose curvature exceeds a
+//! threshold. A recorded run is split into laps at the start position, and every pass
+//! through a corner is summarized by
+//! - the apex: the snapshot closest to the point of highest curvature,
+//! - the minimum speed: the slowest snapshot between the previous corner and the end
+//!   of this one,
+//! - the braking point: where the deceleration leading to that minimum started.
+//!
+//! Laps start where the run crosses the start line (`TimingLine::start_line`), timed
+//! within the step of the crossing. Distances along the center line are measured from
+//! the start position. Speeds are
+//! estimated from consecutive positions, so the states of any model implementing
+//! `Pose` can be analyzed.
+
+use crate::error::{Error, Result};
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::arc_length::ArcLengthTable;
+use crate::tracks::base_track::Track;
+use crate::tracks::timing_line::TimingLine;
+use std::fmt;
+
+/// Distance in meters from the start position within which a run counts as starting on it
+pub(crate) const START_LINE_TOLERANCE: f64 = 1.0;
+
+/// Thresholds of the corner analysis
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct AnalysisOptions {
+    /// Smallest center line curvature in 1/m considered part of a corner
+    pub corner_curvature: f64,
+    /// Smallest deceleration in m/s² considered braking
+    pub braking_deceleration: f64,
+}
+
+impl Default for AnalysisOptions {
+    fn default() -> Self {
+        Self {
+            corner_curvature: 0.01,
+            braking_deceleration: 0.5,
+        }
+    }
+}
+
+/// Corner of the track
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Corner {
+    /// Distance along the center line where the corner begins in meters
+    pub start: f64,
+    /// Distance along the center line of the highest curvature in meters
+    pub apex: f64,
+    /// Distance along the center line where the corner ends in meters
+    ///
+    /// Smaller than `start` for a corner spanning the start position.
+    pub end: f64,
+    /// Curvature at the apex in 1/m, positive in left turns
+    pub curvature: f64,
+}
+
+/// Snapshot marking an event in a corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CornerPoint {
+    /// Time of the snapshot in seconds
+    pub time: f64,
+    pub x: f64,
+    pub y: f64,
+    /// Distance along the center line in meters
+    pub distance: f64,
+    /// Estimated speed in m/s
+    pub speed: f64,
+}
+
+/// One pass through a corner
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct CornerPass {
+    /// Index of the corner in `LapAnalysis::corners`
+    pub corner: usize,
+    pub apex: CornerPoint,
+    pub min_speed: CornerPoint,
+    /// Start of the braking before the minimum speed, `None` if the vehicle did not brake
+    pub braking: Option<CornerPoint>,
+}
+
+/// Corner passes of one lap
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapCorners {
+    /// Lap number, starting at 0 for the snapshots before the first start line crossing
+    pub lap: usize,
+    /// Time the lap started in seconds: the start line crossing, or the first snapshot
+    /// of lap 0
+    pub start_time: f64,
+    /// Time the lap ended in seconds: the next start line crossing for a complete lap,
+    /// the last snapshot otherwise
+    pub end_time: f64,
+    /// Whether the lap was driven from start line to start line
+    pub complete: bool,
+    /// Passes through the corners reached during the lap, in track order
+    pub passes: Vec<CornerPass>,
+}
+
+/// Corners of a track and how a run drove through them, lap by lap
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapAnalysis {
+    /// Length of the center line in meters
+    pub lap_length: f64,
+    /// Corners in the order of their apexes along the lap
+    pub corners: Vec<Corner>,
+    pub laps: Vec<LapCorners>,
+}
+
+impl LapAnalysis {
+    /// Every corner pass of the run, in time order
+    pub fn passes(&self) -> impl Iterator<Item = &CornerPass> {
+        self.laps.iter().flat_map(|lap| lap.passes.iter())
+    }
+}
+
+impl fmt::Display for LapAnalysis {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "{} corners on a {:.1} m lap", self.corners.len(), self.lap_length)?;
+        for lap in &self.laps {
+            write!(
+                f,
+                "\nLap {} ({:.2} s - {:.2} s{})",
+                lap.lap,
+                lap.start_time,
+                lap.end_time,
+                if lap.complete { "" } else { ", partial" }
+            )?;
+            for pass in &lap.passes {
+                let corner = &self.corners[pass.corner];
+                write!(
+                    f,
+                    "\n  Corner {} ({}, radius {:.1} m): apex {:.1} m at {:.2} m/s, \
+                     minimum {:.2} m/s at {:.1} m",
+                    pass.corner + 1,
+                    if corner.curvature > 0.0 { "left" } else { "right" },
+                    1.0 / corner.curvature.abs(),
+                    pass.apex.distance,
+                    pass.apex.speed,
+                    pass.min_speed.speed,
+                    pass.min_speed.distance
+                )?;
+                if let Some(braking) = pass.braking {
+                    write!(
+                        f,
+                        ", braking from {:.2} m/s at {:.1} m",
+                        braking.speed, braking.distance
+                    )?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Find the corners of a track
+///
+/// A corner is a run of center line points turning in the same direction with a
+/// curvature of at least `corner_curvature` (1/m). A track curving everywhere (e.g., a
+/// circle) is a single corner covering the whole lap.
+///
+/// # Returns
+/// Corners sorted by the distance of their apex from the start position
+pub fn detect_corners(track: &dyn Track, corner_curvature: f64) -> Vec<Corner> {
+    let center_line = ArcLengthTable::from_track(track);
+    let n = center_line.len();
+    let total = center_line.total_length();
+    if n < 3 || total <= 0.0 {
+        return Vec::new();
+    }
+    let start = track.get_start_position();
+    let origin = center_line.project(start.0, start.1).unwrap_or(0.0);
+    let relative = |s: f64| (s - origin).rem_euclid(total);
+
+    let curvature = center_line.curvatures();
+    let turn: Vec<i8> = curvature
+        .iter()
+        .map(|&k| {
+            if k >= corner_curvature {
+                1
+            } else if k <= -corner_curvature {
+                -1
+            } else {
+                0
+            }
+        })
+        .collect();
+    let peak = |points: &mut dyn Iterator<Item = usize>| {
+        points
+            .max_by(|&a, &b| curvature[a].abs().total_cmp(&curvature[b].abs()))
+            .unwrap_or(0)
+    };
+
+    if turn.iter().all(|&t| t != 0 && t == turn[0]) {
+        let apex = peak(&mut (0..n));
+        return vec![Corner {
+            start: 0.0,
+            apex: relative(center_line.distance_at(apex)),
+            end: total,
+            curvature: curvature[apex],
+        }];
+    }
+
+    // Start scanning where a run begins, so no corner is split at index 0
+    let Some(first) = (0..n).find(|&j| turn[j] != turn[(j + n - 1) % n]) else {
+        return Vec::new();
+    };
+    let mut corners = Vec::new();
+    let mut offset = 0;
+    while offset < n {
+        let j = (first + offset) % n;
+        let length = (offset..n)
+            .take_while(|&k| turn[(first + k) % n] == turn[j])
+            .count();
+        if turn[j] != 0 {
+            let last = (j + length - 1) % n;
+            let apex = peak(&mut (0..length).map(|k| (j + k) % n));
+            corners.push(Corner {
+                start: relative(center_line.distance_at(j)),
+                apex: relative(center_line.distance_at(apex)),
+                end: relative(center_line.distance_at(last)),
+                curvature: curvature[apex],
+            });
+        }
+        offset += length;
+    }
+    corners.sort_by(|a, b| a.apex.total_cmp(&b.apex));
+    corners
+}
+
+/// Find the corners of `track` and analyze every pass of `result` through them
+///
+/// # Arguments
+/// * `track` - Track the run was recorded on
+/// * `result` - Recorded run
+/// * `options` - Corner and braking thresholds
+///
+/// # Returns
+/// The corners and the passes of each lap, or `Error::InvalidConfig` if the track has no
+/// center line
+pub fn analyze_laps<S: Pose>(
+    track: &dyn Track,
+    result: &SimulationResult<S>,
+    options: &AnalysisOptions,
+) -> Result<LapAnalysis> {
+    let center_line = ArcLengthTable::from_track(track);
+    let total = center_line.total_length();
+    if center_line.len() < 3 || total <= 0.0 {
+        return Err(Error::invalid_config(format!(
+            "track '{}' has no center line to analyze",
+            track.get_track_name()
+        )));
+    }
+    let start = track.get_start_position();
+    let origin = center_line.project(start.0, start.1).unwrap_or(0.0);
+    let corners = detect_corners(track, options.corner_curvature);
+
+    let snapshots = result.snapshots();
+    let positions: Vec<Vec2> = snapshots
+        .iter()
+        .map(|snapshot| Vec2::new(snapshot.state.x(), snapshot.state.y()))
+        .collect();
+    let times: Vec<f64> = result.times().collect();
+    let speeds = estimate_speeds(&positions, &times);
+
+    let progress = lap_progress(&center_line, origin, &positions);
+    let crossings = start_line_crossings(track, result);
+    // Crossing within the step ending at snapshot `i`, falling back to the snapshot time
+    let lap_start = |i: usize| {
+        i.checked_sub(1)
+            .and_then(|before| {
+                crossings
+                    .iter()
+                    .copied()
+                    .find(|&time| time > times[before] && time <= times[i])
+            })
+            .unwrap_or(times[i])
+    };
+
+    let lap_of = |value: f64| (value / total).floor().max(0.0) as usize;
+    let point = |i: usize| CornerPoint {
+        time: times[i],
+        x: positions[i].x,
+        y: positions[i].y,
+        distance: progress[i].rem_euclid(total),
+        speed: speeds[i],
+    };
+    let earliest = progress.iter().copied().fold(f64::INFINITY, f64::min);
+    let furthest = progress.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+    let mut laps: Vec<LapCorners> = (0..=lap_of(furthest))
+        .filter_map(|lap| {
+            let mut indices = (0..progress.len()).filter(|&i| lap_of(progress[i]) == lap);
+            let first = indices.next()?;
+            let last = indices.next_back().unwrap_or(first);
+            Some(LapCorners {
+                lap,
+                start_time: if lap > 0 { lap_start(first) } else { times[first] },
+                end_time: times[last],
+                complete: (lap > 0 || progress[first] <= START_LINE_TOLERANCE)
+                    && last + 1 < progress.len(),
+                passes: Vec::new(),
+            })
+        })
+        .collect();
+    for index in 1..laps.len() {
+        if laps[index - 1].complete && laps[index].lap == laps[index - 1].lap + 1 {
+            laps[index - 1].end_time = laps[index].start_time;
+        }
+    }
+
+    // Corner ends along the unwrapped progress, each zone running from the previous end
+    let corner_end = |corner: &Corner, lap: usize| {
+        let end = if corner.end < corner.apex { corner.end + total } else { corner.end };
+        lap as f64 * total + end
+    };
+    for lap in laps.iter_mut() {
+        for (corner_index, corner) in corners.iter().enumerate() {
+            let apex = lap.lap as f64 * total + corner.apex;
+            if apex < earliest || apex > furthest {
+                continue;
+            }
+            let zone_end = corner_end(corner, lap.lap);
+            let zone_start = match corner_index.checked_sub(1) {
+                Some(previous) => corner_end(&corners[previous], lap.lap),
+                None => corner_end(&corners[corners.len() - 1], lap.lap) - total,
+            };
+            let zone: Vec<usize> = (0..progress.len())
+                .filter(|&i| progress[i] > zone_start && progress[i] <= zone_end)
+                .collect();
+            let (Some(&apex_index), Some(&slowest)) = (
+                zone.iter().min_by(|&&a, &&b| {
+                    (progress[a] - apex).abs().total_cmp(&(progress[b] - apex).abs())
+                }),
+                zone.iter().min_by(|&&a, &&b| speeds[a].total_cmp(&speeds[b])),
+            ) else {
+                continue;
+            };
+
+            // Walk back over the stretch held at the minimum speed, then over the braking
+            let deceleration = |i: usize| {
+                let dt = times[i] - times[i - 1];
+                if dt > 0.0 { (speeds[i - 1] - speeds[i]) / dt } else { 0.0 }
+            };
+            let extends = |i: usize| i > 0 && zone.contains(&(i - 1));
+            let mut braking = slowest;
+            while extends(braking) && deceleration(braking).abs() < options.braking_deceleration {
+                braking -= 1;
+            }
+            let held_from = braking;
+            while extends(braking) && deceleration(braking) >= options.braking_deceleration {
+                braking -= 1;
+            }
+            lap.passes.push(CornerPass {
+                corner: corner_index,
+                apex: point(apex_index),
+                min_speed: point(slowest),
+                braking: (braking < held_from).then(|| point(braking)),
+            });
+        }
+    }
+
+    Ok(LapAnalysis {
+        lap_length: total,
+        corners,
+        laps,
+    })
+}
+
+/// Times in seconds at which a run crosses the start line of `track`
+///
+/// Each crossing is interpolated within the step between the two snapshots around it,
+/// so the times do not snap to multiples of the time step. Only crossings in the
+/// direction of the start yaw and between the track boundaries count.
+pub fn start_line_crossings<S: Pose>(track: &dyn Track, result: &SimulationResult<S>) -> Vec<f64> {
+    let line = TimingLine::start_line(track);
+    result
+        .snapshots()
+        .windows(2)
+        .filter_map(|pair| {
+            let position = |index: usize| Vec2::new(pair[index].state.x(), pair[index].state.y());
+            line.crossing_time(pair[0].time, position(0), pair[1].time, position(1))
+        })
+        .collect()
+}
+
+/// Times in seconds of the laps between consecutive start line crossings of a run
+pub fn lap_times<S: Pose>(track: &dyn Track, result: &SimulationResult<S>) -> Vec<f64> {
+    start_line_crossings(track, result)
+        .windows(2)
+        .map(|pair| pair[1] - pair[0])
+        .collect()
+}
+
+/// Distance of each position along the center line from `origin`, unwrapped across laps
+///
+/// The first position is mapped onto [0, lap length); consecutive positions are
+/// assumed to be less than half a lap apart.
+pub(crate) fn lap_progress(center_line: &ArcLengthTable, origin: f64, positions: &[Vec2]) -> Vec<f64> {
+    let total = center_line.total_length();
+    let mut progress: Vec<f64> = Vec::with_capacity(positions.len());
+    let mut previous: Option<f64> = None;
+    for position in positions {
+        let s = center_line.project(position.x, position.y).unwrap_or(origin);
+        let value = match (previous, progress.last()) {
+            (Some(previous), Some(&last)) => {
+                last + (s - previous + total / 2.0).rem_euclid(total) - total / 2.0
+            }
+            _ => (s - origin).rem_euclid(total),
+        };
+        progress.push(value);
+        previous = Some(s);
+    }
+    progress
+}
+
+/// Speed at each position from the path through the neighbouring positions
+pub(crate) fn estimate_speeds(positions: &[Vec2], times: &[f64]) -> Vec<f64> {
+    let n = positions.len();
+    (0..n)
+        .map(|i| {
+            let (before, after) = (i.saturating_sub(1), (i + 1).min(n - 1));
+            let dt = times[after] - times[before];
+            if dt > 0.0 {
+                let path = positions[before].distance(positions[i])
+                    + positions[i].distance(positions[after]);
+                path / dt
+            } else {
+                0.0
+            }
+        })
+        .collect()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{analyze_laps, detect_corners, lap_times, start_line_crossings, AnalysisOptions};
+    use crate::simulation::result::{SimulationResult, StateSnapshot};
+    use crate::tracks::arc_length::ArcLengthTable;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+
+    /// Drive the square's center line, braking from 20 m/s to 5 m/s before each corner
+    fn square_laps(laps: f64) -> (SquareTrack, SimulationResult<(f64, f64, f64)>) {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let center_line = ArcLengthTable::from_track(&track);
+        let speed = |s: f64| {
+            // Distance to the next corner; corners are every 100 m, starting at s = 0
+            let to_corner = 100.0 - s.rem_euclid(100.0);
+            if to_corner < 30.0 { 5.0 + 0.5 * to_corner } else { 20.0 }
+        };
+
+        let mut result = SimulationResult::new();
+        let (dt, mut s, mut time) = (0.05, 0.0, 0.0);
+        while s < laps * 400.0 {
+            let (x, y, yaw) = center_line.point_at(s).expect("point");
+            result.push(time, (x, y, yaw), true);
+            s += speed(s) * dt;
+            time += dt;
+        }
+        (track, result)
+    }
+
+    #[test]
+    fn test_detect_square_corners() {
+        let corners = detect_corners(&SquareTrack::new(100.0, 10.0, 25), 0.01);
+        assert_eq!(corners.len(), 4);
+        for (index, corner) in corners.iter().enumerate() {
+            assert!((corner.apex - 100.0 * index as f64).abs() < 1e-6, "{corner:?}");
+            assert!(corner.curvature > 0.0);
+        }
+
+        let circle = detect_corners(&CircleTrack::new(50.0, 10.0, 100), 0.01);
+        assert_eq!(circle.len(), 1);
+        assert!((circle[0].curvature - 0.02).abs() < 1e-3);
+        assert!(detect_corners(&CircleTrack::new(50.0, 10.0, 100), 0.05).is_empty());
+    }
+
+    #[test]
+    fn test_analyze_square_laps() {
+        let (track, result) = square_laps(2.5);
+        let analysis =
+            analyze_laps(&track, &result, &AnalysisOptions::default()).expect("analysis");
+
+        assert_eq!(analysis.laps.len(), 3);
+        assert!(analysis.laps[0].complete && analysis.laps[1].complete);
+        assert!(!analysis.laps[2].complete);
+
+        // Corner 1 is at the start position, its pass is reported before the crossing
+        let along = |distance: f64, apex: f64| {
+            let offset = (distance - apex).rem_euclid(400.0);
+            if offset > 200.0 { offset - 400.0 } else { offset }
+        };
+        let lap = &analysis.laps[1];
+        assert_eq!(lap.passes.len(), 4);
+        for pass in &lap.passes {
+            let apex = analysis.corners[pass.corner].apex;
+            assert!(along(pass.apex.distance, apex).abs() < 1.0, "{pass:?}");
+            assert!(along(pass.min_speed.distance, apex).abs() < 1.0, "{pass:?}");
+            assert!(pass.min_speed.speed < 6.0, "{pass:?}");
+            // Braking starts 30 m before the corner
+            let braking = pass.braking.expect("braking point");
+            assert!((along(braking.distance, apex) + 30.0).abs() < 2.0, "{braking:?}");
+            assert!(braking.speed > 19.0);
+        }
+        assert!(analysis.to_string().contains("Lap 2"));
+        assert!(analysis.to_string().contains("braking from"));
+    }
+
+    #[test]
+    fn test_analyze_constant_speed_without_braking() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let center_line = ArcLengthTable::from_track(&track);
+        let result: SimulationResult<(f64, f64, f64)> = (0..200)
+            .map(|i| {
+                let pose = center_line.point_at(i as f64 * 2.0).expect("point");
+                StateSnapshot {
+                    time: i as f64 * 0.2,
+                    state: pose,
+                    in_track: true,
+                }
+            })
+            .collect();
+        let analysis =
+            analyze_laps(&track, &result, &AnalysisOptions::default()).expect("analysis");
+
+        assert_eq!(analysis.corners.len(), 1);
+        assert!(analysis.passes().all(|pass| pass.braking.is_none()));
+        assert!(analysis.passes().all(|pass| (pass.min_speed.speed - 10.0).abs() < 0.1));
+    }
+
+    #[test]
+    fn test_start_line_crossings_are_interpolated() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let center_line = ArcLengthTable::from_track(&track);
+        // 10 m/s along the center line, 2.5 laps with 0.3 s steps
+        let lap_time = center_line.total_length() / 10.0;
+        let result: SimulationResult<(f64, f64, f64)> = (0..)
+            .map(|i| i as f64 * 0.3)
+            .take_while(|&time| time < 2.5 * lap_time)
+            .map(|time| StateSnapshot {
+                time,
+                state: center_line.point_at(time * 10.0).expect("point"),
+                in_track: true,
+            })
+            .collect();
+
+        // Within 1 ms, against up to 0.3 s for the nearest snapshot; the step cuts the
+        // corner of the center line polygon at the start
+        let crossings = start_line_crossings(&track, &result);
+        assert_eq!(crossings.len(), 2);
+        assert!((crossings[0] - lap_time).abs() < 1e-3, "{crossings:?}");
+        assert!((crossings[1] - 2.0 * lap_time).abs() < 1e-3, "{crossings:?}");
+        let laps = lap_times(&track, &result);
+        assert!(laps.len() == 1 && (laps[0] - lap_time).abs() < 1e-3, "{laps:?}");
+
+        let analysis =
+            analyze_laps(&track, &result, &AnalysisOptions::default()).expect("analysis");
+        assert_eq!(analysis.laps[1].start_time, crossings[0]);
+        assert_eq!(analysis.laps[0].end_time, crossings[0]);
+        assert_eq!(analysis.laps[1].end_time, crossings[1]);
+    }
+}
@@ -1,2 +1,19 @@
 pub mod base_simulation;
+pub mod batch;
+pub mod cancel;
+pub mod columnar;
+pub mod comparison;
+pub mod footprint;
+pub mod ghost;
+pub mod lap_analysis;
+pub mod monte_carlo;
+pub mod multi_vehicle;
 pub mod open_loop;
+pub mod performance;
+pub mod profile;
+pub mod result;
+pub mod spill;
+pub mod stop;
+#[cfg(feature = "async")]
+pub mod tasks;
+pub mod weather;
@@ -0,0 +1,508 @@
+//! Monte Carlo robustness runs: many closed-loop runs under random perturbations.
+//!
+//! Every run starts from the track's start position moved by a random initial
+//! perturbation (lateral offset, heading error, speed change) and drives with random
+//! process noise added to the controller's commands at every step. Each run draws
+//! from its own `SplitMix64` generator, seeded from the Monte Carlo seed and the run
+//! index, so a seed reproduces the same runs on any platform and number of threads.
+//! Runs are summarized with the metrics of parameter sweeps (`batch::SweepMetrics`)
+//! and aggregated into statistics over all runs.
+
+use crate::controllers::base_controller::{Controller, ControllerTelemetry};
+use crate::error::Error;
+use crate::math::random::SplitMix64;
+use crate::models::base_model::Model;
+use crate::models::point_mass::{PointMass, PointMassState};
+use crate::simulation::batch::{run_parallel, validate_run, SweepMetrics};
+use crate::simulation::multi_vehicle::MultiVehicleSimulation;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::arc_length::TrackPosition;
+use crate::tracks::base_track::SharedTrack;
+use std::fmt;
+use std::thread;
+
+/// Time step in seconds of the Monte Carlo runs when none is configured
+pub const DEFAULT_MONTE_CARLO_DT: f64 = 0.05;
+
+/// Duration in seconds of the Monte Carlo runs when none is configured
+pub const DEFAULT_MONTE_CARLO_DURATION: f64 = 60.0;
+
+/// Distribution of a random perturbation
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub enum Distribution {
+    /// Always the same value
+    Constant(f64),
+    /// Uniform between `min` and `max`
+    Uniform { min: f64, max: f64 },
+    /// Normal with `mean` and standard deviation `std_dev`
+    Normal { mean: f64, std_dev: f64 },
+}
+
+impl Distribution {
+    /// Zero-mean normal distribution with standard deviation `std_dev`
+    pub fn normal(std_dev: f64) -> Self {
+        Distribution::Normal { mean: 0.0, std_dev }
+    }
+
+    /// Draw a value from `rng`
+    pub fn sample(&self, rng: &mut SplitMix64) -> f64 {
+        match *self {
+            Distribution::Constant(value) => value,
+            Distribution::Uniform { min, max } => rng.range(min, max),
+            Distribution::Normal { mean, std_dev } => mean + std_dev * rng.gaussian(),
+        }
+    }
+
+    /// Check the parameters of the distribution
+    ///
+    /// # Returns
+    /// A description of the problem for non-finite parameters, `min > max`, or a
+    /// negative standard deviation
+    pub fn validate(&self) -> Result<(), String> {
+        match *self {
+            Distribution::Constant(value) if !value.is_finite() => {
+                Err(format!("constant must be a finite number, got {}", value))
+            }
+            Distribution::Uniform { min, max }
+                if !(min.is_finite() && max.is_finite() && min <= max) =>
+            {
+                Err(format!("uniform range must be finite with min <= max, got {}..{}", min, max))
+            }
+            Distribution::Normal { mean, std_dev }
+                if !(mean.is_finite() && std_dev.is_finite() && std_dev >= 0.0) =>
+            {
+                Err(format!(
+                    "normal distribution needs a finite mean and a non-negative standard deviation, got {} and {}",
+                    mean, std_dev
+                ))
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+impl Default for Distribution {
+    fn default() -> Self {
+        Distribution::Constant(0.0)
+    }
+}
+
+/// Distributions of the changes applied to the start position and speed of every run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct InitialPerturbation {
+    /// Offset in meters to the left of the start heading
+    pub lateral: Distribution,
+    /// Heading error in radians
+    pub heading: Distribution,
+    /// Change in m/s of the model's initial speed
+    pub speed: Distribution,
+}
+
+/// Distributions of the noise added to the controller's commands at every step
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct ProcessNoise {
+    /// Acceleration noise in m/s²
+    pub ax: Distribution,
+    /// Yaw rate noise in radians/s
+    pub yaw_rate: Distribution,
+}
+
+/// Initial perturbation drawn for one run
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct InitialOffset {
+    /// Offset in meters to the left of the start heading
+    pub lateral: f64,
+    /// Heading error in radians
+    pub heading: f64,
+    /// Change of the initial speed in m/s
+    pub speed: f64,
+}
+
+/// One perturbed run
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarloRun {
+    /// Seed of the run's random generator
+    pub seed: u64,
+    pub initial: InitialOffset,
+    pub result: SimulationResult<PointMassState>,
+    pub metrics: SweepMetrics,
+}
+
+/// Mean, sample standard deviation, and range of a metric over the runs
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Statistics {
+    pub mean: f64,
+    pub std_dev: f64,
+    pub min: f64,
+    pub max: f64,
+}
+
+impl Statistics {
+    /// Statistics of `values`, `None` if there are none
+    pub fn from_values(values: &[f64]) -> Option<Self> {
+        if values.is_empty() {
+            return None;
+        }
+        let count = values.len() as f64;
+        let mean = values.iter().sum::<f64>() / count;
+        let variance = if values.len() > 1 {
+            values.iter().map(|value| (value - mean).powi(2)).sum::<f64>() / (count - 1.0)
+        } else {
+            0.0
+        };
+        Some(Self {
+            mean,
+            std_dev: variance.sqrt(),
+            min: values.iter().copied().fold(f64::INFINITY, f64::min),
+            max: values.iter().copied().fold(f64::NEG_INFINITY, f64::max),
+        })
+    }
+}
+
+impl fmt::Display for Statistics {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{:.3} ± {:.3} (min {:.3}, max {:.3})",
+            self.mean, self.std_dev, self.min, self.max
+        )
+    }
+}
+
+/// Aggregate metrics of a Monte Carlo study
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct MonteCarloSummary {
+    pub runs: usize,
+    /// Number of runs that completed at least one lap
+    pub completed_laps: usize,
+    /// Number of runs that left the track at least once
+    pub off_track_runs: usize,
+    /// Fastest lap of each run that completed one, in seconds
+    pub lap_time: Option<Statistics>,
+    /// Largest distance from the center line of each run, in meters
+    pub max_cross_track_error: Option<Statistics>,
+}
+
+impl MonteCarloSummary {
+    /// Aggregate the metrics of `runs`
+    pub fn from_runs(runs: &[MonteCarloRun]) -> Self {
+        let lap_times: Vec<f64> = runs.iter().filter_map(|run| run.metrics.lap_time).collect();
+        let errors: Vec<f64> = runs
+            .iter()
+            .map(|run| run.metrics.max_cross_track_error)
+            .collect();
+        Self {
+            runs: runs.len(),
+            completed_laps: lap_times.len(),
+            off_track_runs: runs
+                .iter()
+                .filter(|run| run.metrics.off_track_count > 0)
+                .count(),
+            lap_time: Statistics::from_values(&lap_times),
+            max_cross_track_error: Statistics::from_values(&errors),
+        }
+    }
+
+    /// Fraction of the runs that stayed on the track, 1 without runs
+    pub fn success_rate(&self) -> f64 {
+        if self.runs == 0 {
+            return 1.0;
+        }
+        (self.runs - self.off_track_runs) as f64 / self.runs as f64
+    }
+}
+
+impl fmt::Display for MonteCarloSummary {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} runs, {} with a full lap, {} off track ({:.1}% on track)",
+            self.runs,
+            self.completed_laps,
+            self.off_track_runs,
+            100.0 * self.success_rate()
+        )?;
+        if let Some(lap_time) = &self.lap_time {
+            write!(f, "\nLap time (s): {}", lap_time)?;
+        }
+        if let Some(error) = &self.max_cross_track_error {
+            write!(f, "\nMax cross-track error (m): {}", error)?;
+        }
+        Ok(())
+    }
+}
+
+/// Runs of a Monte Carlo study and their aggregate statistics
+#[derive(Debug, Clone, PartialEq)]
+pub struct MonteCarloResult {
+    /// Runs in index order
+    pub runs: Vec<MonteCarloRun>,
+    pub summary: MonteCarloSummary,
+}
+
+/// Controller adding process noise to the commands of another controller
+struct NoisyController<C> {
+    controller: C,
+    noise: ProcessNoise,
+    rng: SplitMix64,
+}
+
+impl<C> NoisyController<C> {
+    fn perturb(&mut self, (ax, yaw_rate): (f64, f64)) -> (f64, f64) {
+        (
+            ax + self.noise.ax.sample(&mut self.rng),
+            yaw_rate + self.noise.yaw_rate.sample(&mut self.rng),
+        )
+    }
+}
+
+impl<C: Controller<PointMassState>> Controller<PointMassState> for NoisyController<C> {
+    fn step(&mut self, time: f64, state: &PointMassState) -> (f64, f64) {
+        let controls = self.controller.step(time, state);
+        self.perturb(controls)
+    }
+
+    fn step_on_track(
+        &mut self,
+        time: f64,
+        state: &PointMassState,
+        position: Option<&TrackPosition>,
+    ) -> (f64, f64) {
+        let controls = self.controller.step_on_track(time, state, position);
+        self.perturb(controls)
+    }
+
+    fn set_friction_limit(&mut self, limit: Option<f64>) {
+        self.controller.set_friction_limit(limit);
+    }
+
+    fn telemetry(&self) -> Option<ControllerTelemetry> {
+        self.controller.telemetry()
+    }
+
+    fn reset(&mut self) {
+        self.controller.reset();
+    }
+}
+
+/// Configures and runs a Monte Carlo study on one track
+pub struct MonteCarlo {
+    track: SharedTrack,
+    runs: usize,
+    seed: u64,
+    initial: InitialPerturbation,
+    noise: ProcessNoise,
+    dt: f64,
+    duration: f64,
+    threads: usize,
+}
+
+impl MonteCarlo {
+    /// Create a study of `runs` runs on `track` without perturbations, running on all
+    /// available cores
+    pub fn new(track: SharedTrack, runs: usize) -> Self {
+        Self {
+            track,
+            runs,
+            seed: 0,
+            initial: InitialPerturbation::default(),
+            noise: ProcessNoise::default(),
+            dt: DEFAULT_MONTE_CARLO_DT,
+            duration: DEFAULT_MONTE_CARLO_DURATION,
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+        }
+    }
+
+    /// Seed of the random generators (0 if not set)
+    pub fn seed(mut self, seed: u64) -> Self {
+        self.seed = seed;
+        self
+    }
+
+    /// Perturb the start position and speed of every run
+    pub fn initial_perturbation(mut self, initial: InitialPerturbation) -> Self {
+        self.initial = initial;
+        self
+    }
+
+    /// Add noise to the controller's commands at every step
+    pub fn process_noise(mut self, noise: ProcessNoise) -> Self {
+        self.noise = noise;
+        self
+    }
+
+    /// Time step in seconds of every run
+    pub fn dt(mut self, dt: f64) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Duration in seconds of every run
+    pub fn duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Limit the number of worker threads (at least 1; 1 runs on the calling thread)
+    pub fn threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    /// Run every perturbed run and aggregate the metrics
+    ///
+    /// `build` is called with the run index on the calling thread and returns the
+    /// nominal model and the controller driving it; the model is placed on the start
+    /// position of the track, then perturbed.
+    ///
+    /// # Returns
+    /// The runs and their statistics, the first error of `build`, or
+    /// `Error::InvalidConfig` for an invalid distribution, time step, or duration
+    pub fn run<C, F>(&self, mut build: F) -> crate::Result<MonteCarloResult>
+    where
+        C: Controller<PointMassState> + Send + 'static,
+        F: FnMut(usize) -> crate::Result<(PointMass, C)>,
+    {
+        validate_run(self.dt, self.duration)?;
+        for distribution in [
+            self.initial.lateral,
+            self.initial.heading,
+            self.initial.speed,
+            self.noise.ax,
+            self.noise.yaw_rate,
+        ] {
+            distribution.validate().map_err(Error::InvalidConfig)?;
+        }
+
+        let (x, y, yaw) = self.track.get_start_position();
+        let mut seeds = SplitMix64::new(self.seed);
+        let mut simulations = Vec::with_capacity(self.runs);
+        let mut setups = Vec::with_capacity(self.runs);
+        for index in 0..self.runs {
+            let seed = seeds.next_u64();
+            let mut rng = SplitMix64::new(seed);
+            let initial = InitialOffset {
+                lateral: self.initial.lateral.sample(&mut rng),
+                heading: self.initial.heading.sample(&mut rng),
+                speed: self.initial.speed.sample(&mut rng),
+            };
+            let (mut model, controller) = build(index)?;
+            let speed = model.get_velocity().0;
+            model.set_position(
+                x - initial.lateral * yaw.sin(),
+                y + initial.lateral * yaw.cos(),
+                yaw + initial.heading,
+            );
+            model.set_speed(speed + initial.speed);
+
+            let mut simulation = MultiVehicleSimulation::new(self.track.clone()).with_threads(1);
+            simulation.add_controlled_vehicle(
+                model,
+                NoisyController {
+                    controller,
+                    noise: self.noise,
+                    rng,
+                },
+            );
+            simulations.push(simulation);
+            setups.push((seed, initial));
+        }
+
+        let results = run_parallel(&mut simulations, self.dt, self.duration, self.threads)?;
+        let runs: Vec<MonteCarloRun> = setups
+            .into_iter()
+            .zip(results)
+            .map(|((seed, initial), result)| MonteCarloRun {
+                seed,
+                initial,
+                metrics: SweepMetrics::from_run(self.track.as_ref(), &result),
+                result,
+            })
+            .collect();
+        Ok(MonteCarloResult {
+            summary: MonteCarloSummary::from_runs(&runs),
+            runs,
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Distribution, InitialPerturbation, MonteCarlo, ProcessNoise, Statistics};
+    use crate::controllers::stanley::StanleyController;
+    use crate::models::point_mass::PointMass;
+    use crate::math::random::SplitMix64;
+    use crate::tracks::base_track::SharedTrack;
+    use crate::tracks::circle::CircleTrack;
+    use std::sync::Arc;
+
+    #[test]
+    fn test_distribution_samples() {
+        let mut rng = SplitMix64::new(3);
+        assert_eq!(Distribution::Constant(2.0).sample(&mut rng), 2.0);
+        let uniform = Distribution::Uniform { min: -1.0, max: 1.0 };
+        assert!((0..100).all(|_| (-1.0..1.0).contains(&uniform.sample(&mut rng))));
+
+        let samples: Vec<f64> = (0..2000)
+            .map(|_| Distribution::Normal { mean: 5.0, std_dev: 2.0 }.sample(&mut rng))
+            .collect();
+        let statistics = Statistics::from_values(&samples).expect("statistics");
+        assert!((statistics.mean - 5.0).abs() < 0.2, "{statistics}");
+        assert!((statistics.std_dev - 2.0).abs() < 0.2, "{statistics}");
+
+        assert!(Distribution::Uniform { min: 1.0, max: 0.0 }.validate().is_err());
+        assert!(Distribution::normal(-1.0).validate().is_err());
+        assert!(Statistics::from_values(&[]).is_none());
+    }
+
+    #[test]
+    fn test_monte_carlo_is_reproducible() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let study = |threads: usize, seed: u64| {
+            MonteCarlo::new(Arc::clone(&track), 6)
+                .seed(seed)
+                .initial_perturbation(InitialPerturbation {
+                    lateral: Distribution::Uniform { min: -1.5, max: 1.5 },
+                    heading: Distribution::normal(0.05),
+                    ..InitialPerturbation::default()
+                })
+                .process_noise(ProcessNoise {
+                    yaw_rate: Distribution::normal(0.02),
+                    ..ProcessNoise::default()
+                })
+                .duration(50.0)
+                .threads(threads)
+                .run(|_| {
+                    let controller = StanleyController::new(track.as_ref(), 2.0, 1.0, 10.0);
+                    Ok((PointMass::new(), controller))
+                })
+                .expect("monte carlo")
+        };
+        let result = study(3, 7);
+        assert_eq!(result, study(1, 7));
+        assert_ne!(result.runs, study(1, 8).runs);
+
+        let summary = &result.summary;
+        assert_eq!(summary.runs, 6);
+        assert_eq!(summary.completed_laps, 6, "{summary}");
+        assert_eq!(summary.success_rate(), 1.0, "{summary}");
+        let lap_time = summary.lap_time.expect("lap time statistics");
+        assert!(lap_time.std_dev > 0.0 && lap_time.max - lap_time.min < 2.0, "{summary}");
+        let (x, y, _) = track.get_start_position();
+        for run in &result.runs {
+            assert!(run.initial.lateral.abs() <= 1.5);
+            let start = &run.result.first().expect("start").state;
+            let offset = (start.x - x).hypot(start.y - y);
+            assert!((offset - run.initial.lateral.abs()).abs() < 1e-9, "{:?}", run.initial);
+        }
+
+        let err = MonteCarlo::new(Arc::clone(&track), 2)
+            .initial_perturbation(InitialPerturbation {
+                lateral: Distribution::Uniform { min: 1.0, max: -1.0 },
+                ..InitialPerturbation::default()
+            })
+            .run(|_| Ok((PointMass::new(), StanleyController::new(track.as_ref(), 2.0, 1.0, 10.0))))
+            .expect_err("invalid distribution");
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+    }
+}
@@ -0,0 +1,714 @@
+//! Several point masses driving on one track at the same time.
+//!
+//! Vehicles do not influence each other's dynamics, so they are advanced in parallel
+//! for the whole run: each worker thread is spawned once per run, owns a contiguous
+//! slice of the vehicles, and runs their controllers, models, and track checks through
+//! every tick. Interactions (collisions and gaps to the vehicle ahead) are then
+//! evaluated tick by tick on the calling thread in vehicle order, so results are
+//! identical for any number of threads.
+
+use crate::controllers::base_controller::{Controller, ControllerTelemetry};
+use crate::error::{Error, Result};
+use crate::math::vec2::Vec2;
+use crate::models::base_model::Model;
+use crate::models::point_mass::{PointMass, PointMassState};
+use crate::simulation::batch::validate_run;
+use crate::simulation::open_loop::is_finite;
+use crate::simulation::result::{step_plan, SimulationResult};
+use crate::simulation::weather::{Weather, WeatherConditions};
+use crate::tracks::arc_length::{ArcLengthTable, TrackPosition};
+use crate::tracks::base_track::{SharedTrack, Track};
+use std::iter;
+use std::thread;
+
+/// Contact between two vehicles, recorded when their footprints start to overlap
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct Collision {
+    /// Time in seconds of the first tick with overlapping footprints
+    pub time: f64,
+    /// Index of the first vehicle (the lower one)
+    pub first: usize,
+    /// Index of the second vehicle
+    pub second: usize,
+}
+
+/// Recorded states and interactions of a multi-vehicle run
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct MultiVehicleResult {
+    /// States of each vehicle, in the order the vehicles were added
+    pub runs: Vec<SimulationResult<PointMassState>>,
+    /// Distance along the center line to the next vehicle ahead, per tick and vehicle
+    ///
+    /// Infinite when the vehicle is alone on the track.
+    pub gaps: Vec<Vec<f64>>,
+    /// Contacts between vehicles, in order of time, then vehicle indices
+    pub collisions: Vec<Collision>,
+    /// Weather conditions per tick
+    pub weather: Vec<WeatherConditions>,
+    /// Breakdown of the command each controller applied in the step leading to the
+    /// tick, per tick and vehicle
+    ///
+    /// `None` at the first tick and for vehicles whose controller reports none.
+    pub controller_telemetry: Vec<Vec<Option<ControllerTelemetry>>>,
+}
+
+/// Vehicle with its own controller and per-tick track status
+struct Vehicle {
+    model: PointMass,
+    controller: Option<Box<dyn Controller<PointMassState> + Send>>,
+    in_track: bool,
+    /// Position relative to the center line, `None` if the track has no center line
+    position: Option<TrackPosition>,
+}
+
+impl Vehicle {
+    /// Advance the vehicle by `dt` seconds and update its track status
+    fn step(
+        &mut self,
+        time: f64,
+        dt: f64,
+        conditions: &WeatherConditions,
+        track: &dyn Track,
+        arc_length: &ArcLengthTable,
+    ) {
+        self.model.set_surface_conditions(conditions.grip, conditions.drag);
+        if let Some(controller) = self.controller.as_mut() {
+            controller.set_friction_limit(self.model.friction_limit());
+            let (ax, yaw_rate) =
+                controller.step_on_track(time, self.model.get_state(), self.position.as_ref());
+            self.model.set_controls(ax, yaw_rate);
+        }
+        self.model.step(dt);
+        self.update_track_status(dt, track, arc_length);
+    }
+
+    fn update_track_status(&mut self, dt: f64, track: &dyn Track, arc_length: &ArcLengthTable) {
+        let state = self.model.get_state();
+        self.in_track = track.is_in_track(state.x, state.y);
+        // Search a window covering the distance driven since the previous tick
+        let window = PROGRESS_WINDOW + self.model.get_speed() * dt;
+        self.position = arc_length.position_near(state.x, state.y, self.progress(), window);
+    }
+
+    /// Distance along the center line in meters
+    fn progress(&self) -> f64 {
+        self.position.map_or(0.0, |position| position.s)
+    }
+
+    /// Drive through every step of `plan`, recording the vehicle after each one
+    ///
+    /// Stops at the first tick with a NaN or infinite state.
+    fn run(
+        &mut self,
+        start: &WeatherConditions,
+        plan: &[PlannedStep],
+        track: &dyn Track,
+        arc_length: &ArcLengthTable,
+    ) -> VehicleRun {
+        let mut run = VehicleRun {
+            states: SimulationResult::with_capacity(plan.len() + 1),
+            progress: Vec::with_capacity(plan.len() + 1),
+            telemetry: Vec::with_capacity(plan.len() + 1),
+            diverged: None,
+        };
+        run.record(start.time, self);
+        for planned in plan {
+            if run.diverged.is_some() {
+                break;
+            }
+            self.step(planned.start, planned.dt, &planned.conditions, track, arc_length);
+            run.record(planned.conditions.time, self);
+        }
+        run
+    }
+}
+
+/// One step of a run: start time, length, and weather at the tick ending it
+struct PlannedStep {
+    start: f64,
+    dt: f64,
+    conditions: WeatherConditions,
+}
+
+/// Records of one vehicle at every tick of a run
+struct VehicleRun {
+    states: SimulationResult<PointMassState>,
+    progress: Vec<f64>,
+    telemetry: Vec<Option<ControllerTelemetry>>,
+    /// Time of the first tick with a NaN or infinite state
+    diverged: Option<f64>,
+}
+
+impl VehicleRun {
+    fn record(&mut self, time: f64, vehicle: &Vehicle) {
+        self.states.push(time, vehicle.model.get_state().clone(), vehicle.in_track);
+        self.progress.push(vehicle.progress());
+        self.telemetry.push(
+            vehicle.controller.as_ref().and_then(|controller| controller.telemetry()),
+        );
+        if !is_finite(&vehicle.model) {
+            self.diverged = Some(time);
+        }
+    }
+}
+
+/// Half width in meters of the center line window searched for each vehicle's progress
+const PROGRESS_WINDOW: f64 = 10.0;
+
+/// Simulation of many independent point masses on a shared track
+pub struct MultiVehicleSimulation {
+    track: SharedTrack,
+    arc_length: ArcLengthTable,
+    vehicles: Vec<Vehicle>,
+    threads: usize,
+    weather: Weather,
+}
+
+impl MultiVehicleSimulation {
+    /// Create an empty simulation on `track`, stepping on all available cores
+    pub fn new(track: SharedTrack) -> Self {
+        let arc_length = ArcLengthTable::from_track(track.as_ref());
+        Self {
+            track,
+            arc_length,
+            vehicles: Vec::new(),
+            threads: thread::available_parallelism().map_or(1, |threads| threads.get()),
+            weather: Weather::dry(),
+        }
+    }
+
+    /// Limit the number of worker threads (at least 1; 1 steps on the calling thread)
+    pub fn with_threads(mut self, threads: usize) -> Self {
+        self.threads = threads.max(1);
+        self
+    }
+
+    pub fn threads(&self) -> usize {
+        self.threads
+    }
+
+    /// Drive in `weather` instead of the default dry weather
+    ///
+    /// The rain scales the friction and the drag of every vehicle; it has no effect on
+    /// vehicles without friction or drag (see `PointMass::set_friction`).
+    pub fn with_weather(mut self, weather: Weather) -> Self {
+        self.weather = weather;
+        self
+    }
+
+    pub fn weather(&self) -> &Weather {
+        &self.weather
+    }
+
+    pub fn len(&self) -> usize {
+        self.vehicles.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.vehicles.is_empty()
+    }
+
+    /// Add a vehicle driving with the constant controls already set on `model`
+    ///
+    /// # Returns
+    /// Index of the vehicle
+    pub fn add_vehicle(&mut self, model: PointMass) -> usize {
+        self.push_vehicle(model, None)
+    }
+
+    /// Add a vehicle whose controls are computed by `controller` every tick
+    ///
+    /// # Returns
+    /// Index of the vehicle
+    pub fn add_controlled_vehicle<C>(&mut self, model: PointMass, controller: C) -> usize
+    where
+        C: Controller<PointMassState> + Send + 'static,
+    {
+        self.push_vehicle(model, Some(Box::new(controller)))
+    }
+
+    fn push_vehicle(
+        &mut self,
+        model: PointMass,
+        controller: Option<Box<dyn Controller<PointMassState> + Send>>,
+    ) -> usize {
+        let state = model.get_state();
+        self.vehicles.push(Vehicle {
+            in_track: self.track.is_in_track(state.x, state.y),
+            position: self.arc_length.position(state.x, state.y),
+            model,
+            controller,
+        });
+        self.vehicles.len() - 1
+    }
+
+    /// Current model of the vehicle at `index`
+    pub fn model(&self, index: usize) -> Option<&PointMass> {
+        self.vehicles.get(index).map(|vehicle| &vehicle.model)
+    }
+
+    /// Run every vehicle from its current state for `duration` seconds
+    ///
+    /// Uses the same step plan as `OpenLoopSimulation`: full steps of `dt` and a final
+    /// partial step, with a snapshot of every vehicle after each step.
+    ///
+    /// # Returns
+    /// The recorded run, `Error::InvalidConfig` if `dt` is not a positive number or
+    /// `duration` is negative, or `Error::Diverged` once a vehicle state becomes NaN or
+    /// infinite
+    pub fn run(&mut self, dt: f64, duration: f64) -> Result<MultiVehicleResult> {
+        validate_run(dt, duration)?;
+        let (steps, remaining) = step_plan(dt, duration);
+        let mut plan: Vec<PlannedStep> = (0..steps)
+            .map(|step| PlannedStep {
+                start: step as f64 * dt,
+                dt,
+                conditions: self.weather.conditions_at((step + 1) as f64 * dt),
+            })
+            .collect();
+        if remaining > 0.0 {
+            plan.push(PlannedStep {
+                start: steps as f64 * dt,
+                dt: remaining,
+                conditions: self.weather.conditions_at(duration),
+            });
+        }
+        let start = self.weather.conditions_at(0.0);
+        let runs = self.run_vehicles(&start, &plan);
+        let diverged = runs
+            .iter()
+            .filter_map(|run| run.diverged)
+            .min_by(f64::total_cmp);
+        if let Some(time) = diverged {
+            return Err(Error::Diverged { time });
+        }
+
+        let count = plan.len() + 1;
+        let mut result = MultiVehicleResult {
+            runs: Vec::with_capacity(runs.len()),
+            gaps: Vec::with_capacity(count),
+            collisions: Vec::new(),
+            weather: Vec::with_capacity(count),
+            controller_telemetry: Vec::with_capacity(count),
+        };
+        let sizes: Vec<(f64, f64)> = self
+            .vehicles
+            .iter()
+            .map(|vehicle| vehicle.model.get_size())
+            .collect();
+        let mut contacts = Vec::new();
+        let ticks = iter::once(start).chain(plan.into_iter().map(|step| step.conditions));
+        for (tick, conditions) in ticks.enumerate() {
+            self.record(tick, &runs, &sizes, &mut result, &mut contacts);
+            result.weather.push(conditions);
+        }
+        result.runs = runs.into_iter().map(|run| run.states).collect();
+        Ok(result)
+    }
+
+    /// Drive every vehicle through `plan`, spreading contiguous slices of the vehicles
+    /// over the worker threads, each spawned once for the whole run
+    fn run_vehicles(
+        &mut self,
+        start: &WeatherConditions,
+        plan: &[PlannedStep],
+    ) -> Vec<VehicleRun> {
+        let track = self.track.as_ref();
+        let arc_length = &self.arc_length;
+        let run_all = |vehicles: &mut [Vehicle]| -> Vec<VehicleRun> {
+            vehicles
+                .iter_mut()
+                .map(|vehicle| vehicle.run(start, plan, track, arc_length))
+                .collect()
+        };
+        let threads = self.threads.min(self.vehicles.len());
+        if threads <= 1 {
+            return run_all(&mut self.vehicles);
+        }
+
+        let chunk_len = self.vehicles.len().div_ceil(threads);
+        thread::scope(|scope| {
+            let workers: Vec<_> = self
+                .vehicles
+                .chunks_mut(chunk_len)
+                .map(|chunk| scope.spawn(move || run_all(chunk)))
+                .collect();
+            workers
+                .into_iter()
+                .flat_map(|worker| worker.join().expect("vehicle worker panicked"))
+                .collect()
+        })
+    }
+
+    /// Evaluate the interactions of all vehicles at tick `tick` of `runs`
+    ///
+    /// `contacts` holds the overlapping pairs of the previous tick, so that a contact
+    /// lasting several ticks is reported once.
+    fn record(
+        &self,
+        tick: usize,
+        runs: &[VehicleRun],
+        sizes: &[(f64, f64)],
+        result: &mut MultiVehicleResult,
+        contacts: &mut Vec<(usize, usize)>,
+    ) {
+        result
+            .controller_telemetry
+            .push(runs.iter().map(|run| run.telemetry[tick]).collect());
+
+        let progress: Vec<f64> = runs.iter().map(|run| run.progress[tick]).collect();
+        result
+            .gaps
+            .push(gaps_ahead(&progress, self.arc_length.total_length()));
+
+        let snapshots: Vec<_> = runs.iter().map(|run| &run.states.snapshots()[tick]).collect();
+        let time = snapshots.first().map_or(0.0, |snapshot| snapshot.time);
+        let footprints: Vec<[Vec2; 4]> = snapshots
+            .iter()
+            .zip(sizes)
+            .map(|(snapshot, &size)| footprint(&snapshot.state, size))
+            .collect();
+        let mut overlapping = Vec::new();
+        for first in 0..footprints.len() {
+            for second in first + 1..footprints.len() {
+                if rectangles_overlap(&footprints[first], &footprints[second]) {
+                    overlapping.push((first, second));
+                }
+            }
+        }
+        result.collisions.extend(
+            overlapping
+                .iter()
+                .filter(|pair| !contacts.contains(pair))
+                .map(|&(first, second)| Collision {
+                    time,
+                    first,
+                    second,
+                }),
+        );
+        *contacts = overlapping;
+    }
+}
+
+/// Start positions of a grid of `count` vehicles lined up behind the track's start
+///
+/// Vehicles are placed on the center line every `spacing` meters, the first one at the
+/// start position, and face along the center line.
+///
+/// # Returns
+/// List of (x, y, yaw) poses
+pub fn grid_positions(track: &dyn Track, count: usize, spacing: f64) -> Vec<(f64, f64, f64)> {
+    let arc_length = ArcLengthTable::from_track(track);
+    let start = track.get_start_position();
+    let Some(start_progress) = arc_length.project(start.0, start.1) else {
+        return vec![start; count];
+    };
+    (0..count)
+        .map(|index| {
+            if index == 0 {
+                return start;
+            }
+            arc_length
+                .point_at(start_progress - index as f64 * spacing)
+                .unwrap_or(start)
+        })
+        .collect()
+}
+
+/// Distance along a loop of length `total` from each vehicle to the next one ahead
+///
+/// Vehicles at the same progress are ordered by index, the higher index being ahead.
+fn gaps_ahead(progress: &[f64], total: f64) -> Vec<f64> {
+    if progress.len() < 2 || total <= 0.0 {
+        return vec![f64::INFINITY; progress.len()];
+    }
+    let mut order: Vec<usize> = (0..progress.len()).collect();
+    order.sort_by(|&a, &b| progress[a].total_cmp(&progress[b]).then(a.cmp(&b)));
+
+    let mut gaps = vec![0.0; progress.len()];
+    for (position, &index) in order.iter().enumerate() {
+        let ahead = order[(position + 1) % order.len()];
+        gaps[index] = (progress[ahead] - progress[index]).rem_euclid(total);
+    }
+    gaps
+}
+
+/// Corners of the rectangle of a vehicle of `size` (length, width) in the world frame
+fn footprint(state: &PointMassState, size: (f64, f64)) -> [Vec2; 4] {
+    let (x, y, yaw) = (state.x, state.y, state.yaw);
+    let (length, width) = size;
+    let (half_length, half_width) = (length / 2.0, width / 2.0);
+    [
+        Vec2::new(half_length, half_width),
+        Vec2::new(-half_length, half_width),
+        Vec2::new(-half_length, -half_width),
+        Vec2::new(half_length, -half_width),
+    ]
+    .map(|body| Vec2::new(x, y) + body.rotate(yaw))
+}
+
+/// Separating axis test of two rectangles given by their corners in order
+fn rectangles_overlap(a: &[Vec2; 4], b: &[Vec2; 4]) -> bool {
+    let axes = [a[1] - a[0], a[2] - a[1], b[1] - b[0], b[2] - b[1]];
+    axes.iter().all(|&axis| {
+        let project = |corners: &[Vec2; 4]| {
+            corners
+                .iter()
+                .map(|&corner| corner.dot(axis))
+                .fold((f64::INFINITY, f64::NEG_INFINITY), |(min, max), value| {
+                    (min.min(value), max.max(value))
+                })
+        };
+        let (a_min, a_max) = project(a);
+        let (b_min, b_max) = project(b);
+        a_min <= b_max && b_min <= a_max
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{gaps_ahead, grid_positions, rectangles_overlap, MultiVehicleSimulation};
+    use crate::controllers::base_controller::Controller;
+    use crate::controllers::scripted::{ControlSchedule, ScriptedController};
+    use crate::error::Error;
+    use crate::math::vec2::Vec2;
+    use crate::models::point_mass::{PointMass, PointMassState};
+    use crate::simulation::weather::{Weather, WET_GRIP};
+    use crate::tracks::arc_length::TrackPosition;
+    use crate::tracks::base_track::SharedTrack;
+    use crate::tracks::circle::CircleTrack;
+    use std::sync::{Arc, Mutex};
+    use std::thread::{self, ThreadId};
+
+    fn grid_simulation(count: usize, threads: usize) -> MultiVehicleSimulation {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let mut sim = MultiVehicleSimulation::new(Arc::clone(&track)).with_threads(threads);
+        for (index, (x, y, yaw)) in grid_positions(track.as_ref(), count, 8.0)
+            .into_iter()
+            .enumerate()
+        {
+            let mut model = PointMass::with_initial_state(x, y, 0.0, yaw);
+            if index % 2 == 0 {
+                model.set_controls(1.0 + index as f64 * 0.1, 0.02);
+                sim.add_vehicle(model);
+            } else {
+                let schedule = ControlSchedule::parse_csv(&format!(
+                    "time,ax,yaw_rate\n0.0,{},0.0\n1.0,0.5,0.05\n",
+                    index as f64 * 0.2
+                ))
+                .expect("parse schedule");
+                sim.add_controlled_vehicle(model, ScriptedController::new(schedule));
+            }
+        }
+        sim
+    }
+
+    #[test]
+    fn test_results_do_not_depend_on_thread_count() {
+        let sequential = grid_simulation(24, 1).run(0.05, 3.0).expect("sequential run");
+        let parallel = grid_simulation(24, 4).run(0.05, 3.0).expect("parallel run");
+
+        assert_eq!(sequential.runs.len(), 24);
+        assert_eq!(sequential.gaps, parallel.gaps);
+        assert_eq!(sequential.collisions, parallel.collisions);
+        for (a, b) in sequential.runs.iter().zip(&parallel.runs) {
+            assert_eq!(a.len(), 61);
+            for (a, b) in a.snapshots().iter().zip(b.snapshots()) {
+                assert_eq!(a.time, b.time);
+                assert_eq!(a.state.x.to_bits(), b.state.x.to_bits());
+                assert_eq!(a.state.y.to_bits(), b.state.y.to_bits());
+                assert_eq!(a.in_track, b.in_track);
+            }
+        }
+    }
+
+    #[test]
+    fn test_run_reports_errors() {
+        for (dt, duration) in [(0.0, 1.0), (f64::NAN, 1.0), (0.1, -1.0), (0.1, f64::INFINITY)] {
+            let err = grid_simulation(2, 1).run(dt, duration).expect_err("invalid run");
+            assert!(matches!(err, Error::InvalidConfig(_)), "{err}");
+        }
+
+        let mut sim = grid_simulation(2, 1);
+        let mut model = PointMass::new();
+        model.set_controls(f64::INFINITY, 0.0);
+        sim.add_vehicle(model);
+        let err = sim.run(0.1, 1.0).expect_err("expected divergence");
+        assert!(matches!(err, Error::Diverged { time } if (time - 0.1).abs() < 1e-9));
+    }
+
+    /// Controller recording the threads it is stepped on
+    struct ThreadRecorder(Arc<Mutex<Vec<ThreadId>>>);
+
+    impl Controller<PointMassState> for ThreadRecorder {
+        fn step(&mut self, _time: f64, _state: &PointMassState) -> (f64, f64) {
+            self.0.lock().expect("lock threads").push(thread::current().id());
+            (1.0, 0.0)
+        }
+    }
+
+    #[test]
+    fn test_workers_live_for_the_whole_run() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let mut sim = MultiVehicleSimulation::new(Arc::clone(&track)).with_threads(2);
+        let threads: Vec<_> = (0..4).map(|_| Arc::new(Mutex::new(Vec::new()))).collect();
+        let grid = grid_positions(track.as_ref(), 4, 8.0);
+        for ((x, y, yaw), threads) in grid.into_iter().zip(&threads) {
+            let model = PointMass::with_initial_state(x, y, 0.0, yaw);
+            sim.add_controlled_vehicle(model, ThreadRecorder(Arc::clone(threads)));
+        }
+        sim.run(0.1, 2.0).expect("run");
+
+        // Spawning per tick would step every vehicle on a new thread each tick
+        let workers: Vec<ThreadId> = threads
+            .iter()
+            .map(|threads| {
+                let threads = threads.lock().expect("lock threads");
+                assert_eq!(threads.len(), 20);
+                assert!(threads.iter().all(|id| *id == threads[0]));
+                threads[0]
+            })
+            .collect();
+        assert_eq!(workers[0], workers[1]);
+        assert_eq!(workers[2], workers[3]);
+        assert_ne!(workers[0], workers[2]);
+        assert!(!workers.contains(&thread::current().id()));
+    }
+
+    #[test]
+    fn test_grid_gaps_match_spacing() {
+        let result = grid_simulation(4, 2).run(0.1, 0.0).expect("run");
+        let gaps = &result.gaps[0];
+
+        // Vehicle 0 leads, each following vehicle is 8 m behind the previous one
+        for gap in &gaps[1..] {
+            assert!((gap - 8.0).abs() < 0.1, "gap {gap}");
+        }
+        // The leader's gap closes the loop around the track
+        let total: f64 = gaps.iter().sum();
+        assert!(
+            (total - 2.0 * std::f64::consts::PI * 50.0).abs() < 0.5,
+            "total {total}"
+        );
+        assert!(result.collisions.is_empty());
+    }
+
+    #[test]
+    fn test_collision_reported_once_per_contact() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let mut sim = MultiVehicleSimulation::new(Arc::clone(&track)).with_threads(2);
+        let (x, y, yaw) = track.get_start_position();
+        // The second vehicle starts 6 m behind and drives into the first one
+        let leader = PointMass::with_initial_state(x, y, 0.0, yaw);
+        let mut follower =
+            PointMass::with_initial_state(x - 6.0 * yaw.cos(), y - 6.0 * yaw.sin(), 5.0, yaw);
+        follower.set_controls(0.0, 0.0);
+        sim.add_vehicle(leader);
+        sim.add_vehicle(follower);
+
+        let result = sim.run(0.05, 2.0).expect("run");
+
+        assert_eq!(result.collisions.len(), 1);
+        let collision = result.collisions[0];
+        assert_eq!((collision.first, collision.second), (0, 1));
+        // Footprints (4.5 m long) touch after 1.5 m, i.e. 0.3 s at 5 m/s
+        assert!((collision.time - 0.3).abs() < 0.05 + 1e-9);
+    }
+
+    /// Controller recording the track positions it receives
+    struct PositionRecorder(Arc<Mutex<Vec<TrackPosition>>>);
+
+    impl Controller<PointMassState> for PositionRecorder {
+        fn step(&mut self, _time: f64, _state: &PointMassState) -> (f64, f64) {
+            panic!("the simulation provides track positions")
+        }
+
+        fn step_on_track(
+            &mut self,
+            _time: f64,
+            _state: &PointMassState,
+            position: Option<&TrackPosition>,
+        ) -> (f64, f64) {
+            let position = position.expect("track position");
+            self.0.lock().expect("lock positions").push(*position);
+            // Follow a circle of radius 48 m at 5 m/s
+            (0.0, 5.0 / 48.0)
+        }
+    }
+
+    #[test]
+    fn test_controllers_receive_track_position() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 200));
+        let mut sim = MultiVehicleSimulation::new(track).with_threads(1);
+        let positions = Arc::new(Mutex::new(Vec::new()));
+        // 2 m left of the start position, i.e. towards the circle center
+        let model = PointMass::with_initial_state(48.0, 0.0, 5.0, std::f64::consts::FRAC_PI_2);
+        sim.add_controlled_vehicle(model, PositionRecorder(Arc::clone(&positions)));
+
+        sim.run(0.1, 2.0).expect("run");
+
+        let positions = positions.lock().expect("lock positions");
+        assert_eq!(positions.len(), 20);
+        for (step, position) in positions.iter().enumerate() {
+            // 5 m/s on radius 48 m covers 50/48 of that distance on the center line
+            let expected_s = step as f64 * 0.5 * 50.0 / 48.0;
+            assert!((position.s - expected_s).abs() < 0.05, "step {step}: s {}", position.s);
+            assert!((position.lateral_error - 2.0).abs() < 0.1, "step {step}: {position:?}");
+            assert!((position.curvature - 0.02).abs() < 1e-4);
+        }
+    }
+
+    #[test]
+    fn test_gaps_ahead_wraps_and_breaks_ties_by_index() {
+        assert_eq!(gaps_ahead(&[10.0], 100.0), vec![f64::INFINITY]);
+        assert_eq!(gaps_ahead(&[10.0, 90.0], 100.0), vec![80.0, 20.0]);
+        assert_eq!(gaps_ahead(&[5.0, 5.0, 50.0], 100.0), vec![0.0, 45.0, 55.0]);
+    }
+
+    #[test]
+    fn test_rectangles_overlap() {
+        let square = |x: f64, y: f64| {
+            [
+                Vec2::new(x + 1.0, y + 1.0),
+                Vec2::new(x - 1.0, y + 1.0),
+                Vec2::new(x - 1.0, y - 1.0),
+                Vec2::new(x + 1.0, y - 1.0),
+            ]
+        };
+        assert!(rectangles_overlap(&square(0.0, 0.0), &square(1.5, 1.5)));
+        assert!(!rectangles_overlap(&square(0.0, 0.0), &square(2.5, 0.0)));
+        let diamond = [
+            Vec2::new(0.0, 1.0),
+            Vec2::new(-1.0, 0.0),
+            Vec2::new(0.0, -1.0),
+            Vec2::new(1.0, 0.0),
+        ];
+        // Bounding boxes overlap, shapes do not
+        assert!(!rectangles_overlap(&diamond, &square(1.6, 1.6)));
+    }
+
+    #[test]
+    fn test_rain_reduces_grip() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let run = |weather: Weather| {
+            let mut model = PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0);
+            model.set_friction(Some(0.5));
+            model.set_controls(0.0, 1.0);
+            let mut sim = MultiVehicleSimulation::new(Arc::clone(&track)).with_weather(weather);
+            sim.add_vehicle(model);
+            sim.run(0.1, 1.0).expect("run")
+        };
+        let dry = run(Weather::dry());
+        let wet = run(Weather::constant(1.0));
+
+        // The yaw rate saturates at 0.5 * 9.81 / 10 rad/s, less with the wet grip
+        let yaw = |result: &super::MultiVehicleResult| {
+            result.runs[0].states().last().expect("state").yaw
+        };
+        assert!((yaw(&dry) - 0.4905).abs() < 1e-9);
+        assert!((yaw(&wet) - 0.4905 * WET_GRIP).abs() < 1e-9);
+        assert_eq!(wet.weather.len(), 11);
+        assert!(wet.weather.iter().all(|conditions| conditions.rain == 1.0));
+        assert!(dry.weather.iter().all(|conditions| conditions.grip == 1.0));
+    }
+}
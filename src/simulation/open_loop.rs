@@ -1,97 +1,664 @@
+use crate::diagnostics;
+use crate::error::{Error, Result};
+use crate::controllers::base_controller::Controller;
+use crate::controllers::scripted::{ControlSchedule, ScriptedController};
+use crate::io::output_dir::OutputDirectory;
 use crate::models::base_model::Model;
-use crate::models::point_mass::{PointMass, PointMassState};
+use crate::models::point_mass::PointMass;
+use crate::models::pose::Pose;
 use crate::simulation::base_simulation::Simulation;
-use crate::tracks::base_track::Track;
-use crate::tracks::circle::CircleTrack;
+use crate::simulation::cancel::CancelToken;
+use crate::simulation::footprint::InTrackMode;
+use crate::simulation::profile::{timed, RunProfile};
+use crate::simulation::result::{snapshot_count, step_plan, SimulationResult, StateSnapshot};
+use crate::simulation::spill::{SpillState, SpilledResult};
+use crate::simulation::stop::{StopCondition, StopMonitor};
+use crate::telemetry::TelemetrySink;
+use crate::tracks::base_track::{SharedTrack, Track};
+use std::path::Path;
 
-pub struct OpenLoopSimulation {
-    track: Option<CircleTrack>,
-    model: Option<PointMass>,
+/// Constant controls (acceleration, yaw rate) used when none are configured
+pub const DEFAULT_CONTROLS: (f64, f64) = (2.0, 0.4);
+
+/// Time step in seconds used by `OpenLoopSimulation::simulate` when none is configured
+pub const DEFAULT_DT: f64 = 0.1;
+
+/// Duration in seconds used by `OpenLoopSimulation::simulate` when none is configured
+pub const DEFAULT_DURATION: f64 = 10.0;
+
+/// Open-loop simulation of a model on any track
+///
+/// Created with `OpenLoopSimulation::builder()` for a `PointMass`, or with
+/// `OpenLoopSimulation::builder_for(model)` for any other `Model`, including boxed
+/// models (`Box<dyn Model<State = S>>`) picked at runtime. The open-loop commands are
+/// an acceleration and a yaw rate, applied with `Model::set_yaw_rate_controls`, and
+/// the recorded snapshots are the model's own states.
+pub struct OpenLoopSimulation<M: Model = PointMass> {
+    track: Option<SharedTrack>,
+    model: Option<M>,
     controls: (f64, f64),
+    scripted: Option<ScriptedController>,
+    telemetry: Option<Box<dyn TelemetrySink<M::State> + Send>>,
+    outputs: Option<OutputDirectory>,
+    in_track_mode: InTrackMode,
+    record_every: usize,
+    stop_conditions: Vec<StopCondition<M::State>>,
+    dt: f64,
+    duration: f64,
 }
 
-impl OpenLoopSimulation {
-    pub fn new() -> Self {
-        Self {
+/// Builder of `OpenLoopSimulation`
+///
+/// Every setting is optional: without a track the simulation must be initialized with
+/// `Simulation::init` before running; without a model a default `PointMass` is used.
+pub struct OpenLoopSimulationBuilder<M: Model = PointMass> {
+    track: Option<SharedTrack>,
+    model: M,
+    custom_model: bool, // Whether `model` was given rather than the default
+    controls: (f64, f64),
+    schedule: Option<ControlSchedule>,
+    telemetry: Option<Box<dyn TelemetrySink<M::State> + Send>>,
+    outputs: Option<OutputDirectory>,
+    in_track_mode: InTrackMode,
+    record_every: usize,
+    stop_conditions: Vec<StopCondition<M::State>>,
+    dt: f64,
+    duration: f64,
+}
+
+impl<M: Model> OpenLoopSimulationBuilder<M>
+where
+    M::State: Pose + Clone,
+{
+    /// Track to run on; the simulation is initialized at its start position
+    pub fn track(mut self, track: SharedTrack) -> Self {
+        self.track = Some(track);
+        self
+    }
+
+    /// Model to simulate (a default `PointMass` if not set)
+    ///
+    /// Models of another type are given to `OpenLoopSimulation::builder_for` instead.
+    pub fn model(mut self, model: M) -> Self {
+        self.model = model;
+        self.custom_model = true;
+        self
+    }
+
+    /// Constant control inputs (acceleration in m/s², yaw rate in radians/s)
+    pub fn controls(mut self, ax: f64, yaw_rate: f64) -> Self {
+        self.controls = (ax, yaw_rate);
+        self
+    }
+
+    /// Replay a time-stamped control schedule instead of constant controls
+    pub fn schedule(mut self, schedule: ControlSchedule) -> Self {
+        self.schedule = Some(schedule);
+        self
+    }
+
+    /// How snapshots are checked against the track boundaries (the vehicle center if not set)
+    pub fn in_track_mode(mut self, mode: InTrackMode) -> Self {
+        self.in_track_mode = mode;
+        self
+    }
+
+    /// Record a snapshot every `steps` steps instead of every step (1 if not set)
+    ///
+    /// The start and the end of a run are always recorded, so long runs at a fine time
+    /// step keep a coarser trajectory without changing the dynamics.
+    pub fn record_every(mut self, steps: usize) -> Self {
+        self.record_every = steps;
+        self
+    }
+
+    /// End runs early once `condition` holds, e.g. `StopCondition::NumLaps(3)`
+    ///
+    /// May be called several times; the first condition that holds ends the run.
+    pub fn stop_when(mut self, condition: StopCondition<M::State>) -> Self {
+        self.stop_conditions.push(condition);
+        self
+    }
+
+    /// Stream every recorded snapshot to a telemetry sink while running
+    pub fn telemetry<T: TelemetrySink<M::State> + Send + 'static>(mut self, sink: T) -> Self {
+        self.telemetry = Some(Box::new(sink));
+        self
+    }
+
+    /// Directory receiving the rendered artifacts, see `OpenLoopSimulation::outputs_mut`
+    pub fn output_dir<P: AsRef<Path>>(mut self, path: P) -> Self {
+        self.outputs = Some(OutputDirectory::new(path));
+        self
+    }
+
+    /// Time step in seconds used by `simulate`
+    pub fn dt(mut self, dt: f64) -> Self {
+        self.dt = dt;
+        self
+    }
+
+    /// Simulated duration in seconds used by `simulate`
+    pub fn duration(mut self, duration: f64) -> Self {
+        self.duration = duration;
+        self
+    }
+
+    /// Create the simulation
+    ///
+    /// # Returns
+    /// The simulation, or an error if the time step, duration, recording interval, or a
+    /// stop condition is invalid or a model was given without a track
+    pub fn build(self) -> Result<OpenLoopSimulation<M>> {
+        if !self.dt.is_finite() || self.dt <= 0.0 {
+            return Err(Error::InvalidConfig(format!(
+                "dt must be a positive number, got {}",
+                self.dt
+            )));
+        }
+        if !self.duration.is_finite() || self.duration < 0.0 {
+            return Err(Error::InvalidConfig(format!(
+                "duration must be non-negative, got {}",
+                self.duration
+            )));
+        }
+        if self.record_every == 0 {
+            return Err(Error::invalid_config("record_every must be at least 1 step"));
+        }
+        for condition in &self.stop_conditions {
+            condition.validate().map_err(Error::InvalidConfig)?;
+        }
+        if self.custom_model && self.track.is_none() {
+            return Err(Error::invalid_config("a model requires a track to start on"));
+        }
+
+        let mut simulation = OpenLoopSimulation {
             track: None,
             model: None,
-            controls: (2.0, 0.4),
+            controls: self.controls,
+            scripted: None,
+            telemetry: self.telemetry,
+            outputs: self.outputs,
+            in_track_mode: self.in_track_mode,
+            record_every: self.record_every,
+            stop_conditions: self.stop_conditions,
+            dt: self.dt,
+            duration: self.duration,
+        };
+        if let Some(schedule) = self.schedule {
+            simulation.set_schedule(schedule);
+        }
+        if let Some(track) = self.track {
+            simulation.init(track, self.model)?;
         }
+        Ok(simulation)
+    }
+}
+
+impl OpenLoopSimulation {
+    /// Start configuring a point mass simulation
+    pub fn builder() -> OpenLoopSimulationBuilder {
+        let mut builder = OpenLoopSimulation::builder_for(PointMass::default());
+        builder.custom_model = false;
+        builder
     }
 
+    #[deprecated(note = "use `OpenLoopSimulation::builder()`")]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    #[deprecated(note = "use `OpenLoopSimulation::builder().controls(ax, yaw_rate)`")]
     pub fn with_controls(ax: f64, yaw_rate: f64) -> Self {
         Self {
-            track: None,
-            model: None,
             controls: (ax, yaw_rate),
+            ..Self::default()
+        }
+    }
+
+    /// Create a simulation replaying a time-stamped control schedule
+    #[deprecated(note = "use `OpenLoopSimulation::builder().schedule(schedule)`")]
+    pub fn with_schedule(schedule: ControlSchedule) -> Self {
+        let mut simulation = Self::default();
+        simulation.set_schedule(schedule);
+        simulation
+    }
+}
+
+impl<M: Model> OpenLoopSimulation<M>
+where
+    M::State: Pose + Clone,
+{
+    /// Start configuring a simulation of `model`, which requires a track to start on
+    pub fn builder_for(model: M) -> OpenLoopSimulationBuilder<M> {
+        OpenLoopSimulationBuilder {
+            track: None,
+            model,
+            custom_model: true,
+            controls: DEFAULT_CONTROLS,
+            schedule: None,
+            telemetry: None,
+            outputs: None,
+            in_track_mode: InTrackMode::default(),
+            record_every: 1,
+            stop_conditions: Vec::new(),
+            dt: DEFAULT_DT,
+            duration: DEFAULT_DURATION,
         }
     }
 
-    pub fn track(&self) -> Option<&CircleTrack> {
-        self.track.as_ref()
+    /// Time step in seconds used by `simulate`
+    pub fn dt(&self) -> f64 {
+        self.dt
+    }
+
+    /// Simulated duration in seconds used by `simulate`
+    pub fn duration(&self) -> f64 {
+        self.duration
+    }
+
+    /// Number of steps between recorded snapshots
+    pub fn record_every(&self) -> usize {
+        self.record_every
+    }
+
+    /// Conditions ending runs before their duration elapses
+    pub fn stop_conditions(&self) -> &[StopCondition<M::State>] {
+        &self.stop_conditions
+    }
+
+    /// End runs early once `condition` holds, in addition to the existing conditions
+    ///
+    /// # Returns
+    /// An error if the condition is invalid (e.g., zero laps)
+    pub fn add_stop_condition(&mut self, condition: StopCondition<M::State>) -> Result<()> {
+        condition.validate().map_err(Error::InvalidConfig)?;
+        self.stop_conditions.push(condition);
+        Ok(())
     }
 
-    pub fn model(&self) -> Option<&PointMass> {
+    /// Run for the full duration again
+    pub fn clear_stop_conditions(&mut self) {
+        self.stop_conditions.clear();
+    }
+
+    /// Run from time 0 with the time step and duration configured on the builder
+    pub fn simulate(&mut self) -> Result<SimulationResult<M::State>> {
+        self.run(self.dt, self.duration)
+    }
+
+    pub fn track(&self) -> Option<&dyn Track> {
+        self.track.as_deref().map(|track| track as &dyn Track)
+    }
+
+    /// Handle to the track the simulation runs on, to share it with plotting or controllers
+    pub fn shared_track(&self) -> Option<SharedTrack> {
+        self.track.clone()
+    }
+
+    pub fn model(&self) -> Option<&M> {
         self.model.as_ref()
     }
 
     pub fn set_controls(&mut self, ax: f64, yaw_rate: f64) {
         self.controls = (ax, yaw_rate);
+        self.scripted = None;
         if let Some(model) = self.model.as_mut() {
-            model.set_controls(ax, yaw_rate);
+            model.set_yaw_rate_controls(ax, yaw_rate);
         }
     }
-}
 
-impl Default for OpenLoopSimulation {
-    fn default() -> Self {
-        Self::new()
+    /// Replay a time-stamped control schedule instead of constant controls
+    pub fn set_schedule(&mut self, schedule: ControlSchedule) {
+        self.scripted = Some(ScriptedController::new(schedule));
     }
-}
 
-impl Simulation for OpenLoopSimulation {
-    type Track = CircleTrack;
-    type Model = PointMass;
+    pub fn schedule(&self) -> Option<&ControlSchedule> {
+        self.scripted.as_ref().map(ScriptedController::schedule)
+    }
 
-    fn init(&mut self, track: CircleTrack, mut model: PointMass) {
-        model.init();
-        let start_pos = track.get_start_position();
-        model.set_position(start_pos.0, start_pos.1, start_pos.2);
-        model.set_controls(self.controls.0, self.controls.1);
-        self.track = Some(track);
-        self.model = Some(model);
+    /// Control inputs (acceleration, yaw rate) applied at `time`
+    pub fn controls_at(&self, time: f64) -> (f64, f64) {
+        self.scripted
+            .as_ref()
+            .map_or(self.controls, |scripted| scripted.schedule().controls_at(time))
+    }
+
+    /// Stream every recorded snapshot to a telemetry sink while running
+    pub fn set_telemetry<T: TelemetrySink<M::State> + Send + 'static>(&mut self, sink: T) {
+        self.telemetry = Some(Box::new(sink));
+    }
+
+    /// Stop streaming telemetry
+    pub fn clear_telemetry(&mut self) {
+        self.telemetry = None;
+    }
+
+    /// How snapshots are checked against the track boundaries
+    pub fn in_track_mode(&self) -> InTrackMode {
+        self.in_track_mode
+    }
+
+    pub fn set_in_track_mode(&mut self, mode: InTrackMode) {
+        self.in_track_mode = mode;
+    }
+
+    /// Track the artifacts rendered into `path`, replacing the previous output directory
+    pub fn set_output_dir<P: AsRef<Path>>(&mut self, path: P) {
+        self.outputs = Some(OutputDirectory::new(path));
+    }
+
+    pub fn outputs(&self) -> Option<&OutputDirectory> {
+        self.outputs.as_ref()
+    }
+
+    /// Output directory to register rendered artifacts in (e.g. with
+    /// `OpenLoopArtifacts::register`), so `clean` can delete the intermediates
+    pub fn outputs_mut(&mut self) -> Option<&mut OutputDirectory> {
+        self.outputs.as_mut()
     }
 
-    fn run(&mut self, dt: f64, duration: f64) -> Vec<PointMassState> {
+    /// Continue the simulation from its current state, with time starting at `start_time`
+    ///
+    /// Snapshot times and control schedules use absolute time, so consecutive calls
+    /// (e.g., stepping a remote scenario) line up with a single long run.
+    pub fn run_from(
+        &mut self,
+        start_time: f64,
+        dt: f64,
+        duration: f64,
+    ) -> Result<SimulationResult<M::State>> {
+        let mut result = SimulationResult::for_run(dt, duration);
+        self.run_from_into(start_time, dt, duration, &mut result)?;
+        Ok(result)
+    }
+
+    /// Same as `run_from`, but records into `result`, replacing its snapshots
+    ///
+    /// The storage of `result` is reused, so repeated runs (e.g., parameter sweeps)
+    /// only allocate when a run needs more snapshots than any previous one.
+    pub fn run_from_into(
+        &mut self,
+        start_time: f64,
+        dt: f64,
+        duration: f64,
+        result: &mut SimulationResult<M::State>,
+    ) -> Result<()> {
+        // Runs without a token cannot be cancelled
+        self.record_run(start_time, dt, duration, result, None, None)
+    }
+
+    /// Run from the start time 0 for `duration` seconds unless `cancel` is triggered
+    ///
+    /// The token is checked before every step; a cancelled run fails with
+    /// `Error::Cancelled` and leaves the model in the state it reached.
+    pub fn run_cancellable(
+        &mut self,
+        dt: f64,
+        duration: f64,
+        cancel: &CancelToken,
+    ) -> Result<SimulationResult<M::State>> {
+        let mut result = SimulationResult::for_run(dt, duration);
+        self.record_run(0.0, dt, duration, &mut result, Some(cancel), None)?;
+        Ok(result)
+    }
+
+    fn record_run(
+        &mut self,
+        start_time: f64,
+        dt: f64,
+        duration: f64,
+        result: &mut SimulationResult<M::State>,
+        cancel: Option<&CancelToken>,
+        mut spill: Option<&mut dyn SnapshotStore<M::State>>,
+    ) -> Result<()> {
+        let cancelled = || cancel.is_some_and(CancelToken::is_cancelled);
+        result.clear();
+        // The start, every `record_every`-th step, and the end
+        let capacity = (snapshot_count(dt, duration) - 1).div_ceil(self.record_every) + 1;
+        result.reserve(spill.as_ref().map_or(capacity, |store| capacity.min(store.chunk_len())));
         let model = self
             .model
             .as_mut()
-            .expect("OpenLoopSimulation must be initialized before run");
-        model.set_controls(self.controls.0, self.controls.1);
+            .ok_or_else(|| Error::NotInitialized("simulation".to_string()))?;
+        let track = self.track.as_ref();
+        let (mode, size) = (self.in_track_mode, model.get_size());
+        let mut profile = RunProfile::default();
+        let mut in_track = |state: &M::State| {
+            timed(&mut profile.track, || {
+                track.is_none_or(|track| mode.is_in_track(track.as_ref(), state, size))
+            })
+        };
+        let mut monitor = StopMonitor::new(
+            &self.stop_conditions,
+            track.map(|track| track.as_ref() as &dyn Track),
+        );
+        model.set_yaw_rate_controls(self.controls.0, self.controls.1);
+
+        let telemetry = &mut self.telemetry;
+        let mut telemetry_failed = false;
+        if let Some(sink) = telemetry.as_mut() {
+            sink.start_run();
+        }
 
-        let mut states = Vec::new();
-        states.push(model.get_state().clone());
+        if !is_finite(model) {
+            return Err(Error::Diverged { time: start_time });
+        }
+        let state = model.get_state();
+        let inside = in_track(state);
+        result.push(start_time, state.clone(), inside);
+        publish(telemetry, result.last(), &mut telemetry_failed);
+        result.set_stop_reason(monitor.check(start_time, state, inside));
+
+        if dt <= 0.0 || duration <= 0.0 || result.stop_reason().is_some() {
+            result.set_profile(cfg!(feature = "profiling").then_some(profile));
+            return spill_snapshots(result, &mut spill, true);
+        }
+
+        // Step times are multiples of `dt` rather than a running sum, so they do not drift
+        let (steps, remaining) = step_plan(dt, duration);
+        // Saturation of skipped steps is reported at the next recorded snapshot
+        let mut saturated = false;
 
-        if dt <= 0.0 || duration <= 0.0 {
-            return states;
+        for step in 0..steps {
+            if cancelled() {
+                return Err(Error::Cancelled);
+            }
+            if let Some(scripted) = self.scripted.as_mut() {
+                let (ax, yaw_rate) = timed(&mut profile.controller, || {
+                    scripted.step(start_time + step as f64 * dt, model.get_state())
+                });
+                model.set_yaw_rate_controls(ax, yaw_rate);
+            }
+            timed(&mut profile.model, || model.step(dt));
+            if !is_finite(model) {
+                return Err(Error::Diverged {
+                    time: start_time + (step + 1) as f64 * dt,
+                });
+            }
+            saturated |= model.saturation().any();
+            let last = step + 1 == steps && remaining <= 0.0;
+            let record = (step + 1) % self.record_every == 0 || last;
+            if !record && !monitor.is_active() {
+                continue;
+            }
+            let (time, state) = (start_time + (step + 1) as f64 * dt, model.get_state());
+            let inside = in_track(state);
+            // A step ending the run is recorded even between recording intervals
+            let stop = monitor.check(time, state, inside);
+            if !record && stop.is_none() {
+                continue;
+            }
+            if std::mem::take(&mut saturated) {
+                result.mark_saturated(time);
+            }
+            result.push(time, state.clone(), inside);
+            publish(telemetry, result.last(), &mut telemetry_failed);
+            if stop.is_some() {
+                result.set_stop_reason(stop);
+                break;
+            }
+            spill_snapshots(result, &mut spill, false)?;
         }
 
-        let steps = (duration / dt).floor() as usize;
-        let mut current_time = 0.0f64;
+        if remaining > 0.0 && result.stop_reason().is_none() {
+            if cancelled() {
+                return Err(Error::Cancelled);
+            }
+            if let Some(scripted) = self.scripted.as_mut() {
+                let (ax, yaw_rate) = timed(&mut profile.controller, || {
+                    scripted.step(start_time + steps as f64 * dt, model.get_state())
+                });
+                model.set_yaw_rate_controls(ax, yaw_rate);
+            }
+            timed(&mut profile.model, || model.step(remaining));
+            if !is_finite(model) {
+                return Err(Error::Diverged {
+                    time: start_time + duration,
+                });
+            }
+            let state = model.get_state();
+            if saturated || model.saturation().any() {
+                result.mark_saturated(start_time + duration);
+            }
+            let inside = in_track(state);
+            result.push(start_time + duration, state.clone(), inside);
+            publish(telemetry, result.last(), &mut telemetry_failed);
+            result.set_stop_reason(monitor.check(start_time + duration, state, inside));
+        }
+        result.set_profile(cfg!(feature = "profiling").then_some(profile));
+        spill_snapshots(result, &mut spill, true)
+    }
+}
+
+impl<M: Model> OpenLoopSimulation<M>
+where
+    M::State: Pose + SpillState + Clone,
+{
+    /// Run from the current state for `duration` seconds, recording into a disk-backed store
+    ///
+    /// Snapshots are moved to the store whenever `store.chunk_len()` of them are
+    /// buffered, so memory stays bounded for runs of any length. The run itself is the
+    /// same as `run`: stop conditions and the `record_every` interval span the whole
+    /// run, and telemetry sinks receive every snapshot once.
+    pub fn run_spilled(
+        &mut self,
+        dt: f64,
+        duration: f64,
+        store: &mut SpilledResult<M::State>,
+    ) -> Result<()> {
+        let mut buffer = SimulationResult::new();
+        self.record_run(0.0, dt, duration, &mut buffer, None, Some(store))
+    }
+}
+
+/// Disk-backed store the snapshots of a run are moved to while recording
+trait SnapshotStore<S> {
+    /// Number of snapshots buffered in memory before they are moved
+    fn chunk_len(&self) -> usize;
+
+    fn push(&mut self, snapshot: &StateSnapshot<S>) -> Result<()>;
+}
+
+impl<S: SpillState + Clone> SnapshotStore<S> for SpilledResult<S> {
+    fn chunk_len(&self) -> usize {
+        SpilledResult::chunk_len(self)
+    }
+
+    fn push(&mut self, snapshot: &StateSnapshot<S>) -> Result<()> {
+        Ok(SpilledResult::push(self, snapshot.time, snapshot.state.clone(), snapshot.in_track)?)
+    }
+}
+
+/// Move the snapshots of `result` to `store`, if any, once a chunk is full or `finish`
+fn spill_snapshots<S>(
+    result: &mut SimulationResult<S>,
+    store: &mut Option<&mut dyn SnapshotStore<S>>,
+    finish: bool,
+) -> Result<()> {
+    let Some(store) = store.as_mut() else {
+        return Ok(());
+    };
+    if !finish && result.len() < store.chunk_len() {
+        return Ok(());
+    }
+    for snapshot in result.snapshots() {
+        store.push(snapshot)?;
+    }
+    result.clear();
+    Ok(())
+}
+
+/// Whether the pose and velocity of `model` are finite numbers
+pub(crate) fn is_finite<M: Model>(model: &M) -> bool {
+    let ((x, y, yaw), (vx, vy)) = (model.get_position(), model.get_velocity());
+    [x, y, yaw, vx, vy].iter().all(|value| value.is_finite())
+}
+
+/// Forward a snapshot to the telemetry sink, reporting only the first failure
+fn publish<S>(
+    telemetry: &mut Option<Box<dyn TelemetrySink<S> + Send>>,
+    snapshot: Option<&StateSnapshot<S>>,
+    failed: &mut bool,
+) {
+    if let (Some(sink), Some(snapshot)) = (telemetry.as_mut(), snapshot)
+        && let Err(err) = sink.publish(snapshot)
+        && !*failed
+    {
+        diagnostics::warn(format_args!("Failed to publish telemetry: {err}"));
+        *failed = true;
+    }
+}
 
-        for _ in 0..steps {
-            model.step(dt);
-            current_time += dt;
-            states.push(model.get_state().clone());
+impl Default for OpenLoopSimulation {
+    fn default() -> Self {
+        Self {
+            track: None,
+            model: None,
+            controls: DEFAULT_CONTROLS,
+            scripted: None,
+            telemetry: None,
+            outputs: None,
+            in_track_mode: InTrackMode::default(),
+            record_every: 1,
+            stop_conditions: Vec::new(),
+            dt: DEFAULT_DT,
+            duration: DEFAULT_DURATION,
         }
+    }
+}
 
-        let remaining = duration - current_time;
-        if remaining > 0.0 {
-            model.step(remaining);
-            states.push(model.get_state().clone());
+impl<M: Model> Simulation for OpenLoopSimulation<M>
+where
+    M::State: Pose + Clone,
+{
+    type Track = dyn Track + Send + Sync;
+    type Model = M;
+
+    fn init(&mut self, track: SharedTrack, mut model: M) -> Result<()> {
+        if track.get_center_line().is_empty() {
+            return Err(Error::NotInitialized(format!("track '{}'", track.get_track_name())));
         }
+        model.init();
+        let start_pos = track.get_start_position();
+        model.set_position(start_pos.0, start_pos.1, start_pos.2);
+        model.set_yaw_rate_controls(self.controls.0, self.controls.1);
+        self.track = Some(track);
+        self.model = Some(model);
+        Ok(())
+    }
+
+    fn run(&mut self, dt: f64, duration: f64) -> Result<SimulationResult<M::State>> {
+        self.run_from(0.0, dt, duration)
+    }
 
-        states
+    fn run_into(
+        &mut self,
+        dt: f64,
+        duration: f64,
+        result: &mut SimulationResult<M::State>,
+    ) -> Result<()> {
+        self.run_from_into(0.0, dt, duration, result)
     }
 
     fn reset(&mut self) {
@@ -99,34 +666,218 @@ impl Simulation for OpenLoopSimulation {
             model.reset();
             let start_pos = track.get_start_position();
             model.set_position(start_pos.0, start_pos.1, start_pos.2);
-            model.set_controls(self.controls.0, self.controls.1);
+            model.set_yaw_rate_controls(self.controls.0, self.controls.1);
+        }
+        if let Some(scripted) = self.scripted.as_mut() {
+            Controller::<M::State>::reset(scripted);
         }
     }
 
+    /// Release the track and model and delete the intermediate artifacts registered in
+    /// the output directory; final artifacts are kept
     fn clean(&mut self) {
         self.track = None;
         self.model = None;
+        if let Some(outputs) = self.outputs.as_mut()
+            && let Err(err) = outputs.clean()
+        {
+            diagnostics::warn(format_args!("Failed to clean {}: {err}", outputs.root().display()));
+        }
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::OpenLoopSimulation;
+    use crate::error::Error;
+    use crate::simulation::cancel::CancelToken;
+    use crate::models::limits::ControlLimits;
+    use crate::simulation::footprint::{excursions, InTrackMode};
+    use crate::controllers::scripted::ControlSchedule;
+    use crate::io::output_dir::ArtifactKind;
     use crate::models::base_model::Model;
-    use crate::models::point_mass::PointMass;
+    use crate::models::point_mass::{PointMass, PointMassState};
     use crate::simulation::base_simulation::Simulation;
-    use crate::tracks::base_track::Track;
+    use crate::simulation::result::SimulationResult;
+    use crate::simulation::spill::SpilledResult;
+    use crate::simulation::stop::{StopCondition, StopReason};
+    use crate::tracks::base_track::{SharedTrack, Track};
+    use crate::telemetry::udp::UdpTelemetry;
+    use crate::telemetry::{TelemetryFormat, TelemetryPacket};
     use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+    use std::fs;
+    use std::net::UdpSocket;
+    use std::sync::Arc;
+    use std::time::Duration;
+
+    #[test]
+    fn test_open_loop_builder_configures_run() {
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(1.0, 0.0)
+            .dt(0.05)
+            .duration(0.5)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+        assert_eq!((sim.dt(), sim.duration()), (0.05, 0.5));
+        assert!(sim.track().is_some());
+
+        let result = sim.simulate().expect("run");
+        assert_eq!(result.len(), 11);
+        let vx = result.last().expect("final snapshot").state.vx;
+        assert!((vx - 0.5).abs() < 1e-9);
+
+        // Without a track, the simulation waits for `init`
+        let sim = OpenLoopSimulation::builder().build().expect("build simulation");
+        assert!(sim.track().is_none() && sim.model().is_none());
+    }
+
+    #[test]
+    fn test_open_loop_builder_rejects_invalid_settings() {
+        assert!(OpenLoopSimulation::builder().dt(0.0).build().is_err());
+        assert!(OpenLoopSimulation::builder().dt(f64::NAN).build().is_err());
+        assert!(OpenLoopSimulation::builder().duration(-1.0).build().is_err());
+        assert!(OpenLoopSimulation::builder().record_every(0).build().is_err());
+        let err = OpenLoopSimulation::builder()
+            .model(PointMass::new())
+            .build()
+            .err()
+            .expect("model without track");
+        assert!(matches!(err, Error::InvalidConfig(_)));
+        assert_eq!(
+            err.to_string(),
+            "invalid configuration: a model requires a track to start on"
+        );
+    }
+
+    #[test]
+    fn test_open_loop_builder_records_every_nth_step() {
+        let build = |record_every: usize| {
+            OpenLoopSimulation::builder()
+                .controls(1.0, 0.1)
+                .record_every(record_every)
+                .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+                .build()
+                .expect("build simulation")
+        };
+        let full = build(1).run(0.1, 1.05).expect("run");
+        let mut sim = build(4);
+        assert_eq!(sim.record_every(), 4);
+        let sparse = sim.run(0.1, 1.05).expect("run");
+
+        let times: Vec<f64> = sparse.times().collect();
+        assert_eq!(times.len(), 4);
+        for (time, expected) in times.iter().zip([0.0, 0.4, 0.8, 1.05]) {
+            assert!((time - expected).abs() < 1e-9, "{times:?}");
+        }
+        assert_eq!(sparse.last(), full.last());
+        assert_eq!(sparse.snapshots()[1], full.snapshots()[4]);
+    }
 
     #[test]
     fn test_open_loop_run_returns_states() {
         let track = CircleTrack::new(50.0, 10.0, 100);
         let model = PointMass::new();
-        let mut sim = OpenLoopSimulation::new();
-        sim.init(track, model);
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(track))
+            .model(model)
+            .build()
+            .expect("build simulation");
+
+        let result = sim.run(0.1, 0.25).expect("run");
+        assert_eq!(result.len(), 4);
+        assert!((result.duration() - 0.25).abs() < 1e-9);
+        assert!(result.snapshots().iter().all(|snapshot| snapshot.in_track));
+    }
+
+    #[test]
+    fn test_open_loop_runs_boxed_models() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let schedule =
+            ControlSchedule::parse_csv("time,ax,yaw_rate\n0.0,2.0,0.0\n0.5,1.0,0.3\n")
+                .expect("parse schedule");
+        let mut plain = OpenLoopSimulation::builder()
+            .schedule(schedule.clone())
+            .track(Arc::clone(&track))
+            .build()
+            .expect("build simulation");
+        let boxed: Box<dyn Model<State = PointMassState> + Send> = Box::new(PointMass::new());
+        let mut sim = OpenLoopSimulation::builder_for(boxed)
+            .schedule(schedule)
+            .track(track)
+            .build()
+            .expect("build boxed simulation");
+
+        let result = sim.run(0.1, 1.0).expect("run boxed model");
+        assert_eq!(result, plain.run(0.1, 1.0).expect("run"));
+        assert!(result.last().expect("final snapshot").state.yaw > 0.0);
+        sim.reset();
+        assert_eq!(sim.model().expect("model").get_velocity(), (0.0, 0.0));
+
+        let err = OpenLoopSimulation::builder_for(PointMass::new())
+            .build()
+            .err()
+            .expect("model without track");
+        assert!(matches!(err, Error::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_open_loop_footprint_in_track_mode() {
+        // Driving straight off the tangent of the circle: the corners leave first
+        let first_off = |mode: InTrackMode| {
+            let mut sim = OpenLoopSimulation::builder()
+                .controls(2.0, 0.0)
+                .in_track_mode(mode)
+                .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+                .build()
+                .expect("build simulation");
+            let result = sim.run(0.1, 10.0).expect("run");
+            let index = result.snapshots().iter().position(|snapshot| !snapshot.in_track);
+            (index.expect("leaves the track"), result)
+        };
+        let (center, _) = first_off(InTrackMode::Center);
+        let (footprint, result) = first_off(InTrackMode::Footprint);
+        assert!(footprint < center, "{footprint} vs {center}");
+
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let excursions = excursions(&track, &result, PointMass::new().get_size());
+        assert_eq!(excursions[0].start, result.snapshots()[footprint].time);
+    }
+
+    #[test]
+    fn test_open_loop_records_saturated_steps() {
+        let mut model = PointMass::new();
+        model.set_limits(ControlLimits::none().with_max_ax(1.0));
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .model(model)
+            .controls(3.0, 0.0)
+            .build()
+            .expect("build simulation");
+        let result = sim.run(0.1, 0.5).expect("run");
+        assert_eq!(result.saturated_times().len(), 5);
+        assert!((result.last().expect("final snapshot").state.vx - 0.5).abs() < 1e-9);
+
+        sim.set_controls(0.5, 0.0);
+        assert!(sim.run(0.1, 0.5).expect("run").saturated_times().is_empty());
+    }
+
+    #[test]
+    fn test_open_loop_repeated_runs_compare_equal() {
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(1.0, 0.2)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+        let first = sim.run(0.1, 1.0).expect("run");
+        let model = sim.model().cloned().expect("model");
+        sim.reset();
+        let second = sim.run(0.1, 1.0).expect("run");
 
-        let states = sim.run(0.1, 0.25);
-        assert_eq!(states.len(), 4);
+        assert_eq!(first, second);
+        assert_eq!(sim.model(), Some(&model));
+        assert_ne!(first, sim.run(0.1, 1.0).expect("run"));
     }
 
     #[test]
@@ -134,10 +885,13 @@ mod tests {
         let track = CircleTrack::new(50.0, 10.0, 100);
         let start_pos = track.get_start_position();
         let model = PointMass::new();
-        let mut sim = OpenLoopSimulation::new();
-        sim.init(track, model);
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(track))
+            .model(model)
+            .build()
+            .expect("build simulation");
 
-        let _ = sim.run(0.1, 0.5);
+        let _ = sim.run(0.1, 0.5).expect("run");
         sim.reset();
 
         let model = sim.model().expect("model missing after reset");
@@ -151,12 +905,302 @@ mod tests {
     fn test_open_loop_clean_clears_state() {
         let track = CircleTrack::new(50.0, 10.0, 100);
         let model = PointMass::new();
-        let mut sim = OpenLoopSimulation::new();
-        sim.init(track, model);
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(track))
+            .model(model)
+            .build()
+            .expect("build simulation");
 
         sim.clean();
 
         assert!(sim.track().is_none());
         assert!(sim.model().is_none());
     }
+
+    #[test]
+    fn test_open_loop_clean_removes_intermediate_artifacts() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .output_dir(temp_dir.path())
+            .build()
+            .expect("build simulation");
+
+        let outputs = sim.outputs_mut().expect("output directory");
+        let final_svg = outputs.join("final_state.svg");
+        let frame = outputs.join("step_001.svg");
+        fs::write(&final_svg, "<svg/>").expect("write final state");
+        fs::write(&frame, "<svg/>").expect("write frame");
+        outputs.register(&final_svg, ArtifactKind::Final);
+        outputs.register(&frame, ArtifactKind::Intermediate);
+
+        sim.clean();
+
+        assert!(final_svg.exists());
+        assert!(!frame.exists());
+        assert_eq!(sim.outputs().expect("output directory").artifacts().len(), 1);
+    }
+
+    #[test]
+    fn test_open_loop_replays_schedule() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let model = PointMass::new();
+        let schedule =
+            ControlSchedule::parse_csv("time,ax,yaw_rate\n0.0,1.0,0.0\n0.5,0.0,0.0\n")
+                .expect("parse schedule");
+        let mut sim = OpenLoopSimulation::builder()
+            .schedule(schedule)
+            .track(Arc::new(track))
+            .model(model)
+            .build()
+            .expect("build simulation");
+
+        let result = sim.run(0.1, 1.0).expect("run");
+        let final_state = &result.last().expect("final snapshot").state;
+
+        // Accelerates at 1 m/s² for 0.5 s, then coasts
+        assert!((final_state.vx - 0.5).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_open_loop_run_cancellable() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(track))
+            .build()
+            .expect("build simulation");
+
+        let token = CancelToken::new();
+        let result = sim.run_cancellable(0.1, 0.25, &token).expect("run");
+        assert_eq!(result.len(), 4);
+
+        token.cancel();
+        sim.reset();
+        let err = sim.run_cancellable(0.1, 0.25, &token).expect_err("expected cancellation");
+        assert!(matches!(err, Error::Cancelled));
+        // Cancelled before the first step
+        assert_eq!(sim.model().expect("model").get_state().vx, 0.0);
+    }
+
+    #[test]
+    fn test_open_loop_run_reports_errors() {
+        let mut sim = OpenLoopSimulation::builder().build().expect("build simulation");
+        let err = sim.run(0.1, 1.0).expect_err("expected uninitialized simulation");
+        assert!(matches!(err, Error::NotInitialized(_)));
+
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(f64::INFINITY, 0.0)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+        let err = sim.run(0.1, 1.0).expect_err("expected divergence");
+        assert!(matches!(err, Error::Diverged { time } if (time - 0.1).abs() < 1e-9));
+    }
+
+    #[test]
+    fn test_open_loop_stop_conditions() {
+        // 10 m/s around the 50 m center line radius: one lap in 10 * pi seconds
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .model(PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0))
+            .controls(0.0, 0.2)
+            .record_every(10)
+            .stop_when(StopCondition::NumLaps(1))
+            .build()
+            .expect("build simulation");
+        let result = sim.run(0.1, 100.0).expect("run");
+        assert_eq!(result.stop_reason(), Some(StopReason::NumLaps));
+        let lap_time = 10.0 * std::f64::consts::PI;
+        assert!((result.duration() - lap_time).abs() < 0.1, "{}", result.duration());
+
+        // Straight ahead off the circle, stopped on the third step off the track
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .controls(2.0, 0.0)
+            .stop_when(StopCondition::OffTrack { grace_steps: 2 })
+            .build()
+            .expect("build simulation");
+        let result = sim.run(0.1, 60.0).expect("run");
+        assert_eq!(result.stop_reason(), Some(StopReason::OffTrack));
+        let snapshots = result.snapshots();
+        let off = snapshots.iter().rev().take_while(|snapshot| !snapshot.in_track).count();
+        assert_eq!(off, 3);
+
+        sim.clear_stop_conditions();
+        let accelerated = StopCondition::<PointMassState>::custom(|snapshot| snapshot.state.vx >= 1.0);
+        sim.add_stop_condition(accelerated).expect("valid condition");
+        sim.reset();
+        let result = sim.run(0.1, 60.0).expect("run");
+        assert_eq!(result.stop_reason(), Some(StopReason::Custom));
+        assert!((result.duration() - 0.5).abs() < 1e-9);
+        assert!(sim.add_stop_condition(StopCondition::NumLaps(0)).is_err());
+
+        sim.clear_stop_conditions();
+        sim.reset();
+        assert_eq!(sim.run(0.1, 1.0).expect("run").stop_reason(), None);
+        assert!(OpenLoopSimulation::builder()
+            .stop_when(StopCondition::MaxTime(-1.0))
+            .build()
+            .is_err());
+    }
+
+    #[test]
+    fn test_open_loop_runs_on_any_track() {
+        let tracks: Vec<SharedTrack> = vec![
+            Arc::new(CircleTrack::new(50.0, 10.0, 100)),
+            Arc::new(SquareTrack::new(100.0, 10.0, 25)),
+        ];
+        for track in tracks {
+            let name = track.get_track_name().to_string();
+            let mut sim = OpenLoopSimulation::builder()
+                .controls(1.0, 0.0)
+                .track(track)
+                .build()
+                .expect("build simulation");
+
+            let result = sim.run(0.1, 1.0).expect("run");
+            assert_eq!(sim.track().expect("track").get_track_name(), name);
+            assert!(result.snapshots().iter().all(|snapshot| snapshot.in_track), "{name}");
+        }
+    }
+
+    #[test]
+    fn test_open_loop_run_into_reuses_result() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(1.0, 0.1)
+            .track(Arc::new(track))
+            .build()
+            .expect("build simulation");
+        let expected = sim.run(0.1, 1.0).expect("run");
+
+        let mut result = SimulationResult::for_run(0.1, 1.0);
+        let capacity = result.capacity();
+        for _ in 0..3 {
+            sim.reset();
+            sim.run_into(0.1, 1.0, &mut result).expect("run");
+            assert_eq!(result.len(), expected.len());
+            assert_eq!(result.capacity(), capacity);
+        }
+        let last = result.last().expect("last snapshot");
+        assert_eq!(last.state.x, expected.last().expect("last snapshot").state.x);
+    }
+
+    #[test]
+    fn test_open_loop_profile_follows_feature() {
+        let schedule =
+            ControlSchedule::parse_csv("time,ax,yaw_rate\n0.0,1.0,0.1\n").expect("parse schedule");
+        let mut sim = OpenLoopSimulation::builder()
+            .schedule(schedule)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+
+        let result = sim.run(0.1, 1.0).expect("run");
+        if cfg!(feature = "profiling") {
+            let profile = result.profile().expect("profile");
+            assert!(profile.model > Duration::ZERO && profile.track > Duration::ZERO);
+            assert_eq!(profile.rendering, Duration::ZERO);
+        } else {
+            assert!(result.profile().is_none());
+        }
+    }
+
+    #[test]
+    fn test_open_loop_run_spilled_matches_run() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let mut sim = OpenLoopSimulation::builder()
+            .controls(1.0, 0.1)
+            .track(Arc::clone(&track))
+            .build()
+            .expect("build simulation");
+        let expected = sim.run(0.1, 2.55).expect("run");
+
+        sim.reset();
+        let mut store = SpilledResult::create(temp_dir.path().join("run.spill"), 4)
+            .expect("create spill file");
+        sim.run_spilled(0.1, 2.55, &mut store).expect("spilled run");
+
+        assert_eq!(store.len(), expected.len());
+        let spilled = store.to_result().expect("read spilled snapshots");
+        for (snapshot, expected) in spilled.snapshots().iter().zip(expected.snapshots()) {
+            assert!((snapshot.time - expected.time).abs() < 1e-9);
+            assert!((snapshot.state.x - expected.state.x).abs() < 1e-9);
+            assert_eq!(snapshot.in_track, expected.in_track);
+        }
+        assert!((store.duration() - 2.55).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_open_loop_run_spilled_keeps_stop_conditions_across_chunks() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let lap = OpenLoopSimulation::builder()
+            .track(Arc::clone(&track))
+            .model(PointMass::with_initial_state(0.0, 0.0, 10.0, 0.0))
+            .controls(0.0, 0.2)
+            .record_every(3)
+            .stop_when(StopCondition::NumLaps(1));
+        // Off the track for 5 steps: the grace period spans several chunks
+        let off_track = OpenLoopSimulation::builder()
+            .track(Arc::clone(&track))
+            .controls(2.0, 0.0)
+            .stop_when(StopCondition::OffTrack { grace_steps: 4 });
+
+        for (builder, chunk_len) in [(lap, 4), (off_track, 2)] {
+            let mut sim = builder.build().expect("build simulation");
+            let expected = sim.run(0.1, 60.0).expect("run");
+            assert!(expected.stop_reason().is_some());
+
+            sim.reset();
+            let mut store = SpilledResult::create(temp_dir.path().join("run.spill"), chunk_len)
+                .expect("create spill file");
+            sim.run_spilled(0.1, 60.0, &mut store).expect("spilled run");
+            let spilled = store.to_result().expect("read spilled snapshots");
+            assert_eq!(spilled.snapshots(), expected.snapshots());
+        }
+    }
+
+    #[test]
+    fn test_open_loop_shares_track() {
+        let track: SharedTrack = Arc::new(CircleTrack::new(50.0, 10.0, 100));
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::clone(&track))
+            .build()
+            .expect("build simulation");
+
+        let shared = sim.shared_track().expect("shared track");
+        assert!(Arc::ptr_eq(&shared, &track));
+        assert_eq!(Arc::strong_count(&track), 3);
+
+        sim.clean();
+        assert_eq!(Arc::strong_count(&track), 2);
+    }
+
+    #[test]
+    fn test_open_loop_streams_telemetry() {
+        let listener = UdpSocket::bind("127.0.0.1:0").expect("bind listener");
+        listener
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .expect("set read timeout");
+        let telemetry = UdpTelemetry::new(listener.local_addr().expect("listener addr"))
+            .expect("create telemetry");
+        let mut sim = OpenLoopSimulation::builder()
+            .telemetry(telemetry)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation");
+
+        let result = sim.run(0.1, 0.3).expect("run");
+
+        let mut buffer = [0u8; 64];
+        for snapshot in result.snapshots() {
+            let len = listener.recv(&mut buffer).expect("receive packet");
+            let packet = TelemetryPacket::decode(&buffer[..len], TelemetryFormat::Binary)
+                .expect("decode packet");
+            assert_eq!(packet.time, snapshot.time);
+            assert_eq!(packet.x, snapshot.state.x);
+        }
+    }
 }
@@ -0,0 +1,571 @@
+//! Energy and performance reports comparing recorded runs lap by lap.
+//!
+//! Every complete lap of every run is summarized by its lap time, average speed,
+//! tractive energy, and fuel use, and split into equally long sectors along the track
+//! center line. Sector times are compared with the best time of the sector over all
+//! runs, so the report shows where each lap lost time, and what an ideal lap made of
+//! the best sectors would take. Laps and sectors are timed where the motion between two
+//! snapshots intersects the timing line across the track, so the times are accurate to
+//! well below the time step.
+//!
+//! Tractive energy is estimated from consecutive positions as the kinetic energy gained
+//! plus the work against rolling resistance and aerodynamic drag; braking is not
+//! recovered. Fuel use follows from the energy density of the fuel and the efficiency
+//! of the powertrain.
+
+use crate::error::{Error, Result};
+use crate::io::escape_html;
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::simulation::lap_analysis::{estimate_speeds, lap_progress, START_LINE_TOLERANCE};
+use crate::simulation::result::SimulationResult;
+use crate::tracks::arc_length::ArcLengthTable;
+use crate::tracks::base_track::Track;
+use crate::tracks::timing_line::TimingLine;
+use std::fmt;
+
+/// Vehicle mass in kilograms used when the builder does not set one
+pub const DEFAULT_MASS: f64 = 1200.0;
+/// Rolling resistance coefficient used when the builder does not set one
+pub const DEFAULT_ROLLING_RESISTANCE: f64 = 0.015;
+/// Drag coefficient times frontal area in m² used when the builder does not set one
+pub const DEFAULT_DRAG_AREA: f64 = 0.7;
+/// Energy density of gasoline in J/l
+pub const DEFAULT_ENERGY_DENSITY: f64 = 34.2e6;
+/// Share of the fuel energy reaching the wheels
+pub const DEFAULT_EFFICIENCY: f64 = 0.3;
+/// Number of sectors a lap is split into when the builder does not set one
+pub const DEFAULT_SECTORS: usize = 3;
+
+/// Gravitational acceleration in m/s²
+const GRAVITY: f64 = 9.81;
+/// Air density in kg/m³
+const AIR_DENSITY: f64 = 1.225;
+
+/// Summary of one complete lap
+#[derive(Debug, Clone, PartialEq)]
+pub struct LapPerformance {
+    /// Lap number, starting at 0
+    pub lap: usize,
+    /// Time of the start line crossing beginning the lap in seconds
+    pub start_time: f64,
+    /// Lap time in seconds
+    pub time: f64,
+    /// Lap length divided by the lap time in m/s
+    pub average_speed: f64,
+    /// Tractive energy spent in joules
+    pub energy: f64,
+    /// Fuel used in liters
+    pub fuel: f64,
+    /// Time spent in each sector in seconds
+    pub sector_times: Vec<f64>,
+    /// Time lost in each sector against the best time of the sector over all runs
+    pub sector_losses: Vec<f64>,
+    /// Time lost against the best lap over all runs in seconds
+    pub time_loss: f64,
+}
+
+/// Complete laps of one run
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunPerformance {
+    pub name: String,
+    pub laps: Vec<LapPerformance>,
+}
+
+impl RunPerformance {
+    /// Fastest complete lap of the run
+    pub fn best_lap(&self) -> Option<&LapPerformance> {
+        self.laps.iter().min_by(|a, b| a.time.total_cmp(&b.time))
+    }
+
+    /// Average lap time in seconds, `None` without complete laps
+    pub fn average_lap_time(&self) -> Option<f64> {
+        average(self.laps.iter().map(|lap| lap.time))
+    }
+
+    /// Average tractive energy per lap in joules, `None` without complete laps
+    pub fn average_energy(&self) -> Option<f64> {
+        average(self.laps.iter().map(|lap| lap.energy))
+    }
+
+    /// Fuel used over all complete laps in liters
+    pub fn total_fuel(&self) -> f64 {
+        self.laps.iter().map(|lap| lap.fuel).sum()
+    }
+}
+
+/// Lap, sector, and energy summary of one or more runs on the same track
+#[derive(Debug, Clone, PartialEq)]
+pub struct PerformanceReport {
+    /// Track name
+    pub track: String,
+    /// Length of the center line in meters
+    pub lap_length: f64,
+    /// Best time of each sector over all runs in seconds, `NaN` for sectors never completed
+    pub best_sectors: Vec<f64>,
+    pub runs: Vec<RunPerformance>,
+}
+
+impl PerformanceReport {
+    /// Start building a report for runs recorded on `track`
+    pub fn builder(track: &dyn Track) -> PerformanceReportBuilder {
+        PerformanceReportBuilder::new(track)
+    }
+
+    /// Whether any run completed a lap
+    pub fn has_laps(&self) -> bool {
+        self.runs.iter().any(|run| !run.laps.is_empty())
+    }
+
+    /// Fastest complete lap over all runs, with the name of its run
+    pub fn best_lap(&self) -> Option<(&str, &LapPerformance)> {
+        self.runs
+            .iter()
+            .filter_map(|run| run.best_lap().map(|lap| (run.name.as_str(), lap)))
+            .min_by(|a, b| a.1.time.total_cmp(&b.1.time))
+    }
+
+    /// Time in seconds of a lap made of the best sectors, `None` if a sector was never completed
+    pub fn ideal_lap_time(&self) -> Option<f64> {
+        if self.best_sectors.iter().any(|time| !time.is_finite()) {
+            return None;
+        }
+        Some(self.best_sectors.iter().sum())
+    }
+
+    /// HTML `<section>` with the run comparison and the lap table of every run
+    pub fn to_html_section(&self) -> String {
+        let mut html = String::new();
+        html.push_str("  <section class=\"performance\">\n");
+        html.push_str("  <h2>Performance</h2>\n");
+        html.push_str(&format!(
+            "  <p>{} ({:.1} m lap, {} sectors)</p>\n",
+            escape_html(&self.track),
+            self.lap_length,
+            self.best_sectors.len()
+        ));
+        if !self.has_laps() {
+            html.push_str("  <p>No run completed a lap.</p>\n");
+            html.push_str("  </section>\n");
+            return html;
+        }
+        if let Some(ideal) = self.ideal_lap_time() {
+            html.push_str(&format!("  <p>Ideal lap from the best sectors: {:.2} s</p>\n", ideal));
+        }
+
+        html.push_str("  <table class=\"runs\">\n");
+        html.push_str("    <tr><th>Run</th><th>Laps</th><th>Best lap (s)</th><th>Average lap (s)</th><th>Energy per lap (kJ)</th><th>Fuel (l)</th></tr>\n");
+        for run in &self.runs {
+            html.push_str(&format!(
+                "    <tr><th>{}</th><td>{}</td><td>{}</td><td>{}</td><td>{}</td><td>{:.3}</td></tr>\n",
+                escape_html(&run.name),
+                run.laps.len(),
+                cell(run.best_lap().map(|lap| lap.time)),
+                cell(run.average_lap_time()),
+                cell(run.average_energy().map(|energy| energy / 1000.0)),
+                run.total_fuel()
+            ));
+        }
+        html.push_str("  </table>\n");
+
+        for run in self.runs.iter().filter(|run| !run.laps.is_empty()) {
+            html.push_str(&format!("  <h3>{}</h3>\n", escape_html(&run.name)));
+            html.push_str("  <table class=\"laps\">\n");
+            html.push_str("    <tr><th>Lap</th><th>Time (s)</th><th>Loss (s)</th><th>Average speed (m/s)</th><th>Energy (kJ)</th><th>Fuel (l)</th>");
+            for sector in 1..=self.best_sectors.len() {
+                html.push_str(&format!("<th>S{} (s)</th>", sector));
+            }
+            html.push_str("</tr>\n");
+            for lap in &run.laps {
+                html.push_str(&format!(
+                    "    <tr><td>{}</td><td>{:.2}</td><td>{:+.2}</td><td>{:.2}</td><td>{:.1}</td><td>{:.3}</td>",
+                    lap.lap + 1,
+                    lap.time,
+                    lap.time_loss,
+                    lap.average_speed,
+                    lap.energy / 1000.0,
+                    lap.fuel
+                ));
+                for (time, loss) in lap.sector_times.iter().zip(&lap.sector_losses) {
+                    html.push_str(&format!("<td>{:.2} ({:+.2})</td>", time, loss));
+                }
+                html.push_str("</tr>\n");
+            }
+            html.push_str("  </table>\n");
+        }
+        html.push_str("  </section>\n");
+        html
+    }
+}
+
+impl fmt::Display for PerformanceReport {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(f, "Performance on {} ({:.1} m lap)", self.track, self.lap_length)?;
+        if let Some((name, lap)) = self.best_lap() {
+            write!(f, "\nBest lap: {:.2} s ({}, lap {})", lap.time, name, lap.lap + 1)?;
+        }
+        if let Some(ideal) = self.ideal_lap_time() {
+            write!(f, "\nIdeal lap: {:.2} s", ideal)?;
+        }
+        for run in &self.runs {
+            write!(f, "\n{}: {} complete laps", run.name, run.laps.len())?;
+            for lap in &run.laps {
+                write!(
+                    f,
+                    "\n  Lap {}: {:.2} s ({:+.2} s), {:.2} m/s, {:.1} kJ, {:.3} l, sectors",
+                    lap.lap + 1,
+                    lap.time,
+                    lap.time_loss,
+                    lap.average_speed,
+                    lap.energy / 1000.0,
+                    lap.fuel
+                )?;
+                for (time, loss) in lap.sector_times.iter().zip(&lap.sector_losses) {
+                    write!(f, " {:.2} s ({:+.2})", time, loss)?;
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+/// Builder of a `PerformanceReport`, created by `PerformanceReport::builder`
+#[derive(Debug, Clone)]
+pub struct PerformanceReportBuilder {
+    track: String,
+    center_line: ArcLengthTable,
+    origin: f64,
+    /// Track boundaries limiting the width of the timing lines
+    boundaries: (Vec<Vec2>, Vec<Vec2>),
+    mass: f64,
+    rolling_resistance: f64,
+    drag_area: f64,
+    energy_density: f64,
+    efficiency: f64,
+    sectors: usize,
+    runs: Vec<(String, Vec<Vec2>, Vec<f64>)>,
+}
+
+impl PerformanceReportBuilder {
+    fn new(track: &dyn Track) -> Self {
+        let center_line = ArcLengthTable::from_track(track);
+        let start = track.get_start_position();
+        let origin = center_line.project(start.0, start.1).unwrap_or(0.0);
+        Self {
+            track: track.get_track_name().to_string(),
+            center_line,
+            origin,
+            boundaries: (
+                track.get_inside_boundary().to_vec(),
+                track.get_outside_boundary().to_vec(),
+            ),
+            mass: DEFAULT_MASS,
+            rolling_resistance: DEFAULT_ROLLING_RESISTANCE,
+            drag_area: DEFAULT_DRAG_AREA,
+            energy_density: DEFAULT_ENERGY_DENSITY,
+            efficiency: DEFAULT_EFFICIENCY,
+            sectors: DEFAULT_SECTORS,
+            runs: Vec::new(),
+        }
+    }
+
+    /// Vehicle mass in kilograms
+    pub fn mass(mut self, mass: f64) -> Self {
+        self.mass = mass;
+        self
+    }
+
+    /// Rolling resistance coefficient and drag coefficient times frontal area in m²
+    pub fn resistance(mut self, rolling_resistance: f64, drag_area: f64) -> Self {
+        self.rolling_resistance = rolling_resistance;
+        self.drag_area = drag_area;
+        self
+    }
+
+    /// Energy density of the fuel in J/l and share of it reaching the wheels
+    pub fn fuel(mut self, energy_density: f64, efficiency: f64) -> Self {
+        self.energy_density = energy_density;
+        self.efficiency = efficiency;
+        self
+    }
+
+    /// Number of equally long sectors a lap is split into
+    pub fn sectors(mut self, sectors: usize) -> Self {
+        self.sectors = sectors;
+        self
+    }
+
+    /// Add a recorded run under `name`
+    pub fn run<S: Pose>(mut self, name: impl Into<String>, result: &SimulationResult<S>) -> Self {
+        let positions = result
+            .states()
+            .map(|state| Vec2::new(state.x(), state.y()))
+            .collect();
+        self.runs.push((name.into(), positions, result.times().collect()));
+        self
+    }
+
+    /// Summarize the complete laps of every run
+    ///
+    /// # Returns
+    /// The report, or `Error::InvalidConfig` if the track has no center line or a parameter
+    /// is not positive
+    pub fn build(self) -> Result<PerformanceReport> {
+        let total = self.center_line.total_length();
+        if self.center_line.len() < 3 || total <= 0.0 {
+            let message = format!("track '{}' has no center line to analyze", self.track);
+            return Err(Error::invalid_config(message));
+        }
+        if self.sectors == 0 {
+            return Err(Error::invalid_config("a lap needs at least one sector"));
+        }
+        for (name, value) in [
+            ("mass", self.mass),
+            ("energy density", self.energy_density),
+            ("efficiency", self.efficiency),
+        ] {
+            if value.is_nan() || value <= 0.0 {
+                let message = format!("{} must be positive, got {}", name, value);
+                return Err(Error::invalid_config(message));
+            }
+        }
+
+        let mut runs: Vec<RunPerformance> = self
+            .runs
+            .iter()
+            .map(|(name, positions, times)| RunPerformance {
+                name: name.clone(),
+                laps: self.laps(positions, times),
+            })
+            .collect();
+
+        let mut best_sectors = vec![f64::NAN; self.sectors];
+        for lap in runs.iter().flat_map(|run| run.laps.iter()) {
+            for (best, &time) in best_sectors.iter_mut().zip(&lap.sector_times) {
+                *best = best.min(time);
+            }
+        }
+        let best_lap = runs
+            .iter()
+            .flat_map(|run| run.laps.iter())
+            .map(|lap| lap.time)
+            .fold(f64::INFINITY, f64::min);
+        for lap in runs.iter_mut().flat_map(|run| run.laps.iter_mut()) {
+            lap.time_loss = lap.time - best_lap;
+            lap.sector_losses = lap
+                .sector_times
+                .iter()
+                .zip(&best_sectors)
+                .map(|(time, best)| time - best)
+                .collect();
+        }
+
+        Ok(PerformanceReport {
+            track: self.track,
+            lap_length: total,
+            best_sectors,
+            runs,
+        })
+    }
+
+    /// Complete laps of one run, with the time losses left to `build`
+    fn laps(&self, positions: &[Vec2], times: &[f64]) -> Vec<LapPerformance> {
+        if positions.len() < 2 {
+            return Vec::new();
+        }
+        let total = self.center_line.total_length();
+        let progress = lap_progress(&self.center_line, self.origin, positions);
+        let speeds = estimate_speeds(positions, times);
+
+        // Time the run crossed the timing line `distance` along the unwrapped progress.
+        // The progress finds the step of the crossing (and tells laps apart); the motion
+        // segments around it are intersected with the line to time it within the step.
+        let crossing = |distance: f64| {
+            if progress[0] >= distance {
+                return (progress[0] - distance <= START_LINE_TOLERANCE).then_some(times[0]);
+            }
+            let step = (1..progress.len())
+                .find(|&i| progress[i - 1] < distance && progress[i] >= distance)?;
+            let interpolate = |i: usize, ratio: f64| times[i - 1] + ratio * (times[i] - times[i - 1]);
+            let line = TimingLine::spanning(
+                &self.center_line,
+                self.origin + distance,
+                &self.boundaries.0,
+                &self.boundaries.1,
+            );
+            let intersected = line.and_then(|line| {
+                (step.saturating_sub(1).max(1)..=(step + 1).min(progress.len() - 1)).find_map(|i| {
+                    line.crossing(positions[i - 1], positions[i])
+                        .map(|fraction| interpolate(i, fraction))
+                })
+            });
+            // Cutting across beside the line still counts, timed by the progress
+            Some(intersected.unwrap_or_else(|| {
+                let ratio = (distance - progress[step - 1]) / (progress[step] - progress[step - 1]);
+                interpolate(step, ratio)
+            }))
+        };
+        let resistance = self.rolling_resistance * self.mass * GRAVITY;
+
+        let furthest = progress.iter().copied().fold(f64::NEG_INFINITY, f64::max);
+        let mut laps = Vec::new();
+        for lap in 0..(furthest / total).floor().max(0.0) as usize {
+            let boundaries: Option<Vec<f64>> = (0..=self.sectors)
+                .map(|sector| crossing((lap as f64 + sector as f64 / self.sectors as f64) * total))
+                .collect();
+            let Some(boundaries) = boundaries else {
+                continue;
+            };
+            let (start_time, end_time) = (boundaries[0], boundaries[self.sectors]);
+            let energy: f64 = (1..positions.len())
+                .filter(|&i| times[i - 1] >= start_time && times[i] <= end_time)
+                .map(|i| {
+                    let (v0, v1) = (speeds[i - 1], speeds[i]);
+                    let v = 0.5 * (v0 + v1);
+                    let drag = 0.5 * AIR_DENSITY * self.drag_area * v * v;
+                    let work = 0.5 * self.mass * (v1 * v1 - v0 * v0)
+                        + (resistance + drag) * positions[i - 1].distance(positions[i]);
+                    work.max(0.0)
+                })
+                .sum();
+            let time = end_time - start_time;
+            laps.push(LapPerformance {
+                lap,
+                start_time,
+                time,
+                average_speed: if time > 0.0 { total / time } else { 0.0 },
+                energy,
+                fuel: energy / (self.efficiency * self.energy_density),
+                sector_times: boundaries.windows(2).map(|pair| pair[1] - pair[0]).collect(),
+                sector_losses: Vec::new(),
+                time_loss: 0.0,
+            });
+        }
+        laps
+    }
+}
+
+fn average(values: impl Iterator<Item = f64>) -> Option<f64> {
+    let (sum, count) = values.fold((0.0, 0usize), |(sum, count), value| (sum + value, count + 1));
+    (count > 0).then(|| sum / count as f64)
+}
+
+fn cell(value: Option<f64>) -> String {
+    value.map_or_else(|| "-".to_string(), |value| format!("{:.2}", value))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PerformanceReport;
+    use crate::simulation::result::SimulationResult;
+    use crate::tracks::arc_length::ArcLengthTable;
+    use crate::tracks::square::SquareTrack;
+
+    /// Drive the square's center line at `speed(s)` for `laps` laps
+    fn drive(
+        track: &SquareTrack,
+        laps: f64,
+        speed: impl Fn(f64) -> f64,
+    ) -> SimulationResult<(f64, f64, f64)> {
+        drive_with_step(track, laps, 0.05, speed)
+    }
+
+    fn drive_with_step(
+        track: &SquareTrack,
+        laps: f64,
+        dt: f64,
+        speed: impl Fn(f64) -> f64,
+    ) -> SimulationResult<(f64, f64, f64)> {
+        let center_line = ArcLengthTable::from_track(track);
+        let mut result = SimulationResult::new();
+        let (mut s, mut time) = (0.0, 0.0);
+        while s <= laps * 400.0 {
+            result.push(time, center_line.point_at(s).expect("point"), true);
+            s += speed(s) * dt;
+            time += dt;
+        }
+        result
+    }
+
+    #[test]
+    fn test_constant_speed_laps() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let result = drive(&track, 2.5, |_| 10.0);
+        let report = PerformanceReport::builder(&track)
+            .sectors(4)
+            .run("steady", &result)
+            .build()
+            .expect("report");
+
+        let run = &report.runs[0];
+        assert_eq!(run.laps.len(), 2);
+        for lap in &run.laps {
+            assert!((lap.time - 40.0).abs() < 0.1, "{lap:?}");
+            assert!((lap.average_speed - 10.0).abs() < 0.05);
+            assert!(lap.sector_times.iter().all(|time| (time - 10.0).abs() < 0.1));
+            // Rolling resistance and drag only: (0.015 * 1200 * 9.81 + 0.6125 * 0.7 * 100) * 400 m
+            assert!((lap.energy - 87_784.0).abs() < 2000.0, "{}", lap.energy);
+            assert!(lap.fuel > 0.0);
+        }
+        assert!((report.ideal_lap_time().expect("ideal lap") - 40.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_timing_is_independent_of_step() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        // 7.3 m per step: no snapshot lands on a timing line after the start
+        let result = drive_with_step(&track, 2.5, 0.73, |_| 10.0);
+        let report = PerformanceReport::builder(&track)
+            .sectors(4)
+            .run("coarse", &result)
+            .build()
+            .expect("report");
+
+        let run = &report.runs[0];
+        assert_eq!(run.laps.len(), 2);
+        for lap in &run.laps {
+            assert!((lap.time - 40.0).abs() < 1e-6, "{lap:?}");
+            assert!(lap.sector_times.iter().all(|time| (time - 10.0).abs() < 1e-6));
+        }
+        assert!((run.laps[1].start_time - 40.0).abs() < 1e-6);
+    }
+
+    #[test]
+    fn test_compare_strategies() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let steady = drive(&track, 2.2, |_| 10.0);
+        // Faster on the straights of the first half lap, same pace elsewhere
+        let push = drive(&track, 2.2, |s| if s.rem_euclid(400.0) < 200.0 { 20.0 } else { 10.0 });
+        let report = PerformanceReport::builder(&track)
+            .sectors(2)
+            .run("steady", &steady)
+            .run("push", &push)
+            .build()
+            .expect("report");
+
+        let (name, best) = report.best_lap().expect("best lap");
+        assert_eq!(name, "push");
+        assert!((best.time - 30.0).abs() < 0.2, "{best:?}");
+        let steady_lap = &report.runs[0].laps[0];
+        assert!((steady_lap.time_loss - 10.0).abs() < 0.2);
+        assert!((steady_lap.sector_losses[0] - 10.0).abs() < 0.2);
+        assert!(steady_lap.sector_losses[1].abs() < 0.2);
+        // Accelerating to 20 m/s and holding it costs more energy
+        assert!(report.runs[1].average_energy() > report.runs[0].average_energy());
+
+        let html = report.to_html_section();
+        assert!(html.contains("<h2>Performance</h2>"));
+        assert!(html.contains("<th>push</th>"));
+        assert!(report.to_string().contains("Best lap: "));
+    }
+
+    #[test]
+    fn test_invalid_parameters() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let err = PerformanceReport::builder(&track).sectors(0).build().expect_err("no sectors");
+        assert!(err.to_string().contains("sector"));
+        let err = PerformanceReport::builder(&track).mass(-1.0).build().expect_err("mass");
+        assert!(err.to_string().contains("mass"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+    }
+}
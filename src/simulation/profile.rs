@@ -0,0 +1,98 @@
+//! Per-run timing breakdown (`profiling` feature).
+//!
+//! With the feature enabled, simulations time model stepping, controller stepping, and
+//! track queries and attach the totals to the `SimulationResult`. Without it, `timed`
+//! compiles to a plain call and results carry no profile.
+
+use std::fmt;
+use std::time::Duration;
+
+/// Time spent in each part of a run
+#[derive(Debug, Clone, Copy, Default, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct RunProfile {
+    /// Model stepping
+    pub model: Duration,
+    /// Controller stepping (scripted schedules, feedback controllers)
+    pub controller: Duration,
+    /// Track queries (containment checks)
+    pub track: Duration,
+    /// Plotting and video rendering of the result
+    pub rendering: Duration,
+}
+
+impl RunProfile {
+    /// Total time over all parts
+    pub fn total(&self) -> Duration {
+        self.model + self.controller + self.track + self.rendering
+    }
+
+    /// Add the times of `other` (e.g., of a later segment of the same run)
+    pub fn add(&mut self, other: &RunProfile) {
+        self.model += other.model;
+        self.controller += other.controller;
+        self.track += other.track;
+        self.rendering += other.rendering;
+    }
+}
+
+impl fmt::Display for RunProfile {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let millis = |duration: Duration| duration.as_secs_f64() * 1000.0;
+        write!(
+            f,
+            concat!(
+                "model {:.3} ms, controller {:.3} ms, track {:.3} ms, ",
+                "rendering {:.3} ms (total {:.3} ms)"
+            ),
+            millis(self.model),
+            millis(self.controller),
+            millis(self.track),
+            millis(self.rendering),
+            millis(self.total())
+        )
+    }
+}
+
+/// Run `f`, adding its duration to `slot` when profiling is enabled
+#[cfg(feature = "profiling")]
+pub fn timed<R>(slot: &mut Duration, f: impl FnOnce() -> R) -> R {
+    let start = std::time::Instant::now();
+    let value = f();
+    *slot += start.elapsed();
+    value
+}
+
+/// Run `f`, adding its duration to `slot` when profiling is enabled
+#[cfg(not(feature = "profiling"))]
+pub fn timed<R>(_slot: &mut Duration, f: impl FnOnce() -> R) -> R {
+    f()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{timed, RunProfile};
+    use std::time::Duration;
+
+    #[test]
+    fn test_run_profile_totals() {
+        let mut profile = RunProfile {
+            model: Duration::from_millis(2),
+            controller: Duration::from_millis(1),
+            ..RunProfile::default()
+        };
+        profile.add(&RunProfile {
+            track: Duration::from_millis(3),
+            rendering: Duration::from_millis(4),
+            ..RunProfile::default()
+        });
+        assert_eq!(profile.total(), Duration::from_millis(10));
+        assert!(profile
+            .to_string()
+            .starts_with("model 2.000 ms, controller 1.000 ms"));
+
+        let value = timed(&mut profile.model, || 42);
+        assert_eq!(value, 42);
+        assert!(profile.model >= Duration::from_millis(2));
+    }
+}
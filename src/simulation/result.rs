@@ -0,0 +1,427 @@
+use super::columnar::COLUMNAR_CSV_HEADER;
+use super::profile::RunProfile;
+use super::stop::StopReason;
+use crate::io::compression;
+use crate::models::point_mass::PointMassState;
+use crate::models::pose::Pose;
+use crate::models::scalar::Float;
+use crate::provenance::Provenance;
+use std::io::{self, Write};
+use std::path::Path;
+
+/// Model state recorded at a point in time during a simulation run
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct StateSnapshot<S> {
+    /// Simulation time in seconds
+    pub time: f64,
+    /// Model state at `time`
+    pub state: S,
+    /// Whether the model position was inside the track boundaries
+    pub in_track: bool,
+}
+
+/// Recorded trajectory of a simulation run
+///
+/// Results compare equal when their snapshots are equal; the profile is ignored, so
+/// repeated runs of the same scenario compare equal with the `profiling` feature too.
+#[derive(Debug, Clone)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub struct SimulationResult<S> {
+    snapshots: Vec<StateSnapshot<S>>,
+    /// Timing breakdown of the run, recorded with the `profiling` feature
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    profile: Option<RunProfile>,
+    /// Times of the snapshots reached by a step whose controls hit the model limits
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Vec::is_empty"))]
+    saturated: Vec<f64>,
+    /// Stop condition that ended the run before its duration elapsed
+    #[cfg_attr(feature = "serde", serde(default, skip_serializing_if = "Option::is_none"))]
+    stop_reason: Option<StopReason>,
+}
+
+impl<S> SimulationResult<S> {
+    /// Create an empty result
+    pub fn new() -> Self {
+        Self {
+            snapshots: Vec::new(),
+            profile: None,
+            saturated: Vec::new(),
+            stop_reason: None,
+        }
+    }
+
+    /// Create an empty result with room for `capacity` snapshots
+    pub fn with_capacity(capacity: usize) -> Self {
+        Self {
+            snapshots: Vec::with_capacity(capacity),
+            profile: None,
+            saturated: Vec::new(),
+            stop_reason: None,
+        }
+    }
+
+    /// Create an empty result with room for every snapshot of a run
+    ///
+    /// # Arguments
+    /// * `dt` - Time step in seconds
+    /// * `duration` - Run duration in seconds
+    pub fn for_run(dt: f64, duration: f64) -> Self {
+        Self::with_capacity(snapshot_count(dt, duration))
+    }
+
+    /// Remove all snapshots, the profile, the saturation record, and the stop reason,
+    /// keeping the allocated storage for the next run
+    pub fn clear(&mut self) {
+        self.snapshots.clear();
+        self.profile = None;
+        self.saturated.clear();
+        self.stop_reason = None;
+    }
+
+    /// Make room for at least `additional` more snapshots
+    pub fn reserve(&mut self, additional: usize) {
+        self.snapshots.reserve(additional);
+    }
+
+    /// Number of snapshots the result can hold without reallocating
+    pub fn capacity(&self) -> usize {
+        self.snapshots.capacity()
+    }
+
+    /// Append a snapshot to the result
+    pub fn push(&mut self, time: f64, state: S, in_track: bool) {
+        self.snapshots.push(StateSnapshot {
+            time,
+            state,
+            in_track,
+        });
+    }
+
+    /// Number of recorded snapshots
+    pub fn len(&self) -> usize {
+        self.snapshots.len()
+    }
+
+    /// Whether no snapshots were recorded
+    pub fn is_empty(&self) -> bool {
+        self.snapshots.is_empty()
+    }
+
+    /// All recorded snapshots in time order
+    pub fn snapshots(&self) -> &[StateSnapshot<S>] {
+        &self.snapshots
+    }
+
+    /// Iterate over the recorded states in time order
+    pub fn states(&self) -> impl Iterator<Item = &S> + '_ {
+        self.snapshots.iter().map(|snapshot| &snapshot.state)
+    }
+
+    /// Iterate over the snapshot times in seconds
+    pub fn times(&self) -> impl Iterator<Item = f64> + '_ {
+        self.snapshots.iter().map(|snapshot| snapshot.time)
+    }
+
+    /// First recorded snapshot, if any
+    pub fn first(&self) -> Option<&StateSnapshot<S>> {
+        self.snapshots.first()
+    }
+
+    /// Last recorded snapshot, if any
+    pub fn last(&self) -> Option<&StateSnapshot<S>> {
+        self.snapshots.last()
+    }
+
+    /// Timing breakdown of the run, if it was profiled (`profiling` feature)
+    pub fn profile(&self) -> Option<&RunProfile> {
+        self.profile.as_ref()
+    }
+
+    pub fn set_profile(&mut self, profile: Option<RunProfile>) {
+        self.profile = profile;
+    }
+
+    /// Record that the step reaching the snapshot at `time` had saturated controls
+    pub fn mark_saturated(&mut self, time: f64) {
+        self.saturated.push(time);
+    }
+
+    /// Times of the snapshots reached by a step whose controls hit the model limits
+    /// (see `Model::saturation`), in time order
+    pub fn saturated_times(&self) -> &[f64] {
+        &self.saturated
+    }
+
+    /// Stop condition that ended the run early, or `None` if it ran for its full duration
+    pub fn stop_reason(&self) -> Option<StopReason> {
+        self.stop_reason
+    }
+
+    pub fn set_stop_reason(&mut self, reason: Option<StopReason>) {
+        self.stop_reason = reason;
+    }
+
+    /// Time of the last snapshot in seconds (0 for an empty result)
+    pub fn duration(&self) -> f64 {
+        self.snapshots.last().map_or(0.0, |snapshot| snapshot.time)
+    }
+}
+
+impl<T: Float> SimulationResult<PointMassState<T>> {
+    /// Write the snapshots to a CSV file with `time,x,y,vx,vy,yaw,in_track` columns
+    ///
+    /// One row per snapshot, `in_track` written as 0 or 1, after a `# provenance:`
+    /// comment line with the crate version and git commit; the format matches
+    /// `ColumnarResult::write_csv`, so spreadsheets and pandas (`comment="#"`) read
+    /// either directly.
+    pub fn write_csv<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        self.write_csv_with_provenance(path, &Provenance::current())
+    }
+
+    /// Write the snapshots as `write_csv` does, with the scenario parameters and seed
+    /// of `provenance` in the comment line
+    pub fn write_csv_with_provenance<P: AsRef<Path>>(
+        &self,
+        path: P,
+        provenance: &Provenance,
+    ) -> crate::Result<()> {
+        write_csv_file(path.as_ref(), provenance, COLUMNAR_CSV_HEADER, |writer| {
+            for StateSnapshot { time, state, in_track } in &self.snapshots {
+                writeln!(
+                    writer,
+                    "{},{},{},{},{},{},{}",
+                    time,
+                    state.x,
+                    state.y,
+                    state.vx,
+                    state.vy,
+                    state.yaw,
+                    u8::from(*in_track)
+                )?;
+            }
+            Ok(())
+        })
+    }
+}
+
+impl<S: Pose> SimulationResult<S> {
+    /// Write the pose of every snapshot to a CSV file with `POSE_CSV_HEADER` columns
+    ///
+    /// Same layout as `write_csv_with_provenance` (provenance comment, `in_track` as 0
+    /// or 1) for any state implementing `Pose`; the velocities are left out since not
+    /// every state has them.
+    pub fn write_pose_csv<P: AsRef<Path>>(
+        &self,
+        path: P,
+        provenance: &Provenance,
+    ) -> crate::Result<()> {
+        write_csv_file(path.as_ref(), provenance, POSE_CSV_HEADER, |writer| {
+            for StateSnapshot { time, state, in_track } in &self.snapshots {
+                let (x, y, yaw) = state.pose();
+                writeln!(writer, "{},{},{},{},{}", time, x, y, yaw, u8::from(*in_track))?;
+            }
+            Ok(())
+        })
+    }
+}
+
+/// Header line written by `SimulationResult::write_pose_csv`
+pub const POSE_CSV_HEADER: &str = "time,x,y,yaw,in_track";
+
+/// Write a CSV file: the provenance comment line, `header`, then the rows from `rows`
+///
+/// The file is written with `io::compression::write_file`, so parent directories are
+/// created and a `.gz` or `.zst` extension compresses it.
+pub(crate) fn write_csv_file<F>(
+    path: &Path,
+    provenance: &Provenance,
+    header: &str,
+    rows: F,
+) -> crate::Result<()>
+where
+    F: FnOnce(&mut dyn Write) -> io::Result<()>,
+{
+    let mut csv = Vec::new();
+    writeln!(csv, "{}", provenance.csv_comment())?;
+    writeln!(csv, "{}", header)?;
+    rows(&mut csv)?;
+    compression::write_file(path, &csv)
+}
+
+#[cfg(feature = "serde")]
+impl<S: serde::Serialize + serde::de::DeserializeOwned> SimulationResult<S> {
+    /// Save the result as JSON (see `io::json::save_json`) for re-plotting or regression
+    /// comparison without re-running the simulation
+    pub fn save_json<P: AsRef<Path>>(&self, path: P) -> crate::Result<()> {
+        crate::io::json::save_json(self, path)
+    }
+
+    /// Load a result written by `save_json`
+    pub fn load_json<P: AsRef<Path>>(path: P) -> crate::Result<Self> {
+        crate::io::json::load_json(path)
+    }
+}
+
+/// Number of snapshots recorded by a run: the start, one per full step, and a final
+/// partial step when `duration` is not a multiple of `dt`
+pub fn snapshot_count(dt: f64, duration: f64) -> usize {
+    let (steps, remaining) = step_plan(dt, duration);
+    1 + steps + usize::from(remaining > 0.0)
+}
+
+/// Split a run of `duration` seconds into full steps of `dt` and a final partial step
+///
+/// A quotient within `1e-9` of the next integer counts as that many full steps, so
+/// e.g. 0.3 s at 0.1 s steps is three full steps instead of two and a sliver, whatever
+/// the rounding of the division. Runs use this plan and compute step times as
+/// `step * dt` instead of accumulating `dt`, which keeps traces reproducible.
+///
+/// # Returns
+/// (number of full steps, length of the partial step or 0)
+pub(crate) fn step_plan(dt: f64, duration: f64) -> (usize, f64) {
+    if !(dt > 0.0 && duration > 0.0 && dt.is_finite() && duration.is_finite()) {
+        return (0, 0.0);
+    }
+    let ratio = duration / dt;
+    let mut steps = ratio.floor();
+    if ratio - steps > 1.0 - 1e-9 {
+        steps += 1.0;
+    }
+    let remaining = duration - steps * dt;
+    (steps as usize, if remaining > dt * 1e-9 { remaining } else { 0.0 })
+}
+
+impl<S: PartialEq> PartialEq for SimulationResult<S> {
+    fn eq(&self, other: &Self) -> bool {
+        self.snapshots == other.snapshots
+    }
+}
+
+impl<S> Default for SimulationResult<S> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<S> FromIterator<StateSnapshot<S>> for SimulationResult<S> {
+    fn from_iter<I: IntoIterator<Item = StateSnapshot<S>>>(iter: I) -> Self {
+        Self {
+            snapshots: iter.into_iter().collect(),
+            profile: None,
+            saturated: Vec::new(),
+            stop_reason: None,
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{snapshot_count, step_plan, SimulationResult};
+    use crate::models::point_mass::PointMassState;
+    use crate::provenance::Provenance;
+
+    #[test]
+    fn test_simulation_result_push_and_access() {
+        let mut result = SimulationResult::new();
+        assert!(result.is_empty());
+        assert_eq!(result.duration(), 0.0);
+
+        result.push(0.0, 1.0f64, true);
+        result.push(0.1, 2.0, false);
+
+        assert_eq!(result.len(), 2);
+        assert_eq!(result.states().copied().collect::<Vec<_>>(), vec![1.0, 2.0]);
+        assert_eq!(result.times().collect::<Vec<_>>(), vec![0.0, 0.1]);
+        assert!(!result.last().expect("last snapshot").in_track);
+        assert!((result.duration() - 0.1).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_simulation_result_reuses_storage() {
+        assert_eq!(snapshot_count(0.1, 1.0), 11);
+        assert_eq!(snapshot_count(0.1, 0.25), 4);
+        assert_eq!(snapshot_count(0.0, 1.0), 1);
+
+        let mut result = SimulationResult::for_run(0.1, 1.0);
+        let capacity = result.capacity();
+        assert!(capacity >= 11);
+        for step in 0..11 {
+            result.push(step as f64 * 0.1, step, true);
+        }
+        result.clear();
+        assert!(result.is_empty());
+        assert_eq!(result.capacity(), capacity);
+    }
+
+    #[test]
+    fn test_step_plan_snaps_to_whole_steps() {
+        assert_eq!(step_plan(0.1, 0.3), (3, 0.0));
+        assert_eq!(step_plan(0.1, 1.0), (10, 0.0));
+        let (steps, remaining) = step_plan(0.1, 0.25);
+        assert_eq!(steps, 2);
+        assert!((remaining - 0.05).abs() < 1e-12);
+        assert_eq!(step_plan(f64::NAN, 1.0), (0, 0.0));
+    }
+
+    #[test]
+    fn test_simulation_result_write_csv() {
+        let mut result = SimulationResult::new();
+        let state = PointMassState {
+            x: 1.0,
+            y: -2.0,
+            vx: 3.0,
+            vy: 0.0,
+            yaw: 0.5,
+        };
+        result.push(0.0, state.clone(), true);
+        result.push(0.1, PointMassState { x: 1.3, ..state }, false);
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("run.csv");
+        result.write_csv(&path).expect("write csv");
+        let csv = std::fs::read_to_string(&path).expect("read csv");
+        let mut lines = csv.lines();
+        assert_eq!(lines.next(), Some(Provenance::current().csv_comment().as_str()));
+        assert_eq!(
+            lines.collect::<Vec<_>>(),
+            ["time,x,y,vx,vy,yaw,in_track", "0,1,-2,3,0,0.5,1", "0.1,1.3,-2,3,0,0.5,0"]
+        );
+        let provenance = Provenance::current().with_seed(3);
+        result.write_csv_with_provenance(&path, &provenance).expect("write csv");
+        let csv = std::fs::read_to_string(&path).expect("read csv");
+        assert!(csv.starts_with(&format!("{}\n", provenance.csv_comment())));
+        result.write_pose_csv(&path, &provenance).expect("write pose csv");
+        let csv = std::fs::read_to_string(&path).expect("read csv");
+        assert_eq!(
+            csv.lines().skip(1).collect::<Vec<_>>(),
+            ["time,x,y,yaw,in_track", "0,1,-2,0.5,1", "0.1,1.3,-2,0.5,0"]
+        );
+        let nested = temp_dir.path().join("missing").join("run.csv");
+        result.write_csv(&nested).expect("write csv to a new directory");
+        assert_eq!(std::fs::read_to_string(&nested).expect("read csv").lines().count(), 4);
+    }
+
+    #[cfg(feature = "serde")]
+    #[test]
+    fn test_simulation_result_json_round_trip() {
+        let mut result = SimulationResult::new();
+        let state = PointMassState {
+            x: 1.0,
+            y: -2.0,
+            vx: 3.0,
+            vy: 0.0,
+            yaw: 0.5,
+        };
+        result.push(0.0, state.clone(), true);
+        result.push(0.1, PointMassState { x: 1.3, ..state }, false);
+        result.mark_saturated(0.1);
+
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("run.json");
+        result.save_json(&path).expect("save json");
+        let loaded = SimulationResult::<PointMassState>::load_json(&path).expect("load json");
+        assert_eq!(loaded, result);
+        assert_eq!(loaded.saturated_times(), [0.1]);
+    }
+}
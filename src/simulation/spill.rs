@@ -0,0 +1,352 @@
+//! Disk-backed snapshot storage for very long runs.
+//!
+//! `SpilledResult` keeps at most one chunk of snapshots in memory and appends full
+//! chunks to a file as fixed-size little-endian records (`time`, `in_track`, then the
+//! state encoded by `SpillState`), so memory stays bounded however long the run is.
+//! Iteration reads the file back chunk by chunk, followed by the in-memory tail.
+
+use super::result::{SimulationResult, StateSnapshot};
+use crate::models::dynamic_bicycle::DynamicBicycleState;
+use crate::models::point_mass::PointMassState;
+use crate::models::scalar::Float;
+use std::fs::{self, File, OpenOptions};
+use std::io::{self, BufReader, Read, Write};
+use std::path::{Path, PathBuf};
+
+/// Size of the record header: the time (`f64`) and the `in_track` flag
+const HEADER_SIZE: usize = 8 + 1;
+
+/// State that can be written to disk as a fixed number of bytes
+pub trait SpillState: Sized {
+    /// Number of bytes written by `encode`
+    const ENCODED_SIZE: usize;
+
+    /// Append exactly `ENCODED_SIZE` bytes to `out`
+    fn encode(&self, out: &mut Vec<u8>);
+
+    /// Decode a state from `ENCODED_SIZE` bytes written by `encode`
+    fn decode(bytes: &[u8]) -> Self;
+}
+
+impl<T: Float> SpillState for PointMassState<T> {
+    const ENCODED_SIZE: usize = 5 * 8;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        for value in [self.x, self.y, self.vx, self.vy, self.yaw] {
+            out.extend_from_slice(&value.to_f64().to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        let value = |index: usize| T::from_f64(read_f64(bytes, index * 8));
+        Self {
+            x: value(0),
+            y: value(1),
+            vx: value(2),
+            vy: value(3),
+            yaw: value(4),
+        }
+    }
+}
+
+impl SpillState for DynamicBicycleState {
+    const ENCODED_SIZE: usize = 9 * 8;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        for value in [
+            self.x,
+            self.y,
+            self.vx,
+            self.vy,
+            self.yaw,
+            self.yaw_rate,
+            self.steering,
+            self.slip_front,
+            self.slip_rear,
+        ] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        let value = |index: usize| read_f64(bytes, index * 8);
+        Self {
+            x: value(0),
+            y: value(1),
+            vx: value(2),
+            vy: value(3),
+            yaw: value(4),
+            yaw_rate: value(5),
+            steering: value(6),
+            slip_front: value(7),
+            slip_rear: value(8),
+        }
+    }
+}
+
+impl SpillState for (f64, f64, f64) {
+    const ENCODED_SIZE: usize = 3 * 8;
+
+    fn encode(&self, out: &mut Vec<u8>) {
+        for value in [self.0, self.1, self.2] {
+            out.extend_from_slice(&value.to_le_bytes());
+        }
+    }
+
+    fn decode(bytes: &[u8]) -> Self {
+        (read_f64(bytes, 0), read_f64(bytes, 8), read_f64(bytes, 16))
+    }
+}
+
+fn read_f64(bytes: &[u8], offset: usize) -> f64 {
+    let mut raw = [0u8; 8];
+    raw.copy_from_slice(&bytes[offset..offset + 8]);
+    f64::from_le_bytes(raw)
+}
+
+/// Snapshots of a run, spilled to a file once more than a chunk is recorded
+///
+/// The file is created by `create` and removed when the result is dropped.
+pub struct SpilledResult<S: SpillState> {
+    path: PathBuf,
+    file: File,
+    chunk_len: usize,
+    /// Snapshots not yet written to the file
+    tail: Vec<StateSnapshot<S>>,
+    spilled: usize,
+    last: Option<StateSnapshot<S>>,
+}
+
+impl<S: SpillState + Clone> SpilledResult<S> {
+    /// Create an empty result backed by the file at `path`
+    ///
+    /// # Arguments
+    /// * `path` - Spill file, created or truncated (e.g., "results/run.spill")
+    /// * `chunk_len` - Snapshots kept in memory before they are written to the file
+    pub fn create<P: AsRef<Path>>(path: P, chunk_len: usize) -> io::Result<Self> {
+        if chunk_len == 0 {
+            return Err(io::Error::new(
+                io::ErrorKind::InvalidInput,
+                "chunk length must be greater than zero",
+            ));
+        }
+        let path = path.as_ref().to_path_buf();
+        let file = OpenOptions::new()
+            .create(true)
+            .write(true)
+            .truncate(true)
+            .open(&path)?;
+        Ok(Self {
+            path,
+            file,
+            chunk_len,
+            tail: Vec::with_capacity(chunk_len),
+            spilled: 0,
+            last: None,
+        })
+    }
+
+    /// Path of the spill file
+    pub fn path(&self) -> &Path {
+        &self.path
+    }
+
+    /// Snapshots kept in memory before they are written to the file
+    pub fn chunk_len(&self) -> usize {
+        self.chunk_len
+    }
+
+    /// Append a snapshot, writing the buffered chunk to the file when it is full
+    pub fn push(&mut self, time: f64, state: S, in_track: bool) -> io::Result<()> {
+        let snapshot = StateSnapshot {
+            time,
+            state,
+            in_track,
+        };
+        self.last = Some(snapshot.clone());
+        self.tail.push(snapshot);
+        if self.tail.len() >= self.chunk_len {
+            self.flush()?;
+        }
+        Ok(())
+    }
+
+    /// Write the buffered snapshots to the file
+    pub fn flush(&mut self) -> io::Result<()> {
+        if self.tail.is_empty() {
+            return Ok(());
+        }
+        let mut bytes = Vec::with_capacity(self.tail.len() * (HEADER_SIZE + S::ENCODED_SIZE));
+        for snapshot in &self.tail {
+            bytes.extend_from_slice(&snapshot.time.to_le_bytes());
+            bytes.push(u8::from(snapshot.in_track));
+            snapshot.state.encode(&mut bytes);
+        }
+        self.file.write_all(&bytes)?;
+        self.file.flush()?;
+        self.spilled += self.tail.len();
+        self.tail.clear();
+        Ok(())
+    }
+
+    /// Number of recorded snapshots, on disk and in memory
+    pub fn len(&self) -> usize {
+        self.spilled + self.tail.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Last recorded snapshot, if any
+    pub fn last(&self) -> Option<&StateSnapshot<S>> {
+        self.last.as_ref()
+    }
+
+    /// Time of the last snapshot in seconds (0 for an empty result)
+    pub fn duration(&self) -> f64 {
+        self.last.as_ref().map_or(0.0, |snapshot| snapshot.time)
+    }
+
+    /// Iterate over all snapshots in time order, reading the file chunk by chunk
+    pub fn snapshots(&self) -> io::Result<SpilledSnapshots<'_, S>> {
+        Ok(SpilledSnapshots {
+            reader: BufReader::new(File::open(&self.path)?),
+            remaining: self.spilled,
+            chunk: Vec::new(),
+            tail: self.tail.iter(),
+            chunk_len: self.chunk_len,
+        })
+    }
+
+    /// Iterate over the recorded states in time order
+    pub fn states(&self) -> io::Result<impl Iterator<Item = io::Result<S>> + '_> {
+        Ok(self
+            .snapshots()?
+            .map(|snapshot| snapshot.map(|snapshot| snapshot.state)))
+    }
+
+    /// Load every snapshot into an in-memory result
+    pub fn to_result(&self) -> io::Result<SimulationResult<S>> {
+        let snapshots: Vec<StateSnapshot<S>> = self.snapshots()?.collect::<io::Result<_>>()?;
+        Ok(snapshots.into_iter().collect())
+    }
+}
+
+impl<S: SpillState> Drop for SpilledResult<S> {
+    fn drop(&mut self) {
+        let _ = fs::remove_file(&self.path);
+    }
+}
+
+/// Iterator over the snapshots of a `SpilledResult`
+pub struct SpilledSnapshots<'a, S> {
+    reader: BufReader<File>,
+    /// Snapshots left in the file
+    remaining: usize,
+    /// Decoded snapshots of the current file chunk, in reverse order
+    chunk: Vec<StateSnapshot<S>>,
+    tail: std::slice::Iter<'a, StateSnapshot<S>>,
+    chunk_len: usize,
+}
+
+impl<S: SpillState + Clone> SpilledSnapshots<'_, S> {
+    fn read_chunk(&mut self) -> io::Result<()> {
+        let count = self.remaining.min(self.chunk_len);
+        let record_size = HEADER_SIZE + S::ENCODED_SIZE;
+        let mut bytes = vec![0u8; count * record_size];
+        self.reader.read_exact(&mut bytes)?;
+        self.chunk = bytes
+            .chunks_exact(record_size)
+            .rev()
+            .map(|record| StateSnapshot {
+                time: read_f64(record, 0),
+                in_track: record[8] != 0,
+                state: S::decode(&record[HEADER_SIZE..]),
+            })
+            .collect();
+        self.remaining -= count;
+        Ok(())
+    }
+}
+
+impl<S: SpillState + Clone> Iterator for SpilledSnapshots<'_, S> {
+    type Item = io::Result<StateSnapshot<S>>;
+
+    fn next(&mut self) -> Option<Self::Item> {
+        if self.chunk.is_empty()
+            && self.remaining > 0
+            && let Err(err) = self.read_chunk()
+        {
+            self.remaining = 0;
+            return Some(Err(err));
+        }
+        match self.chunk.pop() {
+            Some(snapshot) => Some(Ok(snapshot)),
+            None => self.tail.next().cloned().map(Ok),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::SpilledResult;
+    use crate::models::point_mass::PointMassState;
+
+    #[test]
+    fn test_spilled_snapshots_round_trip() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("poses.spill");
+        let mut store = SpilledResult::create(&path, 3).expect("create spill file");
+        for i in 0..10 {
+            let value = i as f64;
+            store
+                .push(0.1 * value, (value, -value, 0.5), i % 4 != 0)
+                .expect("push");
+        }
+
+        assert_eq!(store.len(), 10);
+        assert_eq!(
+            std::fs::metadata(&path).expect("spill file").len(),
+            9 * (9 + 24)
+        );
+        assert_eq!(store.last().expect("last").state, (9.0, -9.0, 0.5));
+        let states: Vec<(f64, f64, f64)> = store
+            .states()
+            .expect("open spill file")
+            .collect::<std::io::Result<_>>()
+            .expect("read states");
+        assert_eq!(states.len(), 10);
+        assert_eq!(states[4], (4.0, -4.0, 0.5));
+
+        let result = store.to_result().expect("load result");
+        assert!(!result.snapshots()[8].in_track);
+        assert!((result.duration() - 0.9).abs() < 1e-12);
+
+        drop(store);
+        assert!(!path.exists());
+    }
+
+    #[test]
+    fn test_spilled_point_mass_states() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let mut store =
+            SpilledResult::create(temp_dir.path().join("states.spill"), 1).expect("create");
+        let state = PointMassState {
+            x: 1.5f32,
+            y: -2.0,
+            vx: 3.25,
+            vy: 0.0,
+            yaw: 0.5,
+        };
+        store.push(0.0, state.clone(), true).expect("push");
+
+        let result = store.to_result().expect("load result");
+        let loaded = &result.snapshots()[0].state;
+        assert_eq!(
+            (loaded.x, loaded.y, loaded.vx, loaded.yaw),
+            (1.5, -2.0, 3.25, 0.5)
+        );
+        assert!(SpilledResult::<PointMassState>::create(temp_dir.path().join("x"), 0).is_err());
+    }
+}
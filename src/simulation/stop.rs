@@ -0,0 +1,243 @@
+//! Conditions ending a run before its duration elapses.
+//!
+//! A simulation checks its stop conditions after every step (and on the start state);
+//! the first condition that holds ends the run, the snapshot of that step is recorded
+//! even between `record_every` intervals, and the reason is stored in the result
+//! (`SimulationResult::stop_reason`). The duration passed to `run` stays the upper
+//! bound of every run.
+
+use crate::math::vec2::Vec2;
+use crate::models::pose::Pose;
+use crate::simulation::result::StateSnapshot;
+use crate::tracks::base_track::Track;
+use crate::tracks::timing_line::TimingLine;
+use std::fmt;
+use std::sync::Arc;
+
+/// Predicate of a `StopCondition::Custom`, called with the snapshot of every step
+pub type StopPredicate<S> = Arc<dyn Fn(&StateSnapshot<S>) -> bool + Send + Sync>;
+
+/// Condition ending a run early
+pub enum StopCondition<S> {
+    /// Stop once the simulation time reaches this many seconds
+    MaxTime(f64),
+    /// Stop once the vehicle crossed the start line this many times
+    ///
+    /// Crossings are counted from the start of each run (see
+    /// `lap_analysis::start_line_crossings`), so a run starting on the line completes
+    /// one lap per crossing.
+    NumLaps(usize),
+    /// Stop once the vehicle was off the track for more than `grace_steps` consecutive
+    /// steps; 0 stops at the first step off the track
+    OffTrack { grace_steps: usize },
+    /// Stop once the predicate returns true
+    Custom(StopPredicate<S>),
+}
+
+impl<S> StopCondition<S> {
+    /// Stop once `predicate` returns true for the snapshot of a step
+    pub fn custom<F>(predicate: F) -> Self
+    where
+        F: Fn(&StateSnapshot<S>) -> bool + Send + Sync + 'static,
+    {
+        StopCondition::Custom(Arc::new(predicate))
+    }
+
+    /// Reason recorded when this condition ends a run
+    pub fn reason(&self) -> StopReason {
+        match self {
+            StopCondition::MaxTime(_) => StopReason::MaxTime,
+            StopCondition::NumLaps(_) => StopReason::NumLaps,
+            StopCondition::OffTrack { .. } => StopReason::OffTrack,
+            StopCondition::Custom(_) => StopReason::Custom,
+        }
+    }
+
+    /// Check the parameters of the condition
+    ///
+    /// # Returns
+    /// A description of the problem for a negative or non-finite time or zero laps
+    pub fn validate(&self) -> Result<(), String> {
+        match self {
+            StopCondition::MaxTime(time) if !(time.is_finite() && *time >= 0.0) => Err(format!(
+                "stop time must be a non-negative number, got {}",
+                time
+            )),
+            StopCondition::NumLaps(0) => Err("stop lap count must be at least 1".to_string()),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl<S> Clone for StopCondition<S> {
+    fn clone(&self) -> Self {
+        match self {
+            StopCondition::MaxTime(time) => StopCondition::MaxTime(*time),
+            StopCondition::NumLaps(laps) => StopCondition::NumLaps(*laps),
+            StopCondition::OffTrack { grace_steps } => StopCondition::OffTrack {
+                grace_steps: *grace_steps,
+            },
+            StopCondition::Custom(predicate) => StopCondition::Custom(Arc::clone(predicate)),
+        }
+    }
+}
+
+impl<S> fmt::Debug for StopCondition<S> {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopCondition::MaxTime(time) => f.debug_tuple("MaxTime").field(time).finish(),
+            StopCondition::NumLaps(laps) => f.debug_tuple("NumLaps").field(laps).finish(),
+            StopCondition::OffTrack { grace_steps } => f
+                .debug_struct("OffTrack")
+                .field("grace_steps", grace_steps)
+                .finish(),
+            StopCondition::Custom(_) => f.write_str("Custom(..)"),
+        }
+    }
+}
+
+/// Which stop condition ended a run
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+pub enum StopReason {
+    MaxTime,
+    NumLaps,
+    OffTrack,
+    Custom,
+}
+
+impl fmt::Display for StopReason {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            StopReason::MaxTime => write!(f, "time limit reached"),
+            StopReason::NumLaps => write!(f, "laps completed"),
+            StopReason::OffTrack => write!(f, "left the track"),
+            StopReason::Custom => write!(f, "custom condition met"),
+        }
+    }
+}
+
+/// Progress of one run towards its stop conditions
+pub(crate) struct StopMonitor<'a, S> {
+    conditions: &'a [StopCondition<S>],
+    start_line: Option<TimingLine>,
+    previous: Option<(f64, Vec2)>,
+    laps: usize,
+    off_track_steps: usize,
+}
+
+impl<'a, S: Pose + Clone> StopMonitor<'a, S> {
+    pub(crate) fn new(conditions: &'a [StopCondition<S>], track: Option<&dyn Track>) -> Self {
+        let counts_laps = conditions
+            .iter()
+            .any(|condition| matches!(condition, StopCondition::NumLaps(_)));
+        Self {
+            conditions,
+            start_line: track.filter(|_| counts_laps).map(TimingLine::start_line),
+            previous: None,
+            laps: 0,
+            off_track_steps: 0,
+        }
+    }
+
+    /// Whether any condition is checked; runs skip building snapshots otherwise
+    pub(crate) fn is_active(&self) -> bool {
+        !self.conditions.is_empty()
+    }
+
+    /// Update the progress with the state reached at `time`
+    ///
+    /// # Returns
+    /// The reason of the first condition that holds, if any
+    pub(crate) fn check(&mut self, time: f64, state: &S, in_track: bool) -> Option<StopReason> {
+        if !self.is_active() {
+            return None;
+        }
+        let position = Vec2::new(state.x(), state.y());
+        if let (Some(line), Some((previous_time, previous))) = (&self.start_line, self.previous)
+            && line
+                .crossing_time(previous_time, previous, time, position)
+                .is_some()
+        {
+            self.laps += 1;
+        }
+        self.previous = Some((time, position));
+        self.off_track_steps = if in_track { 0 } else { self.off_track_steps + 1 };
+
+        let mut snapshot = None;
+        self.conditions
+            .iter()
+            .find(|condition| match condition {
+                StopCondition::MaxTime(max_time) => time >= *max_time,
+                StopCondition::NumLaps(laps) => self.laps >= *laps,
+                StopCondition::OffTrack { grace_steps } => self.off_track_steps > *grace_steps,
+                StopCondition::Custom(predicate) => predicate(snapshot.get_or_insert_with(|| {
+                    StateSnapshot {
+                        time,
+                        state: state.clone(),
+                        in_track,
+                    }
+                })),
+            })
+            .map(StopCondition::reason)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{StopCondition, StopMonitor, StopReason};
+    use crate::tracks::arc_length::ArcLengthTable;
+    use crate::tracks::circle::CircleTrack;
+
+    type Pose = (f64, f64, f64);
+
+    #[test]
+    fn test_stop_monitor_conditions() {
+        let conditions: [StopCondition<Pose>; 2] = [
+            StopCondition::OffTrack { grace_steps: 1 },
+            StopCondition::<Pose>::custom(|snapshot| snapshot.state.0 > 10.0),
+        ];
+        let mut monitor = StopMonitor::new(&conditions, None);
+        assert_eq!(monitor.check(0.0, &(0.0, 0.0, 0.0), false), None);
+        assert_eq!(monitor.check(0.1, &(1.0, 0.0, 0.0), true), None);
+        assert_eq!(monitor.check(0.2, &(2.0, 0.0, 0.0), false), None);
+        assert_eq!(monitor.check(0.3, &(3.0, 0.0, 0.0), false), Some(StopReason::OffTrack));
+        assert_eq!(monitor.check(0.4, &(11.0, 0.0, 0.0), true), Some(StopReason::Custom));
+
+        let conditions = [StopCondition::<Pose>::MaxTime(0.25)];
+        let mut monitor = StopMonitor::new(&conditions, None);
+        assert_eq!(monitor.check(0.2, &(0.0, 0.0, 0.0), true), None);
+        assert_eq!(monitor.check(0.3, &(0.0, 0.0, 0.0), true), Some(StopReason::MaxTime));
+        assert!(!StopMonitor::<Pose>::new(&[], None).is_active());
+    }
+
+    #[test]
+    fn test_stop_monitor_counts_laps() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let center_line = ArcLengthTable::from_track(&track);
+        let conditions = [StopCondition::<Pose>::NumLaps(2)];
+        let mut monitor = StopMonitor::new(&conditions, Some(&track));
+
+        let total = center_line.total_length();
+        let mut stopped_at = None;
+        for step in 0..1000 {
+            let s = step as f64 * 5.0;
+            let pose = center_line.point_at(s).expect("point");
+            if monitor.check(step as f64, &pose, true).is_some() {
+                stopped_at = Some(s);
+                break;
+            }
+        }
+        let stopped_at = stopped_at.expect("two laps");
+        assert!(stopped_at >= 2.0 * total && stopped_at < 2.0 * total + 5.0, "{stopped_at}");
+    }
+
+    #[test]
+    fn test_validate_stop_conditions() {
+        assert!(StopCondition::<Pose>::MaxTime(-1.0).validate().is_err());
+        assert!(StopCondition::<Pose>::MaxTime(f64::NAN).validate().is_err());
+        assert!(StopCondition::<Pose>::NumLaps(0).validate().is_err());
+        assert!(StopCondition::<Pose>::NumLaps(3).validate().is_ok());
+        assert!(StopCondition::<Pose>::OffTrack { grace_steps: 0 }.validate().is_ok());
+    }
+}
@@ -0,0 +1,239 @@
+//! Async wrappers running simulations on tokio's blocking thread pool.
+//!
+//! Simulations are CPU-bound and synchronous, so they must not run on the async
+//! executor threads. The functions here move them to `spawn_blocking` and return a
+//! `SimulationHandle` that can be awaited for the result and cancelled at any time;
+//! cancellation goes through a `CancelToken`, checked before every simulation step.
+//!
+//! All functions must be called from within a tokio runtime.
+
+use crate::models::point_mass::PointMassState;
+use crate::simulation::cancel::CancelToken;
+use crate::simulation::open_loop::OpenLoopSimulation;
+use crate::simulation::result::SimulationResult;
+use std::error::Error;
+use std::fmt;
+use std::future::Future;
+use std::pin::Pin;
+use std::task::{Context, Poll};
+use tokio::task::{JoinError, JoinHandle};
+
+/// Why an async simulation task produced no result
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum TaskError {
+    /// The task was cancelled through its handle or token
+    Cancelled,
+    /// The task failed or panicked
+    Failed(String),
+}
+
+impl fmt::Display for TaskError {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            TaskError::Cancelled => write!(f, "simulation cancelled"),
+            TaskError::Failed(message) => write!(f, "simulation task failed: {}", message),
+        }
+    }
+}
+
+impl Error for TaskError {}
+
+impl From<crate::Error> for TaskError {
+    fn from(err: crate::Error) -> Self {
+        match err {
+            crate::Error::Cancelled => TaskError::Cancelled,
+            err => TaskError::Failed(err.to_string()),
+        }
+    }
+}
+
+impl From<JoinError> for TaskError {
+    fn from(err: JoinError) -> Self {
+        if err.is_cancelled() {
+            TaskError::Cancelled
+        } else {
+            TaskError::Failed(err.to_string())
+        }
+    }
+}
+
+/// Await-able handle of a simulation task
+///
+/// Dropping the handle does not stop the task; call `cancel` first if the result is
+/// no longer needed.
+pub struct SimulationHandle<T> {
+    join: JoinHandle<Result<T, TaskError>>,
+    cancel: CancelToken,
+}
+
+impl<T> SimulationHandle<T> {
+    /// Ask the task to stop; awaiting the handle then returns `TaskError::Cancelled`
+    /// unless the task already finished
+    pub fn cancel(&self) {
+        self.cancel.cancel();
+    }
+
+    /// Token observed by the task, e.g. to cancel it from another component
+    pub fn cancel_token(&self) -> CancelToken {
+        self.cancel.clone()
+    }
+
+    /// Whether the task finished (successfully or not)
+    pub fn is_finished(&self) -> bool {
+        self.join.is_finished()
+    }
+}
+
+impl<T> Future for SimulationHandle<T> {
+    type Output = Result<T, TaskError>;
+
+    fn poll(mut self: Pin<&mut Self>, cx: &mut Context<'_>) -> Poll<Self::Output> {
+        Pin::new(&mut self.join)
+            .poll(cx)
+            .map(|joined| joined.map_err(TaskError::from).and_then(|outcome| outcome))
+    }
+}
+
+/// Run `job` on the blocking thread pool with a fresh `CancelToken`
+///
+/// `job` receives the token and should check it regularly (e.g., by passing it to
+/// `OpenLoopSimulation::run_cancellable`).
+pub fn spawn_cancellable<T, F>(job: F) -> SimulationHandle<T>
+where
+    T: Send + 'static,
+    F: FnOnce(&CancelToken) -> Result<T, TaskError> + Send + 'static,
+{
+    let cancel = CancelToken::new();
+    let token = cancel.clone();
+    SimulationHandle {
+        join: tokio::task::spawn_blocking(move || job(&token)),
+        cancel,
+    }
+}
+
+/// Run `simulation` from time 0 for `duration` seconds in the background
+///
+/// # Returns
+/// Handle resolving to the simulation (with its model in the final state) and the
+/// recorded result
+pub fn spawn_run(
+    mut simulation: OpenLoopSimulation,
+    dt: f64,
+    duration: f64,
+) -> SimulationHandle<(OpenLoopSimulation, SimulationResult<PointMassState>)> {
+    spawn_cancellable(move |cancel| {
+        let result = simulation.run_cancellable(dt, duration, cancel)?;
+        Ok((simulation, result))
+    })
+}
+
+/// Run every simulation of a batch concurrently on the blocking thread pool
+///
+/// Cancelling the handle stops every run; the first failing run cancels the others.
+///
+/// # Returns
+/// Handle resolving to the results in the order of `simulations`
+pub fn spawn_batch(
+    simulations: Vec<OpenLoopSimulation>,
+    dt: f64,
+    duration: f64,
+) -> SimulationHandle<Vec<SimulationResult<PointMassState>>> {
+    let cancel = CancelToken::new();
+    let runs: Vec<JoinHandle<crate::Result<SimulationResult<PointMassState>>>> = simulations
+        .into_iter()
+        .map(|mut simulation| {
+            let token = cancel.clone();
+            tokio::task::spawn_blocking(move || simulation.run_cancellable(dt, duration, &token))
+        })
+        .collect();
+
+    let token = cancel.clone();
+    let join = tokio::spawn(async move {
+        let mut results = Vec::with_capacity(runs.len());
+        for run in runs {
+            match run
+                .await
+                .map_err(TaskError::from)
+                .and_then(|outcome| Ok(outcome?))
+            {
+                Ok(result) => results.push(result),
+                Err(err) => {
+                    token.cancel();
+                    return Err(err);
+                }
+            }
+        }
+        Ok(results)
+    });
+    SimulationHandle { join, cancel }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{spawn_batch, spawn_cancellable, spawn_run, TaskError};
+    use crate::models::base_model::Model;
+    use crate::simulation::open_loop::OpenLoopSimulation;
+    use crate::tracks::circle::CircleTrack;
+    use std::sync::Arc;
+
+    fn simulation(ax: f64) -> OpenLoopSimulation {
+        OpenLoopSimulation::builder()
+            .controls(ax, 0.0)
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .build()
+            .expect("build simulation")
+    }
+
+    fn runtime() -> tokio::runtime::Runtime {
+        tokio::runtime::Runtime::new().expect("create runtime")
+    }
+
+    #[test]
+    fn test_spawn_run_returns_simulation_and_result() {
+        runtime().block_on(async {
+            let (sim, result) = spawn_run(simulation(1.0), 0.1, 1.0).await.expect("run");
+            assert_eq!(result.len(), 11);
+            let vx = sim.model().expect("model").get_state().vx;
+            assert!((vx - 1.0).abs() < 1e-9);
+        });
+    }
+
+    #[test]
+    fn test_spawn_batch_keeps_order() {
+        runtime().block_on(async {
+            let simulations = (1..=4).map(|ax| simulation(ax as f64)).collect();
+            let results = spawn_batch(simulations, 0.1, 1.0).await.expect("batch");
+
+            assert_eq!(results.len(), 4);
+            for (index, result) in results.iter().enumerate() {
+                let vx = result.last().expect("final snapshot").state.vx;
+                assert!((vx - (index + 1) as f64).abs() < 1e-9);
+            }
+        });
+    }
+
+    #[test]
+    fn test_cancelled_run_stops() {
+        runtime().block_on(async {
+            // 500 000 steps: only finishes if cancellation is ignored
+            let handle = spawn_run(simulation(1.0), 1e-3, 500.0);
+            handle.cancel();
+            assert_eq!(handle.await.err(), Some(TaskError::Cancelled));
+
+            let batch = spawn_batch(vec![simulation(1.0), simulation(2.0)], 1e-3, 500.0);
+            batch.cancel_token().cancel();
+            assert_eq!(batch.await.err(), Some(TaskError::Cancelled));
+        });
+    }
+
+    #[test]
+    fn test_failed_task_reports_error() {
+        runtime().block_on(async {
+            let handle = spawn_cancellable(|_| -> Result<(), TaskError> {
+                Err(TaskError::Failed("bad scenario".to_string()))
+            });
+            let err = handle.await.expect_err("expected failure");
+            assert_eq!(err.to_string(), "simulation task failed: bad scenario");
+        });
+    }
+}
@@ -0,0 +1,236 @@
+//! Weather changing the grip and the drag during a run.
+//!
+//! The weather is a schedule of rain intensities between 0 (dry) and 1 (heavy rain),
+//! interpolated linearly between samples, so a shower that starts or dries up is a
+//! gradual transition. At every tick the rain intensity is turned into
+//! `WeatherConditions`: a factor on the tire friction of the vehicles and a factor on
+//! their drag (spray and standing water).
+
+use crate::error::{Error, Result};
+use std::fmt;
+
+/// Factor on the friction coefficient in heavy rain
+pub const WET_GRIP: f64 = 0.6;
+/// Factor on the drag coefficient in heavy rain
+pub const WET_DRAG: f64 = 1.1;
+
+/// Rain intensity from which the surface counts as damp
+const DAMP_RAIN: f64 = 0.05;
+/// Rain intensity from which the surface counts as wet
+const WET_RAIN: f64 = 0.5;
+
+/// Rain intensity at a point in time
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RainSample {
+    /// Time in seconds
+    pub time: f64,
+    /// Rain intensity in [0, 1]
+    pub rain: f64,
+}
+
+/// State of the track surface
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum Surface {
+    Dry,
+    Damp,
+    Wet,
+}
+
+impl fmt::Display for Surface {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            Surface::Dry => "dry",
+            Surface::Damp => "damp",
+            Surface::Wet => "wet",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Weather at one tick and its effect on the vehicles
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct WeatherConditions {
+    /// Time in seconds
+    pub time: f64,
+    /// Rain intensity in [0, 1]
+    pub rain: f64,
+    /// Factor on the friction coefficient
+    pub grip: f64,
+    /// Factor on the drag coefficient
+    pub drag: f64,
+}
+
+impl WeatherConditions {
+    pub fn surface(&self) -> Surface {
+        if self.rain >= WET_RAIN {
+            Surface::Wet
+        } else if self.rain >= DAMP_RAIN {
+            Surface::Damp
+        } else {
+            Surface::Dry
+        }
+    }
+}
+
+impl fmt::Display for WeatherConditions {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "{} (rain {:.0}%, grip {:.0}%, drag {:.0}%)",
+            self.surface(),
+            self.rain * 100.0,
+            self.grip * 100.0,
+            self.drag * 100.0
+        )
+    }
+}
+
+/// Rain intensity over time
+///
+/// Before the first sample the first intensity holds, after the last sample the last
+/// one holds. Without samples the weather is dry.
+#[derive(Debug, Clone, PartialEq, Default)]
+pub struct Weather {
+    samples: Vec<RainSample>,
+}
+
+impl Weather {
+    /// Create a weather schedule from samples sorted by time
+    ///
+    /// # Returns
+    /// The schedule, or `Error::InvalidConfig` naming the first invalid or unsorted sample
+    pub fn new(samples: Vec<RainSample>) -> Result<Self> {
+        for (index, sample) in samples.iter().enumerate() {
+            if !sample.time.is_finite() || !(0.0..=1.0).contains(&sample.rain) {
+                return Err(Error::invalid_config(format!(
+                    "rain sample {} needs a finite time and an intensity in [0, 1]",
+                    index
+                )));
+            }
+            if index > 0 && sample.time < samples[index - 1].time {
+                return Err(Error::invalid_config(format!(
+                    "rain sample {} at t={} is earlier than the previous sample",
+                    index, sample.time
+                )));
+            }
+        }
+        Ok(Self { samples })
+    }
+
+    /// Dry weather for the whole run
+    pub fn dry() -> Self {
+        Self::default()
+    }
+
+    /// Constant rain intensity for the whole run, clamped to [0, 1]
+    pub fn constant(rain: f64) -> Self {
+        Self {
+            samples: vec![RainSample {
+                time: 0.0,
+                rain: rain.clamp(0.0, 1.0),
+            }],
+        }
+    }
+
+    /// Rain changing linearly from intensity `from` at `start` to `to` at `end` (seconds)
+    ///
+    /// `transition(10.0, 20.0, 0.0, 1.0)` is a shower starting after 10 s and reaching
+    /// heavy rain after 20 s.
+    pub fn transition(start: f64, end: f64, from: f64, to: f64) -> Result<Self> {
+        Self::new(vec![
+            RainSample { time: start, rain: from },
+            RainSample { time: end, rain: to },
+        ])
+    }
+
+    pub fn samples(&self) -> &[RainSample] {
+        &self.samples
+    }
+
+    /// Rain intensity in [0, 1] at `time`
+    pub fn rain_at(&self, time: f64) -> f64 {
+        let next = self.samples.partition_point(|sample| sample.time <= time);
+        match (next.checked_sub(1).map(|index| self.samples[index]), self.samples.get(next)) {
+            (None, None) => 0.0,
+            (None, Some(first)) => first.rain,
+            (Some(last), None) => last.rain,
+            (Some(prev), Some(next)) => {
+                let alpha = (time - prev.time) / (next.time - prev.time);
+                prev.rain + (next.rain - prev.rain) * alpha
+            }
+        }
+    }
+
+    /// Weather conditions at `time`
+    pub fn conditions_at(&self, time: f64) -> WeatherConditions {
+        let rain = self.rain_at(time);
+        WeatherConditions {
+            time,
+            rain,
+            grip: 1.0 + (WET_GRIP - 1.0) * rain,
+            drag: 1.0 + (WET_DRAG - 1.0) * rain,
+        }
+    }
+}
+
+/// One-line summary of logged conditions, e.g. "dry" or "dry -> wet, grip down to 64%"
+pub fn summarize(conditions: &[WeatherConditions]) -> String {
+    let (Some(first), Some(last)) = (conditions.first(), conditions.last()) else {
+        return "dry".to_string();
+    };
+    let min_grip = conditions.iter().map(|c| c.grip).fold(f64::INFINITY, f64::min);
+    if first.surface() == last.surface() && conditions.iter().all(|c| c.rain == first.rain) {
+        return if first.rain == 0.0 { "dry".to_string() } else { first.to_string() };
+    }
+    format!(
+        "{} -> {}, grip down to {:.0}%",
+        first.surface(),
+        last.surface(),
+        min_grip * 100.0
+    )
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{summarize, RainSample, Surface, Weather, WET_GRIP};
+
+    #[test]
+    fn test_rain_transition() {
+        let weather = Weather::transition(10.0, 20.0, 0.0, 1.0).expect("weather");
+        assert_eq!(weather.rain_at(0.0), 0.0);
+        assert_eq!(weather.rain_at(15.0), 0.5);
+        assert_eq!(weather.rain_at(30.0), 1.0);
+
+        let dry = weather.conditions_at(5.0);
+        assert_eq!((dry.grip, dry.drag, dry.surface()), (1.0, 1.0, Surface::Dry));
+        let wet = weather.conditions_at(25.0);
+        assert_eq!(wet.grip, WET_GRIP);
+        assert!(wet.drag > 1.0);
+        assert_eq!(wet.surface(), Surface::Wet);
+        assert_eq!(weather.conditions_at(11.0).surface(), Surface::Damp);
+
+        assert_eq!(Weather::dry().rain_at(3.0), 0.0);
+        assert_eq!(Weather::constant(2.0).rain_at(3.0), 1.0);
+    }
+
+    #[test]
+    fn test_invalid_samples() {
+        let err = Weather::new(vec![
+            RainSample { time: 1.0, rain: 0.5 },
+            RainSample { time: 0.0, rain: 0.5 },
+        ])
+        .expect_err("unsorted samples");
+        assert!(err.to_string().contains("earlier"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(Weather::transition(0.0, 1.0, 0.0, 1.5).is_err());
+    }
+
+    #[test]
+    fn test_summarize() {
+        let weather = Weather::transition(1.0, 2.0, 0.0, 1.0).expect("weather");
+        let log: Vec<_> = (0..4).map(|t| weather.conditions_at(t as f64)).collect();
+        assert_eq!(summarize(&log), "dry -> wet, grip down to 60%");
+        let dry: Vec<_> = (0..4).map(|t| Weather::dry().conditions_at(t as f64)).collect();
+        assert_eq!(summarize(&dry), "dry");
+    }
+}
@@ -0,0 +1,256 @@
+//! Live telemetry emitted while a simulation runs.
+//!
+//! Simulations forward every recorded snapshot to an optional `TelemetrySink`, so
+//! external tools (dashboards, plotters, ...) can follow a run as it progresses.
+
+pub mod ndjson;
+pub mod udp;
+#[cfg(feature = "websocket")]
+pub mod websocket;
+
+use crate::error::{Error, Result};
+use crate::io::json_number;
+use crate::models::pose::Pose;
+use crate::simulation::result::StateSnapshot;
+use std::fmt;
+
+/// Receiver of snapshots streamed during a simulation run
+pub trait TelemetrySink<S> {
+    /// Publish a snapshot that was just recorded
+    ///
+    /// # Returns
+    /// `Error::Io` if the snapshot could not be sent or written
+    fn publish(&mut self, snapshot: &StateSnapshot<S>) -> Result<()>;
+
+    /// Called when a new run starts, e.g. to reset rate limiting
+    fn start_run(&mut self) {}
+}
+
+/// Wire format of telemetry packets
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Default)]
+pub enum TelemetryFormat {
+    /// Fixed-size little-endian binary packet (see `TelemetryPacket::BINARY_SIZE`)
+    #[default]
+    Binary,
+    /// Single CSV line: `time,x,y,yaw,in_track`
+    Csv,
+    /// Single-line JSON object: `{"time":..,"x":..,"y":..,"yaw":..,"in_track":..}`
+    Json,
+}
+
+impl fmt::Display for TelemetryFormat {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        let name = match self {
+            TelemetryFormat::Binary => "binary",
+            TelemetryFormat::Csv => "csv",
+            TelemetryFormat::Json => "json",
+        };
+        write!(f, "{}", name)
+    }
+}
+
+/// Compact description of a snapshot sent over the wire
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TelemetryPacket {
+    /// Simulation time in seconds
+    pub time: f64,
+    /// World frame x position in meters
+    pub x: f64,
+    /// World frame y position in meters
+    pub y: f64,
+    /// Orientation angle in radians
+    pub yaw: f64,
+    /// Whether the model was inside the track boundaries
+    pub in_track: bool,
+}
+
+impl TelemetryPacket {
+    /// Size in bytes of a binary packet: four `f64` values and one flag byte
+    pub const BINARY_SIZE: usize = 4 * 8 + 1;
+
+    /// Build a packet from a recorded snapshot
+    pub fn from_snapshot<S: Pose>(snapshot: &StateSnapshot<S>) -> Self {
+        let (x, y, yaw) = snapshot.state.pose();
+        Self {
+            time: snapshot.time,
+            x,
+            y,
+            yaw,
+            in_track: snapshot.in_track,
+        }
+    }
+
+    /// Encode the packet in the given format
+    pub fn encode(&self, format: TelemetryFormat) -> Vec<u8> {
+        match format {
+            TelemetryFormat::Binary => {
+                let mut bytes = Vec::with_capacity(Self::BINARY_SIZE);
+                for value in [self.time, self.x, self.y, self.yaw] {
+                    bytes.extend_from_slice(&value.to_le_bytes());
+                }
+                bytes.push(u8::from(self.in_track));
+                bytes
+            }
+            TelemetryFormat::Csv => format!(
+                "{},{},{},{},{}",
+                self.time,
+                self.x,
+                self.y,
+                self.yaw,
+                u8::from(self.in_track)
+            )
+            .into_bytes(),
+            TelemetryFormat::Json => format!(
+                "{{\"time\":{},\"x\":{},\"y\":{},\"yaw\":{},\"in_track\":{}}}",
+                json_number(self.time),
+                json_number(self.x),
+                json_number(self.y),
+                json_number(self.yaw),
+                self.in_track
+            )
+            .into_bytes(),
+        }
+    }
+
+    /// Decode a packet previously produced by `encode`
+    ///
+    /// # Returns
+    /// The packet, or `Error::InvalidInput` if `bytes` is not a packet of `format`
+    pub fn decode(bytes: &[u8], format: TelemetryFormat) -> Result<Self> {
+        match format {
+            TelemetryFormat::Binary => {
+                if bytes.len() != Self::BINARY_SIZE {
+                    return Err(Error::invalid_input(format!(
+                        "binary telemetry packet must be {} bytes, got {}",
+                        Self::BINARY_SIZE,
+                        bytes.len()
+                    )));
+                }
+                let value = |index: usize| {
+                    let mut raw = [0u8; 8];
+                    raw.copy_from_slice(&bytes[index * 8..(index + 1) * 8]);
+                    f64::from_le_bytes(raw)
+                };
+                Ok(Self {
+                    time: value(0),
+                    x: value(1),
+                    y: value(2),
+                    yaw: value(3),
+                    in_track: bytes[32] != 0,
+                })
+            }
+            TelemetryFormat::Csv => {
+                let fields: Vec<&str> = packet_text(bytes)?.split(',').collect();
+                if fields.len() != 5 {
+                    return Err(Error::invalid_input(format!(
+                        "CSV telemetry packet must have 5 fields, got {}",
+                        fields.len()
+                    )));
+                }
+                Ok(Self {
+                    time: parse_number(fields[0])?,
+                    x: parse_number(fields[1])?,
+                    y: parse_number(fields[2])?,
+                    yaw: parse_number(fields[3])?,
+                    in_track: fields[4] != "0",
+                })
+            }
+            TelemetryFormat::Json => {
+                let not_object = || Error::invalid_input("JSON telemetry packet must be an object");
+                let body = packet_text(bytes)?
+                    .strip_prefix('{')
+                    .and_then(|text| text.strip_suffix('}'))
+                    .ok_or_else(not_object)?;
+                let mut packet = Self {
+                    time: f64::NAN,
+                    x: f64::NAN,
+                    y: f64::NAN,
+                    yaw: f64::NAN,
+                    in_track: false,
+                };
+                for field in body.split(',') {
+                    let (key, value) = field
+                        .split_once(':')
+                        .ok_or_else(|| {
+                            let message = format!("invalid JSON telemetry field '{}'", field);
+                            Error::invalid_input(message)
+                        })?;
+                    let number = || match value.trim() {
+                        "null" => Ok(f64::NAN),
+                        value => parse_number(value),
+                    };
+                    match key.trim().trim_matches('"') {
+                        "time" => packet.time = number()?,
+                        "x" => packet.x = number()?,
+                        "y" => packet.y = number()?,
+                        "yaw" => packet.yaw = number()?,
+                        "in_track" => packet.in_track = value.trim() == "true",
+                        _ => {}
+                    }
+                }
+                Ok(packet)
+            }
+        }
+    }
+}
+
+/// Trimmed text of a CSV or JSON packet
+fn packet_text(bytes: &[u8]) -> Result<&str> {
+    std::str::from_utf8(bytes)
+        .map(str::trim)
+        .map_err(|err| Error::invalid_input(format!("telemetry packet is not UTF-8: {}", err)))
+}
+
+fn parse_number(field: &str) -> Result<f64> {
+    field
+        .trim()
+        .parse()
+        .map_err(|_| Error::invalid_input(format!("invalid telemetry value '{}'", field)))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{TelemetryFormat, TelemetryPacket};
+
+    #[test]
+    fn test_packet_round_trip() {
+        let packet = TelemetryPacket {
+            time: 1.5,
+            x: 50.0,
+            y: -2.25,
+            yaw: 1.57,
+            in_track: false,
+        };
+
+        for format in [
+            TelemetryFormat::Binary,
+            TelemetryFormat::Csv,
+            TelemetryFormat::Json,
+        ] {
+            let bytes = packet.encode(format);
+            let decoded = TelemetryPacket::decode(&bytes, format).expect("decode packet");
+            assert_eq!(decoded, packet, "{} round trip", format);
+        }
+        assert_eq!(
+            packet.encode(TelemetryFormat::Binary).len(),
+            TelemetryPacket::BINARY_SIZE
+        );
+        assert_eq!(packet.encode(TelemetryFormat::Csv), b"1.5,50,-2.25,1.57,0");
+        assert_eq!(
+            packet.encode(TelemetryFormat::Json),
+            br#"{"time":1.5,"x":50,"y":-2.25,"yaw":1.57,"in_track":false}"#
+        );
+    }
+
+    #[test]
+    fn test_packet_decode_rejects_truncated() {
+        let err = TelemetryPacket::decode(&[0u8; 10], TelemetryFormat::Binary)
+            .expect_err("expected error for truncated packet");
+        assert!(err.to_string().contains("33 bytes"));
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+
+        let err = TelemetryPacket::decode(b"1.5,x,0,0,1", TelemetryFormat::Csv)
+            .expect_err("expected error for invalid number");
+        assert!(err.to_string().contains("'x'"));
+    }
+}
@@ -0,0 +1,71 @@
+use super::{TelemetryFormat, TelemetryPacket, TelemetrySink};
+use crate::error::Result;
+use crate::models::pose::Pose;
+use crate::simulation::result::StateSnapshot;
+use std::io::{self, Stdout, Write};
+
+/// Telemetry writer emitting one JSON object per line (NDJSON)
+///
+/// Each line is flushed as soon as it is written, so the output can be piped into
+/// `jq`, plotting scripts, or other processes while the simulation runs.
+pub struct NdjsonTelemetry<W: Write> {
+    writer: W,
+}
+
+impl NdjsonTelemetry<Stdout> {
+    /// Stream snapshots to standard output
+    pub fn stdout() -> Self {
+        Self::new(io::stdout())
+    }
+}
+
+impl<W: Write> NdjsonTelemetry<W> {
+    pub fn new(writer: W) -> Self {
+        Self { writer }
+    }
+
+    /// Return the underlying writer
+    pub fn into_inner(self) -> W {
+        self.writer
+    }
+}
+
+impl<S: Pose, W: Write> TelemetrySink<S> for NdjsonTelemetry<W> {
+    fn publish(&mut self, snapshot: &StateSnapshot<S>) -> Result<()> {
+        let mut line = TelemetryPacket::from_snapshot(snapshot).encode(TelemetryFormat::Json);
+        line.push(b'\n');
+        self.writer.write_all(&line)?;
+        self.writer.flush()?;
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::NdjsonTelemetry;
+    use crate::simulation::result::SimulationResult;
+    use crate::telemetry::{TelemetryFormat, TelemetryPacket, TelemetrySink};
+
+    #[test]
+    fn test_ndjson_telemetry_writes_one_line_per_snapshot() {
+        let mut result = SimulationResult::new();
+        result.push(0.0, (1.0, 2.0, 0.5), true);
+        result.push(0.1, (1.5, 2.0, 0.5), false);
+
+        let mut telemetry = NdjsonTelemetry::new(Vec::new());
+        for snapshot in result.snapshots() {
+            telemetry.publish(snapshot).expect("publish snapshot");
+        }
+
+        let output = String::from_utf8(telemetry.into_inner()).expect("utf-8");
+        let lines: Vec<&str> = output.lines().collect();
+        assert_eq!(lines.len(), 2);
+        assert_eq!(
+            lines[0],
+            r#"{"time":0,"x":1,"y":2,"yaw":0.5,"in_track":true}"#
+        );
+        let packet = TelemetryPacket::decode(lines[1].as_bytes(), TelemetryFormat::Json)
+            .expect("decode line");
+        assert_eq!((packet.time, packet.x, packet.in_track), (0.1, 1.5, false));
+    }
+}
@@ -0,0 +1,147 @@
+use super::{TelemetryFormat, TelemetryPacket, TelemetrySink};
+use crate::error::{Error, Result};
+use crate::models::pose::Pose;
+use crate::simulation::result::StateSnapshot;
+use std::net::{SocketAddr, ToSocketAddrs, UdpSocket};
+
+/// Telemetry sender emitting one UDP datagram per snapshot
+///
+/// Datagrams are fire-and-forget: nothing is sent back and a missing listener does
+/// not stop the simulation.
+pub struct UdpTelemetry {
+    socket: UdpSocket,
+    target: SocketAddr,
+    format: TelemetryFormat,
+    rate_hz: Option<f64>,
+    last_sent: Option<f64>,
+}
+
+impl UdpTelemetry {
+    /// Create a sender targeting `target` (e.g., "127.0.0.1:9870")
+    ///
+    /// Every snapshot is sent in the binary format by default.
+    ///
+    /// # Returns
+    /// The sender, `Error::Io` if no socket can be bound, or `Error::InvalidInput` if
+    /// `target` does not resolve to an address
+    pub fn new<A: ToSocketAddrs>(target: A) -> Result<Self> {
+        let target = target.to_socket_addrs()?.next().ok_or_else(|| {
+            Error::invalid_input("telemetry target did not resolve to an address")
+        })?;
+        let bind_addr = if target.is_ipv4() {
+            "0.0.0.0:0"
+        } else {
+            "[::]:0"
+        };
+        Ok(Self {
+            socket: UdpSocket::bind(bind_addr)?,
+            target,
+            format: TelemetryFormat::default(),
+            rate_hz: None,
+            last_sent: None,
+        })
+    }
+
+    /// Use the given packet format
+    pub fn with_format(mut self, format: TelemetryFormat) -> Self {
+        self.format = format;
+        self
+    }
+
+    /// Limit the packet rate, in packets per simulated second
+    ///
+    /// The limit is applied on simulation time so the emitted packets do not depend
+    /// on how fast the simulation runs.
+    pub fn with_rate(mut self, rate_hz: f64) -> Result<Self> {
+        if !(rate_hz.is_finite() && rate_hz > 0.0) {
+            let message = format!("telemetry rate must be positive, got {}", rate_hz);
+            return Err(Error::invalid_input(message));
+        }
+        self.rate_hz = Some(rate_hz);
+        Ok(self)
+    }
+
+    pub fn target(&self) -> SocketAddr {
+        self.target
+    }
+
+    pub fn format(&self) -> TelemetryFormat {
+        self.format
+    }
+}
+
+impl<S: Pose> TelemetrySink<S> for UdpTelemetry {
+    fn publish(&mut self, snapshot: &StateSnapshot<S>) -> Result<()> {
+        if let (Some(rate_hz), Some(last_sent)) = (self.rate_hz, self.last_sent)
+            && snapshot.time - last_sent < 1.0 / rate_hz - 1e-9
+        {
+            return Ok(());
+        }
+
+        let bytes = TelemetryPacket::from_snapshot(snapshot).encode(self.format);
+        self.socket.send_to(&bytes, self.target)?;
+        self.last_sent = Some(snapshot.time);
+        Ok(())
+    }
+
+    fn start_run(&mut self) {
+        self.last_sent = None;
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::UdpTelemetry;
+    use crate::telemetry::{TelemetryFormat, TelemetryPacket, TelemetrySink};
+    use crate::simulation::result::SimulationResult;
+    use std::net::UdpSocket;
+    use std::time::Duration;
+
+    fn listener() -> UdpSocket {
+        let socket = UdpSocket::bind("127.0.0.1:0").expect("bind listener");
+        socket
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .expect("set read timeout");
+        socket
+    }
+
+    #[test]
+    fn test_udp_telemetry_sends_packets_at_rate() {
+        let listener = listener();
+        let mut telemetry = UdpTelemetry::new(listener.local_addr().expect("listener addr"))
+            .expect("create telemetry")
+            .with_format(TelemetryFormat::Csv)
+            .with_rate(5.0)
+            .expect("valid rate");
+
+        let mut result = SimulationResult::new();
+        for step in 0..=5 {
+            result.push(step as f64 * 0.1, (step as f64, 0.0, 0.0), true);
+        }
+        TelemetrySink::<(f64, f64, f64)>::start_run(&mut telemetry);
+        for snapshot in result.snapshots() {
+            telemetry.publish(snapshot).expect("publish snapshot");
+        }
+
+        let mut buffer = [0u8; 128];
+        let mut times = Vec::new();
+        for _ in 0..3 {
+            let len = listener.recv(&mut buffer).expect("receive packet");
+            let packet = TelemetryPacket::decode(&buffer[..len], TelemetryFormat::Csv)
+                .expect("decode packet");
+            times.push(packet.time);
+        }
+        assert_eq!(times, vec![0.0, 0.2, 0.4]);
+    }
+
+    #[test]
+    fn test_udp_telemetry_rejects_invalid_rate() {
+        let err = UdpTelemetry::new("127.0.0.1:9")
+            .expect("create telemetry")
+            .with_rate(0.0)
+            .err()
+            .expect("expected error for zero rate");
+        assert!(err.to_string().contains("rate must be positive"));
+        assert!(matches!(err, crate::Error::InvalidInput(_)));
+    }
+}
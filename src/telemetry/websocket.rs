@@ -0,0 +1,195 @@
+use super::TelemetrySink;
+use crate::diagnostics;
+use crate::error::Result;
+use crate::simulation::result::StateSnapshot;
+use serde::Serialize;
+use std::io;
+use std::net::{SocketAddr, TcpListener, TcpStream, ToSocketAddrs};
+use std::sync::atomic::{AtomicBool, Ordering};
+use std::sync::{Arc, Mutex};
+use std::thread;
+use std::time::Duration;
+use tungstenite::{Message, WebSocket};
+
+type Clients = Arc<Mutex<Vec<WebSocket<TcpStream>>>>;
+
+/// Longest time a connecting viewer may take to complete the WebSocket handshake
+const HANDSHAKE_TIMEOUT: Duration = Duration::from_secs(5);
+
+/// WebSocket server broadcasting every snapshot as a JSON text message
+///
+/// Each message is the JSON encoding of a `StateSnapshot`, e.g.
+/// `{"time":0.1,"state":{"x":50.0,"y":0.1,"vx":0.2,"vy":0.0,"yaw":1.57},"in_track":true}`,
+/// so a browser can follow a run with a plain `new WebSocket(url)`.
+/// Clients connect at any time; clients that disconnect are dropped silently.
+pub struct WebSocketTelemetry {
+    local_addr: SocketAddr,
+    clients: Clients,
+    shutdown: Arc<AtomicBool>,
+}
+
+impl WebSocketTelemetry {
+    /// Start listening for viewers on `addr` (e.g., "127.0.0.1:9871")
+    ///
+    /// Connections are accepted on a background thread until the server is dropped. Each
+    /// handshake runs on a thread of its own and times out after `HANDSHAKE_TIMEOUT`, so a
+    /// stalled client does not hold up the viewers connecting after it.
+    pub fn bind<A: ToSocketAddrs>(addr: A) -> Result<Self> {
+        let listener = TcpListener::bind(addr)?;
+        let local_addr = listener.local_addr()?;
+        let clients: Clients = Arc::new(Mutex::new(Vec::new()));
+        let shutdown = Arc::new(AtomicBool::new(false));
+
+        let accepted = Arc::clone(&clients);
+        let stop = Arc::clone(&shutdown);
+        thread::spawn(move || {
+            for stream in listener.incoming() {
+                if stop.load(Ordering::SeqCst) {
+                    break;
+                }
+                let Ok(stream) = stream else {
+                    continue;
+                };
+                let accepted = Arc::clone(&accepted);
+                thread::spawn(move || accept_client(stream, &accepted));
+            }
+        });
+
+        Ok(Self {
+            local_addr,
+            clients,
+            shutdown,
+        })
+    }
+
+    /// Address the server listens on
+    pub fn local_addr(&self) -> SocketAddr {
+        self.local_addr
+    }
+
+    /// URL viewers should connect to
+    pub fn url(&self) -> String {
+        format!("ws://{}", self.local_addr)
+    }
+
+    /// Number of currently connected viewers
+    pub fn client_count(&self) -> usize {
+        self.clients.lock().map_or(0, |clients| clients.len())
+    }
+
+    /// Send a text message to every connected viewer, dropping the ones that left
+    pub fn broadcast(&self, text: &str) -> Result<()> {
+        let mut clients = self
+            .clients
+            .lock()
+            .map_err(|_| io::Error::other("WebSocket client list poisoned"))?;
+        clients.retain_mut(|client| client.send(Message::Text(text.to_string())).is_ok());
+        Ok(())
+    }
+}
+
+/// Complete the handshake of a new viewer and add it to `clients`
+fn accept_client(stream: TcpStream, clients: &Clients) {
+    if let Err(err) = stream.set_read_timeout(Some(HANDSHAKE_TIMEOUT)) {
+        diagnostics::warn(format_args!("WebSocket handshake failed: {err}"));
+        return;
+    }
+    match tungstenite::accept(stream) {
+        Ok(client) => {
+            // Viewers only receive; the timeout only guards the handshake
+            let _ = client.get_ref().set_read_timeout(None);
+            if let Ok(mut clients) = clients.lock() {
+                clients.push(client);
+            }
+        }
+        Err(err) => diagnostics::warn(format_args!("WebSocket handshake failed: {err}")),
+    }
+}
+
+impl<S: Serialize> TelemetrySink<S> for WebSocketTelemetry {
+    fn publish(&mut self, snapshot: &StateSnapshot<S>) -> Result<()> {
+        self.broadcast(&serde_json::to_string(snapshot).map_err(io::Error::from)?)
+    }
+}
+
+impl Drop for WebSocketTelemetry {
+    fn drop(&mut self) {
+        self.shutdown.store(true, Ordering::SeqCst);
+        // Wake the accept loop so the background thread can exit
+        let _ = TcpStream::connect(self.local_addr);
+        if let Ok(mut clients) = self.clients.lock() {
+            for client in clients.iter_mut() {
+                let _ = client.close(None);
+                let _ = client.flush();
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WebSocketTelemetry;
+    use crate::models::point_mass::PointMassState;
+    use crate::simulation::result::SimulationResult;
+    use crate::telemetry::TelemetrySink;
+    use std::net::TcpStream;
+    use std::thread;
+    use std::time::{Duration, Instant};
+
+    #[test]
+    fn test_websocket_broadcasts_snapshots_as_json() {
+        let mut server = WebSocketTelemetry::bind("127.0.0.1:0").expect("bind server");
+        let stream = TcpStream::connect(server.local_addr()).expect("connect");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .expect("set read timeout");
+        let (mut client, _) = tungstenite::client(server.url(), stream).expect("handshake");
+
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while server.client_count() == 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(server.client_count(), 1);
+
+        let mut result = SimulationResult::new();
+        result.push(
+            0.5,
+            PointMassState {
+                x: 50.0,
+                y: 1.0,
+                vx: 2.0,
+                vy: 0.0,
+                yaw: 1.5,
+            },
+            true,
+        );
+        server
+            .publish(result.last().expect("snapshot"))
+            .expect("publish snapshot");
+
+        let message = client.read().expect("read message");
+        let json: serde_json::Value =
+            serde_json::from_str(message.to_text().expect("text message")).expect("valid json");
+        assert_eq!(json["time"], 0.5);
+        assert_eq!(json["state"]["vx"], 2.0);
+        assert_eq!(json["in_track"], true);
+    }
+
+    #[test]
+    fn test_stalled_handshake_does_not_block_viewers() {
+        let server = WebSocketTelemetry::bind("127.0.0.1:0").expect("bind server");
+        // Connects but never sends the handshake request
+        let _stalled = TcpStream::connect(server.local_addr()).expect("connect");
+
+        let stream = TcpStream::connect(server.local_addr()).expect("connect");
+        stream
+            .set_read_timeout(Some(Duration::from_secs(2)))
+            .expect("set read timeout");
+        tungstenite::client(server.url(), stream).expect("handshake");
+        let deadline = Instant::now() + Duration::from_secs(2);
+        while server.client_count() == 0 && Instant::now() < deadline {
+            thread::sleep(Duration::from_millis(10));
+        }
+        assert_eq!(server.client_count(), 1);
+    }
+}
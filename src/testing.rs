@@ -0,0 +1,422 @@
+//! Helpers for golden (snapshot) testing of generated SVG plots and simulation traces.
+//!
+//! SVG output is normalized before comparison so that line endings, indentation,
+//! and tiny floating point differences across platforms do not cause spurious failures.
+//! State traces (time and pose of every snapshot) are compared value by value within a
+//! tolerance, since `sin`/`cos` may differ in the last bits between platform math
+//! libraries. `SplitMix64` (re-exported from `math::random`) provides seeded inputs that are
+//! identical everywhere.
+//! Set the `UPDATE_GOLDEN=1` environment variable to rewrite golden files from the
+//! current output instead of comparing against them.
+
+use crate::error::{Error, Result};
+use crate::models::pose::Pose;
+use crate::simulation::result::SimulationResult;
+use std::fmt;
+use std::fs;
+use std::io;
+use std::path::Path;
+
+pub use crate::math::random::SplitMix64;
+
+/// Environment variable that switches golden comparisons into update mode.
+pub const UPDATE_GOLDEN_ENV: &str = "UPDATE_GOLDEN";
+
+/// Number of decimals kept for fractional numbers during normalization.
+pub const DEFAULT_DECIMALS: usize = 3;
+
+/// First difference found between two normalized SVG documents.
+#[derive(Debug, Clone, PartialEq)]
+pub struct SvgMismatch {
+    /// 1-based line number in the normalized documents
+    pub line: usize,
+    /// Normalized expected line (empty if the expected document ended)
+    pub expected: String,
+    /// Normalized actual line (empty if the actual document ended)
+    pub actual: String,
+}
+
+impl fmt::Display for SvgMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "SVG mismatch at normalized line {}:\n  expected: {}\n  actual:   {}",
+            self.line, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for SvgMismatch {}
+
+/// Normalize an SVG document for comparison.
+///
+/// Converts line endings, trims each line, drops blank lines, and rounds fractional
+/// numbers to `DEFAULT_DECIMALS` decimals.
+pub fn normalize_svg(svg: &str) -> String {
+    normalize_svg_with_decimals(svg, DEFAULT_DECIMALS)
+}
+
+/// Same as `normalize_svg`, with a custom number of decimals for fractional numbers.
+pub fn normalize_svg_with_decimals(svg: &str, decimals: usize) -> String {
+    svg.replace("\r\n", "\n")
+        .lines()
+        .map(str::trim)
+        .filter(|line| !line.is_empty())
+        .map(|line| round_numbers(line, decimals))
+        .collect::<Vec<_>>()
+        .join("\n")
+}
+
+/// Compare two SVG documents after normalization.
+///
+/// # Returns
+/// `Ok(())` if they match, otherwise the first differing line
+pub fn compare_svg(actual: &str, expected: &str) -> Result<(), SvgMismatch> {
+    let actual = normalize_svg(actual);
+    let expected = normalize_svg(expected);
+    let mut actual_lines = actual.lines();
+    let mut expected_lines = expected.lines();
+    let mut line = 0usize;
+
+    loop {
+        line += 1;
+        match (actual_lines.next(), expected_lines.next()) {
+            (None, None) => return Ok(()),
+            (a, e) if a == e => continue,
+            (a, e) => {
+                return Err(SvgMismatch {
+                    line,
+                    expected: e.unwrap_or_default().to_string(),
+                    actual: a.unwrap_or_default().to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Compare an SVG file against a golden file, or update the golden file.
+///
+/// When `UPDATE_GOLDEN=1` is set, the golden file is (re)written from the normalized
+/// actual output instead of being compared.
+///
+/// # Arguments
+/// * `actual_path` - Path of the freshly generated SVG
+/// * `golden_path` - Path of the checked-in golden SVG
+///
+/// # Returns
+/// `Error::Io` if a file cannot be read or written, or `Error::InvalidInput` describing
+/// the first difference
+pub fn check_golden_svg<P: AsRef<Path>, Q: AsRef<Path>>(
+    actual_path: P,
+    golden_path: Q,
+) -> Result<()> {
+    let actual = fs::read_to_string(actual_path.as_ref())?;
+    let golden_path = golden_path.as_ref();
+
+    if update_golden_requested() {
+        if let Some(parent) = golden_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(golden_path, normalize_svg(&actual) + "\n")?;
+        return Ok(());
+    }
+
+    let expected = read_golden(golden_path)?;
+    compare_svg(&actual, &expected).map_err(|mismatch| {
+        Error::invalid_input(format!(
+            "{} does not match golden file {}: {}",
+            actual_path.as_ref().display(),
+            golden_path.display(),
+            mismatch
+        ))
+    })?;
+    Ok(())
+}
+
+/// Default absolute tolerance (scaled up for values larger than 1) of trace comparisons.
+pub const DEFAULT_TRACE_TOLERANCE: f64 = 1e-6;
+
+/// Header line of state traces written by `format_trace`.
+pub const TRACE_HEADER: &str = "time,x,y,yaw,in_track";
+
+/// First difference found between two state traces.
+#[derive(Debug, Clone, PartialEq)]
+pub struct TraceMismatch {
+    /// 1-based line number in the traces (line 1 is the header)
+    pub line: usize,
+    /// Column that differs (`row` if one trace ended early)
+    pub column: String,
+    /// Expected value (empty if the expected trace ended)
+    pub expected: String,
+    /// Actual value (empty if the actual trace ended)
+    pub actual: String,
+}
+
+impl fmt::Display for TraceMismatch {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "trace mismatch at line {}, column {}:\n  expected: {}\n  actual:   {}",
+            self.line, self.column, self.expected, self.actual
+        )
+    }
+}
+
+impl std::error::Error for TraceMismatch {}
+
+/// Format the time, pose, and track status of every snapshot as CSV.
+///
+/// Values are written with 9 decimals, well below any useful tolerance but above the
+/// noise of platform math libraries.
+pub fn format_trace<S: Pose>(result: &SimulationResult<S>) -> String {
+    let mut trace = String::from(TRACE_HEADER);
+    trace.push('\n');
+    for snapshot in result.snapshots() {
+        let (x, y, yaw) = snapshot.state.pose();
+        trace.push_str(&format!(
+            "{:.9},{:.9},{:.9},{:.9},{}\n",
+            snapshot.time, x, y, yaw, snapshot.in_track
+        ));
+    }
+    trace
+}
+
+/// Compare two state traces value by value.
+///
+/// Numbers match when they differ by at most `tolerance`, scaled by the magnitude of
+/// the expected value when it exceeds 1; other values must match exactly.
+///
+/// # Returns
+/// `Ok(())` if they match, otherwise the first differing value
+pub fn compare_trace(actual: &str, expected: &str, tolerance: f64) -> Result<(), TraceMismatch> {
+    let actual = actual.replace("\r\n", "\n");
+    let expected = expected.replace("\r\n", "\n");
+    let mut actual_lines = actual.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut expected_lines = expected.lines().map(str::trim).filter(|line| !line.is_empty());
+    let mut columns: Vec<String> = Vec::new();
+    let mut line = 0usize;
+
+    loop {
+        line += 1;
+        let (actual_line, expected_line) = match (actual_lines.next(), expected_lines.next()) {
+            (None, None) => return Ok(()),
+            (Some(a), Some(e)) => (a, e),
+            (a, e) => {
+                return Err(TraceMismatch {
+                    line,
+                    column: "row".to_string(),
+                    expected: e.unwrap_or_default().to_string(),
+                    actual: a.unwrap_or_default().to_string(),
+                });
+            }
+        };
+        if line == 1 {
+            columns = expected_line.split(',').map(str::to_string).collect();
+        }
+
+        let actual_values: Vec<&str> = actual_line.split(',').collect();
+        let expected_values: Vec<&str> = expected_line.split(',').collect();
+        for index in 0..actual_values.len().max(expected_values.len()) {
+            let a = actual_values.get(index).copied().unwrap_or_default();
+            let e = expected_values.get(index).copied().unwrap_or_default();
+            let matches = match (a.parse::<f64>(), e.parse::<f64>()) {
+                (Ok(a), Ok(e)) => (a - e).abs() <= tolerance * e.abs().max(1.0),
+                _ => a == e,
+            };
+            if !matches {
+                return Err(TraceMismatch {
+                    line,
+                    column: columns
+                        .get(index)
+                        .cloned()
+                        .unwrap_or_else(|| (index + 1).to_string()),
+                    expected: e.to_string(),
+                    actual: a.to_string(),
+                });
+            }
+        }
+    }
+}
+
+/// Compare the trace of `result` against a golden trace file, or update the file.
+///
+/// When `UPDATE_GOLDEN=1` is set, the golden file is (re)written from `result`
+/// instead of being compared.
+///
+/// # Arguments
+/// * `result` - Recorded simulation result
+/// * `golden_path` - Path of the checked-in golden trace
+/// * `tolerance` - Tolerance of the comparison, see `compare_trace`
+///
+/// # Returns
+/// `Error::Io` if the golden file cannot be read or written, or `Error::InvalidInput`
+/// describing the first difference
+pub fn check_golden_trace<S: Pose, P: AsRef<Path>>(
+    result: &SimulationResult<S>,
+    golden_path: P,
+    tolerance: f64,
+) -> Result<()> {
+    let actual = format_trace(result);
+    let golden_path = golden_path.as_ref();
+
+    if update_golden_requested() {
+        if let Some(parent) = golden_path.parent()
+            && !parent.as_os_str().is_empty()
+        {
+            fs::create_dir_all(parent)?;
+        }
+        fs::write(golden_path, actual)?;
+        return Ok(());
+    }
+
+    let expected = read_golden(golden_path)?;
+    compare_trace(&actual, &expected, tolerance).map_err(|mismatch| {
+        let message =
+            format!("trace does not match golden file {}: {}", golden_path.display(), mismatch);
+        Error::invalid_input(message)
+    })?;
+    Ok(())
+}
+
+/// Read a golden file, keeping the kind of the I/O error and hinting at update mode
+fn read_golden(golden_path: &Path) -> io::Result<String> {
+    fs::read_to_string(golden_path).map_err(|err| {
+        let message = format!(
+            "failed to read golden file {} ({}); run with {}=1 to create it",
+            golden_path.display(),
+            err,
+            UPDATE_GOLDEN_ENV
+        );
+        io::Error::new(err.kind(), message)
+    })
+}
+
+/// Whether golden files should be rewritten instead of compared.
+pub fn update_golden_requested() -> bool {
+    std::env::var(UPDATE_GOLDEN_ENV).is_ok_and(|value| value == "1" || value == "true")
+}
+
+/// Round every fractional number in `line` to `decimals` decimals.
+fn round_numbers(line: &str, decimals: usize) -> String {
+    let chars: Vec<char> = line.chars().collect();
+    let mut output = String::with_capacity(line.len());
+    let mut index = 0usize;
+
+    while index < chars.len() {
+        let starts_number = chars[index].is_ascii_digit()
+            && (index == 0 || !(chars[index - 1].is_ascii_alphabetic() || chars[index - 1] == '_'));
+        if !starts_number {
+            output.push(chars[index]);
+            index += 1;
+            continue;
+        }
+
+        let start = index;
+        while index < chars.len() && chars[index].is_ascii_digit() {
+            index += 1;
+        }
+        if index + 1 < chars.len() && chars[index] == '.' && chars[index + 1].is_ascii_digit() {
+            index += 1;
+            while index < chars.len() && chars[index].is_ascii_digit() {
+                index += 1;
+            }
+        }
+
+        let token: String = chars[start..index].iter().collect();
+        match token.contains('.').then(|| token.parse::<f64>()) {
+            Some(Ok(value)) => {
+                let rounded = format!("{:.*}", decimals, value);
+                let rounded = rounded.trim_end_matches('0').trim_end_matches('.');
+                output.push_str(rounded);
+            }
+            _ => output.push_str(&token),
+        }
+    }
+
+    output
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{
+        compare_svg, compare_trace, format_trace, normalize_svg, round_numbers,
+        TRACE_HEADER,
+    };
+    use crate::simulation::result::SimulationResult;
+
+    #[test]
+    fn test_round_numbers() {
+        assert_eq!(round_numbers("M 1.23456 2.5 L 3.0001", 3), "M 1.235 2.5 L 3");
+        assert_eq!(round_numbers("<svg width=\"800\">", 3), "<svg width=\"800\">");
+        assert_eq!(
+            round_numbers("stroke-width=\"2\" x2=\"4.1\"", 3),
+            "stroke-width=\"2\" x2=\"4.1\""
+        );
+    }
+
+    #[test]
+    fn test_normalize_svg_ignores_whitespace_and_line_endings() {
+        let a = "<svg>\r\n    <line x1=\"1.00001\"/>\r\n\r\n</svg>\r\n";
+        let b = "<svg>\n<line x1=\"1\"/>\n</svg>";
+        assert_eq!(normalize_svg(a), normalize_svg(b));
+    }
+
+    #[test]
+    fn test_compare_svg_reports_first_mismatch() {
+        let mismatch = compare_svg("<svg>\n<a/>\n</svg>", "<svg>\n<b/>\n</svg>")
+            .expect_err("expected mismatch");
+        assert_eq!(mismatch.line, 2);
+        assert_eq!(mismatch.expected, "<b/>");
+        assert_eq!(mismatch.actual, "<a/>");
+    }
+
+    #[test]
+    fn test_compare_svg_reports_missing_lines() {
+        let mismatch = compare_svg("<svg>\n</svg>", "<svg>\n<a/>\n</svg>")
+            .expect_err("expected mismatch");
+        assert_eq!(mismatch.line, 2);
+        assert_eq!(mismatch.actual, "</svg>");
+    }
+
+    #[test]
+    fn test_format_trace() {
+        let mut result = SimulationResult::new();
+        result.push(0.0, (1.0, 2.0, 0.5), true);
+        result.push(0.1, (1.5, 2.0, 0.5), false);
+
+        let trace = format_trace(&result);
+        let lines: Vec<&str> = trace.lines().collect();
+        assert_eq!(lines[0], TRACE_HEADER);
+        assert_eq!(lines[1], "0.000000000,1.000000000,2.000000000,0.500000000,true");
+        assert_eq!(lines[2], "0.100000000,1.500000000,2.000000000,0.500000000,false");
+    }
+
+    #[test]
+    fn test_compare_trace_within_tolerance() {
+        let expected = "time,x,y,yaw,in_track\n0.1,100.0,2.0,0.5,true\n";
+        let actual = "time,x,y,yaw,in_track\r\n0.1,100.00001,2.0000000001,0.5,true\r\n";
+        compare_trace(actual, expected, 1e-6).expect("traces should match");
+
+        let mismatch = compare_trace(actual, expected, 1e-8).expect_err("expected mismatch");
+        assert_eq!(mismatch.line, 2);
+        assert_eq!(mismatch.column, "x");
+    }
+
+    #[test]
+    fn test_compare_trace_reports_flags_and_length() {
+        let expected = "time,x,y,yaw,in_track\n0,0,0,0,true\n0.1,0,0,0,true\n";
+
+        let flipped = "time,x,y,yaw,in_track\n0,0,0,0,false\n0.1,0,0,0,true\n";
+        let mismatch = compare_trace(flipped, expected, 1e-6).expect_err("expected mismatch");
+        assert_eq!(mismatch.column, "in_track");
+        assert_eq!(mismatch.actual, "false");
+
+        let short = "time,x,y,yaw,in_track\n0,0,0,0,true\n";
+        let mismatch = compare_trace(short, expected, 1e-6).expect_err("expected mismatch");
+        assert_eq!(mismatch.line, 3);
+        assert_eq!(mismatch.column, "row");
+        assert!(mismatch.actual.is_empty());
+    }
+}
@@ -0,0 +1,323 @@
+//! Cumulative arc length of closed polylines (center lines, racing lines).
+//!
+//! The table is computed once; `s`→point queries then binary search the cumulative
+//! lengths instead of walking the polyline, and point→`s` queries can be restricted
+//! to a window around the previous position when following a vehicle step by step.
+
+use super::base_track::Track;
+use crate::math::angle::Angle;
+use crate::math::vec2::Vec2;
+
+/// Position of a point relative to a closed polyline (usually a track's center line)
+#[derive(Debug, Clone, Copy, PartialEq, Default)]
+pub struct TrackPosition {
+    /// Distance along the line from its first point to the closest point, in meters
+    pub s: f64,
+    /// Signed distance from the line in meters, positive to the left of its direction
+    pub lateral_error: f64,
+    /// Curvature of the line at `s` in 1/m, positive in left turns
+    pub curvature: f64,
+}
+
+/// Cumulative distance along a closed polyline
+#[derive(Debug, Clone, PartialEq)]
+pub struct ArcLengthTable {
+    points: Vec<Vec2>,
+    /// Distance from the first point to each point; one extra entry closes the loop
+    cumulative: Vec<f64>,
+}
+
+impl ArcLengthTable {
+    /// Build the table of a closed polyline (the last point connects back to the first)
+    pub fn new(points: &[Vec2]) -> Self {
+        let n = points.len();
+        let mut cumulative = Vec::with_capacity(n + 1);
+        cumulative.push(0.0);
+        for i in 0..n {
+            cumulative.push(cumulative[i] + points[i].distance(points[(i + 1) % n]));
+        }
+        Self {
+            points: points.to_vec(),
+            cumulative,
+        }
+    }
+
+    /// Build the table of a track's center line
+    pub fn from_track(track: &dyn Track) -> Self {
+        Self::new(track.get_center_line())
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Length of the closed loop in meters
+    pub fn total_length(&self) -> f64 {
+        self.cumulative.last().copied().unwrap_or(0.0)
+    }
+
+    /// Distance from the first point to the point at `index`
+    pub fn distance_at(&self, index: usize) -> f64 {
+        self.cumulative[index]
+    }
+
+    /// Distance from the first point to every point, in point order
+    pub fn distances(&self) -> &[f64] {
+        &self.cumulative[..self.points.len()]
+    }
+
+    /// Curvature at every point in 1/m, positive in left turns
+    ///
+    /// The curvature at a point is the heading change from the segment ending there to
+    /// the segment starting there, over the distance between their midpoints. Together
+    /// with `distances` this is the curvature profile `kappa(s)` of the loop.
+    pub fn curvatures(&self) -> Vec<f64> {
+        let n = self.points.len();
+        if n < 3 {
+            return vec![0.0; n];
+        }
+        (0..n).map(|i| self.turn_curvature((i + n - 1) % n)).collect()
+    }
+
+    /// Index of the segment containing distance `s` (wrapped onto the loop)
+    ///
+    /// Segment `i` runs from point `i` to point `i + 1` (or back to the first point).
+    pub fn segment_at(&self, s: f64) -> usize {
+        let n = self.points.len();
+        if n < 2 {
+            return 0;
+        }
+        let s = self.wrap(s);
+        self.cumulative[1..n].partition_point(|&distance| distance <= s)
+    }
+
+    /// Point and heading at distance `s` along the loop (wrapped onto the loop)
+    ///
+    /// # Returns
+    /// Tuple of (x, y, heading) with the heading in radians, or `None` for an empty table
+    pub fn point_at(&self, s: f64) -> Option<(f64, f64, f64)> {
+        let n = self.points.len();
+        if n == 0 {
+            return None;
+        }
+        let s = self.wrap(s);
+        let segment = self.segment_at(s);
+        let (start, end) = (self.points[segment], self.points[(segment + 1) % n]);
+        let length = self.cumulative[segment + 1] - self.cumulative[segment];
+        let t = if length > 0.0 {
+            ((s - self.cumulative[segment]) / length).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        let point = start.lerp(end, t);
+        Some((point.x, point.y, (end - start).angle()))
+    }
+
+    /// Distance along the loop of the point closest to (x, y)
+    ///
+    /// Scans every segment; prefer `project_near` when the previous position is known.
+    pub fn project(&self, x: f64, y: f64) -> Option<f64> {
+        self.project_segments(x, y, 0..self.points.len())
+    }
+
+    /// Distance along the loop of the closest point within `window` meters of `s_hint`
+    ///
+    /// Only the segments around the hint are checked, so following a vehicle costs
+    /// O(log n + window) per step instead of O(n).
+    pub fn project_near(&self, x: f64, y: f64, s_hint: f64, window: f64) -> Option<f64> {
+        let n = self.points.len();
+        let total = self.total_length();
+        if n == 0 || !(window.is_finite() && window * 2.0 < total) {
+            return self.project(x, y);
+        }
+        let first = self.segment_at(s_hint - window);
+        let last = self.segment_at(s_hint + window);
+        let count = (last + n - first) % n + 1;
+        self.project_segments(x, y, (first..first + count).map(|index| index % n))
+    }
+
+    /// Position of (x, y) relative to the loop (see `project`)
+    pub fn position(&self, x: f64, y: f64) -> Option<TrackPosition> {
+        self.project(x, y).map(|s| self.position_at(x, y, s))
+    }
+
+    /// Position of (x, y) relative to the loop, searched around `s_hint` (see `project_near`)
+    pub fn position_near(
+        &self,
+        x: f64,
+        y: f64,
+        s_hint: f64,
+        window: f64,
+    ) -> Option<TrackPosition> {
+        self.project_near(x, y, s_hint, window).map(|s| self.position_at(x, y, s))
+    }
+
+    /// Curvature at distance `s` in 1/m, positive in left turns
+    ///
+    /// Estimated from the heading change between the segment containing `s` and the
+    /// next one, over the distance between their midpoints.
+    pub fn curvature_at(&self, s: f64) -> f64 {
+        if self.points.len() < 3 {
+            return 0.0;
+        }
+        self.turn_curvature(self.segment_at(s))
+    }
+
+    /// Curvature of the turn from `segment` to the next segment in 1/m
+    fn turn_curvature(&self, segment: usize) -> f64 {
+        let next = (segment + 1) % self.points.len();
+        let spacing = (self.segment_length(segment) + self.segment_length(next)) / 2.0;
+        if spacing <= 0.0 {
+            return 0.0;
+        }
+        Angle::wrap(self.segment_direction(next).angle() - self.segment_direction(segment).angle())
+            / spacing
+    }
+
+    fn position_at(&self, x: f64, y: f64, s: f64) -> TrackPosition {
+        let segment = self.segment_at(s);
+        let offset = Vec2::new(x, y) - self.points[segment];
+        let lateral_error = self
+            .segment_direction(segment)
+            .normalized()
+            .map_or(0.0, |direction| direction.cross(offset));
+        TrackPosition {
+            s,
+            lateral_error,
+            curvature: self.curvature_at(s),
+        }
+    }
+
+    fn segment_direction(&self, segment: usize) -> Vec2 {
+        self.points[(segment + 1) % self.points.len()] - self.points[segment]
+    }
+
+    fn segment_length(&self, segment: usize) -> f64 {
+        self.cumulative[segment + 1] - self.cumulative[segment]
+    }
+
+    fn project_segments<I: Iterator<Item = usize>>(
+        &self,
+        x: f64,
+        y: f64,
+        segments: I,
+    ) -> Option<f64> {
+        let n = self.points.len();
+        let mut best: Option<(f64, f64)> = None;
+        for segment in segments {
+            let start = self.points[segment];
+            let direction = self.points[(segment + 1) % n] - start;
+            let offset = Vec2::new(x, y) - start;
+            let length_squared = direction.norm_squared();
+            let t = if length_squared > 0.0 {
+                (offset.dot(direction) / length_squared).clamp(0.0, 1.0)
+            } else {
+                0.0
+            };
+            let distance_squared = (offset - direction * t).norm_squared();
+            if best.is_none_or(|(best_distance, _)| distance_squared < best_distance) {
+                let s = self.cumulative[segment] + t * length_squared.sqrt();
+                best = Some((distance_squared, s));
+            }
+        }
+        best.map(|(_, s)| self.wrap(s))
+    }
+
+    /// Map `s` onto [0, total_length)
+    fn wrap(&self, s: f64) -> f64 {
+        let total = self.total_length();
+        if total > 0.0 {
+            s.rem_euclid(total)
+        } else {
+            0.0
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::ArcLengthTable;
+    use crate::math::vec2::Vec2;
+    use crate::tracks::circle::CircleTrack;
+    use std::f64::consts::PI;
+
+    fn unit_square() -> ArcLengthTable {
+        ArcLengthTable::new(&[
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ])
+    }
+
+    #[test]
+    fn test_point_at_distance() {
+        let table = unit_square();
+        assert_eq!(table.total_length(), 4.0);
+        assert_eq!(table.distance_at(2), 2.0);
+        assert_eq!(table.segment_at(0.0), 0);
+        assert_eq!(table.segment_at(2.5), 2);
+        assert_eq!(table.segment_at(3.99), 3);
+
+        let (x, y, heading) = table.point_at(1.5).expect("point");
+        assert_eq!((x, y), (1.0, 0.5));
+        assert!((heading - PI / 2.0).abs() < 1e-12);
+
+        // Distances wrap around the loop
+        let (x, y, _) = table.point_at(-0.5).expect("point");
+        assert_eq!((x, y), (0.0, 0.5));
+        assert_eq!(table.point_at(4.25).map(|p| (p.0, p.1)), Some((0.25, 0.0)));
+
+        assert_eq!(ArcLengthTable::new(&[]).point_at(1.0), None);
+    }
+
+    #[test]
+    fn test_project_point() {
+        let table = unit_square();
+        assert_eq!(table.project(0.5, -0.2), Some(0.5));
+        assert_eq!(table.project(1.3, 0.75), Some(1.75));
+        assert_eq!(table.project(-0.1, 0.25), Some(3.75));
+    }
+
+    #[test]
+    fn test_position_relative_to_circle() {
+        let track = CircleTrack::new(50.0, 10.0, 200);
+        let table = ArcLengthTable::from_track(&track);
+
+        // Counter-clockwise loop: the circle center is to the left
+        let position = table.position(0.0, 47.0).expect("position");
+        assert!((position.s - table.total_length() / 4.0).abs() < 0.1);
+        assert!((position.lateral_error - 3.0).abs() < 0.01);
+        assert!((position.curvature - 1.0 / 50.0).abs() < 1e-4);
+
+        let position = table
+            .position_near(0.0, 54.0, table.total_length() / 4.0, 10.0)
+            .expect("position");
+        assert!((position.lateral_error + 4.0).abs() < 0.01);
+
+        assert_eq!(unit_square().curvature_at(0.5), PI / 2.0);
+        assert_eq!(unit_square().curvatures(), vec![PI / 2.0; 4]);
+        assert_eq!(unit_square().distances(), &[0.0, 1.0, 2.0, 3.0]);
+        assert_eq!(ArcLengthTable::new(&[]).position(0.0, 0.0), None);
+    }
+
+    #[test]
+    fn test_project_near_matches_full_scan() {
+        let track = CircleTrack::new(50.0, 10.0, 200);
+        let table = ArcLengthTable::from_track(&track);
+        assert!((table.total_length() - 2.0 * PI * 50.0).abs() < 0.1);
+
+        let mut s = 0.0;
+        for step in 0..100 {
+            let angle = step as f64 * 0.07;
+            let (x, y) = (52.0 * angle.cos(), 52.0 * angle.sin());
+            s = table.project_near(x, y, s, 10.0).expect("projection");
+            let full = table.project(x, y).expect("projection");
+            assert!((s - full).abs() < 1e-9, "step {step}: {s} != {full}");
+        }
+    }
+}
@@ -1,3 +1,7 @@
+use super::arc_length::ArcLengthTable;
+use crate::math::vec2::Vec2;
+use std::sync::Arc;
+
 /// Trait for track definitions with boundaries and center line
 pub trait Track {
     /// Initialize the track from coordinate lists
@@ -9,9 +13,9 @@ pub trait Track {
     /// * `get_start_position` - (x, y, yaw) coordinates of the starting position and orientation
     fn init(
         &mut self,
-        center_line: Vec<(f64, f64)>,
-        inside_border: Vec<(f64, f64)>,
-        outside_border: Vec<(f64, f64)>,
+        center_line: Vec<Vec2>,
+        inside_border: Vec<Vec2>,
+        outside_border: Vec<Vec2>,
         get_start_position: (f64, f64, f64),
     );
     
@@ -35,7 +39,7 @@ pub trait Track {
     /// 
     /// # Returns
     /// Reference to the list of (x, y) coordinates defining the center line
-    fn get_center_line(&self) -> &[(f64, f64)];
+    fn get_center_line(&self) -> &[Vec2];
 
     /// Get the yaw orientation along the center line
     ///
@@ -47,13 +51,13 @@ pub trait Track {
     /// 
     /// # Returns
     /// Reference to the list of (x, y) coordinates defining the inside boundary
-    fn get_inside_boundary(&self) -> &[(f64, f64)];
+    fn get_inside_boundary(&self) -> &[Vec2];
     
     /// Get the outside boundary coordinates
     /// 
     /// # Returns
     /// Reference to the list of (x, y) coordinates defining the outside boundary
-    fn get_outside_boundary(&self) -> &[(f64, f64)];
+    fn get_outside_boundary(&self) -> &[Vec2];
     
     /// Get the name of the track for plotting
     /// 
@@ -66,10 +70,121 @@ pub trait Track {
     /// # Returns
     /// Tuple of (min_coord, max_coord) for the plot range
     fn get_plot_range(&self) -> (f64, f64);
+
+    /// Length of the closed center line in meters
+    fn total_length(&self) -> f64 {
+        ArcLengthTable::new(self.get_center_line()).total_length()
+    }
+
+    /// Distance along the center line from its first point to every center line point
+    ///
+    /// # Returns
+    /// Cumulative arc length `s` in meters, one entry per center line point
+    fn center_line_arc_length(&self) -> Vec<f64> {
+        ArcLengthTable::new(self.get_center_line()).distances().to_vec()
+    }
+
+    /// Curvature of the center line at every center line point
+    ///
+    /// Paired with `center_line_arc_length`, this is the curvature profile `kappa(s)`
+    /// used for speed profiles, apex detection, and distance-based plots.
+    ///
+    /// # Returns
+    /// Curvature in 1/m, positive in left turns, one entry per center line point
+    fn center_line_curvature(&self) -> Vec<f64> {
+        ArcLengthTable::new(self.get_center_line()).curvatures()
+    }
+
+    /// Pose on the center line at distance `s` from its first point
+    ///
+    /// The position is interpolated linearly between the center line points and the
+    /// heading is the one of the segment containing `s`. Distances wrap around the loop,
+    /// so negative values and values beyond `total_length` are valid. Each call builds
+    /// the arc length table of the center line; build an `ArcLengthTable` once instead
+    /// when querying many distances.
+    ///
+    /// # Arguments
+    /// * `s` - Distance along the center line in meters
+    ///
+    /// # Returns
+    /// Tuple of (x, y, yaw), or the start position for a track without center line
+    fn pose_at(&self, s: f64) -> (f64, f64, f64) {
+        ArcLengthTable::new(self.get_center_line())
+            .point_at(s)
+            .unwrap_or_else(|| self.get_start_position())
+    }
 }
 
+/// Boxed tracks are tracks too, so `Box<dyn Track>` can be used wherever a `Track` is
+/// expected (e.g., a track type chosen from a config at runtime)
+impl<T: Track + ?Sized> Track for Box<T> {
+    fn init(
+        &mut self,
+        center_line: Vec<Vec2>,
+        inside_border: Vec<Vec2>,
+        outside_border: Vec<Vec2>,
+        get_start_position: (f64, f64, f64),
+    ) {
+        (**self).init(center_line, inside_border, outside_border, get_start_position)
+    }
+
+    fn is_in_track(&self, x: f64, y: f64) -> bool {
+        (**self).is_in_track(x, y)
+    }
+
+    fn get_start_position(&self) -> (f64, f64, f64) {
+        (**self).get_start_position()
+    }
+
+    fn get_center_line(&self) -> &[Vec2] {
+        (**self).get_center_line()
+    }
+
+    fn get_center_line_yaw(&self) -> &[f64] {
+        (**self).get_center_line_yaw()
+    }
+
+    fn get_inside_boundary(&self) -> &[Vec2] {
+        (**self).get_inside_boundary()
+    }
+
+    fn get_outside_boundary(&self) -> &[Vec2] {
+        (**self).get_outside_boundary()
+    }
+
+    fn get_track_name(&self) -> &str {
+        (**self).get_track_name()
+    }
+
+    fn get_plot_range(&self) -> (f64, f64) {
+        (**self).get_plot_range()
+    }
+
+    fn total_length(&self) -> f64 {
+        (**self).total_length()
+    }
+
+    fn center_line_arc_length(&self) -> Vec<f64> {
+        (**self).center_line_arc_length()
+    }
+
+    fn center_line_curvature(&self) -> Vec<f64> {
+        (**self).center_line_curvature()
+    }
+
+    fn pose_at(&self, s: f64) -> (f64, f64, f64) {
+        (**self).pose_at(s)
+    }
+}
+
+/// Track shared between a simulation, its controllers, and plotting
+///
+/// Build the track once and clone the `Arc` instead of constructing the same track
+/// several times with (hopefully) matching parameters.
+pub type SharedTrack = Arc<dyn Track + Send + Sync>;
+
 /// Compute yaw angles for a closed center line using forward differences.
-pub fn compute_center_line_yaw(center_line: &[(f64, f64)]) -> Vec<f64> {
+pub fn compute_center_line_yaw(center_line: &[Vec2]) -> Vec<f64> {
     let n = center_line.len();
     if n == 0 {
         return Vec::new();
@@ -78,13 +193,88 @@ pub fn compute_center_line_yaw(center_line: &[(f64, f64)]) -> Vec<f64> {
         return vec![0.0];
     }
 
-    let mut yaw = Vec::with_capacity(n);
+    (0..n)
+        .map(|i| (center_line[(i + 1) % n] - center_line[i]).angle())
+        .collect()
+}
+
+/// Check whether a point lies inside a closed polygon using ray casting.
+///
+/// Points exactly on an edge may be reported either way.
+pub fn point_in_polygon(x: f64, y: f64, polygon: &[Vec2]) -> bool {
+    let n = polygon.len();
+    if n < 3 {
+        return false;
+    }
+
+    let mut inside = false;
+    let mut j = n - 1;
     for i in 0..n {
-        let (x0, y0) = center_line[i];
-        let (x1, y1) = center_line[(i + 1) % n];
-        let dx = x1 - x0;
-        let dy = y1 - y0;
-        yaw.push(dy.atan2(dx));
+        let Vec2 { x: xi, y: yi } = polygon[i];
+        let Vec2 { x: xj, y: yj } = polygon[j];
+        if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+            inside = !inside;
+        }
+        j = i;
+    }
+    inside
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{point_in_polygon, Track};
+    use crate::math::vec2::Vec2;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+    use std::f64::consts::PI;
+
+    #[test]
+    fn test_point_in_polygon_square() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+
+        assert!(point_in_polygon(5.0, 5.0, &square));
+        assert!(!point_in_polygon(15.0, 5.0, &square));
+        assert!(!point_in_polygon(5.0, -1.0, &square));
+    }
+
+    #[test]
+    fn test_track_length_and_pose_at() {
+        // Center line starts at (50, -50) and runs up the right side
+        let square: Box<dyn Track> = Box::new(SquareTrack::new(100.0, 10.0, 25));
+        assert!((square.total_length() - 400.0).abs() < 1e-9);
+
+        let (x, y, yaw) = square.pose_at(10.0);
+        assert!((x - 50.0).abs() < 1e-9 && (y + 40.0).abs() < 1e-9);
+        assert!((yaw - PI / 2.0).abs() < 1e-9);
+
+        let (x, y, yaw) = square.pose_at(-10.0);
+        assert!((x - 40.0).abs() < 1e-9 && (y + 50.0).abs() < 1e-9);
+        assert!(yaw.abs() < 1e-9);
+        assert_eq!(square.pose_at(0.0), square.get_start_position());
+
+        let circle = CircleTrack::new(50.0, 10.0, 360);
+        assert!((circle.total_length() - 2.0 * PI * 50.0).abs() < 0.01);
+        let (x, y, _) = circle.pose_at(circle.total_length() / 4.0);
+        assert!(x.abs() < 0.01 && (y - 50.0).abs() < 0.01);
+
+        // Straight sides with a quarter turn at each corner
+        let s = square.center_line_arc_length();
+        assert_eq!(s.len(), square.get_center_line().len());
+        assert_eq!((s[1], s[25]), (4.0, 100.0));
+        let curvature = square.center_line_curvature();
+        assert_eq!(curvature[5], 0.0);
+        assert!(curvature[0] > 0.0);
+        let circle_curvature = circle.center_line_curvature();
+        assert!(circle_curvature.iter().all(|k| (k - 0.02).abs() < 1e-4));
+    }
+
+    #[test]
+    fn test_point_in_polygon_degenerate() {
+        assert!(!point_in_polygon(0.0, 0.0, &[Vec2::ZERO, Vec2::new(1.0, 1.0)]));
     }
-    yaw
 }
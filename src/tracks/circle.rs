@@ -1,13 +1,15 @@
 use super::base_track::{compute_center_line_yaw, Track};
+use crate::math::vec2::Vec2;
 use std::f64::consts::PI;
 use std::fmt;
 
 /// Circular track defined by center line radius and track width
+#[derive(Debug, Clone, PartialEq)]
 pub struct CircleTrack {
-    center_line: Vec<(f64, f64)>,
+    center_line: Vec<Vec2>,
     center_line_yaw: Vec<f64>,
-    inside_border: Vec<(f64, f64)>,
-    outside_border: Vec<(f64, f64)>,
+    inside_border: Vec<Vec2>,
+    outside_border: Vec<Vec2>,
     start_pos: (f64, f64, f64),
     center_radius: f64,
     track_width: f64,
@@ -47,30 +49,15 @@ impl CircleTrack {
         
         for i in 0..num_points {
             let angle = 2.0 * PI * (i as f64) / (num_points as f64);
-            let cos_a = angle.cos();
-            let sin_a = angle.sin();
+            let direction = Vec2::from_angle(angle);
             
-            // Center line
-            self.center_line.push((
-                self.center_radius * cos_a,
-                self.center_radius * sin_a,
-            ));
-            
-            // Inside boundary
-            self.inside_border.push((
-                inside_radius * cos_a,
-                inside_radius * sin_a,
-            ));
-            
-            // Outside boundary
-            self.outside_border.push((
-                outside_radius * cos_a,
-                outside_radius * sin_a,
-            ));
+            self.center_line.push(direction * self.center_radius);
+            self.inside_border.push(direction * inside_radius);
+            self.outside_border.push(direction * outside_radius);
         }
 
         self.center_line_yaw = compute_center_line_yaw(&self.center_line);
-        if let (Some(&(x, y)), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
+        if let (Some(&Vec2 { x, y }), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
             self.start_pos = (x, y, yaw);
         }
     }
@@ -79,9 +66,9 @@ impl CircleTrack {
 impl Track for CircleTrack {
     fn init(
         &mut self,
-        center_line: Vec<(f64, f64)>,
-        inside_border: Vec<(f64, f64)>,
-        outside_border: Vec<(f64, f64)>,
+        center_line: Vec<Vec2>,
+        inside_border: Vec<Vec2>,
+        outside_border: Vec<Vec2>,
         get_start_position: (f64, f64, f64),
     ) {
         self.center_line = center_line;
@@ -89,7 +76,7 @@ impl Track for CircleTrack {
         self.inside_border = inside_border;
         self.outside_border = outside_border;
         self.start_pos = get_start_position;
-        if let (Some(&(x, y)), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
+        if let (Some(&Vec2 { x, y }), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
             self.start_pos = (x, y, yaw);
         }
     }
@@ -106,7 +93,7 @@ impl Track for CircleTrack {
         self.start_pos
     }
     
-    fn get_center_line(&self) -> &[(f64, f64)] {
+    fn get_center_line(&self) -> &[Vec2] {
         &self.center_line
     }
 
@@ -114,11 +101,11 @@ impl Track for CircleTrack {
         &self.center_line_yaw
     }
     
-    fn get_inside_boundary(&self) -> &[(f64, f64)] {
+    fn get_inside_boundary(&self) -> &[Vec2] {
         &self.inside_border
     }
     
-    fn get_outside_boundary(&self) -> &[(f64, f64)] {
+    fn get_outside_boundary(&self) -> &[Vec2] {
         &self.outside_border
     }
     
@@ -178,8 +165,8 @@ mod tests {
         let center_line = track.get_center_line();
 
         let first_point = center_line[0];
-        assert!((first_point.0 - 50.0).abs() < 1e-10);
-        assert!((first_point.1 - 0.0).abs() < 1e-10);
+        assert!((first_point.x - 50.0).abs() < 1e-10);
+        assert!((first_point.y - 0.0).abs() < 1e-10);
     }
 
     #[test]
@@ -245,12 +232,12 @@ mod tests {
 
         // Check first point of inside boundary (angle 0)
         let inside_first = inside_boundary[0];
-        let inside_radius = (inside_first.0.powi(2) + inside_first.1.powi(2)).sqrt();
+        let inside_radius = (inside_first.x.powi(2) + inside_first.y.powi(2)).sqrt();
         assert!((inside_radius - (center_radius - track_width / 2.0)).abs() < 1e-10);
 
         // Check first point of outside boundary (angle 0)
         let outside_first = outside_boundary[0];
-        let outside_radius = (outside_first.0.powi(2) + outside_first.1.powi(2)).sqrt();
+        let outside_radius = (outside_first.x.powi(2) + outside_first.y.powi(2)).sqrt();
         assert!((outside_radius - (center_radius + track_width / 2.0)).abs() < 1e-10);
     }
 
@@ -261,7 +248,7 @@ mod tests {
 
         // Each point on center line should be at 50m radius
         for point in center_line {
-            let radius = (point.0.powi(2) + point.1.powi(2)).sqrt();
+            let radius = (point.x.powi(2) + point.y.powi(2)).sqrt();
             assert!((radius - 50.0).abs() < 1e-10);
         }
     }
@@ -290,7 +277,7 @@ mod tests {
         // At 360 points, each point is 1 degree apart
         // Point at 90 degrees should be approximately at (0, 50)
         let point_90 = center_line[90];
-        assert!(point_90.0.abs() < 0.1);
-        assert!((point_90.1 - 50.0).abs() < 0.1);
+        assert!(point_90.x.abs() < 0.1);
+        assert!((point_90.y - 50.0).abs() < 0.1);
     }
 }
@@ -0,0 +1,217 @@
+//! Import of Formula Student style cone tracks.
+//!
+//! A track is described by two CSV files listing the left (blue) and right (yellow)
+//! cones in driving order, one `x,y` pair per line. Extra columns (e.g., cone color or
+//! id), a header line, blank lines, and `#` comments are ignored.
+
+use super::arc_length::ArcLengthTable;
+use super::geometry::TrackGeometry;
+use crate::error::{Error, Result};
+use crate::io::compression;
+use crate::math::vec2::Vec2;
+use std::path::Path;
+
+/// Default number of points used to resample the boundaries and center line
+pub const DEFAULT_CONE_TRACK_POINTS: usize = 200;
+
+/// Parse cone positions from CSV text
+///
+/// # Returns
+/// The (x, y) cone positions in file order, or `Error::InvalidConfig` naming the line
+/// that is malformed
+pub fn parse_cone_csv(text: &str) -> Result<Vec<Vec2>> {
+    let mut cones = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let mut fields = line.split(',').map(str::trim);
+        let (Some(x), Some(y)) = (fields.next(), fields.next()) else {
+            let message = format!("line {}: expected at least x,y", line_index + 1);
+            return Err(Error::invalid_config(message));
+        };
+        match (x.parse::<f64>(), y.parse::<f64>()) {
+            (Ok(x), Ok(y)) if x.is_finite() && y.is_finite() => cones.push(Vec2::new(x, y)),
+            _ if cones.is_empty() && line_index == 0 => continue, // header
+            _ => {
+                return Err(Error::invalid_config(format!(
+                    "line {}: invalid cone position '{}'",
+                    line_index + 1,
+                    line
+                )));
+            }
+        }
+    }
+    Ok(cones)
+}
+
+/// Load cone positions from a CSV file (see `parse_cone_csv`)
+pub fn load_cones<P: AsRef<Path>>(path: P) -> Result<Vec<Vec2>> {
+    let path = path.as_ref();
+    let text = compression::read_to_string(path)?;
+    parse_cone_csv(&text).map_err(|err| err.context(path.display()))
+}
+
+/// Reconstruct a closed track from its left and right cones
+///
+/// Both cone lines are resampled to `num_points` equally spaced points along their
+/// closed polylines. The center line follows the left cones, each point being the
+/// midpoint between a left boundary point and the closest right boundary point. The
+/// boundary enclosing the smaller area becomes the inside boundary. The start
+/// position is the first center line point, facing the second one.
+///
+/// # Arguments
+/// * `left` - Left cones in driving order
+/// * `right` - Right cones in driving order
+/// * `num_points` - Number of points of the reconstructed lines
+pub fn track_from_cones(left: &[Vec2], right: &[Vec2], num_points: usize) -> Result<TrackGeometry> {
+    if left.len() < 3 || right.len() < 3 {
+        return Err(Error::invalid_config(format!(
+            "need at least 3 cones per side, got {} left and {} right",
+            left.len(),
+            right.len()
+        )));
+    }
+    if num_points < 3 {
+        return Err(Error::invalid_config("cone track needs at least 3 points"));
+    }
+
+    let left = resample_closed(left, num_points);
+    let right = resample_closed(right, num_points);
+    let center_line: Vec<Vec2> = left
+        .iter()
+        .map(|&l| {
+            let r = right
+                .iter()
+                .copied()
+                .min_by(|a, b| (*a - l).norm_squared().total_cmp(&(*b - l).norm_squared()))
+                .unwrap_or(l);
+            l.lerp(r, 0.5)
+        })
+        .collect();
+
+    let (inside, outside) = if polygon_area(&left).abs() < polygon_area(&right).abs() {
+        (left, right)
+    } else {
+        (right, left)
+    };
+
+    let start = center_line[0];
+    let start_position = (start.x, start.y, (center_line[1] - start).angle());
+
+    Ok(TrackGeometry::new(
+        "FS Cone Track",
+        center_line,
+        inside,
+        outside,
+        start_position,
+    ))
+}
+
+/// Load a cone track from left and right cone CSV files
+pub fn load_cone_track<P: AsRef<Path>, Q: AsRef<Path>>(
+    left_path: P,
+    right_path: Q,
+    num_points: usize,
+) -> Result<TrackGeometry> {
+    track_from_cones(
+        &load_cones(left_path)?,
+        &load_cones(right_path)?,
+        num_points,
+    )
+}
+
+/// Resample a closed polyline to `count` points equally spaced by arc length
+fn resample_closed(points: &[Vec2], count: usize) -> Vec<Vec2> {
+    let table = ArcLengthTable::new(points);
+    let total = table.total_length();
+    if total <= 0.0 {
+        return vec![points[0]; count];
+    }
+    (0..count)
+        .filter_map(|k| table.point_at(total * k as f64 / count as f64))
+        .map(|(x, y, _)| Vec2::new(x, y))
+        .collect()
+}
+
+/// Signed area of a closed polygon (positive when counter-clockwise)
+pub(crate) fn polygon_area(points: &[Vec2]) -> f64 {
+    let n = points.len();
+    (0..n)
+        .map(|i| {
+            points[i].cross(points[(i + 1) % n])
+        })
+        .sum::<f64>()
+        / 2.0
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{parse_cone_csv, polygon_area, resample_closed, track_from_cones};
+    use crate::math::vec2::Vec2;
+    use crate::tracks::base_track::Track;
+    use std::f64::consts::PI;
+
+    fn circle(radius: f64, count: usize) -> Vec<Vec2> {
+        (0..count)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / count as f64;
+                Vec2::from_angle(angle) * radius
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_parse_cone_csv() {
+        let cones =
+            parse_cone_csv("x,y,color\n# start\n1.0,2.0,blue\n\n3.5, -1\n").expect("parse cones");
+        assert_eq!(cones, vec![Vec2::new(1.0, 2.0), Vec2::new(3.5, -1.0)]);
+
+        let err = parse_cone_csv("1.0,2.0\nabc,3\n").expect_err("expected invalid line");
+        assert!(err.to_string().contains("line 2"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_resample_closed_square() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(1.0, 0.0),
+            Vec2::new(1.0, 1.0),
+            Vec2::new(0.0, 1.0),
+        ];
+        let points = resample_closed(&square, 8);
+        assert_eq!(points.len(), 8);
+        assert_eq!(points[1], Vec2::new(0.5, 0.0));
+        assert_eq!(points[4], Vec2::new(1.0, 1.0));
+        assert!((polygon_area(&square) - 1.0).abs() < 1e-12);
+    }
+
+    #[test]
+    fn test_track_from_cones_ring() {
+        // Counter-clockwise driving: left cones on the inner ring
+        let left = circle(45.0, 30);
+        let right = circle(55.0, 40);
+
+        let track = track_from_cones(&left, &right, 120).expect("build track");
+
+        assert_eq!(track.get_center_line().len(), 120);
+        for point in track.get_center_line() {
+            assert!((point.norm() - 50.0).abs() < 0.5, "{point}");
+        }
+        assert!(track.is_in_track(50.0, 0.0));
+        assert!(!track.is_in_track(0.0, 0.0));
+        assert!(!track.is_in_track(60.0, 0.0));
+
+        let (x, y, yaw) = track.get_start_position();
+        assert!((x - 50.0).abs() < 0.5 && y.abs() < 0.5);
+        assert!((yaw - PI / 2.0).abs() < 0.1);
+    }
+
+    #[test]
+    fn test_track_from_cones_rejects_too_few() {
+        let err = track_from_cones(&[Vec2::ZERO], &circle(5.0, 4), 50).expect_err("expected error");
+        assert!(err.to_string().contains("at least 3 cones"));
+    }
+}
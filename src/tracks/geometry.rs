@@ -0,0 +1,211 @@
+use super::base_track::{compute_center_line_yaw, Track};
+use super::spatial::PolygonIndex;
+use crate::math::vec2::Vec2;
+use std::fmt;
+
+/// Track described only by its sampled geometry
+///
+/// Useful to snapshot any `Track` (e.g., for archiving it as JSON) and to use the
+/// stored geometry again as a track. Containment is evaluated against the boundary
+/// polygons: a point is on track when it is inside the outside boundary and not
+/// inside the inside boundary. Both boundaries are indexed in a grid, so containment
+/// and boundary distance stay cheap for tracks with thousands of points.
+#[derive(Debug, Clone, PartialEq)]
+#[cfg_attr(feature = "serde", derive(serde::Serialize, serde::Deserialize))]
+#[cfg_attr(feature = "serde", serde(from = "TrackGeometryData"))]
+pub struct TrackGeometry {
+    pub name: String,
+    pub center_line: Vec<Vec2>,
+    pub inside_boundary: Vec<Vec2>,
+    pub outside_boundary: Vec<Vec2>,
+    pub start_position: (f64, f64, f64),
+    #[cfg_attr(feature = "serde", serde(skip))]
+    center_line_yaw: Vec<f64>,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    inside_index: PolygonIndex,
+    #[cfg_attr(feature = "serde", serde(skip))]
+    outside_index: PolygonIndex,
+}
+
+impl TrackGeometry {
+    /// Create a track from its center line, boundaries, and start position
+    pub fn new(
+        name: &str,
+        center_line: Vec<Vec2>,
+        inside_boundary: Vec<Vec2>,
+        outside_boundary: Vec<Vec2>,
+        start_position: (f64, f64, f64),
+    ) -> Self {
+        let center_line_yaw = compute_center_line_yaw(&center_line);
+        let inside_index = PolygonIndex::new(&inside_boundary);
+        let outside_index = PolygonIndex::new(&outside_boundary);
+        Self {
+            name: name.to_string(),
+            center_line,
+            inside_boundary,
+            outside_boundary,
+            start_position,
+            center_line_yaw,
+            inside_index,
+            outside_index,
+        }
+    }
+
+    /// Snapshot the geometry of any track
+    pub fn from_track(track: &dyn Track) -> Self {
+        Self::new(
+            track.get_track_name(),
+            track.get_center_line().to_vec(),
+            track.get_inside_boundary().to_vec(),
+            track.get_outside_boundary().to_vec(),
+            track.get_start_position(),
+        )
+    }
+
+    /// Recompute derived data after the public fields were modified
+    pub fn refresh(&mut self) {
+        self.center_line_yaw = compute_center_line_yaw(&self.center_line);
+        self.inside_index = PolygonIndex::new(&self.inside_boundary);
+        self.outside_index = PolygonIndex::new(&self.outside_boundary);
+    }
+
+    /// Distance from (x, y) to the closest boundary in meters
+    ///
+    /// # Returns
+    /// The distance to the nearer of both boundaries, or infinity without boundaries
+    pub fn distance_to_boundary(&self, x: f64, y: f64) -> f64 {
+        let inside = self.inside_index.distance(x, y).unwrap_or(f64::INFINITY);
+        let outside = self.outside_index.distance(x, y).unwrap_or(f64::INFINITY);
+        inside.min(outside)
+    }
+}
+
+/// Serialized form of `TrackGeometry`, without the derived center line yaw and indices
+#[cfg(feature = "serde")]
+#[derive(serde::Deserialize)]
+struct TrackGeometryData {
+    name: String,
+    center_line: Vec<Vec2>,
+    inside_boundary: Vec<Vec2>,
+    outside_boundary: Vec<Vec2>,
+    start_position: (f64, f64, f64),
+}
+
+#[cfg(feature = "serde")]
+impl From<TrackGeometryData> for TrackGeometry {
+    fn from(data: TrackGeometryData) -> Self {
+        Self::new(
+            &data.name,
+            data.center_line,
+            data.inside_boundary,
+            data.outside_boundary,
+            data.start_position,
+        )
+    }
+}
+
+impl Track for TrackGeometry {
+    fn init(
+        &mut self,
+        center_line: Vec<Vec2>,
+        inside_border: Vec<Vec2>,
+        outside_border: Vec<Vec2>,
+        get_start_position: (f64, f64, f64),
+    ) {
+        self.center_line = center_line;
+        self.inside_boundary = inside_border;
+        self.outside_boundary = outside_border;
+        self.start_position = get_start_position;
+        self.refresh();
+    }
+
+    fn is_in_track(&self, x: f64, y: f64) -> bool {
+        self.outside_index.contains(x, y) && !self.inside_index.contains(x, y)
+    }
+
+    fn get_start_position(&self) -> (f64, f64, f64) {
+        self.start_position
+    }
+
+    fn get_center_line(&self) -> &[Vec2] {
+        &self.center_line
+    }
+
+    fn get_center_line_yaw(&self) -> &[f64] {
+        &self.center_line_yaw
+    }
+
+    fn get_inside_boundary(&self) -> &[Vec2] {
+        &self.inside_boundary
+    }
+
+    fn get_outside_boundary(&self) -> &[Vec2] {
+        &self.outside_boundary
+    }
+
+    fn get_track_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_plot_range(&self) -> (f64, f64) {
+        let (min_coord, max_coord) = self
+            .outside_boundary
+            .iter()
+            .chain(self.center_line.iter())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &Vec2 { x, y }| {
+                (lo.min(x).min(y), hi.max(x).max(y))
+            });
+        if !min_coord.is_finite() || !max_coord.is_finite() {
+            return (-1.0, 1.0);
+        }
+        let margin = ((max_coord - min_coord) * 0.1).max(1.0);
+        (min_coord - margin, max_coord + margin)
+    }
+}
+
+impl fmt::Display for TrackGeometry {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "TrackGeometry {{ name: {}, num_points: {} }}",
+            self.name,
+            self.center_line.len()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TrackGeometry;
+    use crate::tracks::base_track::Track;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+
+    #[test]
+    fn test_track_geometry_from_square_track() {
+        let square = SquareTrack::new(100.0, 10.0, 25);
+        let geometry = TrackGeometry::from_track(&square);
+
+        assert_eq!(geometry.get_track_name(), "Square Track");
+        assert_eq!(geometry.get_center_line(), square.get_center_line());
+        assert_eq!(geometry.get_center_line_yaw(), square.get_center_line_yaw());
+        assert_eq!(geometry.get_start_position(), square.get_start_position());
+        for &(x, y) in &[(50.0, 0.0), (0.0, 0.0), (54.0, 10.0), (56.0, 0.0), (46.0, 46.0)] {
+            assert_eq!(geometry.is_in_track(x, y), square.is_in_track(x, y), "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn test_track_geometry_from_circle_track() {
+        let circle = CircleTrack::new(50.0, 10.0, 200);
+        let geometry = TrackGeometry::from_track(&circle);
+
+        assert!(geometry.is_in_track(50.0, 0.0));
+        assert!(!geometry.is_in_track(40.0, 0.0));
+        assert!(!geometry.is_in_track(60.0, 0.0));
+        assert!((geometry.distance_to_boundary(52.0, 0.0) - 3.0).abs() < 0.1);
+        assert!((geometry.distance_to_boundary(0.0, 0.0) - 45.0).abs() < 0.1);
+        let (min_coord, max_coord) = geometry.get_plot_range();
+        assert!(min_coord < -55.0 && max_coord > 55.0);
+    }
+}
@@ -0,0 +1,184 @@
+//! Import of center line tracks from CSV files.
+//!
+//! A track file lists the center line in driving order, one `x,y[,width_left,width_right]`
+//! row per waypoint; widths are the distances in meters from the center line to the left
+//! and right boundary. Without width columns the track is `DEFAULT_TRACK_WIDTH` wide.
+//! Blank lines and `#` comments are ignored.
+//!
+//! The header is optional and may itself be a comment, as in the F1 center line CSVs of
+//! the TUM racetrack database (`# x_m,y_m,w_tr_right_m,w_tr_left_m`). With a header the
+//! columns may come in any order and use either naming; without one they are read in
+//! the order above.
+
+use super::waypoint::WaypointTrack;
+use crate::error::{Error, Result};
+use crate::io::compression;
+use crate::math::vec2::Vec2;
+use std::path::Path;
+
+/// Track width in meters used when a track file has no width columns
+pub const DEFAULT_TRACK_WIDTH: f64 = 10.0;
+
+/// Column indices of a track file
+struct Columns {
+    x: usize,
+    y: usize,
+    /// Left and right width columns, if the file has them
+    widths: Option<(usize, usize)>,
+}
+
+impl Columns {
+    /// Columns in the documented order, for files without a header
+    fn positional(field_count: usize) -> Self {
+        Self {
+            x: 0,
+            y: 1,
+            widths: (field_count >= 4).then_some((2, 3)),
+        }
+    }
+
+    /// Columns named by a header line; `None` if the line names no x and y columns
+    fn from_header(header: &str) -> Result<Option<Self>> {
+        let names: Vec<String> = header
+            .split(',')
+            .map(|name| name.trim().to_ascii_lowercase())
+            .collect();
+        let find = |aliases: &[&str]| names.iter().position(|name| aliases.contains(&name.as_str()));
+        let (Some(x), Some(y)) = (find(&["x", "x_m"]), find(&["y", "y_m"])) else {
+            return Ok(None);
+        };
+        let widths = match (
+            find(&["width_left", "w_tr_left_m"]),
+            find(&["width_right", "w_tr_right_m"]),
+        ) {
+            (Some(left), Some(right)) => Some((left, right)),
+            (None, None) => None,
+            _ => {
+                let message = "track header needs both width_left and width_right columns";
+                return Err(Error::invalid_config(message));
+            }
+        };
+        Ok(Some(Self { x, y, widths }))
+    }
+}
+
+/// Parse a track from CSV text (see the module documentation)
+///
+/// # Arguments
+/// * `name` - Track name for plotting
+/// * `text` - CSV text
+///
+/// # Returns
+/// The track, or `Error::InvalidConfig` naming the line that is malformed
+pub fn parse_track_csv(name: &str, text: &str) -> Result<WaypointTrack> {
+    let mut columns: Option<Columns> = None;
+    let mut waypoints = Vec::new();
+    let mut widths = Vec::new();
+    for (line_index, line) in text.lines().enumerate() {
+        let line = line.trim();
+        if line.is_empty() {
+            continue;
+        }
+        let fields: Vec<&str> = line.trim_start_matches('#').split(',').map(str::trim).collect();
+        let is_data = fields[0].parse::<f64>().is_ok();
+        if columns.is_none() && !is_data {
+            // Header line, possibly commented out
+            columns = Columns::from_header(line.trim_start_matches('#'))
+                .map_err(|err| err.context(format_args!("line {}", line_index + 1)))?;
+            if columns.is_some() || line.starts_with('#') {
+                continue;
+            }
+            let message = format!("line {}: header must contain x and y columns", line_index + 1);
+            return Err(Error::invalid_config(message));
+        }
+        if line.starts_with('#') {
+            continue;
+        }
+
+        let columns = columns.get_or_insert_with(|| Columns::positional(fields.len()));
+        let value = |index: usize, what: &str| -> Result<f64> {
+            fields
+                .get(index)
+                .and_then(|field| field.parse::<f64>().ok())
+                .filter(|value| value.is_finite())
+                .ok_or_else(|| {
+                    Error::invalid_config(format!(
+                        "line {}: invalid {} in '{}'",
+                        line_index + 1,
+                        what,
+                        line
+                    ))
+                })
+        };
+        waypoints.push(Vec2::new(value(columns.x, "x")?, value(columns.y, "y")?));
+        widths.push(match columns.widths {
+            Some((left, right)) => (value(left, "width_left")?, value(right, "width_right")?),
+            None => (DEFAULT_TRACK_WIDTH / 2.0, DEFAULT_TRACK_WIDTH / 2.0),
+        });
+    }
+    WaypointTrack::with_widths(name, waypoints, widths)
+}
+
+/// Load a track from a CSV file (see the module documentation)
+///
+/// The track is named after the file stem.
+///
+/// # Returns
+/// The track, `Error::Io` if the file cannot be read, or `Error::InvalidConfig`
+/// mentioning the file if it is malformed
+pub fn load_track_from_csv<P: AsRef<Path>>(path: P) -> Result<WaypointTrack> {
+    let path = path.as_ref();
+    let text = compression::read_to_string(path)?;
+    let name = path
+        .file_stem()
+        .map_or_else(|| "CSV Track".to_string(), |stem| stem.to_string_lossy().into_owned());
+    parse_track_csv(&name, &text).map_err(|err| err.context(path.display()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{load_track_from_csv, parse_track_csv, DEFAULT_TRACK_WIDTH};
+    use crate::tracks::base_track::Track;
+
+    #[test]
+    fn test_parse_track_csv_formats() {
+        // Plain x,y without header: default width
+        let plain = parse_track_csv("Plain", "0,0\n100,0\n100,100\n0,100\n").expect("plain");
+        assert_eq!(plain.get_center_line().len(), 4);
+        assert_eq!(plain.track_width(), DEFAULT_TRACK_WIDTH);
+
+        // Commented F1 style header with the right width first
+        let text = "# x_m,y_m,w_tr_right_m,w_tr_left_m\n0,0,6,2\n100,0,6,2\n100,100,6,2\n0,100,6,2\n";
+        let f1 = parse_track_csv("F1", text).expect("f1 style");
+        assert_eq!(f1.widths()[0], (2.0, 6.0));
+        // Counter-clockwise square: left is the inside
+        assert!(f1.is_in_track(50.0, 1.5) && f1.is_in_track(50.0, -5.5));
+        assert!(!f1.is_in_track(50.0, 2.5));
+
+        let named = parse_track_csv("Named", "y,x\n0,0\n0,100\n100,100\n").expect("named");
+        assert_eq!(named.get_start_position().2, 0.0);
+    }
+
+    #[test]
+    fn test_parse_track_csv_errors() {
+        let err = parse_track_csv("Bad", "x,y\n0,0\n1,a\n").expect_err("bad value");
+        assert!(err.to_string().contains("line 3"), "{err}");
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(parse_track_csv("Bad", "a,b\n0,0\n").is_err());
+        assert!(parse_track_csv("Bad", "x,y,width_left\n0,0,1\n").is_err());
+        assert!(parse_track_csv("Short", "x,y\n0,0\n1,1\n").is_err());
+    }
+
+    #[test]
+    fn test_load_track_from_csv() {
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("oval.csv");
+        std::fs::write(&path, "x,y,width_left,width_right\n0,0,4,4\n50,0,4,4\n50,30,4,4\n0,30,4,4\n")
+            .expect("write track");
+        let track = load_track_from_csv(&path).expect("load track");
+        assert_eq!(track.get_track_name(), "oval");
+        assert_eq!(track.track_width(), 8.0);
+        let err = load_track_from_csv(temp_dir.path().join("missing.csv")).expect_err("missing");
+        assert!(matches!(err, crate::Error::Io(_)));
+    }
+}
@@ -1,3 +1,12 @@
+pub mod arc_length;
 pub mod base_track;
 pub mod circle;
+pub mod cones;
+pub mod geometry;
+pub mod io;
+pub mod racing_line;
+pub mod spatial;
 pub mod square;
+pub mod timing_line;
+pub mod tum;
+pub mod waypoint;
@@ -0,0 +1,239 @@
+//! Externally optimized racing lines attached to a track.
+//!
+//! A racing line is a CSV file with an `x,y` or `x,y,v` header followed by one point
+//! per line in driving order. `v` is the target speed in m/s; when the column is
+//! missing (or a value is left empty), the controller chooses the speed. Blank lines
+//! and `#` comments are ignored.
+
+use super::base_track::Track;
+use crate::error::{Error, Result};
+use crate::io::compression;
+use crate::math::vec2::Vec2;
+use std::path::Path;
+
+/// Point of a racing line
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct RacingLinePoint {
+    pub x: f64,
+    pub y: f64,
+    /// Target speed in m/s, if the line prescribes one
+    pub v: Option<f64>,
+}
+
+/// Closed racing line in driving order
+#[derive(Debug, Clone, PartialEq)]
+pub struct RacingLine {
+    points: Vec<RacingLinePoint>,
+}
+
+impl RacingLine {
+    /// Create a racing line from at least two points
+    pub fn new(points: Vec<RacingLinePoint>) -> Result<Self> {
+        if points.len() < 2 {
+            return Err(Error::invalid_config(format!(
+                "racing line needs at least 2 points, got {}",
+                points.len()
+            )));
+        }
+        Ok(Self { points })
+    }
+
+    /// Parse a racing line from CSV text (see the module documentation)
+    ///
+    /// # Returns
+    /// The racing line, or `Error::InvalidConfig` naming the line that is malformed
+    pub fn parse_csv(text: &str) -> Result<Self> {
+        let mut lines = text
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()))
+            .filter(|(_, line)| !line.is_empty() && !line.starts_with('#'));
+
+        let (_, header) = lines
+            .next()
+            .ok_or_else(|| Error::invalid_config("racing line is empty"))?;
+        let columns: Vec<String> = header
+            .split(',')
+            .map(|column| column.trim().to_ascii_lowercase())
+            .collect();
+        let column = |name: &str| columns.iter().position(|column| column == name);
+        let (Some(x_index), Some(y_index)) = (column("x"), column("y")) else {
+            return Err(Error::invalid_config("racing line header must contain x and y columns"));
+        };
+        let v_index = column("v");
+
+        let mut points = Vec::new();
+        for (line_number, line) in lines {
+            let fields: Vec<&str> = line.split(',').map(str::trim).collect();
+            let value = |index: usize, name: &str| -> Result<f64> {
+                fields
+                    .get(index)
+                    .and_then(|field| field.parse::<f64>().ok())
+                    .filter(|value| value.is_finite())
+                    .ok_or_else(|| {
+                        Error::invalid_config(format!(
+                            "line {}: invalid {} in '{}'",
+                            line_number, name, line
+                        ))
+                    })
+            };
+            let v = match v_index {
+                Some(index) if fields.get(index).is_some_and(|field| !field.is_empty()) => {
+                    Some(value(index, "v")?)
+                }
+                _ => None,
+            };
+            if v.is_some_and(|v| v < 0.0) {
+                let message = format!("line {}: speed must not be negative", line_number);
+                return Err(Error::invalid_config(message));
+            }
+            points.push(RacingLinePoint {
+                x: value(x_index, "x")?,
+                y: value(y_index, "y")?,
+                v,
+            });
+        }
+        Self::new(points)
+    }
+
+    /// Load a racing line from a CSV file (see `parse_csv`)
+    pub fn load_csv<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = compression::read_to_string(path)?;
+        Self::parse_csv(&text).map_err(|err| err.context(path.display()))
+    }
+
+    /// Load a racing line and check that it lies within the track boundaries
+    pub fn load_for_track<P: AsRef<Path>>(path: P, track: &dyn Track) -> Result<Self> {
+        let line = Self::load_csv(path)?;
+        line.validate(track)?;
+        Ok(line)
+    }
+
+    /// Check that every point lies within the track boundaries
+    ///
+    /// # Returns
+    /// `Error::InvalidConfig` naming the first point outside the track
+    pub fn validate(&self, track: &dyn Track) -> Result<()> {
+        match self
+            .points
+            .iter()
+            .position(|point| !track.is_in_track(point.x, point.y))
+        {
+            Some(index) => {
+                let point = self.points[index];
+                Err(Error::invalid_config(format!(
+                    "racing line point {} ({}, {}) is outside {}",
+                    index,
+                    point.x,
+                    point.y,
+                    track.get_track_name()
+                )))
+            }
+            None => Ok(()),
+        }
+    }
+
+    pub fn points(&self) -> &[RacingLinePoint] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// (x, y) coordinates of the line, e.g. for plotting
+    pub fn positions(&self) -> Vec<Vec2> {
+        self.points.iter().map(|point| Vec2::new(point.x, point.y)).collect()
+    }
+
+    /// Whether every point prescribes a target speed
+    pub fn has_speeds(&self) -> bool {
+        self.points.iter().all(|point| point.v.is_some())
+    }
+
+    /// Index of the line point closest to (x, y)
+    pub fn nearest_index(&self, x: f64, y: f64) -> usize {
+        self.points
+            .iter()
+            .enumerate()
+            .min_by(|(_, a), (_, b)| {
+                let da = (a.x - x).powi(2) + (a.y - y).powi(2);
+                let db = (b.x - x).powi(2) + (b.y - y).powi(2);
+                da.total_cmp(&db)
+            })
+            .map_or(0, |(index, _)| index)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::RacingLine;
+    use crate::math::vec2::Vec2;
+    use crate::tracks::base_track::Track;
+    use crate::tracks::circle::CircleTrack;
+
+    fn circle_line(track: &CircleTrack) -> String {
+        let mut csv = String::from("x,y,v\n");
+        for &Vec2 { x, y } in track.get_center_line().iter().step_by(10) {
+            csv.push_str(&format!("{},{},12.5\n", x, y));
+        }
+        csv
+    }
+
+    #[test]
+    fn test_parse_racing_line() {
+        let line = RacingLine::parse_csv("# optimized\nY, X\n1.0, 2.0\n\n3.0, 4.0\n")
+            .expect("parse racing line");
+        assert_eq!(line.positions(), vec![Vec2::new(2.0, 1.0), Vec2::new(4.0, 3.0)]);
+        assert!(!line.has_speeds());
+
+        let line = RacingLine::parse_csv("x,y,v\n0,0,5\n1,0,\n").expect("parse speeds");
+        assert_eq!(line.points()[0].v, Some(5.0));
+        assert_eq!(line.points()[1].v, None);
+    }
+
+    #[test]
+    fn test_parse_racing_line_errors() {
+        assert!(RacingLine::parse_csv("").is_err());
+        assert!(RacingLine::parse_csv("a,b\n0,0\n1,1\n").is_err());
+        assert!(RacingLine::parse_csv("x,y\n0,0\n").is_err());
+        let err = RacingLine::parse_csv("x,y\n0,0\n1,nan\n").expect_err("invalid y");
+        assert!(err.to_string().contains("line 3"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(RacingLine::parse_csv("x,y,v\n0,0,1\n1,1,-2\n").is_err());
+    }
+
+    #[test]
+    fn test_validate_against_track() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let line = RacingLine::parse_csv(&circle_line(&track)).expect("parse racing line");
+        assert!(line.has_speeds());
+        line.validate(&track).expect("line within track");
+
+        let Vec2 { x, y } = track.get_center_line()[0];
+        assert_eq!(line.nearest_index(x + 0.1, y), 0);
+
+        let outside =
+            RacingLine::parse_csv(&format!("x,y\n{},{}\n0,0\n", x, y)).expect("parse racing line");
+        let err = outside.validate(&track).expect_err("point outside");
+        assert!(err.to_string().contains("point 1"));
+    }
+
+    #[test]
+    fn test_load_for_track() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let temp_dir = tempfile::tempdir().expect("failed to create temp dir");
+        let path = temp_dir.path().join("line.csv");
+        std::fs::write(&path, circle_line(&track)).expect("write racing line");
+
+        let line = RacingLine::load_for_track(&path, &track).expect("load racing line");
+        assert_eq!(line.len(), 10);
+        let err = RacingLine::load_csv(temp_dir.path().join("missing.csv")).expect_err("missing");
+        assert!(matches!(err, crate::Error::Io(_)));
+    }
+}
@@ -0,0 +1,242 @@
+//! Uniform grid over the segments of a closed polygon.
+//!
+//! Imported and waypoint tracks have hundreds to thousands of boundary points, and
+//! `is_in_track` runs every simulation step. The grid buckets the boundary segments
+//! by cell once, so containment only visits the segments in the query's row and
+//! distance queries only visit the cells around the query point.
+
+use super::base_track::point_in_polygon;
+use crate::math::vec2::Vec2;
+
+/// Spatial index over the segments of a closed polygon
+#[derive(Debug, Clone, PartialEq)]
+pub struct PolygonIndex {
+    polygon: Vec<Vec2>,
+    origin: Vec2,
+    cell_size: f64,
+    columns: usize,
+    rows: usize,
+    /// Segments whose y-range overlaps each row, for ray casting
+    row_segments: Vec<Vec<usize>>,
+    /// Segments whose bounding box overlaps each cell (row-major), for distance queries
+    cell_segments: Vec<Vec<usize>>,
+}
+
+impl PolygonIndex {
+    /// Index a closed polygon (the last point connects back to the first)
+    ///
+    /// The grid has roughly `sqrt(n)` cells per side for `n` segments.
+    pub fn new(polygon: &[Vec2]) -> Self {
+        let n = polygon.len();
+        let (mut min_x, mut min_y) = (f64::INFINITY, f64::INFINITY);
+        let (mut max_x, mut max_y) = (f64::NEG_INFINITY, f64::NEG_INFINITY);
+        for &Vec2 { x, y } in polygon {
+            min_x = min_x.min(x);
+            min_y = min_y.min(y);
+            max_x = max_x.max(x);
+            max_y = max_y.max(y);
+        }
+        if n < 3
+            || !(min_x.is_finite() && min_y.is_finite() && max_x.is_finite() && max_y.is_finite())
+        {
+            return Self {
+                polygon: polygon.to_vec(),
+                origin: Vec2::ZERO,
+                cell_size: 1.0,
+                columns: 0,
+                rows: 0,
+                row_segments: Vec::new(),
+                cell_segments: Vec::new(),
+            };
+        }
+
+        let span = (max_x - min_x).max(max_y - min_y).max(f64::EPSILON);
+        let cells_per_side = ((n as f64).sqrt().ceil() as usize).max(1);
+        let cell_size = span / cells_per_side as f64;
+        let columns = (((max_x - min_x) / cell_size).floor() as usize + 1).min(cells_per_side + 1);
+        let rows = (((max_y - min_y) / cell_size).floor() as usize + 1).min(cells_per_side + 1);
+
+        let mut index = Self {
+            polygon: polygon.to_vec(),
+            origin: Vec2::new(min_x, min_y),
+            cell_size,
+            columns,
+            rows,
+            row_segments: vec![Vec::new(); rows],
+            cell_segments: vec![Vec::new(); columns * rows],
+        };
+        for segment in 0..n {
+            let (p0, p1) = (polygon[segment], polygon[(segment + 1) % n]);
+            let (column_start, row_start) = index.cell_of(p0.x.min(p1.x), p0.y.min(p1.y));
+            let (column_end, row_end) = index.cell_of(p0.x.max(p1.x), p0.y.max(p1.y));
+            for row in row_start..=row_end {
+                index.row_segments[row].push(segment);
+                for column in column_start..=column_end {
+                    index.cell_segments[row * columns + column].push(segment);
+                }
+            }
+        }
+        index
+    }
+
+    /// Indexed polygon
+    pub fn polygon(&self) -> &[Vec2] {
+        &self.polygon
+    }
+
+    /// Whether (x, y) lies inside the polygon, with the same result as `point_in_polygon`
+    pub fn contains(&self, x: f64, y: f64) -> bool {
+        if self.rows == 0 {
+            return point_in_polygon(x, y, &self.polygon);
+        }
+        let Some(row) = self.row_of(y) else {
+            return false;
+        };
+        let n = self.polygon.len();
+        let mut inside = false;
+        for &segment in &self.row_segments[row] {
+            // Same crossing rule as `point_in_polygon`, with (i, j) = (segment + 1, segment)
+            let Vec2 { x: xj, y: yj } = self.polygon[segment];
+            let Vec2 { x: xi, y: yi } = self.polygon[(segment + 1) % n];
+            if (yi > y) != (yj > y) && x < (xj - xi) * (y - yi) / (yj - yi) + xi {
+                inside = !inside;
+            }
+        }
+        inside
+    }
+
+    /// Distance from (x, y) to the closest point of the polygon outline
+    ///
+    /// # Returns
+    /// The distance in meters, or `None` for an empty polygon
+    pub fn distance(&self, x: f64, y: f64) -> Option<f64> {
+        if self.rows == 0 {
+            return (0..self.polygon.len())
+                .map(|segment| self.segment_distance(segment, x, y))
+                .min_by(f64::total_cmp);
+        }
+
+        // Search rings of cells around the query until no closer segment can exist
+        let column = ((x - self.origin.x) / self.cell_size).floor() as i64;
+        let row = ((y - self.origin.y) / self.cell_size).floor() as i64;
+        let (columns, rows) = (self.columns as i64, self.rows as i64);
+        // Queries outside the grid start at the first ring that reaches it
+        let first_ring = [-column, column - (columns - 1), -row, row - (rows - 1)]
+            .into_iter()
+            .fold(0, i64::max);
+        let mut best = f64::INFINITY;
+        for ring in first_ring..=first_ring + columns.max(rows) {
+            for r in (row - ring).max(0)..=(row + ring).min(rows - 1) {
+                for c in (column - ring).max(0)..=(column + ring).min(columns - 1) {
+                    if (r - row).abs() != ring && (c - column).abs() != ring {
+                        continue;
+                    }
+                    for &segment in &self.cell_segments[(r * columns + c) as usize] {
+                        best = best.min(self.segment_distance(segment, x, y));
+                    }
+                }
+            }
+            // Cells beyond this ring are at least `ring` cells away from the query cell
+            if best <= ring as f64 * self.cell_size {
+                break;
+            }
+        }
+        Some(best)
+    }
+
+    fn segment_distance(&self, segment: usize, x: f64, y: f64) -> f64 {
+        let n = self.polygon.len();
+        let start = self.polygon[segment];
+        let direction = self.polygon[(segment + 1) % n] - start;
+        let offset = Vec2::new(x, y) - start;
+        let length_squared = direction.norm_squared();
+        let t = if length_squared > 0.0 {
+            (offset.dot(direction) / length_squared).clamp(0.0, 1.0)
+        } else {
+            0.0
+        };
+        (offset - direction * t).norm()
+    }
+
+    fn cell_of(&self, x: f64, y: f64) -> (usize, usize) {
+        let column = ((x - self.origin.x) / self.cell_size).floor().max(0.0) as usize;
+        let row = ((y - self.origin.y) / self.cell_size).floor().max(0.0) as usize;
+        (column.min(self.columns - 1), row.min(self.rows - 1))
+    }
+
+    fn row_of(&self, y: f64) -> Option<usize> {
+        let row = ((y - self.origin.y) / self.cell_size).floor();
+        (row >= 0.0 && row < self.rows as f64).then_some(row as usize)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::PolygonIndex;
+    use crate::math::vec2::Vec2;
+    use crate::tracks::base_track::point_in_polygon;
+    use std::f64::consts::PI;
+
+    fn star(count: usize) -> Vec<Vec2> {
+        (0..count)
+            .map(|i| {
+                let angle = 2.0 * PI * i as f64 / count as f64;
+                let radius = if i % 2 == 0 { 50.0 } else { 30.0 };
+                Vec2::from_angle(angle) * radius
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_contains_matches_ray_casting() {
+        let polygon = star(400);
+        let index = PolygonIndex::new(&polygon);
+        for i in -60..=60 {
+            for j in -60..=60 {
+                let (x, y) = (i as f64 + 0.37, j as f64 + 0.51);
+                assert_eq!(
+                    index.contains(x, y),
+                    point_in_polygon(x, y, &polygon),
+                    "({x}, {y})"
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_distance_to_outline() {
+        let square = [
+            Vec2::new(0.0, 0.0),
+            Vec2::new(10.0, 0.0),
+            Vec2::new(10.0, 10.0),
+            Vec2::new(0.0, 10.0),
+        ];
+        let index = PolygonIndex::new(&square);
+        assert_eq!(index.distance(5.0, 2.0), Some(2.0));
+        assert_eq!(index.distance(13.0, 14.0), Some(5.0));
+        assert_eq!(index.distance(-40.0, 5.0), Some(40.0));
+
+        let polygon = star(400);
+        let index = PolygonIndex::new(&polygon);
+        let brute = |x: f64, y: f64| {
+            (0..polygon.len())
+                .map(|segment| index.segment_distance(segment, x, y))
+                .fold(f64::INFINITY, f64::min)
+        };
+        for &(x, y) in &[(0.0, 0.0), (45.0, 3.0), (-70.0, 20.0), (12.5, -33.3)] {
+            let distance = index.distance(x, y).expect("distance");
+            assert!((distance - brute(x, y)).abs() < 1e-9, "({x}, {y})");
+        }
+    }
+
+    #[test]
+    fn test_degenerate_polygons() {
+        let index = PolygonIndex::new(&[]);
+        assert!(!index.contains(0.0, 0.0));
+        assert_eq!(index.distance(0.0, 0.0), None);
+
+        let line = PolygonIndex::new(&[Vec2::ZERO, Vec2::new(4.0, 0.0)]);
+        assert!(!line.contains(1.0, 0.0));
+        assert_eq!(line.distance(2.0, 3.0), Some(3.0));
+    }
+}
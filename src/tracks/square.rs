@@ -1,12 +1,14 @@
 use super::base_track::{compute_center_line_yaw, Track};
+use crate::math::vec2::Vec2;
 use std::fmt;
 
 /// Square track defined by height and track width
+#[derive(Debug, Clone, PartialEq)]
 pub struct SquareTrack {
-    center_line: Vec<(f64, f64)>,
+    center_line: Vec<Vec2>,
     center_line_yaw: Vec<f64>,
-    inside_border: Vec<(f64, f64)>,
-    outside_border: Vec<(f64, f64)>,
+    inside_border: Vec<Vec2>,
+    outside_border: Vec<Vec2>,
     start_pos: (f64, f64, f64),
     height: f64,
     track_width: f64,
@@ -51,9 +53,9 @@ impl SquareTrack {
             let t = i as f64 / points_per_side as f64;
             let y = -half_center + t * self.height;
             
-            self.center_line.push((half_center, y));
-            self.inside_border.push((half_inside, y));
-            self.outside_border.push((half_outside, y));
+            self.center_line.push(Vec2::new(half_center, y));
+            self.inside_border.push(Vec2::new(half_inside, y));
+            self.outside_border.push(Vec2::new(half_outside, y));
         }
         
         // Top side (moving left)
@@ -61,9 +63,9 @@ impl SquareTrack {
             let t = i as f64 / points_per_side as f64;
             let x = half_center - t * self.height;
             
-            self.center_line.push((x, half_center));
-            self.inside_border.push((x, half_inside));
-            self.outside_border.push((x, half_outside));
+            self.center_line.push(Vec2::new(x, half_center));
+            self.inside_border.push(Vec2::new(x, half_inside));
+            self.outside_border.push(Vec2::new(x, half_outside));
         }
         
         // Left side (moving down)
@@ -71,9 +73,9 @@ impl SquareTrack {
             let t = i as f64 / points_per_side as f64;
             let y = half_center - t * self.height;
             
-            self.center_line.push((-half_center, y));
-            self.inside_border.push((-half_inside, y));
-            self.outside_border.push((-half_outside, y));
+            self.center_line.push(Vec2::new(-half_center, y));
+            self.inside_border.push(Vec2::new(-half_inside, y));
+            self.outside_border.push(Vec2::new(-half_outside, y));
         }
         
         // Bottom side (moving right)
@@ -81,13 +83,13 @@ impl SquareTrack {
             let t = i as f64 / points_per_side as f64;
             let x = -half_center + t * self.height;
             
-            self.center_line.push((x, -half_center));
-            self.inside_border.push((x, -half_inside));
-            self.outside_border.push((x, -half_outside));
+            self.center_line.push(Vec2::new(x, -half_center));
+            self.inside_border.push(Vec2::new(x, -half_inside));
+            self.outside_border.push(Vec2::new(x, -half_outside));
         }
 
         self.center_line_yaw = compute_center_line_yaw(&self.center_line);
-        if let (Some(&(x, y)), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
+        if let (Some(&Vec2 { x, y }), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
             self.start_pos = (x, y, yaw);
         }
     }
@@ -96,9 +98,9 @@ impl SquareTrack {
 impl Track for SquareTrack {
     fn init(
         &mut self,
-        center_line: Vec<(f64, f64)>,
-        inside_border: Vec<(f64, f64)>,
-        outside_border: Vec<(f64, f64)>,
+        center_line: Vec<Vec2>,
+        inside_border: Vec<Vec2>,
+        outside_border: Vec<Vec2>,
         get_start_position: (f64, f64, f64),
     ) {
         self.center_line = center_line;
@@ -106,7 +108,7 @@ impl Track for SquareTrack {
         self.inside_border = inside_border;
         self.outside_border = outside_border;
         self.start_pos = get_start_position;
-        if let (Some(&(x, y)), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
+        if let (Some(&Vec2 { x, y }), Some(&yaw)) = (self.center_line.first(), self.center_line_yaw.first()) {
             self.start_pos = (x, y, yaw);
         }
     }
@@ -128,7 +130,7 @@ impl Track for SquareTrack {
         self.start_pos
     }
     
-    fn get_center_line(&self) -> &[(f64, f64)] {
+    fn get_center_line(&self) -> &[Vec2] {
         &self.center_line
     }
 
@@ -136,11 +138,11 @@ impl Track for SquareTrack {
         &self.center_line_yaw
     }
     
-    fn get_inside_boundary(&self) -> &[(f64, f64)] {
+    fn get_inside_boundary(&self) -> &[Vec2] {
         &self.inside_border
     }
     
-    fn get_outside_boundary(&self) -> &[(f64, f64)] {
+    fn get_outside_boundary(&self) -> &[Vec2] {
         &self.outside_border
     }
     
@@ -202,8 +204,8 @@ mod tests {
 
         // First point should be at (50, -50) - middle of right side, bottom
         let first_point = center_line[0];
-        assert!((first_point.0 - 50.0).abs() < 1e-10);
-        assert!((first_point.1 - (-50.0)).abs() < 1e-10);
+        assert!((first_point.x - 50.0).abs() < 1e-10);
+        assert!((first_point.y - (-50.0)).abs() < 1e-10);
     }
 
     #[test]
@@ -283,8 +285,8 @@ mod tests {
 
         // Last point of right side (i=24, t=24/25=0.96) is at y = -50 + 0.96*100 = 46
         let last_right = center_line[24];
-        assert!((last_right.0 - 50.0).abs() < 1e-10);
-        assert!((last_right.1 - 46.0).abs() < 1.0);
+        assert!((last_right.x - 50.0).abs() < 1e-10);
+        assert!((last_right.y - 46.0).abs() < 1.0);
     }
 
     #[test]
@@ -294,10 +296,10 @@ mod tests {
 
         // Check x-coordinates on right and left sides
         // Right side (first point): should have positive x
-        assert!(center_line[0].0 > 0.0);
+        assert!(center_line[0].x > 0.0);
 
         // Left side (50th point area): should have negative x approximately
-        assert!(center_line[50].0 < 0.0);
+        assert!(center_line[50].x < 0.0);
     }
 
     #[test]
@@ -334,8 +336,8 @@ mod tests {
         let inside_first = inside_boundary[0];
         let outside_first = outside_boundary[0];
 
-        let inside_dist = inside_first.0.abs() + inside_first.1.abs();
-        let outside_dist = outside_first.0.abs() + outside_first.1.abs();
+        let inside_dist = inside_first.x.abs() + inside_first.y.abs();
+        let outside_dist = outside_first.x.abs() + outside_first.y.abs();
 
         // Distance difference should be small due to narrow track
         assert!((outside_dist - inside_dist - 2.0).abs() < 1.0);
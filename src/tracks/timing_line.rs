@@ -0,0 +1,181 @@
+//! Timing lines across the track for lap and sector timing.
+//!
+//! A timing line is the segment across the track perpendicular to the center line at
+//! some distance along it. A vehicle crosses it when the straight motion segment
+//! between two consecutive positions intersects the line in the direction of travel;
+//! interpolating along that segment gives the crossing time within the step, so lap
+//! times do not snap to multiples of the time step.
+//!
+//! The start line is the timing line through the start position, perpendicular to the
+//! start yaw.
+
+use super::arc_length::ArcLengthTable;
+use super::base_track::Track;
+use crate::math::vec2::Vec2;
+
+/// Distance in meters before and after a timing line over which its direction is measured
+const TANGENT_SPAN: f64 = 0.5;
+
+/// Segment across the track at which laps or sectors are timed
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TimingLine {
+    /// Point on the center line in the middle of the timing line
+    pub center: Vec2,
+    /// Unit vector in the direction of travel, normal to the timing line
+    pub direction: Vec2,
+    /// Distance in meters from `center` to either end of the timing line
+    pub half_width: f64,
+}
+
+impl TimingLine {
+    /// Timing line through `center` for travel along `heading` (radians)
+    pub fn new(center: Vec2, heading: f64, half_width: f64) -> Self {
+        Self {
+            center,
+            direction: Vec2::from_angle(heading),
+            half_width,
+        }
+    }
+
+    /// Timing line at distance `s` along `center_line`, spanning both boundaries of `track`
+    ///
+    /// The line reaches the farther of the two boundaries, so a vehicle on the track
+    /// always crosses it. Returns `None` for an empty center line.
+    pub fn across(track: &dyn Track, center_line: &ArcLengthTable, s: f64) -> Option<Self> {
+        Self::spanning(
+            center_line,
+            s,
+            track.get_inside_boundary(),
+            track.get_outside_boundary(),
+        )
+    }
+
+    /// Start line of `track`: through the start position, perpendicular to the start yaw
+    ///
+    /// Like `across`, the line reaches the farther of the two boundaries.
+    pub fn start_line(track: &dyn Track) -> Self {
+        let (x, y, yaw) = track.get_start_position();
+        let center = Vec2::new(x, y);
+        let half_width = nearest_distance(track.get_inside_boundary(), center)
+            .max(nearest_distance(track.get_outside_boundary(), center));
+        Self::new(
+            center,
+            yaw,
+            if half_width.is_finite() { half_width } else { f64::INFINITY },
+        )
+    }
+
+    /// Same as `across`, for callers that keep the boundaries without the track
+    pub(crate) fn spanning(
+        center_line: &ArcLengthTable,
+        s: f64,
+        inside: &[Vec2],
+        outside: &[Vec2],
+    ) -> Option<Self> {
+        let (x, y, _) = center_line.point_at(s)?;
+        let center = Vec2::new(x, y);
+        // Direction of the center line through `s`; bisects the corner at a vertex
+        let point = |s: f64| center_line.point_at(s).map(|(x, y, _)| Vec2::new(x, y));
+        let heading = (point(s + TANGENT_SPAN)? - point(s - TANGENT_SPAN)?).angle();
+        let half_width = nearest_distance(inside, center).max(nearest_distance(outside, center));
+        Some(Self::new(
+            center,
+            heading,
+            if half_width.is_finite() { half_width } else { f64::INFINITY },
+        ))
+    }
+
+    /// Signed distance of `point` past the line in the direction of travel
+    pub fn distance_past(&self, point: Vec2) -> f64 {
+        (point - self.center).dot(self.direction)
+    }
+
+    /// Fraction in (0, 1] of the motion from `from` to `to` at which it crosses the line
+    ///
+    /// Only crossings in the direction of travel within the line's width count; moving
+    /// backwards over the line or passing beside it returns `None`.
+    pub fn crossing(&self, from: Vec2, to: Vec2) -> Option<f64> {
+        let (before, after) = (self.distance_past(from), self.distance_past(to));
+        if !(before < 0.0 && after >= 0.0) {
+            return None;
+        }
+        let fraction = -before / (after - before);
+        let offset = (from.lerp(to, fraction) - self.center).dot(self.direction.perp());
+        (offset.abs() <= self.half_width).then_some(fraction)
+    }
+
+    /// Time at which the motion from `from` (at `from_time`) to `to` (at `to_time`)
+    /// crosses the line, interpolated within the step
+    ///
+    /// Same rules as `crossing`.
+    pub fn crossing_time(&self, from_time: f64, from: Vec2, to_time: f64, to: Vec2) -> Option<f64> {
+        self.crossing(from, to)
+            .map(|fraction| from_time + fraction * (to_time - from_time))
+    }
+}
+
+/// Distance from `point` to the nearest point of `boundary`, infinite if it is empty
+fn nearest_distance(boundary: &[Vec2], point: Vec2) -> f64 {
+    boundary
+        .iter()
+        .map(|vertex| vertex.distance(point))
+        .fold(f64::INFINITY, f64::min)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TimingLine;
+    use crate::math::vec2::Vec2;
+    use crate::tracks::arc_length::ArcLengthTable;
+    use crate::tracks::base_track::Track;
+    use crate::tracks::circle::CircleTrack;
+    use crate::tracks::square::SquareTrack;
+
+    #[test]
+    fn test_crossing_fraction() {
+        let line = TimingLine::new(Vec2::new(10.0, 0.0), 0.0, 5.0);
+        assert_eq!(line.crossing(Vec2::new(9.0, 1.0), Vec2::new(13.0, 2.0)), Some(0.25));
+        assert_eq!(line.crossing(Vec2::new(11.0, 0.0), Vec2::new(9.0, 0.0)), None);
+        assert_eq!(line.crossing(Vec2::new(8.0, 0.0), Vec2::new(9.0, 0.0)), None);
+        assert_eq!(line.crossing(Vec2::new(9.0, 6.0), Vec2::new(11.0, 6.0)), None);
+    }
+
+    #[test]
+    fn test_line_across_track() {
+        let track = SquareTrack::new(100.0, 10.0, 25);
+        let center_line = ArcLengthTable::from_track(&track);
+        let line = TimingLine::across(&track, &center_line, 50.0).expect("timing line");
+        assert!((line.half_width - 5.0).abs() < 1.0, "{line:?}");
+        let (x, y, yaw) = center_line.point_at(50.0).expect("middle of a side");
+        let center = Vec2::new(x, y);
+        let step = Vec2::from_angle(yaw);
+        assert_eq!(line.crossing(center - step, center + step * 3.0), Some(0.25));
+
+        // At a corner the line bisects it, so cutting the corner is timed in proportion
+        let corner = TimingLine::across(&track, &center_line, 100.0).expect("corner line");
+        let point = |s: f64| {
+            let (x, y, _) = center_line.point_at(s).expect("point");
+            Vec2::new(x, y)
+        };
+        let fraction = corner.crossing(point(97.0), point(101.0)).expect("crossing");
+        assert!((fraction - 0.75).abs() < 1e-9, "{fraction}");
+    }
+
+    #[test]
+    fn test_start_line_crossing_time() {
+        let track = CircleTrack::new(50.0, 10.0, 100);
+        let line = TimingLine::start_line(&track);
+        let (x, y, yaw) = track.get_start_position();
+        assert_eq!(line.center, Vec2::new(x, y));
+        assert!((line.half_width - 5.0).abs() < 0.1, "{line:?}");
+
+        // 10 m/s through the start line, 0.03 s into a 0.1 s step
+        let step = Vec2::from_angle(yaw);
+        let from = line.center - step * 0.3;
+        let time = line
+            .crossing_time(1.0, from, 1.1, from + step)
+            .expect("crossing");
+        assert!((time - 1.03).abs() < 1e-9, "{time}");
+        assert_eq!(line.crossing_time(1.1, from + step, 1.2, from), None);
+    }
+}
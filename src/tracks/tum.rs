@@ -0,0 +1,256 @@
+//! Import of TUM global raceline files.
+//!
+//! The TUM global race trajectory optimization (also used by OpenLap-style tools)
+//! writes one raceline point per line with the columns `s_m; x_m; y_m; psi_rad;
+//! kappa_radpm; vx_mps` (an `ax_mps2` column may follow). Fields are separated by `;`
+//! or `,`, and `#` lines (including the column header) are ignored. The heading `psi`
+//! is measured from the north (+y) axis, so the yaw used by this crate is `psi + pi/2`.
+
+use super::cones::polygon_area;
+use super::geometry::TrackGeometry;
+use super::racing_line::{RacingLine, RacingLinePoint};
+use crate::error::{Error, Result};
+use crate::io::compression;
+use crate::math::vec2::Vec2;
+use std::f64::consts::FRAC_PI_2;
+use std::path::Path;
+
+/// Point of a TUM raceline
+#[derive(Debug, Clone, Copy, PartialEq)]
+pub struct TumRacelinePoint {
+    /// Distance along the raceline in meters
+    pub s: f64,
+    pub x: f64,
+    pub y: f64,
+    /// Heading in radians, measured from the north (+y) axis
+    pub psi: f64,
+    /// Curvature in 1/m
+    pub kappa: f64,
+    /// Target speed in m/s
+    pub vx: f64,
+}
+
+impl TumRacelinePoint {
+    /// Heading in this crate's convention (radians from the +x axis)
+    pub fn yaw(&self) -> f64 {
+        self.psi + FRAC_PI_2
+    }
+}
+
+/// Closed raceline read from a TUM global raceline file
+#[derive(Debug, Clone, PartialEq)]
+pub struct TumRaceline {
+    points: Vec<TumRacelinePoint>,
+}
+
+impl TumRaceline {
+    /// Parse a raceline from the text of a TUM file (see the module documentation)
+    ///
+    /// # Returns
+    /// The raceline, or `Error::InvalidConfig` naming the line that is malformed
+    pub fn parse(text: &str) -> Result<Self> {
+        let mut points: Vec<TumRacelinePoint> = Vec::new();
+        for (line_index, line) in text.lines().enumerate() {
+            let line = line.trim();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+            let values: Vec<f64> = line
+                .split([';', ','])
+                .map(|field| field.trim().parse::<f64>())
+                .collect::<Result<_, _>>()
+                .ok()
+                .filter(|values: &Vec<f64>| values.iter().all(|value| value.is_finite()))
+                .ok_or_else(|| {
+                    let message =
+                        format!("line {}: invalid raceline point '{}'", line_index + 1, line);
+                    Error::invalid_config(message)
+                })?;
+            if values.len() < 6 {
+                return Err(Error::invalid_config(format!(
+                    "line {}: expected s, x, y, psi, kappa, vx, got {} fields",
+                    line_index + 1,
+                    values.len()
+                )));
+            }
+            if values[5] < 0.0 {
+                let message = format!("line {}: speed must not be negative", line_index + 1);
+                return Err(Error::invalid_config(message));
+            }
+            points.push(TumRacelinePoint {
+                s: values[0],
+                x: values[1],
+                y: values[2],
+                psi: values[3],
+                kappa: values[4],
+                vx: values[5],
+            });
+        }
+
+        // Some exports repeat the first point to close the loop
+        if points.len() > 1 {
+            let (first, last) = (points[0], points[points.len() - 1]);
+            if (first.x - last.x).hypot(first.y - last.y) < 1e-6 {
+                points.pop();
+            }
+        }
+        if points.len() < 3 {
+            let message = format!("raceline needs at least 3 points, got {}", points.len());
+            return Err(Error::invalid_config(message));
+        }
+        Ok(Self { points })
+    }
+
+    /// Load a raceline from a TUM file (see `parse`)
+    pub fn load<P: AsRef<Path>>(path: P) -> Result<Self> {
+        let path = path.as_ref();
+        let text = compression::read_to_string(path)?;
+        Self::parse(&text).map_err(|err| err.context(path.display()))
+    }
+
+    pub fn points(&self) -> &[TumRacelinePoint] {
+        &self.points
+    }
+
+    pub fn len(&self) -> usize {
+        self.points.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.points.is_empty()
+    }
+
+    /// Racing line with the raceline positions and target speeds
+    pub fn to_racing_line(&self) -> RacingLine {
+        let points = self
+            .points
+            .iter()
+            .map(|point| RacingLinePoint {
+                x: point.x,
+                y: point.y,
+                v: Some(point.vx),
+            })
+            .collect();
+        RacingLine::new(points).expect("raceline has at least 3 points")
+    }
+
+    /// Track following the raceline with a constant width
+    ///
+    /// The boundaries are offset by half the width along the normal of each raceline
+    /// heading; the one enclosing the smaller area becomes the inside boundary. The
+    /// start position is the first raceline point and heading.
+    ///
+    /// # Arguments
+    /// * `track_width` - Distance between the boundaries in meters
+    pub fn to_track(&self, track_width: f64) -> Result<TrackGeometry> {
+        if !(track_width.is_finite() && track_width > 0.0) {
+            let message = format!("track width must be positive, got {}", track_width);
+            return Err(Error::invalid_config(message));
+        }
+        // Offset to the left of the heading for a positive distance
+        let offset = |distance: f64| -> Vec<Vec2> {
+            self.points
+                .iter()
+                .map(|point| {
+                    Vec2::new(point.x, point.y) + Vec2::from_angle(point.yaw()).perp() * distance
+                })
+                .collect()
+        };
+        let left = offset(track_width / 2.0);
+        let right = offset(-track_width / 2.0);
+        let (inside, outside) = if polygon_area(&left).abs() < polygon_area(&right).abs() {
+            (left, right)
+        } else {
+            (right, left)
+        };
+
+        let first = self.points[0];
+        Ok(TrackGeometry::new(
+            "TUM Raceline Track",
+            self.points.iter().map(|point| Vec2::new(point.x, point.y)).collect(),
+            inside,
+            outside,
+            (first.x, first.y, first.yaw()),
+        ))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::TumRaceline;
+    use crate::tracks::base_track::Track;
+    use std::f64::consts::PI;
+
+    /// Counter-clockwise circle of radius 50 m in the TUM format
+    fn circle_raceline(count: usize, separator: &str) -> String {
+        let mut text = String::from("# s_m; x_m; y_m; psi_rad; kappa_radpm; vx_mps; ax_mps2\n");
+        for i in 0..count {
+            let angle = 2.0 * PI * i as f64 / count as f64;
+            let fields = [
+                50.0 * angle,
+                50.0 * angle.cos(),
+                50.0 * angle.sin(),
+                angle, // heading angle + pi/2 from +x, i.e. angle from north
+                0.02,
+                15.0,
+                0.0,
+            ];
+            let fields: Vec<String> = fields.iter().map(|value| value.to_string()).collect();
+            text.push_str(&fields.join(separator));
+            text.push('\n');
+        }
+        text
+    }
+
+    #[test]
+    fn test_parse_tum_raceline() {
+        let raceline = TumRaceline::parse(&circle_raceline(40, "; ")).expect("parse raceline");
+        assert_eq!(raceline.len(), 40);
+        assert_eq!(raceline.points()[0].vx, 15.0);
+        assert!((raceline.points()[0].yaw() - PI / 2.0).abs() < 1e-12);
+
+        let comma = TumRaceline::parse(&circle_raceline(40, ",")).expect("parse comma raceline");
+        assert_eq!(comma, raceline);
+
+        let racing_line = raceline.to_racing_line();
+        assert_eq!(racing_line.len(), 40);
+        assert!(racing_line.has_speeds());
+    }
+
+    #[test]
+    fn test_parse_tum_raceline_errors() {
+        assert!(TumRaceline::parse("# s_m; x_m; y_m\n").is_err());
+        let err = TumRaceline::parse("0;0;0;0;0;1\n1;1;0;0;0\n").expect_err("short line");
+        assert!(err.to_string().contains("line 2"));
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+        assert!(TumRaceline::parse("0;0;0;0;0;abc\n").is_err());
+        assert!(TumRaceline::parse("0;0;0;0;0;1\n1;1;0;0;0;-1\n2;1;1;0;0;1\n").is_err());
+    }
+
+    #[test]
+    fn test_closing_point_is_dropped() {
+        let text = "0;0;0;0;0;1\n1;1;0;0;0;1\n2;1;1;0;0;1\n3;0;0;0;0;1\n";
+        assert_eq!(TumRaceline::parse(text).expect("parse raceline").len(), 3);
+    }
+
+    #[test]
+    fn test_tum_raceline_as_track() {
+        let raceline = TumRaceline::parse(&circle_raceline(100, ";")).expect("parse raceline");
+        let track = raceline.to_track(10.0).expect("build track");
+
+        assert!(track.is_in_track(50.0, 0.0));
+        assert!(!track.is_in_track(0.0, 0.0));
+        assert!(!track.is_in_track(60.0, 0.0));
+        assert!((track.get_inside_boundary()[0].norm() - 45.0).abs() < 1e-9);
+
+        let (x, y, yaw) = track.get_start_position();
+        assert!((x - 50.0).abs() < 1e-9 && y.abs() < 1e-9);
+        assert!((yaw - PI / 2.0).abs() < 1e-9);
+        raceline
+            .to_racing_line()
+            .validate(&track)
+            .expect("raceline within track");
+
+        assert!(raceline.to_track(0.0).is_err());
+    }
+}
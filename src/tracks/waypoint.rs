@@ -0,0 +1,296 @@
+use super::base_track::{compute_center_line_yaw, Track};
+use super::cones::polygon_area;
+use super::spatial::PolygonIndex;
+use crate::error::{Error, Result};
+use crate::math::vec2::Vec2;
+use std::fmt;
+
+/// Largest factor by which a boundary offset is stretched at a corner, so the width
+/// stays constant through moderate corners without spikes at hairpins
+const MAX_MITER: f64 = 2.0;
+
+/// Closed circuit of arbitrary shape defined by center line waypoints and widths
+///
+/// The boundaries are offset from the center line on both sides, along the bisector of
+/// the segments meeting at each waypoint, by the width to the left and to the right of
+/// that waypoint. The yaw of every waypoint and the start position (first waypoint,
+/// facing the second) are derived from the waypoints. Containment is evaluated against
+/// the indexed boundary polygons.
+#[derive(Debug, Clone, PartialEq)]
+pub struct WaypointTrack {
+    name: String,
+    center_line: Vec<Vec2>,
+    center_line_yaw: Vec<f64>,
+    inside_border: Vec<Vec2>,
+    outside_border: Vec<Vec2>,
+    start_pos: (f64, f64, f64),
+    widths: Vec<(f64, f64)>, // Distance to the left and right boundary per waypoint
+    inside_index: PolygonIndex,
+    outside_index: PolygonIndex,
+}
+
+impl WaypointTrack {
+    /// Create a track of constant width through `waypoints`
+    ///
+    /// A last waypoint repeating the first one (an explicitly closed loop) is dropped.
+    ///
+    /// # Arguments
+    /// * `name` - Track name for plotting
+    /// * `waypoints` - Center line points in driving order, at least 3
+    /// * `track_width` - Distance between the boundaries in meters
+    pub fn new(name: &str, waypoints: Vec<Vec2>, track_width: f64) -> Result<Self> {
+        if !(track_width.is_finite() && track_width > 0.0) {
+            let message = format!("track width must be positive, got {}", track_width);
+            return Err(Error::invalid_config(message));
+        }
+        let widths = vec![(track_width / 2.0, track_width / 2.0); waypoints.len()];
+        Self::with_widths(name, waypoints, widths)
+    }
+
+    /// Create a track through `waypoints` with a width on each side of every waypoint
+    ///
+    /// # Arguments
+    /// * `name` - Track name for plotting
+    /// * `waypoints` - Center line points in driving order, at least 3
+    /// * `widths` - Distance in meters from each waypoint to the (left, right) boundary,
+    ///   looking in the direction of travel
+    pub fn with_widths(name: &str, waypoints: Vec<Vec2>, widths: Vec<(f64, f64)>) -> Result<Self> {
+        if widths.len() != waypoints.len() {
+            return Err(Error::invalid_config(format!(
+                "got {} widths for {} waypoints",
+                widths.len(),
+                waypoints.len()
+            )));
+        }
+        if let Some(&(left, right)) = widths.iter().find(|&&(left, right)| {
+            !(left.is_finite() && right.is_finite() && left >= 0.0 && right >= 0.0 && left + right > 0.0)
+        }) {
+            let message = format!("invalid track widths ({}, {})", left, right);
+            return Err(Error::invalid_config(message));
+        }
+        let mut points: Vec<(Vec2, (f64, f64))> = waypoints.into_iter().zip(widths).collect();
+        if points.len() > 1 && points.first().map(|p| p.0) == points.last().map(|p| p.0) {
+            points.pop();
+        }
+        points.dedup_by_key(|point| point.0);
+        if points.len() < 3 {
+            return Err(Error::invalid_config(format!(
+                "waypoint track needs at least 3 distinct waypoints, got {}",
+                points.len()
+            )));
+        }
+
+        let (waypoints, widths) = points.into_iter().unzip();
+        let mut track = Self {
+            name: name.to_string(),
+            center_line: Vec::new(),
+            center_line_yaw: Vec::new(),
+            inside_border: Vec::new(),
+            outside_border: Vec::new(),
+            start_pos: (0.0, 0.0, 0.0),
+            widths,
+            inside_index: PolygonIndex::new(&[]),
+            outside_index: PolygonIndex::new(&[]),
+        };
+        track.generate_boundaries(waypoints);
+        Ok(track)
+    }
+
+    /// Create a track of constant width through `(x, y)` waypoints, see `new`
+    pub fn from_points(name: &str, points: &[(f64, f64)], track_width: f64) -> Result<Self> {
+        Self::new(
+            name,
+            points.iter().map(|&(x, y)| Vec2::new(x, y)).collect(),
+            track_width,
+        )
+    }
+
+    /// Distance in meters from each waypoint to the (left, right) boundary
+    pub fn widths(&self) -> &[(f64, f64)] {
+        &self.widths
+    }
+
+    /// Average distance between the boundaries in meters
+    pub fn track_width(&self) -> f64 {
+        self.widths.iter().map(|(left, right)| left + right).sum::<f64>() / self.widths.len() as f64
+    }
+
+    fn generate_boundaries(&mut self, waypoints: Vec<Vec2>) {
+        let n = waypoints.len();
+        // Boundary on the left or the right of the direction of travel
+        let offset = |left_side: bool| -> Vec<Vec2> {
+            (0..n)
+                .map(|i| {
+                    let point = waypoints[i];
+                    let (left, right) = self.widths[i];
+                    let distance = if left_side { left } else { -right };
+                    let incoming = (point - waypoints[(i + n - 1) % n]).normalized();
+                    let outgoing = (waypoints[(i + 1) % n] - point).normalized();
+                    let (Some(incoming), Some(outgoing)) = (incoming, outgoing) else {
+                        return point;
+                    };
+                    let tangent = (incoming + outgoing).normalized().unwrap_or(outgoing);
+                    let miter = (1.0 / tangent.dot(outgoing).max(f64::EPSILON)).min(MAX_MITER);
+                    point + tangent.perp() * (distance * miter)
+                })
+                .collect()
+        };
+        let left = offset(true);
+        let right = offset(false);
+        let (inside, outside) = if polygon_area(&left).abs() < polygon_area(&right).abs() {
+            (left, right)
+        } else {
+            (right, left)
+        };
+
+        self.center_line_yaw = compute_center_line_yaw(&waypoints);
+        self.start_pos = (waypoints[0].x, waypoints[0].y, self.center_line_yaw[0]);
+        self.center_line = waypoints;
+        self.inside_index = PolygonIndex::new(&inside);
+        self.outside_index = PolygonIndex::new(&outside);
+        self.inside_border = inside;
+        self.outside_border = outside;
+    }
+}
+
+impl Track for WaypointTrack {
+    fn init(
+        &mut self,
+        center_line: Vec<Vec2>,
+        inside_border: Vec<Vec2>,
+        outside_border: Vec<Vec2>,
+        get_start_position: (f64, f64, f64),
+    ) {
+        self.center_line_yaw = compute_center_line_yaw(&center_line);
+        self.center_line = center_line;
+        self.inside_index = PolygonIndex::new(&inside_border);
+        self.outside_index = PolygonIndex::new(&outside_border);
+        self.inside_border = inside_border;
+        self.outside_border = outside_border;
+        self.start_pos = get_start_position;
+    }
+
+    fn is_in_track(&self, x: f64, y: f64) -> bool {
+        self.outside_index.contains(x, y) && !self.inside_index.contains(x, y)
+    }
+
+    fn get_start_position(&self) -> (f64, f64, f64) {
+        self.start_pos
+    }
+
+    fn get_center_line(&self) -> &[Vec2] {
+        &self.center_line
+    }
+
+    fn get_center_line_yaw(&self) -> &[f64] {
+        &self.center_line_yaw
+    }
+
+    fn get_inside_boundary(&self) -> &[Vec2] {
+        &self.inside_border
+    }
+
+    fn get_outside_boundary(&self) -> &[Vec2] {
+        &self.outside_border
+    }
+
+    fn get_track_name(&self) -> &str {
+        &self.name
+    }
+
+    fn get_plot_range(&self) -> (f64, f64) {
+        let (min_coord, max_coord) = self
+            .outside_border
+            .iter()
+            .chain(self.inside_border.iter())
+            .fold((f64::INFINITY, f64::NEG_INFINITY), |(lo, hi), &Vec2 { x, y }| {
+                (lo.min(x).min(y), hi.max(x).max(y))
+            });
+        let margin = ((max_coord - min_coord) * 0.1).max(1.0);
+        (min_coord - margin, max_coord + margin)
+    }
+}
+
+impl fmt::Display for WaypointTrack {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        write!(
+            f,
+            "WaypointTrack {{ name: {}, num_points: {}, track_width: {} }}",
+            self.name,
+            self.center_line.len(),
+            self.track_width()
+        )
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WaypointTrack;
+    use crate::math::vec2::Vec2;
+    use crate::tracks::base_track::Track;
+    use std::f64::consts::PI;
+
+    /// L-shaped circuit: 100 m x 60 m with the top right quarter cut out
+    fn l_shape() -> WaypointTrack {
+        let points = [
+            (0.0, 0.0),
+            (100.0, 0.0),
+            (100.0, 30.0),
+            (50.0, 30.0),
+            (50.0, 60.0),
+            (0.0, 60.0),
+            (0.0, 0.0),
+        ];
+        WaypointTrack::from_points("L Track", &points, 8.0).expect("waypoint track")
+    }
+
+    #[test]
+    fn test_waypoint_track_geometry() {
+        let track = l_shape();
+        assert_eq!(track.get_center_line().len(), 6);
+        assert_eq!(track.get_start_position(), (0.0, 0.0, 0.0));
+        assert!((track.get_center_line_yaw()[1] - PI / 2.0).abs() < 1e-12);
+        assert!((track.total_length() - 320.0).abs() < 1e-9);
+
+        // On the straights and around the concave corner at (50, 30)
+        for (x, y) in [(50.0, 0.0), (50.0, 3.5), (100.0, 15.0), (75.0, 30.0), (50.0, 45.0), (53.0, 33.0)] {
+            assert!(track.is_in_track(x, y), "({x}, {y}) should be on track");
+        }
+        for (x, y) in [(50.0, 5.0), (50.0, -5.0), (25.0, 30.0), (75.0, 45.0), (105.0, 15.0)] {
+            assert!(!track.is_in_track(x, y), "({x}, {y}) should be off track");
+        }
+
+        // Corners are mitered, so the outside corner sits half the width out on both axes
+        let outside = track.get_outside_boundary();
+        assert!(outside.iter().any(|p| (p.x - 104.0).abs() < 1e-9 && (p.y + 4.0).abs() < 1e-9));
+        let (min_coord, max_coord) = track.get_plot_range();
+        assert!(min_coord < -4.0 && max_coord > 104.0);
+    }
+
+    #[test]
+    fn test_waypoint_track_errors() {
+        assert!(WaypointTrack::from_points("Line", &[(0.0, 0.0), (10.0, 0.0), (0.0, 0.0)], 5.0).is_err());
+        let err = WaypointTrack::from_points("Flat", &[(0.0, 0.0), (10.0, 0.0), (0.0, 10.0)], 0.0)
+            .expect_err("zero width");
+        assert!(matches!(err, crate::Error::InvalidConfig(_)));
+    }
+
+    #[test]
+    fn test_waypoint_track_with_widths() {
+        // Square driven counter-clockwise: left is the inside, right the outside
+        let waypoints = vec![
+            Vec2::new(0.0, 0.0),
+            Vec2::new(100.0, 0.0),
+            Vec2::new(100.0, 100.0),
+            Vec2::new(0.0, 100.0),
+        ];
+        let track = WaypointTrack::with_widths("Square", waypoints.clone(), vec![(2.0, 6.0); 4])
+            .expect("waypoint track");
+        assert!(track.is_in_track(50.0, 1.5) && track.is_in_track(50.0, -5.5));
+        assert!(!track.is_in_track(50.0, 2.5) && !track.is_in_track(50.0, -6.5));
+        assert_eq!(track.track_width(), 8.0);
+
+        assert!(WaypointTrack::with_widths("Square", waypoints.clone(), vec![(2.0, 6.0); 3]).is_err());
+        assert!(WaypointTrack::with_widths("Square", waypoints, vec![(2.0, -1.0); 4]).is_err());
+    }
+}
@@ -0,0 +1,225 @@
+//! Unit-checked quantities for the public API (`units` feature).
+//!
+//! The core API takes plain `f64` values in SI units (meters, seconds, radians). The
+//! functions here accept and return `uom` quantities instead, so a scenario cannot be
+//! configured with a speed where a length is expected, or with degrees where radians
+//! are expected. Quantities are converted to SI at this boundary; the simulation itself
+//! still runs on plain numbers.
+
+use crate::config::{ScenarioConfig, TrackConfig};
+use crate::models::point_mass::{PointMass, PointMassState};
+use crate::models::scalar::Float;
+use crate::simulation::open_loop::OpenLoopSimulationBuilder;
+use uom::si::acceleration::meter_per_second_squared;
+use uom::si::angle::radian;
+use uom::si::angular_velocity::radian_per_second;
+use uom::si::length::meter;
+use uom::si::time::second;
+use uom::si::velocity::meter_per_second;
+
+pub use uom::si::f64::{Acceleration, Angle, AngularVelocity, Length, Time, Velocity};
+
+impl TrackConfig {
+    /// Circular track, see `CircleTrack::new`
+    pub fn circle(radius: Length, track_width: Length, num_points: usize) -> Self {
+        TrackConfig::Circle {
+            radius: radius.get::<meter>(),
+            track_width: track_width.get::<meter>(),
+            num_points,
+        }
+    }
+
+    /// Square track, see `SquareTrack::new`
+    pub fn square(height: Length, track_width: Length, points_per_side: usize) -> Self {
+        TrackConfig::Square {
+            height: height.get::<meter>(),
+            track_width: track_width.get::<meter>(),
+            points_per_side,
+        }
+    }
+}
+
+impl ScenarioConfig {
+    /// Time step of the run
+    pub fn dt_quantity(&self) -> Time {
+        Time::new::<second>(self.dt)
+    }
+
+    pub fn set_dt_quantity(&mut self, dt: Time) {
+        self.dt = dt.get::<second>();
+    }
+
+    /// Simulated duration of the run
+    pub fn duration_quantity(&self) -> Time {
+        Time::new::<second>(self.duration)
+    }
+
+    pub fn set_duration_quantity(&mut self, duration: Time) {
+        self.duration = duration.get::<second>();
+    }
+
+    /// Constant (acceleration, yaw rate) controls
+    pub fn controls_quantities(&self) -> (Acceleration, AngularVelocity) {
+        (
+            Acceleration::new::<meter_per_second_squared>(self.controls.0),
+            AngularVelocity::new::<radian_per_second>(self.controls.1),
+        )
+    }
+
+    pub fn set_controls_quantities(&mut self, ax: Acceleration, yaw_rate: AngularVelocity) {
+        self.controls = (
+            ax.get::<meter_per_second_squared>(),
+            yaw_rate.get::<radian_per_second>(),
+        );
+    }
+}
+
+impl OpenLoopSimulationBuilder {
+    /// Time step used by `simulate`
+    pub fn dt_quantity(self, dt: Time) -> Self {
+        self.dt(dt.get::<second>())
+    }
+
+    /// Simulated duration used by `simulate`
+    pub fn duration_quantity(self, duration: Time) -> Self {
+        self.duration(duration.get::<second>())
+    }
+
+    /// Constant control inputs
+    pub fn controls_quantities(self, ax: Acceleration, yaw_rate: AngularVelocity) -> Self {
+        self.controls(
+            ax.get::<meter_per_second_squared>(),
+            yaw_rate.get::<radian_per_second>(),
+        )
+    }
+}
+
+impl PointMass {
+    /// Create a new point mass with initial position, forward velocity, and orientation
+    pub fn from_quantities(x: Length, y: Length, vx: Velocity, yaw: Angle) -> Self {
+        Self::with_initial_state(
+            x.get::<meter>(),
+            y.get::<meter>(),
+            vx.get::<meter_per_second>(),
+            yaw.get::<radian>(),
+        )
+    }
+}
+
+impl<T: Float> PointMass<T> {
+    /// Set the control inputs (body frame acceleration, yaw rate)
+    pub fn set_controls_quantities(&mut self, ax: Acceleration, yaw_rate: AngularVelocity) {
+        self.set_controls(
+            ax.get::<meter_per_second_squared>(),
+            yaw_rate.get::<radian_per_second>(),
+        );
+    }
+}
+
+impl<T: Float> PointMassState<T> {
+    /// World frame (x, y) position
+    pub fn position_quantities(&self) -> (Length, Length) {
+        (
+            Length::new::<meter>(self.x.to_f64()),
+            Length::new::<meter>(self.y.to_f64()),
+        )
+    }
+
+    /// Body frame (vx, vy) velocity
+    pub fn velocity_quantities(&self) -> (Velocity, Velocity) {
+        (
+            Velocity::new::<meter_per_second>(self.vx.to_f64()),
+            Velocity::new::<meter_per_second>(self.vy.to_f64()),
+        )
+    }
+
+    /// Orientation angle
+    pub fn yaw_quantity(&self) -> Angle {
+        Angle::new::<radian>(self.yaw.to_f64())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::{Acceleration, Angle, AngularVelocity, Length, Time, Velocity};
+    use crate::config::{ScenarioConfig, TrackConfig};
+    use crate::models::base_model::Model;
+    use crate::models::point_mass::PointMass;
+    use crate::simulation::open_loop::OpenLoopSimulation;
+    use crate::tracks::circle::CircleTrack;
+    use std::sync::Arc;
+    use uom::si::acceleration::meter_per_second_squared;
+    use uom::si::angle::degree;
+    use uom::si::angular_velocity::degree_per_second;
+    use uom::si::length::{kilometer, meter};
+    use uom::si::time::millisecond;
+    use uom::si::velocity::kilometer_per_hour;
+
+    #[test]
+    fn test_scenario_config_from_quantities() {
+        let mut config = ScenarioConfig {
+            track: TrackConfig::circle(
+                Length::new::<kilometer>(0.05),
+                Length::new::<meter>(10.0),
+                100,
+            ),
+            ..ScenarioConfig::default()
+        };
+        config.set_dt_quantity(Time::new::<millisecond>(50.0));
+        config.set_controls_quantities(
+            Acceleration::new::<meter_per_second_squared>(1.5),
+            AngularVelocity::new::<degree_per_second>(180.0),
+        );
+
+        assert_eq!(
+            config.track,
+            TrackConfig::Circle {
+                radius: 50.0,
+                track_width: 10.0,
+                num_points: 100,
+            }
+        );
+        assert!((config.dt - 0.05).abs() < 1e-12);
+        assert!((config.controls.1 - std::f64::consts::PI).abs() < 1e-12);
+        let (ax, _) = config.controls_quantities();
+        assert!((ax.get::<meter_per_second_squared>() - 1.5).abs() < 1e-12);
+        assert!((config.dt_quantity().get::<millisecond>() - 50.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_point_mass_quantities_round_trip() {
+        let model = PointMass::from_quantities(
+            Length::new::<meter>(1.0),
+            Length::new::<meter>(2.0),
+            Velocity::new::<kilometer_per_hour>(36.0),
+            Angle::new::<degree>(90.0),
+        );
+        let state = model.get_state();
+        assert!((state.vx - 10.0).abs() < 1e-12);
+        assert!((state.yaw - std::f64::consts::FRAC_PI_2).abs() < 1e-12);
+
+        let (x, _) = state.position_quantities();
+        assert_eq!(x.get::<meter>(), 1.0);
+        let (vx, _) = state.velocity_quantities();
+        assert!((vx.get::<kilometer_per_hour>() - 36.0).abs() < 1e-9);
+        assert!((state.yaw_quantity().get::<degree>() - 90.0).abs() < 1e-9);
+    }
+
+    #[test]
+    fn test_builder_accepts_quantities() {
+        let mut sim = OpenLoopSimulation::builder()
+            .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+            .controls_quantities(
+                Acceleration::new::<meter_per_second_squared>(1.0),
+                AngularVelocity::new::<degree_per_second>(0.0),
+            )
+            .dt_quantity(Time::new::<millisecond>(100.0))
+            .duration_quantity(Time::new::<millisecond>(1000.0))
+            .build()
+            .expect("build simulation");
+
+        let result = sim.simulate().expect("run");
+        assert_eq!(result.len(), 11);
+        assert!((result.last().expect("final snapshot").state.vx - 1.0).abs() < 1e-9);
+    }
+}
@@ -0,0 +1,161 @@
+//! JavaScript-friendly API for running the simulation in the browser.
+//!
+//! Build with
+//! `cargo rustc --lib --release --target wasm32-unknown-unknown --features wasm --crate-type cdylib`
+//! and generate the bindings with `wasm-bindgen --target web`. Only the core (models, tracks,
+//! controllers, simulation) is exposed; rendering to files and ffmpeg are not
+//! available in the browser. Trajectories are returned as flat `Float64Array`s.
+
+use crate::math::vec2::Vec2;
+use crate::models::base_model::Model;
+use crate::models::point_mass::PointMassState;
+use crate::simulation::base_simulation::Simulation;
+use crate::simulation::open_loop::OpenLoopSimulation;
+use crate::simulation::result::SimulationResult;
+use crate::tracks::base_track::Track;
+use crate::tracks::circle::CircleTrack;
+use std::sync::Arc;
+use wasm_bindgen::prelude::*;
+
+/// Open-loop simulation on a circular track, driven from JavaScript
+#[wasm_bindgen]
+pub struct WasmSimulation {
+    simulation: OpenLoopSimulation,
+    result: SimulationResult<PointMassState>,
+}
+
+#[wasm_bindgen]
+impl WasmSimulation {
+    /// Create a simulation on a circular track with constant controls
+    ///
+    /// # Arguments
+    /// * `radius` - Radius of the track center line in meters
+    /// * `track_width` - Width of the track in meters
+    /// * `ax` - Body frame x-axis acceleration in m/s²
+    /// * `yaw_rate` - Yaw rate in radians/s
+    #[wasm_bindgen(constructor)]
+    pub fn new(radius: f64, track_width: f64, ax: f64, yaw_rate: f64) -> Self {
+        let simulation = OpenLoopSimulation::builder()
+            .controls(ax, yaw_rate)
+            .track(Arc::new(CircleTrack::new(radius, track_width, 100)))
+            .build()
+            .expect("default time step and duration are valid");
+        Self {
+            simulation,
+            result: SimulationResult::new(),
+        }
+    }
+
+    /// Run the simulation from its current state
+    ///
+    /// # Returns
+    /// Number of recorded snapshots, or an error thrown in JavaScript if the model
+    /// diverged
+    pub fn run(&mut self, dt: f64, duration: f64) -> Result<usize, JsError> {
+        self.simulation
+            .run_into(dt, duration, &mut self.result)
+            .map_err(|err| JsError::new(&err.to_string()))?;
+        Ok(self.result.len())
+    }
+
+    /// Reset the model to the track start position and clear the last result
+    pub fn reset(&mut self) {
+        self.simulation.reset();
+        self.result.clear();
+    }
+
+    /// Change the constant controls used by the next run
+    #[wasm_bindgen(js_name = setControls)]
+    pub fn set_controls(&mut self, ax: f64, yaw_rate: f64) {
+        self.simulation.set_controls(ax, yaw_rate);
+    }
+
+    /// Snapshot times of the last run in seconds
+    pub fn times(&self) -> Vec<f64> {
+        self.result.times().collect()
+    }
+
+    /// Poses of the last run, flattened as `[x0, y0, yaw0, x1, y1, yaw1, ...]`
+    pub fn poses(&self) -> Vec<f64> {
+        self.result
+            .states()
+            .flat_map(|state| [state.x, state.y, state.yaw])
+            .collect()
+    }
+
+    /// Whether each snapshot of the last run was on track (1) or off track (0)
+    #[wasm_bindgen(js_name = inTrack)]
+    pub fn in_track(&self) -> Vec<u8> {
+        self.result
+            .snapshots()
+            .iter()
+            .map(|snapshot| u8::from(snapshot.in_track))
+            .collect()
+    }
+
+    /// Current model pose as `[x, y, yaw]`
+    pub fn position(&self) -> Vec<f64> {
+        self.simulation.model().map_or_else(Vec::new, |model| {
+            let (x, y, yaw) = model.get_position();
+            vec![x, y, yaw]
+        })
+    }
+
+    /// Vehicle size as `[length, width]` in meters
+    #[wasm_bindgen(js_name = vehicleSize)]
+    pub fn vehicle_size(&self) -> Vec<f64> {
+        self.simulation.model().map_or_else(Vec::new, |model| {
+            let (length, width) = model.get_size();
+            vec![length, width]
+        })
+    }
+
+    /// Track center line, flattened as `[x0, y0, x1, y1, ...]`
+    #[wasm_bindgen(js_name = centerLine)]
+    pub fn center_line(&self) -> Vec<f64> {
+        self.track_points(|track| track.get_center_line())
+    }
+
+    /// Inside track boundary, flattened as `[x0, y0, x1, y1, ...]`
+    #[wasm_bindgen(js_name = insideBoundary)]
+    pub fn inside_boundary(&self) -> Vec<f64> {
+        self.track_points(|track| track.get_inside_boundary())
+    }
+
+    /// Outside track boundary, flattened as `[x0, y0, x1, y1, ...]`
+    #[wasm_bindgen(js_name = outsideBoundary)]
+    pub fn outside_boundary(&self) -> Vec<f64> {
+        self.track_points(|track| track.get_outside_boundary())
+    }
+}
+
+impl WasmSimulation {
+    fn track_points(&self, points: fn(&dyn Track) -> &[Vec2]) -> Vec<f64> {
+        self.simulation.track().map_or_else(Vec::new, |track| {
+            points(track).iter().flat_map(|point| [point.x, point.y]).collect()
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::WasmSimulation;
+
+    #[test]
+    fn test_wasm_simulation_flattens_results() {
+        let mut simulation = WasmSimulation::new(50.0, 10.0, 2.0, 0.0);
+
+        let count = simulation.run(0.1, 0.5).expect("run");
+
+        assert_eq!(count, 6);
+        assert_eq!(simulation.times().len(), count);
+        assert_eq!(simulation.poses().len(), 3 * count);
+        assert_eq!(simulation.in_track(), vec![1; count]);
+        assert_eq!(simulation.center_line().len(), 2 * 100);
+        assert_eq!(&simulation.poses()[..2], &[50.0, 0.0]);
+
+        simulation.reset();
+        assert!(simulation.times().is_empty());
+        assert_eq!(simulation.position()[..2], [50.0, 0.0]);
+    }
+}
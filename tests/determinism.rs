@@ -0,0 +1,110 @@
+use lap_simulation::controllers::scripted::{
+    ControlSample, ControlSchedule, ScheduleInterpolation,
+};
+use lap_simulation::models::point_mass::PointMassState;
+use lap_simulation::simulation::base_simulation::Simulation;
+use lap_simulation::simulation::open_loop::OpenLoopSimulation;
+use lap_simulation::simulation::result::SimulationResult;
+use lap_simulation::testing::{
+    check_golden_trace, format_trace, SplitMix64, DEFAULT_TRACE_TOLERANCE,
+};
+use lap_simulation::tracks::circle::CircleTrack;
+use lap_simulation::tracks::square::SquareTrack;
+use std::path::{Path, PathBuf};
+use std::sync::Arc;
+
+const SEED: u64 = 42;
+
+fn golden_path(name: &str) -> PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(name)
+}
+
+/// Schedule of `count` random control samples, one every 0.5 s
+fn seeded_schedule(seed: u64, count: usize) -> ControlSchedule {
+    let mut rng = SplitMix64::new(seed);
+    let samples = (0..count)
+        .map(|index| ControlSample {
+            time: index as f64 * 0.5,
+            ax: rng.range(-2.0, 4.0),
+            yaw_rate: rng.range(-0.5, 0.5),
+        })
+        .collect();
+    ControlSchedule::new(samples)
+        .expect("valid schedule")
+        .with_interpolation(ScheduleInterpolation::Linear)
+}
+
+fn run_seeded_square(seed: u64) -> SimulationResult<PointMassState> {
+    let mut sim = OpenLoopSimulation::builder()
+        .schedule(seeded_schedule(seed, 12))
+        .track(Arc::new(SquareTrack::new(100.0, 10.0, 25)))
+        .build()
+        .expect("build simulation");
+    sim.run(0.02, 6.0).expect("run")
+}
+
+#[test]
+fn test_circle_constant_controls_matches_golden_trace() {
+    let mut sim = OpenLoopSimulation::builder()
+        .controls(2.0, 0.4)
+        .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+        .build()
+        .expect("build simulation");
+    let result = sim.run(0.01, 5.0).expect("run");
+
+    check_golden_trace(
+        &result,
+        golden_path("circle_constant_controls.csv"),
+        DEFAULT_TRACE_TOLERANCE,
+    )
+    .expect("trace differs from golden file");
+}
+
+#[test]
+fn test_square_seeded_schedule_matches_golden_trace() {
+    let result = run_seeded_square(SEED);
+
+    check_golden_trace(
+        &result,
+        golden_path("square_seeded_schedule.csv"),
+        DEFAULT_TRACE_TOLERANCE,
+    )
+    .expect("trace differs from golden file");
+}
+
+#[test]
+fn test_seeded_runs_are_bitwise_reproducible() {
+    let first = run_seeded_square(SEED);
+    let second = run_seeded_square(SEED);
+
+    assert_eq!(first.len(), second.len());
+    for (a, b) in first.snapshots().iter().zip(second.snapshots()) {
+        assert_eq!(a.time.to_bits(), b.time.to_bits());
+        assert_eq!(a.state.x.to_bits(), b.state.x.to_bits());
+        assert_eq!(a.state.y.to_bits(), b.state.y.to_bits());
+        assert_eq!(a.state.yaw.to_bits(), b.state.yaw.to_bits());
+        assert_eq!(a.in_track, b.in_track);
+    }
+    assert_ne!(
+        format_trace(&first),
+        format_trace(&run_seeded_square(SEED + 1))
+    );
+}
+
+#[test]
+fn test_step_times_do_not_drift() {
+    let mut sim = OpenLoopSimulation::builder()
+        .controls(1.0, 0.0)
+        .track(Arc::new(CircleTrack::new(50.0, 10.0, 100)))
+        .build()
+        .expect("build simulation");
+    let result = sim.run(0.1, 100.0).expect("run");
+
+    assert_eq!(result.len(), 1001);
+    for (step, time) in result.times().enumerate() {
+        assert_eq!(time, step as f64 * 0.1);
+    }
+}
@@ -1,11 +1,13 @@
 use lap_simulation::models::base_model::Model;
 use lap_simulation::models::point_mass::PointMass;
-use lap_simulation::plotting::render_open_loop_outputs;
+use lap_simulation::models::point_mass::PointMassState;
+use lap_simulation::plotting::{render_open_loop_outputs, render_simulation_outputs, RenderOptions};
 use lap_simulation::simulation::base_simulation::Simulation;
 use lap_simulation::simulation::open_loop::OpenLoopSimulation;
 use lap_simulation::tracks::base_track::Track;
 use lap_simulation::tracks::circle::CircleTrack;
 use std::process::Command;
+use std::sync::Arc;
 
 #[test]
 fn test_open_loop_simulation_outputs_svgs_and_video() {
@@ -21,13 +23,16 @@ fn test_open_loop_simulation_outputs_svgs_and_video() {
 
     let track = CircleTrack::new(50.0, 10.0, 100);
     let model = PointMass::new();
-    let mut simulation = OpenLoopSimulation::new();
-    simulation.init(track, model);
+    let mut simulation = OpenLoopSimulation::builder()
+        .track(Arc::new(track))
+        .model(model)
+        .build()
+        .expect("build simulation");
 
     let dt = 0.1;
     let duration = 3.0;
     let fps = 10;
-    let states = simulation.run(dt, duration);
+    let states: Vec<PointMassState> = simulation.run(dt, duration).expect("run").states().cloned().collect();
 
     let track = simulation.track().expect("track missing after run");
     let model = simulation.model().expect("model missing after run");
@@ -49,6 +54,15 @@ fn test_open_loop_simulation_outputs_svgs_and_video() {
     assert!(initial_svg.exists(), "missing initial_state.svg in output dir");
     assert!(final_svg.exists(), "missing final_state.svg in output dir");
     assert!(video_mp4.exists(), "missing open_loop.mp4 in output dir");
+
+    // Frames are streamed to ffmpeg, so no step frames or concat lists are left behind
+    let leftovers: Vec<_> = std::fs::read_dir(&output_path)
+        .expect("read output dir")
+        .filter_map(|entry| entry.ok())
+        .map(|entry| entry.file_name().to_string_lossy().into_owned())
+        .filter(|name| name.starts_with("step_") || name.ends_with(".txt"))
+        .collect();
+    assert!(leftovers.is_empty(), "unexpected files: {leftovers:?}");
 }
 
 #[test]
@@ -56,16 +70,20 @@ fn test_open_loop_simulation_returns_state_trajectory() {
     let track = CircleTrack::new(50.0, 10.0, 100);
     let start_pos = track.get_start_position();
     let model = PointMass::new();
-    let mut simulation = OpenLoopSimulation::with_controls(0.0, 0.0);
-    simulation.init(track, model);
+    let mut simulation = OpenLoopSimulation::builder()
+        .controls(0.0, 0.0)
+        .track(Arc::new(track))
+        .model(model)
+        .build()
+        .expect("build simulation");
 
     let dt = 0.2;
     let duration = 0.5;
-    let states = simulation.run(dt, duration);
+    let result = simulation.run(dt, duration).expect("run");
 
-    assert_eq!(states.len(), 4, "unexpected trajectory length");
+    assert_eq!(result.len(), 4, "unexpected trajectory length");
 
-    for (index, state) in states.iter().enumerate() {
+    for (index, state) in result.states().enumerate() {
         assert!(
             (state.x - start_pos.0).abs() < 1e-9,
             "state {index} has unexpected x"
@@ -82,3 +100,43 @@ fn test_open_loop_simulation_returns_state_trajectory() {
         assert!(state.vy.abs() < 1e-9, "state {index} has unexpected vy");
     }
 }
+
+#[test]
+fn test_open_loop_simulation_keeps_step_frames_when_requested() {
+    let output_dir = crate::common::temp_output_dir("open_loop_frames");
+    let output_path = output_dir.path().join("results");
+
+    let track = CircleTrack::new(50.0, 10.0, 100);
+    let model = PointMass::new();
+    let mut simulation = OpenLoopSimulation::builder()
+        .track(Arc::new(track))
+        .model(model)
+        .build()
+        .expect("build simulation");
+
+    let dt = 0.1;
+    let duration = 1.0;
+    let fps = 10;
+    let result = simulation.run(dt, duration).expect("run");
+
+    let track = simulation.track().expect("track missing after run");
+    let model = simulation.model().expect("model missing after run");
+    let options = RenderOptions {
+        keep_frames: true,
+        ..RenderOptions::default()
+    };
+    let artifacts = render_simulation_outputs(
+        &output_path,
+        track,
+        &result,
+        model.get_size(),
+        fps,
+        &options,
+    )
+    .expect("failed to render open-loop outputs");
+
+    assert_eq!(artifacts.step_svgs.len(), 9, "unexpected number of step frames");
+    for step_svg in &artifacts.step_svgs {
+        assert!(step_svg.exists(), "missing {}", step_svg.display());
+    }
+}
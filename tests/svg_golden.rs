@@ -0,0 +1,42 @@
+#![cfg(feature = "plotting")]
+
+mod common;
+
+use lap_simulation::plotting::plot_track_thumbnail;
+use lap_simulation::testing::check_golden_svg;
+use lap_simulation::tracks::circle::CircleTrack;
+use lap_simulation::tracks::square::SquareTrack;
+use std::path::Path;
+
+fn golden_path(name: &str) -> std::path::PathBuf {
+    Path::new(env!("CARGO_MANIFEST_DIR"))
+        .join("tests")
+        .join("golden")
+        .join(name)
+}
+
+#[test]
+fn test_square_track_thumbnail_matches_golden() {
+    let output_dir = common::temp_output_dir("svg_golden");
+    let output = output_dir.path().join("square_track_thumbnail.svg");
+
+    let track = SquareTrack::new(100.0, 10.0, 25);
+    plot_track_thumbnail(&track, output.to_str().expect("temp path not utf-8"), (200, 200))
+        .expect("failed to plot thumbnail");
+
+    check_golden_svg(&output, golden_path("square_track_thumbnail.svg"))
+        .expect("thumbnail differs from golden file");
+}
+
+#[test]
+fn test_circle_track_thumbnail_matches_golden() {
+    let output_dir = common::temp_output_dir("svg_golden");
+    let output = output_dir.path().join("circle_track_thumbnail.svg");
+
+    let track = CircleTrack::new(50.0, 10.0, 36);
+    plot_track_thumbnail(&track, output.to_str().expect("temp path not utf-8"), (200, 200))
+        .expect("failed to plot thumbnail");
+
+    check_golden_svg(&output, golden_path("circle_track_thumbnail.svg"))
+        .expect("thumbnail differs from golden file");
+}